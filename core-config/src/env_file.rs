@@ -0,0 +1,398 @@
+//! Env files referenced by launch profiles (`env_files`).
+//!
+//! The format is the one direnv and docker-compose users already write:
+//!
+//! - `KEY=value` lines, optionally prefixed with `export`.
+//! - Blank lines and `#` comments, including trailing ` # comment`s after
+//!   unquoted values.
+//! - Single-quoted values are literal and may span lines.
+//! - Double-quoted values may span lines and take `\n`, `\r`, `\t` and
+//!   `\uXXXX` escapes; a backslash before any other character keeps that
+//!   character.
+//! - `$NAME` and `${NAME}` interpolate in unquoted and double-quoted values,
+//!   from variables earlier in the file and then the caller's lookup.
+//!   Unknown names expand to nothing, as in the shell.
+//!
+//! Malformed input is an error with its line number rather than a silently
+//! truncated value. Errors never quote values, which may be secrets.
+//! [`format_env_line`] writes a line that parses back to the same value.
+
+use std::collections::HashMap;
+use std::fmt::Write;
+
+/// A line of an env file that could not be parsed.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("line {line}: {message}")]
+pub struct EnvFileError {
+    /// 1-based line where the offending entry starts.
+    pub line: usize,
+    pub message: String,
+}
+
+/// Parse env file `contents` into `(name, value)` pairs in file order.
+///
+/// `lookup` resolves interpolated names not defined earlier in the file,
+/// normally from the process environment.
+///
+/// # Errors
+///
+/// Returns the first malformed entry: a missing name or `=`, an
+/// unterminated quote or escape, or text after a closing quote.
+pub fn parse_env_file(
+    contents: &str,
+    lookup: impl Fn(&str) -> Option<String>,
+) -> Result<Vec<(String, String)>, EnvFileError> {
+    let mut parser = Parser {
+        chars: contents.chars().collect(),
+        pos: 0,
+        line: 1,
+    };
+    let mut defined: HashMap<String, String> = HashMap::new();
+    let mut entries = Vec::new();
+    while parser.skip_blank() {
+        let start_line = parser.line;
+        let fail = |message: &str| EnvFileError {
+            line: start_line,
+            message: message.into(),
+        };
+        let mut key = parser.name();
+        if key == "export" && parser.peek().is_some_and(|c| c == ' ' || c == '\t') {
+            parser.skip_spaces();
+            key = parser.name();
+        }
+        if key.is_empty() || key.starts_with(|c: char| c.is_ascii_digit()) {
+            return Err(fail("expected a variable name"));
+        }
+        parser.skip_spaces();
+        if parser.bump() != Some('=') {
+            return Err(fail(&format!("expected '=' after '{key}'")));
+        }
+        parser.skip_spaces();
+        let resolve = |name: &str| defined.get(name).cloned().or_else(|| lookup(name));
+        let value = match parser.peek() {
+            Some('\'') => {
+                parser.pos += 1;
+                parser
+                    .single_quoted()
+                    .ok_or_else(|| fail("unterminated single quote"))?
+            }
+            Some('"') => {
+                parser.pos += 1;
+                parser.double_quoted(&resolve).map_err(fail)?
+            }
+            _ => parser.unquoted(&resolve).map_err(fail)?,
+        };
+        parser.skip_spaces();
+        match parser.peek() {
+            None | Some('\n' | '\r') => {}
+            Some('#') => parser.skip_line(),
+            Some(_) => return Err(fail("unexpected text after quoted value")),
+        }
+        defined.insert(key.clone(), value.clone());
+        entries.push((key, value));
+    }
+    Ok(entries)
+}
+
+/// `name=value` as an env file line that [`parse_env_file`] reads back as
+/// `value` exactly.
+#[must_use]
+pub fn format_env_line(name: &str, value: &str) -> String {
+    let mut line = format!("{name}=\"");
+    for c in value.chars() {
+        match c {
+            '"' | '\\' | '$' => {
+                line.push('\\');
+                line.push(c);
+            }
+            '\n' => line.push_str("\\n"),
+            '\r' => line.push_str("\\r"),
+            '\t' => line.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(line, "\\u{:04x}", u32::from(c));
+            }
+            c => line.push(c),
+        }
+    }
+    line.push('"');
+    line
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+    line: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += 1;
+        if c == '\n' {
+            self.line += 1;
+        }
+        Some(c)
+    }
+
+    fn skip_spaces(&mut self) {
+        while self.peek().is_some_and(|c| c == ' ' || c == '\t') {
+            self.pos += 1;
+        }
+    }
+
+    fn skip_line(&mut self) {
+        while self.peek().is_some_and(|c| c != '\n') {
+            self.pos += 1;
+        }
+    }
+
+    /// Skip blank lines and comments. Whether an entry follows.
+    fn skip_blank(&mut self) -> bool {
+        loop {
+            self.skip_spaces();
+            match self.peek() {
+                None => return false,
+                Some('\n' | '\r') => {
+                    self.bump();
+                }
+                Some('#') => self.skip_line(),
+                Some(_) => return true,
+            }
+        }
+    }
+
+    fn name(&mut self) -> String {
+        let mut name = String::new();
+        while let Some(c) = self
+            .peek()
+            .filter(|c| c.is_ascii_alphanumeric() || *c == '_')
+        {
+            name.push(c);
+            self.pos += 1;
+        }
+        name
+    }
+
+    fn single_quoted(&mut self) -> Option<String> {
+        let mut value = String::new();
+        loop {
+            match self.bump()? {
+                '\'' => return Some(value),
+                c => value.push(c),
+            }
+        }
+    }
+
+    fn double_quoted(
+        &mut self,
+        resolve: &impl Fn(&str) -> Option<String>,
+    ) -> Result<String, &'static str> {
+        let mut value = String::new();
+        loop {
+            match self.bump().ok_or("unterminated double quote")? {
+                '"' => return Ok(value),
+                '\\' => value.push(self.escape()?),
+                '$' => self.interpolate(&mut value, resolve)?,
+                c => value.push(c),
+            }
+        }
+    }
+
+    /// An unquoted value runs to the end of the line or a ` #` comment,
+    /// without trailing whitespace.
+    fn unquoted(
+        &mut self,
+        resolve: &impl Fn(&str) -> Option<String>,
+    ) -> Result<String, &'static str> {
+        let mut value = String::new();
+        let mut literal_len = 0;
+        while let Some(c) = self.peek() {
+            match c {
+                '\n' => break,
+                '#' if matches!(self.chars[self.pos - 1], ' ' | '\t') => break,
+                '\\' => {
+                    self.pos += 1;
+                    match self.peek() {
+                        None | Some('\n') => {}
+                        Some(_) => {
+                            let c = self.escape()?;
+                            value.push(c);
+                            literal_len = value.len();
+                        }
+                    }
+                }
+                '$' => {
+                    self.pos += 1;
+                    self.interpolate(&mut value, resolve)?;
+                    literal_len = value.len();
+                }
+                c => {
+                    self.pos += 1;
+                    value.push(c);
+                }
+            }
+        }
+        let trimmed = value.trim_end_matches([' ', '\t', '\r']).len();
+        value.truncate(trimmed.max(literal_len));
+        Ok(value)
+    }
+
+    /// The character a backslash escape stands for; the backslash is
+    /// consumed.
+    fn escape(&mut self) -> Result<char, &'static str> {
+        match self.bump().ok_or("unterminated escape")? {
+            'n' => Ok('\n'),
+            'r' => Ok('\r'),
+            't' => Ok('\t'),
+            'u' => {
+                let hex: String = self.chars.iter().skip(self.pos).take(4).collect();
+                let code = (hex.len() == 4 && hex.chars().all(|c| c.is_ascii_hexdigit()))
+                    .then(|| u32::from_str_radix(&hex, 16).ok())
+                    .flatten()
+                    .and_then(char::from_u32)
+                    .ok_or("invalid \\u escape")?;
+                self.pos += 4;
+                Ok(code)
+            }
+            c => Ok(c),
+        }
+    }
+
+    /// Expand the `$NAME` or `${NAME}` after a consumed `$` into `value`. A
+    /// `$` not followed by a name (including `$5`) is kept.
+    fn interpolate(
+        &mut self,
+        value: &mut String,
+        resolve: &impl Fn(&str) -> Option<String>,
+    ) -> Result<(), &'static str> {
+        if self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            value.push('$');
+            return Ok(());
+        }
+        let braced = self.peek() == Some('{');
+        if braced {
+            self.pos += 1;
+        }
+        let name = self.name();
+        if braced {
+            if name.is_empty() || self.peek() != Some('}') {
+                return Err("invalid ${...} reference");
+            }
+            self.pos += 1;
+        } else if name.is_empty() {
+            value.push('$');
+            return Ok(());
+        }
+        value.push_str(&resolve(&name).unwrap_or_default());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn parse(contents: &str) -> Result<Vec<(String, String)>, EnvFileError> {
+        parse_env_file(contents, |name| (name == "HOME").then(|| "/home/me".into()))
+    }
+
+    fn pairs(entries: &[(&str, &str)]) -> Vec<(String, String)> {
+        entries
+            .iter()
+            .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn parses_direnv_style_file() {
+        let contents = r#"
+# Project settings
+export RUST_LOG=debug
+CARGO_HOME=$HOME/.cargo   # per-user cache
+DATA='${not} expanded # kept'
+GREETING="hello
+  world\t$RUST_LOG"
+EMPTY=
+URL=http://example.com/#anchor
+COLOR=#ff0000
+   INDENTED = spaced out
+"#;
+        assert_eq!(
+            parse(contents).unwrap(),
+            pairs(&[
+                ("RUST_LOG", "debug"),
+                ("CARGO_HOME", "/home/me/.cargo"),
+                ("DATA", "${not} expanded # kept"),
+                ("GREETING", "hello\n  world\tdebug"),
+                ("EMPTY", ""),
+                ("URL", "http://example.com/#anchor"),
+                ("COLOR", "#ff0000"),
+                ("INDENTED", "spaced out"),
+            ])
+        );
+    }
+
+    #[test]
+    fn interpolation_forms() {
+        assert_eq!(
+            parse("A=x\nB=${A}y$A-$MISSING.${HOME}\nC=\"cost: $5\"\nD=\\$A").unwrap(),
+            pairs(&[
+                ("A", "x"),
+                ("B", "xyx-./home/me"),
+                ("C", "cost: $5"),
+                ("D", "$A"),
+            ])
+        );
+    }
+
+    #[test]
+    fn crlf_line_endings() {
+        assert_eq!(
+            parse("A=1\r\nB=\"2\"\r\n").unwrap(),
+            pairs(&[("A", "1"), ("B", "2")])
+        );
+    }
+
+    #[test]
+    fn errors_name_the_line_without_the_value() {
+        let err = |contents| parse(contents).unwrap_err();
+        assert_eq!(err("A=1\nB=\"open secret\nC=2").line, 2);
+        assert_eq!(err("A=1\n\nC='open secret").line, 3);
+        assert_eq!(
+            err("A=\"x\" trailing").message,
+            "unexpected text after quoted value"
+        );
+        assert_eq!(err("JUST_A_WORD").line, 1);
+        assert_eq!(err("1A=x").message, "expected a variable name");
+        assert_eq!(err("A=${B").line, 1);
+        assert_eq!(err("A=\"\\u00zz\"").message, "invalid \\u escape");
+        assert!(!err("A=\"hunter2").to_string().contains("hunter2"));
+    }
+
+    proptest! {
+        #[test]
+        fn formatted_lines_round_trip(
+            name in "[A-Za-z_][A-Za-z0-9_]{0,12}",
+            value in any::<String>(),
+        ) {
+            let parsed = parse_env_file(&format_env_line(&name, &value), |_| None).unwrap();
+            prop_assert_eq!(parsed, vec![(name, value)]);
+        }
+
+        #[test]
+        fn single_quoted_values_are_literal(value in "[^']*") {
+            let parsed = parse_env_file(&format!("export A='{value}'"), |_| Some("x".into())).unwrap();
+            prop_assert_eq!(parsed, vec![("A".to_string(), value)]);
+        }
+
+        #[test]
+        fn never_panics(contents in any::<String>()) {
+            let _ = parse_env_file(&contents, |_| None);
+        }
+    }
+}
@@ -4,6 +4,7 @@
 //! deep merge, semantic validation, and filesystem-watched hot-reload.
 #![forbid(unsafe_code)]
 
+mod env_file;
 mod loader;
 mod loader_installation;
 mod loader_workspace;
@@ -15,14 +16,21 @@ mod schema_peripheral;
 mod schema_secrets;
 mod schema_wm;
 mod schema_workspace;
+mod themes;
+mod title_rules;
 mod validation;
 mod watcher;
 
+pub use env_file::{EnvFileError, format_env_line, parse_env_file};
 pub use loader::{
-    atomic_write, bootstrap_dirs, config_dir, installation_path, load_config, load_installation,
-    load_workspace_config, resolve_config_paths, resolve_config_real_dirs, save_workspace_config,
-    write_installation,
+    application_dirs, atomic_write, bootstrap_dirs, config_dir, installation_path, load_config,
+    load_installation, load_workspace_config, resolve_config_paths, resolve_config_real_dirs,
+    save_workspace_config, write_installation,
 };
 pub use schema::*;
-pub use validation::{ConfigDiagnostic, DiagnosticSeverity, validate};
+pub use themes::{THEME_PRESETS, ThemePreset, theme_preset};
+pub use title_rules::TitleRewriter;
+pub use validation::{
+    ConfigDiagnostic, DiagnosticSeverity, MIN_TEXT_CONTRAST, relative_luminance, validate,
+};
 pub use watcher::ConfigWatcher;
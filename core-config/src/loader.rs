@@ -18,148 +18,360 @@ pub fn config_dir() -> PathBuf {
         .join("pds")
 }
 
+/// XDG application directories holding `.desktop` files, highest priority
+/// first: `$XDG_DATA_HOME/applications`, then each `$XDG_DATA_DIRS` entry's.
+#[must_use]
+pub fn application_dirs() -> Vec<PathBuf> {
+    let data_dirs = std::env::var("XDG_DATA_DIRS")
+        .ok()
+        .filter(|v| !v.is_empty())
+        .unwrap_or_else(|| "/usr/local/share:/usr/share".into());
+    dirs::data_dir()
+        .into_iter()
+        .chain(std::env::split_paths(&data_dirs))
+        .map(|dir| dir.join("applications"))
+        .collect()
+}
+
+/// System-wide defaults directory, shipped by distros and fleet tooling.
+#[cfg(target_os = "linux")]
+const SYSTEM_CONFIG_DIR: &str = "/etc/open-sesame";
+
+/// System policy, merged over the system defaults.
+#[cfg(target_os = "linux")]
+const SYSTEM_POLICY: &str = "/etc/pds/policy.toml";
+
 /// Resolve all config paths in loading order (lowest to highest priority).
 ///
-/// 1. System policy (`/etc/pds/policy.toml` on Linux)
-/// 2. User config (`~/.config/pds/config.toml`)
-/// 3. Drop-in fragments (`~/.config/pds/config.d/*.toml`, alphabetical)
-/// 4. Profile overrides (`~/.config/pds/profiles/{name}/config.toml`)
+/// 1. System defaults (`/etc/open-sesame/config.toml` on Linux)
+/// 2. System drop-ins (`/etc/open-sesame/config.toml.d/*.toml`, lexical order)
+/// 3. System policy (`/etc/pds/policy.toml` on Linux)
+/// 4. User config (`~/.config/pds/config.toml`)
+/// 5. Drop-in fragments (`~/.config/pds/config.d/*.toml`, lexical order)
+/// 6. Profile overrides (`~/.config/pds/profiles/{name}/config.toml`)
 #[must_use]
 pub fn resolve_config_paths(profile_name: Option<&str>) -> Vec<PathBuf> {
-    let mut paths = Vec::new();
-
-    // System policy (Linux only for now)
     #[cfg(target_os = "linux")]
-    {
-        let system = PathBuf::from("/etc/pds/policy.toml");
-        if system.exists() {
-            paths.push(system);
-        }
-    }
+    let (system_dir, policy) = (
+        Some(Path::new(SYSTEM_CONFIG_DIR)),
+        Some(Path::new(SYSTEM_POLICY)),
+    );
+    #[cfg(not(target_os = "linux"))]
+    let (system_dir, policy) = (None, None);
+
+    resolve_config_paths_in(system_dir, policy, &config_dir(), profile_name)
+}
 
-    let base = config_dir();
+fn resolve_config_paths_in(
+    system_dir: Option<&Path>,
+    policy: Option<&Path>,
+    user_dir: &Path,
+    profile_name: Option<&str>,
+) -> Vec<PathBuf> {
+    let mut paths = Vec::new();
 
-    // User config
-    let user_config = base.join("config.toml");
-    if user_config.exists() {
-        paths.push(user_config);
+    if let Some(system) = system_dir {
+        push_existing(&mut paths, system.join("config.toml"));
+        paths.extend(dropin_fragments(&system.join("config.toml.d")));
     }
-
-    // Drop-in fragments (sorted alphabetically)
-    let dropin_dir = base.join("config.d");
-    if dropin_dir.is_dir()
-        && let Ok(entries) = std::fs::read_dir(&dropin_dir)
-    {
-        let mut fragments: Vec<PathBuf> = entries
-            .filter_map(std::result::Result::ok)
-            .map(|e| e.path())
-            .filter(|p| p.extension().is_some_and(|ext| ext == "toml"))
-            .collect();
-        fragments.sort();
-        paths.extend(fragments);
+    if let Some(policy) = policy {
+        push_existing(&mut paths, policy.to_path_buf());
     }
 
-    // Profile overrides
+    push_existing(&mut paths, user_dir.join("config.toml"));
+    paths.extend(dropin_fragments(&user_dir.join("config.d")));
+
     if let Some(name) = profile_name {
-        let profile_config = base.join("profiles").join(name).join("config.toml");
-        if profile_config.exists() {
-            paths.push(profile_config);
-        }
+        push_existing(
+            &mut paths,
+            user_dir.join("profiles").join(name).join("config.toml"),
+        );
     }
 
     paths
 }
 
+fn push_existing(paths: &mut Vec<PathBuf>, path: PathBuf) {
+    if path.exists() {
+        paths.push(path);
+    }
+}
+
+/// `*.toml` files in a drop-in directory, sorted lexically.
+fn dropin_fragments(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut fragments: Vec<PathBuf> = entries
+        .filter_map(std::result::Result::ok)
+        .map(|e| e.path())
+        .filter(|p| p.is_file() && p.extension().is_some_and(|ext| ext == "toml"))
+        .collect();
+    fragments.sort();
+    fragments
+}
+
 /// Load configuration by merging all layers.
 ///
-/// Layers are applied in order: compiled defaults, then each file from
-/// `resolve_config_paths` (lowest to highest priority). Higher-priority
-/// values override lower-priority ones via TOML deep merge.
+/// Layers are the files from `resolve_config_paths` (lowest to highest
+/// priority), merged as TOML before deserializing, so compiled defaults
+/// only fill keys that no layer sets. See [`merge_layer`] for the rules.
+///
+/// A layer may list other files in a top-level `include` array; those are
+/// merged just before it, so the including file wins. See [`expand_include`].
 ///
 /// # Errors
 ///
 /// Returns an error if any config file contains invalid TOML or fails
 /// schema validation.
 pub fn load_config(profile_name: Option<&str>) -> core_types::Result<Config> {
-    let mut config = Config::default();
-    let paths = resolve_config_paths(profile_name);
+    load_config_from(&resolve_config_paths(profile_name))
+}
 
-    for path in &paths {
-        let contents = std::fs::read_to_string(path).map_err(|e| {
-            core_types::Error::Config(format!("failed to read {}: {e}", path.display()))
-        })?;
-        let layer: Config = toml::from_str(&contents).map_err(|e| {
-            core_types::Error::Config(format!("failed to parse {}: {e}", path.display()))
-        })?;
-        merge_config(&mut config, &layer);
+fn load_config_from(paths: &[PathBuf]) -> core_types::Result<Config> {
+    let mut merged = toml::Table::new();
+
+    for path in paths {
+        merge_file(&mut merged, path, &mut Vec::new())?;
     }
 
-    Ok(config)
+    toml::Value::Table(merged)
+        .try_into()
+        .map_err(|e| core_types::Error::Config(format!("failed to merge config layers: {e}")))
 }
 
-/// Deep merge `overlay` into `base`. Non-default overlay values override base.
-fn merge_config(base: &mut Config, overlay: &Config) {
-    // Schema version: always take the higher
-    if overlay.config_version > base.config_version {
-        base.config_version = overlay.config_version;
+/// Top-level key listing files to merge beneath the file that names them.
+const INCLUDE_KEY: &str = "include";
+
+/// Merge one config file into `merged`, preceded by the files its `include`
+/// list names. `chain` holds the canonical paths of the files currently being
+/// merged, so an include cycle is reported instead of followed.
+fn merge_file(
+    merged: &mut toml::Table,
+    path: &Path,
+    chain: &mut Vec<PathBuf>,
+) -> core_types::Result<()> {
+    let read_error = |e: std::io::Error| {
+        core_types::Error::Config(format!("failed to read {}: {e}", path.display()))
+    };
+    let canonical = std::fs::canonicalize(path).map_err(read_error)?;
+    if chain.contains(&canonical) {
+        let cycle: Vec<String> = chain
+            .iter()
+            .chain(std::iter::once(&canonical))
+            .map(|p| p.display().to_string())
+            .collect();
+        return Err(core_types::Error::Config(format!(
+            "config include cycle: {}",
+            cycle.join(" -> ")
+        )));
     }
 
-    // Global settings
-    if overlay.global.default_profile.as_ref() != GlobalConfigDefaults::DEFAULT_PROFILE {
-        base.global.default_profile = overlay.global.default_profile.clone();
-    }
+    let contents = std::fs::read_to_string(path).map_err(read_error)?;
+    // Type-check each layer on its own so errors name the offending file.
+    toml::from_str::<Config>(&contents).map_err(|e| {
+        core_types::Error::Config(format!("failed to parse {}: {e}", path.display()))
+    })?;
+    let mut layer: toml::Table = toml::from_str(&contents).map_err(|e| {
+        core_types::Error::Config(format!("failed to parse {}: {e}", path.display()))
+    })?;
+    let includes = take_includes(&mut layer, path)?;
 
-    // Profiles: overlay profiles merge into base by name
-    for (name, profile) in &overlay.profiles {
-        base.profiles
-            .entry(name.clone())
-            .and_modify(|existing| merge_profile(existing, profile))
-            .or_insert_with(|| profile.clone());
+    chain.push(canonical);
+    for included in &includes {
+        merge_file(merged, included, chain)?;
     }
+    chain.pop();
 
-    // Policy: append (policies are additive)
-    base.policy.extend(overlay.policy.iter().cloned());
+    merge_layer(merged, layer);
+    Ok(())
 }
 
-fn merge_profile(base: &mut crate::schema::ProfileConfig, overlay: &crate::schema::ProfileConfig) {
-    if overlay.extends.is_some() {
-        base.extends.clone_from(&overlay.extends);
-    }
-    if overlay.color.is_some() {
-        base.color.clone_from(&overlay.color);
-    }
-    if overlay.icon.is_some() {
-        base.icon.clone_from(&overlay.icon);
-    }
-    // Sub-configs: merge non-default values
-    if overlay.clipboard.max_history != crate::schema::ClipboardConfig::default().max_history {
-        base.clipboard.max_history = overlay.clipboard.max_history;
-    }
-    if overlay.wm.hint_keys != crate::schema::WmConfig::default().hint_keys {
-        base.wm.hint_keys.clone_from(&overlay.wm.hint_keys);
-    }
-    if overlay.wm.overlay_delay_ms != crate::schema::WmConfig::default().overlay_delay_ms {
-        base.wm.overlay_delay_ms = overlay.wm.overlay_delay_ms;
-    }
-    if overlay.wm.max_visible_windows != crate::schema::WmConfig::default().max_visible_windows {
-        base.wm.max_visible_windows = overlay.wm.max_visible_windows;
+/// Remove the `include` key from a parsed layer and expand its patterns, in
+/// the order listed, relative to the directory of `path`.
+fn take_includes(layer: &mut toml::Table, path: &Path) -> core_types::Result<Vec<PathBuf>> {
+    let Some(value) = layer.remove(INCLUDE_KEY) else {
+        return Ok(Vec::new());
+    };
+    let invalid = |reason: String| {
+        core_types::Error::Config(format!("invalid include in {}: {reason}", path.display()))
+    };
+    let toml::Value::Array(patterns) = value else {
+        return Err(invalid("expected an array of path patterns".into()));
+    };
+    let base = path.parent().unwrap_or_else(|| Path::new("."));
+
+    let mut files = Vec::new();
+    for pattern in &patterns {
+        let pattern = pattern
+            .as_str()
+            .ok_or_else(|| invalid(format!("expected a path pattern, found {pattern}")))?;
+        files.extend(expand_include(base, pattern).map_err(invalid)?);
+    }
+    Ok(files)
+}
+
+/// Expand one `include` pattern relative to `base`.
+///
+/// The file-name component may use `*` (any run of characters) and `?` (one
+/// character); matches are returned in lexical order, and a pattern matching
+/// nothing is not an error. A `*` or `?` never matches a leading dot. A
+/// pattern without wildcards must name an existing file.
+fn expand_include(base: &Path, pattern: &str) -> Result<Vec<PathBuf>, String> {
+    let full = base.join(pattern);
+    let name = full
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let dir = full.parent().unwrap_or(base);
+
+    if dir.to_string_lossy().contains(['*', '?']) {
+        return Err(format!(
+            "wildcards are only supported in the file name: {pattern}"
+        ));
+    }
+    if !name.contains(['*', '?']) {
+        return if full.is_file() {
+            Ok(vec![full])
+        } else {
+            Err(format!("{} does not exist", full.display()))
+        };
+    }
+
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Ok(Vec::new());
+    };
+    let mut matches: Vec<PathBuf> = entries
+        .filter_map(std::result::Result::ok)
+        .map(|e| e.path())
+        .filter(|p| {
+            p.is_file()
+                && p.file_name()
+                    .and_then(|n| n.to_str())
+                    .is_some_and(|n| !n.starts_with('.') && wildcard_match(&name, n))
+        })
+        .collect();
+    matches.sort();
+    Ok(matches)
+}
+
+/// Shell-style match of a whole file name against a `*` / `?` pattern.
+fn wildcard_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    // Position of the last `*` seen and the name index it was tried at.
+    let mut star: Option<(usize, usize)> = None;
+
+    while n < name.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == name[n]) {
+            p += 1;
+            n += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, n));
+            p += 1;
+        } else if let Some((star_p, star_n)) = star {
+            // Let the last `*` swallow one more character and retry.
+            p = star_p + 1;
+            n = star_n + 1;
+            star = Some((star_p, n));
+        } else {
+            return false;
+        }
     }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// What the `include` lists of a set of config files pull in.
+#[derive(Debug, Default)]
+pub(crate) struct Included {
+    /// Included files, transitively.
+    pub(crate) files: Vec<PathBuf>,
+    /// Directories the include patterns expand in, so files that start
+    /// matching a wildcard pattern later can be noticed.
+    pub(crate) dirs: Vec<PathBuf>,
+}
 
-    // Key bindings: overlay entries override matching keys, base entries preserved.
-    for (key, binding) in &overlay.wm.key_bindings {
-        base.wm.key_bindings.insert(key.clone(), binding.clone());
+/// The files and pattern directories pulled in by the `include` lists of
+/// `paths`, transitively.
+///
+/// Used to extend the hot-reload watch set. Unreadable files and bad
+/// patterns are skipped here; [`load_config`] reports them.
+pub(crate) fn included_files(paths: &[PathBuf]) -> Included {
+    let mut seen: std::collections::HashSet<PathBuf> = paths
+        .iter()
+        .map(|p| std::fs::canonicalize(p).unwrap_or_else(|_| p.clone()))
+        .collect();
+    let mut pending = paths.to_vec();
+    let mut found = Included::default();
+
+    while let Some(path) = pending.pop() {
+        let Ok(contents) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        let Ok(mut layer) = toml::from_str::<toml::Table>(&contents) else {
+            continue;
+        };
+        if let Some(toml::Value::Array(patterns)) = layer.get(INCLUDE_KEY) {
+            let base = path.parent().unwrap_or_else(|| Path::new("."));
+            for pattern in patterns.iter().filter_map(toml::Value::as_str) {
+                if let Some(dir) = base.join(pattern).parent()
+                    && !found.dirs.iter().any(|d| d == dir)
+                {
+                    found.dirs.push(dir.to_path_buf());
+                }
+            }
+        }
+        let Ok(includes) = take_includes(&mut layer, &path) else {
+            continue;
+        };
+        for included in includes {
+            let canonical = std::fs::canonicalize(&included).unwrap_or_else(|_| included.clone());
+            if seen.insert(canonical) {
+                found.files.push(included.clone());
+                pending.push(included);
+            }
+        }
     }
+    found
+}
 
-    // Launch profiles: overlay entries override matching names, base entries preserved.
-    for (name, lp) in &overlay.launch_profiles {
-        base.launch_profiles.insert(name.clone(), lp.clone());
+/// Merge a higher-priority layer into the accumulated config table.
+///
+/// - Tables (profiles, key bindings, launch profiles, ...) deep-merge key by key.
+/// - Everything else — scalars, arrays, arrays of tables — is replaced whole.
+///
+/// Two top-level keys are special: `policy` entries accumulate across layers
+/// (policies are additive), and `config_version` keeps the highest value.
+fn merge_layer(base: &mut toml::Table, mut layer: toml::Table) {
+    if let Some(toml::Value::Array(policies)) = layer.remove("policy") {
+        match base.get_mut("policy") {
+            Some(toml::Value::Array(existing)) => existing.extend(policies),
+            _ => {
+                base.insert("policy".into(), toml::Value::Array(policies));
+            }
+        }
+    }
+    if let (Some(toml::Value::Integer(old)), Some(toml::Value::Integer(new))) =
+        (base.get("config_version"), layer.get("config_version"))
+        && old > new
+    {
+        layer.remove("config_version");
     }
+    merge_tables(base, layer);
 }
 
-/// Helpers for detecting default values.
-struct GlobalConfigDefaults;
-impl GlobalConfigDefaults {
-    const DEFAULT_PROFILE: &str = core_types::DEFAULT_PROFILE_NAME;
+fn merge_tables(base: &mut toml::Table, overlay: toml::Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(existing)), toml::Value::Table(table)) => {
+                merge_tables(existing, table);
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
 }
 
 /// Resolve all config file symlinks to their real filesystem paths.
@@ -258,6 +470,18 @@ pub fn atomic_write(path: &Path, contents: &[u8]) -> std::io::Result<()> {
 mod tests {
     use super::*;
 
+    /// Merge two typed configs the way `load_config` merges files.
+    fn merge_config(base: &mut Config, overlay: &Config) {
+        let mut table = toml::Table::try_from(&*base).unwrap();
+        merge_layer(&mut table, toml::Table::try_from(overlay).unwrap());
+        *base = toml::Value::Table(table).try_into().unwrap();
+    }
+
+    fn write(path: &Path, contents: &str) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    }
+
     #[test]
     fn atomic_write_creates_file() {
         let dir = tempfile::tempdir().unwrap();
@@ -367,7 +591,7 @@ mod tests {
                 apps: vec!["custom-app".into()],
                 launch: Some("custom-app".into()),
                 tags: vec!["my-tag".into()],
-                launch_args: Vec::new(),
+                ..Default::default()
             },
         );
         overlay.profiles.insert("default".into(), overlay_profile);
@@ -450,4 +674,256 @@ mod tests {
             vec!["dev-rust"]
         );
     }
+
+    #[test]
+    fn resolve_paths_system_layers_come_first() {
+        let root = tempfile::tempdir().unwrap();
+        let system = root.path().join("etc/open-sesame");
+        let policy = root.path().join("etc/pds/policy.toml");
+        let user = root.path().join("user");
+        for file in [
+            "config.toml",
+            "config.toml.d/20-fleet.toml",
+            "config.toml.d/10-distro.toml",
+            "config.toml.d/README",
+        ] {
+            write(&system.join(file), "");
+        }
+        write(&policy, "");
+        for file in [
+            "config.toml",
+            "config.d/50-local.toml",
+            "profiles/work/config.toml",
+        ] {
+            write(&user.join(file), "");
+        }
+
+        let paths = resolve_config_paths_in(Some(&system), Some(&policy), &user, Some("work"));
+        let rel: Vec<_> = paths
+            .iter()
+            .map(|p| p.strip_prefix(root.path()).unwrap().to_path_buf())
+            .collect();
+        assert_eq!(
+            rel,
+            [
+                "etc/open-sesame/config.toml",
+                "etc/open-sesame/config.toml.d/10-distro.toml",
+                "etc/open-sesame/config.toml.d/20-fleet.toml",
+                "etc/pds/policy.toml",
+                "user/config.toml",
+                "user/config.d/50-local.toml",
+                "user/profiles/work/config.toml",
+            ]
+            .map(PathBuf::from)
+        );
+    }
+
+    #[test]
+    fn resolve_paths_skips_missing_files() {
+        let root = tempfile::tempdir().unwrap();
+        let paths = resolve_config_paths_in(
+            Some(root.path()),
+            Some(&root.path().join("policy.toml")),
+            &root.path().join("user"),
+            None,
+        );
+        assert!(paths.is_empty());
+    }
+
+    #[test]
+    fn layers_deep_merge_tables_and_replace_arrays() {
+        let root = tempfile::tempdir().unwrap();
+        let system = root.path().join("config.toml");
+        let user = root.path().join("user.toml");
+        write(
+            &system,
+            r#"
+            [profiles.default.wm]
+            hint_keys = "asdf"
+            show_title = false
+
+            [profiles.default.wm.key_bindings.g]
+            apps = ["ghostty", "kitty"]
+            launch = "ghostty"
+            "#,
+        );
+        write(
+            &user,
+            r#"
+            [profiles.default.wm]
+            overlay_delay_ms = 0
+
+            [profiles.default.wm.key_bindings.g]
+            apps = ["foot"]
+            "#,
+        );
+
+        let config = load_config_from(&[system, user]).unwrap();
+        let wm = &config.profiles["default"].wm;
+        assert_eq!(wm.hint_keys, "asdf", "system value kept");
+        assert!(!wm.show_title, "system value kept");
+        assert_eq!(wm.overlay_delay_ms, 0, "user value applied");
+        let g = &wm.key_bindings["g"];
+        assert_eq!(g.apps, vec!["foot"], "arrays replace");
+        assert_eq!(g.launch.as_deref(), Some("ghostty"), "tables deep-merge");
+    }
+
+    #[test]
+    fn layers_accumulate_policy_and_keep_highest_version() {
+        let mut base = toml::Table::new();
+        merge_layer(
+            &mut base,
+            toml::from_str(
+                r#"
+                config_version = 3
+                [[policy]]
+                key = "crypto.kdf"
+                value = "argon2id"
+                source = "/etc/pds/policy.toml"
+                "#,
+            )
+            .unwrap(),
+        );
+        merge_layer(
+            &mut base,
+            toml::from_str(
+                r#"
+                config_version = 2
+                [[policy]]
+                key = "crypto.hkdf"
+                value = "blake3"
+                source = "enterprise-mdm"
+                "#,
+            )
+            .unwrap(),
+        );
+        assert_eq!(base["config_version"].as_integer(), Some(3));
+        assert_eq!(base["policy"].as_array().map(Vec::len), Some(2));
+    }
+
+    #[test]
+    fn layer_parse_error_names_file() {
+        let root = tempfile::tempdir().unwrap();
+        let bad = root.path().join("config.d/10-bad.toml");
+        write(&bad, "[profiles.default.wm]\noverlay_delay_ms = \"soon\"\n");
+        let err = load_config_from(&[bad]).unwrap_err().to_string();
+        assert!(err.contains("10-bad.toml"), "{err}");
+    }
+
+    #[test]
+    fn includes_merge_before_the_including_file_in_lexical_order() {
+        let root = tempfile::tempdir().unwrap();
+        let config = root.path().join("config.toml");
+        write(
+            &config,
+            r#"
+            include = ["keys.d/*.toml"]
+
+            [profiles.default.wm]
+            hint_keys = "asdf"
+            "#,
+        );
+        write(
+            &root.path().join("keys.d/20-firefox.toml"),
+            "[profiles.default.wm]\nhint_keys = \"jkl\"\noverlay_delay_ms = 200\n",
+        );
+        write(
+            &root.path().join("keys.d/10-foot.toml"),
+            "[profiles.default.wm]\noverlay_delay_ms = 100\nquick_switch_threshold_ms = 50\n",
+        );
+        write(&root.path().join("keys.d/notes.txt"), "not toml");
+
+        let config = load_config_from(&[config]).unwrap();
+        let wm = &config.profiles["default"].wm;
+        assert_eq!(wm.hint_keys, "asdf", "including file wins");
+        assert_eq!(wm.overlay_delay_ms, 200, "later fragment wins");
+        assert_eq!(wm.quick_switch_threshold_ms, 50);
+    }
+
+    #[test]
+    fn nested_includes_resolve_relative_to_their_file() {
+        let root = tempfile::tempdir().unwrap();
+        let config = root.path().join("config.toml");
+        write(&config, "include = [\"keys.d/apps.toml\"]\n");
+        write(
+            &root.path().join("keys.d/apps.toml"),
+            "include = [\"shared/base.toml\"]\n",
+        );
+        write(
+            &root.path().join("keys.d/shared/base.toml"),
+            "[profiles.default.wm]\noverlay_delay_ms = 321\n",
+        );
+
+        let config = load_config_from(std::slice::from_ref(&config)).unwrap();
+        assert_eq!(config.profiles["default"].wm.overlay_delay_ms, 321);
+        assert_eq!(
+            included_files(&[root.path().join("config.toml")])
+                .files
+                .len(),
+            2,
+            "watch set covers both levels"
+        );
+    }
+
+    #[test]
+    fn include_pattern_directories_are_watched_while_empty() {
+        let root = tempfile::tempdir().unwrap();
+        let config = root.path().join("config.toml");
+        write(&config, "include = [\"keys.d/*.toml\"]\n");
+        std::fs::create_dir(root.path().join("keys.d")).unwrap();
+
+        let included = included_files(std::slice::from_ref(&config));
+        assert!(included.files.is_empty());
+        assert_eq!(included.dirs, [root.path().join("keys.d")]);
+
+        write(&root.path().join("keys.d/apps.toml"), "");
+        assert_eq!(included_files(&[config]).files.len(), 1);
+    }
+
+    #[test]
+    fn include_cycle_is_an_error() {
+        let root = tempfile::tempdir().unwrap();
+        let config = root.path().join("config.toml");
+        write(&config, "include = [\"a.toml\"]\n");
+        write(&root.path().join("a.toml"), "include = [\"b.toml\"]\n");
+        write(&root.path().join("b.toml"), "include = [\"a.toml\"]\n");
+
+        let err = load_config_from(std::slice::from_ref(&config))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("include cycle"), "{err}");
+        assert!(err.contains("b.toml"), "{err}");
+        // The watch-set walk terminates on the same cycle.
+        assert_eq!(included_files(&[config]).files.len(), 2);
+    }
+
+    #[test]
+    fn include_patterns_are_checked() {
+        let root = tempfile::tempdir().unwrap();
+        let config = root.path().join("config.toml");
+
+        write(&config, "include = [\"missing.toml\"]\n");
+        let err = load_config_from(std::slice::from_ref(&config))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("missing.toml"), "{err}");
+
+        write(&config, "include = [\"empty.d/*.toml\"]\n");
+        assert!(load_config_from(std::slice::from_ref(&config)).is_ok());
+
+        write(&config, "include = [\"*.d/keys.toml\"]\n");
+        assert!(load_config_from(std::slice::from_ref(&config)).is_err());
+
+        write(&config, "include = \"keys.toml\"\n");
+        assert!(load_config_from(&[config]).is_err());
+    }
+
+    #[test]
+    fn wildcards_match_whole_names() {
+        assert!(wildcard_match("*.toml", "firefox.toml"));
+        assert!(wildcard_match("1?-*.toml", "10-foot.toml"));
+        assert!(wildcard_match("a*b*c", "aXbYbZc"));
+        assert!(!wildcard_match("*.toml", "firefox.toml.bak"));
+        assert!(!wildcard_match("?.toml", ".toml"));
+    }
 }
@@ -13,9 +13,16 @@ pub use crate::schema_agents::{
 };
 pub use crate::schema_crypto::CryptoConfigToml;
 pub use crate::schema_installation::{InstallationConfig, MachineBindingConfig, OrgConfig};
-pub use crate::schema_peripheral::{AuditConfig, ClipboardConfig, InputConfig, LauncherConfig};
+pub use crate::schema_peripheral::{
+    AuditConfig, ClipboardConfig, DEFAULT_ENV_BLOCKLIST, InputConfig, LauncherConfig,
+    env_pattern_matches,
+};
 pub use crate::schema_secrets::{AuthConfig, SecretsConfig};
-pub use crate::schema_wm::{LaunchProfile, WmConfig, WmKeyBinding};
+pub use crate::schema_wm::{
+    HintPlacement, HookAction, InvocationMode, KeyboardMode, LaunchProfile, MinimizedWindows,
+    PowerSaving, ShortcutInhibit, TextDirection, TitleRule, TransientWindows, WmConfig, WmHooks,
+    WmKeyBinding, WmModeOverrides, is_icon_theme_name,
+};
 pub use crate::schema_workspace::{LocalSesameConfig, WorkspaceConfig, WorkspaceSettings};
 
 /// Top-level PDS configuration.
@@ -122,6 +129,10 @@ pub struct LogConfig {
 
     /// Enable journald integration (Linux only).
     pub journald: bool,
+
+    /// Show window titles as short hashes in logs, crash reports and
+    /// `sesame wm list`. App IDs stay readable.
+    pub redact_titles_in_logs: bool,
 }
 
 impl Default for LogConfig {
@@ -130,6 +141,7 @@ impl Default for LogConfig {
             level: "info".into(),
             json: false,
             journald: true,
+            redact_titles_in_logs: false,
         }
     }
 }
@@ -36,6 +36,18 @@ pub struct InputConfig {
     pub layers: BTreeMap<String, BTreeMap<String, String>>,
 }
 
+/// Inherited variables withheld from launched apps by default: agent sockets
+/// and anything named like a credential.
+pub const DEFAULT_ENV_BLOCKLIST: &[&str] = &[
+    "SSH_AUTH_SOCK",
+    "SSH_AGENT_PID",
+    "GPG_AGENT_INFO",
+    "*_TOKEN",
+    "*_API_KEY",
+    "*_SECRET",
+    "*_PASSWORD",
+];
+
 /// Launcher configuration for a profile.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
@@ -44,6 +56,18 @@ pub struct LauncherConfig {
     pub max_results: usize,
     /// Enable frecency-based ranking.
     pub frecency: bool,
+    /// Index recently used files (`$XDG_DATA_HOME/recently-used.xbel`).
+    /// Off by default: it puts file names from other apps' history into
+    /// launcher results.
+    pub recent_files: bool,
+    /// Index recently opened editor projects (VS Code workspace storage).
+    /// Off by default.
+    pub recent_projects: bool,
+    /// Variables removed from the environment launched apps inherit from
+    /// daemon-launcher ([`env_pattern_matches`] patterns). Launch profiles
+    /// let some through with `allow_env`; their own `env` and `secrets`
+    /// are always set.
+    pub env_blocklist: Vec<String>,
 }
 
 impl Default for LauncherConfig {
@@ -51,10 +75,26 @@ impl Default for LauncherConfig {
         Self {
             max_results: 20,
             frecency: true,
+            recent_files: false,
+            recent_projects: false,
+            env_blocklist: DEFAULT_ENV_BLOCKLIST.iter().map(|&s| s.into()).collect(),
         }
     }
 }
 
+/// Whether environment variable `name` matches `pattern`: an exact name, or
+/// a prefix (`AWS_*`) or suffix (`*_TOKEN`) pattern with one `*`.
+#[must_use]
+pub fn env_pattern_matches(pattern: &str, name: &str) -> bool {
+    if let Some(prefix) = pattern.strip_suffix('*') {
+        name.starts_with(prefix)
+    } else if let Some(suffix) = pattern.strip_prefix('*') {
+        name.ends_with(suffix)
+    } else {
+        pattern == name
+    }
+}
+
 /// Audit log configuration for a profile.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
@@ -3,10 +3,11 @@
 //! Consumed by daemon-wm and daemon-launcher.
 
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 use std::collections::BTreeMap;
 
 /// Per-key app binding for hint assignment and launch-or-focus.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct WmKeyBinding {
     /// App ID patterns that match this key.
     #[serde(default)]
@@ -21,6 +22,64 @@ pub struct WmKeyBinding {
     /// Additional CLI arguments to pass to the launched command.
     #[serde(default)]
     pub launch_args: Vec<String>,
+    /// Terminal multiplexer whose windows/tabs are listed as extra overlay
+    /// rows under this app. Activating a row focuses the terminal and
+    /// switches the multiplexer to that window.
+    #[serde(default)]
+    pub multiplexer: Option<core_types::MultiplexerKind>,
+    /// Shell command to run when the key is pressed. Makes this a
+    /// command-only binding: no windows are matched, the key is never
+    /// assigned as a window hint, and `apps`/`launch` are ignored.
+    #[serde(default)]
+    pub command: Option<String>,
+    /// Name shown in the overlay for the matched apps, in place of the one
+    /// derived from the app ID (`org.gnome.Nautilus` → `Nautilus`).
+    #[serde(default)]
+    pub label: Option<String>,
+    /// Icon drawn before the name: an icon theme name such as
+    /// `utilities-terminal`, looked up in the XDG icon theme, or a glyph
+    /// such as an emoji.
+    #[serde(default)]
+    pub icon: Option<String>,
+}
+
+impl WmKeyBinding {
+    /// `icon`, if it is a glyph rather than an icon theme name.
+    #[must_use]
+    pub fn glyph(&self) -> Option<&str> {
+        self.icon
+            .as_deref()
+            .filter(|icon| !is_icon_theme_name(icon))
+    }
+
+    /// `icon`, if it is an icon theme name rather than a glyph.
+    #[must_use]
+    pub fn icon_name(&self) -> Option<&str> {
+        self.icon.as_deref().filter(|icon| is_icon_theme_name(icon))
+    }
+}
+
+/// Whether a key binding `icon` names an icon in the XDG icon theme (more
+/// than one ASCII letter or digit, like `utilities-terminal`) rather than
+/// being a glyph to draw as text.
+#[must_use]
+pub fn is_icon_theme_name(icon: &str) -> bool {
+    icon.chars()
+        .filter(char::is_ascii_alphanumeric)
+        .nth(1)
+        .is_some()
+}
+
+/// A window title rewrite applied before the overlay shows or searches the
+/// title (see `TitleRewriter`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TitleRule {
+    /// Regular expression matched anywhere in the title.
+    pub pattern: String,
+    /// Replacement for every match; `$1` or `${name}` insert capture groups.
+    /// Empty by default, which deletes the match.
+    #[serde(default)]
+    pub replace: String,
 }
 
 /// A named, composable launch profile for environment injection.
@@ -43,17 +102,277 @@ pub struct LaunchProfile {
     /// the last tag wins (same merge semantics as `devshell`).
     #[serde(default)]
     pub cwd: Option<String>,
+    /// Env files (absolute paths) read at launch, in order, before `env` is
+    /// applied. See [`crate::parse_env_file`] for the format.
+    #[serde(default)]
+    pub env_files: Vec<String>,
+    /// Inherited variables kept despite the launcher's `env_blocklist`
+    /// (e.g. `SSH_AUTH_SOCK` for a terminal). Accumulates across tags.
+    #[serde(default)]
+    pub allow_env: Vec<String>,
+}
+
+/// How the overlay takes keyboard focus while it is visible.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyboardMode {
+    /// Grab all keyboard input (layer-shell `exclusive`). Most reliable, but
+    /// popups and input methods lose input while the overlay is open.
+    #[default]
+    Exclusive,
+    /// Let the compositor decide when the overlay gets focus (layer-shell
+    /// `on_demand`). Keys reach the overlay through daemon-input if the
+    /// compositor keeps focus elsewhere.
+    OnDemand,
+}
+
+/// Where the overlay draws hint badges.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HintPlacement {
+    /// One row per window in a centered card.
+    #[default]
+    List,
+    /// Each badge over its window, where the compositor reports the
+    /// window's position; other rows stay in a card at the bottom.
+    Windows,
+}
+
+/// Horizontal direction of the overlay's row layout.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TextDirection {
+    /// Right-to-left when the locale's language is written right-to-left
+    /// (`LC_ALL`, `LC_MESSAGES`, then `LANG`).
+    #[default]
+    Auto,
+    /// Badge, app name, then title, from left to right.
+    Ltr,
+    /// Mirrored: title, app name, then badge, from left to right.
+    Rtl,
+}
+
+/// How the picker lists transient windows (dialogs) whose parent window is
+/// also listed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransientWindows {
+    /// List each dialog directly below its parent, indented.
+    #[default]
+    Nest,
+    /// Leave dialogs out of the picker; activating the parent raises them.
+    Hide,
+    /// List dialogs like any other window, in MRU order.
+    Show,
+}
+
+/// How the picker lists minimized windows.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MinimizedWindows {
+    /// List them in MRU order like any other window, dimmed.
+    #[default]
+    Show,
+    /// Leave them out of the picker.
+    Hide,
+    /// List them after every other window, dimmed.
+    Last,
+}
+
+/// When the overlay throttles rendering to save power.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PowerSaving {
+    /// While power-profiles-daemon reports the `power-saver` profile.
+    #[default]
+    Auto,
+    /// Always.
+    On,
+    /// Never.
+    Off,
+}
+
+/// What activating the overlay does while the focused window is one that
+/// grabs keyboard shortcuts (`shortcut_inhibit_apps`), such as a VM or
+/// remote desktop viewer that may also have received the Alt+Tab.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ShortcutInhibit {
+    /// Open the picker at once with a warning; releasing Alt does not
+    /// switch, so pick with a hint and Enter or dismiss with Escape.
+    #[default]
+    Warn,
+    /// Ignore the activation, leaving the keys to the focused window.
+    Skip,
+    /// Activate as for any other window.
+    Off,
+}
+
+/// What a hook does: run a command, play a sound, or both.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HookAction {
+    /// Shell command, run by daemon-launcher with `sh -c`.
+    pub command: Option<String>,
+    /// Sound file, played with `paplay`.
+    pub sound: Option<String>,
+}
+
+impl HookAction {
+    /// The shell commands this hook runs: the sound, then the command.
+    #[must_use]
+    pub fn command_lines(&self) -> Vec<String> {
+        let sound = self
+            .sound
+            .as_deref()
+            .map(|path| format!("paplay '{}'", path.replace('\'', "'\\''")));
+        sound.into_iter().chain(self.command.clone()).collect()
+    }
+}
+
+/// Commands and sounds run when the overlay activates a window or is
+/// dismissed (`[wm.hooks]`), as feedback for switches that never show it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WmHooks {
+    /// Run when a window is activated, including by a quick switch.
+    pub activate: HookAction,
+    /// Run when the overlay is dismissed without activating anything.
+    pub cancel: HookAction,
+    /// Activation hooks for particular apps, used instead of `activate`.
+    /// Keys are matched like `key_bindings` app patterns.
+    pub apps: BTreeMap<String, HookAction>,
+    /// Hooks that would run within this many milliseconds of the last one
+    /// are skipped, so holding Alt+Tab does not queue a burst of sounds.
+    pub min_interval_ms: u32,
+}
+
+impl Default for WmHooks {
+    fn default() -> Self {
+        Self {
+            activate: HookAction::default(),
+            cancel: HookAction::default(),
+            apps: BTreeMap::new(),
+            min_interval_ms: 300,
+        }
+    }
+}
+
+impl WmHooks {
+    /// Every shell command a hook can run. daemon-launcher runs only these
+    /// (and palette or key binding commands) for daemon-wm.
+    pub fn command_lines(&self) -> impl Iterator<Item = String> + '_ {
+        [&self.activate, &self.cancel]
+            .into_iter()
+            .chain(self.apps.values())
+            .flat_map(HookAction::command_lines)
+    }
+}
+
+/// How the overlay was invoked, selecting `[wm.switcher]` or `[wm.launcher]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvocationMode {
+    /// Alt+Tab and Alt+Shift+Tab.
+    Switcher,
+    /// Alt+Space and Alt+Shift+Space.
+    Launcher,
+}
+
+/// Per-mode overrides of `[wm]` settings. Unset fields keep the `[wm]` value.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WmModeOverrides {
+    /// Overrides `overlay_delay_ms`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub overlay_delay_ms: Option<u32>,
+    /// Overrides `quick_switch_threshold_ms`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quick_switch_threshold_ms: Option<u32>,
+    /// Overrides `show_title`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub show_title: Option<bool>,
+    /// Overrides `show_app_id`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub show_app_id: Option<bool>,
+    /// Overrides `max_visible_windows`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_visible_windows: Option<u32>,
+    /// Overrides `page_rows`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page_rows: Option<u32>,
+    /// Overrides `pinned_apps`, the apps sorted ahead of the MRU order.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pinned_apps: Option<Vec<String>>,
+    /// Overrides `border_width`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub border_width: Option<f32>,
+    /// Overrides `border_color`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub border_color: Option<String>,
+    /// Overrides `background_color`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub background_color: Option<String>,
+    /// Overrides `card_color`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub card_color: Option<String>,
+    /// Overrides `text_color`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text_color: Option<String>,
+    /// Overrides `hint_color`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hint_color: Option<String>,
+    /// Overrides `hint_matched_color`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hint_matched_color: Option<String>,
+}
+
+impl WmModeOverrides {
+    /// Whether no setting is overridden.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    fn apply(&self, wm: &mut WmConfig) {
+        fn set<T: Clone>(target: &mut T, value: Option<&T>) {
+            if let Some(value) = value {
+                target.clone_from(value);
+            }
+        }
+        set(&mut wm.overlay_delay_ms, self.overlay_delay_ms.as_ref());
+        set(
+            &mut wm.quick_switch_threshold_ms,
+            self.quick_switch_threshold_ms.as_ref(),
+        );
+        set(&mut wm.show_title, self.show_title.as_ref());
+        set(&mut wm.show_app_id, self.show_app_id.as_ref());
+        set(
+            &mut wm.max_visible_windows,
+            self.max_visible_windows.as_ref(),
+        );
+        set(&mut wm.page_rows, self.page_rows.as_ref());
+        set(&mut wm.pinned_apps, self.pinned_apps.as_ref());
+        set(&mut wm.border_width, self.border_width.as_ref());
+        set(&mut wm.border_color, self.border_color.as_ref());
+        set(&mut wm.background_color, self.background_color.as_ref());
+        set(&mut wm.card_color, self.card_color.as_ref());
+        set(&mut wm.text_color, self.text_color.as_ref());
+        set(&mut wm.hint_color, self.hint_color.as_ref());
+        set(&mut wm.hint_matched_color, self.hint_matched_color.as_ref());
+    }
 }
 
 /// Window manager overlay configuration for a profile.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
+#[allow(clippy::struct_excessive_bools)] // Independent user-facing toggles, not a state machine
 pub struct WmConfig {
     /// Characters used for Vimium-style window hints (each char = one hint key).
     pub hint_keys: String,
     /// Delay (ms) before transitioning from border-only to full overlay.
     pub overlay_delay_ms: u32,
-    /// Delay (ms) after activation before dismissing the overlay.
+    /// Delay (ms) a single remaining match waits before it is activated,
+    /// with `auto_activate_single_match`.
     pub activation_delay_ms: u32,
     /// Border width (px) for the focused window indicator.
     pub border_width: f32,
@@ -72,6 +391,10 @@ pub struct WmConfig {
     /// Quick-switch threshold in ms -- Alt+Tab released within this time
     /// activates the previous window instantly (v1 default: 250ms).
     pub quick_switch_threshold_ms: u32,
+    /// Learn how long Alt is held for a quick switch and use a threshold
+    /// fitted to it instead of `quick_switch_threshold_ms`, once enough
+    /// releases are recorded (`sesame stats --timing`).
+    pub adaptive_quick_switch: bool,
     /// Per-key app bindings for hint assignment and launch-or-focus.
     #[serde(default)]
     pub key_bindings: BTreeMap<String, WmKeyBinding>,
@@ -81,6 +404,98 @@ pub struct WmConfig {
     pub show_app_id: bool,
     /// Maximum windows visible in the overlay list.
     pub max_visible_windows: u32,
+    /// Rows drawn in the card at once; further matches are counted in a
+    /// "+N more — keep typing" footer. `0` sizes the card to the screen and
+    /// scrolls.
+    pub page_rows: u32,
+    /// Milliseconds the selection must stay on a row whose title is cut
+    /// off before the full title is shown in a tooltip. `0` disables it.
+    pub title_tooltip_ms: u32,
+    /// Treat overlay input starting with `=` as a calculator expression and
+    /// `>` as a shell command. Commands only run after Enter is pressed twice
+    /// (confirm), never on modifier release.
+    pub command_palette: bool,
+    /// Pressing a hint key again cycles through its app's windows instead
+    /// of typing the longer hint (`g`, `g` rather than `gg`).
+    pub repeat_key_cycles: bool,
+    /// Draw a progress ring while the overlay is armed, filling up until the
+    /// card appears, so the Alt+Tab dwell time is visible.
+    pub show_delay_indicator: bool,
+    /// Dismiss the overlay after this many ms without keys or IPC activity,
+    /// so a forgotten overlay does not hold the keyboard grab. `0` disables.
+    pub overlay_timeout_ms: u32,
+    /// Clear typed input this many ms after it stops matching any hint or
+    /// key binding, so the next key starts over. `0` keeps it until erased.
+    pub dead_input_clear_ms: u32,
+    /// Activate the window after `activation_delay_ms` once typed hint or
+    /// search input matches only it, without Enter or releasing Alt.
+    pub auto_activate_single_match: bool,
+    /// Typed after a hint (`f!`) to launch a new instance of the key's app
+    /// even though it has windows, once per instance (`f!!!`). Empty
+    /// disables it; Shift+Enter still launches one.
+    pub launch_new_instance_key: String,
+    /// A key whose app was launched this many ms ago and has no window yet
+    /// does not launch it again, so an app slow to open its window is not
+    /// started twice. `0` disables the guard.
+    pub relaunch_guard_ms: u32,
+    /// List the compositor's workspaces as rows after the windows;
+    /// activating one switches to that workspace.
+    pub workspace_rows: bool,
+    /// List the outputs (monitors) as rows after the windows; activating
+    /// one focuses the most recently used window on it.
+    pub output_rows: bool,
+    /// Keyboard interactivity requested while the overlay is visible.
+    pub keyboard_mode: KeyboardMode,
+    /// Draw hint badges in the card (`list`) or over the windows they
+    /// name (`windows`).
+    pub hint_placement: HintPlacement,
+    /// Draw the overlay on every output, not only the one the compositor
+    /// places it on. Only that one takes keyboard focus.
+    pub show_on_all_outputs: bool,
+    /// Row layout direction; `auto` follows the locale.
+    pub text_direction: TextDirection,
+    /// Apps whose windows are listed first, in this order, ahead of the MRU
+    /// order. Matched like `key_bindings` app patterns.
+    pub pinned_apps: Vec<String>,
+    /// Leave the focused window out of the list instead of listing it last.
+    pub hide_current_window: bool,
+    /// Title rewrites applied in order, e.g. stripping " — Mozilla Firefox".
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub title_rules: Vec<TitleRule>,
+    /// Ask "close N windows? y/n" before closing windows from the overlay
+    /// (Ctrl+W, Ctrl+Shift+W). `false` closes immediately.
+    pub confirm_close: bool,
+    /// Dialogs and other transient windows, when the compositor reports
+    /// their parent window.
+    pub transient_windows: TransientWindows,
+    /// Minimized windows, when the compositor reports them. Activating one
+    /// restores it.
+    pub minimized_windows: MinimizedWindows,
+    /// Poll input less often and skip animations; `auto` follows the
+    /// system power profile.
+    pub power_saving: PowerSaving,
+    /// Activations while an app in `shortcut_inhibit_apps` is focused.
+    pub shortcut_inhibit: ShortcutInhibit,
+    /// Apps that grab keyboard shortcuts while focused, so Alt+Tab may
+    /// reach them too. Matched like `key_bindings` app patterns.
+    pub shortcut_inhibit_apps: Vec<String>,
+    /// Log which apps are chosen from the overlay, and how fast, to the
+    /// state database for `sesame stats`.
+    pub usage_stats: bool,
+    /// Serve the panel applet interface (`org.pds.WindowSwitcher`) on the
+    /// session bus. Read at startup.
+    pub applet_interface: bool,
+    /// Log time spent between switches made through sesame to the state
+    /// database for `sesame stats --focus-time`. Off by default.
+    pub focus_time: bool,
+    /// Commands and sounds run on activation and cancel.
+    pub hooks: WmHooks,
+    /// Overrides applied when the overlay is opened as a window switcher.
+    #[serde(skip_serializing_if = "WmModeOverrides::is_empty")]
+    pub switcher: WmModeOverrides,
+    /// Overrides applied when the overlay is opened as a launcher.
+    #[serde(skip_serializing_if = "WmModeOverrides::is_empty")]
+    pub launcher: WmModeOverrides,
 }
 
 impl Default for WmConfig {
@@ -97,6 +512,7 @@ impl Default for WmConfig {
             hint_color: "#646464".into(),
             hint_matched_color: "#4caf50".into(),
             quick_switch_threshold_ms: 250,
+            adaptive_quick_switch: false,
             key_bindings: [
                 (
                     "g",
@@ -124,8 +540,7 @@ impl Default for WmConfig {
                     WmKeyBinding {
                         apps: apps.into_iter().map(String::from).collect(),
                         launch: launch.map(String::from),
-                        tags: Vec::new(),
-                        launch_args: Vec::new(),
+                        ..Default::default()
                     },
                 )
             })
@@ -133,7 +548,71 @@ impl Default for WmConfig {
             show_title: true,
             show_app_id: false,
             max_visible_windows: 20,
+            page_rows: 0,
+            title_tooltip_ms: 600,
+            command_palette: false,
+            repeat_key_cycles: false,
+            show_delay_indicator: false,
+            overlay_timeout_ms: 30_000,
+            dead_input_clear_ms: 800,
+            auto_activate_single_match: false,
+            launch_new_instance_key: "!".into(),
+            relaunch_guard_ms: 5000,
+            workspace_rows: false,
+            output_rows: false,
+            keyboard_mode: KeyboardMode::Exclusive,
+            hint_placement: HintPlacement::List,
+            show_on_all_outputs: false,
+            text_direction: TextDirection::Auto,
+            pinned_apps: Vec::new(),
+            hide_current_window: false,
+            title_rules: Vec::new(),
+            confirm_close: true,
+            transient_windows: TransientWindows::Nest,
+            minimized_windows: MinimizedWindows::Show,
+            power_saving: PowerSaving::Auto,
+            shortcut_inhibit: ShortcutInhibit::Warn,
+            shortcut_inhibit_apps: [
+                "remote-viewer",
+                "virt-viewer",
+                "org.remmina.Remmina",
+                "org.gnome.Boxes",
+                "org.gnome.Connections",
+                "VirtualBox Machine",
+                "vmware",
+                "xfreerdp",
+                "sdl-freerdp",
+            ]
+            .into_iter()
+            .map(String::from)
+            .collect(),
+            usage_stats: true,
+            applet_interface: true,
+            focus_time: false,
+            hooks: WmHooks::default(),
+            switcher: WmModeOverrides::default(),
+            launcher: WmModeOverrides::default(),
+        }
+    }
+}
+
+impl WmConfig {
+    /// The effective settings for one invocation mode: this config with the
+    /// mode's `[wm.switcher]` or `[wm.launcher]` overrides applied.
+    ///
+    /// Borrows `self` when the mode overrides nothing.
+    #[must_use]
+    pub fn for_mode(&self, mode: InvocationMode) -> Cow<'_, WmConfig> {
+        let overrides = match mode {
+            InvocationMode::Switcher => &self.switcher,
+            InvocationMode::Launcher => &self.launcher,
+        };
+        if overrides.is_empty() {
+            return Cow::Borrowed(self);
         }
+        let mut wm = self.clone();
+        overrides.apply(&mut wm);
+        Cow::Owned(wm)
     }
 }
 
@@ -183,6 +662,21 @@ mod tests {
         assert!(kb.tags.is_empty());
     }
 
+    #[test]
+    fn wm_key_binding_icon_is_a_glyph_or_an_icon_theme_name() {
+        let mut kb: WmKeyBinding = toml::from_str(r#"apps = ["nautilus"]"#).unwrap();
+        assert_eq!((kb.glyph(), kb.icon_name()), (None, None));
+        for (icon, glyph, name) in [
+            ("📁", Some("📁"), None),
+            ("1️⃣", Some("1️⃣"), None),
+            ("folder", None, Some("folder")),
+            ("utilities-terminal", None, Some("utilities-terminal")),
+        ] {
+            kb.icon = Some(icon.into());
+            assert_eq!((kb.glyph(), kb.icon_name()), (glyph, name), "{icon}");
+        }
+    }
+
     #[test]
     fn wm_key_binding_with_launch_args() {
         let toml_str = r#"
@@ -210,4 +704,70 @@ mod tests {
             Some("/workspace/usrbinkat/github.com/org/repo")
         );
     }
+
+    #[test]
+    fn wm_keyboard_mode_parses_snake_case() {
+        let wm: WmConfig = toml::from_str(r#"keyboard_mode = "on_demand""#).unwrap();
+        assert_eq!(wm.keyboard_mode, KeyboardMode::OnDemand);
+        let wm: WmConfig = toml::from_str("").unwrap();
+        assert_eq!(wm.keyboard_mode, KeyboardMode::Exclusive);
+    }
+
+    #[test]
+    fn wm_hint_placement_parses_snake_case() {
+        let wm: WmConfig = toml::from_str(r#"hint_placement = "windows""#).unwrap();
+        assert_eq!(wm.hint_placement, HintPlacement::Windows);
+        let wm: WmConfig = toml::from_str("").unwrap();
+        assert_eq!(wm.hint_placement, HintPlacement::List);
+    }
+
+    #[test]
+    fn wm_minimized_windows_parses_snake_case() {
+        let wm: WmConfig = toml::from_str(r#"minimized_windows = "last""#).unwrap();
+        assert_eq!(wm.minimized_windows, MinimizedWindows::Last);
+        let wm: WmConfig = toml::from_str("").unwrap();
+        assert_eq!(wm.minimized_windows, MinimizedWindows::Show);
+    }
+
+    #[test]
+    fn mode_overrides_apply_only_to_their_mode() {
+        let wm: WmConfig = toml::from_str(
+            r##"
+            overlay_delay_ms = 200
+            show_app_id = false
+
+            [launcher]
+            overlay_delay_ms = 50
+            show_app_id = true
+            pinned_apps = ["firefox"]
+
+            [switcher]
+            card_color = "#000000"
+            "##,
+        )
+        .unwrap();
+
+        let launcher = wm.for_mode(InvocationMode::Launcher);
+        assert_eq!(launcher.overlay_delay_ms, 50);
+        assert!(launcher.show_app_id);
+        assert_eq!(launcher.pinned_apps, vec!["firefox"]);
+        assert_eq!(launcher.card_color, wm.card_color);
+
+        let switcher = wm.for_mode(InvocationMode::Switcher);
+        assert_eq!(switcher.overlay_delay_ms, 200);
+        assert!(!switcher.show_app_id);
+        assert!(switcher.pinned_apps.is_empty());
+        assert_eq!(switcher.card_color, "#000000");
+    }
+
+    #[test]
+    fn mode_without_overrides_borrows_base_config() {
+        let wm = WmConfig::default();
+        assert!(matches!(
+            wm.for_mode(InvocationMode::Launcher),
+            Cow::Borrowed(_)
+        ));
+        let serialized = toml::to_string(&wm).unwrap();
+        assert!(!serialized.contains("[launcher]"), "{serialized}");
+    }
 }
@@ -0,0 +1,149 @@
+//! Built-in overlay color themes.
+//!
+//! A preset is a complete set of `WmConfig` color fields. Presets are only
+//! applied on request (`sesame theme preview --theme NAME`); to use one,
+//! copy its colors into `[profiles.<name>.wm]`.
+
+use crate::schema::WmConfig;
+use core_types::ThemeColors;
+
+/// A named set of overlay colors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ThemePreset {
+    pub name: &'static str,
+    pub border_color: &'static str,
+    pub background_color: &'static str,
+    pub card_color: &'static str,
+    pub text_color: &'static str,
+    pub hint_color: &'static str,
+    pub hint_matched_color: &'static str,
+}
+
+/// Every built-in preset. `default` matches `WmConfig::default()`.
+pub const THEME_PRESETS: &[ThemePreset] = &[
+    ThemePreset {
+        name: "default",
+        border_color: "#89b4fa",
+        background_color: "#000000c8",
+        card_color: "#1e1e1ef0",
+        text_color: "#ffffff",
+        hint_color: "#646464",
+        hint_matched_color: "#4caf50",
+    },
+    ThemePreset {
+        name: "dracula",
+        border_color: "#bd93f9",
+        background_color: "#000000c8",
+        card_color: "#282a36f0",
+        text_color: "#f8f8f2",
+        hint_color: "#44475a",
+        hint_matched_color: "#6272a4",
+    },
+    ThemePreset {
+        name: "nord",
+        border_color: "#88c0d0",
+        background_color: "#000000c8",
+        card_color: "#2e3440f0",
+        text_color: "#eceff4",
+        hint_color: "#4c566a",
+        hint_matched_color: "#5e81ac",
+    },
+    ThemePreset {
+        name: "gruvbox",
+        border_color: "#fabd2f",
+        background_color: "#000000c8",
+        card_color: "#282828f0",
+        text_color: "#ebdbb2",
+        hint_color: "#504945",
+        hint_matched_color: "#79740e",
+    },
+    ThemePreset {
+        name: "catppuccin-latte",
+        border_color: "#1e66f5",
+        background_color: "#00000060",
+        card_color: "#eff1f5f0",
+        text_color: "#4c4f69",
+        hint_color: "#ccd0da",
+        hint_matched_color: "#a6d189",
+    },
+];
+
+/// Look up a built-in preset by name (ASCII-case-insensitive).
+#[must_use]
+pub fn theme_preset(name: &str) -> Option<&'static ThemePreset> {
+    THEME_PRESETS
+        .iter()
+        .find(|p| p.name.eq_ignore_ascii_case(name))
+}
+
+impl ThemePreset {
+    /// The preset's colors.
+    #[must_use]
+    pub fn colors(&self) -> ThemeColors {
+        ThemeColors {
+            border_color: self.border_color.into(),
+            background_color: self.background_color.into(),
+            card_color: self.card_color.into(),
+            text_color: self.text_color.into(),
+            hint_color: self.hint_color.into(),
+            hint_matched_color: self.hint_matched_color.into(),
+        }
+    }
+}
+
+impl WmConfig {
+    /// The color fields of this config.
+    #[must_use]
+    pub fn theme_colors(&self) -> ThemeColors {
+        ThemeColors {
+            border_color: self.border_color.clone(),
+            background_color: self.background_color.clone(),
+            card_color: self.card_color.clone(),
+            text_color: self.text_color.clone(),
+            hint_color: self.hint_color.clone(),
+            hint_matched_color: self.hint_matched_color.clone(),
+        }
+    }
+
+    /// Replace the color fields, leaving everything else as is.
+    pub fn set_theme_colors(&mut self, colors: ThemeColors) {
+        self.border_color = colors.border_color;
+        self.background_color = colors.background_color;
+        self.card_color = colors.card_color;
+        self.text_color = colors.text_color;
+        self.hint_color = colors.hint_color;
+        self.hint_matched_color = colors.hint_matched_color;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::{Config, ProfileConfig};
+    use core_types::TrustProfileName;
+
+    #[test]
+    fn default_preset_matches_default_config() {
+        assert_eq!(
+            theme_preset("default").unwrap().colors(),
+            WmConfig::default().theme_colors()
+        );
+        assert_eq!(theme_preset("Nord").unwrap().name, "nord");
+        assert!(theme_preset("solarized").is_none());
+    }
+
+    #[test]
+    fn presets_pass_validation() {
+        for preset in THEME_PRESETS {
+            let mut profile = ProfileConfig {
+                name: TrustProfileName::try_from("default").unwrap(),
+                ..Default::default()
+            };
+            profile.wm.set_theme_colors(preset.colors());
+            let mut config = Config::default();
+            config.profiles.insert("default".into(), profile);
+            let diags = crate::validate(&config);
+            assert!(diags.is_empty(), "{}: {diags:?}", preset.name);
+        }
+    }
+}
@@ -0,0 +1,113 @@
+//! Window title rewriting (`wm.title_rules`).
+//!
+//! Rules are regular expressions with replacements, applied in order to
+//! every window title before the overlay shows or searches it. They keep
+//! narrow cards readable: strip an app's " — Mozilla Firefox" suffix, or
+//! shorten long file paths to their last component.
+
+use crate::schema::TitleRule;
+use regex::Regex;
+use std::borrow::Cow;
+
+/// Compiled title rules.
+#[derive(Debug, Clone, Default)]
+pub struct TitleRewriter {
+    rules: Vec<(Regex, String)>,
+}
+
+impl TitleRewriter {
+    /// Compile `rules`. Rules whose pattern does not compile are skipped;
+    /// `validate()` reports them.
+    #[must_use]
+    pub fn new(rules: &[TitleRule]) -> Self {
+        let rules = rules
+            .iter()
+            .filter_map(|rule| match Regex::new(&rule.pattern) {
+                Ok(regex) => Some((regex, rule.replace.clone())),
+                Err(e) => {
+                    tracing::warn!(pattern = %rule.pattern, error = %e, "skipping invalid title rule");
+                    None
+                }
+            })
+            .collect();
+        Self { rules }
+    }
+
+    /// Whether no rule applies.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// `title` with every rule applied in order, each to the previous
+    /// rule's output. Surrounding whitespace left by a rule is trimmed. A
+    /// title the rules would erase entirely is kept as it was.
+    #[must_use]
+    pub fn rewrite<'a>(&self, title: &'a str) -> Cow<'a, str> {
+        let mut out = Cow::Borrowed(title);
+        for (regex, replace) in &self.rules {
+            if let Cow::Owned(rewritten) = regex.replace_all(&out, replace.as_str()) {
+                out = Cow::Owned(rewritten.trim().to_string());
+            }
+        }
+        if out.is_empty() {
+            return Cow::Borrowed(title);
+        }
+        out
+    }
+}
+
+/// The error of a rule pattern that does not compile.
+pub(crate) fn pattern_error(rule: &TitleRule) -> Option<String> {
+    Regex::new(&rule.pattern).err().map(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rewriter(rules: &[(&str, &str)]) -> TitleRewriter {
+        let rules: Vec<TitleRule> = rules
+            .iter()
+            .map(|(pattern, replace)| TitleRule {
+                pattern: (*pattern).into(),
+                replace: (*replace).into(),
+            })
+            .collect();
+        TitleRewriter::new(&rules)
+    }
+
+    #[test]
+    fn strips_suffix_and_collapses_paths_in_order() {
+        let titles = rewriter(&[
+            (" — Mozilla Firefox$", ""),
+            (r"(?:~|/[^/\s]+)(?:/[^/\s]+)*/([^/\s]+)", "…/$1"),
+        ]);
+        assert_eq!(titles.rewrite("Rust docs — Mozilla Firefox"), "Rust docs");
+        assert_eq!(
+            titles.rewrite("vim ~/src/open-sesame/daemon-wm/src/lib.rs"),
+            "vim …/lib.rs"
+        );
+        assert!(matches!(titles.rewrite("Terminal"), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn keeps_titles_the_rules_would_erase() {
+        let titles = rewriter(&[(".*", "")]);
+        assert_eq!(titles.rewrite("Mozilla Firefox"), "Mozilla Firefox");
+    }
+
+    #[test]
+    fn skips_invalid_patterns() {
+        let titles = rewriter(&[("(unclosed", ""), ("^Inbox - ", "")]);
+        assert_eq!(titles.rewrite("Inbox - Thunderbird"), "Thunderbird");
+        assert!(
+            pattern_error(&TitleRule {
+                pattern: "(unclosed".into(),
+                replace: String::new(),
+            })
+            .is_some()
+        );
+        assert!(rewriter(&[]).is_empty());
+    }
+}
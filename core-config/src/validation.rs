@@ -3,7 +3,7 @@
 use crate::schema::Config;
 use core_types::TrustProfileName;
 use std::collections::HashSet;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Severity level for configuration diagnostics.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -30,6 +30,11 @@ pub struct ConfigDiagnostic {
 /// - Circular profile inheritance (`extends` chains must be acyclic)
 /// - Referenced profiles in `extends` fields exist
 /// - Policy-locked fields are not overridden
+/// - Key bindings are reachable: keys can be typed, app patterns are not
+///   claimed by two keys, and `launch` names an installed desktop entry
+/// - Launch profile `cwd` and `devshell` paths exist
+/// - Colors parse as hex
+/// - `wm.title_rules` patterns are valid regular expressions
 #[must_use]
 pub fn validate(config: &Config) -> Vec<ConfigDiagnostic> {
     let mut diagnostics = Vec::new();
@@ -38,7 +43,16 @@ pub fn validate(config: &Config) -> Vec<ConfigDiagnostic> {
     check_circular_inheritance(config, &mut diagnostics);
     check_extends_references(config, &mut diagnostics);
     check_wm_config(config, &mut diagnostics);
+    check_title_rules(config, &mut diagnostics);
+    check_theme_contrast(config, &mut diagnostics);
+    check_key_bindings(config, &mut diagnostics);
+    check_duplicate_app_patterns(config, &mut diagnostics);
+    check_launch_targets(config, &desktop_entry_ids(), &mut diagnostics);
     check_launch_profiles(config, &mut diagnostics);
+    check_launch_profile_paths(config, &mut diagnostics);
+    check_launch_profile_env(config, &mut diagnostics);
+    check_hooks(config, &mut diagnostics);
+    check_colors(config, &mut diagnostics);
 
     diagnostics
 }
@@ -161,6 +175,22 @@ fn check_wm_config(config: &Config, diagnostics: &mut Vec<ConfigDiagnostic>) {
             });
         }
 
+        if (1..1000).contains(&wm.overlay_timeout_ms) {
+            diagnostics.push(ConfigDiagnostic {
+                severity: DiagnosticSeverity::Warning,
+                file: None,
+                line: None,
+                column: None,
+                message: format!(
+                    "profile '{name}': wm.overlay_timeout_ms={} dismisses the overlay before it can be used",
+                    wm.overlay_timeout_ms
+                ),
+                remediation: Some(
+                    "set wm.overlay_timeout_ms to at least 1000, or 0 to disable".into(),
+                ),
+            });
+        }
+
         if !(1.0..=20.0).contains(&wm.border_width) {
             diagnostics.push(ConfigDiagnostic {
                 severity: DiagnosticSeverity::Warning,
@@ -177,6 +207,289 @@ fn check_wm_config(config: &Config, diagnostics: &mut Vec<ConfigDiagnostic>) {
     }
 }
 
+/// Title rule patterns must compile; the overlay skips any that do not.
+fn check_title_rules(config: &Config, diagnostics: &mut Vec<ConfigDiagnostic>) {
+    for (name, profile) in &config.profiles {
+        for (i, rule) in profile.wm.title_rules.iter().enumerate() {
+            if let Some(error) = crate::title_rules::pattern_error(rule) {
+                diagnostics.push(ConfigDiagnostic {
+                    severity: DiagnosticSeverity::Error,
+                    file: None,
+                    line: None,
+                    column: None,
+                    message: format!(
+                        "profile '{name}': wm.title_rules[{i}] pattern '{}' is not a valid regex: {error}",
+                        rule.pattern
+                    ),
+                    remediation: Some(
+                        "fix the pattern; see https://docs.rs/regex for the syntax".into(),
+                    ),
+                });
+            }
+        }
+    }
+}
+
+/// Minimum WCAG contrast ratio between overlay text and what it is drawn
+/// on. The overlay lightens or darkens text that falls below it.
+pub const MIN_TEXT_CONTRAST: f64 = 3.0;
+
+/// WCAG relative luminance of sRGB-encoded channels in `0.0..=1.0`. The
+/// overlay's runtime contrast correction uses the same formula.
+#[must_use]
+pub fn relative_luminance(r: f64, g: f64, b: f64) -> f64 {
+    let linear = |c: f64| {
+        if c <= 0.040_45 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    0.2126 * linear(r) + 0.7152 * linear(g) + 0.0722 * linear(b)
+}
+
+/// Warn about user-set colors that leave overlay text unreadable.
+///
+/// Only pairs where at least one side differs from the default are checked;
+/// the overlay corrects such pairs at runtime, and this warning says so.
+/// Alpha is ignored since the backdrop behind the card is not known here.
+fn check_theme_contrast(config: &Config, diagnostics: &mut Vec<ConfigDiagnostic>) {
+    let defaults = crate::schema::WmConfig::default();
+    for (name, profile) in &config.profiles {
+        let wm = &profile.wm;
+        let pairs = [
+            (
+                "text_color",
+                &wm.text_color,
+                &defaults.text_color,
+                "card_color",
+                &wm.card_color,
+                &defaults.card_color,
+            ),
+            (
+                "text_color",
+                &wm.text_color,
+                &defaults.text_color,
+                "hint_color",
+                &wm.hint_color,
+                &defaults.hint_color,
+            ),
+            (
+                "text_color",
+                &wm.text_color,
+                &defaults.text_color,
+                "hint_matched_color",
+                &wm.hint_matched_color,
+                &defaults.hint_matched_color,
+            ),
+        ];
+        for (fg_key, fg, fg_default, bg_key, bg, bg_default) in pairs {
+            if fg == fg_default && bg == bg_default {
+                continue;
+            }
+            let (Some(fg_lum), Some(bg_lum)) = (hex_luminance(fg), hex_luminance(bg)) else {
+                continue;
+            };
+            let ratio = (fg_lum.max(bg_lum) + 0.05) / (fg_lum.min(bg_lum) + 0.05);
+            if ratio < MIN_TEXT_CONTRAST {
+                diagnostics.push(ConfigDiagnostic {
+                    severity: DiagnosticSeverity::Warning,
+                    file: None,
+                    line: None,
+                    column: None,
+                    message: format!(
+                        "profile '{name}': wm.{fg_key} \"{fg}\" on wm.{bg_key} \"{bg}\" has contrast {ratio:.1}:1, below {MIN_TEXT_CONTRAST}:1"
+                    ),
+                    remediation: Some(format!(
+                        "the overlay adjusts the text lightness automatically; pick a lighter or darker wm.{fg_key} or wm.{bg_key} to keep your colors as written"
+                    )),
+                });
+            }
+        }
+    }
+}
+
+/// WCAG relative luminance of a `#RRGGBB[AA]` color, ignoring alpha.
+fn hex_luminance(hex: &str) -> Option<f64> {
+    let hex = hex.strip_prefix('#').unwrap_or(hex);
+    if hex.len() != 6 && hex.len() != 8 {
+        return None;
+    }
+    let channel = |i: usize| -> Option<f64> {
+        Some(f64::from(u8::from_str_radix(hex.get(i..i + 2)?, 16).ok()?) / 255.0)
+    };
+    Some(relative_luminance(channel(0)?, channel(2)?, channel(4)?))
+}
+
+fn check_key_bindings(config: &Config, diagnostics: &mut Vec<ConfigDiagnostic>) {
+    for (name, profile) in &config.profiles {
+        for (key, binding) in &profile.wm.key_bindings {
+            // The overlay matches one lowercased letter or digit against the
+            // key; anything else is dropped or starts search/palette input.
+            let mut chars = key.chars();
+            let typable = matches!(
+                (chars.next(), chars.next()),
+                (Some(c), None) if c.is_alphanumeric() && !c.is_uppercase()
+            );
+            if !typable {
+                let lower = key.to_lowercase();
+                let remediation = if lower != *key && lower.chars().count() == 1 {
+                    format!("rename key binding '{key}' to '{lower}'")
+                } else {
+                    format!("rename key binding '{key}' to a single lowercase letter or digit")
+                };
+                diagnostics.push(ConfigDiagnostic {
+                    severity: DiagnosticSeverity::Warning,
+                    file: None,
+                    line: None,
+                    column: None,
+                    message: format!(
+                        "profile '{name}': key binding '{key}' can never be typed in the overlay"
+                    ),
+                    remediation: Some(remediation),
+                });
+            }
+            if binding.command.is_some()
+                && (!binding.apps.is_empty()
+                    || binding.launch.is_some()
+                    || binding.multiplexer.is_some())
+            {
+                diagnostics.push(ConfigDiagnostic {
+                    severity: DiagnosticSeverity::Warning,
+                    file: None,
+                    line: None,
+                    column: None,
+                    message: format!(
+                        "profile '{name}': key binding '{key}' sets command, so its apps, \
+                         launch and multiplexer are ignored"
+                    ),
+                    remediation: Some(format!(
+                        "remove apps/launch/multiplexer from key binding '{key}', or move the \
+                         command to a key of its own"
+                    )),
+                });
+            }
+        }
+    }
+}
+
+/// Warn about app patterns listed under two keys. Windows go to the first
+/// key in sorted order, so the later listing never takes effect.
+fn check_duplicate_app_patterns(config: &Config, diagnostics: &mut Vec<ConfigDiagnostic>) {
+    for (name, profile) in &config.profiles {
+        let listed: Vec<(&str, &str)> = profile
+            .wm
+            .key_bindings
+            .iter()
+            .filter(|(_, binding)| binding.command.is_none())
+            .flat_map(|(key, binding)| {
+                binding
+                    .apps
+                    .iter()
+                    .map(move |pattern| (key.as_str(), pattern.as_str()))
+            })
+            .collect();
+        let mut reported = HashSet::new();
+        for (i, &(first_key, first)) in listed.iter().enumerate() {
+            for &(key, pattern) in &listed[i + 1..] {
+                if key == first_key
+                    || !patterns_overlap(first, pattern)
+                    || !reported.insert((key, pattern))
+                {
+                    continue;
+                }
+                diagnostics.push(ConfigDiagnostic {
+                    severity: DiagnosticSeverity::Warning,
+                    file: None,
+                    line: None,
+                    column: None,
+                    message: format!(
+                        "profile '{name}': app pattern '{pattern}' under key binding '{key}' \
+                         matches the same windows as '{first}' under '{first_key}', which wins"
+                    ),
+                    remediation: Some(format!(
+                        "remove '{pattern}' from key binding '{key}' or '{first}' from \
+                         key binding '{first_key}'"
+                    )),
+                });
+            }
+        }
+    }
+}
+
+/// Whether two app patterns name the same app, under the matching rules of
+/// daemon-wm: whole app ID or its last reverse-DNS segment, ignoring case.
+fn patterns_overlap(a: &str, b: &str) -> bool {
+    let segment = |p: &str| p.rsplit('.').next().unwrap_or(p).to_lowercase();
+    let (a_lower, b_lower) = (a.to_lowercase(), b.to_lowercase());
+    a_lower == b_lower || a_lower == segment(b) || b_lower == segment(a)
+}
+
+/// Desktop entry IDs in the XDG application directories, as daemon-launcher
+/// resolves `launch` against them.
+fn desktop_entry_ids() -> Vec<String> {
+    crate::loader::application_dirs()
+        .iter()
+        .filter_map(|dir| std::fs::read_dir(dir).ok())
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.ok()?.file_name().into_string().ok()?;
+            name.strip_suffix(".desktop").map(String::from)
+        })
+        .collect()
+}
+
+/// Warn about `launch` values that name no installed desktop entry.
+///
+/// Matches like daemon-launcher: exact ID, last reverse-DNS segment, or ID
+/// ignoring case. Bindings left at their default are skipped, as is the
+/// whole check when no desktop entries are found (e.g. in a container).
+fn check_launch_targets(
+    config: &Config,
+    entry_ids: &[String],
+    diagnostics: &mut Vec<ConfigDiagnostic>,
+) {
+    if entry_ids.is_empty() {
+        return;
+    }
+    let defaults = crate::schema::WmConfig::default().key_bindings;
+    let installed = |launch: &str| {
+        let lower = launch.to_lowercase();
+        entry_ids.iter().any(|id| {
+            id == launch
+                || id.to_lowercase() == lower
+                || id
+                    .rsplit('.')
+                    .next()
+                    .is_some_and(|s| s.to_lowercase() == lower)
+        })
+    };
+    for (name, profile) in &config.profiles {
+        for (key, binding) in &profile.wm.key_bindings {
+            let Some(launch) = binding.launch.as_deref() else {
+                continue;
+            };
+            let default_launch = defaults.get(key).and_then(|b| b.launch.as_deref());
+            if binding.command.is_some() || default_launch == Some(launch) || installed(launch) {
+                continue;
+            }
+            diagnostics.push(ConfigDiagnostic {
+                severity: DiagnosticSeverity::Warning,
+                file: None,
+                line: None,
+                column: None,
+                message: format!(
+                    "profile '{name}': key binding '{key}' launches '{launch}', \
+                     which matches no installed desktop entry"
+                ),
+                remediation: Some(format!(
+                    "install '{launch}', or set launch to an ID from `sesame launch search {launch}`"
+                )),
+            });
+        }
+    }
+}
+
 fn check_launch_profiles(config: &Config, diagnostics: &mut Vec<ConfigDiagnostic>) {
     for (profile_name, profile) in &config.profiles {
         for (key, binding) in &profile.wm.key_bindings {
@@ -255,6 +568,298 @@ fn check_launch_profiles(config: &Config, diagnostics: &mut Vec<ConfigDiagnostic
     }
 }
 
+/// Check the paths launch profiles hand to daemon-launcher, which refuses a
+/// relative or missing `cwd` and runs `nix develop` for a devshell.
+fn check_launch_profile_paths(config: &Config, diagnostics: &mut Vec<ConfigDiagnostic>) {
+    let mut nix_checked = false;
+    for (profile_name, profile) in &config.profiles {
+        for (lp_name, lp) in &profile.launch_profiles {
+            if let Some(cwd) = lp.cwd.as_deref() {
+                let path = Path::new(cwd);
+                let problem = if !path.is_absolute() {
+                    Some("is not an absolute path")
+                } else if !path.is_dir() {
+                    Some("is not an existing directory")
+                } else {
+                    None
+                };
+                if let Some(problem) = problem {
+                    diagnostics.push(ConfigDiagnostic {
+                        severity: DiagnosticSeverity::Warning,
+                        file: None,
+                        line: None,
+                        column: None,
+                        message: format!(
+                            "profile '{profile_name}': launch profile '{lp_name}' cwd \
+                             '{cwd}' {problem}, so launches using it fail"
+                        ),
+                        remediation: Some(format!(
+                            "set [profiles.{profile_name}.launch_profiles.{lp_name}] cwd to an \
+                             absolute path of an existing directory"
+                        )),
+                    });
+                }
+            }
+            let Some(devshell) = lp.devshell.as_deref() else {
+                continue;
+            };
+            let flake = devshell
+                .split_once('#')
+                .map_or(devshell, |(flake, _)| flake);
+            if flake.starts_with('/') && !Path::new(flake).exists() {
+                diagnostics.push(ConfigDiagnostic {
+                    severity: DiagnosticSeverity::Warning,
+                    file: None,
+                    line: None,
+                    column: None,
+                    message: format!(
+                        "profile '{profile_name}': launch profile '{lp_name}' devshell \
+                         '{devshell}' points at '{flake}', which does not exist"
+                    ),
+                    remediation: Some(format!(
+                        "fix the flake path in the devshell of launch profile '{lp_name}'"
+                    )),
+                });
+            }
+            if !nix_checked {
+                nix_checked = true;
+                if !on_path("nix") {
+                    diagnostics.push(ConfigDiagnostic {
+                        severity: DiagnosticSeverity::Warning,
+                        file: None,
+                        line: None,
+                        column: None,
+                        message: format!(
+                            "profile '{profile_name}': launch profile '{lp_name}' sets a \
+                             devshell, but `nix` is not on PATH"
+                        ),
+                        remediation: Some(
+                            "install Nix, or remove devshell from launch profiles".into(),
+                        ),
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// Variable name suffixes that usually hold a credential.
+const SECRET_NAME_SUFFIXES: &[&str] = &["_TOKEN", "_API_KEY", "_SECRET", "_PASSWORD", "_PASSWD"];
+
+/// Value prefixes of well-known token formats: GitHub, GitLab, `sk-` API
+/// keys, Slack and AWS access keys.
+const SECRET_VALUE_PREFIXES: &[&str] = &[
+    "ghp_",
+    "gho_",
+    "github_pat_",
+    "glpat-",
+    "sk-",
+    "xoxb-",
+    "xoxp-",
+    "AKIA",
+];
+
+/// Whether an env entry looks like a credential, by name or value.
+fn looks_like_secret(key: &str, value: &str) -> bool {
+    let upper = key.to_ascii_uppercase();
+    !value.is_empty()
+        && (SECRET_NAME_SUFFIXES.iter().any(|s| upper.ends_with(s))
+            || SECRET_VALUE_PREFIXES.iter().any(|p| value.starts_with(p)))
+}
+
+/// Check launch profile `env` entries and `env_files`. Files must be
+/// absolute, readable and parse; entries that look like secrets are
+/// warned about, since they sit in plain text, and `env` entries are never
+/// scrubbed from launched apps. The vault and `secrets` keep them out of
+/// both.
+fn check_launch_profile_env(config: &Config, diagnostics: &mut Vec<ConfigDiagnostic>) {
+    for (profile_name, profile) in &config.profiles {
+        let remediation = format!(
+            "store it with `sesame secret set -p {profile_name} <name>` and list the name in \
+             the launch profile's secrets instead"
+        );
+        for (lp_name, lp) in &profile.launch_profiles {
+            let context = format!("profile '{profile_name}': launch profile '{lp_name}'");
+            for (key, value) in &lp.env {
+                if looks_like_secret(key, value) {
+                    diagnostics.push(ConfigDiagnostic {
+                        severity: DiagnosticSeverity::Warning,
+                        file: None,
+                        line: None,
+                        column: None,
+                        message: format!(
+                            "{context} env '{key}' looks like a secret stored in plain text"
+                        ),
+                        remediation: Some(remediation.clone()),
+                    });
+                }
+            }
+            for path in &lp.env_files {
+                let warn =
+                    |line: Option<usize>, message: String, remediation: String| ConfigDiagnostic {
+                        severity: DiagnosticSeverity::Warning,
+                        file: Some(PathBuf::from(path)),
+                        line,
+                        column: None,
+                        message,
+                        remediation: Some(remediation),
+                    };
+                let entries = if Path::new(path).is_absolute() {
+                    std::fs::read_to_string(path)
+                        .map_err(|e| (None, e.to_string()))
+                        .and_then(|contents| {
+                            crate::parse_env_file(&contents, |name| std::env::var(name).ok())
+                                .map_err(|e| (Some(e.line), e.message))
+                        })
+                } else {
+                    Err((None, "is not an absolute path".into()))
+                };
+                match entries {
+                    Ok(entries) => {
+                        for (key, value) in entries {
+                            if looks_like_secret(&key, &value) {
+                                diagnostics.push(warn(
+                                    None,
+                                    format!(
+                                        "{context} env file '{path}' sets '{key}', which looks \
+                                         like a secret stored in plain text"
+                                    ),
+                                    remediation.clone(),
+                                ));
+                            }
+                        }
+                    }
+                    Err((line, problem)) => diagnostics.push(warn(
+                        line,
+                        format!(
+                            "{context} env file '{path}': {problem}, so launches using it fail"
+                        ),
+                        format!(
+                            "fix or remove '{path}' in env_files of launch profile '{lp_name}'"
+                        ),
+                    )),
+                }
+            }
+        }
+    }
+}
+
+/// Warn about `[wm.hooks]` sounds that cannot play: a missing file, or no
+/// `paplay` to play it with.
+fn check_hooks(config: &Config, diagnostics: &mut Vec<ConfigDiagnostic>) {
+    for (name, profile) in &config.profiles {
+        let hooks = &profile.wm.hooks;
+        let sounds: Vec<(String, &str)> =
+            [("activate", &hooks.activate), ("cancel", &hooks.cancel)]
+                .into_iter()
+                .map(|(hook, action)| (hook.to_string(), action))
+                .chain(
+                    hooks
+                        .apps
+                        .iter()
+                        .map(|(app, action)| (format!("apps.\"{app}\""), action)),
+                )
+                .filter_map(|(hook, action)| Some((hook, action.sound.as_deref()?)))
+                .collect();
+        for (hook, sound) in &sounds {
+            if !Path::new(sound).is_file() {
+                diagnostics.push(ConfigDiagnostic {
+                    severity: DiagnosticSeverity::Warning,
+                    file: None,
+                    line: None,
+                    column: None,
+                    message: format!(
+                        "profile '{name}': wm.hooks.{hook} sound '{sound}' is not a file"
+                    ),
+                    remediation: Some("set sound to the absolute path of a sound file".into()),
+                });
+            }
+        }
+        if !sounds.is_empty() && !on_path("paplay") {
+            diagnostics.push(ConfigDiagnostic {
+                severity: DiagnosticSeverity::Warning,
+                file: None,
+                line: None,
+                column: None,
+                message: format!(
+                    "profile '{name}': wm.hooks plays sounds with `paplay`, which is not on PATH"
+                ),
+                remediation: Some(
+                    "install pulseaudio-utils (also works with PipeWire), or use a hook command"
+                        .into(),
+                ),
+            });
+        }
+    }
+}
+
+/// Whether an executable named `program` is in a `$PATH` directory.
+fn on_path(program: &str) -> bool {
+    std::env::var_os("PATH").is_some_and(|path| {
+        std::env::split_paths(&path).any(|dir| {
+            std::fs::metadata(dir.join(program)).is_ok_and(|m| {
+                #[cfg(unix)]
+                {
+                    use std::os::unix::fs::PermissionsExt;
+                    m.is_file() && m.permissions().mode() & 0o111 != 0
+                }
+                #[cfg(not(unix))]
+                {
+                    m.is_file()
+                }
+            })
+        })
+    })
+}
+
+/// Warn about colors that are not `#RRGGBB[AA]`; the overlay ignores them
+/// and keeps the theme color.
+fn check_colors(config: &Config, diagnostics: &mut Vec<ConfigDiagnostic>) {
+    for (name, profile) in &config.profiles {
+        let wm = &profile.wm;
+        let mut colors = vec![
+            ("wm.border_color".to_string(), &wm.border_color),
+            ("wm.background_color".to_string(), &wm.background_color),
+            ("wm.card_color".to_string(), &wm.card_color),
+            ("wm.text_color".to_string(), &wm.text_color),
+            ("wm.hint_color".to_string(), &wm.hint_color),
+            ("wm.hint_matched_color".to_string(), &wm.hint_matched_color),
+        ];
+        for (mode, overrides) in [("switcher", &wm.switcher), ("launcher", &wm.launcher)] {
+            let set = [
+                ("border_color", &overrides.border_color),
+                ("background_color", &overrides.background_color),
+                ("card_color", &overrides.card_color),
+                ("text_color", &overrides.text_color),
+                ("hint_color", &overrides.hint_color),
+                ("hint_matched_color", &overrides.hint_matched_color),
+            ];
+            colors
+                .extend(set.into_iter().filter_map(|(key, value)| {
+                    Some((format!("wm.{mode}.{key}"), value.as_ref()?))
+                }));
+        }
+        for (key, value) in colors {
+            let hex = value.strip_prefix('#').unwrap_or(value);
+            if matches!(hex.len(), 6 | 8) && hex.chars().all(|c| c.is_ascii_hexdigit()) {
+                continue;
+            }
+            diagnostics.push(ConfigDiagnostic {
+                severity: DiagnosticSeverity::Warning,
+                file: None,
+                line: None,
+                column: None,
+                message: format!(
+                    "profile '{name}': {key} \"{value}\" is not a color; the overlay ignores it"
+                ),
+                remediation: Some(format!(
+                    "set {key} to \"#RRGGBB\" or \"#RRGGBBAA\", e.g. \"#1e1e1ef0\""
+                )),
+            });
+        }
+    }
+}
+
 fn check_extends_references(config: &Config, diagnostics: &mut Vec<ConfigDiagnostic>) {
     for (name, profile) in &config.profiles {
         if let Some(ref parent) = profile.extends
@@ -286,6 +891,33 @@ mod tests {
         TrustProfileName::try_from(s).unwrap()
     }
 
+    #[test]
+    fn warns_on_low_contrast_theme() {
+        let mut config = Config::default();
+        let mut profile = ProfileConfig {
+            name: tpn("default"),
+            ..Default::default()
+        };
+        profile.wm.card_color = "#e0e0e0f0".into();
+        config.profiles.insert("default".into(), profile);
+        let diags = validate(&config);
+        assert!(
+            diags
+                .iter()
+                .any(|d| d.severity == DiagnosticSeverity::Warning
+                    && d.message.contains("wm.card_color")),
+            "expected contrast warning, got: {diags:?}"
+        );
+
+        // The defaults are left alone, and readable overrides pass.
+        config.profiles.get_mut("default").unwrap().wm.card_color = "#101010".into();
+        let diags = validate(&config);
+        assert!(
+            !diags.iter().any(|d| d.message.contains("contrast")),
+            "unexpected contrast warning: {diags:?}"
+        );
+    }
+
     #[test]
     fn detects_circular_inheritance() {
         let mut config = Config::default();
@@ -374,7 +1006,7 @@ mod tests {
                 apps: vec!["ghostty".into()],
                 launch: Some("ghostty".into()),
                 tags: vec!["nonexistent".into()],
-                launch_args: Vec::new(),
+                ..Default::default()
             },
         );
         config.profiles.insert("default".into(), pc);
@@ -388,6 +1020,196 @@ mod tests {
         );
     }
 
+    #[test]
+    fn warns_when_command_binding_also_matches_apps() {
+        let mut config = Config::default();
+        let mut pc = ProfileConfig {
+            name: tpn("default"),
+            ..Default::default()
+        };
+        pc.wm.key_bindings.insert(
+            "p".into(),
+            crate::schema::WmKeyBinding {
+                apps: vec!["pavucontrol".into()],
+                command: Some("grim".into()),
+                ..Default::default()
+            },
+        );
+        config.profiles.insert("default".into(), pc);
+        let diags = validate(&config);
+        assert!(
+            diags
+                .iter()
+                .any(|d| d.severity == DiagnosticSeverity::Warning
+                    && d.message.contains("key binding 'p' sets command")),
+            "expected warning about ignored apps, got: {diags:?}"
+        );
+    }
+
+    fn binding(apps: &[&str], launch: Option<&str>) -> crate::schema::WmKeyBinding {
+        crate::schema::WmKeyBinding {
+            apps: apps.iter().map(|a| (*a).to_string()).collect(),
+            launch: launch.map(String::from),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn warns_on_keys_that_cannot_be_typed() {
+        let mut config = Config::default();
+        let mut pc = ProfileConfig {
+            name: tpn("default"),
+            ..Default::default()
+        };
+        for key in ["K", "ff", "/", "7"] {
+            pc.wm.key_bindings.insert(key.into(), binding(&[], None));
+        }
+        pc.wm.key_bindings.get_mut("7").unwrap().icon = Some("utilities-terminal".into());
+        config.profiles.insert("default".into(), pc);
+        let diags = validate(&config);
+        let untypable: Vec<_> = diags
+            .iter()
+            .filter(|d| d.message.contains("can never be typed"))
+            .collect();
+        assert_eq!(untypable.len(), 3, "{untypable:?}");
+        assert!(untypable.iter().any(|d| d.message.contains("'K'")
+            && d.remediation.as_deref() == Some("rename key binding 'K' to 'k'")));
+        assert!(!untypable.iter().any(|d| d.message.contains("'7'")));
+        // Icon theme names are looked up by the overlay, not rejected.
+        assert!(
+            !diags
+                .iter()
+                .any(|d| d.message.contains("utilities-terminal")),
+            "{diags:?}"
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_title_rule_patterns() {
+        let mut config = Config::default();
+        let mut pc = ProfileConfig {
+            name: tpn("default"),
+            ..Default::default()
+        };
+        for pattern in [" — Mozilla Firefox$", "(unclosed"] {
+            pc.wm.title_rules.push(crate::schema::TitleRule {
+                pattern: pattern.into(),
+                replace: String::new(),
+            });
+        }
+        config.profiles.insert("default".into(), pc);
+        let errors: Vec<_> = validate(&config)
+            .into_iter()
+            .filter(|d| d.severity == DiagnosticSeverity::Error)
+            .collect();
+        assert_eq!(errors.len(), 1, "{errors:?}");
+        assert!(errors[0].message.contains("wm.title_rules[1]"));
+    }
+
+    #[test]
+    fn warns_when_two_keys_list_the_same_app() {
+        let mut config = Config::default();
+        let mut pc = ProfileConfig {
+            name: tpn("default"),
+            ..Default::default()
+        };
+        pc.wm
+            .key_bindings
+            .insert("b".into(), binding(&["Firefox", "brave"], None));
+        config.profiles.insert("default".into(), pc);
+        let diags = validate(&config);
+        let duplicates: Vec<_> = diags
+            .iter()
+            .filter(|d| d.message.contains("matches the same windows"))
+            .collect();
+        // "Firefox" under 'b' wins over the default 'f' binding's
+        // "firefox" and "org.mozilla.firefox".
+        assert_eq!(duplicates.len(), 2, "{duplicates:?}");
+        assert!(
+            duplicates
+                .iter()
+                .all(|d| d.severity == DiagnosticSeverity::Warning
+                    && d.message.contains("under key binding 'f'")
+                    && d.message.contains("'Firefox' under 'b', which wins"))
+        );
+    }
+
+    #[test]
+    fn warns_on_launch_without_desktop_entry() {
+        let mut config = Config::default();
+        let mut pc = ProfileConfig {
+            name: tpn("default"),
+            ..Default::default()
+        };
+        pc.wm
+            .key_bindings
+            .insert("z".into(), binding(&["zed"], Some("zed")));
+        pc.wm
+            .key_bindings
+            .insert("k".into(), binding(&["kitty"], Some("kitty")));
+        config.profiles.insert("default".into(), pc);
+        let entries = ["dev.zed.Zed".to_string()];
+        let mut diags = Vec::new();
+        check_launch_targets(&config, &entries, &mut diags);
+        // "zed" resolves by last segment; the default bindings are skipped.
+        assert_eq!(diags.len(), 1, "{diags:?}");
+        assert!(diags[0].message.contains("launches 'kitty'"));
+
+        let mut diags = Vec::new();
+        check_launch_targets(&config, &[], &mut diags);
+        assert!(diags.is_empty(), "no entries found means no check");
+    }
+
+    #[test]
+    fn warns_on_bad_launch_profile_paths_and_colors() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = Config::default();
+        let mut pc = ProfileConfig {
+            name: tpn("default"),
+            ..Default::default()
+        };
+        pc.launch_profiles.insert(
+            "ok".into(),
+            crate::schema::LaunchProfile {
+                cwd: Some(dir.path().display().to_string()),
+                ..Default::default()
+            },
+        );
+        pc.launch_profiles.insert(
+            "relative".into(),
+            crate::schema::LaunchProfile {
+                cwd: Some("src".into()),
+                ..Default::default()
+            },
+        );
+        pc.launch_profiles.insert(
+            "gone".into(),
+            crate::schema::LaunchProfile {
+                devshell: Some(format!("{}/missing#rust", dir.path().display())),
+                ..Default::default()
+            },
+        );
+        pc.wm.card_color = "#12345".into();
+        pc.wm.launcher.hint_color = Some("green".into());
+        config.profiles.insert("default".into(), pc);
+        let diags = validate(&config);
+        let has = |needle: &str| diags.iter().any(|d| d.message.contains(needle));
+        assert!(
+            has("'relative' cwd 'src' is not an absolute path"),
+            "{diags:?}"
+        );
+        assert!(!has("'ok' cwd"), "{diags:?}");
+        assert!(has("/missing', which does not exist"), "{diags:?}");
+        assert!(has("wm.card_color \"#12345\" is not a color"), "{diags:?}");
+        assert!(has("wm.launcher.hint_color \"green\""), "{diags:?}");
+        assert!(
+            diags
+                .iter()
+                .all(|d| d.severity != DiagnosticSeverity::Error),
+            "{diags:?}"
+        );
+    }
+
     #[test]
     fn warns_on_missing_cross_profile_tag() {
         let mut config = Config::default();
@@ -401,7 +1223,7 @@ mod tests {
                 apps: vec!["ghostty".into()],
                 launch: Some("ghostty".into()),
                 tags: vec!["work:corp".into()],
-                launch_args: Vec::new(),
+                ..Default::default()
             },
         );
         config.profiles.insert("default".into(), pc);
@@ -441,7 +1263,7 @@ mod tests {
                 apps: vec!["ghostty".into()],
                 launch: Some("ghostty".into()),
                 tags: vec!["a".into(), "b".into()],
-                launch_args: Vec::new(),
+                ..Default::default()
             },
         );
         config.profiles.insert("default".into(), pc);
@@ -454,6 +1276,93 @@ mod tests {
         );
     }
 
+    #[test]
+    fn warns_on_plain_text_secrets_in_launch_env() {
+        let mut config = Config::default();
+        let mut pc = ProfileConfig {
+            name: tpn("default"),
+            ..Default::default()
+        };
+        pc.launch_profiles.insert(
+            "ai".into(),
+            crate::schema::LaunchProfile {
+                env: [
+                    ("GITHUB_TOKEN".into(), "abc123".into()),
+                    ("OPENAI_KEY".into(), "sk-proj-0123".into()),
+                    ("RUST_LOG".into(), "debug".into()),
+                ]
+                .into(),
+                ..Default::default()
+            },
+        );
+        config.profiles.insert("default".into(), pc);
+        let secrets: Vec<_> = validate(&config)
+            .into_iter()
+            .filter(|d| d.message.contains("looks like a secret"))
+            .collect();
+        assert_eq!(secrets.len(), 2, "got: {secrets:?}");
+        assert!(secrets.iter().any(|d| d.message.contains("'GITHUB_TOKEN'")));
+        assert!(secrets.iter().any(|d| d.message.contains("'OPENAI_KEY'")));
+    }
+
+    #[test]
+    fn checks_launch_profile_env_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let good = dir.path().join("dev.env");
+        std::fs::write(&good, "export RUST_LOG=debug\nNPM_TOKEN='npm_abc'\n").unwrap();
+        let bad = dir.path().join("bad.env");
+        std::fs::write(&bad, "A=1\nB=\"unterminated\n").unwrap();
+        let mut config = Config::default();
+        let mut pc = ProfileConfig {
+            name: tpn("default"),
+            ..Default::default()
+        };
+        pc.launch_profiles.insert(
+            "dev".into(),
+            crate::schema::LaunchProfile {
+                env_files: vec![
+                    good.display().to_string(),
+                    bad.display().to_string(),
+                    "relative.env".into(),
+                ],
+                ..Default::default()
+            },
+        );
+        config.profiles.insert("default".into(), pc);
+        let diags: Vec<_> = validate(&config)
+            .into_iter()
+            .filter(|d| d.message.contains("env file"))
+            .collect();
+        assert_eq!(diags.len(), 3, "got: {diags:?}");
+        assert!(diags[0].message.contains("'NPM_TOKEN'"));
+        assert_eq!(diags[1].line, Some(2));
+        assert!(diags[2].message.contains("not an absolute path"));
+    }
+
+    #[test]
+    fn warns_on_missing_hook_sound() {
+        let mut config = Config::default();
+        let mut pc = ProfileConfig {
+            name: tpn("default"),
+            ..Default::default()
+        };
+        pc.wm.hooks.apps.insert(
+            "firefox".into(),
+            crate::schema::HookAction {
+                command: None,
+                sound: Some("/nonexistent/ding.oga".into()),
+            },
+        );
+        config.profiles.insert("default".into(), pc);
+        let diags = validate(&config);
+        assert!(
+            diags
+                .iter()
+                .any(|d| d.message.contains("wm.hooks.apps.\"firefox\" sound")),
+            "got: {diags:?}"
+        );
+    }
+
     #[test]
     fn no_warning_for_valid_tags() {
         let mut config = Config::default();
@@ -474,7 +1383,7 @@ mod tests {
                 apps: vec!["ghostty".into()],
                 launch: Some("ghostty".into()),
                 tags: vec!["dev-rust".into()],
-                launch_args: Vec::new(),
+                ..Default::default()
             },
         );
         config.profiles.insert("default".into(), pc);
@@ -1,7 +1,8 @@
 //! Filesystem-based config hot-reload using the `notify` crate.
 
 use notify::{RecommendedWatcher, RecursiveMode, Watcher};
-use std::path::PathBuf;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, RwLock};
 use tracing::{info, warn};
 
@@ -44,51 +45,24 @@ impl ConfigWatcher {
     ) -> core_types::Result<(Self, Arc<RwLock<Config>>)> {
         let current = Arc::new(RwLock::new(initial_config));
         let current_clone = Arc::clone(&current);
-
-        // Collect watched config file paths so the callback can filter out
-        // unrelated changes in the same parent directory (e.g. audit.jsonl,
-        // vault DBs, salt files). Without this filter, ANY write to
-        // ~/.config/pds/ triggers a config reload cascade that causes 100%
-        // CPU via feedback loop: write → reload → IPC publish → more writes.
-        let watched_files: std::collections::HashSet<PathBuf> = config_paths
-            .iter()
-            .filter_map(|p| std::fs::canonicalize(p).ok().or_else(|| Some(p.clone())))
-            .collect();
-        // Also match by filename for drop-in directories where new files may appear.
-        let watched_extensions: Vec<String> = config_paths
-            .iter()
-            .filter_map(|p| p.extension().map(|e| e.to_string_lossy().to_string()))
-            .collect();
+        let roots = config_paths.to_vec();
+        // Files named by `include` directives reload the config too.
+        let included = crate::loader::included_files(config_paths);
+        let mut watched = WatchedFiles::new(&roots, &included.files);
 
         let watcher =
             notify::recommended_watcher(move |res: Result<notify::Event, notify::Error>| {
                 match res {
                     Ok(event) => {
                         if event.kind.is_modify() || event.kind.is_create() {
-                            // Filter: only react to changes in actual config files.
-                            let is_config_file = event.paths.iter().any(|p| {
-                                // Exact match against known config paths.
-                                if let Ok(canon) = std::fs::canonicalize(p)
-                                    && watched_files.contains(&canon)
-                                {
-                                    return true;
-                                }
-                                if watched_files.contains(p) {
-                                    return true;
-                                }
-                                // Extension match for drop-in fragments.
-                                if let Some(ext) = p.extension() {
-                                    let ext_str = ext.to_string_lossy();
-                                    return watched_extensions
-                                        .iter()
-                                        .any(|we| we == ext_str.as_ref());
-                                }
-                                false
-                            });
-                            if !is_config_file {
+                            if !event.paths.iter().any(|p| watched.contains(p)) {
                                 return;
                             }
                             info!(?event, "config file changed, reloading");
+                            // The edit may have changed what the include
+                            // lists name, or a new file may match a pattern.
+                            let included = crate::loader::included_files(&roots);
+                            watched = WatchedFiles::new(&roots, &included.files);
                             match crate::loader::load_config(None) {
                                 Ok(new_config) => {
                                     let diags = crate::validation::validate(&new_config);
@@ -126,12 +100,21 @@ impl ConfigWatcher {
             current: Arc::clone(&current),
         };
 
-        for path in config_paths {
-            if let Some(parent) = path.parent()
-                && parent.exists()
-                && let Err(e) = w.watcher.watch(parent, RecursiveMode::NonRecursive)
+        // Include pattern directories are watched as they are at startup;
+        // the notify thread cannot add watches from its own callback.
+        let mut dirs: Vec<&Path> = config_paths
+            .iter()
+            .chain(&included.files)
+            .filter_map(|p| p.parent())
+            .chain(included.dirs.iter().map(PathBuf::as_path))
+            .collect();
+        dirs.sort();
+        dirs.dedup();
+        for dir in dirs {
+            if dir.exists()
+                && let Err(e) = w.watcher.watch(dir, RecursiveMode::NonRecursive)
             {
-                warn!(path = %parent.display(), error = %e, "failed to watch config directory");
+                warn!(path = %dir.display(), error = %e, "failed to watch config directory");
             }
         }
 
@@ -144,3 +127,39 @@ impl ConfigWatcher {
         Arc::clone(&self.current)
     }
 }
+
+/// The config files a change must touch to trigger a reload.
+///
+/// Unrelated changes in the same directories (audit.jsonl, vault DBs, salt
+/// files) are filtered out. Without this filter, ANY write to
+/// ~/.config/pds/ triggers a config reload cascade that causes 100% CPU via
+/// feedback loop: write → reload → IPC publish → more writes.
+struct WatchedFiles {
+    files: HashSet<PathBuf>,
+    /// Extensions of the watched files, so fragments newly dropped into a
+    /// watched directory match too.
+    extensions: HashSet<String>,
+}
+
+impl WatchedFiles {
+    fn new(roots: &[PathBuf], included: &[PathBuf]) -> Self {
+        let paths = roots.iter().chain(included);
+        Self {
+            files: paths
+                .clone()
+                .map(|p| std::fs::canonicalize(p).unwrap_or_else(|_| p.clone()))
+                .collect(),
+            extensions: paths
+                .filter_map(|p| p.extension().map(|e| e.to_string_lossy().into_owned()))
+                .collect(),
+        }
+    }
+
+    fn contains(&self, path: &Path) -> bool {
+        std::fs::canonicalize(path).is_ok_and(|canon| self.files.contains(&canon))
+            || self.files.contains(path)
+            || path
+                .extension()
+                .is_some_and(|ext| self.extensions.contains(&*ext.to_string_lossy()))
+    }
+}
@@ -7,12 +7,15 @@
 //! - `FuzzyMatcher`: nucleo-backed parallel fuzzy matcher
 //! - `FrecencyDb`: SQLite-backed per-profile frecency index
 //! - `SearchEngine`: combines fuzzy + frecency for ranked results
+//! - `ItemSource`: pluggable providers of launchable items beyond desktop entries
 #![forbid(unsafe_code)]
 
 mod frecency;
 mod matcher;
 mod search;
+mod source;
 
 pub use frecency::FrecencyDb;
 pub use matcher::{FuzzyMatcher, MatchItem, inject_items};
 pub use search::{SearchEngine, SearchResult};
+pub use source::{ItemSource, SourceItem, collect_all, source_item_id};
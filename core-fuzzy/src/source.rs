@@ -0,0 +1,91 @@
+//! Pluggable item sources.
+//!
+//! Desktop entries are the launcher's primary index. Additional sources
+//! (recent files, editor projects, ...) implement [`ItemSource`] and are
+//! injected into the same matcher, so one query ranks everything with the
+//! same fuzzy + frecency scoring.
+
+use crate::MatchItem;
+
+/// A launchable item contributed by an [`ItemSource`].
+#[derive(Debug, Clone)]
+pub struct SourceItem {
+    /// Matcher entry. The ID must be namespaced with [`source_item_id`] so it
+    /// cannot collide with a desktop entry ID.
+    pub item: MatchItem,
+    /// Command that opens the item: program followed by its arguments.
+    pub command: Vec<String>,
+}
+
+/// A provider of launchable items beyond XDG desktop entries.
+///
+/// `collect()` may perform blocking I/O; callers run it off the async runtime.
+pub trait ItemSource: Send + Sync {
+    /// Short stable name, used as the ID namespace (e.g. `"recent"`).
+    fn name(&self) -> &'static str;
+
+    /// Collect the source's current items. Errors are the source's concern:
+    /// an unreadable or missing backing file yields an empty list.
+    fn collect(&self) -> Vec<SourceItem>;
+}
+
+/// Build a namespaced item ID: `"{source}:{key}"`.
+#[must_use]
+pub fn source_item_id(source: &str, key: &str) -> String {
+    format!("{source}:{key}")
+}
+
+/// Collect from every source, dropping items whose command is empty.
+pub fn collect_all(sources: &[Box<dyn ItemSource>]) -> Vec<SourceItem> {
+    sources
+        .iter()
+        .flat_map(|s| s.collect())
+        .filter(|i| !i.command.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Fixed(Vec<SourceItem>);
+
+    impl ItemSource for Fixed {
+        fn name(&self) -> &'static str {
+            "fixed"
+        }
+
+        fn collect(&self) -> Vec<SourceItem> {
+            self.0.clone()
+        }
+    }
+
+    fn item(key: &str, command: &[&str]) -> SourceItem {
+        SourceItem {
+            item: MatchItem {
+                id: source_item_id("fixed", key),
+                name: key.into(),
+                extra: String::new(),
+            },
+            command: command.iter().map(|s| (*s).to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn ids_are_namespaced() {
+        assert_eq!(source_item_id("recent", "/tmp/a"), "recent:/tmp/a");
+    }
+
+    #[test]
+    fn collect_all_drops_empty_commands() {
+        let sources: Vec<Box<dyn ItemSource>> = vec![
+            Box::new(Fixed(vec![item("a", &["xdg-open", "a"]), item("b", &[])])),
+            Box::new(Fixed(vec![item("c", &["code", "c"])])),
+        ];
+        let ids: Vec<String> = collect_all(&sources)
+            .into_iter()
+            .map(|i| i.item.id)
+            .collect();
+        assert_eq!(ids, vec!["fixed:a", "fixed:c"]);
+    }
+}
@@ -285,26 +285,18 @@ impl BusClient {
         timeout: Duration,
     ) -> core_types::Result<Message<EventKind>> {
         let msg = Message::new(&self.msg_ctx, event, security_level, self.epoch);
-        let msg_id = msg.msg_id;
-
-        let (tx, rx) = oneshot::channel();
-        self.pending.lock().await.insert(msg_id, tx);
-
-        self.send(&msg).await?;
+        await_response(&self.outbound_tx, &self.pending, msg, timeout).await
+    }
 
-        match tokio::time::timeout(timeout, rx).await {
-            Ok(Ok(response)) => Ok(response),
-            Ok(Err(_)) => {
-                self.pending.lock().await.remove(&msg_id);
-                Err(core_types::Error::Ipc("response channel dropped".into()))
-            }
-            Err(_) => {
-                self.pending.lock().await.remove(&msg_id);
-                Err(core_types::Error::Ipc(format!(
-                    "request timed out after {}ms",
-                    timeout.as_millis()
-                )))
-            }
+    /// A cloneable handle that sends requests on this client's connection,
+    /// for tasks that run while the owner keeps calling [`Self::recv`].
+    #[must_use]
+    pub fn requester(&self) -> BusRequester {
+        BusRequester {
+            msg_ctx: self.msg_ctx.clone(),
+            outbound_tx: self.outbound_tx.clone(),
+            pending: Arc::clone(&self.pending),
+            epoch: self.epoch,
         }
     }
 
@@ -615,6 +607,71 @@ async fn perform_rotation(
     Ok(())
 }
 
+/// Sends requests on a [`BusClient`]'s connection from another task.
+///
+/// Responses are routed to it by the client's I/O task, so it works without
+/// access to the client itself. Created by [`BusClient::requester`].
+#[derive(Clone)]
+pub struct BusRequester {
+    msg_ctx: MessageContext,
+    outbound_tx: mpsc::Sender<Vec<u8>>,
+    pending: Arc<Mutex<HashMap<Uuid, oneshot::Sender<Message<EventKind>>>>>,
+    epoch: Instant,
+}
+
+impl BusRequester {
+    /// Send a request and wait for a correlated response, like
+    /// [`BusClient::request`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error on send failure or timeout.
+    pub async fn request(
+        &self,
+        event: EventKind,
+        security_level: SecurityLevel,
+        timeout: Duration,
+    ) -> core_types::Result<Message<EventKind>> {
+        let msg = Message::new(&self.msg_ctx, event, security_level, self.epoch);
+        await_response(&self.outbound_tx, &self.pending, msg, timeout).await
+    }
+}
+
+/// Register a waiter for `msg`, send it, and wait up to `timeout` for the
+/// correlated response.
+async fn await_response(
+    outbound_tx: &mpsc::Sender<Vec<u8>>,
+    pending: &Mutex<HashMap<Uuid, oneshot::Sender<Message<EventKind>>>>,
+    msg: Message<EventKind>,
+    timeout: Duration,
+) -> core_types::Result<Message<EventKind>> {
+    let msg_id = msg.msg_id;
+
+    let (tx, rx) = oneshot::channel();
+    pending.lock().await.insert(msg_id, tx);
+
+    let payload = encode_frame(&msg)?;
+    outbound_tx
+        .send(payload)
+        .await
+        .map_err(|_| core_types::Error::Ipc("outbound channel closed".into()))?;
+
+    match tokio::time::timeout(timeout, rx).await {
+        Ok(Ok(response)) => Ok(response),
+        Ok(Err(_)) => {
+            pending.lock().await.remove(&msg_id);
+            Err(core_types::Error::Ipc("response channel dropped".into()))
+        }
+        Err(_) => {
+            pending.lock().await.remove(&msg_id);
+            Err(core_types::Error::Ipc(format!(
+                "request timed out after {}ms",
+                timeout.as_millis()
+            )))
+        }
+    }
+}
+
 /// Route an inbound payload to pending waiters or the broadcast channel.
 async fn route_inbound(
     payload: Vec<u8>,
@@ -100,7 +100,8 @@ pub struct BusServer {
     socket_path: Option<PathBuf>,
     state: Arc<ServerState>,
     /// Noise IK static keypair for the bus server.
-    /// Always `Some` from `bind()`. `None` only from `new()` (channel-wired mode).
+    /// Always `Some` from `bind()`/`from_listener()`. `None` only from `new()`
+    /// (channel-wired mode).
     keypair: Option<Arc<snow::Keypair>>,
 }
 
@@ -197,9 +198,50 @@ impl BusServer {
 
         tracing::info!(path = %path.display(), "IPC bus server bound");
 
-        Ok(Self {
+        Ok(Self::with_listener(
+            listener,
+            Some(path.to_owned()),
+            keypair,
+            registry,
+        ))
+    }
+
+    /// Serve on an already-bound listener, e.g. one inherited through
+    /// systemd socket activation.
+    ///
+    /// The socket file belongs to whoever created the listener, so it is not
+    /// removed when the server is dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the listener cannot be registered with the tokio
+    /// reactor.
+    pub fn from_listener(
+        listener: std::os::unix::net::UnixListener,
+        keypair: snow::Keypair,
+        registry: ClearanceRegistry,
+    ) -> core_types::Result<Self> {
+        listener.set_nonblocking(true).map_err(|e| {
+            core_types::Error::Ipc(format!("failed to set inherited socket non-blocking: {e}"))
+        })?;
+        let listener = UnixListener::from_std(listener).map_err(|e| {
+            core_types::Error::Ipc(format!("failed to adopt inherited socket: {e}"))
+        })?;
+
+        tracing::info!("IPC bus server using inherited listener");
+
+        Ok(Self::with_listener(listener, None, keypair, registry))
+    }
+
+    fn with_listener(
+        listener: UnixListener,
+        socket_path: Option<PathBuf>,
+        keypair: snow::Keypair,
+        registry: ClearanceRegistry,
+    ) -> Self {
+        Self {
             listener: Some(listener),
-            socket_path: Some(path.to_owned()),
+            socket_path,
             state: Arc::new(ServerState {
                 connections: RwLock::new(HashMap::new()),
                 pending_requests: RwLock::new(HashMap::new()),
@@ -210,7 +252,7 @@ impl BusServer {
                 name_to_conn: RwLock::new(HashMap::new()),
             }),
             keypair: Some(Arc::new(keypair)),
-        })
+        }
     }
 
     /// Run the accept loop. This future never completes unless the listener
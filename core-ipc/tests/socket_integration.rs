@@ -85,6 +85,41 @@ async fn server_bind_creates_socket_file() {
     assert!(sock.exists(), "socket file should exist after bind");
 }
 
+#[tokio::test]
+async fn inherited_listener_accepts_and_keeps_socket_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let sock = dir.path().join("bus.sock");
+    let keypair = generate_keypair().unwrap();
+    let server_pub: [u8; 32] = keypair.public().try_into().unwrap();
+    let client_kp = generate_keypair().unwrap();
+    let mut client_pub = [0u8; 32];
+    client_pub.copy_from_slice(client_kp.public());
+    let mut registry = ClearanceRegistry::new();
+    registry.register("test-default".into(), client_pub, SecurityLevel::Internal);
+
+    // Stands in for the socket systemd creates and passes via LISTEN_FDS.
+    let listener = std::os::unix::net::UnixListener::bind(&sock).unwrap();
+    let server = BusServer::from_listener(listener, keypair.into_inner(), registry).unwrap();
+
+    let server_handle = tokio::spawn(async move {
+        tokio::select! {
+            _ = server.run() => unreachable!(),
+            () = tokio::time::sleep(Duration::from_millis(500)) => {
+                server.connection_count().await
+            }
+        }
+    });
+
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    let _client = connect_with_keypair(did(1), &sock, &server_pub, &client_kp).await;
+
+    assert_eq!(server_handle.await.unwrap(), 1);
+    assert!(
+        sock.exists(),
+        "inherited socket file belongs to its creator"
+    );
+}
+
 #[tokio::test]
 async fn client_connect_and_server_accept() {
     let (server, dir, server_pub, client_kp) = start_server().await;
@@ -203,6 +238,61 @@ async fn request_response_correlation() {
     }
 }
 
+#[tokio::test]
+async fn requester_gets_responses_while_owner_receives() {
+    let (server, dir, server_pub, kps) = start_server_with_clients(2).await;
+    let sock = dir.path().join("bus.sock");
+
+    tokio::spawn(async move {
+        let _ = server.run().await;
+    });
+    tokio::time::sleep(Duration::from_millis(20)).await;
+
+    let mut client_a = connect_with_keypair(did(1), &sock, &server_pub, &kps[0]).await;
+    let mut client_b = connect_with_keypair(did(2), &sock, &server_pub, &kps[1]).await;
+
+    tokio::time::sleep(Duration::from_millis(20)).await;
+
+    let requester = client_a.requester();
+    let response_handle = tokio::spawn(async move {
+        requester
+            .request(
+                EventKind::StatusRequest,
+                SecurityLevel::Internal,
+                Duration::from_secs(2),
+            )
+            .await
+    });
+
+    let request_msg = tokio::time::timeout(Duration::from_millis(500), client_b.recv())
+        .await
+        .expect("timeout waiting for request")
+        .expect("channel closed");
+    assert!(matches!(request_msg.payload, EventKind::StatusRequest));
+
+    let msg_ctx = core_ipc::MessageContext::new(did(2));
+    let response = Message::new(
+        &msg_ctx,
+        EventKind::WmActivateWindowResponse { success: true },
+        SecurityLevel::Internal,
+        client_b.epoch(),
+    )
+    .with_correlation(request_msg.msg_id);
+    client_b.send(&response).await.unwrap();
+
+    let result = response_handle.await.unwrap().unwrap();
+    assert!(matches!(
+        result.payload,
+        EventKind::WmActivateWindowResponse { success: true }
+    ));
+    // The response went to the requester, not the owner's inbound queue.
+    assert!(
+        tokio::time::timeout(Duration::from_millis(50), client_a.recv())
+            .await
+            .is_err()
+    );
+}
+
 #[tokio::test]
 async fn launch_execute_response_roundtrip() {
     let (server, dir, server_pub, kps) = start_server_with_clients(2).await;
@@ -0,0 +1,241 @@
+//! One-time import of the flat files the state store replaces.
+//!
+//! Before the store, daemon-wm kept its state in `~/.cache/open-sesame`:
+//!
+//! | File | Format |
+//! |------|--------|
+//! | `mru` | `window-id<TAB>unix-time` per line, most recent first; the time is optional |
+//! | `marks` | `<letter> <window-id>` per line |
+//! | `window-order` | one window ID per line, oldest first |
+//! | `release-timings.json` | a JSON [`ReleaseTimings`] |
+//! | `usage.jsonl` | one JSON [`UsageEvent`] per line |
+//! | `focus-time.csv` | `start,end,app_id` CSV rows under that header; the app ID quoted if it has a comma or quote |
+//!
+//! [`StateStore::import_legacy`] reads whichever exist, replaces the
+//! matching tables (appending for the two logs), and renames each imported
+//! file to `<name>.imported` so it is never read twice. Malformed lines
+//! are skipped, as the old readers did.
+
+use crate::{MruEntry, StateStore, db_error, insert_focus_interval, insert_usage};
+use core_types::{FocusInterval, ReleaseTimings, UsageEvent};
+use rusqlite::TransactionBehavior;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+const MRU_FILE: &str = "mru";
+const MARKS_FILE: &str = "marks";
+const WINDOW_ORDER_FILE: &str = "window-order";
+const RELEASE_TIMINGS_FILE: &str = "release-timings.json";
+const USAGE_LOG_FILE: &str = "usage.jsonl";
+const FOCUS_TIME_FILE: &str = "focus-time.csv";
+
+/// Legacy files in the order they are imported.
+const FILES: [&str; 6] = [
+    MRU_FILE,
+    MARKS_FILE,
+    WINDOW_ORDER_FILE,
+    RELEASE_TIMINGS_FILE,
+    USAGE_LOG_FILE,
+    FOCUS_TIME_FILE,
+];
+
+/// What [`StateStore::import_legacy`] moved in.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ImportReport {
+    /// Names of the files imported and renamed.
+    pub files: Vec<String>,
+}
+
+impl StateStore {
+    /// Import the legacy flat files in `dir` (normally
+    /// `~/.cache/open-sesame`) and rename them to `<name>.imported`.
+    ///
+    /// All files are imported in one transaction. A file that cannot be
+    /// renamed afterwards is logged and would be imported again on the next
+    /// call, replacing newer stack-like state; the logs would double.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the import transaction fails; no file is renamed
+    /// then.
+    pub fn import_legacy(&mut self, dir: &Path) -> core_types::Result<ImportReport> {
+        let found: Vec<(&str, PathBuf, String)> = FILES
+            .iter()
+            .filter_map(|&name| {
+                let path = dir.join(name);
+                let contents = std::fs::read_to_string(&path).ok()?;
+                Some((name, path, contents))
+            })
+            .collect();
+        if found.is_empty() {
+            return Ok(ImportReport::default());
+        }
+
+        let tx = self
+            .conn
+            .transaction_with_behavior(TransactionBehavior::Immediate)
+            .map_err(db_error("legacy import"))?;
+        for (name, _, contents) in &found {
+            import_file(&tx, name, contents).map_err(db_error("legacy import"))?;
+        }
+        tx.commit().map_err(db_error("legacy import"))?;
+
+        let mut report = ImportReport::default();
+        for (name, path, _) in found {
+            let mut imported = path.clone().into_os_string();
+            imported.push(".imported");
+            if let Err(e) = std::fs::rename(&path, &imported) {
+                tracing::warn!(file = name, error = %e, "state: imported file could not be renamed");
+            }
+            report.files.push(name.to_string());
+        }
+        tracing::info!(files = ?report.files, "state: imported legacy cache files");
+        Ok(report)
+    }
+}
+
+fn import_file(conn: &rusqlite::Connection, name: &str, contents: &str) -> rusqlite::Result<()> {
+    match name {
+        MRU_FILE => crate::write_mru(conn, &parse_mru(contents)),
+        MARKS_FILE => crate::write_marks(conn, &parse_marks(contents)),
+        WINDOW_ORDER_FILE => crate::write_window_order(conn, &parse_lines(contents)),
+        RELEASE_TIMINGS_FILE => {
+            let timings: ReleaseTimings = serde_json::from_str(contents).unwrap_or_default();
+            crate::write_release_timings(conn, &timings)
+        }
+        USAGE_LOG_FILE => contents
+            .lines()
+            .filter_map(|line| serde_json::from_str::<UsageEvent>(line).ok())
+            .try_for_each(|event| insert_usage(conn, &event)),
+        FOCUS_TIME_FILE => contents
+            .lines()
+            .filter_map(parse_focus_row)
+            .try_for_each(|interval| insert_focus_interval(conn, &interval)),
+        _ => Ok(()),
+    }
+}
+
+fn parse_mru(contents: &str) -> Vec<MruEntry> {
+    contents
+        .lines()
+        .filter_map(|line| {
+            let (id, at) = line.split_once('\t').unwrap_or((line, ""));
+            let id = id.trim();
+            (!id.is_empty()).then(|| MruEntry {
+                window_id: id.to_string(),
+                focused_at: at.trim().parse().ok(),
+            })
+        })
+        .collect()
+}
+
+fn parse_marks(contents: &str) -> BTreeMap<char, String> {
+    contents
+        .lines()
+        .filter_map(|line| {
+            let (mark, id) = line.trim().split_once(' ')?;
+            let mut chars = mark.chars();
+            let mark = chars
+                .next()
+                .filter(|&c| c.is_ascii_lowercase() && chars.next().is_none())?;
+            let id = id.trim();
+            (!id.is_empty()).then(|| (mark, id.to_string()))
+        })
+        .collect()
+}
+
+/// One `focus-time.csv` row; `None` for the header and malformed rows.
+fn parse_focus_row(row: &str) -> Option<FocusInterval> {
+    let mut fields = row.splitn(3, ',');
+    let start = fields.next()?.trim().parse().ok()?;
+    let end = fields.next()?.trim().parse().ok()?;
+    let app_id = fields.next()?;
+    let app_id = match app_id.strip_prefix('"') {
+        Some(quoted) => quoted.strip_suffix('"')?.replace("\"\"", "\""),
+        None => app_id.to_string(),
+    };
+    Some(FocusInterval { start, end, app_id })
+}
+
+fn parse_lines(contents: &str) -> Vec<String> {
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parsers_skip_malformed_lines() {
+        let mru = parse_mru("win-A\t1700000300\nwin-B\n\nwin-C\tsoon\n");
+        assert_eq!(
+            mru.iter()
+                .map(|e| (e.window_id.as_str(), e.focused_at))
+                .collect::<Vec<_>>(),
+            [
+                ("win-A", Some(1_700_000_300)),
+                ("win-B", None),
+                ("win-C", None)
+            ]
+        );
+        let marks = parse_marks("a win-1\nB win-2\nab win-3\nc\n\n d  win-4 \n");
+        assert_eq!(
+            marks.into_iter().collect::<Vec<_>>(),
+            [('a', "win-1".to_string()), ('d', "win-4".to_string())]
+        );
+        assert_eq!(parse_lines(" A \n\nB\n"), ["A", "B"]);
+
+        let row = parse_focus_row("1,2,\"a,b\"\"c\"").unwrap();
+        assert_eq!((row.start, row.end, row.app_id.as_str()), (1, 2, "a,b\"c"));
+        assert_eq!(
+            parse_focus_row("1,2,org.mozilla.firefox").unwrap().app_id,
+            "org.mozilla.firefox"
+        );
+        assert!(parse_focus_row("start,end,app_id").is_none());
+        assert!(parse_focus_row("1,2").is_none());
+        assert!(parse_focus_row("1,2,\"unterminated").is_none());
+    }
+
+    #[test]
+    fn import_moves_every_file_in_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let write =
+            |name: &str, contents: &str| std::fs::write(dir.path().join(name), contents).unwrap();
+        write(MRU_FILE, "B\t20\nA\t10");
+        write(MARKS_FILE, "a A\n");
+        write(WINDOW_ORDER_FILE, "A\nB\n");
+        write(RELEASE_TIMINGS_FILE, r#"{"samples_ms":[150,210]}"#);
+        write(
+            USAGE_LOG_FILE,
+            "{\"at\":5,\"kind\":\"activate\",\"app\":\"kitty\",\"elapsed_ms\":300}\nnot json\n",
+        );
+        write(FOCUS_TIME_FILE, "start,end,app_id\n1,5,kitty\n");
+
+        let mut store = StateStore::open_in_memory().unwrap();
+        let report = store.import_legacy(dir.path()).unwrap();
+        assert_eq!(report.files.len(), FILES.len());
+        assert_eq!(store.mru().unwrap()[0].window_id, "B");
+        assert_eq!(
+            store.marks().unwrap().get(&'a').map(String::as_str),
+            Some("A")
+        );
+        assert_eq!(store.window_order().unwrap(), ["A", "B"]);
+        assert_eq!(store.release_timings().unwrap().samples_ms, [150, 210]);
+        assert_eq!(
+            store.usage_events(None).unwrap()[0].app.as_deref(),
+            Some("kitty")
+        );
+        assert_eq!(store.focus_intervals(None).unwrap()[0].seconds(), 4);
+
+        assert!(!dir.path().join(MRU_FILE).exists());
+        assert!(dir.path().join("mru.imported").exists());
+        // Nothing left to import: the logs are not doubled.
+        assert!(store.import_legacy(dir.path()).unwrap().files.is_empty());
+        assert_eq!(store.usage_events(None).unwrap().len(), 1);
+    }
+}
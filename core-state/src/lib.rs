@@ -0,0 +1,955 @@
+//! Local state store for daemon-wm and the `sesame` CLI.
+//!
+//! One `SQLite` database, `$XDG_STATE_HOME/open-sesame/state.db`, holds the
+//! window switcher's state:
+//!
+//! | Table | Contents | Written by |
+//! |-------|----------|------------|
+//! | `mru` | Window IDs, most recently focused first, with focus times | every switch |
+//! | `marks` | Mark letter to window ID | Ctrl+M in the picker |
+//! | `window_order` | Window IDs in first-seen order, for stable repeated hints | every activation |
+//! | `release_timings` | Alt hold times for `wm.adaptive_quick_switch` | plain Alt+Tab releases |
+//! | `usage_events` | The usage log for `sesame stats` (`wm.usage_stats`) | every overlay outcome |
+//! | `focus_intervals` | Time between switches for `sesame stats --focus-time` (`wm.focus_time`) | every switch |
+//! | `settings` | Named values the daemon keeps between runs, such as the first-run walkthrough's progress | as they change |
+//!
+//! These used to be separate flat files in `~/.cache/open-sesame`, each with
+//! its own parser and `flock` read-modify-write. Transactions replace the
+//! locking, and `PRAGMA user_version` numbers the schema so a newer build
+//! migrates it in place (`MIGRATIONS`). [`StateStore::import_legacy`] moves
+//! the old files in.
+//!
+//! The database is plaintext: like launcher frecency (ADR-LNC-002), window
+//! IDs and app usage are not secrets. Frecency keeps its per-profile
+//! databases, which is what isolates it between profiles.
+#![forbid(unsafe_code)]
+
+mod legacy;
+
+pub use legacy::ImportReport;
+
+use core_types::{FocusInterval, ReleaseTimings, UsageEvent, UsageKind};
+use rusqlite::{Connection, OptionalExtension, TransactionBehavior, params};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// File name of the state database in the state directory.
+pub const STATE_DB_FILE: &str = "state.db";
+
+/// Usage events kept; the oldest are dropped past this (about a year of
+/// heavy use).
+pub const MAX_USAGE_EVENTS: u64 = 50_000;
+
+/// How long a write waits for another process's transaction. Short: a
+/// daemon write queued behind a CLI `stats` or `bundle` run is dropped
+/// rather than held up.
+const BUSY_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// Schema migrations in order. `user_version` is the number applied.
+const MIGRATIONS: &[&str] = &[
+    // 1: the flat files of 1.14.
+    "CREATE TABLE mru (
+        position    INTEGER PRIMARY KEY,
+        window_id   TEXT NOT NULL UNIQUE,
+        focused_at  INTEGER
+    );
+    CREATE TABLE marks (
+        mark        TEXT PRIMARY KEY,
+        window_id   TEXT NOT NULL
+    );
+    CREATE TABLE window_order (
+        position    INTEGER PRIMARY KEY,
+        window_id   TEXT NOT NULL UNIQUE
+    );
+    CREATE TABLE release_timings (
+        id          INTEGER PRIMARY KEY AUTOINCREMENT,
+        ms          INTEGER NOT NULL
+    );
+    CREATE TABLE usage_events (
+        id          INTEGER PRIMARY KEY AUTOINCREMENT,
+        at          INTEGER NOT NULL,
+        kind        TEXT NOT NULL,
+        app         TEXT,
+        key         TEXT,
+        elapsed_ms  INTEGER NOT NULL
+    );
+    CREATE INDEX idx_usage_events_at ON usage_events (at);
+    CREATE TABLE focus_intervals (
+        start       INTEGER NOT NULL,
+        end         INTEGER NOT NULL,
+        app_id      TEXT NOT NULL
+    );
+    CREATE INDEX idx_focus_intervals_end ON focus_intervals (end);",
+    // 2: daemon settings.
+    "CREATE TABLE settings (
+        name        TEXT PRIMARY KEY,
+        value       TEXT NOT NULL
+    );",
+];
+
+/// Every table of recorded state, for whole-store operations.
+const TABLES: [&str; 6] = [
+    "mru",
+    "marks",
+    "window_order",
+    "release_timings",
+    "usage_events",
+    "focus_intervals",
+];
+
+/// The settings table: copied with the recorded state, but not counted by
+/// [`StateStore::is_empty`], so a fresh install is still empty.
+const SETTINGS_TABLE: &str = "settings";
+
+/// Schema version after the last migration.
+#[allow(clippy::cast_possible_truncation)] // a handful of migrations
+const SCHEMA_VERSION: u32 = MIGRATIONS.len() as u32;
+
+/// One window in the MRU stack.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MruEntry {
+    pub window_id: String,
+    /// Unix time in seconds the window was last focused, where recorded.
+    pub focused_at: Option<u64>,
+}
+
+/// Database size around a [`StateStore::vacuum`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VacuumReport {
+    pub before_bytes: u64,
+    pub after_bytes: u64,
+}
+
+/// Handle on the state database.
+pub struct StateStore {
+    conn: Connection,
+}
+
+impl StateStore {
+    /// `$XDG_STATE_HOME/open-sesame/state.db` (`~/.local/state` by
+    /// default; the local data directory where there is no state
+    /// directory).
+    #[must_use]
+    pub fn default_path() -> Option<PathBuf> {
+        let dir = dirs::state_dir().or_else(dirs::data_local_dir)?;
+        Some(dir.join("open-sesame").join(STATE_DB_FILE))
+    }
+
+    /// Open or create the database at `path`, creating its directory
+    /// (mode 0700) and migrating the schema to the current version.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory or database cannot be created,
+    /// or the schema is newer than this build or fails to migrate.
+    pub fn open(path: &Path) -> core_types::Result<Self> {
+        if let Some(dir) = path.parent() {
+            create_private_dir(dir).map_err(|e| {
+                core_types::Error::Platform(format!(
+                    "state directory {} could not be created: {e}",
+                    dir.display()
+                ))
+            })?;
+        }
+        let conn = Connection::open(path).map_err(db_error("open"))?;
+        conn.pragma_update(None, "journal_mode", "WAL")
+            .map_err(db_error("WAL mode"))?;
+        // In WAL mode this syncs at checkpoints rather than on every commit.
+        // A crash can lose the last few switches, never corrupt the file.
+        conn.pragma_update(None, "synchronous", "NORMAL")
+            .map_err(db_error("synchronous mode"))?;
+        conn.busy_timeout(BUSY_TIMEOUT)
+            .map_err(db_error("busy timeout"))?;
+        Self::migrated(conn)
+    }
+
+    /// Open a private in-memory database (for testing).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `SQLite` cannot allocate the database.
+    pub fn open_in_memory() -> core_types::Result<Self> {
+        let conn = Connection::open_in_memory().map_err(db_error("in-memory open"))?;
+        Self::migrated(conn)
+    }
+
+    fn migrated(mut conn: Connection) -> core_types::Result<Self> {
+        let version: u32 = conn
+            .pragma_query_value(None, "user_version", |row| row.get(0))
+            .map_err(db_error("schema version"))?;
+        if version > SCHEMA_VERSION {
+            return Err(core_types::Error::Platform(format!(
+                "state database schema {version} is newer than this build ({SCHEMA_VERSION})"
+            )));
+        }
+        if version < SCHEMA_VERSION {
+            let tx = conn
+                .transaction_with_behavior(TransactionBehavior::Immediate)
+                .map_err(db_error("migration"))?;
+            for migration in &MIGRATIONS[version as usize..] {
+                tx.execute_batch(migration).map_err(db_error("migration"))?;
+            }
+            tx.pragma_update(None, "user_version", SCHEMA_VERSION)
+                .map_err(db_error("migration"))?;
+            tx.commit().map_err(db_error("migration"))?;
+            tracing::info!(
+                from = version,
+                to = SCHEMA_VERSION,
+                "state: schema migrated"
+            );
+        }
+        Ok(Self { conn })
+    }
+
+    /// Schema version of the open database.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the version cannot be read.
+    pub fn schema_version(&self) -> core_types::Result<u32> {
+        self.conn
+            .pragma_query_value(None, "user_version", |row| row.get(0))
+            .map_err(db_error("schema version"))
+    }
+
+    /// The database file, or `None` for an in-memory store.
+    #[must_use]
+    pub fn path(&self) -> Option<&str> {
+        self.conn.path().filter(|p| !p.is_empty())
+    }
+
+    // -- MRU stack ----------------------------------------------------------
+
+    /// The MRU stack, most recent first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the table cannot be read.
+    pub fn mru(&self) -> core_types::Result<Vec<MruEntry>> {
+        read_mru(&self.conn).map_err(db_error("MRU read"))
+    }
+
+    /// Read-modify-write the MRU stack in one transaction. `change`
+    /// returns whether it modified the stack; it is only written then.
+    /// Returns the stack as left.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the transaction fails; nothing is written then.
+    pub fn update_mru(
+        &mut self,
+        change: impl FnOnce(&mut Vec<MruEntry>) -> bool,
+    ) -> core_types::Result<Vec<MruEntry>> {
+        self.update(read_mru, write_mru, change)
+            .map_err(db_error("MRU update"))
+    }
+
+    // -- Marks --------------------------------------------------------------
+
+    /// Marks by letter.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the table cannot be read.
+    pub fn marks(&self) -> core_types::Result<BTreeMap<char, String>> {
+        read_marks(&self.conn).map_err(db_error("marks read"))
+    }
+
+    /// Read-modify-write the marks, like [`update_mru`](Self::update_mru).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the transaction fails; nothing is written then.
+    pub fn update_marks(
+        &mut self,
+        change: impl FnOnce(&mut BTreeMap<char, String>) -> bool,
+    ) -> core_types::Result<BTreeMap<char, String>> {
+        self.update(read_marks, write_marks, change)
+            .map_err(db_error("marks update"))
+    }
+
+    // -- Window order -------------------------------------------------------
+
+    /// Window IDs in first-seen order, oldest first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the table cannot be read.
+    pub fn window_order(&self) -> core_types::Result<Vec<String>> {
+        read_window_order(&self.conn).map_err(db_error("window order read"))
+    }
+
+    /// Read-modify-write the window order, like
+    /// [`update_mru`](Self::update_mru).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the transaction fails; nothing is written then.
+    pub fn update_window_order(
+        &mut self,
+        change: impl FnOnce(&mut Vec<String>) -> bool,
+    ) -> core_types::Result<Vec<String>> {
+        self.update(read_window_order, write_window_order, change)
+            .map_err(db_error("window order update"))
+    }
+
+    // -- Release timings ----------------------------------------------------
+
+    /// Recorded Alt-release timings, oldest first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the table cannot be read.
+    pub fn release_timings(&self) -> core_types::Result<ReleaseTimings> {
+        read_release_timings(&self.conn).map_err(db_error("release timings read"))
+    }
+
+    /// Read-modify-write the release timings, like
+    /// [`update_mru`](Self::update_mru).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the transaction fails; nothing is written then.
+    pub fn update_release_timings(
+        &mut self,
+        change: impl FnOnce(&mut ReleaseTimings) -> bool,
+    ) -> core_types::Result<ReleaseTimings> {
+        self.update(read_release_timings, write_release_timings, change)
+            .map_err(db_error("release timings update"))
+    }
+
+    // -- Usage log ----------------------------------------------------------
+
+    /// Append a usage event, dropping the oldest past
+    /// [`MAX_USAGE_EVENTS`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the event cannot be written.
+    pub fn append_usage(&self, event: &UsageEvent) -> core_types::Result<()> {
+        insert_usage(&self.conn, event).map_err(db_error("usage append"))?;
+        self.conn
+            .execute(
+                "DELETE FROM usage_events
+                 WHERE id <= (SELECT MAX(id) FROM usage_events) - ?1",
+                params![sql_int(MAX_USAGE_EVENTS)],
+            )
+            .map_err(db_error("usage trim"))?;
+        Ok(())
+    }
+
+    /// Usage events at or after `since` (Unix seconds), oldest first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the table cannot be read.
+    pub fn usage_events(&self, since: Option<u64>) -> core_types::Result<Vec<UsageEvent>> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "SELECT at, kind, app, key, elapsed_ms FROM usage_events
+                 WHERE at >= ?1 ORDER BY id",
+            )
+            .map_err(db_error("usage read"))?;
+        let rows = stmt
+            .query_map(params![sql_int(since.unwrap_or(0))], |row| {
+                let kind: String = row.get(1)?;
+                Ok((
+                    kind,
+                    UsageEvent {
+                        at: uint(row.get(0)?),
+                        kind: UsageKind::Cancel,
+                        app: row.get(2)?,
+                        key: row.get(3)?,
+                        elapsed_ms: uint(row.get(4)?),
+                    },
+                ))
+            })
+            .map_err(db_error("usage read"))?;
+        let mut events = Vec::new();
+        for row in rows {
+            let (kind, mut event) = row.map_err(db_error("usage read"))?;
+            // Rows of a kind this build does not know are skipped.
+            let Some(kind) = usage_kind(&kind) else {
+                continue;
+            };
+            event.kind = kind;
+            events.push(event);
+        }
+        Ok(events)
+    }
+
+    // -- Focus time ---------------------------------------------------------
+
+    /// Append a focus interval.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the interval cannot be written.
+    pub fn append_focus_interval(&self, interval: &FocusInterval) -> core_types::Result<()> {
+        insert_focus_interval(&self.conn, interval).map_err(db_error("focus time append"))
+    }
+
+    /// Focus intervals ending after `since` (Unix seconds), oldest first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the table cannot be read.
+    pub fn focus_intervals(&self, since: Option<u64>) -> core_types::Result<Vec<FocusInterval>> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "SELECT start, end, app_id FROM focus_intervals
+                 WHERE end > ?1 ORDER BY start",
+            )
+            .map_err(db_error("focus time read"))?;
+        stmt.query_map(params![sql_int(since.unwrap_or(0))], |row| {
+            Ok(FocusInterval {
+                start: uint(row.get(0)?),
+                end: uint(row.get(1)?),
+                app_id: row.get(2)?,
+            })
+        })
+        .and_then(Iterator::collect)
+        .map_err(db_error("focus time read"))
+    }
+
+    // -- Settings -----------------------------------------------------------
+
+    /// The setting `name`, if set.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the table cannot be read.
+    pub fn setting(&self, name: &str) -> core_types::Result<Option<String>> {
+        self.conn
+            .query_row(
+                "SELECT value FROM settings WHERE name = ?1",
+                params![name],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(db_error("setting read"))
+    }
+
+    /// Set `name` to `value`, replacing any earlier value.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the setting cannot be written.
+    pub fn set_setting(&self, name: &str, value: &str) -> core_types::Result<()> {
+        self.conn
+            .execute(
+                "INSERT INTO settings (name, value) VALUES (?1, ?2)
+                 ON CONFLICT (name) DO UPDATE SET value = excluded.value",
+                params![name, value],
+            )
+            .map_err(db_error("setting write"))?;
+        Ok(())
+    }
+
+    // -- Maintenance --------------------------------------------------------
+
+    /// Checkpoint the write-ahead log and rebuild the database file to
+    /// reclaim the space of deleted rows.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if another process holds a write transaction for
+    /// longer than the busy timeout, or the rebuild fails.
+    pub fn vacuum(&self) -> core_types::Result<VacuumReport> {
+        let before_bytes = self.size_bytes()?;
+        self.conn
+            .execute_batch("VACUUM; PRAGMA optimize;")
+            .map_err(db_error("vacuum"))?;
+        self.conn
+            .pragma_update(None, "wal_checkpoint", "TRUNCATE")
+            .map_err(db_error("WAL checkpoint"))?;
+        Ok(VacuumReport {
+            before_bytes,
+            after_bytes: self.size_bytes()?,
+        })
+    }
+
+    /// Write a consistent copy of the database to `path`, which must not
+    /// exist. Safe while the daemon is writing.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the copy cannot be written.
+    pub fn snapshot_to(&self, path: &Path) -> core_types::Result<()> {
+        self.conn
+            .execute("VACUUM INTO ?1", params![path.to_string_lossy()])
+            .map_err(db_error("snapshot"))?;
+        Ok(())
+    }
+
+    /// Whether every table is empty (nothing recorded yet). Settings are
+    /// not counted.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a table cannot be read.
+    pub fn is_empty(&self) -> core_types::Result<bool> {
+        for table in TABLES {
+            let rows: i64 = self
+                .conn
+                .query_row(&format!("SELECT COUNT(*) FROM {table}"), [], |row| {
+                    row.get(0)
+                })
+                .map_err(db_error("row count"))?;
+            if rows > 0 {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Replace the whole store with the contents of the database at
+    /// `path` (a [`snapshot_to`](Self::snapshot_to) copy), in one
+    /// transaction. `path` is migrated to the current schema first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `path` is not a state database, has a newer
+    /// schema, or the copy fails; the store is unchanged then.
+    pub fn restore_from(&mut self, path: &Path) -> core_types::Result<()> {
+        if !path.is_file() {
+            return Err(core_types::Error::Platform(format!(
+                "state store restore failed: {} is not a file",
+                path.display()
+            )));
+        }
+        drop(Self::open(path)?);
+        self.conn
+            .execute(
+                "ATTACH DATABASE ?1 AS source",
+                params![path.to_string_lossy()],
+            )
+            .map_err(db_error("restore"))?;
+        let copied = (|| {
+            let tx = self
+                .conn
+                .transaction_with_behavior(TransactionBehavior::Immediate)?;
+            for table in TABLES.into_iter().chain([SETTINGS_TABLE]) {
+                tx.execute_batch(&format!(
+                    "DELETE FROM main.{table};
+                     INSERT INTO main.{table} SELECT * FROM source.{table};"
+                ))?;
+            }
+            tx.commit()
+        })();
+        let detached = self.conn.execute("DETACH DATABASE source", []);
+        copied.and(detached.map(drop)).map_err(db_error("restore"))
+    }
+
+    fn size_bytes(&self) -> core_types::Result<u64> {
+        let pages: u64 = self
+            .conn
+            .pragma_query_value(None, "page_count", |row| row.get(0).map(uint))
+            .map_err(db_error("page count"))?;
+        let page_size: u64 = self
+            .conn
+            .pragma_query_value(None, "page_size", |row| row.get(0).map(uint))
+            .map_err(db_error("page size"))?;
+        Ok(pages * page_size)
+    }
+
+    fn update<T>(
+        &mut self,
+        read: impl FnOnce(&Connection) -> rusqlite::Result<T>,
+        write: impl FnOnce(&Connection, &T) -> rusqlite::Result<()>,
+        change: impl FnOnce(&mut T) -> bool,
+    ) -> rusqlite::Result<T> {
+        let tx = self
+            .conn
+            .transaction_with_behavior(TransactionBehavior::Immediate)?;
+        let mut value = read(&tx)?;
+        if change(&mut value) {
+            write(&tx, &value)?;
+            tx.commit()?;
+        }
+        Ok(value)
+    }
+}
+
+fn db_error(context: &'static str) -> impl Fn(rusqlite::Error) -> core_types::Error {
+    move |e| core_types::Error::Platform(format!("state store {context} failed: {e}"))
+}
+
+/// `SQLite` integers are signed 64-bit; times and sizes here never reach
+/// the sign bit, so out-of-range values saturate.
+fn sql_int(value: u64) -> i64 {
+    i64::try_from(value).unwrap_or(i64::MAX)
+}
+
+fn uint(value: i64) -> u64 {
+    u64::try_from(value).unwrap_or(0)
+}
+
+fn create_private_dir(dir: &Path) -> std::io::Result<()> {
+    if dir.exists() {
+        return Ok(());
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::DirBuilderExt;
+        std::fs::DirBuilder::new()
+            .mode(0o700)
+            .recursive(true)
+            .create(dir)
+    }
+    #[cfg(not(unix))]
+    {
+        std::fs::create_dir_all(dir)
+    }
+}
+
+fn read_mru(conn: &Connection) -> rusqlite::Result<Vec<MruEntry>> {
+    conn.prepare("SELECT window_id, focused_at FROM mru ORDER BY position")?
+        .query_map([], |row| {
+            Ok(MruEntry {
+                window_id: row.get(0)?,
+                focused_at: row.get::<_, Option<i64>>(1)?.map(uint),
+            })
+        })?
+        .collect()
+}
+
+fn write_mru(conn: &Connection, stack: &Vec<MruEntry>) -> rusqlite::Result<()> {
+    conn.execute("DELETE FROM mru", [])?;
+    let mut insert = conn.prepare(
+        "INSERT OR IGNORE INTO mru (position, window_id, focused_at) VALUES (?1, ?2, ?3)",
+    )?;
+    for (position, entry) in (0_i64..).zip(stack) {
+        insert.execute(params![
+            position,
+            entry.window_id,
+            entry.focused_at.map(sql_int)
+        ])?;
+    }
+    Ok(())
+}
+
+fn read_marks(conn: &Connection) -> rusqlite::Result<BTreeMap<char, String>> {
+    let rows: Vec<(String, String)> = conn
+        .prepare("SELECT mark, window_id FROM marks")?
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<rusqlite::Result<_>>()?;
+    Ok(rows
+        .into_iter()
+        .filter_map(|(mark, id)| Some((mark.chars().next()?, id)))
+        .collect())
+}
+
+fn write_marks(conn: &Connection, marks: &BTreeMap<char, String>) -> rusqlite::Result<()> {
+    conn.execute("DELETE FROM marks", [])?;
+    let mut insert = conn.prepare("INSERT INTO marks (mark, window_id) VALUES (?1, ?2)")?;
+    for (mark, id) in marks {
+        insert.execute(params![mark.to_string(), id])?;
+    }
+    Ok(())
+}
+
+fn read_window_order(conn: &Connection) -> rusqlite::Result<Vec<String>> {
+    conn.prepare("SELECT window_id FROM window_order ORDER BY position")?
+        .query_map([], |row| row.get(0))?
+        .collect()
+}
+
+fn write_window_order(conn: &Connection, order: &Vec<String>) -> rusqlite::Result<()> {
+    conn.execute("DELETE FROM window_order", [])?;
+    let mut insert =
+        conn.prepare("INSERT OR IGNORE INTO window_order (position, window_id) VALUES (?1, ?2)")?;
+    for (position, id) in (0_i64..).zip(order) {
+        insert.execute(params![position, id])?;
+    }
+    Ok(())
+}
+
+fn read_release_timings(conn: &Connection) -> rusqlite::Result<ReleaseTimings> {
+    let samples_ms = conn
+        .prepare("SELECT ms FROM release_timings ORDER BY id")?
+        .query_map([], |row| row.get(0))?
+        .collect::<rusqlite::Result<_>>()?;
+    Ok(ReleaseTimings { samples_ms })
+}
+
+fn write_release_timings(conn: &Connection, timings: &ReleaseTimings) -> rusqlite::Result<()> {
+    conn.execute("DELETE FROM release_timings", [])?;
+    let mut insert = conn.prepare("INSERT INTO release_timings (ms) VALUES (?1)")?;
+    for ms in &timings.samples_ms {
+        insert.execute(params![ms])?;
+    }
+    Ok(())
+}
+
+fn insert_usage(conn: &Connection, event: &UsageEvent) -> rusqlite::Result<()> {
+    conn.execute(
+        "INSERT INTO usage_events (at, kind, app, key, elapsed_ms) VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![
+            sql_int(event.at),
+            usage_kind_name(event.kind),
+            event.app,
+            event.key,
+            sql_int(event.elapsed_ms)
+        ],
+    )?;
+    Ok(())
+}
+
+fn insert_focus_interval(conn: &Connection, interval: &FocusInterval) -> rusqlite::Result<()> {
+    conn.execute(
+        "INSERT INTO focus_intervals (start, end, app_id) VALUES (?1, ?2, ?3)",
+        params![
+            sql_int(interval.start),
+            sql_int(interval.end),
+            interval.app_id
+        ],
+    )?;
+    Ok(())
+}
+
+/// Stored name of a usage kind, as in the old JSON log.
+fn usage_kind_name(kind: UsageKind) -> &'static str {
+    match kind {
+        UsageKind::Activate => "activate",
+        UsageKind::Launch => "launch",
+        UsageKind::Cancel => "cancel",
+    }
+}
+
+fn usage_kind(name: &str) -> Option<UsageKind> {
+    match name {
+        "activate" => Some(UsageKind::Activate),
+        "launch" => Some(UsageKind::Launch),
+        "cancel" => Some(UsageKind::Cancel),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: &str, at: Option<u64>) -> MruEntry {
+        MruEntry {
+            window_id: id.into(),
+            focused_at: at,
+        }
+    }
+
+    #[test]
+    fn fresh_database_is_at_the_latest_schema() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state").join(STATE_DB_FILE);
+        let store = StateStore::open(&path).unwrap();
+        assert_eq!(store.schema_version().unwrap(), SCHEMA_VERSION);
+        drop(store);
+        // Reopening does not migrate again.
+        let store = StateStore::open(&path).unwrap();
+        assert!(store.mru().unwrap().is_empty());
+        assert_eq!(store.path(), Some(path.to_str().unwrap()));
+    }
+
+    #[test]
+    fn newer_schema_is_refused() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(STATE_DB_FILE);
+        let conn = Connection::open(&path).unwrap();
+        conn.pragma_update(None, "user_version", SCHEMA_VERSION + 1)
+            .unwrap();
+        drop(conn);
+        let err = StateStore::open(&path).err().unwrap();
+        assert!(err.to_string().contains("newer than this build"), "{err}");
+    }
+
+    #[test]
+    fn updates_write_only_when_changed() {
+        let mut store = StateStore::open_in_memory().unwrap();
+        let stack = store
+            .update_mru(|stack| {
+                stack.push(entry("A", Some(10)));
+                stack.push(entry("B", None));
+                true
+            })
+            .unwrap();
+        assert_eq!(stack.len(), 2);
+        assert_eq!(
+            store.mru().unwrap(),
+            [entry("A", Some(10)), entry("B", None)]
+        );
+
+        store
+            .update_mru(|stack| {
+                stack.clear();
+                false
+            })
+            .unwrap();
+        assert_eq!(
+            store.mru().unwrap().len(),
+            2,
+            "unchanged update is not written"
+        );
+    }
+
+    #[test]
+    fn marks_order_and_timings_round_trip() {
+        let mut store = StateStore::open_in_memory().unwrap();
+        store
+            .update_marks(|marks| {
+                marks.insert('b', "win-2".into());
+                marks.insert('a', "win-1".into());
+                true
+            })
+            .unwrap();
+        assert_eq!(
+            store.marks().unwrap().into_iter().collect::<Vec<_>>(),
+            [('a', "win-1".to_string()), ('b', "win-2".to_string())]
+        );
+
+        store
+            .update_window_order(|order| {
+                order.extend(["C".to_string(), "A".to_string()]);
+                true
+            })
+            .unwrap();
+        assert_eq!(store.window_order().unwrap(), ["C", "A"]);
+
+        store
+            .update_release_timings(|timings| {
+                timings.record(180);
+                timings.record(220);
+                true
+            })
+            .unwrap();
+        assert_eq!(store.release_timings().unwrap().samples_ms, [180, 220]);
+    }
+
+    #[test]
+    fn usage_log_filters_by_time_and_keeps_the_newest() {
+        let store = StateStore::open_in_memory().unwrap();
+        let event = |at, kind| UsageEvent {
+            at,
+            kind,
+            app: Some("kitty".into()),
+            key: None,
+            elapsed_ms: 5,
+        };
+        store.append_usage(&event(10, UsageKind::Activate)).unwrap();
+        store.append_usage(&event(20, UsageKind::Launch)).unwrap();
+        store.append_usage(&event(30, UsageKind::Cancel)).unwrap();
+        let since = store.usage_events(Some(20)).unwrap();
+        assert_eq!(since.len(), 2);
+        assert_eq!(since[0].kind, UsageKind::Launch);
+        assert_eq!(since[1], event(30, UsageKind::Cancel));
+
+        // Trim: only the newest MAX_USAGE_EVENTS survive.
+        store
+            .conn
+            .execute(
+                "UPDATE sqlite_sequence SET seq = seq + ?1 WHERE name = 'usage_events'",
+                params![sql_int(MAX_USAGE_EVENTS)],
+            )
+            .unwrap();
+        store.append_usage(&event(40, UsageKind::Activate)).unwrap();
+        let all = store.usage_events(None).unwrap();
+        assert_eq!(all.iter().map(|e| e.at).collect::<Vec<_>>(), [40]);
+    }
+
+    #[test]
+    fn focus_intervals_overlapping_the_cutoff_are_returned() {
+        let store = StateStore::open_in_memory().unwrap();
+        for (start, end) in [(0, 100), (100, 400), (400, 460)] {
+            store
+                .append_focus_interval(&FocusInterval {
+                    start,
+                    end,
+                    app_id: "kitty".into(),
+                })
+                .unwrap();
+        }
+        let starts: Vec<u64> = store
+            .focus_intervals(Some(300))
+            .unwrap()
+            .iter()
+            .map(|i| i.start)
+            .collect();
+        assert_eq!(starts, [100, 400]);
+    }
+
+    #[test]
+    fn vacuum_and_snapshot() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = StateStore::open(&dir.path().join(STATE_DB_FILE)).unwrap();
+        for at in 0..500 {
+            store
+                .append_focus_interval(&FocusInterval {
+                    start: at,
+                    end: at + 1,
+                    app_id: "x".repeat(100),
+                })
+                .unwrap();
+        }
+        store
+            .conn
+            .execute("DELETE FROM focus_intervals", [])
+            .unwrap();
+        let report = store.vacuum().unwrap();
+        assert!(report.after_bytes < report.before_bytes, "{report:?}");
+
+        let copy = dir.path().join("copy.db");
+        store.snapshot_to(&copy).unwrap();
+        let copy = StateStore::open(&copy).unwrap();
+        assert!(copy.focus_intervals(None).unwrap().is_empty());
+    }
+
+    #[test]
+    fn settings_are_replaced_in_place() {
+        let store = StateStore::open_in_memory().unwrap();
+        assert_eq!(store.setting("onboarding").unwrap(), None);
+        store.set_setting("onboarding", "pending").unwrap();
+        store.set_setting("onboarding", "done").unwrap();
+        assert_eq!(
+            store.setting("onboarding").unwrap().as_deref(),
+            Some("done")
+        );
+        assert!(store.is_empty().unwrap(), "settings are not recorded state");
+    }
+
+    #[test]
+    fn restore_replaces_every_table() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut source = StateStore::open_in_memory().unwrap();
+        source
+            .update_marks(|marks| marks.insert('a', "win-1".into()).is_none())
+            .unwrap();
+        let snapshot = dir.path().join("snapshot.db");
+        source.snapshot_to(&snapshot).unwrap();
+
+        let mut store = StateStore::open_in_memory().unwrap();
+        assert!(store.is_empty().unwrap());
+        store
+            .update_window_order(|order| {
+                order.push("stale".into());
+                true
+            })
+            .unwrap();
+        store.restore_from(&snapshot).unwrap();
+        assert_eq!(
+            store.marks().unwrap().get(&'a').map(String::as_str),
+            Some("win-1")
+        );
+        assert!(store.window_order().unwrap().is_empty());
+        assert!(!store.is_empty().unwrap());
+
+        assert!(store.restore_from(&dir.path().join("missing.db")).is_err());
+        std::fs::write(dir.path().join("junk.db"), "not a database").unwrap();
+        assert!(store.restore_from(&dir.path().join("junk.db")).is_err());
+        assert!(
+            !store.is_empty().unwrap(),
+            "a failed restore changes nothing"
+        );
+    }
+}
@@ -22,6 +22,12 @@ pub const PROFILE_NAMESPACE: Uuid = Uuid::from_bytes([
 /// rather than hardcoding `"default"` to prevent silent divergence.
 pub const DEFAULT_PROFILE_NAME: &str = "default";
 
+/// Latency budget from overlay activation request to first committed frame.
+///
+/// daemon-wm warns when an activation exceeds it; `sesame wm overlay
+/// --timings` reports against it.
+pub const OVERLAY_FIRST_FRAME_BUDGET: Duration = Duration::from_millis(50);
+
 // ============================================================================
 // Timestamp
 // ============================================================================
@@ -12,7 +12,10 @@ use crate::ids::{
 };
 use crate::oci::OciReference;
 use crate::profile::TrustProfileName;
-use crate::rpc::{ClipboardEntry, InputLayerInfo, LaunchResult, ProfileSummary, SnippetInfo};
+use crate::rpc::{
+    ClipboardEntry, InputLayerInfo, LaunchResult, MultiplexerKind, MultiplexerTarget,
+    OverlayTimings, ProfileSummary, SnippetInfo, ThemeColors,
+};
 use crate::security::{
     AgentType, AttestationType, CapabilitySet, InstallationId, OrganizationNamespace,
     SensitivityClass,
@@ -386,10 +389,19 @@ pub enum EventKind {
     },
     WmActivateWindow {
         window_id: String,
+        /// Scripted activation: focus the window without promoting it in the
+        /// MRU stack, so the Alt+Tab previous-window slot is unaffected.
+        #[serde(default)]
+        silent: bool,
     },
     WmActivateWindowResponse {
         success: bool,
     },
+    /// Activate the window a mark (`a`–`z`) names, set from the overlay
+    /// with Ctrl+M. Answered with `WmActivateWindowResponse`.
+    WmActivateMark {
+        mark: char,
+    },
     /// Trigger the window switcher overlay (forward direction).
     WmActivateOverlay,
     /// Trigger the window switcher overlay (backward direction).
@@ -398,8 +410,53 @@ pub enum EventKind {
     WmActivateOverlayLauncher,
     /// Trigger the overlay in launcher mode with backward initial selection.
     WmActivateOverlayLauncherBackward,
+    /// Trigger the overlay with everything `sesame wm overlay` was asked
+    /// for: launcher or switcher mode, direction, the input buffer
+    /// pre-filled as if `query` had been typed (`--query`), and the output
+    /// to open on (`--output`, a connector name). A launcher request while
+    /// a switcher overlay is open upgrades it to launcher mode.
+    WmActivateOverlayIntent {
+        launcher: bool,
+        backward: bool,
+        query: Option<String>,
+        output: Option<String>,
+    },
     WmOverlayShown,
     WmOverlayDismissed,
+    /// A `sesame wm overlay` invocation found the resident fast-path
+    /// process hung (no answer to its ping), killed it, and took over its
+    /// lock and socket. Logged by daemon-wm.
+    WmResidentHandoff {
+        /// PID of the replaced resident.
+        pid: i32,
+    },
+    /// Query the latency breakdown of the most recent overlay activation.
+    WmTimingsRequest,
+    WmTimingsResponse {
+        timings: Option<OverlayTimings>,
+    },
+    /// Prepare the overlay so the next activation is fast: refresh the
+    /// window list and render a picker frame offscreen (`sesame wm warmup`).
+    WmWarmup,
+    WmWarmupResponse {
+        /// Windows in the refreshed list.
+        window_count: u32,
+        /// Whether an offscreen frame was queued. False while the overlay
+        /// is in use.
+        rendered: bool,
+    },
+    /// Render the picker for a fixed set of sample windows with `colors`
+    /// and return it as a PNG (`sesame theme preview`). The running theme
+    /// is not changed.
+    WmThemePreview {
+        colors: ThemeColors,
+    },
+    WmThemePreviewResponse {
+        /// PNG-encoded image of the card; empty on failure.
+        png: Vec<u8>,
+        #[serde(default)]
+        error: Option<String>,
+    },
 
     // -- RPC: Launcher --
     LaunchQuery {
@@ -431,6 +488,34 @@ pub enum EventKind {
         #[serde(default)]
         denial: Option<LaunchDenial>,
     },
+    /// Run a command-palette shell command via `sh -c`. daemon-launcher refuses
+    /// it unless the profile's `wm.command_palette` is enabled. Answered with
+    /// `LaunchExecuteResponse`.
+    LaunchShellCommand {
+        command: String,
+        /// Trust profile context — injected as `SESAME_PROFILE` env var in spawned process.
+        #[serde(default)]
+        profile: Option<TrustProfileName>,
+    },
+    /// List the windows/tabs of a terminal multiplexer (served by daemon-launcher,
+    /// since daemon-wm's sandbox cannot exec).
+    MultiplexerList {
+        kind: MultiplexerKind,
+    },
+    MultiplexerListResponse {
+        targets: Vec<MultiplexerTarget>,
+        #[serde(default)]
+        error: Option<String>,
+    },
+    /// Switch the multiplexer to the given window/tab.
+    MultiplexerSelect {
+        target: MultiplexerTarget,
+    },
+    MultiplexerSelectResponse {
+        success: bool,
+        #[serde(default)]
+        error: Option<String>,
+    },
 
     // -- RPC: Clipboard --
     ClipboardHistory {
@@ -747,18 +832,32 @@ impl_event_debug! {
         SecretsStateResponse { locked, active_profiles, lock_state },
         WmListWindows,
         WmListWindowsResponse { windows },
-        WmActivateWindow { window_id },
+        WmActivateWindow { window_id, silent },
         WmActivateWindowResponse { success },
+        WmActivateMark { mark },
         WmActivateOverlay,
         WmActivateOverlayBackward,
         WmActivateOverlayLauncher,
         WmActivateOverlayLauncherBackward,
+        WmActivateOverlayIntent { launcher, backward, query, output },
         WmOverlayShown,
         WmOverlayDismissed,
+        WmResidentHandoff { pid },
+        WmTimingsRequest,
+        WmTimingsResponse { timings },
+        WmWarmup,
+        WmWarmupResponse { window_count, rendered },
+        WmThemePreview { colors },
+        WmThemePreviewResponse { png, error },
         LaunchQuery { query, max_results, profile },
         LaunchQueryResponse { results },
         LaunchExecute { entry_id, profile, tags, launch_args },
         LaunchExecuteResponse { pid, error, denial },
+        LaunchShellCommand { command, profile },
+        MultiplexerList { kind },
+        MultiplexerListResponse { targets, error },
+        MultiplexerSelect { target },
+        MultiplexerSelectResponse { success, error },
         ClipboardHistory { profile, limit },
         ClipboardHistoryResponse { entries },
         ClipboardClear { profile },
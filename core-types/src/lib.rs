@@ -14,6 +14,7 @@ pub mod events;
 pub mod ids;
 pub mod oci;
 pub mod profile;
+pub mod redact;
 pub mod rpc;
 pub mod security;
 pub mod sensitive;
@@ -29,6 +30,7 @@ pub use events::*;
 pub use ids::*;
 pub use oci::*;
 pub use profile::*;
+pub use redact::*;
 pub use rpc::*;
 pub use security::*;
 pub use sensitive::*;
@@ -128,6 +130,22 @@ mod tests {
         assert!(SecurityLevel::ProfileScoped < SecurityLevel::SecretsOnly);
     }
 
+    // -- ToplevelState --
+
+    #[test]
+    fn toplevel_state_set_insert_remove() {
+        let mut state = ToplevelState::empty();
+        assert!(state.is_empty());
+        state.set(ToplevelState::MINIMIZED, true);
+        state |= ToplevelState::STICKY;
+        assert!(state.contains(ToplevelState::MINIMIZED | ToplevelState::STICKY));
+        assert!(!state.contains(ToplevelState::MINIMIZED | ToplevelState::ACTIVATED));
+        state.set(ToplevelState::MINIMIZED, false);
+        assert_eq!(state, ToplevelState::STICKY);
+        state.remove(ToplevelState::STICKY);
+        assert!(state.is_empty());
+    }
+
     // -- Serialization round-trip property tests --
 
     proptest! {
@@ -1202,4 +1220,65 @@ mod tests {
             "profile name should be visible"
         );
     }
+
+    #[test]
+    fn redacted_titles_hash_in_window_debug() {
+        let window = Window {
+            id: WindowId::new(),
+            app_id: AppId::new("thunderbird"),
+            title: "Inbox - alice@example.com".into(),
+            workspace_id: CompositorWorkspaceId::new(),
+            monitor_id: MonitorId::new(),
+            geometry: Geometry {
+                x: 0,
+                y: 0,
+                width: 800,
+                height: 600,
+            },
+            is_focused: false,
+            is_minimized: false,
+            is_fullscreen: false,
+            is_maximized: false,
+            profile_id: ProfileId::new(),
+            parent: None,
+            is_sticky: false,
+            is_keep_above: false,
+        };
+        assert!(format!("{window:?}").contains("\"Inbox - alice@example.com\""));
+
+        set_redact_titles(true);
+        let debug = format!("{window:?}");
+        let shown = redacted_title(&window.title).to_string();
+        set_redact_titles(false);
+        assert!(!debug.contains("alice"), "{debug}");
+        assert!(debug.contains("thunderbird"), "app ID stays readable");
+        assert!(debug.contains(&shown));
+        assert!(shown.starts_with("title:") && shown.len() == "title:".len() + 8);
+        assert_eq!(
+            redacted_title("Inbox - alice@example.com").to_string(),
+            window.title
+        );
+    }
+
+    #[test]
+    fn release_timings_learn_a_threshold_above_habitual_releases() {
+        let mut timings = ReleaseTimings::default();
+        for ms in (0..19).map(|i| 150 + i * 5) {
+            timings.record(ms);
+        }
+        assert_eq!(timings.learned_threshold_ms(), None, "too few samples");
+        timings.record(240);
+        timings.record(5000); // Browsing, not a quick switch.
+        assert_eq!(timings.samples_ms.len(), ReleaseTimings::MIN_SAMPLES);
+        assert_eq!(timings.percentile(50), Some(195));
+        assert_eq!(timings.percentile(90), Some(235));
+        assert_eq!(timings.learned_threshold_ms(), Some(293));
+
+        // Very fast releasers are held to the lower bound.
+        for _ in 0..ReleaseTimings::MAX_SAMPLES {
+            timings.record(40);
+        }
+        assert_eq!(timings.samples_ms.len(), ReleaseTimings::MAX_SAMPLES);
+        assert_eq!(timings.learned_threshold_ms(), Some(100));
+    }
 }
@@ -0,0 +1,66 @@
+//! Window title redaction (`global.logging.redact_titles_in_logs`).
+//!
+//! Titles can carry document names, email subjects and the like. When
+//! redaction is on, [`redacted_title`] shows a title as a short hash
+//! instead, and [`Window`](crate::Window)'s `Debug` output uses it, so
+//! titles stay out of tracing output and crash reports while app IDs stay
+//! readable. The hash is stable across processes, so the same title can
+//! be followed from `sesame wm list` into the logs; it does not hide a
+//! short, guessable title from someone who tries candidates.
+
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static REDACT_TITLES: AtomicBool = AtomicBool::new(false);
+
+/// Turn title redaction on or off for this process.
+pub fn set_redact_titles(on: bool) {
+    REDACT_TITLES.store(on, Ordering::Relaxed);
+}
+
+/// Whether titles are redacted in this process.
+#[must_use]
+pub fn redact_titles() -> bool {
+    REDACT_TITLES.load(Ordering::Relaxed)
+}
+
+/// `title` for logs and listings: as is, or `title:<hash>` while
+/// redaction is on.
+#[must_use]
+pub fn redacted_title(title: &str) -> RedactedTitle<'_> {
+    RedactedTitle(title)
+}
+
+/// A title displayed through [`redacted_title`].
+#[derive(Clone, Copy)]
+pub struct RedactedTitle<'a>(&'a str);
+
+impl RedactedTitle<'_> {
+    /// 32-bit FNV-1a of the title, as 8 hex digits.
+    fn hash(self) -> String {
+        let hash = self.0.bytes().fold(0x811c_9dc5_u32, |hash, byte| {
+            (hash ^ u32::from(byte)).wrapping_mul(0x0100_0193)
+        });
+        format!("title:{hash:08x}")
+    }
+}
+
+impl fmt::Display for RedactedTitle<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if redact_titles() {
+            f.write_str(&self.hash())
+        } else {
+            f.write_str(self.0)
+        }
+    }
+}
+
+impl fmt::Debug for RedactedTitle<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if redact_titles() {
+            f.write_str(&self.hash())
+        } else {
+            fmt::Debug::fmt(self.0, f)
+        }
+    }
+}
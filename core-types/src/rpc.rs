@@ -50,3 +50,64 @@ pub struct SnippetInfo {
     pub trigger: String,
     pub template_preview: String,
 }
+
+/// Terminal multiplexer whose sessions can be listed as overlay rows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MultiplexerKind {
+    Tmux,
+    Zellij,
+}
+
+impl std::fmt::Display for MultiplexerKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Tmux => f.write_str("tmux"),
+            Self::Zellij => f.write_str("zellij"),
+        }
+    }
+}
+
+/// A selectable multiplexer window (tmux) or tab (zellij).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MultiplexerTarget {
+    pub kind: MultiplexerKind,
+    pub session: String,
+    /// Window index (tmux) or 1-based tab position (zellij).
+    pub index: u32,
+    /// Window or tab name.
+    pub name: String,
+    /// Whether this is the active window of its session.
+    pub active: bool,
+}
+
+/// Latency breakdown of the most recent overlay activation.
+///
+/// Stage offsets are microseconds since daemon-wm received the activation
+/// request; `None` means the stage has not been reached (yet).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OverlayTimings {
+    /// Increments on every fresh activation, so callers can tell a new
+    /// activation's timings from the previous one.
+    pub sequence: u64,
+    /// daemon-wm process start → event loop ready.
+    pub daemon_startup_us: u64,
+    /// Fresh window list available for the overlay snapshot.
+    pub windows_enumerated_us: Option<u64>,
+    /// First buffer committed to the overlay surface.
+    pub first_frame_us: Option<u64>,
+    /// Compositor delivered keyboard focus to the overlay.
+    pub input_ready_us: Option<u64>,
+}
+
+/// Overlay colors as `WmConfig` hex strings (`#RRGGBB[AA]`), for rendering a
+/// theme preview (`sesame theme preview`).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ThemeColors {
+    pub border_color: String,
+    pub background_color: String,
+    pub card_color: String,
+    pub text_color: String,
+    pub hint_color: String,
+    pub hint_matched_color: String,
+}
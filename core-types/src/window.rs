@@ -1,8 +1,12 @@
 use serde::{Deserialize, Serialize};
 
 use crate::ids::{AppId, CompositorWorkspaceId, MonitorId, ProfileId, WindowId};
+use crate::rpc::MultiplexerTarget;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// A toplevel window. `Debug` shows the title through
+/// [`redacted_title`](crate::redacted_title).
+#[derive(Clone, Serialize, Deserialize)]
+#[allow(clippy::struct_excessive_bools)] // Independent compositor-reported states
 pub struct Window {
     pub id: WindowId,
     pub app_id: AppId,
@@ -13,7 +17,149 @@ pub struct Window {
     pub is_focused: bool,
     pub is_minimized: bool,
     pub is_fullscreen: bool,
+    /// Maximized, when the compositor reports it.
+    #[serde(default)]
+    pub is_maximized: bool,
     pub profile_id: ProfileId,
+    /// Window this one is transient for (a dialog's main window), when the
+    /// compositor reports it.
+    #[serde(default)]
+    pub parent: Option<WindowId>,
+    /// Shown on every workspace, when the compositor reports it.
+    #[serde(default)]
+    pub is_sticky: bool,
+    /// Kept above other windows, when the compositor reports it.
+    #[serde(default)]
+    pub is_keep_above: bool,
+}
+
+impl std::fmt::Debug for Window {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Window")
+            .field("id", &self.id)
+            .field("app_id", &self.app_id)
+            .field("title", &crate::redacted_title(&self.title))
+            .field("workspace_id", &self.workspace_id)
+            .field("monitor_id", &self.monitor_id)
+            .field("geometry", &self.geometry)
+            .field("is_focused", &self.is_focused)
+            .field("is_minimized", &self.is_minimized)
+            .field("is_fullscreen", &self.is_fullscreen)
+            .field("is_maximized", &self.is_maximized)
+            .field("profile_id", &self.profile_id)
+            .field("parent", &self.parent)
+            .field("is_sticky", &self.is_sticky)
+            .field("is_keep_above", &self.is_keep_above)
+            .finish()
+    }
+}
+
+impl Window {
+    /// The compositor-reported states as one set.
+    #[must_use]
+    pub fn state(&self) -> ToplevelState {
+        let mut state = ToplevelState::empty();
+        state.set(ToplevelState::MAXIMIZED, self.is_maximized);
+        state.set(ToplevelState::MINIMIZED, self.is_minimized);
+        state.set(ToplevelState::ACTIVATED, self.is_focused);
+        state.set(ToplevelState::FULLSCREEN, self.is_fullscreen);
+        state.set(ToplevelState::STICKY, self.is_sticky);
+        state
+    }
+}
+
+/// Set of toplevel states a compositor reports for a window.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct ToplevelState(u8);
+
+impl ToplevelState {
+    pub const MAXIMIZED: Self = Self(1 << 0);
+    pub const MINIMIZED: Self = Self(1 << 1);
+    pub const ACTIVATED: Self = Self(1 << 2);
+    pub const FULLSCREEN: Self = Self(1 << 3);
+    pub const STICKY: Self = Self(1 << 4);
+
+    #[must_use]
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    #[must_use]
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Whether every state in `other` is set.
+    #[must_use]
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub fn insert(&mut self, other: Self) {
+        self.0 |= other.0;
+    }
+
+    pub fn remove(&mut self, other: Self) {
+        self.0 &= !other.0;
+    }
+
+    /// Inserts `other` when `value` is true, removes it otherwise.
+    pub fn set(&mut self, other: Self, value: bool) {
+        if value {
+            self.insert(other);
+        } else {
+            self.remove(other);
+        }
+    }
+}
+
+impl std::ops::BitOr for ToplevelState {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl std::ops::BitOrAssign for ToplevelState {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.insert(rhs);
+    }
+}
+
+/// A compositor workspace.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Workspace {
+    pub id: CompositorWorkspaceId,
+    pub name: String,
+    pub is_active: bool,
+}
+
+/// A compositor output (monitor).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Output {
+    pub id: MonitorId,
+    /// Connector name, e.g. `DP-1`.
+    pub name: String,
+    /// Human-readable description (make and model), when reported.
+    pub description: String,
+}
+
+/// What an overlay row selects. Every row is listed with a [`Window`]: the
+/// window itself, the window a row acts through (the terminal of a
+/// multiplexer row), or a stand-in carrying the row's title, so hints,
+/// search and rendering treat every item alike; activation depends on the
+/// item.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SelectableItem {
+    /// The window.
+    Window,
+    /// A tmux window or zellij tab inside the row's terminal window.
+    Multiplexer(MultiplexerTarget),
+    /// A workspace: activating the row switches to it.
+    Workspace(Workspace),
+    /// An output: activating the row focuses a window on it.
+    Output(Output),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
@@ -24,6 +170,23 @@ pub struct Geometry {
     pub height: u32,
 }
 
+/// Where the overlay's tiling verbs put a window on its output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WindowPlacement {
+    LeftHalf,
+    RightHalf,
+    Maximized,
+}
+
+/// A window state the overlay can switch on and off.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WindowToggle {
+    /// Show the window on every workspace.
+    Sticky,
+    /// Keep the window above other windows.
+    KeepAbove,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct Padding {
     pub top: u32,
@@ -31,3 +194,104 @@ pub struct Padding {
     pub bottom: u32,
     pub left: u32,
 }
+
+/// How an overlay activation ended, for the usage log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UsageKind {
+    /// A window was activated.
+    Activate,
+    /// An app was launched through a key binding.
+    Launch,
+    /// The overlay was dismissed without a choice.
+    Cancel,
+}
+
+/// One overlay activation outcome in the usage log. Records which app was
+/// chosen and how fast, never window titles.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UsageEvent {
+    /// Unix time in seconds.
+    pub at: u64,
+    pub kind: UsageKind,
+    /// The activated window's app ID, or the launched desktop entry ID.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app: Option<String>,
+    /// The key binding whose apps include `app`, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    /// Milliseconds from the overlay activation to this outcome.
+    pub elapsed_ms: u64,
+}
+
+/// How long after Alt+Tab the user lets go of Alt when quick-switching,
+/// learned to tune the quick-switch threshold (`wm.adaptive_quick_switch`).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReleaseTimings {
+    /// Milliseconds from activation to release, oldest first.
+    pub samples_ms: Vec<u32>,
+}
+
+impl ReleaseTimings {
+    /// Samples kept; older ones are dropped so the threshold follows habit
+    /// changes.
+    pub const MAX_SAMPLES: usize = 200;
+    /// Samples needed before a threshold is learned.
+    pub const MIN_SAMPLES: usize = 20;
+    /// Releases slower than this were browsing the picker, not a quick
+    /// switch, and are not recorded.
+    pub const MAX_SAMPLE_MS: u32 = 1500;
+    /// Bounds of the learned threshold.
+    pub const THRESHOLD_RANGE_MS: (u32, u32) = (100, 1000);
+
+    /// Add a release, keeping the newest `MAX_SAMPLES`.
+    pub fn record(&mut self, ms: u32) {
+        if ms > Self::MAX_SAMPLE_MS {
+            return;
+        }
+        self.samples_ms.push(ms);
+        let excess = self.samples_ms.len().saturating_sub(Self::MAX_SAMPLES);
+        self.samples_ms.drain(..excess);
+    }
+
+    /// The `percent`th percentile release (nearest rank), if any.
+    #[must_use]
+    pub fn percentile(&self, percent: usize) -> Option<u32> {
+        let mut sorted = self.samples_ms.clone();
+        sorted.sort_unstable();
+        let rank = (sorted.len() * percent.min(100)).div_ceil(100);
+        sorted.get(rank.saturating_sub(1)).copied()
+    }
+
+    /// A threshold a quarter above the 90th percentile release, so nearly
+    /// every habitual release still quick-switches while holding a little
+    /// longer shows the picker. `None` until `MIN_SAMPLES` are recorded.
+    #[must_use]
+    pub fn learned_threshold_ms(&self) -> Option<u32> {
+        if self.samples_ms.len() < Self::MIN_SAMPLES {
+            return None;
+        }
+        let (min, max) = Self::THRESHOLD_RANGE_MS;
+        let p90 = self.percentile(90)?;
+        Some((p90 + p90 / 4).clamp(min, max))
+    }
+}
+
+/// Time spent on a window switched to through sesame, from that switch to
+/// the next one (or to a session lock or daemon exit).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FocusInterval {
+    /// Unix time in seconds.
+    pub start: u64,
+    /// Unix time in seconds.
+    pub end: u64,
+    pub app_id: String,
+}
+
+impl FocusInterval {
+    /// Seconds spent.
+    #[must_use]
+    pub fn seconds(&self) -> u64 {
+        self.end.saturating_sub(self.start)
+    }
+}
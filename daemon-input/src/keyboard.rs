@@ -3,14 +3,17 @@
 //! Provides:
 //! - `XkbContext`: maintains xkbcommon state for keycode-to-keysym translation.
 //! - `KeyboardEvent`: a processed keyboard event with keysym, unicode, and modifiers.
+//! - `TypeAhead`: holds keystrokes typed between an activation chord and the grab.
 //! - `spawn_keyboard_readers()`: starts async tasks that read from all keyboard
 //!   devices and funnel events through a single channel.
 
 use evdev::EventSummary;
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
+use zeroize::{Zeroize, Zeroizing};
 
 /// A processed keyboard event ready for IPC forwarding.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Zeroize)]
 pub struct KeyboardEvent {
     /// XKB keysym value (e.g., 0xFF1B for Escape).
     pub keyval: u32,
@@ -128,6 +131,120 @@ impl XkbContext {
     }
 }
 
+/// How long a buffered keystroke stays eligible for replay.
+pub const TYPE_AHEAD_WINDOW: Duration = Duration::from_secs(1);
+
+/// Maximum keystrokes held while waiting for a grab.
+const TYPE_AHEAD_CAPACITY: usize = 32;
+
+/// GDK modifier bits that make a key press a chord (Ctrl, Alt, Super).
+const CHORD_MODIFIERS: u32 = (1 << 2) | (1 << 3) | (1 << 26);
+
+/// Type-ahead buffer for keystrokes typed before a grab is granted.
+///
+/// `Alt+Space g` typed in one motion reaches evdev before daemon-wm has
+/// refreshed its window list and sent `InputGrabRequest`, so the `g` would be
+/// dropped. A chord (a press of Ctrl/Alt/Super, or any press while one is
+/// held) arms the buffer; plain presses within `TYPE_AHEAD_WINDOW` of it are
+/// held and replayed when the next grab starts. Without a preceding chord
+/// nothing is buffered, so ordinary typing is never replayed into an overlay.
+///
+/// Held keystrokes may be a password typed right after `Super+L`, so they
+/// are zeroized whenever they are dropped, and nothing is buffered while
+/// secure input (the session lock screen) is active.
+pub struct TypeAhead {
+    armed_at: Option<Instant>,
+    secure: bool,
+    times: Vec<Instant>,
+    // Allocated once at full capacity so pushes never leave copies behind.
+    keys: Zeroizing<Vec<KeyboardEvent>>,
+}
+
+impl Default for TypeAhead {
+    fn default() -> Self {
+        Self {
+            armed_at: None,
+            secure: false,
+            times: Vec::with_capacity(TYPE_AHEAD_CAPACITY),
+            keys: Zeroizing::new(Vec::with_capacity(TYPE_AHEAD_CAPACITY)),
+        }
+    }
+}
+
+impl std::fmt::Debug for TypeAhead {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TypeAhead")
+            .field("armed_at", &self.armed_at)
+            .field("secure", &self.secure)
+            .field("keys", &self.keys.len())
+            .finish()
+    }
+}
+
+impl TypeAhead {
+    /// Observe a key event received while no grab is active.
+    pub fn observe(&mut self, event: &KeyboardEvent, now: Instant) {
+        if !event.pressed || self.secure {
+            return;
+        }
+        if is_chord_modifier(event.keyval) || event.modifiers & CHORD_MODIFIERS != 0 {
+            self.clear();
+            self.armed_at = Some(now);
+            return;
+        }
+        if is_shift_or_lock(event.keyval) {
+            return;
+        }
+        match self.armed_at {
+            Some(armed) if now.saturating_duration_since(armed) <= TYPE_AHEAD_WINDOW => {
+                if self.keys.len() < TYPE_AHEAD_CAPACITY {
+                    self.times.push(now);
+                    self.keys.push(event.clone());
+                }
+            }
+            _ => self.clear(),
+        }
+    }
+
+    /// Take the keystrokes still eligible for replay, oldest first, and disarm.
+    pub fn drain(&mut self, now: Instant) -> Zeroizing<Vec<KeyboardEvent>> {
+        let mut replay = Zeroizing::new(Vec::with_capacity(self.keys.len()));
+        for (at, event) in self.times.iter().zip(self.keys.iter()) {
+            if now.saturating_duration_since(*at) <= TYPE_AHEAD_WINDOW {
+                replay.push(event.clone());
+            }
+        }
+        self.clear();
+        replay
+    }
+
+    /// Disarm and zeroize anything held.
+    pub fn clear(&mut self) {
+        self.armed_at = None;
+        self.times.clear();
+        self.keys.zeroize();
+    }
+
+    /// Enter or leave secure input. While it is active nothing is buffered;
+    /// entering it discards what is already held.
+    pub fn set_secure(&mut self, secure: bool) {
+        self.secure = secure;
+        if secure {
+            self.clear();
+        }
+    }
+}
+
+/// Control, Meta, Alt, Super and Hyper keysyms.
+fn is_chord_modifier(keyval: u32) -> bool {
+    matches!(keyval, 0xFFE3..=0xFFEE) && !matches!(keyval, 0xFFE5 | 0xFFE6)
+}
+
+/// Shift and Caps/Shift Lock keysyms: they change case, not intent.
+fn is_shift_or_lock(keyval: u32) -> bool {
+    matches!(keyval, 0xFFE1 | 0xFFE2 | 0xFFE5 | 0xFFE6)
+}
+
 /// Spawn async reader tasks for all keyboard devices.
 ///
 /// Enumerates `/dev/input/event*`, opens all keyboard devices as async
@@ -267,6 +384,68 @@ mod tests {
         }
     }
 
+    fn press(keyval: u32, modifiers: u32) -> KeyboardEvent {
+        KeyboardEvent {
+            keyval,
+            keycode: 0,
+            pressed: true,
+            modifiers,
+            unicode: char::from_u32(keyval).filter(char::is_ascii_graphic),
+        }
+    }
+
+    #[test]
+    fn type_ahead_replays_keys_after_chord() {
+        let t0 = Instant::now();
+        let ms = Duration::from_millis;
+        let mut buf = TypeAhead::default();
+        buf.observe(&press(0x0078, 0), t0); // 'x' before any chord: ignored
+        buf.observe(&press(0xFFE9, 0), t0); // Alt_L
+        buf.observe(&press(0x0020, 1 << 3), t0 + ms(10)); // Alt+Space
+        buf.observe(&press(0xFFE1, 0), t0 + ms(40)); // Shift_L: not buffered
+        buf.observe(&press(0x0047, 1), t0 + ms(50)); // 'G'
+        buf.observe(&press(0x0069, 0), t0 + ms(60)); // 'i'
+
+        let keys: Vec<u32> = buf.drain(t0 + ms(200)).iter().map(|k| k.keyval).collect();
+        assert_eq!(keys, vec![0x0047, 0x0069]);
+        assert!(buf.drain(t0 + ms(300)).is_empty());
+    }
+
+    #[test]
+    fn type_ahead_ignores_plain_typing_and_stale_keys() {
+        let t0 = Instant::now();
+        let mut buf = TypeAhead::default();
+        buf.observe(&press(0x0061, 0), t0);
+        assert!(buf.drain(t0).is_empty());
+
+        buf.observe(&press(0x006C, 1 << 2), t0); // Ctrl+L
+        buf.observe(&press(0x0061, 0), t0 + TYPE_AHEAD_WINDOW * 2);
+        assert!(buf.drain(t0 + TYPE_AHEAD_WINDOW * 2).is_empty());
+
+        buf.observe(&press(0xFFEB, 0), t0); // Super_L
+        buf.observe(&press(0x0061, 0), t0);
+        assert!(buf.drain(t0 + TYPE_AHEAD_WINDOW * 2).is_empty());
+    }
+
+    #[test]
+    fn type_ahead_holds_nothing_during_secure_input() {
+        let t0 = Instant::now();
+        let mut buf = TypeAhead::default();
+        buf.observe(&press(0x006C, 1 << 26), t0); // Super+L
+        buf.observe(&press(0x0070, 0), t0); // 'p'
+        buf.set_secure(true);
+        assert!(buf.drain(t0).is_empty());
+
+        buf.observe(&press(0x0020, 1 << 3), t0); // Alt+Space
+        buf.observe(&press(0x0070, 0), t0);
+        assert!(buf.drain(t0).is_empty());
+
+        buf.set_secure(false);
+        buf.observe(&press(0x0020, 1 << 3), t0);
+        buf.observe(&press(0x0067, 0), t0); // 'g'
+        assert_eq!(buf.drain(t0).len(), 1);
+    }
+
     #[test]
     fn raw_key_event_copy() {
         let ev = RawKeyEvent {
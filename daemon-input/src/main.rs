@@ -4,7 +4,8 @@
 //! and re-emits remapped events via uinput virtual device. App-aware layer
 //! switching requires integration with daemon-wm focus tracking.
 //!
-//! Landlock: /dev/input (read), /dev/uinput (write), runtime dir (IPC).
+//! Landlock: /dev/input (read), /dev/uinput (write), runtime dir (IPC),
+//! system bus socket (logind session lock).
 //! No network access beyond local IPC.
 
 mod keyboard;
@@ -73,6 +74,14 @@ async fn main() -> anyhow::Result<()> {
     // Grab state: tracks whether daemon-wm has requested keyboard forwarding.
     let mut grab_active = false;
     let mut grab_requester: Option<DaemonId> = None;
+    // Keystrokes typed after an activation chord but before the grab arrives.
+    let mut type_ahead = keyboard::TypeAhead::default();
+
+    // Session lock (Linux): the lock screen reads the password without our
+    // grab, so type-ahead is held off until logind reports the unlock.
+    let (lock_tx, mut lock_rx) = tokio::sync::mpsc::channel(4);
+    #[cfg(target_os = "linux")]
+    tokio::spawn(platform_linux::dbus::session_lock_monitor(lock_tx));
 
     let mut client = BusClient::connect_daemon_with_keypair_retry(
         "daemon-input",
@@ -153,6 +162,7 @@ async fn main() -> anyhow::Result<()> {
                     // Even when no grab is active, update XKB state so modifier
                     // tracking stays accurate for when a grab is activated.
                     let kb_event = xkb.process_key(raw_event.keycode, raw_event.pressed);
+                    type_ahead.observe(&kb_event, std::time::Instant::now());
 
                     // Always forward Alt/Meta release events regardless of grab
                     // state. This solves a critical race condition: on a
@@ -180,6 +190,15 @@ async fn main() -> anyhow::Result<()> {
                     }
                 }
             }
+            Some(change) = lock_rx.recv() => {
+                use platform_linux::dbus::SessionLockChange;
+                match change {
+                    // Lockers like swaylock never report the unlock, so a
+                    // request only discards what was typed before it.
+                    SessionLockChange::LockRequested => type_ahead.clear(),
+                    SessionLockChange::Locked(locked) => type_ahead.set_secure(locked),
+                }
+            }
             msg_opt = client.recv() => {
                 let Some(msg) = msg_opt else {
                     tracing::error!("IPC bus disconnected — exiting for systemd restart");
@@ -209,6 +228,25 @@ async fn main() -> anyhow::Result<()> {
                         if let Err(e) = client.send(&response).await {
                             tracing::warn!(error = %e, "failed to send grab response");
                         }
+
+                        // Replay keys typed while the requester was still
+                        // getting ready (e.g. the `g` in a fast `Alt+Space g`).
+                        let buffered = type_ahead.drain(std::time::Instant::now());
+                        if !buffered.is_empty() {
+                            tracing::debug!(count = buffered.len(), "replaying type-ahead keys");
+                        }
+                        for kb_event in buffered.iter() {
+                            client.publish(
+                                EventKind::InputKeyEvent {
+                                    keyval: kb_event.keyval,
+                                    keycode: kb_event.keycode,
+                                    pressed: true,
+                                    modifiers: kb_event.modifiers,
+                                    unicode: kb_event.unicode,
+                                },
+                                SecurityLevel::Internal,
+                            ).await.ok();
+                        }
                         None
                     }
 
@@ -356,6 +394,21 @@ fn apply_sandbox() {
         });
     }
 
+    // System bus socket: logind's session lock state.
+    let system_bus = std::env::var("DBUS_SYSTEM_BUS_ADDRESS")
+        .ok()
+        .and_then(|addr| {
+            let path = addr.strip_prefix("unix:path=")?.split(',').next()?;
+            Some(std::path::PathBuf::from(path))
+        })
+        .unwrap_or_else(|| std::path::PathBuf::from("/run/dbus/system_bus_socket"));
+    if system_bus.exists() {
+        rules.push(LandlockRule {
+            path: system_bus,
+            access: FsAccess::ReadWriteFile,
+        });
+    }
+
     let seccomp = SeccompProfile {
         daemon_name: "daemon-input".into(),
         allowed_syscalls: vec![
@@ -31,6 +31,9 @@ impl From<anyhow::Error> for LaunchError {
 /// 1. Exact match on the full ID
 /// 2. Last dot-separated segment match (e.g. "firefox" matches "org.mozilla.firefox")
 /// 3. Case-insensitive full ID match
+///
+/// Items from other sources (recent files, projects) only resolve exactly —
+/// a query like "rs" must never fuzzy-resolve to `recent:/src/main.rs`.
 pub(crate) fn resolve_entry<'a>(
     entry_id: &str,
     cache: &'a HashMap<String, scanner::CachedEntry>,
@@ -42,7 +45,8 @@ pub(crate) fn resolve_entry<'a>(
 
     // Strategy 2: last segment match (e.g., "firefox" matches "org.mozilla.firefox")
     let lower = entry_id.to_lowercase();
-    if let Some(entry) = cache.values().find(|e| {
+    let desktop = || cache.values().filter(|e| e.argv.is_none());
+    if let Some(entry) = desktop().find(|e| {
         e.id.rsplit('.')
             .next()
             .map(|seg| seg.to_lowercase() == lower)
@@ -53,7 +57,7 @@ pub(crate) fn resolve_entry<'a>(
     }
 
     // Strategy 3: case-insensitive full ID match
-    if let Some(entry) = cache.values().find(|e| e.id.to_lowercase() == lower) {
+    if let Some(entry) = desktop().find(|e| e.id.to_lowercase() == lower) {
         tracing::info!(entry_id, resolved_id = %entry.id, "resolved via case-insensitive match");
         return Some(entry);
     }
@@ -76,9 +80,24 @@ pub(crate) async fn launch_entry(
     let cached =
         resolve_entry(entry_id, cache).ok_or(LaunchError::Denial(LaunchDenial::EntryNotFound))?;
     tracing::info!(entry_id, resolved_id = %cached.id, "entry resolved");
+    launch_cached(cached, profile, tags, launch_args, client, config_state).await
+}
 
-    let exec = scanner::strip_field_codes(&cached.exec);
-    let parts = scanner::tokenize_exec(&exec);
+/// Run the launch pipeline for an already-resolved entry. Also used for
+/// command-palette shell commands, which have no desktop entry.
+pub(crate) async fn launch_cached(
+    cached: &scanner::CachedEntry,
+    profile: Option<&str>,
+    tags: &[String],
+    launch_args: &[String],
+    client: &BusClient,
+    config_state: &Arc<std::sync::RwLock<core_config::Config>>,
+) -> Result<u32, LaunchError> {
+    let entry_id = cached.id.as_str();
+    let parts = match &cached.argv {
+        Some(argv) => argv.clone(),
+        None => scanner::tokenize_exec(&scanner::strip_field_codes(&cached.exec)),
+    };
     if parts.is_empty() {
         return Err(LaunchError::Other(anyhow::anyhow!(
             "empty Exec line for '{entry_id}'"
@@ -97,6 +116,9 @@ pub(crate) async fn launch_entry(
     let mut all_secrets: Vec<(String, String)> = Vec::new(); // (secret_name, trust_profile_name)
     let mut devshell: Option<String> = None;
     let mut cwd: Option<String> = None;
+    let mut allow_env: Vec<String> = Vec::new();
+    // Names whose value came from an env file rather than a profile's `env`.
+    let mut env_file_vars: std::collections::BTreeSet<String> = std::collections::BTreeSet::new();
 
     tracing::info!(
         entry_id,
@@ -120,8 +142,17 @@ pub(crate) async fn launch_entry(
                 })
             })?;
 
+            // Env files first, so the profile's own env overrides them
+            for path in &lp.env_files {
+                for (k, v) in read_env_file(path, &composed_env)? {
+                    env_file_vars.insert(k.clone());
+                    composed_env.insert(k, v);
+                }
+            }
+
             // Merge env (later tag wins on conflict)
             for (k, v) in &lp.env {
+                env_file_vars.remove(k);
                 composed_env.insert(k.clone(), v.clone());
             }
 
@@ -135,6 +166,9 @@ pub(crate) async fn launch_entry(
                 cwd.clone_from(&lp.cwd);
             }
 
+            // Blocklist exemptions accumulate
+            allow_env.extend(lp.allow_env.iter().cloned());
+
             // Collect secrets with their owning trust profile
             for secret in &lp.secrets {
                 if !all_secrets.iter().any(|(s, _)| s == secret) {
@@ -144,6 +178,19 @@ pub(crate) async fn launch_entry(
         }
     }
 
+    // Env files get the same blocklist and exemptions as the inherited
+    // environment, so a file cannot pass on what scrubbing withholds.
+    let blocklist = config.profiles.get(default_profile).map_or_else(
+        || core_config::LauncherConfig::default().env_blocklist,
+        |p| p.launcher.env_blocklist.clone(),
+    );
+    let scrubbed_env_files = scrubbed_vars(env_file_vars, &blocklist, &allow_env);
+    for name in &scrubbed_env_files {
+        if let Some(mut value) = composed_env.remove(name) {
+            value.zeroize();
+        }
+    }
+
     // Fetch secrets via IPC — collect ALL denials before aborting so the WM
     // can prompt for all required vault unlocks at once.
     let mut locked_profiles: Vec<TrustProfileName> = Vec::new();
@@ -269,6 +316,18 @@ pub(crate) async fn launch_entry(
         cmd.current_dir(path);
     }
 
+    // Withhold blocklisted inherited vars (agent sockets, stray tokens)
+    // unless a tag allows them. Profile env and secrets are set after, so
+    // they are never scrubbed.
+    let scrubbed = scrubbed_vars(
+        std::env::vars_os().map(|(k, _)| k.to_string_lossy().into_owned()),
+        &blocklist,
+        &allow_env,
+    );
+    for name in &scrubbed {
+        cmd.env_remove(name);
+    }
+
     // Inject composed env vars from launch profiles
     for (k, v) in &composed_env {
         cmd.env(k, v);
@@ -322,10 +381,11 @@ pub(crate) async fn launch_entry(
         scope_cmd.args(launch_args);
     }
 
-    scope_cmd
-        .stdin(std::process::Stdio::null())
-        .stdout(std::process::Stdio::null())
-        .stderr(std::process::Stdio::inherit());
+    detach(&mut scope_cmd);
+
+    for name in &scrubbed {
+        scope_cmd.env_remove(name);
+    }
 
     // Launch profile env vars (user-configured per-app environment).
     for (k, v) in &composed_env {
@@ -343,20 +403,27 @@ pub(crate) async fn launch_entry(
         scope_cmd.current_dir(dir);
     }
 
-    let spawn_result = scope_cmd.spawn();
-    let (mut child, via_scope) = match spawn_result {
+    let spawn_result = tokio::process::Command::from(scope_cmd).spawn();
+    let (child, via_scope) = match spawn_result {
         Ok(child) => (child, true),
         Err(e) => {
             tracing::warn!(error = %e, "systemd-run unavailable, falling back to direct spawn");
-            cmd.stdin(std::process::Stdio::null())
-                .stdout(std::process::Stdio::null())
-                .stderr(std::process::Stdio::inherit());
-            let child = cmd.spawn().context("failed to spawn process")?;
-            (child, false)
+            detach(&mut cmd);
+            match tokio::process::Command::from(cmd).spawn() {
+                Ok(child) => (child, false),
+                Err(e) => {
+                    let reason = e.to_string();
+                    tracing::error!(entry_id, %program, %reason, "failed to spawn process");
+                    crate::notify::launch_failed(entry_id, &program, &reason).await;
+                    return Err(LaunchError::Other(
+                        anyhow::Error::new(e).context("failed to spawn process"),
+                    ));
+                }
+            }
         }
     };
 
-    let pid = child.id();
+    let pid = child.id().unwrap_or(0);
 
     // Zeroize secret values after they've been copied to the child process.
     for v in composed_env.values_mut() {
@@ -364,14 +431,15 @@ pub(crate) async fn launch_entry(
     }
     drop(composed_env);
 
-    // Reap the systemd-run wrapper (or direct child) to prevent zombies.
+    // Reap the app when it exits so it never lingers as a zombie. A quick
+    // non-zero exit means the program never really started (missing binary,
+    // not executable), which the user is told about.
     let entry_id_owned = entry_id.to_string();
-    tokio::task::spawn_blocking(move || match child.wait() {
-        Ok(status) => {
-            tracing::debug!(pid, entry_id = %entry_id_owned, %status, via_scope, "child reaped");
-        }
-        Err(e) => {
-            tracing::warn!(pid, entry_id = %entry_id_owned, error = %e, "child wait failed");
+    let program_owned = program.clone();
+    tokio::spawn(async move {
+        if let Some(reason) = reap(child, &entry_id_owned).await {
+            tracing::error!(pid, entry_id = %entry_id_owned, %program_owned, %reason, via_scope, "launched process failed to start");
+            crate::notify::launch_failed(&entry_id_owned, &program_owned, &reason).await;
         }
     });
 
@@ -383,6 +451,8 @@ pub(crate) async fn launch_entry(
         ?devshell,
         env_count,
         secret_count,
+        ?scrubbed,
+        ?scrubbed_env_files,
         via_scope,
         "launched"
     );
@@ -400,6 +470,78 @@ fn parse_tag<'a>(tag: &'a str, default_profile: &'a str) -> (String, String) {
     }
 }
 
+/// Detach a launch command from daemon-launcher's session and stdio, so
+/// the app survives the launcher and anything signalling its process
+/// group. stderr stays on the launcher's, which is the journal.
+fn detach(cmd: &mut std::process::Command) {
+    #[cfg(target_os = "linux")]
+    platform_linux::process::detach(cmd);
+    #[cfg(not(target_os = "linux"))]
+    cmd.stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null());
+    cmd.stderr(std::process::Stdio::inherit());
+}
+
+/// Wait for a launched app to exit, without holding a thread while it
+/// runs. Why it failed to start, if it exited non-zero right away.
+async fn reap(mut child: tokio::process::Child, entry_id: &str) -> Option<String> {
+    let spawned_at = std::time::Instant::now();
+    let pid = child.id();
+    match child.wait().await {
+        Ok(status) => {
+            tracing::debug!(?pid, entry_id, %status, "child reaped");
+            crate::notify::early_exit_reason(status, spawned_at.elapsed())
+        }
+        Err(e) => {
+            tracing::warn!(?pid, entry_id, error = %e, "child wait failed");
+            None
+        }
+    }
+}
+
+/// Read and parse the env file at `path`. Interpolations resolve against
+/// the env composed so far, then daemon-launcher's environment.
+fn read_env_file(
+    path: &str,
+    composed: &std::collections::BTreeMap<String, String>,
+) -> Result<Vec<(String, String)>, LaunchError> {
+    if !std::path::Path::new(path).is_absolute() {
+        return Err(LaunchError::Other(anyhow::anyhow!(
+            "env file must be an absolute path, got: {path}"
+        )));
+    }
+    let mut contents =
+        std::fs::read_to_string(path).with_context(|| format!("failed to read env file {path}"))?;
+    let parsed = core_config::parse_env_file(&contents, |name| {
+        composed
+            .get(name)
+            .cloned()
+            .or_else(|| std::env::var(name).ok())
+    });
+    contents.zeroize();
+    parsed.map_err(|e| LaunchError::Other(anyhow::anyhow!("invalid env file {path}: {e}")))
+}
+
+/// Names in `inherited` matching a `blocklist` pattern and no `allow`
+/// pattern, which are removed from the launched app's environment.
+fn scrubbed_vars(
+    inherited: impl IntoIterator<Item = String>,
+    blocklist: &[String],
+    allow: &[String],
+) -> Vec<String> {
+    let matches_any = |patterns: &[String], name: &str| {
+        patterns
+            .iter()
+            .any(|p| core_config::env_pattern_matches(p, name))
+    };
+    let mut names: Vec<String> = inherited
+        .into_iter()
+        .filter(|name| matches_any(blocklist, name) && !matches_any(allow, name))
+        .collect();
+    names.sort();
+    names
+}
+
 /// Transform a secret name to an environment variable name.
 /// Uppercase, hyphens to underscores.
 fn secret_name_to_env_var(name: &str) -> String {
@@ -420,7 +562,10 @@ fn sanitize_unit_name(s: &str) -> String {
             prev_dash = true;
         }
     }
-    result.trim_matches('-').to_string()
+    // Unit names are capped at 255 bytes; recent-file IDs embed full paths.
+    let mut result = result.trim_matches('-').to_string();
+    result.truncate(128);
+    result
 }
 
 #[cfg(test)]
@@ -432,14 +577,17 @@ mod tests {
             scanner::CachedEntry {
                 id: "org.mozilla.firefox".into(),
                 exec: "firefox".into(),
+                argv: None,
             },
             scanner::CachedEntry {
                 id: "com.mitchellh.ghostty".into(),
                 exec: "ghostty".into(),
+                argv: None,
             },
             scanner::CachedEntry {
                 id: "Alacritty".into(),
                 exec: "alacritty".into(),
+                argv: None,
             },
         ];
         entries.into_iter().map(|e| (e.id.clone(), e)).collect()
@@ -472,6 +620,22 @@ mod tests {
         assert!(resolve_entry("nonexistent", &cache).is_none());
     }
 
+    #[test]
+    fn resolve_source_items_exact_only() {
+        let mut cache = test_cache();
+        cache.insert(
+            "recent:/src/main.rs".into(),
+            scanner::CachedEntry {
+                id: "recent:/src/main.rs".into(),
+                exec: "xdg-open /src/main.rs".into(),
+                argv: Some(vec!["xdg-open".into(), "/src/main.rs".into()]),
+            },
+        );
+        assert!(resolve_entry("recent:/src/main.rs", &cache).is_some());
+        assert!(resolve_entry("rs", &cache).is_none());
+        assert!(resolve_entry("RECENT:/src/main.rs", &cache).is_none());
+    }
+
     #[test]
     fn secret_name_to_env_var_basic() {
         assert_eq!(secret_name_to_env_var("github-token"), "GITHUB_TOKEN");
@@ -483,6 +647,98 @@ mod tests {
         assert_eq!(secret_name_to_env_var("a-b-c"), "A_B_C");
     }
 
+    fn spawn_detached(script: &str) -> tokio::process::Child {
+        let mut cmd = std::process::Command::new("sh");
+        cmd.args(["-c", script]);
+        detach(&mut cmd);
+        tokio::process::Command::from(cmd).spawn().unwrap()
+    }
+
+    /// Whether `pid` is running: it exists and is not a zombie.
+    #[cfg(target_os = "linux")]
+    fn is_running(pid: u32) -> bool {
+        std::fs::read_to_string(format!("/proc/{pid}/stat")).is_ok_and(|stat| {
+            stat.rsplit(") ")
+                .next()
+                .is_some_and(|s| !s.starts_with('Z'))
+        })
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn reap_waits_out_a_long_running_app() {
+        let child = spawn_detached("sleep 30");
+        let pid = child.id().unwrap();
+        let reaper = tokio::spawn(async move { reap(child, "fake").await });
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        assert!(!reaper.is_finished(), "app still running");
+        assert!(is_running(pid));
+
+        std::process::Command::new("kill")
+            .arg(pid.to_string())
+            .status()
+            .unwrap();
+        assert_eq!(
+            reaper.await.unwrap(),
+            None,
+            "killed apps are not launch failures"
+        );
+        assert!(!std::path::Path::new(&format!("/proc/{pid}")).exists());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn reap_reports_an_app_that_never_started() {
+        let child = spawn_detached("exit 127");
+        let pid = child.id().unwrap();
+        let reason = reap(child, "fake").await.unwrap();
+        assert!(reason.contains("command not found"), "{reason}");
+        assert!(!std::path::Path::new(&format!("/proc/{pid}")).exists());
+    }
+
+    #[test]
+    fn read_env_file_interpolates_composed_env() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("dev.env");
+        std::fs::write(&path, "export CARGO_TARGET_DIR=\"$WORKSPACE/target\"\n").unwrap();
+        let composed = [("WORKSPACE".to_string(), "/ws".to_string())].into();
+        let parsed = read_env_file(path.to_str().unwrap(), &composed)
+            .ok()
+            .unwrap();
+        assert_eq!(
+            parsed,
+            [("CARGO_TARGET_DIR".to_string(), "/ws/target".to_string())]
+        );
+
+        std::fs::write(&path, "TOKEN=\"unterminated").unwrap();
+        assert!(read_env_file(path.to_str().unwrap(), &composed).is_err());
+        assert!(read_env_file("relative.env", &composed).is_err());
+    }
+
+    #[test]
+    fn scrubbed_vars_honours_blocklist_and_allow() {
+        let blocklist = core_config::LauncherConfig::default().env_blocklist;
+        let inherited = [
+            "SSH_AUTH_SOCK",
+            "GITHUB_TOKEN",
+            "OPENAI_API_KEY",
+            "WAYLAND_DISPLAY",
+            "HOME",
+            "TOKEN_FILE",
+        ]
+        .map(String::from);
+        assert_eq!(
+            scrubbed_vars(inherited.clone(), &blocklist, &[]),
+            ["GITHUB_TOKEN", "OPENAI_API_KEY", "SSH_AUTH_SOCK"]
+        );
+        let allow = ["SSH_AUTH_SOCK".to_string(), "GITHUB_*".to_string()];
+        assert_eq!(
+            scrubbed_vars(inherited, &blocklist, &allow),
+            ["OPENAI_API_KEY"]
+        );
+        assert!(scrubbed_vars(["SSH_AUTH_SOCK".to_string()], &[], &[]).is_empty());
+    }
+
     #[test]
     fn parse_tag_unqualified() {
         let (profile, name) = parse_tag("dev-rust", "default");
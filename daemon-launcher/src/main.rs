@@ -1,7 +1,8 @@
 //! daemon-launcher: Application launcher daemon.
 //!
-//! Scans XDG desktop entries, builds a nucleo fuzzy index with frecency
-//! ranking, and serves LaunchQuery/LaunchExecute requests over the IPC bus.
+//! Scans XDG desktop entries (plus recent files and editor projects), builds
+//! a nucleo fuzzy index with frecency ranking, and serves
+//! LaunchQuery/LaunchExecute requests over the IPC bus.
 
 use anyhow::Context;
 use clap::Parser;
@@ -14,6 +15,9 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 mod launch;
+mod multiplexer;
+mod notify;
+mod recent;
 mod scanner;
 
 #[derive(Parser)]
@@ -57,6 +61,11 @@ async fn main() -> anyhow::Result<()> {
 
     // Config hot-reload.
     let config = core_config::load_config(None).context("failed to load config")?;
+    let launcher_config = config
+        .profiles
+        .get(&*profile)
+        .map(|p| p.launcher.clone())
+        .unwrap_or_default();
     let config_paths = core_config::resolve_config_paths(None);
     let (reload_tx, mut reload_rx) = tokio::sync::mpsc::channel::<()>(4);
     let (_config_watcher, _config_state) = core_config::ConfigWatcher::with_callback(
@@ -77,11 +86,26 @@ async fn main() -> anyhow::Result<()> {
     // Fuzzy matcher.
     let matcher = FuzzyMatcher::new(Arc::new(|| {}));
 
-    // Scan desktop entries (blocking I/O) — cache Exec lines before sandbox.
-    let (items, entry_cache) = tokio::task::spawn_blocking(|| {
-        let (items, cached) = scanner::scan_all();
-        let cache: HashMap<String, scanner::CachedEntry> =
+    // Scan desktop entries and extra item sources (blocking I/O) — cache
+    // Exec lines before sandbox.
+    let sources = recent::sources(&launcher_config);
+    let (items, entry_cache) = tokio::task::spawn_blocking(move || {
+        let (mut items, cached) = scanner::scan_all();
+        let mut cache: HashMap<String, scanner::CachedEntry> =
             cached.into_iter().map(|e| (e.id.clone(), e)).collect();
+        let extra = core_fuzzy::collect_all(&sources);
+        tracing::info!(count = extra.len(), "source items collected");
+        for source_item in extra {
+            cache.insert(
+                source_item.item.id.clone(),
+                scanner::CachedEntry {
+                    id: source_item.item.id.clone(),
+                    exec: source_item.command.join(" "),
+                    argv: Some(source_item.command),
+                },
+            );
+            items.push(source_item.item);
+        }
         (items, cache)
     })
     .await
@@ -233,6 +257,87 @@ async fn main() -> anyhow::Result<()> {
                                 }
                             }
 
+                            EventKind::LaunchShellCommand { command, profile } => {
+                                tracing::info!(%command, ?profile, "handling LaunchShellCommand");
+                                // Same profile daemon-wm reads its WmConfig from.
+                                // Outside the palette, only commands of
+                                // command-only key bindings and hooks may run.
+                                let enabled = _config_state
+                                    .read()
+                                    .unwrap_or_else(|e| e.into_inner())
+                                    .profiles
+                                    .values()
+                                    .next()
+                                    .is_some_and(|p| {
+                                        p.wm.command_palette
+                                            || p.wm.key_bindings.values().any(|b| {
+                                                b.command.as_deref() == Some(command.as_str())
+                                            })
+                                            || p.wm.hooks.command_lines().any(|c| c == *command)
+                                    });
+                                if enabled {
+                                    let entry = scanner::CachedEntry {
+                                        id: "command-palette".into(),
+                                        exec: command.clone(),
+                                        argv: Some(vec!["sh".into(), "-c".into(), command.clone()]),
+                                    };
+                                    match launch::launch_cached(&entry, profile.as_ref().map(|p| p.as_ref()), &[], &[], &client, &_config_state).await {
+                                        Ok(pid) => Some(EventKind::LaunchExecuteResponse { pid, error: None, denial: None }),
+                                        Err(launch::LaunchError::Denial(denial)) => Some(EventKind::LaunchExecuteResponse {
+                                            pid: 0,
+                                            error: Some(format!("{denial:?}")),
+                                            denial: Some(denial),
+                                        }),
+                                        Err(launch::LaunchError::Other(e)) => {
+                                            tracing::error!(error = %e, "shell command failed");
+                                            Some(EventKind::LaunchExecuteResponse {
+                                                pid: 0,
+                                                error: Some(e.to_string()),
+                                                denial: Some(LaunchDenial::SpawnFailed { reason: e.to_string() }),
+                                            })
+                                        }
+                                    }
+                                } else {
+                                    tracing::warn!("LaunchShellCommand refused: command_palette is disabled and no key binding runs this command");
+                                    Some(EventKind::LaunchExecuteResponse {
+                                        pid: 0,
+                                        error: Some("command palette is disabled".into()),
+                                        denial: None,
+                                    })
+                                }
+                            }
+
+                            EventKind::MultiplexerList { kind } => {
+                                let kind = *kind;
+                                let result = tokio::task::spawn_blocking(move || multiplexer::list(kind)).await;
+                                match result {
+                                    Ok(Ok(targets)) => {
+                                        tracing::debug!(%kind, count = targets.len(), "multiplexer targets listed");
+                                        Some(EventKind::MultiplexerListResponse { targets, error: None })
+                                    }
+                                    Ok(Err(e)) => Some(EventKind::MultiplexerListResponse {
+                                        targets: Vec::new(),
+                                        error: Some(format!("{e:#}")),
+                                    }),
+                                    Err(e) => Some(EventKind::MultiplexerListResponse {
+                                        targets: Vec::new(),
+                                        error: Some(e.to_string()),
+                                    }),
+                                }
+                            }
+
+                            EventKind::MultiplexerSelect { target } => {
+                                tracing::info!(kind = %target.kind, session = %target.session, index = target.index, "handling MultiplexerSelect");
+                                let target = target.clone();
+                                let result = tokio::task::spawn_blocking(move || multiplexer::select(&target)).await;
+                                let error = match result {
+                                    Ok(Ok(())) => None,
+                                    Ok(Err(e)) => Some(format!("{e:#}")),
+                                    Err(e) => Some(e.to_string()),
+                                };
+                                Some(EventKind::MultiplexerSelectResponse { success: error.is_none(), error })
+                            }
+
                             // Ignore events not addressed to us.
                             _ => None,
                         };
@@ -0,0 +1,161 @@
+//! Terminal multiplexer listing and window selection.
+//!
+//! Serves `MultiplexerList`/`MultiplexerSelect` for daemon-wm, which cannot
+//! exec from inside its sandbox. Runs the multiplexer's own CLI:
+//!
+//! - tmux: `tmux list-windows -a -F ...`, `tmux select-window -t`, `tmux switch-client -t`
+//! - zellij: `zellij list-sessions`, `zellij --session S action query-tab-names`,
+//!   `zellij --session S action go-to-tab N`
+
+use anyhow::Context;
+use core_types::{MultiplexerKind, MultiplexerTarget};
+
+/// Field separator for `tmux -F` output. Window names may contain spaces.
+const TMUX_FORMAT: &str = "#{session_name}\t#{window_index}\t#{window_active}\t#{window_name}";
+
+/// List every window/tab of every session.
+///
+/// A multiplexer that is installed but has no running server yields an empty list.
+pub(crate) fn list(kind: MultiplexerKind) -> anyhow::Result<Vec<MultiplexerTarget>> {
+    match kind {
+        MultiplexerKind::Tmux => {
+            let Some(stdout) = run("tmux", &["list-windows", "-a", "-F", TMUX_FORMAT])? else {
+                return Ok(Vec::new());
+            };
+            Ok(parse_tmux_windows(&stdout))
+        }
+        MultiplexerKind::Zellij => {
+            let Some(sessions) = run("zellij", &["list-sessions", "--short", "--no-formatting"])?
+            else {
+                return Ok(Vec::new());
+            };
+            let mut targets = Vec::new();
+            for session in sessions.lines().map(str::trim).filter(|s| !s.is_empty()) {
+                let args = ["--session", session, "action", "query-tab-names"];
+                if let Some(tabs) = run("zellij", &args)? {
+                    targets.extend(parse_zellij_tabs(session, &tabs));
+                }
+            }
+            Ok(targets)
+        }
+    }
+}
+
+/// Switch the multiplexer's client to `target`.
+pub(crate) fn select(target: &MultiplexerTarget) -> anyhow::Result<()> {
+    match target.kind {
+        MultiplexerKind::Tmux => {
+            let spec = format!("{}:{}", target.session, target.index);
+            run("tmux", &["select-window", "-t", &spec])?
+                .with_context(|| format!("tmux window {spec} not found"))?;
+            // Only succeeds when a client is attached elsewhere; the window
+            // selection above is what matters for the focused terminal.
+            if let Err(e) = run("tmux", &["switch-client", "-t", &spec]) {
+                tracing::debug!(%spec, error = %e, "tmux switch-client failed");
+            }
+            Ok(())
+        }
+        MultiplexerKind::Zellij => {
+            let index = target.index.to_string();
+            let args = ["--session", &target.session, "action", "go-to-tab", &index];
+            run("zellij", &args)?
+                .with_context(|| format!("zellij session {} not running", target.session))?;
+            Ok(())
+        }
+    }
+}
+
+/// Run a multiplexer command and return its stdout.
+///
+/// `Ok(None)` means the command ran but failed (e.g. no server running);
+/// `Err` means it could not be spawned at all.
+fn run(program: &str, args: &[&str]) -> anyhow::Result<Option<String>> {
+    let output = std::process::Command::new(program)
+        .args(args)
+        .stdin(std::process::Stdio::null())
+        .output()
+        .with_context(|| format!("failed to run {program}"))?;
+    if !output.status.success() {
+        tracing::debug!(
+            program,
+            ?args,
+            status = %output.status,
+            stderr = %String::from_utf8_lossy(&output.stderr).trim(),
+            "multiplexer command failed"
+        );
+        return Ok(None);
+    }
+    Ok(Some(String::from_utf8_lossy(&output.stdout).into_owned()))
+}
+
+/// Parse `tmux list-windows` output in [`TMUX_FORMAT`]. Malformed lines are skipped.
+fn parse_tmux_windows(stdout: &str) -> Vec<MultiplexerTarget> {
+    stdout
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(4, '\t');
+            let session = fields.next()?;
+            let index = fields.next()?.parse().ok()?;
+            let active = fields.next()? == "1";
+            let name = fields.next().unwrap_or_default();
+            Some(MultiplexerTarget {
+                kind: MultiplexerKind::Tmux,
+                session: session.to_string(),
+                index,
+                name: name.to_string(),
+                active,
+            })
+        })
+        .collect()
+}
+
+/// Parse `zellij action query-tab-names` output (one tab name per line, in order).
+fn parse_zellij_tabs(session: &str, stdout: &str) -> Vec<MultiplexerTarget> {
+    stdout
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .zip(1u32..)
+        .map(|(name, index)| MultiplexerTarget {
+            kind: MultiplexerKind::Zellij,
+            session: session.to_string(),
+            index,
+            name: name.trim().to_string(),
+            active: false,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_tmux_basic() {
+        let out = "main\t1\t1\tvim\nmain\t2\t0\tcargo watch\nwork\t0\t1\tlogs\n";
+        let targets = parse_tmux_windows(out);
+        assert_eq!(targets.len(), 3);
+        assert_eq!(targets[1].session, "main");
+        assert_eq!(targets[1].index, 2);
+        assert_eq!(targets[1].name, "cargo watch");
+        assert!(!targets[1].active);
+        assert!(targets[2].active);
+    }
+
+    #[test]
+    fn parse_tmux_skips_malformed() {
+        let targets = parse_tmux_windows("garbage\nmain\tx\t1\tvim\nmain\t3\t0\t\n");
+        assert_eq!(targets.len(), 1);
+        assert_eq!(targets[0].index, 3);
+        assert_eq!(targets[0].name, "");
+    }
+
+    #[test]
+    fn parse_zellij_numbers_tabs_from_one() {
+        let targets = parse_zellij_tabs("dev", "editor\n\nshell\n");
+        assert_eq!(targets.len(), 2);
+        assert_eq!(targets[0].index, 1);
+        assert_eq!(targets[1].index, 2);
+        assert_eq!(targets[1].name, "shell");
+        assert_eq!(targets[1].session, "dev");
+    }
+}
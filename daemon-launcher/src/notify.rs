@@ -0,0 +1,92 @@
+//! Desktop notifications for launches that fail after spawning.
+//!
+//! A launch goes through `systemd-run --scope`, which spawns even when the
+//! program it then executes is missing or not executable. Such failures
+//! only show up as the child exiting 126 or 127 right away, after the launch
+//! has already been reported as successful and the overlay is gone. They
+//! are surfaced as a desktop notification instead of only being logged.
+
+use std::process::ExitStatus;
+use std::time::Duration;
+
+/// A non-zero exit within this long after spawning is a failed launch
+/// rather than an app that ran and quit.
+pub(crate) const EARLY_EXIT_WINDOW: Duration = Duration::from_secs(3);
+
+/// Why a child that exited after `elapsed` failed to launch, or `None` if
+/// it ran, was killed by a signal, or ran for a while first.
+///
+/// Only exit statuses 126 and 127, which the shell and `systemd-run` use
+/// for a program that cannot be executed or found, count as failed
+/// launches. Any other status is the app's own, such as a single-instance
+/// app handing off to its running window and exiting 1, and is only
+/// logged.
+pub(crate) fn early_exit_reason(status: ExitStatus, elapsed: Duration) -> Option<String> {
+    if elapsed > EARLY_EXIT_WINDOW {
+        return None;
+    }
+    match status.code()? {
+        126 => Some("permission denied or not executable (exit status 126)".into()),
+        127 => Some("command not found (exit status 127)".into()),
+        _ => None,
+    }
+}
+
+/// Notify the user that `entry_id` (running `program`) failed to launch.
+///
+/// Best effort: without a session bus or notification server the failure
+/// stays in the log only.
+pub(crate) async fn launch_failed(entry_id: &str, program: &str, reason: &str) {
+    #[cfg(target_os = "linux")]
+    {
+        use platform_linux::dbus::{NotificationsProxy, SessionBus, Urgency};
+
+        let summary = format!("Could not launch {entry_id}");
+        let body = format!(
+            "{program}: {reason}\nSee journalctl --user -u open-sesame-launcher for details."
+        );
+        let result = match SessionBus::connect().await {
+            Ok(bus) => {
+                NotificationsProxy::new(&bus)
+                    .notify(&summary, &body, "dialog-error", Urgency::Critical)
+                    .await
+            }
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+            tracing::warn!(entry_id, error = %e, "launch failure notification not delivered");
+        }
+    }
+    #[cfg(not(target_os = "linux"))]
+    let _ = (entry_id, program, reason);
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::process::ExitStatusExt;
+
+    fn exited(code: i32) -> ExitStatus {
+        ExitStatus::from_raw(code << 8)
+    }
+
+    #[test]
+    fn early_exec_failure_is_a_failure() {
+        let soon = Duration::from_millis(200);
+        assert_eq!(
+            early_exit_reason(exited(127), soon).as_deref(),
+            Some("command not found (exit status 127)")
+        );
+        assert!(early_exit_reason(exited(126), soon).is_some());
+        assert!(early_exit_reason(exited(0), soon).is_none());
+        // The app's own status, e.g. a single-instance app handing off.
+        assert!(early_exit_reason(exited(1), soon).is_none());
+        // Killed by SIGTERM: no exit code.
+        assert!(early_exit_reason(ExitStatus::from_raw(15), soon).is_none());
+    }
+
+    #[test]
+    fn late_exit_is_not_a_launch_failure() {
+        assert!(early_exit_reason(exited(127), Duration::from_secs(60)).is_none());
+    }
+}
@@ -0,0 +1,323 @@
+//! Recent files and editor projects as launcher item sources.
+//!
+//! - [`RecentFiles`] reads `$XDG_DATA_HOME/recently-used.xbel` (written by GTK,
+//!   KDE and most file pickers) and opens entries with `xdg-open`.
+//! - [`EditorProjects`] reads VS Code workspace storage
+//!   (`$XDG_CONFIG_HOME/Code/User/workspaceStorage/*/workspace.json`, plus the
+//!   OSS and VSCodium variants) and opens folders in the matching editor.
+//!
+//! Both are scanned once at startup alongside desktop entries. Entries whose
+//! local path no longer exists are skipped; remote URIs are ignored.
+
+use core_fuzzy::{ItemSource, MatchItem, SourceItem, source_item_id};
+use std::path::{Path, PathBuf};
+
+/// Most recent `recently-used.xbel` entries to index.
+const MAX_RECENT_FILES: usize = 200;
+
+/// VS Code flavours: config directory name and CLI program.
+const VSCODE_FLAVOURS: &[(&str, &str)] = &[
+    ("Code", "code"),
+    ("Code - OSS", "code-oss"),
+    ("VSCodium", "codium"),
+];
+
+/// Sources enabled by the launcher config, rooted at the user's XDG dirs.
+pub(crate) fn sources(config: &core_config::LauncherConfig) -> Vec<Box<dyn ItemSource>> {
+    let mut sources: Vec<Box<dyn ItemSource>> = Vec::new();
+    if config.recent_files
+        && let Some(data) = dirs::data_dir()
+    {
+        sources.push(Box::new(RecentFiles {
+            path: data.join("recently-used.xbel"),
+        }));
+    }
+    if config.recent_projects
+        && let Some(config_dir) = dirs::config_dir()
+    {
+        sources.push(Box::new(EditorProjects { config_dir }));
+    }
+    sources
+}
+
+/// Recently used files from the freedesktop bookmark file.
+pub(crate) struct RecentFiles {
+    pub path: PathBuf,
+}
+
+impl ItemSource for RecentFiles {
+    fn name(&self) -> &'static str {
+        "recent"
+    }
+
+    fn collect(&self) -> Vec<SourceItem> {
+        let Ok(xml) = std::fs::read_to_string(&self.path) else {
+            return Vec::new();
+        };
+        parse_xbel(&xml)
+            .into_iter()
+            .filter(|p| p.exists())
+            .take(MAX_RECENT_FILES)
+            .map(|path| {
+                let shown = path.display().to_string();
+                SourceItem {
+                    item: MatchItem {
+                        id: source_item_id(self.name(), &shown),
+                        name: file_name(&path),
+                        extra: shown.clone(),
+                    },
+                    command: vec!["xdg-open".into(), shown],
+                }
+            })
+            .collect()
+    }
+}
+
+/// Recently opened VS Code (and derivative) folders and workspaces.
+pub(crate) struct EditorProjects {
+    pub config_dir: PathBuf,
+}
+
+impl ItemSource for EditorProjects {
+    fn name(&self) -> &'static str {
+        "project"
+    }
+
+    fn collect(&self) -> Vec<SourceItem> {
+        let mut seen = std::collections::HashSet::new();
+        let mut items = Vec::new();
+        for &(dir, program) in VSCODE_FLAVOURS {
+            let storage = self.config_dir.join(dir).join("User/workspaceStorage");
+            let Ok(entries) = std::fs::read_dir(&storage) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let Ok(json) = std::fs::read_to_string(entry.path().join("workspace.json")) else {
+                    continue;
+                };
+                let Some(path) = parse_vscode_workspace(&json) else {
+                    continue;
+                };
+                if !path.exists() || !seen.insert((program, path.clone())) {
+                    continue;
+                }
+                let shown = path.display().to_string();
+                items.push(SourceItem {
+                    item: MatchItem {
+                        id: source_item_id(self.name(), &format!("{program}:{shown}")),
+                        name: file_name(&path).trim_end_matches(".code-workspace").into(),
+                        extra: format!("{shown} {program}"),
+                    },
+                    command: vec![program.into(), shown],
+                });
+            }
+        }
+        items
+    }
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+/// Local paths from an XBEL document, most recently modified first.
+///
+/// Only `<bookmark href="file://...">` elements are considered; the rest of
+/// the document (metadata, application lists) is ignored.
+fn parse_xbel(xml: &str) -> Vec<PathBuf> {
+    let mut entries: Vec<(String, PathBuf)> = xml
+        .split("<bookmark")
+        .skip(1)
+        .filter(|chunk| chunk.starts_with(char::is_whitespace))
+        .filter_map(|chunk| {
+            let tag = &chunk[..chunk.find('>')?];
+            let path = file_uri_to_path(&xml_unescape(xml_attr(tag, "href")?))?;
+            let modified = xml_attr(tag, "modified")
+                .or_else(|| xml_attr(tag, "added"))
+                .unwrap_or_default();
+            // RFC 3339 timestamps in UTC sort lexicographically.
+            Some((modified.to_string(), path))
+        })
+        .collect();
+    entries.sort_by(|a, b| b.0.cmp(&a.0));
+    entries.into_iter().map(|(_, p)| p).collect()
+}
+
+/// The folder or `.code-workspace` file recorded in a VS Code `workspace.json`.
+fn parse_vscode_workspace(json: &str) -> Option<PathBuf> {
+    let value: serde_json::Value = serde_json::from_str(json).ok()?;
+    let uri = value
+        .get("folder")
+        .or_else(|| value.get("workspace"))?
+        .as_str()?;
+    file_uri_to_path(uri)
+}
+
+/// Value of `name="..."` inside a start tag (attributes are whitespace-separated).
+fn xml_attr<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let needle = format!("{name}=\"");
+    let mut from = 0;
+    while let Some(pos) = tag[from..].find(&needle) {
+        let at = from + pos;
+        if tag[..at].ends_with(char::is_whitespace) {
+            let start = at + needle.len();
+            let len = tag[start..].find('"')?;
+            return Some(&tag[start..start + len]);
+        }
+        from = at + 1;
+    }
+    None
+}
+
+/// Decode the five predefined XML entities.
+fn xml_unescape(s: &str) -> String {
+    s.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Convert a `file://` URI to a local path, percent-decoding it.
+///
+/// Returns `None` for other schemes, non-local hosts, and invalid encodings.
+fn file_uri_to_path(uri: &str) -> Option<PathBuf> {
+    let rest = uri.strip_prefix("file://")?;
+    let path = rest.strip_prefix("localhost").unwrap_or(rest);
+    if !path.starts_with('/') {
+        return None;
+    }
+    let bytes = path.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = std::str::from_utf8(bytes.get(i + 1..i + 3)?).ok()?;
+            out.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    Some(PathBuf::from(String::from_utf8(out).ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const XBEL: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xbel version="1.0" xmlns:bookmark="http://www.freedesktop.org/standards/desktop-bookmarks">
+  <bookmark href="file:///home/u/old.txt" added="2026-01-01T10:00:00Z" modified="2026-01-01T10:00:00Z" visited="2026-01-01T10:00:00Z">
+    <info><metadata owner="http://freedesktop.org">
+      <bookmark:applications>
+        <bookmark:application name="gedit" exec="&apos;gedit %u&apos;" modified="2026-01-01T10:00:00Z" count="1"/>
+      </bookmark:applications>
+    </metadata></info>
+  </bookmark>
+  <bookmark href="file:///home/u/My%20Notes/a&amp;b.md" added="2026-03-01T10:00:00Z" modified="2026-03-02T10:00:00Z" visited="2026-03-02T10:00:00Z"/>
+  <bookmark href="sftp://host/remote.txt" added="2026-04-01T10:00:00Z" modified="2026-04-01T10:00:00Z" visited="2026-04-01T10:00:00Z"/>
+</xbel>"#;
+
+    #[test]
+    fn sources_are_opt_in() {
+        let mut config = core_config::LauncherConfig::default();
+        assert!(sources(&config).is_empty());
+
+        config.recent_files = true;
+        config.recent_projects = true;
+        let names: Vec<_> = sources(&config).iter().map(|s| s.name()).collect();
+        assert_eq!(names, ["recent", "project"]);
+    }
+
+    #[test]
+    fn xbel_local_files_newest_first() {
+        assert_eq!(
+            parse_xbel(XBEL),
+            vec![
+                PathBuf::from("/home/u/My Notes/a&b.md"),
+                PathBuf::from("/home/u/old.txt"),
+            ]
+        );
+    }
+
+    #[test]
+    fn file_uri_decoding() {
+        assert_eq!(
+            file_uri_to_path("file:///tmp/a%20b"),
+            Some(PathBuf::from("/tmp/a b"))
+        );
+        assert_eq!(
+            file_uri_to_path("file://localhost/tmp/x"),
+            Some(PathBuf::from("/tmp/x"))
+        );
+        assert_eq!(file_uri_to_path("file://otherhost/tmp/x"), None);
+        assert_eq!(file_uri_to_path("vscode-remote://ssh/x"), None);
+        assert_eq!(file_uri_to_path("file:///tmp/bad%2"), None);
+    }
+
+    #[test]
+    fn vscode_workspace_json() {
+        assert_eq!(
+            parse_vscode_workspace(r#"{"folder":"file:///home/u/src/open-sesame"}"#),
+            Some(PathBuf::from("/home/u/src/open-sesame"))
+        );
+        assert_eq!(
+            parse_vscode_workspace(r#"{"workspace":"file:///home/u/w.code-workspace"}"#),
+            Some(PathBuf::from("/home/u/w.code-workspace"))
+        );
+        assert_eq!(parse_vscode_workspace("{}"), None);
+    }
+
+    #[test]
+    fn recent_files_skip_missing_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let present = dir.path().join("present.txt");
+        std::fs::write(&present, "").unwrap();
+        let xbel = dir.path().join("recently-used.xbel");
+        std::fs::write(
+            &xbel,
+            format!(
+                r#"<xbel><bookmark href="file://{}" modified="2026-01-01T00:00:00Z"/><bookmark href="file:///nonexistent/gone.txt" modified="2026-02-01T00:00:00Z"/></xbel>"#,
+                present.display()
+            ),
+        )
+        .unwrap();
+
+        let items = RecentFiles { path: xbel }.collect();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].item.name, "present.txt");
+        assert_eq!(items[0].item.id, format!("recent:{}", present.display()));
+        assert_eq!(items[0].command[0], "xdg-open");
+    }
+
+    #[test]
+    fn editor_projects_from_storage() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = dir.path().join("proj");
+        std::fs::create_dir(&project).unwrap();
+        let storage = dir.path().join("Code/User/workspaceStorage");
+        for (hash, folder) in [("a1", project.as_path()), ("b2", Path::new("/nonexistent"))] {
+            std::fs::create_dir_all(storage.join(hash)).unwrap();
+            std::fs::write(
+                storage.join(hash).join("workspace.json"),
+                format!(r#"{{"folder":"file://{}"}}"#, folder.display()),
+            )
+            .unwrap();
+        }
+
+        let items = EditorProjects {
+            config_dir: dir.path().to_path_buf(),
+        }
+        .collect();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].item.name, "proj");
+        assert_eq!(
+            items[0].command,
+            vec!["code".to_string(), project.display().to_string()]
+        );
+    }
+}
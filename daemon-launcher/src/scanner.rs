@@ -15,6 +15,9 @@ use std::collections::HashSet;
 pub struct CachedEntry {
     pub id: String,
     pub exec: String,
+    /// Pre-split command from a non-desktop item source (recent files,
+    /// editor projects). When set, `exec` is display-only and not parsed.
+    pub argv: Option<Vec<String>>,
 }
 
 /// Scan all XDG desktop entry paths in a single pass, returning both fuzzy
@@ -48,6 +51,7 @@ pub fn scan_all() -> (Vec<MatchItem>, Vec<CachedEntry>) {
         cached.push(CachedEntry {
             id: id.clone(),
             exec,
+            argv: None,
         });
 
         let name = e
@@ -170,6 +170,16 @@ async fn main() -> anyhow::Result<()> {
         );
     }
 
+    // Under socket activation (open-sesame-profile.socket) systemd owns the
+    // listening socket and passes it in; otherwise bind it ourselves.
+    #[cfg(target_os = "linux")]
+    let bus = match platform_linux::systemd::listen_socket() {
+        Some(listener) => BusServer::from_listener(listener, bus_keypair.into_inner(), registry)
+            .context("failed to adopt socket-activated IPC bus listener")?,
+        None => BusServer::bind(&socket_path, bus_keypair.into_inner(), registry)
+            .context("failed to bind IPC bus server")?,
+    };
+    #[cfg(not(target_os = "linux"))]
     let bus = BusServer::bind(&socket_path, bus_keypair.into_inner(), registry)
         .context("failed to bind IPC bus server")?;
     tracing::info!(path = %socket_path.display(), "IPC bus server bound (Noise IK encrypted)");
@@ -0,0 +1,473 @@
+//! Overlay action registry: every key the overlay acts on, and what it does.
+//!
+//! [`ACTIONS`] is the one place a key is bound to a controller [`Event`].
+//! The overlay's keyboard handler and the IPC keysym mapping
+//! ([`crate::ipc_keys::map_ipc_key_to_event`]) both resolve keys through
+//! [`lookup`], the controller resolves characters typed on empty input
+//! through [`typed`], and the help overlay (`?`) and the row menu take
+//! their key names from here, so what they show is what the keys do.
+//! Hint keys come from `key_bindings` and are listed by [`help`] beside
+//! the registry.
+
+use crate::controller::Event;
+use core_config::WmKeyBinding;
+use core_types::{WindowPlacement, WindowToggle};
+use std::collections::BTreeMap;
+
+/// X11 keysyms (identical to GDK key values) of the keys the overlay uses.
+pub mod keysym {
+    pub const BACKSPACE: u32 = 0xFF08;
+    pub const TAB: u32 = 0xFF09;
+    pub const RETURN: u32 = 0xFF0D;
+    pub const ESCAPE: u32 = 0xFF1B;
+    pub const UP: u32 = 0xFF52;
+    pub const DOWN: u32 = 0xFF54;
+    pub const MENU: u32 = 0xFF67;
+    pub const KP_ENTER: u32 = 0xFF8D;
+    pub const F1: u32 = 0xFFBE;
+    pub const SPACE: u32 = 0x0020;
+    pub const UPPER_C: u32 = 0x0043;
+    pub const UPPER_W: u32 = 0x0057;
+    pub const LOWER_C: u32 = 0x0063;
+    pub const LOWER_F: u32 = 0x0066;
+    pub const LOWER_H: u32 = 0x0068;
+    pub const LOWER_L: u32 = 0x006C;
+    pub const LOWER_M: u32 = 0x006D;
+    pub const LOWER_O: u32 = 0x006F;
+    pub const LOWER_P: u32 = 0x0070;
+    pub const LOWER_R: u32 = 0x0072;
+    pub const LOWER_S: u32 = 0x0073;
+    pub const LOWER_T: u32 = 0x0074;
+    pub const LOWER_W: u32 = 0x0077;
+}
+
+/// What the compositor can do, deciding which actions are offered.
+#[derive(Debug, Clone, Copy, Default)]
+#[allow(clippy::struct_excessive_bools)] // Independent compositor capabilities
+pub struct Capabilities {
+    pub activate: bool,
+    pub maximize: bool,
+    pub tile: bool,
+    pub stick: bool,
+    pub keep_above: bool,
+    pub move_to_output: bool,
+}
+
+/// The capability an action needs; without it the help overlay and row
+/// menu leave the action out (the key still answers with a notice).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Needs {
+    Nothing,
+    Activate,
+    Maximize,
+    Tile,
+    Stick,
+    KeepAbove,
+    MoveToOutput,
+}
+
+impl Needs {
+    #[must_use]
+    pub fn met(self, caps: Capabilities) -> bool {
+        match self {
+            Self::Nothing => true,
+            Self::Activate => caps.activate,
+            Self::Maximize => caps.maximize,
+            Self::Tile => caps.tile,
+            Self::Stick => caps.stick,
+            Self::KeepAbove => caps.keep_above,
+            Self::MoveToOutput => caps.move_to_output,
+        }
+    }
+}
+
+/// A key bound to a controller event.
+#[derive(Debug)]
+pub struct Action {
+    /// Keysyms that trigger it; the first is the one the overlay and IPC
+    /// copies of a press are deduplicated by.
+    pub keysyms: &'static [u32],
+    /// Control must be held.
+    pub ctrl: bool,
+    /// Shift must be held (for keys whose keysym does not change with it).
+    pub shift: bool,
+    /// A character that triggers it when typed with nothing else typed.
+    pub typed: Option<char>,
+    /// How the keys are written in the help overlay and row menu.
+    pub keys: &'static str,
+    pub description: &'static str,
+    pub event: Event,
+    pub needs: Needs,
+}
+
+impl Action {
+    /// `keys` sends `event`, needing no modifier or capability.
+    const fn new(
+        keysyms: &'static [u32],
+        keys: &'static str,
+        description: &'static str,
+        event: Event,
+    ) -> Self {
+        Self {
+            keysyms,
+            ctrl: false,
+            shift: false,
+            typed: None,
+            keys,
+            description,
+            event,
+            needs: Needs::Nothing,
+        }
+    }
+
+    const fn ctrl(mut self) -> Self {
+        self.ctrl = true;
+        self
+    }
+
+    const fn shift(mut self) -> Self {
+        self.shift = true;
+        self
+    }
+
+    const fn typed(mut self, ch: char) -> Self {
+        self.typed = Some(ch);
+        self
+    }
+
+    const fn needs(mut self, needs: Needs) -> Self {
+        self.needs = needs;
+        self
+    }
+}
+
+const ENTER: &[u32] = &[keysym::RETURN, keysym::KP_ENTER];
+
+/// Every overlay key, in help order. Earlier entries win: Ctrl+Enter and
+/// Shift+Enter are matched before Enter.
+pub static ACTIONS: &[Action] = &[
+    Action::new(&[keysym::DOWN], "Down", "Next row", Event::SelectionDown),
+    Action::new(&[keysym::UP], "Up", "Previous row", Event::SelectionUp),
+    Action::new(
+        ENTER,
+        "Ctrl+Enter",
+        "Switch and keep the picker open",
+        Event::KeepOpen,
+    )
+    .ctrl()
+    .needs(Needs::Activate),
+    Action::new(
+        ENTER,
+        "Shift+Enter",
+        "Launch a new instance",
+        Event::LaunchNew,
+    )
+    .shift(),
+    Action::new(ENTER, "Enter", "Switch to the selected row", Event::Confirm),
+    Action::new(
+        &[keysym::ESCAPE],
+        "Escape",
+        "Close the overlay",
+        Event::Escape,
+    ),
+    Action::new(
+        &[keysym::BACKSPACE],
+        "Backspace",
+        "Delete the last typed character",
+        Event::Backspace,
+    ),
+    Action::new(
+        &[keysym::LOWER_W],
+        "Ctrl+W",
+        "Close the window",
+        Event::CloseWindow,
+    )
+    .ctrl(),
+    Action::new(
+        &[keysym::UPPER_W],
+        "Ctrl+Shift+W",
+        "Close every window of the app",
+        Event::CloseAppWindows,
+    )
+    .ctrl(),
+    Action::new(
+        &[keysym::LOWER_P],
+        "Ctrl+P",
+        "Peek at the window",
+        Event::Peek,
+    )
+    .ctrl(),
+    Action::new(
+        &[keysym::LOWER_H],
+        "Ctrl+H",
+        "Tile left",
+        Event::Place(WindowPlacement::LeftHalf),
+    )
+    .ctrl()
+    .needs(Needs::Tile),
+    Action::new(
+        &[keysym::LOWER_L],
+        "Ctrl+L",
+        "Tile right",
+        Event::Place(WindowPlacement::RightHalf),
+    )
+    .ctrl()
+    .needs(Needs::Tile),
+    Action::new(
+        &[keysym::LOWER_F],
+        "Ctrl+F",
+        "Maximize",
+        Event::Place(WindowPlacement::Maximized),
+    )
+    .ctrl()
+    .needs(Needs::Maximize),
+    Action::new(
+        &[keysym::LOWER_S],
+        "Ctrl+S",
+        "Pin to all workspaces, or unpin",
+        Event::Toggle(WindowToggle::Sticky),
+    )
+    .ctrl()
+    .needs(Needs::Stick),
+    Action::new(
+        &[keysym::LOWER_T],
+        "Ctrl+T",
+        "Keep above others, or stop",
+        Event::Toggle(WindowToggle::KeepAbove),
+    )
+    .ctrl()
+    .needs(Needs::KeepAbove),
+    Action::new(
+        &[keysym::LOWER_O],
+        "Ctrl+O",
+        "Move to the output numbered next",
+        Event::MoveToOutput,
+    )
+    .ctrl()
+    .needs(Needs::MoveToOutput),
+    Action::new(
+        &[keysym::LOWER_M],
+        "Ctrl+M",
+        "Mark with the letter typed next",
+        Event::Mark,
+    )
+    .ctrl(),
+    Action::new(
+        &[keysym::LOWER_C],
+        "Ctrl+C",
+        "Copy the title",
+        Event::CopyTitle,
+    )
+    .ctrl(),
+    Action::new(
+        &[keysym::UPPER_C],
+        "Ctrl+Shift+C",
+        "Copy the app ID",
+        Event::CopyAppId,
+    )
+    .ctrl(),
+    Action::new(
+        &[keysym::LOWER_R],
+        "Ctrl+R",
+        "Switch to the focus timeline and back",
+        Event::Timeline,
+    )
+    .ctrl(),
+    Action::new(
+        &[keysym::MENU],
+        "Menu, .",
+        "Open the row menu",
+        Event::RowMenu,
+    )
+    .typed('.'),
+    Action::new(&[keysym::F1], "F1, ?", "Show this help", Event::Help).typed('?'),
+];
+
+/// The action for `keysym` with Control and Shift held as given.
+#[must_use]
+pub fn lookup(keysym: u32, ctrl: bool, shift: bool) -> Option<&'static Action> {
+    ACTIONS
+        .iter()
+        .find(|a| a.keysyms.contains(&keysym) && (ctrl || !a.ctrl) && (shift || !a.shift))
+}
+
+/// The action for `ch` typed on empty input.
+#[must_use]
+pub fn typed(ch: char) -> Option<&'static Action> {
+    ACTIONS.iter().find(|a| a.typed == Some(ch))
+}
+
+/// Whether `a` and `b` are the same action (the same variant, and for
+/// placements and toggles the same payload).
+fn same(a: &Event, b: &Event) -> bool {
+    match (a, b) {
+        (Event::Place(a), Event::Place(b)) => a == b,
+        (Event::Toggle(a), Event::Toggle(b)) => a == b,
+        _ => std::mem::discriminant(a) == std::mem::discriminant(b),
+    }
+}
+
+/// The keys bound to `event`, as written in the help overlay.
+#[must_use]
+pub fn keys_for(event: &Event) -> Option<&'static str> {
+    ACTIONS
+        .iter()
+        .find(|a| same(&a.event, event))
+        .map(|a| a.keys)
+}
+
+/// A titled group of help lines.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HelpSection {
+    pub title: String,
+    /// Keys and what they do.
+    pub entries: Vec<(String, String)>,
+}
+
+/// The help overlay as drawn.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HelpView {
+    pub sections: Vec<HelpSection>,
+}
+
+/// The help overlay: the actions the compositor supports, the input
+/// prefixes, and the hint keys of `key_bindings`.
+#[must_use]
+pub fn help(
+    caps: Capabilities,
+    command_palette: bool,
+    key_bindings: &BTreeMap<String, WmKeyBinding>,
+) -> HelpView {
+    let line = |keys: &str, what: &str| (keys.to_string(), what.to_string());
+    let mut sections = vec![HelpSection {
+        title: "Keys".into(),
+        entries: ACTIONS
+            .iter()
+            .filter(|a| a.needs.met(caps))
+            .map(|a| line(a.keys, a.description))
+            .collect(),
+    }];
+
+    let mut typing = vec![
+        line("a–z", "Type a hint"),
+        line(
+            &crate::search::SEARCH_PREFIX.to_string(),
+            "Search titles and app names",
+        ),
+    ];
+    if command_palette {
+        typing.push(line(&crate::palette::CALC_PREFIX.to_string(), "Calculate"));
+        typing.push(line(
+            &crate::palette::SHELL_PREFIX.to_string(),
+            "Run a shell command",
+        ));
+    }
+    sections.push(HelpSection {
+        title: "Typing".into(),
+        entries: typing,
+    });
+
+    let names = crate::app_names::current();
+    let bindings: Vec<(String, String)> = key_bindings
+        .iter()
+        .map(|(key, binding)| (key.clone(), binding_description(binding, &names)))
+        .collect();
+    if !bindings.is_empty() {
+        sections.push(HelpSection {
+            title: "Key bindings".into(),
+            entries: bindings,
+        });
+    }
+    HelpView { sections }
+}
+
+/// What a `key_bindings` entry does, for the help overlay.
+fn binding_description(binding: &WmKeyBinding, names: &crate::app_names::AppNames) -> String {
+    if let Some(command) = &binding.command {
+        return format!("Run {command}");
+    }
+    let app = binding.label.clone().or_else(|| {
+        binding
+            .apps
+            .first()
+            .map(|app| names.name_for(app).unwrap_or_else(|| app.clone()))
+    });
+    match (app, &binding.launch) {
+        (Some(app), Some(_)) => format!("{app}, launched if not open"),
+        (Some(app), None) => app,
+        (None, Some(launch)) => format!("Launch {launch}"),
+        (None, None) => "Unused".into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn modified_keys_win_over_the_plain_key() {
+        let event = |keysym, ctrl, shift| lookup(keysym, ctrl, shift).map(|a| &a.event);
+        assert!(matches!(
+            event(keysym::RETURN, true, true),
+            Some(Event::KeepOpen)
+        ));
+        assert!(matches!(
+            event(keysym::KP_ENTER, false, true),
+            Some(Event::LaunchNew)
+        ));
+        assert!(matches!(
+            event(keysym::RETURN, false, false),
+            Some(Event::Confirm)
+        ));
+        assert!(event(keysym::LOWER_W, false, false).is_none());
+        assert!(matches!(typed('?').map(|a| &a.event), Some(Event::Help)));
+        assert!(typed('a').is_none());
+    }
+
+    #[test]
+    fn every_key_is_bound_once() {
+        for (i, a) in ACTIONS.iter().enumerate() {
+            for b in &ACTIONS[i + 1..] {
+                let overlap = a.keysyms.iter().any(|k| b.keysyms.contains(k));
+                assert!(
+                    !overlap || a.ctrl != b.ctrl || a.shift != b.shift,
+                    "{} and {} share a key",
+                    a.keys,
+                    b.keys
+                );
+                assert!(a.typed.is_none() || a.typed != b.typed);
+            }
+        }
+    }
+
+    #[test]
+    fn help_leaves_out_what_the_compositor_cannot_do() {
+        let mut bindings = BTreeMap::new();
+        bindings.insert(
+            "f".to_string(),
+            WmKeyBinding {
+                apps: vec!["firefox".into()],
+                launch: Some("firefox".into()),
+                label: Some("Firefox".into()),
+                ..Default::default()
+            },
+        );
+        let view = help(Capabilities::default(), false, &bindings);
+        let keys: Vec<&str> = view.sections[0]
+            .entries
+            .iter()
+            .map(|(k, _)| k.as_str())
+            .collect();
+        assert!(keys.contains(&"Ctrl+W") && !keys.contains(&"Ctrl+F"));
+        assert!(!keys.contains(&"Ctrl+Enter"));
+        assert_eq!(view.sections[1].entries.len(), 2, "no palette prefixes");
+        assert_eq!(
+            view.sections[2].entries,
+            [("f".to_string(), "Firefox, launched if not open".to_string())]
+        );
+        assert_eq!(
+            keys_for(&Event::Place(WindowPlacement::Maximized)),
+            Some("Ctrl+F")
+        );
+    }
+}
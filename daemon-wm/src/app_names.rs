@@ -0,0 +1,205 @@
+//! App display names from desktop entries.
+//!
+//! The overlay shows `org.gnome.Nautilus` as "Files", like the rest of the
+//! desktop: the localized `Name=` of the matching `.desktop` file in the XDG
+//! application directories. Entries are read with `freedesktop-desktop-entry`
+//! and filtered as the launcher's scanner does, so locales, escapes and
+//! `NoDisplay`/`Hidden` agree with the launcher. Apps without a desktop entry
+//! keep the name derived from the app ID (`render::extract_app_name`).
+//!
+//! The index of desktop entries is built at daemon startup ([`warm`]).
+//! Each snapshot takes it with [`current`], which stats the application
+//! directories once and rebuilds the index if installing or removing an app
+//! changed a directory's modification time.
+
+use freedesktop_desktop_entry::{DesktopEntry, Iter};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+/// Desktop entries found in one pass over the application directories.
+#[derive(Debug)]
+pub struct AppNames {
+    /// Displayed entries, highest-priority directory first.
+    entries: Vec<DesktopEntry>,
+    /// `StartupWMClass=` values, lowercased, to the entry that sets them.
+    wm_classes: HashMap<String, usize>,
+    locales: Vec<String>,
+}
+
+impl AppNames {
+    /// The desktop entry name for `app_id`, if an entry matches it.
+    #[must_use]
+    pub fn name_for(&self, app_id: &str) -> Option<String> {
+        self.entry_for(app_id)?
+            .name(&self.locales)
+            .map(std::borrow::Cow::into_owned)
+    }
+
+    /// The entry for `app_id`. Tries, in order: the exact ID, the ID
+    /// ignoring case, the last reverse-DNS segment of the ID (`firefox` →
+    /// `org.mozilla.firefox.desktop`), then `StartupWMClass=`.
+    fn entry_for(&self, app_id: &str) -> Option<&DesktopEntry> {
+        let lower = app_id.to_lowercase();
+        let segment = |id: &str| id.rsplit('.').next().unwrap_or(id).to_lowercase();
+        self.entries
+            .iter()
+            .find(|e| e.id() == app_id)
+            .or_else(|| self.entries.iter().find(|e| e.id().to_lowercase() == lower))
+            .or_else(|| self.entries.iter().find(|e| segment(e.id()) == lower))
+            .or_else(|| {
+                let i = *self.wm_classes.get(&app_id.to_ascii_lowercase())?;
+                self.entries.get(i)
+            })
+    }
+}
+
+/// The index and the directory modification times it was built from.
+static INDEX: Mutex<Option<(Vec<Option<SystemTime>>, Arc<AppNames>)>> = Mutex::new(None);
+
+/// Build the index ahead of the first activation.
+pub fn warm() {
+    let names = current();
+    tracing::debug!(
+        entries = names.entries.len(),
+        "app names: indexed desktop entries"
+    );
+}
+
+/// The index, rebuilt first if an application directory changed since it
+/// was built. Take it once per snapshot; every lookup after that is in
+/// memory.
+#[must_use]
+pub fn current() -> Arc<AppNames> {
+    let dirs = core_config::application_dirs();
+    let stamps: Vec<Option<SystemTime>> = dirs
+        .iter()
+        .map(|dir| std::fs::metadata(dir).and_then(|m| m.modified()).ok())
+        .collect();
+    let mut guard = INDEX
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    match guard.as_ref() {
+        Some((built_from, names)) if *built_from == stamps => Arc::clone(names),
+        _ => {
+            let locales = freedesktop_desktop_entry::get_languages_from_env();
+            let names = Arc::new(index(dirs, locales));
+            *guard = Some((stamps, Arc::clone(&names)));
+            names
+        }
+    }
+}
+
+/// Read the desktop entries under `dirs`. Like the launcher's scanner, it
+/// skips `NoDisplay=true` and `Hidden=true` entries and keeps the first
+/// entry for each ID, so an earlier directory shadows later ones. The
+/// first entry to claim a `StartupWMClass=` keeps it.
+fn index(dirs: Vec<PathBuf>, locales: Vec<String>) -> AppNames {
+    let mut seen = HashSet::new();
+    let entries: Vec<DesktopEntry> = Iter::new(dirs.into_iter())
+        .filter_map(|path| DesktopEntry::from_path(path, Some(locales.as_slice())).ok())
+        .filter(|e| !e.no_display() && !e.hidden() && seen.insert(e.id().to_string()))
+        .collect();
+    let mut wm_classes = HashMap::new();
+    for (i, entry) in entries.iter().enumerate() {
+        if let Some(class) = entry.startup_wm_class() {
+            wm_classes.entry(class.to_ascii_lowercase()).or_insert(i);
+        }
+    }
+    AppNames {
+        entries,
+        wm_classes,
+        locales,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    const NAUTILUS: &str = "[Desktop Entry]\n\
+        Type=Application\n\
+        Name=Files\n\
+        Name[de]=Dateien\n\
+        Name[pt_BR]=Arquivos\n\
+        StartupWMClass=Nautilus\n\
+        \n\
+        [Desktop Action new-window]\n\
+        Name=New Window\n";
+
+    fn write(dir: &Path, file: &str, contents: &str) {
+        let path = dir.join(file);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    }
+
+    #[test]
+    fn name_is_localized_and_unescaped() {
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path(), "org.gnome.Nautilus.desktop", NAUTILUS);
+        write(
+            dir.path(),
+            "org.gnome.TextEditor.desktop",
+            "[Desktop Entry]\nType=Application\nName=Text\\sEditor\n",
+        );
+        let dirs = vec![dir.path().to_path_buf()];
+        let names = index(dirs.clone(), vec!["pt_BR".into(), "pt".into()]);
+        assert_eq!(
+            names.name_for("org.gnome.Nautilus").as_deref(),
+            Some("Arquivos")
+        );
+        let names = index(dirs, Vec::new());
+        assert_eq!(
+            names.name_for("org.gnome.Nautilus").as_deref(),
+            Some("Files")
+        );
+        assert_eq!(
+            names.name_for("org.gnome.TextEditor").as_deref(),
+            Some("Text Editor")
+        );
+    }
+
+    #[test]
+    fn lookup_matches_id_segment_and_wm_class() {
+        let home = tempfile::tempdir().unwrap();
+        let system = tempfile::tempdir().unwrap();
+        write(system.path(), "org.gnome.Nautilus.desktop", NAUTILUS);
+        write(
+            system.path(),
+            "org.mozilla.firefox.desktop",
+            "[Desktop Entry]\nType=Application\nName=Firefox Web Browser\n",
+        );
+        write(
+            system.path(),
+            "vendor/code.desktop",
+            "[Desktop Entry]\nType=Application\nName=Visual Studio Code\nStartupWMClass=Code\n",
+        );
+        write(
+            system.path(),
+            "org.example.Helper.desktop",
+            "[Desktop Entry]\nType=Application\nName=Helper\nNoDisplay=true\n",
+        );
+        // The user's copy shadows the system one.
+        write(
+            home.path(),
+            "org.mozilla.firefox.desktop",
+            "[Desktop Entry]\nType=Application\nName=Firefox\n",
+        );
+        let names = index(
+            vec![home.path().to_path_buf(), system.path().to_path_buf()],
+            Vec::new(),
+        );
+
+        let name = |app_id: &str| names.name_for(app_id);
+        assert_eq!(name("org.gnome.Nautilus").as_deref(), Some("Files"));
+        assert_eq!(name("org.gnome.nautilus").as_deref(), Some("Files"));
+        assert_eq!(name("nautilus").as_deref(), Some("Files"));
+        assert_eq!(name("firefox").as_deref(), Some("Firefox"));
+        assert_eq!(name("Code").as_deref(), Some("Visual Studio Code"));
+        // Hidden from menus, so hidden from the overlay too.
+        assert_eq!(name("org.example.Helper"), None);
+        assert_eq!(name("ghostty"), None);
+    }
+}
@@ -0,0 +1,130 @@
+//! Status published to panel applets (`platform_linux::applet`).
+//!
+//! The window count is set by the window-list poll and the last activation
+//! wherever a switch goes through; the applet server task subscribes.
+
+use platform_linux::applet::{AppletStatus, LastActivation, SwitchedWindow, WindowSwitch};
+use std::sync::LazyLock;
+use tokio::sync::{broadcast, watch};
+
+/// Switch events buffered for a slow applet server before it lags.
+const SWITCH_BACKLOG: usize = 16;
+
+static STATUS: LazyLock<watch::Sender<AppletStatus>> =
+    LazyLock::new(|| watch::channel(AppletStatus::default()).0);
+
+static SWITCHES: LazyLock<broadcast::Sender<WindowSwitch>> =
+    LazyLock::new(|| broadcast::channel(SWITCH_BACKLOG).0);
+
+/// Receiver for the applet server.
+#[must_use]
+pub fn subscribe() -> watch::Receiver<AppletStatus> {
+    STATUS.subscribe()
+}
+
+/// Switch events for the applet server, from now on.
+#[must_use]
+pub fn subscribe_switches() -> broadcast::Receiver<WindowSwitch> {
+    SWITCHES.subscribe()
+}
+
+/// The switcher now lists `count` windows.
+pub fn set_window_count(count: usize) {
+    let count = u32::try_from(count).unwrap_or(u32::MAX);
+    STATUS.send_if_modified(|status| {
+        let changed = status.window_count != count;
+        status.window_count = count;
+        changed
+    });
+}
+
+/// The user switched from `origin` (the window focused until now) to
+/// `target` through sesame.
+pub fn record_switch(origin: Option<&core_types::Window>, target: &core_types::Window) {
+    let at_ms = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| u64::try_from(d.as_millis()).unwrap_or(u64::MAX));
+    STATUS.send_modify(|status| {
+        status.last_activation = Some(LastActivation {
+            app_id: target.app_id.to_string(),
+            title: target.title.clone(),
+            at_ms,
+        });
+    });
+    // No receivers (interface off or not yet serving) is not an error.
+    let _ = SWITCHES.send(WindowSwitch {
+        origin: origin.map(switched_window),
+        target: switched_window(target),
+        at_ms,
+    });
+}
+
+/// The focused window in `windows` other than `target`, the origin of a
+/// switch to `target`.
+#[must_use]
+pub fn origin_of<'a>(
+    windows: &'a [core_types::Window],
+    target: &core_types::Window,
+) -> Option<&'a core_types::Window> {
+    windows.iter().find(|w| w.is_focused && w.id != target.id)
+}
+
+fn switched_window(window: &core_types::Window) -> SwitchedWindow {
+    SwitchedWindow {
+        id: window.id.to_string(),
+        app_id: window.app_id.to_string(),
+        title: window.title.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::WindowBuilder;
+
+    // The status and switch channels are process-wide, so everything that
+    // writes them stays in this one test.
+    #[test]
+    fn status_and_switches() {
+        let mut rx = subscribe();
+        set_window_count(3);
+        assert!(rx.has_changed().unwrap());
+        rx.borrow_and_update();
+        set_window_count(3);
+        assert!(
+            !rx.has_changed().unwrap(),
+            "unchanged count must not notify"
+        );
+
+        let windows = [
+            WindowBuilder::new("firefox")
+                .title("Docs")
+                .focused()
+                .build(),
+            WindowBuilder::new("kitty").title("shell").build(),
+        ];
+        let mut switches = subscribe_switches();
+        record_switch(origin_of(&windows, &windows[1]), &windows[1]);
+        let switch = switches.try_recv().unwrap();
+        let origin = switch.origin.unwrap();
+        assert_eq!(
+            (origin.app_id.as_str(), origin.title.as_str()),
+            ("firefox", "Docs")
+        );
+        assert_eq!(switch.target.id, windows[1].id.to_string());
+        assert_eq!(
+            rx.borrow_and_update()
+                .last_activation
+                .as_ref()
+                .unwrap()
+                .title,
+            "shell"
+        );
+    }
+
+    #[test]
+    fn focused_target_is_not_its_own_origin() {
+        let target = WindowBuilder::new("kitty").focused().build();
+        assert!(origin_of(std::slice::from_ref(&target), &target).is_none());
+    }
+}
@@ -11,6 +11,73 @@ use core_types::{EventKind, SecurityLevel};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
+/// How long [`hide_and_sync`] waits for the overlay to unmap.
+const HIDE_SYNC_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Draw `cmd` on the overlay, if it is a command that only changes what
+/// the overlay shows. Returns whether it was.
+///
+/// The embedded [`Session`](crate::session::Session) draws through this
+/// too, so a new overlay command only needs adding here.
+pub fn draw(cmd: Command, overlay_cmd_tx: &std::sync::mpsc::Sender<OverlayCmd>) -> bool {
+    let overlay_cmd = match cmd {
+        Command::ShowPicker { windows, hints } => OverlayCmd::ShowFull { windows, hints },
+        Command::ShowRetry {
+            windows,
+            hints,
+            message,
+        } => OverlayCmd::ShowRetry {
+            windows,
+            hints,
+            message,
+        },
+        Command::UpdatePicker { input, selection } => OverlayCmd::UpdateInput { input, selection },
+        Command::UpdateRows { windows } => OverlayCmd::UpdateRows { windows },
+        Command::SetRowMenu { menu } => OverlayCmd::SetRowMenu(menu),
+        Command::SetHelp { help } => OverlayCmd::SetHelp(help),
+        Command::SetWalkthrough { page } => OverlayCmd::SetWalkthrough(page),
+        Command::ShowLaunching => OverlayCmd::ShowLaunching,
+        Command::ShowLaunchStaged { command, instances } => {
+            OverlayCmd::ShowLaunchStaged { command, instances }
+        }
+        Command::ShowPalette { message } => OverlayCmd::ShowPalette { message },
+        Command::ShowLaunchError { message, .. } => OverlayCmd::ShowLaunchError { message },
+        Command::ResetGrace => OverlayCmd::ResetGrace,
+        _ => return false,
+    };
+    if overlay_cmd_tx.send(overlay_cmd).is_err() {
+        tracing::error!("overlay thread has exited unexpectedly");
+    }
+    true
+}
+
+/// Hide the overlay and wait until its surface has unmapped, so the
+/// compositor gives focus back before a window is activated.
+pub async fn hide_and_sync(
+    overlay_cmd_tx: &std::sync::mpsc::Sender<OverlayCmd>,
+    overlay_event_rx: &mut tokio::sync::mpsc::Receiver<OverlayEvent>,
+) {
+    if overlay_cmd_tx.send(OverlayCmd::HideAndSync).is_err() {
+        tracing::error!("overlay thread has exited unexpectedly");
+        return;
+    }
+    let sync_deadline = tokio::time::timeout(HIDE_SYNC_TIMEOUT, async {
+        while let Some(ev) = overlay_event_rx.recv().await {
+            if matches!(ev, OverlayEvent::SurfaceUnmapped) {
+                return;
+            }
+        }
+    });
+    if sync_deadline.await.is_err() {
+        tracing::error!("timed out waiting for SurfaceUnmapped from overlay thread");
+        // Recovery: force-hide the overlay. Even if the overlay thread is
+        // stuck, this queues a Hide command that will be processed when
+        // the thread resumes. If the thread has died, the send fails
+        // silently and the channel is already disconnected.
+        let _ = overlay_cmd_tx.send(OverlayCmd::Hide);
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 pub async fn execute_commands(
     commands: Vec<Command>,
@@ -31,10 +98,16 @@ pub async fn execute_commands(
         match cmd {
             Command::ShowBorder {
                 zero_window_launcher,
+                delay_indicator_ms,
             } => {
                 // Reset IPC keyboard confirmation for new activation cycle.
                 *ipc_keyboard_confirmed = false;
-                if overlay_cmd_tx.send(OverlayCmd::ShowBorder).is_err() {
+                let delay_indicator =
+                    delay_indicator_ms.map(|ms| std::time::Duration::from_millis(u64::from(ms)));
+                if overlay_cmd_tx
+                    .send(OverlayCmd::ShowBorder { delay_indicator })
+                    .is_err()
+                {
                     tracing::error!("overlay thread has exited unexpectedly");
                 }
                 if zero_window_launcher {
@@ -52,21 +125,57 @@ pub async fn execute_commands(
                     .await
                     .ok();
             }
-            Command::ShowPicker { windows, hints } => {
-                if overlay_cmd_tx
-                    .send(OverlayCmd::ShowFull { windows, hints })
-                    .is_err()
-                {
-                    tracing::error!("overlay thread has exited unexpectedly");
-                }
+            Command::FinishWalkthrough => {
+                tracing::info!("first-run walkthrough finished");
+                crate::onboarding::mark_done();
             }
-            Command::UpdatePicker { input, selection } => {
-                if overlay_cmd_tx
-                    .send(OverlayCmd::UpdateInput { input, selection })
-                    .is_err()
-                {
-                    tracing::error!("overlay thread has exited unexpectedly");
+            Command::EnsureWindowAlive { window_id } => {
+                // The cached list can lag the compositor by a poll interval;
+                // re-enumerate so a window closed since the snapshot is caught
+                // while the overlay is still up to offer another choice.
+                #[cfg(target_os = "linux")]
+                if let Some(live) = backend {
+                    match live.list_windows().await {
+                        Ok(fresh) if !fresh.iter().any(|w| w.id == window_id) => {
+                            tracing::warn!(target = %window_id, "selected window closed before activation");
+                            *windows.lock().await = fresh;
+                            let win_list = windows.lock().await;
+                            let cfg = wm_config.lock().await;
+                            let retry_cmds = controller.handle(
+                                Event::ActivationFailed {
+                                    window_id,
+                                    error: None,
+                                },
+                                &win_list,
+                                &cfg,
+                            );
+                            drop(cfg);
+                            drop(win_list);
+                            Box::pin(execute_commands(
+                                retry_cmds,
+                                overlay_cmd_tx,
+                                overlay_event_rx,
+                                backend,
+                                client,
+                                config_state,
+                                controller,
+                                windows,
+                                wm_config,
+                                ipc_keyboard_confirmed,
+                                password_buffer,
+                            ))
+                            .await;
+                            // Drop the remaining hide/activate commands.
+                            return;
+                        }
+                        Ok(_) => {}
+                        Err(e) => {
+                            tracing::debug!(error = %e, "window re-enumeration failed, activating anyway");
+                        }
+                    }
                 }
+                #[cfg(not(target_os = "linux"))]
+                let _ = window_id;
             }
             Command::HideAndSync => {
                 // Release keyboard grab BEFORE hiding — daemon-input stops forwarding.
@@ -80,26 +189,7 @@ pub async fn execute_commands(
                     .await
                     .ok();
 
-                if overlay_cmd_tx.send(OverlayCmd::HideAndSync).is_err() {
-                    tracing::error!("overlay thread has exited unexpectedly");
-                    continue;
-                }
-                let sync_deadline =
-                    tokio::time::timeout(std::time::Duration::from_secs(5), async {
-                        while let Some(ev) = overlay_event_rx.recv().await {
-                            if matches!(ev, OverlayEvent::SurfaceUnmapped) {
-                                return;
-                            }
-                        }
-                    });
-                if sync_deadline.await.is_err() {
-                    tracing::error!("timed out waiting for SurfaceUnmapped from overlay thread");
-                    // Recovery: force-hide the overlay. Even if the overlay thread is
-                    // stuck, this queues a Hide command that will be processed when
-                    // the thread resumes. If the thread has died, the send fails
-                    // silently and the channel is already disconnected.
-                    let _ = overlay_cmd_tx.send(OverlayCmd::Hide);
-                }
+                hide_and_sync(overlay_cmd_tx, overlay_event_rx).await;
             }
             Command::Hide => {
                 client
@@ -116,29 +206,98 @@ pub async fn execute_commands(
                     tracing::error!("overlay thread has exited unexpectedly");
                 }
             }
-            Command::ActivateWindow { window, .. } => {
+            Command::ActivateWindow {
+                window,
+                multiplexer,
+            } => {
                 let target_id = window.id.to_string();
-                crate::mru::save(&target_id);
+                #[cfg(all(target_os = "linux", feature = "dbus"))]
+                let origin = crate::applet::origin_of(&windows.lock().await, &window).cloned();
 
                 #[cfg(target_os = "linux")]
-                let activate_ok = if let Some(backend) = backend {
-                    match backend.activate_window(&window.id).await {
-                        Ok(()) => true,
+                if let Some(live) = backend
+                    && let Err(e) = live.activate_window(&window.id).await
+                {
+                    tracing::warn!(error = %e, target = %target_id, "compositor activate_window failed");
+                    let message = format!("Could not switch to {}: {e}", window.app_id);
+                    if !controller.is_idle() {
+                        // Kept-open picker: it is still up to show the failure.
+                        let _ = overlay_cmd_tx.send(OverlayCmd::Toast { message });
+                        continue;
+                    }
+                    // The overlay is already down: take the keyboard back and
+                    // re-show the picker so the user can retry or pick another.
+                    client
+                        .publish(
+                            EventKind::InputGrabRequest {
+                                requester: client.daemon_id(),
+                            },
+                            SecurityLevel::Internal,
+                        )
+                        .await
+                        .ok();
+                    let win_list = windows.lock().await;
+                    let cfg = wm_config.lock().await;
+                    let retry_cmds = controller.handle(
+                        Event::ActivationFailed {
+                            window_id: window.id,
+                            error: Some(message),
+                        },
+                        &win_list,
+                        &cfg,
+                    );
+                    drop(cfg);
+                    drop(win_list);
+                    Box::pin(execute_commands(
+                        retry_cmds,
+                        overlay_cmd_tx,
+                        overlay_event_rx,
+                        backend,
+                        client,
+                        config_state,
+                        controller,
+                        windows,
+                        wm_config,
+                        ipc_keyboard_confirmed,
+                        password_buffer,
+                    ))
+                    .await;
+                    // Drop the usage record and dismissal that follow.
+                    return;
+                }
+
+                crate::mru::save(&target_id);
+                tracing::info!(target = %target_id, app_id = %window.app_id, "window activated via overlay");
+                #[cfg(all(target_os = "linux", feature = "dbus"))]
+                crate::applet::record_switch(origin.as_ref(), &window);
+                crate::focus_time::switched(
+                    window.app_id.as_str(),
+                    wm_config.lock().await.focus_time,
+                );
+                if let Some(target) = multiplexer {
+                    crate::multiplexer::select(client, target).await;
+                }
+            }
+            Command::ActivateWorkspace { workspace } => {
+                #[cfg(target_os = "linux")]
+                if let Some(backend) = backend {
+                    match backend.activate_workspace(&workspace.id).await {
+                        Ok(()) => {
+                            tracing::info!(workspace = %workspace.name, "workspace activated via overlay");
+                        }
                         Err(e) => {
-                            tracing::warn!(error = %e, target = %target_id, "compositor activate_window failed");
-                            false
+                            tracing::warn!(workspace = %workspace.name, error = %e, "activate_workspace failed");
+                            let _ = overlay_cmd_tx.send(OverlayCmd::Toast {
+                                message: format!(
+                                    "Could not switch to workspace {}: {e}",
+                                    workspace.name
+                                ),
+                            });
                         }
                     }
-                } else {
-                    true
-                };
-
-                #[cfg(not(target_os = "linux"))]
-                let activate_ok = true;
-
-                if activate_ok {
-                    tracing::info!(target = %target_id, app_id = %window.app_id, "window activated via overlay");
                 }
+                #[cfg(not(target_os = "linux"))]
+                let _ = workspace;
             }
             Command::LaunchApp {
                 command,
@@ -259,35 +418,243 @@ pub async fn execute_commands(
                 ))
                 .await;
             }
-            Command::ShowLaunchStaged { command } => {
-                if overlay_cmd_tx
-                    .send(OverlayCmd::ShowLaunchStaged { command })
-                    .is_err()
+            Command::RunShellCommand { command } => {
+                tracing::info!(%command, "running shell command");
+
+                client
+                    .publish(
+                        EventKind::InputGrabRelease {
+                            requester: client.daemon_id(),
+                        },
+                        SecurityLevel::Internal,
+                    )
+                    .await
+                    .ok();
+
+                let active_profile = {
+                    let cfg_guard = config_state.read().ok();
+                    cfg_guard.and_then(|c| {
+                        core_types::TrustProfileName::try_from(c.global.default_profile.as_ref())
+                            .ok()
+                    })
+                };
+                let result = client
+                    .request(
+                        EventKind::LaunchShellCommand {
+                            command,
+                            profile: active_profile,
+                        },
+                        SecurityLevel::Internal,
+                        std::time::Duration::from_secs(10),
+                    )
+                    .await;
+
+                let error = match result {
+                    Ok(msg) => match msg.payload {
+                        EventKind::LaunchExecuteResponse { pid, error, .. } => {
+                            if pid > 0 && error.is_none() {
+                                None
+                            } else {
+                                Some(error.unwrap_or_else(|| "command failed".into()))
+                            }
+                        }
+                        _ => Some("unexpected response from launcher".into()),
+                    },
+                    Err(e) => {
+                        tracing::error!(error = %e, "shell command request failed");
+                        Some(format!("IPC error: {e}"))
+                    }
+                };
+                // No retry context: palette commands carry no tags, so there
+                // is nothing to unlock and re-run.
+                let launch_event = Event::LaunchResult {
+                    success: error.is_none(),
+                    error,
+                    denial: None,
+                    original_command: None,
+                    original_tags: None,
+                    original_launch_args: None,
+                };
+
+                let win_list = windows.lock().await;
+                let cfg = wm_config.lock().await;
+                let result_cmds = controller.handle(launch_event, &win_list, &cfg);
+                drop(cfg);
+                drop(win_list);
+                Box::pin(execute_commands(
+                    result_cmds,
+                    overlay_cmd_tx,
+                    overlay_event_rx,
+                    #[cfg(target_os = "linux")]
+                    backend,
+                    client,
+                    config_state,
+                    controller,
+                    windows,
+                    wm_config,
+                    ipc_keyboard_confirmed,
+                    password_buffer,
+                ))
+                .await;
+            }
+            Command::CopyToClipboard { text } => {
+                #[cfg(target_os = "linux")]
+                match platform_linux::clipboard::copy_text(&text).await {
+                    Ok(()) => tracing::info!(len = text.len(), "copied to clipboard from overlay"),
+                    Err(e) => tracing::warn!(error = %e, "clipboard copy failed"),
+                }
+                #[cfg(not(target_os = "linux"))]
+                let _ = text;
+            }
+            Command::CloseWindows { windows: closing } => {
+                #[cfg(target_os = "linux")]
+                if let Some(backend) = backend {
+                    for window in &closing {
+                        match backend.close_window(&window.id).await {
+                            Ok(()) => {
+                                tracing::info!(target = %window.id, "closed window from overlay")
+                            }
+                            Err(e) => {
+                                tracing::warn!(target = %window.id, error = %e, "close_window failed")
+                            }
+                        }
+                    }
+                }
+                // Drop them from the shared list now rather than waiting for
+                // the next enumeration, so later picker rebuilds do not bring
+                // them back.
+                windows
+                    .lock()
+                    .await
+                    .retain(|w| !closing.iter().any(|c| c.id == w.id));
+            }
+            Command::RefreshPicker => {
+                // The activation before this saved the MRU stack; a fresh
+                // enumeration also picks up the new focus flags.
+                #[cfg(target_os = "linux")]
+                if let Some(live) = backend {
+                    match live.list_windows().await {
+                        Ok(fresh) => *windows.lock().await = fresh,
+                        Err(e) => {
+                            tracing::debug!(error = %e, "window re-enumeration failed, refreshing from cache");
+                        }
+                    }
+                }
+                let win_list = windows.lock().await;
+                let cfg = wm_config.lock().await;
+                let refresh_cmds = controller.handle(Event::Refresh, &win_list, &cfg);
+                drop(cfg);
+                drop(win_list);
+                Box::pin(execute_commands(
+                    refresh_cmds,
+                    overlay_cmd_tx,
+                    overlay_event_rx,
+                    #[cfg(target_os = "linux")]
+                    backend,
+                    client,
+                    config_state,
+                    controller,
+                    windows,
+                    wm_config,
+                    ipc_keyboard_confirmed,
+                    password_buffer,
+                ))
+                .await;
+            }
+            Command::PeekWindow { window } => {
+                // No MRU update: a peek is not a switch until it is committed.
+                #[cfg(target_os = "linux")]
+                if let Some(backend) = backend
+                    && let Err(e) = backend.activate_window(&window.id).await
                 {
-                    tracing::error!("overlay thread has exited unexpectedly");
+                    tracing::warn!(target = %window.id, error = %e, "peek activate_window failed");
                 }
+                tracing::debug!(target = %window.id, app_id = %window.app_id, "peeked window raised");
             }
-            Command::ResetGrace => {
-                if overlay_cmd_tx.send(OverlayCmd::ResetGrace).is_err() {
-                    tracing::error!("overlay thread has exited unexpectedly");
+            Command::RecordUsage { kind, app, elapsed } => {
+                let hooks = {
+                    let cfg = wm_config.lock().await;
+                    if cfg.usage_stats {
+                        crate::stats::record(kind, app.as_deref(), &cfg.key_bindings, elapsed);
+                    }
+                    cfg.hooks.clone()
+                };
+                let profile = config_state.read().ok().and_then(|c| {
+                    core_types::TrustProfileName::try_from(c.global.default_profile.as_ref()).ok()
+                });
+                crate::hooks::run(client, &hooks, kind, app.as_deref(), profile).await;
+            }
+            Command::RecordReleaseTime(elapsed) => {
+                let learned = crate::release_timing::record(elapsed).learned_threshold_ms();
+                tracing::debug!(
+                    held_ms = elapsed.as_millis(),
+                    learned_ms = learned,
+                    "recorded quick-switch release"
+                );
+                controller.set_learned_quick_switch(learned);
+            }
+            Command::PlaceWindow { window, placement } => {
+                #[cfg(target_os = "linux")]
+                if let Some(backend) = backend
+                    && let Err(e) = backend.place_window(&window.id, placement).await
+                {
+                    tracing::warn!(target = %window.id, ?placement, error = %e, "place_window failed");
+                    let _ = overlay_cmd_tx.send(OverlayCmd::Toast {
+                        message: format!("Could not place {}: {e}", window.app_id),
+                    });
                 }
+                #[cfg(not(target_os = "linux"))]
+                let _ = (window, placement);
             }
-            Command::ShowLaunching => {
-                if overlay_cmd_tx.send(OverlayCmd::ShowLaunching).is_err() {
-                    tracing::error!("overlay thread has exited unexpectedly");
+            Command::MoveWindowToOutput { window, output } => {
+                #[cfg(target_os = "linux")]
+                if let Some(backend) = backend
+                    && let Err(e) = backend.move_to_output(&window.id, &output.id).await
+                {
+                    tracing::warn!(target = %window.id, output = %output.name, error = %e, "move_to_output failed");
+                    let _ = overlay_cmd_tx.send(OverlayCmd::Toast {
+                        message: format!(
+                            "Could not move {} to {}: {e}",
+                            window.app_id, output.name
+                        ),
+                    });
                 }
+                #[cfg(not(target_os = "linux"))]
+                let _ = (window, output);
             }
-            Command::ShowLaunchError { message, .. } => {
-                if overlay_cmd_tx
-                    .send(OverlayCmd::ShowLaunchError { message })
-                    .is_err()
+            Command::MarkWindow { mark, window } => {
+                crate::marks::set(mark, &window.id.to_string());
+            }
+            Command::SetWindowToggle { window, toggle, on } => {
+                #[cfg(target_os = "linux")]
+                if let Some(backend) = backend
+                    && let Err(e) = backend.set_window_toggle(&window.id, toggle, on).await
                 {
-                    tracing::error!("overlay thread has exited unexpectedly");
+                    tracing::warn!(target = %window.id, ?toggle, on, error = %e, "set_window_toggle failed");
+                    let _ = overlay_cmd_tx.send(OverlayCmd::Toast {
+                        message: format!("Could not update {}: {e}", window.app_id),
+                    });
                 }
+                #[cfg(not(target_os = "linux"))]
+                let _ = (window, toggle, on);
             }
             Command::Publish(event, level) => {
                 client.publish(event, level).await.ok();
             }
+            cmd @ (Command::ShowPicker { .. }
+            | Command::ShowRetry { .. }
+            | Command::UpdatePicker { .. }
+            | Command::UpdateRows { .. }
+            | Command::SetRowMenu { .. }
+            | Command::SetHelp { .. }
+            | Command::SetWalkthrough { .. }
+            | Command::ShowLaunching
+            | Command::ShowLaunchStaged { .. }
+            | Command::ShowPalette { .. }
+            | Command::ShowLaunchError { .. }
+            | Command::ResetGrace) => {
+                draw(cmd, overlay_cmd_tx);
+            }
             // -- Unlock flow commands --
             //
             // The AttemptAutoUnlock handler cannot be unit-tested in isolation
@@ -17,18 +17,49 @@
 //! sits at the last index — lowest priority in cycling order. The user can
 //! still reach it by cycling all the way around or by typing its hint key.
 //! Origin is never the *default* target for quick-switch or initial selection.
+//! With `hide_current_window` it is left out of the list entirely.
 
+use crate::actions::{self, Capabilities, HelpView};
+use crate::app_names;
 use crate::hints::{self, MatchResult};
+use crate::items;
+use crate::marks;
 use crate::mru;
+use crate::multiplexer::{self, MultiplexerTargets};
+use crate::onboarding::{self, Walkthrough, WalkthroughView};
+use crate::outputs;
 use crate::overlay::WindowInfo;
-use core_config::WmConfig;
-use core_types::{EventKind, LaunchDenial, SecurityLevel, TrustProfileName, Window};
+use crate::palette::{self, PaletteInput};
+use crate::row_menu::{self, MenuView, RowMenu};
+use crate::search;
+use crate::timeline;
+use crate::window_order;
+use core_config::{
+    HintPlacement, InvocationMode, MinimizedWindows, ShortcutInhibit, TitleRewriter, TitleRule,
+    TransientWindows, WmConfig,
+};
+use core_types::{
+    EventKind, LaunchDenial, MultiplexerTarget, Output, SecurityLevel, SelectableItem,
+    TrustProfileName, UsageKind, Window, WindowId, WindowPlacement, WindowToggle, Workspace,
+};
 use std::collections::BTreeMap;
+use std::sync::Arc;
 use std::time::Instant;
 
 /// Maximum input buffer length.
 const MAX_INPUT_LENGTH: usize = 64;
 
+/// Maximum input buffer length for command-palette input (`=`/`>`).
+const MAX_PALETTE_INPUT_LENGTH: usize = 256;
+
+/// Notice shown when the overlay opens over a window that grabs shortcuts.
+const SHORTCUT_INHIBIT_NOTICE: &str =
+    "The focused window may also have received this shortcut \u{2014} pick with Enter";
+
+/// Notice shown while keep-open mode is on.
+const KEEP_OPEN_NOTICE: &str =
+    "Keep open: Enter switches and leaves the picker up \u{2014} Ctrl+Enter to stop";
+
 // ---------------------------------------------------------------------------
 // Commands — concrete orders the main loop executes without interpretation
 // ---------------------------------------------------------------------------
@@ -39,7 +70,11 @@ pub enum Command {
     /// When `zero_window_launcher` is true, immediately confirm IPC keyboard input
     /// to prevent the stale activation timeout (the Wayland compositor will not
     /// grant keyboard focus when zero toplevel windows exist).
-    ShowBorder { zero_window_launcher: bool },
+    ShowBorder {
+        zero_window_launcher: bool,
+        /// Armed dwell to visualise as a progress ring (`show_delay_indicator`).
+        delay_indicator_ms: Option<u32>,
+    },
     /// Send OverlayCmd::ShowFull with the given data.
     ShowPicker {
         windows: Vec<WindowInfo>,
@@ -47,12 +82,32 @@ pub enum Command {
     },
     /// Send OverlayCmd::UpdateInput.
     UpdatePicker { input: String, selection: usize },
+    /// Send OverlayCmd::ShowRetry: a refreshed picker with a notice, after
+    /// the selected window disappeared.
+    ShowRetry {
+        windows: Vec<WindowInfo>,
+        hints: Vec<String>,
+        message: String,
+    },
+    /// Re-enumerate windows and confirm `window_id` still exists before the
+    /// overlay tears down. If it is gone, the executor feeds
+    /// `Event::ActivationFailed` back and drops the rest of the batch.
+    EnsureWindowAlive { window_id: WindowId },
     /// Send OverlayCmd::HideAndSync, wait for SurfaceUnmapped ack.
     HideAndSync,
     /// Send OverlayCmd::Hide (no sync needed).
     Hide,
     /// Activate a window via compositor backend + save MRU state.
-    ActivateWindow { window: Window },
+    /// `multiplexer` is set for tmux/zellij rows: after focusing the
+    /// terminal, switch the multiplexer to this target. If the compositor
+    /// refuses, the executor feeds `Event::ActivationFailed` back and drops
+    /// the rest of the batch.
+    ActivateWindow {
+        window: Window,
+        multiplexer: Option<MultiplexerTarget>,
+    },
+    /// Switch to a workspace via the compositor backend.
+    ActivateWorkspace { workspace: Workspace },
     /// Launch an application via IPC (request-response, not fire-and-forget).
     LaunchApp {
         command: String,
@@ -66,8 +121,72 @@ pub enum Command {
     },
     /// Show "Launching..." spinner/status in the overlay (launch in progress).
     ShowLaunching,
-    /// Show staged launch intent in the overlay (waiting for Alt release).
-    ShowLaunchStaged { command: String },
+    /// Show staged launch intent in the overlay (waiting for Alt release),
+    /// with how many instances it starts.
+    ShowLaunchStaged { command: String, instances: usize },
+    /// Show a notice beneath the window list: a command-palette result or
+    /// prompt, the command a command-only key binding will run, or a
+    /// confirmation prompt.
+    ShowPalette { message: String },
+    /// Run a shell command via daemon-launcher: a confirmed command-palette
+    /// command or the command of a command-only key binding.
+    /// Result is fed back as `Event::LaunchResult`.
+    RunShellCommand { command: String },
+    /// Copy text to the Wayland clipboard (data-control). The overlay stays open.
+    CopyToClipboard { text: String },
+    /// Ask the compositor to close these windows. The overlay stays open.
+    CloseWindows { windows: Vec<Window> },
+    /// Re-enumerate windows and feed `Event::Refresh` back, so a picker kept
+    /// open after an activation shows the new focus and MRU order.
+    RefreshPicker,
+    /// Send OverlayCmd::UpdateRows: the same rows with new titles or app
+    /// IDs. Input, selection and hints are unchanged.
+    UpdateRows { windows: Vec<WindowInfo> },
+    /// Send OverlayCmd::SetRowMenu: draw the selected row's context menu,
+    /// or close it.
+    SetRowMenu { menu: Option<MenuView> },
+    /// Send OverlayCmd::SetHelp: draw the help overlay in place of the
+    /// picker, or hide it.
+    SetHelp { help: Option<HelpView> },
+    /// Send OverlayCmd::SetWalkthrough: draw a first-run walkthrough page
+    /// in place of the picker, or hide the walkthrough.
+    SetWalkthrough { page: Option<WalkthroughView> },
+    /// The walkthrough was finished or dismissed for good: record it in
+    /// the state store so it is not shown again.
+    FinishWalkthrough,
+    /// Activate a window via the compositor backend without recording it in
+    /// the MRU stack: a peek preview, or the origin raised again after one.
+    /// The overlay stays open.
+    PeekWindow { window: Window },
+    /// Tile or maximize a window via the compositor backend. Follows the
+    /// `ActivateWindow` of the same window.
+    PlaceWindow {
+        window: Window,
+        placement: WindowPlacement,
+    },
+    /// Move a window to an output via the compositor backend. Follows the
+    /// `ActivateWindow` of the same window.
+    MoveWindowToOutput { window: Window, output: Output },
+    /// Switch a window state on or off via the compositor backend. The
+    /// overlay stays open.
+    SetWindowToggle {
+        window: Window,
+        toggle: WindowToggle,
+        on: bool,
+    },
+    /// Save `mark` as naming a window, for `sesame wm mark`. The overlay
+    /// stays open.
+    MarkWindow { mark: char, window: Window },
+    /// Append how this activation ended to the usage log
+    /// (`wm.usage_stats`). `elapsed` is the time since the activation.
+    RecordUsage {
+        kind: UsageKind,
+        app: Option<String>,
+        elapsed: std::time::Duration,
+    },
+    /// Add how long Alt was held in a plain Alt+Tab to the learned
+    /// release timings (`wm.adaptive_quick_switch`).
+    RecordReleaseTime(std::time::Duration),
     /// Reset the overlay's modifier-poll grace timer. Sent on IPC
     /// re-activation to prove Alt is still held and prevent premature commit.
     ResetGrace,
@@ -115,6 +234,9 @@ pub enum Event {
     ModifierReleased,
     /// Character typed.
     Char(char),
+    /// Pre-filled input (`--query`), applied as if each character had been
+    /// typed in turn.
+    Prefill(String),
     /// Backspace.
     Backspace,
     /// Tab / Down arrow.
@@ -125,11 +247,76 @@ pub enum Event {
     Confirm,
     /// Escape.
     Escape,
+    /// Ctrl+C: copy the selected window's title.
+    CopyTitle,
+    /// Ctrl+Shift+C: copy the selected window's app_id.
+    CopyAppId,
+    /// Ctrl+W: close the selected window.
+    CloseWindow,
+    /// Ctrl+Shift+W: close every listed window of the selected window's app.
+    CloseAppWindows,
+    /// Ctrl+P: raise the selected window behind the open picker.
+    Peek,
+    /// Ctrl+Enter: switch keep-open mode on or off. While it is on, a
+    /// committed window is activated behind the picker, which stays open.
+    KeepOpen,
+    /// Shift+Enter: launch a new instance of the app the typed hint names,
+    /// or of the selected window's app, although it has windows.
+    LaunchNew,
+    /// Ctrl+H / Ctrl+L / Ctrl+F: tile the selected window to the left or
+    /// right half, or maximize it, and switch to it.
+    Place(WindowPlacement),
+    /// Ctrl+S / Ctrl+T: show the selected window on every workspace, or
+    /// keep it above other windows, or stop doing so.
+    Toggle(WindowToggle),
+    /// Ctrl+M: mark the selected window with the next letter typed.
+    Mark,
+    /// Ctrl+O: move the selected window to the output numbered by the next
+    /// digit typed.
+    MoveToOutput,
+    /// Ctrl+R: switch the picker between MRU order and the focus timeline.
+    Timeline,
+    /// Menu key, or `.` on empty input: open or close the selected row's
+    /// context menu.
+    RowMenu,
+    /// F1, or `?` on empty input: show or hide the help overlay listing
+    /// every key (see [`crate::actions`]).
+    Help,
+    /// The session locked (`true`) or unlocked, from logind's
+    /// `LockedHint`. Locking dismisses the overlay; activations are ignored
+    /// until the session unlocks.
+    SessionLock(bool),
+    /// logind's `Lock` signal. Dismisses the overlay once without blocking
+    /// later activations: lockers that never set `LockedHint` never send
+    /// `Unlock` either.
+    SessionLockRequested,
     /// Stale activation timeout — overlay was visible too long with no
     /// keyboard interaction. Dismiss without activating any window.
     Dismiss,
-    /// The dwell timer expired (main loop polls `next_deadline()`).
+    /// The controller deadline expired (main loop polls `next_deadline()`):
+    /// either the Armed dwell or the `overlay_timeout_ms` inactivity timeout.
     DwellTimeout,
+    /// The committed window could not be activated. Fed back from the
+    /// command executor: with a fresh window list and no `error` when the
+    /// window closed first, or with the compositor's `error` when
+    /// activation itself failed.
+    ActivationFailed {
+        window_id: WindowId,
+        error: Option<String>,
+    },
+    /// Windows were re-enumerated after a keep-open activation. Fed back
+    /// from the command executor with the fresh window list.
+    Refresh,
+    /// A listed window's title or app ID changed. Fed back from the main
+    /// loop while the overlay is open, so rows show live titles.
+    WindowChanged {
+        window_id: WindowId,
+        app_id: String,
+        title: String,
+    },
+    /// Multiplexer targets listed for the open overlay. Fed back from the
+    /// main loop, which lists them after the overlay is shown.
+    MultiplexerListed(MultiplexerTargets),
     /// Launch request completed (success or failure). Fed back from main loop.
     LaunchResult {
         success: bool,
@@ -157,6 +344,40 @@ pub enum Event {
     },
 }
 
+impl Event {
+    /// The invocation mode an activation event opens the overlay in.
+    pub fn invocation_mode(&self) -> Option<InvocationMode> {
+        match self {
+            Event::Activate | Event::ActivateBackward => Some(InvocationMode::Switcher),
+            Event::ActivateLauncher | Event::ActivateLauncherBackward => {
+                Some(InvocationMode::Launcher)
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether the event is a key the user pressed, as opposed to a timer,
+    /// a compositor notification or a result fed back from the main loop.
+    fn is_key(&self) -> bool {
+        !matches!(
+            self,
+            Event::ModifierReleased
+                | Event::Dismiss
+                | Event::SessionLock(_)
+                | Event::SessionLockRequested
+                | Event::DwellTimeout
+                | Event::ActivationFailed { .. }
+                | Event::Refresh
+                | Event::WindowChanged { .. }
+                | Event::MultiplexerListed(_)
+                | Event::LaunchResult { .. }
+                | Event::AutoUnlockResult { .. }
+                | Event::TouchResult { .. }
+                | Event::UnlockResult { .. }
+        )
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Pre-computed activation snapshot — built once, carried through phases
 // ---------------------------------------------------------------------------
@@ -178,27 +399,77 @@ struct Snapshot {
     windows: Vec<Window>,
     /// Assigned hint strings (parallel to windows).
     hints: Vec<String>,
+    /// What each row selects (parallel to windows).
+    items: Vec<SelectableItem>,
     /// Overlay-ready window info (parallel to windows).
     overlay_windows: Vec<WindowInfo>,
     /// Index of the origin window in `windows`, if present.
     /// Used to prevent auto-selection of origin on quick-switch.
     origin_index: Option<usize>,
+    /// Index of the MRU previous window (the quick-switch target). Not
+    /// always 0: pinned apps are listed ahead of MRU order.
+    previous_index: Option<usize>,
     /// Key bindings snapshot for launch-or-focus.
     key_bindings: BTreeMap<String, core_config::WmKeyBinding>,
+    /// Whether `=`/`>` input is treated as calculator/shell command.
+    command_palette: bool,
+    /// Whether pressing a hint key again cycles its app's windows.
+    repeat_key_cycles: bool,
+    /// Unix time each row's window was last focused (parallel to windows).
+    focused_at: Vec<Option<u64>>,
+    /// Set while the rows are in timeline order: the MRU-order index of
+    /// each row.
+    timeline: Option<Vec<usize>>,
+    /// Desktop entry names, for rows whose app ID changes later.
+    app_names: Arc<app_names::AppNames>,
 }
 
 impl Snapshot {
-    fn build(windows: &[Window], config: &WmConfig) -> Self {
+    fn build(
+        windows: &[Window],
+        config: &WmConfig,
+        titles: &TitleRewriter,
+        targets: &MultiplexerTargets,
+        extra: &[SelectableItem],
+    ) -> Self {
         // Prune MRU entries for windows that no longer exist. This prevents
         // stale origin detection and keeps the MRU file accurate across
         // window open/close cycles.
         let live_ids: std::collections::HashSet<String> =
             windows.iter().map(|w| w.id.to_string()).collect();
-        mru::prune(&live_ids);
-
-        let mru_state = mru::load();
+        let mut mru_state = mru::load();
+        mru::prune(&mut mru_state, &live_ids);
+        marks::prune(&live_ids);
+        let live_order: Vec<String> = windows.iter().map(|w| w.id.to_string()).collect();
+        let window_order = window_order::sync(&live_order);
+        let names = app_names::current();
+        // The overlay opens on the focused window's output; on-window badges
+        // are drawn for the windows shown with it.
+        let badge_focus = (config.hint_placement == HintPlacement::Windows)
+            .then(|| windows.iter().find(|w| w.is_focused).cloned());
         let mut win_list = windows.to_vec();
+        if config.transient_windows == TransientWindows::Hide {
+            window_order::hide_transients(&mut win_list, |w| w.id, |w| w.parent);
+        }
+        if config.minimized_windows == MinimizedWindows::Hide {
+            win_list.retain(|w| !w.is_minimized);
+        }
         mru::reorder(&mut win_list, |w| w.id.to_string(), &mru_state);
+        if config.hide_current_window {
+            mru::remove_current(&mut win_list, |w| w.id.to_string(), &mru_state);
+        }
+        if config.minimized_windows == MinimizedWindows::Last {
+            // Stable, so the MRU order holds on either side.
+            win_list.sort_by_key(|w| w.is_minimized);
+        }
+        // Quick-switch targets the MRU previous window even when pinned apps
+        // are listed above it.
+        let previous_id = win_list
+            .iter()
+            .map(|w| w.id.to_string())
+            .find(|id| Some(id.as_str()) != mru_state.current());
+        // Pin before truncating so pinned windows are never cut off.
+        window_order::pin_first(&mut win_list, |w| w.app_id.as_str(), &config.pinned_apps);
         win_list.truncate(config.max_visible_windows as usize);
 
         // Rotate origin (MRU current, typically index 0) to the end of the
@@ -217,16 +488,94 @@ impl Snapshot {
         } else {
             None
         };
-
+        // A pinned origin goes back to the end of the pinned section.
+        window_order::pin_first(&mut win_list, |w| w.app_id.as_str(), &config.pinned_apps);
+        // Dialogs follow their parent, wherever pinning and the origin
+        // rotation left it.
+        let nested_ids: std::collections::HashSet<WindowId> =
+            if config.transient_windows == TransientWindows::Nest {
+                let nested = window_order::nest_transients(&mut win_list, |w| w.id, |w| w.parent);
+                win_list
+                    .iter()
+                    .zip(nested)
+                    .filter(|(_, nested)| *nested)
+                    .map(|(w, _)| w.id)
+                    .collect()
+            } else {
+                std::collections::HashSet::new()
+            };
+        let origin_id = origin_index.and_then(|_| mru_state.current());
+        let origin_index =
+            origin_id.and_then(|id| win_list.iter().position(|w| w.id.to_string() == id));
+
+        // Multiplexer rows fill whatever is left of the visible budget.
+        let max_rows = (config.max_visible_windows as usize).saturating_sub(win_list.len());
+        let mut items: Vec<SelectableItem> =
+            multiplexer::insert_rows(&mut win_list, origin_index, config, targets, max_rows)
+                .into_iter()
+                .map(|target| target.map_or(SelectableItem::Window, SelectableItem::Multiplexer))
+                .collect();
+        // Multiplexer rows are clones of their host window, so look up real
+        // windows only.
+        let real_index = |win_list: &[Window], items: &[SelectableItem], id: &str| {
+            win_list
+                .iter()
+                .zip(items)
+                .position(|(w, item)| *item == SelectableItem::Window && w.id.to_string() == id)
+        };
+        // Other items (workspaces, outputs) take what the multiplexer rows
+        // left, in the order given.
+        let max_rows = (config.max_visible_windows as usize).saturating_sub(win_list.len());
+        let origin_row = origin_id.and_then(|id| real_index(&win_list, &items, id));
+        items::append_rows(&mut win_list, &mut items, origin_row, extra, max_rows);
+        let origin_index = origin_id.and_then(|id| real_index(&win_list, &items, id));
+        let previous_index = previous_id
+            .as_deref()
+            .and_then(|id| real_index(&win_list, &items, id));
+
+        // Repeated-letter hints follow first-seen order, not MRU order, so
+        // `ff` names the same window until it closes.
         let app_ids: Vec<&str> = win_list.iter().map(|w| w.app_id.as_str()).collect();
-        let app_hints = hints::assign_app_hints(&app_ids, &config.key_bindings);
+        let ranks: Vec<usize> = win_list
+            .iter()
+            .map(|w| window_order.rank(&w.id.to_string()))
+            .collect();
+        let app_hints = hints::assign_app_hints_ranked(&app_ids, &ranks, &config.key_bindings);
         let hint_strings: Vec<String> = app_hints.iter().map(|(h, _)| h.clone()).collect();
+        let positions = hints::app_positions(&app_ids, &ranks);
+
+        let focused_at: Vec<Option<u64>> = win_list
+            .iter()
+            .map(|w| mru_state.focused_at(&w.id.to_string()))
+            .collect();
 
         let overlay_windows: Vec<WindowInfo> = win_list
             .iter()
-            .map(|w| WindowInfo {
-                app_id: w.app_id.to_string(),
-                title: w.title.clone(),
+            .zip(positions)
+            .zip(&items)
+            .map(|((w, position), item)| {
+                let (label, icon) = hints::label_and_icon(w.app_id.as_str(), &config.key_bindings);
+                let label = items::label(item)
+                    .map(Into::into)
+                    .or(label)
+                    .or_else(|| names.name_for(w.app_id.as_str()));
+                WindowInfo {
+                    app_id: w.app_id.to_string(),
+                    title: titles.rewrite(&w.title).into_owned(),
+                    position,
+                    pinned: window_order::pinned_rank(w.app_id.as_str(), &config.pinned_apps)
+                        .is_some(),
+                    nested: *item == SelectableItem::Window && nested_ids.contains(&w.id),
+                    sticky: w.is_sticky,
+                    keep_above: w.is_keep_above,
+                    minimized: w.is_minimized,
+                    label,
+                    icon,
+                    focused_ago: None,
+                    geometry: badge_focus
+                        .as_ref()
+                        .and_then(|focused| items::on_screen(w, item, focused.as_ref())),
+                }
             })
             .collect();
 
@@ -236,43 +585,170 @@ impl Snapshot {
             hints = ?hint_strings,
             apps = ?app_ids,
             mru_origin = mru_state.current().unwrap_or("<none>"),
-            quick_target = win_list.first().map(|w| w.id.to_string()).as_deref().unwrap_or("<none>"),
+            quick_target = previous_id.as_deref().unwrap_or("<none>"),
             "snapshot: pre-computed overlay data"
         );
 
         Self {
             windows: win_list,
             hints: hint_strings,
+            items,
             overlay_windows,
             origin_index,
+            previous_index,
             key_bindings: config.key_bindings.clone(),
+            command_palette: config.command_palette,
+            repeat_key_cycles: config.repeat_key_cycles,
+            focused_at,
+            timeline: None,
+            app_names: names,
         }
     }
 
-    /// First valid forward selection (index 0 unless that's origin).
+    /// Rows listing a window. Only these are an initial selection, so a
+    /// plain Alt+Tab always switches window, never workspace or output.
+    fn window_rows(&self) -> impl DoubleEndedIterator<Item = usize> + '_ {
+        self.items
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| **item == SelectableItem::Window)
+            .map(|(i, _)| i)
+    }
+
+    /// First valid forward selection: the MRU previous window if known,
+    /// otherwise the first window row unless that's origin.
     fn initial_forward(&self) -> usize {
-        if self.origin_index == Some(0) && self.windows.len() > 1 {
-            1
-        } else {
-            0
+        if let Some(previous) = self.previous_index {
+            return previous;
         }
+        self.window_rows()
+            .find(|&i| Some(i) != self.origin_index)
+            .or(self.origin_index)
+            .unwrap_or(0)
     }
 
-    /// First valid backward selection (last index unless that's origin).
+    /// First valid backward selection (last window row unless that's
+    /// origin).
     fn initial_backward(&self) -> usize {
-        let last = self.windows.len().saturating_sub(1);
-        if self.origin_index == Some(last) && last > 0 {
-            last - 1
-        } else {
-            last
+        self.window_rows()
+            .rev()
+            .find(|&i| Some(i) != self.origin_index)
+            .or(self.origin_index)
+            .unwrap_or_else(|| self.windows.len().saturating_sub(1))
+    }
+
+    /// Drop every window row, keeping launch bindings. Used when the
+    /// compositor cannot activate windows, so the rows would be dead ends.
+    fn without_windows(mut self) -> Self {
+        self.windows.clear();
+        self.hints.clear();
+        self.items.clear();
+        self.overlay_windows.clear();
+        self.focused_at.clear();
+        self.origin_index = None;
+        self.previous_index = None;
+        self.timeline = None;
+        self
+    }
+
+    /// Switch the rows between MRU order and timeline order. Returns the
+    /// new position of each old row's index: row `i` now holds what was
+    /// row `order[i]`.
+    fn toggle_timeline(&mut self, now: u64) -> Vec<usize> {
+        let entering = self.timeline.is_none();
+        let order = match self.timeline.take() {
+            Some(list_index) => {
+                let mut back = vec![0; list_index.len()];
+                for (row, &index) in list_index.iter().enumerate() {
+                    back[index] = row;
+                }
+                back
+            }
+            None => timeline::order(&self.focused_at),
+        };
+        self.permute(&order);
+        for (i, row) in self.overlay_windows.iter_mut().enumerate() {
+            row.focused_ago = entering.then(|| match self.focused_at[i] {
+                _ if self.origin_index == Some(i) => "current".into(),
+                Some(at) => timeline::relative_time(now.saturating_sub(at)),
+                None => "earlier".into(),
+            });
+        }
+        self.timeline = entering.then(|| order.clone());
+        order
+    }
+
+    /// Give `window_id`'s row a new app ID and title. A new app ID also
+    /// brings its label and icon; hints stay as assigned, so they do not
+    /// move while the user reads them. Returns whether the window is
+    /// listed.
+    fn retitle(
+        &mut self,
+        window_id: WindowId,
+        app_id: &str,
+        title: &str,
+        titles: &TitleRewriter,
+    ) -> bool {
+        let Some(row) = self
+            .windows
+            .iter()
+            .zip(&self.items)
+            .position(|(w, item)| *item == SelectableItem::Window && w.id == window_id)
+        else {
+            return false;
+        };
+        let window = &mut self.windows[row];
+        window.app_id = core_types::AppId::new(app_id);
+        window.title = title.to_string();
+        let info = &mut self.overlay_windows[row];
+        if info.app_id != app_id {
+            let (label, icon) = hints::label_and_icon(app_id, &self.key_bindings);
+            info.label = label.or_else(|| self.app_names.name_for(app_id));
+            info.icon = icon;
+            info.app_id = app_id.to_string();
+        }
+        info.title = titles.rewrite(title).into_owned();
+        true
+    }
+
+    /// Reorder every per-row list so row `i` holds what was row `order[i]`.
+    fn permute(&mut self, order: &[usize]) {
+        fn apply<T: Clone>(rows: &mut Vec<T>, order: &[usize]) {
+            *rows = order.iter().map(|&i| rows[i].clone()).collect();
+        }
+        apply(&mut self.windows, order);
+        apply(&mut self.hints, order);
+        apply(&mut self.items, order);
+        apply(&mut self.overlay_windows, order);
+        apply(&mut self.focused_at, order);
+        let moved = |index: Option<usize>| index.and_then(|i| order.iter().position(|&o| o == i));
+        self.origin_index = moved(self.origin_index);
+        self.previous_index = moved(self.previous_index);
+    }
+
+    /// The window at row `index`, unless the row lists some other item:
+    /// window actions (peek, place, mark, toggles) have nothing to act on
+    /// there.
+    fn window(&self, index: usize) -> Option<&Window> {
+        match self.items.get(index)? {
+            SelectableItem::Window | SelectableItem::Multiplexer(_) => self.windows.get(index),
+            _ => None,
+        }
+    }
+
+    /// The multiplexer target of row `index`, for tmux/zellij rows.
+    fn multiplexer(&self, index: usize) -> Option<MultiplexerTarget> {
+        match self.items.get(index)? {
+            SelectableItem::Multiplexer(target) => Some(target.clone()),
+            _ => None,
         }
     }
 
     /// Whether there are any windows to activate. A single window is a valid
     /// target — the user may be on a different workspace and Alt+Tab should
-    /// bring it to them.
+    /// bring it to them. Stand-in rows (workspaces, outputs) do not count.
     fn has_targets(&self) -> bool {
-        !self.windows.is_empty()
+        self.window_rows().next().is_some()
     }
 
     /// Test-only constructor with explicit origin_index.
@@ -281,19 +757,41 @@ impl Snapshot {
         let app_ids: Vec<&str> = windows.iter().map(|w| w.app_id.as_str()).collect();
         let app_hints = hints::assign_app_hints(&app_ids, &config.key_bindings);
         let hint_strings: Vec<String> = app_hints.iter().map(|(h, _)| h.clone()).collect();
+        let ranks: Vec<usize> = (0..windows.len()).collect();
         let overlay_windows: Vec<WindowInfo> = windows
             .iter()
-            .map(|w| WindowInfo {
-                app_id: w.app_id.to_string(),
-                title: w.title.clone(),
+            .zip(hints::app_positions(&app_ids, &ranks))
+            .map(|(w, position)| {
+                let (label, icon) = hints::label_and_icon(w.app_id.as_str(), &config.key_bindings);
+                WindowInfo {
+                    app_id: w.app_id.to_string(),
+                    title: w.title.clone(),
+                    position,
+                    pinned: false,
+                    nested: false,
+                    sticky: w.is_sticky,
+                    keep_above: w.is_keep_above,
+                    minimized: w.is_minimized,
+                    label,
+                    icon,
+                    focused_ago: None,
+                    geometry: None,
+                }
             })
             .collect();
         Self {
             windows: windows.to_vec(),
             hints: hint_strings,
+            items: vec![SelectableItem::Window; windows.len()],
             overlay_windows,
             origin_index,
+            previous_index: None,
             key_bindings: config.key_bindings.clone(),
+            command_palette: config.command_palette,
+            repeat_key_cycles: config.repeat_key_cycles,
+            focused_at: vec![None; windows.len()],
+            timeline: None,
+            app_names: app_names::current(),
         }
     }
 }
@@ -305,10 +803,86 @@ impl Snapshot {
 /// Staged launch — user typed a launch key but hasn't released Alt yet.
 /// Stored in Armed/Picking so `on_modifier_released` can execute it.
 #[derive(Debug, Clone)]
-struct PendingLaunch {
-    command: String,
-    tags: Vec<String>,
-    launch_args: Vec<String>,
+enum PendingLaunch {
+    /// Launch-or-focus binding whose app has no window, or new instances
+    /// of an app asked for with `launch_new_instance_key` or Shift+Enter.
+    App {
+        command: String,
+        tags: Vec<String>,
+        launch_args: Vec<String>,
+        instances: usize,
+    },
+    /// Command-only binding: run its shell command.
+    Shell { command: String },
+    /// Launch-or-focus binding whose app was launched within
+    /// `relaunch_guard_ms` and has no window yet: committing only
+    /// dismisses the overlay.
+    Starting { command: String },
+}
+
+impl PendingLaunch {
+    /// The launch of `instances` of `key`'s app binding, with the notice
+    /// shown while it is staged.
+    fn app_for_key(
+        key: char,
+        key_bindings: &BTreeMap<String, core_config::WmKeyBinding>,
+        instances: usize,
+    ) -> Option<(Self, Command)> {
+        hints::launch_for_key(key, key_bindings).map(|cmd| {
+            let command = cmd.to_string();
+            let launch = Self::App {
+                command: command.clone(),
+                tags: hints::tags_for_key(key, key_bindings),
+                launch_args: hints::launch_args_for_key(key, key_bindings),
+                instances,
+            };
+            (launch, Command::ShowLaunchStaged { command, instances })
+        })
+    }
+
+    /// Commands that execute the staged launch.
+    fn into_commands(self) -> Vec<Command> {
+        let mut cmds = vec![Command::ShowLaunching];
+        match self {
+            Self::App {
+                command,
+                tags,
+                launch_args,
+                instances,
+            } => cmds.extend((0..instances).map(|_| Command::LaunchApp {
+                command: command.clone(),
+                tags: tags.clone(),
+                launch_args: launch_args.clone(),
+            })),
+            Self::Shell { command } => cmds.push(Command::RunShellCommand { command }),
+            Self::Starting { .. } => {
+                return vec![
+                    Command::Hide,
+                    Command::Publish(EventKind::WmOverlayDismissed, SecurityLevel::Internal),
+                ];
+            }
+        }
+        cmds
+    }
+}
+
+/// A destructive overlay action that needs confirmation (`confirm_close`).
+#[derive(Debug, Clone)]
+enum ConfirmAction {
+    /// Close these windows.
+    Close { windows: Vec<Window> },
+}
+
+impl ConfirmAction {
+    /// Prompt shown beneath the card while confirming.
+    fn prompt(&self) -> String {
+        match self {
+            Self::Close { windows } => match windows.as_slice() {
+                [window] => format!("Close \u{2018}{}\u{2019}? y/n", window.title),
+                _ => format!("Close {} windows? y/n", windows.len()),
+            },
+        }
+    }
 }
 
 /// Sub-mode within the vault unlock flow.
@@ -341,6 +915,14 @@ enum Phase {
         input: String,
         pending_launch: Option<PendingLaunch>,
     },
+    /// A destructive action is waiting for y/n. The picker state is kept
+    /// so answering no returns to it unchanged.
+    Confirming {
+        action: ConfirmAction,
+        snap: Snapshot,
+        selection: usize,
+        input: String,
+    },
     /// Waiting for LaunchExecuteResponse from daemon-launcher.
     Launching,
     /// Launch failed — showing error toast. Any key dismisses.
@@ -377,6 +959,15 @@ enum ActivationMode {
     LauncherBackward,
 }
 
+/// A window raised by `Event::Peek` while the picker stays open.
+#[derive(Debug)]
+struct Peek {
+    window_id: WindowId,
+    /// The window focused before the overlay opened, raised again when the
+    /// peek ends without a commit.
+    origin: Option<Window>,
+}
+
 /// Window within which a SelectionDown/SelectionUp event is suppressed
 /// after an IPC re-activation already advanced the selection. Prevents
 /// the same physical keystroke from advancing twice (once via IPC
@@ -391,6 +982,101 @@ pub struct OverlayController {
     /// the SCTK keyboard handler or IPC InputKeyEvent that correspond to
     /// the same physical keystroke that triggered the re-activation.
     last_ipc_advance: Option<Instant>,
+    /// Multiplexer targets for the snapshots of this cycle. Listed over IPC
+    /// by the main loop after each fresh activation and merged into the
+    /// open overlay by `Event::MultiplexerListed`.
+    multiplexer_targets: MultiplexerTargets,
+    /// Workspaces listed as rows by the next snapshot (`workspace_rows`).
+    /// Fetched from the compositor by the main loop before each fresh
+    /// activation.
+    workspaces: Vec<Workspace>,
+    /// Outputs, listed as rows by the next snapshot (`output_rows`) and
+    /// numbered for Ctrl+O. Fetched like `workspaces`.
+    outputs: Vec<Output>,
+    /// Palette input for which Enter was pressed once. A second Enter on the
+    /// same input runs the shell command; any edit clears it.
+    palette_confirm: Option<String>,
+    /// Whether the compositor backend can activate windows. When it cannot,
+    /// snapshots carry no window rows and the overlay is launch-only.
+    can_activate: bool,
+    /// Last event other than a deadline expiry, for `overlay_timeout_ms`.
+    last_activity: Instant,
+    /// `overlay_timeout_ms` from the config passed to the latest `handle`.
+    overlay_timeout_ms: u32,
+    /// When the typed input last became or stayed a dead end (see
+    /// `hints::is_dead_end`), for `dead_input_clear_ms`.
+    dead_input_at: Option<Instant>,
+    /// `dead_input_clear_ms` from the config passed to the latest `handle`.
+    dead_input_clear_ms: u32,
+    /// When the typed input last narrowed the picker to the selected
+    /// window alone, for `auto_activate_single_match`.
+    single_match_at: Option<Instant>,
+    /// `activation_delay_ms` from the config passed to the latest `handle`,
+    /// if `auto_activate_single_match` is on.
+    auto_activate_ms: Option<u32>,
+    /// `launch_new_instance_key` from the config passed to the latest
+    /// `handle`, if set to a usable character.
+    new_instance_suffix: Option<char>,
+    /// `relaunch_guard_ms` from the config passed to the latest `handle`.
+    relaunch_guard_ms: u32,
+    /// `title_rules` from the config passed to the latest `handle`, and
+    /// `titles` compiled from them. Recompiled only when the rules change.
+    title_rules: Vec<TitleRule>,
+    titles: TitleRewriter,
+    /// App launch commands run within the last `relaunch_guard_ms`, and
+    /// when.
+    recent_launches: Vec<(String, Instant)>,
+    /// The app launch command awaiting its `LaunchResult`. A launch that
+    /// fails is dropped from `recent_launches` so it can be retried.
+    launch_in_flight: Option<String>,
+    /// The window raised behind the picker by Ctrl+P, if any.
+    peek: Option<Peek>,
+    /// Keep-open mode (Ctrl+Enter): committing a window activates it and
+    /// leaves the picker open. Reset by every fresh activation.
+    keep_open: bool,
+    /// Whether the compositor can maximize windows (Ctrl+F).
+    can_maximize: bool,
+    /// Whether the compositor can tile windows to a half (Ctrl+H, Ctrl+L).
+    can_tile: bool,
+    /// Whether the compositor can show windows on every workspace (Ctrl+S).
+    can_stick: bool,
+    /// Whether the compositor can keep windows above others (Ctrl+T).
+    can_keep_above: bool,
+    /// Whether the compositor can move windows between outputs (Ctrl+O).
+    can_move_to_output: bool,
+    /// The session is locked; the overlay must not show over the lock
+    /// screen.
+    session_locked: bool,
+    /// Ctrl+M was pressed in the picker: the next character names a mark
+    /// instead of being typed.
+    mark_pending: bool,
+    /// Ctrl+O was pressed in the picker: the next character numbers an
+    /// output instead of being typed.
+    output_pending: bool,
+    /// The context menu open over the picker (Menu key), if any.
+    row_menu: Option<RowMenu>,
+    /// The help overlay (`?`) is shown in place of the picker.
+    help_open: bool,
+    /// First-run walkthrough, shown on activation until finished or
+    /// closed.
+    walkthrough: Option<Walkthrough>,
+    /// The walkthrough is shown in place of the picker.
+    walkthrough_open: bool,
+    /// Mode of the latest fresh activation, selecting the `[wm.switcher]`
+    /// or `[wm.launcher]` overrides for the rest of the cycle.
+    invocation: InvocationMode,
+    /// When the latest fresh activation began, for the usage log's time to
+    /// select.
+    cycle_started: Instant,
+    /// This cycle is a plain Alt+Tab whose release time is learned
+    /// (`adaptive_quick_switch`), until something but the release ends it.
+    time_release: bool,
+    /// Quick-switch threshold fitted to the recorded releases, once there
+    /// are enough.
+    learned_quick_switch_ms: Option<u32>,
+    /// Releasing Alt does not commit this cycle: the picker opened over a
+    /// window that grabs shortcuts (`shortcut_inhibit`).
+    release_spent: bool,
 }
 
 impl OverlayController {
@@ -398,19 +1084,160 @@ impl OverlayController {
         Self {
             phase: Phase::Idle,
             last_ipc_advance: None,
+            multiplexer_targets: MultiplexerTargets::new(),
+            workspaces: Vec::new(),
+            outputs: Vec::new(),
+            palette_confirm: None,
+            can_activate: true,
+            last_activity: Instant::now(),
+            cycle_started: Instant::now(),
+            time_release: false,
+            learned_quick_switch_ms: None,
+            release_spent: false,
+            overlay_timeout_ms: 0,
+            dead_input_at: None,
+            dead_input_clear_ms: 0,
+            single_match_at: None,
+            auto_activate_ms: None,
+            new_instance_suffix: None,
+            relaunch_guard_ms: 0,
+            title_rules: Vec::new(),
+            titles: TitleRewriter::default(),
+            recent_launches: Vec::new(),
+            launch_in_flight: None,
+            peek: None,
+            keep_open: false,
+            can_maximize: false,
+            can_tile: false,
+            can_stick: false,
+            can_keep_above: false,
+            can_move_to_output: false,
+            session_locked: false,
+            mark_pending: false,
+            output_pending: false,
+            row_menu: None,
+            help_open: false,
+            walkthrough: None,
+            walkthrough_open: false,
+            invocation: InvocationMode::Switcher,
         }
     }
 
+    /// Replace the multiplexer targets used by the next snapshot.
+    pub fn set_multiplexer_targets(&mut self, targets: MultiplexerTargets) {
+        self.multiplexer_targets = targets;
+    }
+
+    /// Replace the workspaces listed as rows by the next snapshot.
+    pub fn set_workspaces(&mut self, workspaces: Vec<Workspace>) {
+        self.workspaces = workspaces;
+    }
+
+    /// Replace the outputs listed as rows by the next snapshot and offered
+    /// by Ctrl+O.
+    pub fn set_outputs(&mut self, outputs: Vec<Output>) {
+        self.outputs = outputs;
+    }
+
+    /// The items the next snapshot lists after the windows: workspaces,
+    /// then outputs (`output_rows`).
+    fn extra_items(&self, config: &WmConfig) -> Vec<SelectableItem> {
+        let outputs = if config.output_rows {
+            self.outputs.as_slice()
+        } else {
+            &[]
+        };
+        let workspaces = self
+            .workspaces
+            .iter()
+            .cloned()
+            .map(SelectableItem::Workspace);
+        workspaces
+            .chain(outputs.iter().cloned().map(SelectableItem::Output))
+            .collect()
+    }
+
+    /// Replace the threshold learned from recorded Alt releases.
+    pub fn set_learned_quick_switch(&mut self, ms: Option<u32>) {
+        self.learned_quick_switch_ms = ms;
+    }
+
+    /// The Alt+Tab dwell: the learned threshold under
+    /// `adaptive_quick_switch` once there is one, else the configured one.
+    fn quick_switch_ms(&self, config: &WmConfig) -> u32 {
+        self.learned_quick_switch_ms
+            .filter(|_| config.adaptive_quick_switch)
+            .unwrap_or(config.quick_switch_threshold_ms)
+    }
+
+    /// Show `walkthrough` (the first-run pages) on the next activation.
+    pub fn start_walkthrough(&mut self, walkthrough: Walkthrough) {
+        self.walkthrough = Some(walkthrough);
+    }
+
+    /// Record whether the compositor supports window activation.
+    pub fn set_can_activate(&mut self, can_activate: bool) {
+        self.can_activate = can_activate;
+    }
+
+    /// Record which placement verbs the compositor supports.
+    pub fn set_placements(&mut self, can_maximize: bool, can_tile: bool) {
+        self.can_maximize = can_maximize;
+        self.can_tile = can_tile;
+    }
+
+    /// Record which window toggles the compositor supports.
+    pub fn set_toggles(&mut self, can_stick: bool, can_keep_above: bool) {
+        self.can_stick = can_stick;
+        self.can_keep_above = can_keep_above;
+    }
+
+    /// Record whether the compositor can move windows between outputs.
+    pub fn set_can_move_to_output(&mut self, can_move_to_output: bool) {
+        self.can_move_to_output = can_move_to_output;
+    }
+
     /// Returns the next deadline the main loop should wake for, if any.
     pub fn next_deadline(&self) -> Option<Instant> {
-        match &self.phase {
+        let dwell = match &self.phase {
             Phase::Armed {
                 entered_at,
                 dwell_ms,
                 ..
             } => Some(*entered_at + std::time::Duration::from_millis(*dwell_ms as u64)),
             _ => None,
+        };
+        dwell
+            .into_iter()
+            .chain(self.inactivity_deadline())
+            .chain(self.dead_input_deadline())
+            .chain(self.auto_activate_deadline())
+            .min()
+    }
+
+    /// When the single remaining match is activated, if enabled and the
+    /// input has narrowed to one window.
+    fn auto_activate_deadline(&self) -> Option<Instant> {
+        let delay = self.auto_activate_ms?;
+        self.single_match_at
+            .map(|at| at + std::time::Duration::from_millis(delay.into()))
+    }
+
+    /// When dead-end input is cleared, if enabled and the input is dead.
+    fn dead_input_deadline(&self) -> Option<Instant> {
+        if self.dead_input_clear_ms == 0 {
+            return None;
+        }
+        self.dead_input_at
+            .map(|at| at + std::time::Duration::from_millis(self.dead_input_clear_ms.into()))
+    }
+
+    /// When the visible overlay is dismissed for inactivity, if enabled.
+    fn inactivity_deadline(&self) -> Option<Instant> {
+        if self.overlay_timeout_ms == 0 || self.is_idle() {
+            return None;
         }
+        Some(self.last_activity + std::time::Duration::from_millis(self.overlay_timeout_ms.into()))
     }
 
     /// Is the controller idle?
@@ -418,11 +1245,107 @@ impl OverlayController {
         matches!(self.phase, Phase::Idle)
     }
 
+    /// Mode of the current cycle, selecting its `[wm.switcher]` or
+    /// `[wm.launcher]` overrides.
+    pub fn invocation(&self) -> InvocationMode {
+        self.invocation
+    }
+
+    /// The current phase for a crash report: row count, selection and
+    /// input length, never window titles, typed text or launch commands.
+    pub fn redacted_state(&self) -> String {
+        let phase = match &self.phase {
+            Phase::Idle => "Idle".to_string(),
+            Phase::Armed {
+                snap,
+                selection,
+                input,
+                dwell_ms,
+                pending_launch,
+                ..
+            } => format!(
+                "Armed {{ rows: {}, selection: {selection}, input_len: {}, dwell_ms: {dwell_ms}, pending_launch: {} }}",
+                snap.windows.len(),
+                input.chars().count(),
+                pending_launch.is_some()
+            ),
+            Phase::Picking {
+                snap,
+                selection,
+                input,
+                pending_launch,
+            } => format!(
+                "Picking {{ rows: {}, selection: {selection}, input_len: {}, pending_launch: {} }}",
+                snap.windows.len(),
+                input.chars().count(),
+                pending_launch.is_some()
+            ),
+            Phase::Confirming {
+                action: ConfirmAction::Close { windows },
+                snap,
+                selection,
+                input,
+            } => format!(
+                "Confirming {{ close: {}, rows: {}, selection: {selection}, input_len: {} }}",
+                windows.len(),
+                snap.windows.len(),
+                input.chars().count()
+            ),
+            Phase::Launching => "Launching".to_string(),
+            Phase::LaunchError => "LaunchError".to_string(),
+            Phase::Unlocking {
+                profiles_to_unlock,
+                current_index,
+                unlock_mode,
+                ..
+            } => format!(
+                "Unlocking {{ profiles: {}, current: {current_index}, mode: {unlock_mode:?} }}",
+                profiles_to_unlock.len()
+            ),
+        };
+        format!("{phase}, invocation: {:?}", self.invocation)
+    }
+
     /// Force the controller back to Idle, discarding all in-flight state.
     /// Used when the overlay thread dies and must be respawned.
     pub fn force_idle(&mut self) {
         self.phase = Phase::Idle;
         self.last_ipc_advance = None;
+        self.palette_confirm = None;
+        self.dead_input_at = None;
+        self.single_match_at = None;
+        self.peek = None;
+        self.keep_open = false;
+    }
+
+    /// The picker rows and hints an activation would show for `windows`,
+    /// for rendering a warm-up frame. Does not change the phase.
+    pub fn preview_picker(
+        &self,
+        windows: &[Window],
+        config: &WmConfig,
+    ) -> (Vec<WindowInfo>, Vec<String>) {
+        // May run before the first `handle` with this config.
+        let compiled;
+        let titles = if config.title_rules == self.title_rules {
+            &self.titles
+        } else {
+            compiled = TitleRewriter::new(&config.title_rules);
+            &compiled
+        };
+        let snapshot = Snapshot::build(
+            windows,
+            config,
+            titles,
+            &self.multiplexer_targets,
+            &self.extra_items(config),
+        );
+        let snapshot = if self.can_activate {
+            snapshot
+        } else {
+            snapshot.without_windows()
+        };
+        (snapshot.overlay_windows, snapshot.hints)
     }
 
     /// Return the profile currently being unlocked, if in the unlocking phase.
@@ -467,21 +1390,201 @@ impl OverlayController {
 
     /// Handle an event, returning commands to execute.
     pub fn handle(&mut self, event: Event, windows: &[Window], config: &WmConfig) -> Vec<Command> {
+        // A launcher activation while the switcher is open upgrades the
+        // cycle to launcher mode instead of cycling the selection.
+        let upgrade = self.invocation == InvocationMode::Switcher
+            && event.invocation_mode() == Some(InvocationMode::Launcher)
+            && matches!(self.phase, Phase::Armed { .. } | Phase::Picking { .. })
+            && !self.session_locked;
+        if (self.is_idle() || upgrade)
+            && let Some(mode) = event.invocation_mode()
+        {
+            self.invocation = mode;
+        }
+        let config = config.for_mode(self.invocation);
+        let config = &*config;
+        self.overlay_timeout_ms = config.overlay_timeout_ms;
+        self.dead_input_clear_ms = config.dead_input_clear_ms;
+        self.auto_activate_ms = config
+            .auto_activate_single_match
+            .then_some(config.activation_delay_ms);
+        self.new_instance_suffix = hints::new_instance_suffix(&config.launch_new_instance_key);
+        self.relaunch_guard_ms = config.relaunch_guard_ms;
+        if config.title_rules != self.title_rules {
+            self.titles = TitleRewriter::new(&config.title_rules);
+            self.title_rules.clone_from(&config.title_rules);
+        }
+        // Deadline expiries and compositor changes are not user activity.
+        let is_activity = !matches!(
+            event,
+            Event::DwellTimeout | Event::WindowChanged { .. } | Event::MultiplexerListed(_)
+        );
+        if is_activity {
+            self.last_activity = Instant::now();
+        }
+
+        let mut cmds = if upgrade {
+            let backward = matches!(event, Event::ActivateLauncherBackward);
+            self.upgrade_to_launcher(windows, config, backward)
+        } else {
+            self.dispatch(event, windows, config)
+        };
+        // Each key that leaves the input dead restarts the clear delay.
+        if !self.input_is_dead() {
+            self.dead_input_at = None;
+        } else if is_activity || self.dead_input_at.is_none() {
+            self.dead_input_at = Some(Instant::now());
+        }
+        // Likewise each key that leaves a single match restarts its delay.
+        if self.auto_activate_ms.is_none() || !self.input_is_single_match() {
+            self.single_match_at = None;
+        } else if is_activity || self.single_match_at.is_none() {
+            self.single_match_at = Some(Instant::now());
+        }
+        self.settle_peek(&mut cmds);
+        cmds
+    }
+
+    fn dispatch(&mut self, event: Event, windows: &[Window], config: &WmConfig) -> Vec<Command> {
+        if matches!(event, Event::DwellTimeout) {
+            let now = Instant::now();
+            if self.inactivity_deadline().is_some_and(|d| now >= d) {
+                tracing::info!(
+                    timeout_ms = self.overlay_timeout_ms,
+                    "overlay inactive, dismissing"
+                );
+                return self.on_escape();
+            }
+            if self.dead_input_deadline().is_some_and(|d| now >= d) {
+                return self.clear_dead_input();
+            }
+            if self.auto_activate_deadline().is_some_and(|d| now >= d) {
+                self.single_match_at = None;
+                tracing::info!(
+                    delay_ms = self.auto_activate_ms,
+                    "input matches one window, activating"
+                );
+                return self.on_confirm();
+            }
+        }
+        if let Event::MultiplexerListed(targets) = event {
+            return self.on_multiplexer_listed(targets, windows, config);
+        }
+        if let Event::SessionLock(locked) = event {
+            self.session_locked = locked;
+            if locked && !self.is_idle() {
+                tracing::info!("session locked, dismissing overlay");
+                return self.on_escape();
+            }
+            return Vec::new();
+        }
+        if matches!(event, Event::SessionLockRequested) {
+            if self.is_idle() {
+                return Vec::new();
+            }
+            tracing::info!("session lock requested, dismissing overlay");
+            return self.on_escape();
+        }
+        if self.session_locked
+            && matches!(
+                event,
+                Event::Activate
+                    | Event::ActivateBackward
+                    | Event::ActivateLauncher
+                    | Event::ActivateLauncherBackward
+            )
+        {
+            tracing::info!("session locked, ignoring activation");
+            return Vec::new();
+        }
+        if matches!(self.phase, Phase::Confirming { .. }) {
+            return self.on_confirming(event, windows, config);
+        }
+        if !matches!(self.phase, Phase::Picking { .. }) {
+            self.row_menu = None;
+            self.help_open = false;
+            self.walkthrough_open = false;
+        }
+        if self.walkthrough_open {
+            return self.on_walkthrough_event(event, windows, config);
+        }
+        if self.help_open {
+            return self.on_help_event(event, windows, config);
+        }
+        if self.row_menu.is_some() {
+            return self.on_row_menu_event(event, windows, config);
+        }
+        if !matches!(event, Event::DwellTimeout)
+            && std::mem::take(&mut self.mark_pending)
+            && let Event::Char(ch) = event
+        {
+            return self.on_mark_letter(ch);
+        }
+        if !matches!(event, Event::DwellTimeout)
+            && std::mem::take(&mut self.output_pending)
+            && let Event::Char(ch) = event
+        {
+            return self.on_output_digit(ch);
+        }
+        if let Event::Char(ch) = event
+            && self.input_is_empty()
+            && let Some(action) = actions::typed(ch)
+        {
+            return self.dispatch(action.event.clone(), windows, config);
+        }
+
         match event {
-            Event::Activate => self.on_activate(windows, config, ActivationMode::Forward),
-            Event::ActivateBackward => self.on_activate(windows, config, ActivationMode::Backward),
-            Event::ActivateLauncher => self.on_activate(windows, config, ActivationMode::Launcher),
+            Event::Activate => {
+                let cmds = self.on_activate(windows, config, ActivationMode::Forward);
+                self.open_walkthrough(cmds)
+            }
+            Event::ActivateBackward => {
+                let cmds = self.on_activate(windows, config, ActivationMode::Backward);
+                self.open_walkthrough(cmds)
+            }
+            Event::ActivateLauncher => {
+                let cmds = self.on_activate(windows, config, ActivationMode::Launcher);
+                self.open_walkthrough(cmds)
+            }
             Event::ActivateLauncherBackward => {
-                self.on_activate(windows, config, ActivationMode::LauncherBackward)
+                let cmds = self.on_activate(windows, config, ActivationMode::LauncherBackward);
+                self.open_walkthrough(cmds)
             }
             Event::ModifierReleased => self.on_modifier_released(),
             Event::Char(ch) => self.on_char(ch),
+            Event::Prefill(text) => text.chars().flat_map(|ch| self.on_char(ch)).collect(),
             Event::Backspace => self.on_backspace(),
             Event::SelectionDown => self.on_selection_down(),
             Event::SelectionUp => self.on_selection_up(),
             Event::Confirm => self.on_confirm(),
             Event::Escape | Event::Dismiss => self.on_escape(),
+            Event::CopyTitle => self.on_copy(|w| w.title.clone()),
+            Event::CopyAppId => self.on_copy(|w| w.app_id.to_string()),
+            Event::CloseWindow => self.on_close(false, windows, config),
+            Event::CloseAppWindows => self.on_close(true, windows, config),
+            Event::Peek => self.on_peek(),
+            Event::KeepOpen => self.on_keep_open(),
+            Event::LaunchNew => self.on_launch_new(),
+            Event::Refresh => self.on_refresh(windows, config),
+            Event::WindowChanged {
+                window_id,
+                app_id,
+                title,
+            } => self.on_window_changed(window_id, &app_id, &title),
+            Event::Place(placement) => self.on_place(placement),
+            Event::Toggle(toggle) => self.on_toggle(toggle),
+            Event::Mark => self.on_mark(),
+            Event::MoveToOutput => self.on_move_to_output(),
+            Event::Timeline => self.on_timeline(),
+            Event::RowMenu => self.on_row_menu(),
+            Event::Help => self.on_help(),
+            Event::SessionLock(_) | Event::SessionLockRequested | Event::MultiplexerListed(_) => {
+                unreachable!("handled above")
+            }
             Event::DwellTimeout => self.on_dwell_timeout(),
+            Event::ActivationFailed { window_id, error } => {
+                self.on_activation_failed(&window_id, error, windows, config)
+            }
             Event::LaunchResult {
                 success,
                 error,
@@ -522,7 +1625,32 @@ impl OverlayController {
         // cycle selection and transition without borrow conflicts.
         match std::mem::replace(&mut self.phase, Phase::Idle) {
             Phase::Idle => {
-                let snap = Snapshot::build(windows, config);
+                let inhibited = config.shortcut_inhibit != ShortcutInhibit::Off
+                    && windows.iter().any(|w| {
+                        w.is_focused
+                            && config
+                                .shortcut_inhibit_apps
+                                .iter()
+                                .any(|p| hints::app_matches(p, w.app_id.as_str()))
+                    });
+                if inhibited && config.shortcut_inhibit == ShortcutInhibit::Skip {
+                    tracing::info!("focused window grabs shortcuts, ignoring activation");
+                    return Vec::new();
+                }
+                self.palette_confirm = None;
+                self.cycle_started = Instant::now();
+                self.release_spent = false;
+                self.keep_open = false;
+                let mut snap = Snapshot::build(
+                    windows,
+                    config,
+                    &self.titles,
+                    &self.multiplexer_targets,
+                    &self.extra_items(config),
+                );
+                if !self.can_activate {
+                    snap = snap.without_windows();
+                }
 
                 // Launcher mode always activates — it's a launcher, not just
                 // a switcher. Zero windows is a valid state for launching apps.
@@ -534,7 +1662,13 @@ impl OverlayController {
                 {
                     return Vec::new();
                 }
+                if inhibited {
+                    return self.show_inhibit_warning(snap);
+                }
 
+                let quick_switch_ms = self.quick_switch_ms(config);
+                self.time_release =
+                    config.adaptive_quick_switch && matches!(mode, ActivationMode::Forward);
                 match mode {
                     ActivationMode::Forward => {
                         let selection = snap.initial_forward();
@@ -543,12 +1677,15 @@ impl OverlayController {
                             snap,
                             selection,
                             input: String::new(),
-                            dwell_ms: config.quick_switch_threshold_ms,
+                            dwell_ms: quick_switch_ms,
                             pending_launch: None,
                         };
                         vec![
                             Command::ShowBorder {
                                 zero_window_launcher: false,
+                                delay_indicator_ms: config
+                                    .show_delay_indicator
+                                    .then_some(quick_switch_ms),
                             },
                             Command::Publish(EventKind::WmOverlayShown, SecurityLevel::Internal),
                         ]
@@ -560,12 +1697,15 @@ impl OverlayController {
                             snap,
                             selection,
                             input: String::new(),
-                            dwell_ms: config.quick_switch_threshold_ms,
+                            dwell_ms: quick_switch_ms,
                             pending_launch: None,
                         };
                         vec![
                             Command::ShowBorder {
                                 zero_window_launcher: false,
+                                delay_indicator_ms: config
+                                    .show_delay_indicator
+                                    .then_some(quick_switch_ms),
                             },
                             Command::Publish(EventKind::WmOverlayShown, SecurityLevel::Internal),
                         ]
@@ -592,6 +1732,9 @@ impl OverlayController {
                         vec![
                             Command::ShowBorder {
                                 zero_window_launcher,
+                                // The launcher dwell is at most 100ms; a ring
+                                // would only flicker.
+                                delay_indicator_ms: None,
                             },
                             Command::Publish(EventKind::WmOverlayShown, SecurityLevel::Internal),
                         ]
@@ -672,46 +1815,149 @@ impl OverlayController {
                 };
                 cmds
             }
-            other @ (Phase::Launching | Phase::LaunchError | Phase::Unlocking { .. }) => {
+            other @ (Phase::Confirming { .. }
+            | Phase::Launching
+            | Phase::LaunchError
+            | Phase::Unlocking { .. }) => {
                 self.phase = other;
                 Vec::new()
             }
         }
     }
 
-    // -----------------------------------------------------------------------
-    // Modifier released
-    // -----------------------------------------------------------------------
-
-    fn on_modifier_released(&mut self) -> Vec<Command> {
-        match std::mem::replace(&mut self.phase, Phase::Idle) {
+    /// Reopen the switcher's cycle as a launcher: rebuild the snapshot
+    /// with the launcher overrides and show the full picker, keeping any
+    /// typed input.
+    fn upgrade_to_launcher(
+        &mut self,
+        windows: &[Window],
+        config: &WmConfig,
+        backward: bool,
+    ) -> Vec<Command> {
+        let (input, pending_launch) = match std::mem::replace(&mut self.phase, Phase::Idle) {
             Phase::Armed {
-                entered_at,
-                dwell_ms,
-                selection,
                 input,
-                snap,
                 pending_launch,
-            } => {
-                // Pending launch takes priority over window activation.
-                if let Some(launch) = pending_launch {
-                    self.phase = Phase::Launching;
-                    return vec![
-                        Command::ShowLaunching,
-                        Command::LaunchApp {
-                            command: launch.command,
-                            tags: launch.tags,
-                            launch_args: launch.launch_args,
-                        },
-                    ];
-                }
-
-                if snap.windows.is_empty() {
-                    let cmds = vec![Command::ShowPicker {
-                        windows: snap.overlay_windows.clone(),
-                        hints: snap.hints.clone(),
-                    }];
-                    self.phase = Phase::Picking {
+                ..
+            }
+            | Phase::Picking {
+                input,
+                pending_launch,
+                ..
+            } => (input, pending_launch),
+            other => {
+                self.phase = other;
+                return Vec::new();
+            }
+        };
+        tracing::info!("launcher activation while switcher open, upgrading to launcher");
+        let mut snap = Snapshot::build(
+            windows,
+            config,
+            &self.titles,
+            &self.multiplexer_targets,
+            &self.extra_items(config),
+        );
+        if !self.can_activate {
+            snap = snap.without_windows();
+        }
+        let selection = if backward {
+            snap.initial_backward()
+        } else {
+            snap.initial_forward()
+        };
+        let cmds = vec![
+            Command::ShowPicker {
+                windows: snap.overlay_windows.clone(),
+                hints: snap.hints.clone(),
+            },
+            Command::UpdatePicker {
+                input: input.clone(),
+                selection,
+            },
+            Command::ResetGrace,
+        ];
+        self.phase = Phase::Picking {
+            snap,
+            selection,
+            input,
+            pending_launch,
+        };
+        cmds
+    }
+
+    // -----------------------------------------------------------------------
+    // Modifier released
+    // -----------------------------------------------------------------------
+
+    fn on_modifier_released(&mut self) -> Vec<Command> {
+        if self.release_spent {
+            return Vec::new();
+        }
+        // Palette input never commits implicitly — only Enter acts on it.
+        if self.palette_input().is_some() {
+            return Vec::new();
+        }
+        // A search with no results has nothing visible to commit.
+        if self.search_matches().is_some_and(|m| m.is_empty()) {
+            return Vec::new();
+        }
+        let untouched = self.untouched();
+        let mut cmds = self.release_to_commit();
+        // A plain Alt+Tab's hold time, for `adaptive_quick_switch`.
+        if untouched
+            && !matches!(self.phase, Phase::Armed { .. } | Phase::Picking { .. })
+            && std::mem::take(&mut self.time_release)
+        {
+            cmds.push(Command::RecordReleaseTime(self.cycle_started.elapsed()));
+        }
+        cmds
+    }
+
+    /// Whether the overlay is still as activation left it: the initial
+    /// selection, nothing typed, no launch staged.
+    fn untouched(&self) -> bool {
+        match &self.phase {
+            Phase::Armed {
+                snap,
+                selection,
+                input,
+                pending_launch,
+                ..
+            }
+            | Phase::Picking {
+                snap,
+                selection,
+                input,
+                pending_launch,
+            } => {
+                *selection == snap.initial_forward() && input.is_empty() && pending_launch.is_none()
+            }
+            _ => false,
+        }
+    }
+
+    fn release_to_commit(&mut self) -> Vec<Command> {
+        match std::mem::replace(&mut self.phase, Phase::Idle) {
+            Phase::Armed {
+                entered_at,
+                dwell_ms,
+                selection,
+                input,
+                snap,
+                pending_launch,
+            } => {
+                // Pending launch takes priority over window activation.
+                if let Some(launch) = pending_launch {
+                    return self.commit_launch(launch);
+                }
+
+                if snap.windows.is_empty() {
+                    let cmds = vec![Command::ShowPicker {
+                        windows: snap.overlay_windows.clone(),
+                        hints: snap.hints.clone(),
+                    }];
+                    self.phase = Phase::Picking {
                         snap,
                         selection,
                         input,
@@ -738,15 +1984,7 @@ impl OverlayController {
             } => {
                 // Pending launch takes priority over window activation.
                 if let Some(launch) = pending_launch {
-                    self.phase = Phase::Launching;
-                    return vec![
-                        Command::ShowLaunching,
-                        Command::LaunchApp {
-                            command: launch.command,
-                            tags: launch.tags,
-                            launch_args: launch.launch_args,
-                        },
-                    ];
+                    return self.commit_launch(launch);
                 }
 
                 if snap.windows.is_empty() {
@@ -762,6 +2000,7 @@ impl OverlayController {
                 self.activate_index(selection, &snap)
             }
             other @ (Phase::Idle
+            | Phase::Confirming { .. }
             | Phase::Launching
             | Phase::LaunchError
             | Phase::Unlocking { .. }) => {
@@ -771,8 +2010,52 @@ impl OverlayController {
         }
     }
 
-    /// Activate window at `index`. Honors any selection including origin.
+    /// Activate the item at `index`. Honors any selection including origin.
     fn activate_index(&mut self, index: usize, snap: &Snapshot) -> Vec<Command> {
+        match snap.items.get(index) {
+            Some(SelectableItem::Workspace(workspace)) => self.activate_workspace(index, workspace),
+            Some(SelectableItem::Output(output)) => self.activate_output(index, output, snap),
+            Some(SelectableItem::Window | SelectableItem::Multiplexer(_)) | None => {
+                self.activate_window(index, snap)
+            }
+        }
+    }
+
+    /// Switch to a workspace row's workspace.
+    fn activate_workspace(&mut self, index: usize, workspace: &Workspace) -> Vec<Command> {
+        self.phase = Phase::Idle;
+        self.last_ipc_advance = None;
+        tracing::info!(index, workspace = %workspace.name, "switching workspace");
+        vec![
+            Command::Hide,
+            Command::ActivateWorkspace {
+                workspace: workspace.clone(),
+            },
+            Command::Publish(EventKind::WmOverlayDismissed, SecurityLevel::Internal),
+        ]
+    }
+
+    /// Focus the most recently used window on an output row's output.
+    fn activate_output(&mut self, index: usize, output: &Output, snap: &Snapshot) -> Vec<Command> {
+        if let Some(window) = outputs::first_window_on(&snap.windows, &snap.items, &output.id) {
+            tracing::info!(index, output = %output.name, "focusing window on output");
+            return self.activate_window(window, snap);
+        }
+        self.phase = Phase::Idle;
+        self.last_ipc_advance = None;
+        tracing::info!(index, output = %output.name, "no listed window on output");
+        vec![
+            Command::Hide,
+            Command::Publish(EventKind::WmOverlayDismissed, SecurityLevel::Internal),
+        ]
+    }
+
+    /// Activate the window of row `index`, switching the multiplexer too
+    /// for tmux/zellij rows.
+    fn activate_window(&mut self, index: usize, snap: &Snapshot) -> Vec<Command> {
+        if self.keep_open && index < snap.windows.len() {
+            return self.activate_kept_open(index, snap);
+        }
         self.phase = Phase::Idle;
         self.last_ipc_advance = None;
 
@@ -784,8 +2067,13 @@ impl OverlayController {
                 "activating window"
             );
             vec![
+                Command::EnsureWindowAlive { window_id: w.id },
                 Command::HideAndSync,
-                Command::ActivateWindow { window: w.clone() },
+                Command::ActivateWindow {
+                    window: w.clone(),
+                    multiplexer: snap.multiplexer(index),
+                },
+                self.record_usage(UsageKind::Activate, Some(w.app_id.to_string())),
                 Command::Publish(EventKind::WmOverlayDismissed, SecurityLevel::Internal),
             ]
         } else {
@@ -796,6 +2084,150 @@ impl OverlayController {
         }
     }
 
+    /// Keep-open mode: activate the window at `index` behind the picker and
+    /// ask for the window list again, so the picker can show the new focus.
+    fn activate_kept_open(&mut self, index: usize, snap: &Snapshot) -> Vec<Command> {
+        let window = snap.windows[index].clone();
+        tracing::info!(
+            index,
+            target = %window.id,
+            app_id = %window.app_id,
+            "activating window, picker kept open"
+        );
+        let cmds = vec![
+            Command::ActivateWindow {
+                window: window.clone(),
+                multiplexer: snap.multiplexer(index),
+            },
+            self.record_usage(UsageKind::Activate, Some(window.app_id.to_string())),
+            Command::RefreshPicker,
+        ];
+        self.phase = Phase::Picking {
+            snap: snap.clone(),
+            selection: index,
+            input: String::new(),
+            pending_launch: None,
+        };
+        cmds
+    }
+
+    /// Run a staged launch on modifier release or Enter.
+    fn commit_launch(&mut self, launch: PendingLaunch) -> Vec<Command> {
+        self.phase = Phase::Launching;
+        self.launch_in_flight = None;
+        // Shell commands are not logged: they may carry arguments.
+        let usage = match &launch {
+            PendingLaunch::App { command, .. } => {
+                self.forget_old_launches();
+                self.recent_launches.push((command.clone(), Instant::now()));
+                self.launch_in_flight = Some(command.clone());
+                Some(self.record_usage(UsageKind::Launch, Some(command.clone())))
+            }
+            PendingLaunch::Shell { .. } => None,
+            PendingLaunch::Starting { command } => {
+                tracing::info!(%command, "app still starting, not launching it again");
+                self.phase = Phase::Idle;
+                None
+            }
+        };
+        let mut cmds = launch.into_commands();
+        cmds.extend(usage);
+        cmds
+    }
+
+    /// Drop launches older than `relaunch_guard_ms`.
+    fn forget_old_launches(&mut self) {
+        let guard = std::time::Duration::from_millis(u64::from(self.relaunch_guard_ms));
+        self.recent_launches.retain(|(_, at)| at.elapsed() < guard);
+    }
+
+    /// Whether `command` was launched within `relaunch_guard_ms`.
+    fn recently_launched(&self, command: &str) -> bool {
+        let guard = std::time::Duration::from_millis(u64::from(self.relaunch_guard_ms));
+        self.recent_launches
+            .iter()
+            .any(|(launched, at)| launched == command && at.elapsed() < guard)
+    }
+
+    fn record_usage(&self, kind: UsageKind, app: Option<String>) -> Command {
+        Command::RecordUsage {
+            kind,
+            app,
+            elapsed: self.cycle_started.elapsed(),
+        }
+    }
+
+    /// Fresh activation over a window that grabs shortcuts, which may have
+    /// acted on the same Alt+Tab: skip Armed and show the picker with a
+    /// warning, and let only an explicit pick commit.
+    fn show_inhibit_warning(&mut self, snap: Snapshot) -> Vec<Command> {
+        tracing::info!("focused window grabs shortcuts, showing picker with warning");
+        self.release_spent = true;
+        let cmds = vec![
+            Command::ShowBorder {
+                zero_window_launcher: !snap.has_targets(),
+                delay_indicator_ms: None,
+            },
+            Command::ShowRetry {
+                windows: snap.overlay_windows.clone(),
+                hints: snap.hints.clone(),
+                message: SHORTCUT_INHIBIT_NOTICE.into(),
+            },
+            Command::Publish(EventKind::WmOverlayShown, SecurityLevel::Internal),
+        ];
+        // `ShowRetry` highlights the first row.
+        self.phase = Phase::Picking {
+            selection: 0,
+            snap,
+            input: String::new(),
+            pending_launch: None,
+        };
+        cmds
+    }
+
+    /// The committed window vanished between enumeration and activation.
+    /// The overlay is still mapped: rebuild the snapshot from the fresh
+    /// window list and stay open in Picking so the user can choose again.
+    fn on_activation_failed(
+        &mut self,
+        window_id: &WindowId,
+        error: Option<String>,
+        windows: &[Window],
+        config: &WmConfig,
+    ) -> Vec<Command> {
+        if !matches!(self.phase, Phase::Idle) {
+            return Vec::new();
+        }
+        let snap = Snapshot::build(
+            windows,
+            config,
+            &self.titles,
+            &self.multiplexer_targets,
+            &self.extra_items(config),
+        );
+        if !snap.has_targets() {
+            tracing::info!(target = %window_id, "selected window not activated, no windows left");
+            return vec![
+                Command::Hide,
+                Command::Publish(EventKind::WmOverlayDismissed, SecurityLevel::Internal),
+            ];
+        }
+        tracing::info!(target = %window_id, "selected window not activated, re-showing picker");
+        let reason = error.unwrap_or_else(|| "That window has closed".into());
+        let cmds = vec![Command::ShowRetry {
+            windows: snap.overlay_windows.clone(),
+            hints: snap.hints.clone(),
+            message: format!("{reason} \u{2014} pick another"),
+        }];
+        self.phase = Phase::Picking {
+            selection: snap.initial_forward(),
+            snap,
+            input: String::new(),
+            pending_launch: None,
+        };
+        cmds
+    }
+
     // -----------------------------------------------------------------------
     // Dwell timeout — transition Armed → Picking
     // -----------------------------------------------------------------------
@@ -833,20 +2265,59 @@ impl OverlayController {
     // -----------------------------------------------------------------------
 
     fn on_char(&mut self, ch: char) -> Vec<Command> {
+        self.palette_confirm = None;
         match &mut self.phase {
-            Phase::Armed { input, .. } | Phase::Picking { input, .. } => {
+            Phase::Armed { input, snap, .. } | Phase::Picking { input, snap, .. } => {
                 // Centralised character filter: only alphanumeric chars are valid
                 // for launcher search input. All printable chars pass through the
                 // overlay/IPC layers; filtering happens here so every input path
-                // shares the same policy.
-                if !ch.is_alphanumeric() {
+                // shares the same policy. Command-palette input (`=`/`>` prefix)
+                // and search input (`/` prefix) accept any printable char.
+                let is_palette = snap.command_palette
+                    && (palette::parse(input).is_some()
+                        || (input.is_empty()
+                            && matches!(ch, palette::CALC_PREFIX | palette::SHELL_PREFIX)));
+                let is_search = !is_palette
+                    && (search::parse(input).is_some()
+                        || (input.is_empty() && ch == search::SEARCH_PREFIX));
+                let max_len = if is_palette {
+                    MAX_PALETTE_INPUT_LENGTH
+                } else {
+                    MAX_INPUT_LENGTH
+                };
+                let printable_only = is_palette || is_search;
+                // The new-instance key may end a hint, once per instance.
+                let new_instance = Some(ch) == self.new_instance_suffix
+                    && !input.is_empty()
+                    && hints::new_instance_count(input, self.new_instance_suffix)
+                        < hints::MAX_NEW_INSTANCES;
+                if (printable_only && ch.is_control())
+                    || (!printable_only && !ch.is_alphanumeric() && !new_instance)
+                {
                     return Vec::new();
                 }
-                if input.len() >= MAX_INPUT_LENGTH {
+                if input.len() >= max_len {
                     return Vec::new();
                 }
+                // A hint key on empty input, or pressed again, cycles its
+                // app's windows instead of extending the hint.
+                let repeated =
+                    input.is_empty() || input.to_lowercase() == ch.to_lowercase().to_string();
+                if snap.repeat_key_cycles
+                    && !printable_only
+                    && repeated
+                    && hints::next_in_key_group(ch, &snap.hints, None).is_some()
+                {
+                    return self.cycle_key_group(ch);
+                }
                 input.push(ch);
-                self.check_hint_or_launch()
+                if is_palette {
+                    self.update_palette()
+                } else if is_search {
+                    self.update_search()
+                } else {
+                    self.check_hint_or_launch()
+                }
             }
             Phase::Unlocking {
                 unlock_mode: UnlockMode::Password,
@@ -868,6 +2339,94 @@ impl OverlayController {
         }
     }
 
+    /// Whether nothing is typed in the picker (or behind the armed border).
+    fn input_is_empty(&self) -> bool {
+        match &self.phase {
+            Phase::Armed { input, .. } | Phase::Picking { input, .. } => input.is_empty(),
+            _ => false,
+        }
+    }
+
+    /// Whether the typed hint input can never resolve. Search and palette
+    /// input is free text and never dead.
+    fn input_is_dead(&self) -> bool {
+        match &self.phase {
+            Phase::Armed {
+                input,
+                snap,
+                pending_launch: None,
+                ..
+            }
+            | Phase::Picking {
+                input,
+                snap,
+                pending_launch: None,
+                ..
+            } => {
+                search::parse(input).is_none()
+                    && !(snap.command_palette && palette::parse(input).is_some())
+                    && hints::is_dead_end(
+                        input,
+                        &snap.hints,
+                        &snap.key_bindings,
+                        self.new_instance_suffix,
+                    )
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether typed hint or search input matches the selected window and
+    /// no other, so `auto_activate_single_match` may commit it. Staged
+    /// launches and palette input never are.
+    fn input_is_single_match(&self) -> bool {
+        let Phase::Picking {
+            input,
+            snap,
+            selection,
+            pending_launch: None,
+        } = &self.phase
+        else {
+            return false;
+        };
+        if input.is_empty() || (snap.command_palette && palette::parse(input).is_some()) {
+            return false;
+        }
+        let only = match search::parse(input) {
+            Some(query) => match search::matching_indices(query, &snap.overlay_windows)[..] {
+                [only] => Some(only),
+                _ => None,
+            },
+            None => match hints::match_input(input, &snap.hints) {
+                MatchResult::Exact(only) => Some(only),
+                MatchResult::Partial(_) | MatchResult::NoMatch => None,
+            },
+        };
+        only == Some(*selection)
+    }
+
+    /// Erase dead-end input so the next key starts a fresh hint.
+    fn clear_dead_input(&mut self) -> Vec<Command> {
+        self.dead_input_at = None;
+        match &mut self.phase {
+            Phase::Armed { input, .. } => {
+                input.clear();
+                Vec::new()
+            }
+            Phase::Picking {
+                input, selection, ..
+            } => {
+                tracing::debug!(input = %input, "clearing input that matches nothing");
+                input.clear();
+                vec![Command::UpdatePicker {
+                    input: String::new(),
+                    selection: *selection,
+                }]
+            }
+            _ => Vec::new(),
+        }
+    }
+
     fn check_hint_or_launch(&mut self) -> Vec<Command> {
         let (input, hints, key_bindings, is_armed) = match &self.phase {
             Phase::Armed { input, snap, .. } => {
@@ -879,6 +2438,34 @@ impl OverlayController {
             _ => return Vec::new(),
         };
 
+        // `f!` stages a new instance of `f`'s app even though it has
+        // windows, `f!!` two.
+        let instances = hints::new_instance_count(&input, self.new_instance_suffix);
+        if instances > 0 {
+            let staged = hints::new_instance_key(&input, self.new_instance_suffix)
+                .and_then(|key| PendingLaunch::app_for_key(key, key_bindings, instances));
+            let notice = match staged {
+                Some((launch, notice)) => {
+                    self.set_pending_launch(launch);
+                    Some(notice)
+                }
+                None => {
+                    self.clear_pending_launch();
+                    None
+                }
+            };
+            let mut cmds = if is_armed {
+                self.transition_armed_to_picking()
+            } else {
+                vec![Command::UpdatePicker {
+                    input,
+                    selection: self.current_selection(),
+                }]
+            };
+            cmds.extend(notice);
+            return cmds;
+        }
+
         let match_result = hints::match_input(&input, hints);
         tracing::debug!(
             input = %input,
@@ -910,18 +2497,23 @@ impl OverlayController {
             MatchResult::NoMatch => {
                 if input.len() == 1 {
                     let key = input.chars().next().unwrap();
-                    if let Some(cmd) = hints::launch_for_key(key, key_bindings) {
-                        // Stage the launch — do NOT execute yet.
-                        // Commitment happens on Alt release or Enter.
-                        // User can Backspace to cancel or Escape to dismiss.
-                        let command = cmd.to_string();
-                        let tags = hints::tags_for_key(key, key_bindings);
-                        let launch_args = hints::launch_args_for_key(key, key_bindings);
-                        self.set_pending_launch(PendingLaunch {
-                            command: command.clone(),
-                            tags,
-                            launch_args,
-                        });
+                    // Stage the launch — do NOT execute yet.
+                    // Commitment happens on Alt release or Enter.
+                    // User can Backspace to cancel or Escape to dismiss.
+                    let staged = if let Some(command) = hints::command_for_key(key, key_bindings) {
+                        // Command-only binding: shown as the command it runs.
+                        let command = command.to_string();
+                        let message = format!("Run \u{2018}{command}\u{2019}");
+                        Some((
+                            PendingLaunch::Shell { command },
+                            Command::ShowPalette { message },
+                        ))
+                    } else {
+                        PendingLaunch::app_for_key(key, key_bindings, 1)
+                            .map(|staged| self.unless_starting(staged, key))
+                    };
+                    if let Some((launch, notice)) = staged {
+                        self.set_pending_launch(launch);
                         let mut cmds = if is_armed {
                             self.transition_armed_to_picking()
                         } else {
@@ -930,7 +2522,7 @@ impl OverlayController {
                                 selection: self.current_selection(),
                             }]
                         };
-                        cmds.push(Command::ShowLaunchStaged { command });
+                        cmds.push(notice);
                         return cmds;
                     }
                 }
@@ -960,6 +2552,71 @@ impl OverlayController {
         }
     }
 
+    /// A focus-or-launch of an app launched within `relaunch_guard_ms`
+    /// that has no window yet waits for it instead of launching it again.
+    /// `launch_new_instance_key` still launches another.
+    fn unless_starting(
+        &self,
+        staged: (PendingLaunch, Command),
+        key: char,
+    ) -> (PendingLaunch, Command) {
+        let (PendingLaunch::App { command, .. }, _) = &staged else {
+            return staged;
+        };
+        if !self.recently_launched(command) {
+            return staged;
+        }
+        let message = match self.new_instance_suffix {
+            Some(suffix) => format!(
+                "\u{2018}{command}\u{2019} is starting; type {key}{suffix} to launch another"
+            ),
+            None => format!("\u{2018}{command}\u{2019} is starting"),
+        };
+        (
+            PendingLaunch::Starting {
+                command: command.clone(),
+            },
+            Command::ShowPalette { message },
+        )
+    }
+
+    /// `repeat_key_cycles`: a hint key selects the first window of its app
+    /// and each further press the next one, wrapping, where the default
+    /// style would need `gg`, `ggg`. The input stays the single key.
+    fn cycle_key_group(&mut self, key: char) -> Vec<Command> {
+        let (Phase::Armed {
+            input,
+            snap,
+            selection,
+            ..
+        }
+        | Phase::Picking {
+            input,
+            snap,
+            selection,
+            ..
+        }) = &mut self.phase
+        else {
+            return Vec::new();
+        };
+        let current = (!input.is_empty()).then_some(*selection);
+        let Some(next) = hints::next_in_key_group(key, &snap.hints, current) else {
+            return Vec::new();
+        };
+        *input = hints::fold_key(key).to_string();
+        *selection = next;
+        let input = input.clone();
+        self.clear_pending_launch();
+        if matches!(self.phase, Phase::Armed { .. }) {
+            self.transition_armed_to_picking()
+        } else {
+            vec![Command::UpdatePicker {
+                input,
+                selection: next,
+            }]
+        }
+    }
+
     fn transition_armed_to_picking(&mut self) -> Vec<Command> {
         match std::mem::replace(&mut self.phase, Phase::Idle) {
             Phase::Armed {
@@ -1033,7 +2690,10 @@ impl OverlayController {
                 input,
                 ..
             } => {
-                if !snap.windows.is_empty() {
+                if let Some(query) = search::parse(input) {
+                    let matches = search::matching_indices(query, &snap.overlay_windows);
+                    *selection = step_within(&matches, *selection, true);
+                } else if !snap.windows.is_empty() {
                     *selection = (*selection + 1) % snap.windows.len();
                 }
                 vec![Command::UpdatePicker {
@@ -1068,7 +2728,10 @@ impl OverlayController {
                 input,
                 ..
             } => {
-                if !snap.windows.is_empty() {
+                if let Some(query) = search::parse(input) {
+                    let matches = search::matching_indices(query, &snap.overlay_windows);
+                    *selection = step_within(&matches, *selection, false);
+                } else if !snap.windows.is_empty() {
                     *selection = if *selection == 0 {
                         snap.windows.len() - 1
                     } else {
@@ -1085,6 +2748,7 @@ impl OverlayController {
     }
 
     fn on_backspace(&mut self) -> Vec<Command> {
+        self.palette_confirm = None;
         match &mut self.phase {
             Phase::Unlocking {
                 unlock_mode: UnlockMode::Password,
@@ -1114,17 +2778,28 @@ impl OverlayController {
                 input,
                 selection,
                 pending_launch,
-                ..
+                snap,
             } => {
                 input.pop();
                 if input.is_empty() {
                     *pending_launch = None;
                 }
-                vec![Command::UpdatePicker {
+                if let Some(query) = search::parse(input) {
+                    select_first_match(query, snap, selection);
+                }
+                let mut cmds = vec![Command::UpdatePicker {
                     input: input.clone(),
                     selection: *selection,
-                }]
-            }
+                }];
+                if snap.command_palette
+                    && let Some(parsed) = palette::parse(input)
+                {
+                    cmds.push(Command::ShowPalette {
+                        message: palette::message(&parsed, false),
+                    });
+                }
+                cmds
+            }
             _ => Vec::new(),
         }
     }
@@ -1149,6 +2824,13 @@ impl OverlayController {
             return vec![Command::SubmitPasswordUnlock { profile }];
         }
 
+        if let Some(input) = self.palette_input() {
+            return self.on_palette_confirm(input);
+        }
+        if self.search_matches().is_some_and(|m| m.is_empty()) {
+            return Vec::new();
+        }
+
         match std::mem::replace(&mut self.phase, Phase::Idle) {
             unlocking @ Phase::Unlocking { .. } => {
                 // Confirm during non-Password unlock modes is a no-op.
@@ -1156,1621 +2838,4627 @@ impl OverlayController {
                 Vec::new()
             }
             Phase::Armed {
-                selection, snap, ..
+                selection,
+                snap,
+                pending_launch,
+                ..
             }
             | Phase::Picking {
-                selection, snap, ..
-            } => self.activate_index(selection, &snap),
+                selection,
+                snap,
+                pending_launch,
+                ..
+            } => match pending_launch {
+                // A staged launch commits on Enter as on Alt release.
+                Some(launch) => self.commit_launch(launch),
+                None => self.activate_index(selection, &snap),
+            },
             Phase::LaunchError => vec![
                 Command::Hide,
                 Command::Publish(EventKind::WmOverlayDismissed, SecurityLevel::Internal),
             ],
-            Phase::Idle | Phase::Launching => Vec::new(),
+            other @ (Phase::Idle | Phase::Confirming { .. } | Phase::Launching) => {
+                self.phase = other;
+                Vec::new()
+            }
         }
     }
 
-    fn on_escape(&mut self) -> Vec<Command> {
-        self.last_ipc_advance = None;
-        match std::mem::replace(&mut self.phase, Phase::Idle) {
-            Phase::Idle => Vec::new(),
-            Phase::Unlocking { .. } => {
-                vec![
-                    Command::ClearPasswordBuffer,
-                    Command::Hide,
-                    Command::Publish(EventKind::WmOverlayDismissed, SecurityLevel::Internal),
-                ]
-            }
-            _ => vec![
-                Command::Hide,
-                Command::Publish(EventKind::WmOverlayDismissed, SecurityLevel::Internal),
-            ],
+    /// Copy a field of the selected window. Only meaningful once the picker
+    /// is visible — in Armed the user cannot see what is selected.
+    fn on_copy(&self, field: impl Fn(&Window) -> String) -> Vec<Command> {
+        match &self.phase {
+            Phase::Picking {
+                selection, snap, ..
+            } => snap
+                .window(*selection)
+                .map(|w| vec![Command::CopyToClipboard { text: field(w) }])
+                .unwrap_or_default(),
+            _ => Vec::new(),
         }
     }
 
     // -----------------------------------------------------------------------
-    // Launch result
+    // Peeking
     // -----------------------------------------------------------------------
 
-    #[allow(clippy::too_many_arguments)]
-    fn on_launch_result(
-        &mut self,
-        success: bool,
-        error: Option<String>,
-        denial: Option<LaunchDenial>,
-        original_command: Option<String>,
-        original_tags: Option<Vec<String>>,
-        original_launch_args: Option<Vec<String>>,
-    ) -> Vec<Command> {
-        if !matches!(self.phase, Phase::Launching) {
+    /// Ctrl+P in the picker: raise the selected window so it can be seen
+    /// behind the overlay before committing. Peeking at the origin is a
+    /// no-op; `settle_peek` raises the origin again once the selection
+    /// moves on.
+    fn on_peek(&mut self) -> Vec<Command> {
+        let Phase::Picking {
+            snap, selection, ..
+        } = &self.phase
+        else {
+            return Vec::new();
+        };
+        let Some(window) = snap.window(*selection) else {
+            return Vec::new();
+        };
+        if snap.origin_index == Some(*selection)
+            || self.peek.as_ref().is_some_and(|p| p.window_id == window.id)
+        {
             return Vec::new();
         }
+        let origin = match self.peek.take() {
+            Some(peek) => peek.origin,
+            None => snap
+                .origin_index
+                .and_then(|i| snap.windows.get(i))
+                .or_else(|| snap.windows.iter().find(|w| w.is_focused))
+                .cloned(),
+        };
+        self.peek = Some(Peek {
+            window_id: window.id,
+            origin,
+        });
+        vec![Command::PeekWindow {
+            window: window.clone(),
+        }]
+    }
 
-        if success {
-            self.phase = Phase::Idle;
-            return vec![
-                Command::Hide,
-                Command::Publish(EventKind::WmOverlayDismissed, SecurityLevel::Internal),
-            ];
+    /// End a peek once the peeked window is no longer selected in the
+    /// picker. Unless the batch commits to a window or launch, the origin is
+    /// raised again so cancelling leaves focus where it was.
+    fn settle_peek(&mut self, cmds: &mut Vec<Command>) {
+        let Some(peek) = &self.peek else {
+            return;
+        };
+        let still_selected = match &self.phase {
+            Phase::Picking {
+                snap, selection, ..
+            } => snap
+                .windows
+                .get(*selection)
+                .is_some_and(|w| w.id == peek.window_id),
+            _ => false,
+        };
+        if still_selected {
+            return;
         }
-
-        // Check for VaultsLocked denial — transition to inline unlock.
-        if let Some(LaunchDenial::VaultsLocked { locked_profiles }) = denial
-            && let (Some(cmd), Some(tags), Some(args)) =
-                (original_command, original_tags, original_launch_args)
-            && let Some(first_profile) = locked_profiles.first()
+        let committed = cmds.iter().any(|c| {
+            matches!(
+                c,
+                Command::ActivateWindow { .. }
+                    | Command::LaunchApp { .. }
+                    | Command::RunShellCommand { .. }
+            )
+        });
+        if let Some(peek) = self.peek.take()
+            && !committed
+            && let Some(origin) = peek.origin
         {
-            let profile = first_profile.clone();
-            self.phase = Phase::Unlocking {
-                profiles_to_unlock: locked_profiles,
-                current_index: 0,
-                password_len: 0,
-                unlock_mode: UnlockMode::AutoAttempt,
-                retry_command: cmd,
-                retry_tags: tags,
-                retry_launch_args: args,
-            };
-            return vec![Command::AttemptAutoUnlock { profile }];
+            cmds.push(Command::PeekWindow { window: origin });
         }
-
-        let message = error.unwrap_or_else(|| "launch failed".into());
-        self.phase = Phase::LaunchError;
-        vec![Command::ShowLaunchError {
-            message,
-            denial: None,
-        }]
     }
 
     // -----------------------------------------------------------------------
-    // Unlock result handlers
+    // Keep-open mode
     // -----------------------------------------------------------------------
 
-    fn on_auto_unlock_result(
-        &mut self,
-        success: bool,
-        profile: TrustProfileName,
-        needs_touch: bool,
-    ) -> Vec<Command> {
-        if !matches!(self.phase, Phase::Unlocking { .. }) {
-            return Vec::new();
+    /// Ctrl+Enter: switch keep-open mode. Turning it on shows the picker if
+    /// it is still Armed and spends the Alt release, so only Enter commits;
+    /// turning it off brings back the normal Enter, which dismisses.
+    fn on_keep_open(&mut self) -> Vec<Command> {
+        let mut cmds = match &self.phase {
+            Phase::Armed { .. } => self.transition_armed_to_picking(),
+            Phase::Picking { .. } => Vec::new(),
+            _ => return Vec::new(),
+        };
+        self.keep_open = !self.keep_open;
+        tracing::info!(keep_open = self.keep_open, "keep-open mode toggled");
+        if self.keep_open {
+            self.release_spent = true;
+            cmds.push(Command::ShowPalette {
+                message: KEEP_OPEN_NOTICE.into(),
+            });
+        } else if let Phase::Picking {
+            input, selection, ..
+        } = &self.phase
+        {
+            cmds.push(Command::UpdatePicker {
+                input: input.clone(),
+                selection: *selection,
+            });
         }
-        if success {
-            return self.advance_to_next_profile_or_retry();
+        cmds
+    }
+
+    /// Shift+Enter: launch a new instance of the app the typed hint names,
+    /// or with no input of the selected window's app, instead of switching
+    /// to one of its windows.
+    fn on_launch_new(&mut self) -> Vec<Command> {
+        if self.palette_input().is_some() {
+            return Vec::new();
         }
-        if needs_touch {
-            if let Phase::Unlocking { unlock_mode, .. } = &mut self.phase {
-                *unlock_mode = UnlockMode::WaitingForTouch;
-            }
-            return vec![Command::ShowTouchPrompt { profile }];
+        let (Phase::Armed {
+            input,
+            snap,
+            selection,
+            ..
         }
-        // Fall back to password.
-        if let Phase::Unlocking { unlock_mode, .. } = &mut self.phase {
-            *unlock_mode = UnlockMode::Password;
+        | Phase::Picking {
+            input,
+            snap,
+            selection,
+            ..
+        }) = &self.phase
+        else {
+            return Vec::new();
+        };
+        if search::parse(input).is_some() {
+            return Vec::new();
         }
-        vec![Command::ShowPasswordPrompt { profile }]
+        let key = if input.is_empty() {
+            snap.windows
+                .get(*selection)
+                .and_then(|w| hints::binding_for_app(w.app_id.as_str(), &snap.key_bindings))
+                .and_then(|(key, _)| key.chars().next())
+        } else {
+            hints::new_instance_key(input, self.new_instance_suffix)
+        };
+        let instances = hints::new_instance_count(input, self.new_instance_suffix).max(1);
+        let Some((launch, _)) =
+            key.and_then(|key| PendingLaunch::app_for_key(key, &snap.key_bindings, instances))
+        else {
+            return vec![Command::ShowPalette {
+                message: "No launch command is bound to this app".into(),
+            }];
+        };
+        tracing::info!(?key, "launching a new instance");
+        self.commit_launch(launch)
     }
 
-    fn on_touch_result(&mut self, success: bool, profile: TrustProfileName) -> Vec<Command> {
-        if !matches!(self.phase, Phase::Unlocking { .. }) {
+    /// Rebuild the kept-open picker from the re-enumerated `windows`. The
+    /// MRU stack now has the activated window on top, so it becomes the
+    /// origin and the window it replaced is selected, as on a fresh Alt+Tab.
+    fn on_refresh(&mut self, windows: &[Window], config: &WmConfig) -> Vec<Command> {
+        let Phase::Picking { snap, .. } = &self.phase else {
+            return Vec::new();
+        };
+        if !self.keep_open {
             return Vec::new();
         }
-        if success {
-            return self.advance_to_next_profile_or_retry();
+        let timeline = snap.timeline.is_some();
+        let mut snap = Snapshot::build(
+            windows,
+            config,
+            &self.titles,
+            &self.multiplexer_targets,
+            &self.extra_items(config),
+        );
+        if timeline {
+            snap.toggle_timeline(mru::now_secs());
         }
-        // Fall back to password on touch failure.
-        if let Phase::Unlocking { unlock_mode, .. } = &mut self.phase {
-            *unlock_mode = UnlockMode::Password;
+        if !snap.has_targets() {
+            self.phase = Phase::Idle;
+            self.last_ipc_advance = None;
+            return vec![
+                Command::Hide,
+                Command::Publish(EventKind::WmOverlayDismissed, SecurityLevel::Internal),
+            ];
         }
-        vec![
-            Command::ShowPasswordPrompt { profile },
-            Command::ShowUnlockError {
-                message: "Touch verification failed".into(),
+        let selection = snap.initial_forward();
+        let cmds = vec![
+            Command::ShowPicker {
+                windows: snap.overlay_windows.clone(),
+                hints: snap.hints.clone(),
             },
-        ]
+            Command::UpdatePicker {
+                input: String::new(),
+                selection,
+            },
+            Command::ShowPalette {
+                message: KEEP_OPEN_NOTICE.into(),
+            },
+        ];
+        self.phase = Phase::Picking {
+            snap,
+            selection,
+            input: String::new(),
+            pending_launch: None,
+        };
+        cmds
     }
 
-    fn on_unlock_result(&mut self, success: bool, profile: TrustProfileName) -> Vec<Command> {
-        if !matches!(self.phase, Phase::Unlocking { .. }) {
+    /// Multiplexer rows listed after the overlay opened. They are merged in
+    /// while the input is empty and nothing open over the picker refers to
+    /// a row, keeping the selected row selected; otherwise the typed hints
+    /// would shift under the user, and the rows wait for the next snapshot
+    /// of the cycle.
+    fn on_multiplexer_listed(
+        &mut self,
+        targets: MultiplexerTargets,
+        windows: &[Window],
+        config: &WmConfig,
+    ) -> Vec<Command> {
+        if self.is_idle() {
             return Vec::new();
         }
-        if success {
-            return self.advance_to_next_profile_or_retry();
+        self.multiplexer_targets = targets;
+        let picker_free = self.row_menu.is_none()
+            && !self.help_open
+            && !self.walkthrough_open
+            && !self.mark_pending
+            && !self.output_pending
+            && self.peek.is_none();
+        let mut rebuilt = Snapshot::build(
+            windows,
+            config,
+            &self.titles,
+            &self.multiplexer_targets,
+            &self.extra_items(config),
+        );
+        if !self.can_activate {
+            rebuilt = rebuilt.without_windows();
         }
-        // Wrong password — reset for retry.
-        if let Phase::Unlocking {
-            password_len,
-            unlock_mode,
-            ..
-        } = &mut self.phase
-        {
-            *password_len = 0;
-            *unlock_mode = UnlockMode::Password;
+        let (snap, selection, visible) = match &mut self.phase {
+            Phase::Armed {
+                snap,
+                selection,
+                input,
+                ..
+            } if input.is_empty() => (snap, selection, false),
+            Phase::Picking {
+                snap,
+                selection,
+                input,
+                ..
+            } if input.is_empty() && picker_free => (snap, selection, true),
+            _ => return Vec::new(),
+        };
+        if snap.timeline.is_some() {
+            rebuilt.toggle_timeline(mru::now_secs());
+        }
+        let selected = snap.windows.get(*selection).zip(snap.items.get(*selection));
+        *selection = selected
+            .and_then(|(window, item)| {
+                rebuilt
+                    .windows
+                    .iter()
+                    .zip(&rebuilt.items)
+                    .position(|(w, i)| w.id == window.id && i == item)
+            })
+            .unwrap_or_else(|| rebuilt.initial_forward());
+        *snap = rebuilt;
+        if !visible {
+            return Vec::new();
         }
         vec![
-            Command::ClearPasswordBuffer,
-            Command::ShowPasswordPrompt { profile },
-            Command::ShowUnlockError {
-                message: "Wrong password".into(),
+            Command::ShowPicker {
+                windows: snap.overlay_windows.clone(),
+                hints: snap.hints.clone(),
+            },
+            Command::UpdatePicker {
+                input: String::new(),
+                selection: *selection,
             },
         ]
     }
 
-    /// Advance to the next locked profile, or retry the original launch.
-    fn advance_to_next_profile_or_retry(&mut self) -> Vec<Command> {
-        let mut cmds = vec![Command::ClearPasswordBuffer];
+    /// A compositor title or app ID change. The picker redraws the row in
+    /// place; behind the armed border only the snapshot is updated, for
+    /// the picker to show.
+    fn on_window_changed(
+        &mut self,
+        window_id: WindowId,
+        app_id: &str,
+        title: &str,
+    ) -> Vec<Command> {
+        let (snap, visible) = match &mut self.phase {
+            Phase::Armed { snap, .. } => (snap, false),
+            Phase::Picking { snap, .. } | Phase::Confirming { snap, .. } => (snap, true),
+            _ => return Vec::new(),
+        };
+        if !snap.retitle(window_id, app_id, title, &self.titles) || !visible {
+            return Vec::new();
+        }
+        vec![Command::UpdateRows {
+            windows: snap.overlay_windows.clone(),
+        }]
+    }
 
-        match std::mem::replace(&mut self.phase, Phase::Idle) {
-            Phase::Unlocking {
-                profiles_to_unlock,
-                current_index,
-                retry_command,
-                retry_tags,
-                retry_launch_args,
-                ..
-            } => {
-                let next_index = current_index + 1;
-                if next_index < profiles_to_unlock.len() {
-                    // More profiles to unlock.
-                    let profile = profiles_to_unlock[next_index].clone();
-                    self.phase = Phase::Unlocking {
-                        profiles_to_unlock,
-                        current_index: next_index,
-                        password_len: 0,
-                        unlock_mode: UnlockMode::AutoAttempt,
-                        retry_command,
-                        retry_tags,
-                        retry_launch_args,
-                    };
-                    cmds.push(Command::AttemptAutoUnlock { profile });
-                } else {
-                    // All profiles unlocked — activate them, then retry the launch.
-                    self.phase = Phase::Launching;
-                    cmds.push(Command::ShowLaunching);
-                    cmds.push(Command::ActivateProfiles {
-                        profiles: profiles_to_unlock,
-                    });
-                    cmds.push(Command::LaunchApp {
-                        command: retry_command,
-                        tags: retry_tags,
-                        launch_args: retry_launch_args,
-                    });
+    // -----------------------------------------------------------------------
+    // Placing windows
+    // -----------------------------------------------------------------------
+
+    /// Ctrl+H / Ctrl+L / Ctrl+F in the picker: switch to the selected window
+    /// and tile or maximize it. Placements the compositor cannot perform
+    /// leave the picker open with a notice.
+    fn on_place(&mut self, placement: WindowPlacement) -> Vec<Command> {
+        let Phase::Picking {
+            snap, selection, ..
+        } = &self.phase
+        else {
+            return Vec::new();
+        };
+        let Some(window) = snap.window(*selection).cloned() else {
+            return Vec::new();
+        };
+        let (supported, verb) = match placement {
+            WindowPlacement::Maximized => (self.can_maximize, "maximize"),
+            WindowPlacement::LeftHalf | WindowPlacement::RightHalf => (self.can_tile, "tile"),
+        };
+        if !supported {
+            return vec![Command::ShowPalette {
+                message: format!("This compositor cannot {verb} windows"),
+            }];
+        }
+        let Phase::Picking {
+            snap, selection, ..
+        } = std::mem::replace(&mut self.phase, Phase::Idle)
+        else {
+            unreachable!("phase checked above");
+        };
+        let mut cmds = self.activate_index(selection, &snap);
+        let at = cmds
+            .iter()
+            .position(|c| matches!(c, Command::ActivateWindow { .. }))
+            .map_or(cmds.len(), |i| i + 1);
+        cmds.insert(at, Command::PlaceWindow { window, placement });
+        cmds
+    }
+
+    // -----------------------------------------------------------------------
+    // Row menu
+    // -----------------------------------------------------------------------
+
+    /// Menu key (or `.` on empty input): open the selected window row's
+    /// context menu, showing the picker if it is still Armed, or close it.
+    /// Like keep-open, an open menu spends the Alt release, so only Enter
+    /// on an entry commits.
+    fn on_row_menu(&mut self) -> Vec<Command> {
+        let mut cmds = match &self.phase {
+            Phase::Armed { .. } => self.transition_armed_to_picking(),
+            Phase::Picking { .. } => Vec::new(),
+            _ => return Vec::new(),
+        };
+        let Phase::Picking {
+            snap, selection, ..
+        } = &self.phase
+        else {
+            return cmds;
+        };
+        let Some(window) = snap.window(*selection) else {
+            return cmds;
+        };
+        let menu = RowMenu::new(row_menu::actions(self.capabilities()));
+        cmds.push(Command::SetRowMenu {
+            menu: Some(menu.view(window)),
+        });
+        self.row_menu = Some(menu);
+        self.release_spent = true;
+        cmds
+    }
+
+    /// An event while the row menu is open. Up and Down move through it,
+    /// Enter runs the highlighted verb, Escape, Backspace or the menu key
+    /// close it, and other typing is ignored. Another verb's shortcut
+    /// closes the menu and runs as usual; events that are not keys leave
+    /// it open.
+    fn on_row_menu_event(
+        &mut self,
+        event: Event,
+        windows: &[Window],
+        config: &WmConfig,
+    ) -> Vec<Command> {
+        let close = vec![Command::SetRowMenu { menu: None }];
+        let event = popup_event(event);
+        match event {
+            Event::SelectionDown | Event::SelectionUp => {
+                let (
+                    Some(menu),
+                    Phase::Picking {
+                        snap, selection, ..
+                    },
+                ) = (self.row_menu.as_mut(), &self.phase)
+                else {
+                    return Vec::new();
+                };
+                menu.step(matches!(event, Event::SelectionDown));
+                match snap.window(*selection) {
+                    Some(window) => vec![Command::SetRowMenu {
+                        menu: Some(menu.view(window)),
+                    }],
+                    None => Vec::new(),
                 }
             }
-            other => {
-                self.phase = other;
+            Event::Confirm => {
+                let action = self.row_menu.take().and_then(|menu| menu.current());
+                let mut cmds = close;
+                if let Some(action) = action {
+                    cmds.extend(self.dispatch(action.event(), windows, config));
+                }
+                cmds
+            }
+            Event::Escape | Event::Backspace | Event::RowMenu => {
+                self.row_menu = None;
+                close
+            }
+            Event::Char(_) => Vec::new(),
+            event if !event.is_key() => {
+                let menu = self.row_menu.take();
+                let cmds = self.dispatch(event, windows, config);
+                if matches!(self.phase, Phase::Picking { .. }) {
+                    self.row_menu = menu;
+                    cmds
+                } else {
+                    close.into_iter().chain(cmds).collect()
+                }
+            }
+            event => {
+                self.row_menu = None;
+                close
+                    .into_iter()
+                    .chain(self.dispatch(event, windows, config))
+                    .collect()
             }
         }
-
-        cmds
     }
 
     // -----------------------------------------------------------------------
-    // Helpers
+    // Help overlay
     // -----------------------------------------------------------------------
 
-    fn current_selection(&self) -> usize {
-        match &self.phase {
-            Phase::Armed { selection, .. } | Phase::Picking { selection, .. } => *selection,
-            _ => 0,
-        }
+    /// F1 (or `?` on empty input): show every key the picker answers, the
+    /// input prefixes and the configured hint keys in place of the picker,
+    /// showing the picker first if it is still Armed. Like the row menu,
+    /// help spends the Alt release.
+    fn on_help(&mut self) -> Vec<Command> {
+        let mut cmds = match &self.phase {
+            Phase::Armed { .. } => self.transition_armed_to_picking(),
+            Phase::Picking { .. } => Vec::new(),
+            _ => return Vec::new(),
+        };
+        let Phase::Picking { snap, .. } = &self.phase else {
+            return cmds;
+        };
+        let help = actions::help(
+            self.capabilities(),
+            snap.command_palette,
+            &snap.key_bindings,
+        );
+        cmds.push(Command::SetHelp { help: Some(help) });
+        self.help_open = true;
+        self.release_spent = true;
+        cmds
     }
 
-    fn update_selection(&mut self, idx: usize) {
-        match &mut self.phase {
-            Phase::Armed { selection, .. } | Phase::Picking { selection, .. } => {
-                *selection = idx;
+    /// An event while help is shown. Escape, Backspace or the help key hide
+    /// it, and typing and moving are ignored. Another verb's shortcut hides
+    /// help and runs as usual; events that are not keys leave it shown.
+    fn on_help_event(
+        &mut self,
+        event: Event,
+        windows: &[Window],
+        config: &WmConfig,
+    ) -> Vec<Command> {
+        let close = vec![Command::SetHelp { help: None }];
+        let event = popup_event(event);
+        match event {
+            Event::Escape | Event::Backspace | Event::Help => {
+                self.help_open = false;
+                close
+            }
+            Event::Char(_) | Event::SelectionDown | Event::SelectionUp | Event::Confirm => {
+                Vec::new()
+            }
+            event if !event.is_key() => {
+                self.help_open = false;
+                let cmds = self.dispatch(event, windows, config);
+                if matches!(self.phase, Phase::Picking { .. }) {
+                    self.help_open = true;
+                    cmds
+                } else {
+                    close.into_iter().chain(cmds).collect()
+                }
+            }
+            event => {
+                self.help_open = false;
+                close
+                    .into_iter()
+                    .chain(self.dispatch(event, windows, config))
+                    .collect()
             }
-            _ => {}
         }
     }
 
-    fn set_pending_launch(&mut self, launch: PendingLaunch) {
-        match &mut self.phase {
-            Phase::Armed { pending_launch, .. } | Phase::Picking { pending_launch, .. } => {
-                *pending_launch = Some(launch);
-            }
-            _ => {}
+    // -----------------------------------------------------------------------
+    // First-run walkthrough
+    // -----------------------------------------------------------------------
+
+    /// After an activation's `cmds`, show the pending walkthrough in place
+    /// of the picker, showing the picker first if it is still Armed. The
+    /// walkthrough spends the Alt release, so letting go does not switch.
+    fn open_walkthrough(&mut self, mut cmds: Vec<Command>) -> Vec<Command> {
+        let Some(page) = self.walkthrough.as_ref().and_then(Walkthrough::view) else {
+            return cmds;
+        };
+        match self.phase {
+            Phase::Armed { .. } => cmds.extend(self.transition_armed_to_picking()),
+            Phase::Picking { .. } => {}
+            _ => return cmds,
         }
+        cmds.push(Command::SetWalkthrough { page: Some(page) });
+        self.walkthrough_open = true;
+        self.release_spent = true;
+        cmds
     }
 
-    fn clear_pending_launch(&mut self) {
-        match &mut self.phase {
-            Phase::Armed { pending_launch, .. } | Phase::Picking { pending_launch, .. } => {
-                *pending_launch = None;
+    /// An event while the walkthrough is shown. Enter, Down, Space or
+    /// Alt+Tab turn the page, Up, Backspace or Alt+Shift+Tab turn back;
+    /// Enter on the last page, or `d`, finish it for good. Escape closes it
+    /// until the daemon next starts, leaving the picker. Other keys are
+    /// ignored; events that are not keys leave it shown.
+    fn on_walkthrough_event(
+        &mut self,
+        event: Event,
+        windows: &[Window],
+        config: &WmConfig,
+    ) -> Vec<Command> {
+        let Some(walkthrough) = self.walkthrough.as_mut() else {
+            self.walkthrough_open = false;
+            return vec![Command::SetWalkthrough { page: None }];
+        };
+        match event {
+            Event::Confirm
+            | Event::SelectionDown
+            | Event::Char(' ')
+            | Event::Activate
+            | Event::ActivateLauncher => {
+                if walkthrough.turn_forward() {
+                    vec![Command::SetWalkthrough {
+                        page: walkthrough.view(),
+                    }]
+                } else {
+                    self.finish_walkthrough()
+                }
             }
-            _ => {}
+            Event::SelectionUp
+            | Event::Backspace
+            | Event::ActivateBackward
+            | Event::ActivateLauncherBackward => {
+                walkthrough.turn_back();
+                vec![Command::SetWalkthrough {
+                    page: walkthrough.view(),
+                }]
+            }
+            Event::Char(ch) if ch.eq_ignore_ascii_case(&onboarding::DONT_SHOW_AGAIN) => {
+                self.finish_walkthrough()
+            }
+            Event::Escape => {
+                self.walkthrough = None;
+                self.walkthrough_open = false;
+                vec![Command::SetWalkthrough { page: None }]
+            }
+            event if !event.is_key() => {
+                self.walkthrough_open = false;
+                let cmds = self.dispatch(event, windows, config);
+                if matches!(self.phase, Phase::Picking { .. }) {
+                    self.walkthrough_open = true;
+                    cmds
+                } else {
+                    std::iter::once(Command::SetWalkthrough { page: None })
+                        .chain(cmds)
+                        .collect()
+                }
+            }
+            _ => Vec::new(),
         }
     }
-}
 
-impl Default for OverlayController {
-    fn default() -> Self {
-        Self::new()
+    /// Close the walkthrough for good.
+    fn finish_walkthrough(&mut self) -> Vec<Command> {
+        self.walkthrough = None;
+        self.walkthrough_open = false;
+        vec![
+            Command::SetWalkthrough { page: None },
+            Command::FinishWalkthrough,
+        ]
     }
-}
 
-// ---------------------------------------------------------------------------
-// Tests
-// ---------------------------------------------------------------------------
+    /// What the compositor can do, for the row menu and help overlay.
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            activate: self.can_activate,
+            maximize: self.can_maximize,
+            tile: self.can_tile,
+            stick: self.can_stick,
+            keep_above: self.can_keep_above,
+            move_to_output: self.can_move_to_output && !self.outputs.is_empty(),
+        }
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use core_config::WmKeyBinding;
+    // -----------------------------------------------------------------------
+    // Marks
+    // -----------------------------------------------------------------------
 
-    fn test_config() -> WmConfig {
-        WmConfig {
-            quick_switch_threshold_ms: 250,
-            activation_delay_ms: 200,
-            max_visible_windows: 20,
-            hint_keys: "asdfghjkl".into(),
-            key_bindings: [
-                ("g", vec!["com.mitchellh.ghostty"], Some("ghostty")),
-                ("f", vec!["firefox"], Some("firefox")),
-                ("e", vec!["microsoft-edge"], Some("microsoft-edge")),
-            ]
-            .into_iter()
-            .map(|(k, apps, launch)| {
-                (
-                    k.to_string(),
-                    WmKeyBinding {
-                        apps: apps.into_iter().map(String::from).collect(),
-                        launch: launch.map(String::from),
-                        tags: Vec::new(),
-                        launch_args: Vec::new(),
-                    },
-                )
-            })
-            .collect(),
-            ..Default::default()
+    /// Ctrl+M in the picker: the next letter marks the selected window.
+    fn on_mark(&mut self) -> Vec<Command> {
+        let Phase::Picking {
+            snap, selection, ..
+        } = &self.phase
+        else {
+            return Vec::new();
+        };
+        if snap.window(*selection).is_none() {
+            return Vec::new();
         }
+        self.mark_pending = true;
+        vec![Command::ShowPalette {
+            message: "Mark window: press a letter a–z".into(),
+        }]
     }
 
-    fn test_windows() -> Vec<Window> {
+    /// The character typed after Ctrl+M. The picker stays open.
+    fn on_mark_letter(&mut self, mark: char) -> Vec<Command> {
+        let Phase::Picking {
+            snap, selection, ..
+        } = &self.phase
+        else {
+            return Vec::new();
+        };
+        let Some(window) = snap.window(*selection) else {
+            return Vec::new();
+        };
+        if !marks::is_mark(mark) {
+            return vec![Command::ShowPalette {
+                message: "Marks are letters a–z".into(),
+            }];
+        }
+        tracing::info!(%mark, target = %window.id, "marking window from overlay");
         vec![
-            Window {
-                id: core_types::WindowId::new(),
-                app_id: core_types::AppId::new("com.mitchellh.ghostty"),
-                title: "Terminal".into(),
-                workspace_id: core_types::CompositorWorkspaceId::new(),
-                monitor_id: core_types::MonitorId::new(),
-                geometry: core_types::Geometry {
-                    x: 0,
-                    y: 0,
-                    width: 800,
-                    height: 600,
-                },
-                is_focused: true,
-                is_minimized: false,
-                is_fullscreen: false,
-                profile_id: core_types::ProfileId::new(),
-            },
-            Window {
-                id: core_types::WindowId::new(),
-                app_id: core_types::AppId::new("firefox"),
-                title: "Firefox".into(),
-                workspace_id: core_types::CompositorWorkspaceId::new(),
-                monitor_id: core_types::MonitorId::new(),
-                geometry: core_types::Geometry {
-                    x: 0,
-                    y: 0,
-                    width: 800,
-                    height: 600,
-                },
-                is_focused: false,
-                is_minimized: false,
-                is_fullscreen: false,
-                profile_id: core_types::ProfileId::new(),
+            Command::MarkWindow {
+                mark,
+                window: window.clone(),
             },
-            Window {
-                id: core_types::WindowId::new(),
-                app_id: core_types::AppId::new("microsoft-edge"),
-                title: "Edge".into(),
-                workspace_id: core_types::CompositorWorkspaceId::new(),
-                monitor_id: core_types::MonitorId::new(),
-                geometry: core_types::Geometry {
-                    x: 0,
-                    y: 0,
-                    width: 800,
-                    height: 600,
-                },
-                is_focused: false,
-                is_minimized: false,
-                is_fullscreen: false,
-                profile_id: core_types::ProfileId::new(),
+            Command::ShowPalette {
+                message: format!("Marked {}: sesame wm mark {mark}", window.app_id),
             },
         ]
     }
 
-    // === Forward activation ===
+    // -----------------------------------------------------------------------
+    // Moving windows between outputs
+    // -----------------------------------------------------------------------
 
-    #[test]
-    fn activate_emits_show_border() {
-        let mut ctrl = OverlayController::new();
-        let cmds = ctrl.handle(Event::Activate, &test_windows(), &test_config());
-        assert!(cmds.iter().any(|c| matches!(c, Command::ShowBorder { .. })));
-        assert!(!ctrl.is_idle());
+    /// Ctrl+O in the picker: the next digit moves the selected window to
+    /// that output and switches to it.
+    fn on_move_to_output(&mut self) -> Vec<Command> {
+        let Phase::Picking {
+            snap, selection, ..
+        } = &self.phase
+        else {
+            return Vec::new();
+        };
+        if snap.window(*selection).is_none() {
+            return Vec::new();
+        }
+        if !self.can_move_to_output || self.outputs.is_empty() {
+            return vec![Command::ShowPalette {
+                message: "This compositor cannot move windows between outputs".into(),
+            }];
+        }
+        self.output_pending = true;
+        let numbered: Vec<String> = self
+            .outputs
+            .iter()
+            .take(9)
+            .zip(1..)
+            .map(|(output, n)| format!("{n} {}", output.name))
+            .collect();
+        vec![Command::ShowPalette {
+            message: format!("Move to output: {}", numbered.join(", ")),
+        }]
     }
 
-    #[test]
-    fn activate_sets_dwell_deadline() {
-        let mut ctrl = OverlayController::new();
-        ctrl.handle(Event::Activate, &test_windows(), &test_config());
-        assert!(ctrl.next_deadline().is_some());
+    /// The character typed after Ctrl+O.
+    fn on_output_digit(&mut self, ch: char) -> Vec<Command> {
+        let Phase::Picking {
+            snap, selection, ..
+        } = &self.phase
+        else {
+            return Vec::new();
+        };
+        let Some(window) = snap.window(*selection).cloned() else {
+            return Vec::new();
+        };
+        let output = ch
+            .to_digit(10)
+            .and_then(|n| n.checked_sub(1))
+            .and_then(|n| self.outputs.get(n as usize));
+        let Some(output) = output.cloned() else {
+            return vec![Command::ShowPalette {
+                message: format!("Outputs are numbered 1–{}", self.outputs.len().min(9)),
+            }];
+        };
+        let Phase::Picking {
+            snap, selection, ..
+        } = std::mem::replace(&mut self.phase, Phase::Idle)
+        else {
+            unreachable!("phase checked above");
+        };
+        tracing::info!(target = %window.id, output = %output.name, "moving window to output");
+        let mut cmds = self.activate_index(selection, &snap);
+        let at = cmds
+            .iter()
+            .position(|c| matches!(c, Command::ActivateWindow { .. }))
+            .map_or(cmds.len(), |i| i + 1);
+        cmds.insert(at, Command::MoveWindowToOutput { window, output });
+        cmds
     }
 
-    #[test]
-    fn forward_initial_selection_is_not_origin() {
-        let mut ctrl = OverlayController::new();
-        ctrl.handle(Event::Activate, &test_windows(), &test_config());
-        if let Phase::Armed {
-            selection, snap, ..
-        } = &ctrl.phase
-        {
-            assert_ne!(
-                Some(*selection),
-                snap.origin_index,
+    // -----------------------------------------------------------------------
+    // Focus timeline
+    // -----------------------------------------------------------------------
+
+    /// Ctrl+R: list the rows by when each window was last focused, or go
+    /// back to MRU order. The selection stays on the same window.
+    fn on_timeline(&mut self) -> Vec<Command> {
+        if matches!(self.phase, Phase::Armed { .. }) {
+            self.transition_armed_to_picking();
+        }
+        let Phase::Picking {
+            snap,
+            selection,
+            input,
+            ..
+        } = &mut self.phase
+        else {
+            return Vec::new();
+        };
+        let order = snap.toggle_timeline(mru::now_secs());
+        *selection = order.iter().position(|&i| i == *selection).unwrap_or(0);
+        tracing::info!(timeline = snap.timeline.is_some(), "focus timeline toggled");
+        vec![
+            Command::ShowPicker {
+                windows: snap.overlay_windows.clone(),
+                hints: snap.hints.clone(),
+            },
+            Command::UpdatePicker {
+                input: input.clone(),
+                selection: *selection,
+            },
+        ]
+    }
+
+    // -----------------------------------------------------------------------
+    // Window toggles
+    // -----------------------------------------------------------------------
+
+    /// Ctrl+S / Ctrl+T in the picker: flip the selected window's sticky or
+    /// keep-above state and redraw its row. The picker stays open.
+    fn on_toggle(&mut self, toggle: WindowToggle) -> Vec<Command> {
+        let (supported, what) = match toggle {
+            WindowToggle::Sticky => (self.can_stick, "show windows on every workspace"),
+            WindowToggle::KeepAbove => (self.can_keep_above, "keep windows above others"),
+        };
+        let Phase::Picking {
+            snap,
+            selection,
+            input,
+            ..
+        } = &mut self.phase
+        else {
+            return Vec::new();
+        };
+        let Some(target) = snap.window(*selection) else {
+            return Vec::new();
+        };
+        if !supported {
+            return vec![Command::ShowPalette {
+                message: format!("This compositor cannot {what}"),
+            }];
+        }
+        let target = target.id;
+        let mut window = None;
+        // Multiplexer rows share their terminal's window; flip them all.
+        for (w, row) in snap.windows.iter_mut().zip(&mut snap.overlay_windows) {
+            if w.id != target {
+                continue;
+            }
+            let on = match toggle {
+                WindowToggle::Sticky => {
+                    w.is_sticky = !w.is_sticky;
+                    row.sticky = w.is_sticky;
+                    w.is_sticky
+                }
+                WindowToggle::KeepAbove => {
+                    w.is_keep_above = !w.is_keep_above;
+                    row.keep_above = w.is_keep_above;
+                    w.is_keep_above
+                }
+            };
+            window.get_or_insert((w.clone(), on));
+        }
+        let Some((window, on)) = window else {
+            return Vec::new();
+        };
+        tracing::info!(target = %window.id, ?toggle, on, "toggling window state from overlay");
+        vec![
+            Command::SetWindowToggle { window, toggle, on },
+            Command::ShowPicker {
+                windows: snap.overlay_windows.clone(),
+                hints: snap.hints.clone(),
+            },
+            Command::UpdatePicker {
+                input: input.clone(),
+                selection: *selection,
+            },
+        ]
+    }
+
+    // -----------------------------------------------------------------------
+    // Closing windows
+    // -----------------------------------------------------------------------
+
+    /// Ctrl+W / Ctrl+Shift+W in the picker: close the selected window or all
+    /// of its app's windows, after a y/n prompt unless `confirm_close` is off.
+    fn on_close(&mut self, whole_app: bool, windows: &[Window], config: &WmConfig) -> Vec<Command> {
+        let Phase::Picking {
+            snap, selection, ..
+        } = &self.phase
+        else {
+            return Vec::new();
+        };
+        let targets = close_targets(snap, *selection, whole_app);
+        if targets.is_empty() {
+            return Vec::new();
+        }
+        let action = ConfirmAction::Close { windows: targets };
+        let Phase::Picking {
+            snap,
+            selection,
+            input,
+            ..
+        } = std::mem::replace(&mut self.phase, Phase::Idle)
+        else {
+            unreachable!("phase checked above");
+        };
+        if !config.confirm_close {
+            return self.run_confirmed(action, selection, windows, config);
+        }
+        let message = action.prompt();
+        self.phase = Phase::Confirming {
+            action,
+            snap,
+            selection,
+            input,
+        };
+        vec![Command::ShowPalette { message }]
+    }
+
+    /// Events while a confirmation prompt is showing. `y` or Enter runs the
+    /// action; `n`, Backspace or Escape returns to the picker. Everything
+    /// else, modifier release included, is ignored until answered.
+    fn on_confirming(
+        &mut self,
+        event: Event,
+        windows: &[Window],
+        config: &WmConfig,
+    ) -> Vec<Command> {
+        let answer = match event {
+            Event::Char('y' | 'Y') | Event::Confirm => true,
+            Event::Char('n' | 'N') | Event::Backspace | Event::Escape => false,
+            Event::Dismiss => return self.on_escape(),
+            _ => return Vec::new(),
+        };
+        let Phase::Confirming {
+            action,
+            snap,
+            selection,
+            input,
+        } = std::mem::replace(&mut self.phase, Phase::Idle)
+        else {
+            unreachable!("only called while confirming");
+        };
+        if answer {
+            return self.run_confirmed(action, selection, windows, config);
+        }
+        let cmds = vec![Command::UpdatePicker {
+            input: input.clone(),
+            selection,
+        }];
+        self.phase = Phase::Picking {
+            snap,
+            selection,
+            input,
+            pending_launch: None,
+        };
+        cmds
+    }
+
+    /// Carry out `action` and show the picker rebuilt from the windows that
+    /// remain, or dismiss the overlay if none do.
+    fn run_confirmed(
+        &mut self,
+        action: ConfirmAction,
+        selection: usize,
+        windows: &[Window],
+        config: &WmConfig,
+    ) -> Vec<Command> {
+        let ConfirmAction::Close { windows: closing } = action;
+        tracing::info!(count = closing.len(), "closing windows from overlay");
+        let closed: std::collections::HashSet<WindowId> = closing.iter().map(|w| w.id).collect();
+        let remaining: Vec<Window> = windows
+            .iter()
+            .filter(|w| !closed.contains(&w.id))
+            .cloned()
+            .collect();
+        let mut cmds = vec![Command::CloseWindows { windows: closing }];
+
+        let snap = Snapshot::build(
+            &remaining,
+            config,
+            &self.titles,
+            &self.multiplexer_targets,
+            &self.extra_items(config),
+        );
+        if !snap.has_targets() {
+            self.last_ipc_advance = None;
+            cmds.push(Command::Hide);
+            cmds.push(Command::Publish(
+                EventKind::WmOverlayDismissed,
+                SecurityLevel::Internal,
+            ));
+            return cmds;
+        }
+        let selection = selection.min(snap.windows.len() - 1);
+        cmds.push(Command::ShowPicker {
+            windows: snap.overlay_windows.clone(),
+            hints: snap.hints.clone(),
+        });
+        cmds.push(Command::UpdatePicker {
+            input: String::new(),
+            selection,
+        });
+        self.phase = Phase::Picking {
+            snap,
+            selection,
+            input: String::new(),
+            pending_launch: None,
+        };
+        cmds
+    }
+
+    fn on_escape(&mut self) -> Vec<Command> {
+        self.last_ipc_advance = None;
+        match std::mem::replace(&mut self.phase, Phase::Idle) {
+            Phase::Idle => Vec::new(),
+            Phase::Unlocking { .. } => {
+                vec![
+                    Command::ClearPasswordBuffer,
+                    Command::Hide,
+                    Command::Publish(EventKind::WmOverlayDismissed, SecurityLevel::Internal),
+                ]
+            }
+            Phase::Armed { .. } | Phase::Picking { .. } | Phase::Confirming { .. } => vec![
+                Command::Hide,
+                Command::Publish(EventKind::WmOverlayDismissed, SecurityLevel::Internal),
+                self.record_usage(UsageKind::Cancel, None),
+            ],
+            Phase::Launching | Phase::LaunchError => vec![
+                Command::Hide,
+                Command::Publish(EventKind::WmOverlayDismissed, SecurityLevel::Internal),
+            ],
+        }
+    }
+
+    // -----------------------------------------------------------------------
+    // Launch result
+    // -----------------------------------------------------------------------
+
+    #[allow(clippy::too_many_arguments)]
+    fn on_launch_result(
+        &mut self,
+        success: bool,
+        error: Option<String>,
+        denial: Option<LaunchDenial>,
+        original_command: Option<String>,
+        original_tags: Option<Vec<String>>,
+        original_launch_args: Option<Vec<String>>,
+    ) -> Vec<Command> {
+        // A failed or refused launch started nothing, so the relaunch guard
+        // must not hold back a retry.
+        if let Some(command) = self.launch_in_flight.take()
+            && !success
+        {
+            self.recent_launches
+                .retain(|(launched, _)| *launched != command);
+        }
+        if !matches!(self.phase, Phase::Launching) {
+            return Vec::new();
+        }
+
+        if success {
+            self.phase = Phase::Idle;
+            return vec![
+                Command::Hide,
+                Command::Publish(EventKind::WmOverlayDismissed, SecurityLevel::Internal),
+            ];
+        }
+
+        // Check for VaultsLocked denial — transition to inline unlock.
+        if let Some(LaunchDenial::VaultsLocked { locked_profiles }) = denial
+            && let (Some(cmd), Some(tags), Some(args)) =
+                (original_command, original_tags, original_launch_args)
+            && let Some(first_profile) = locked_profiles.first()
+        {
+            let profile = first_profile.clone();
+            self.phase = Phase::Unlocking {
+                profiles_to_unlock: locked_profiles,
+                current_index: 0,
+                password_len: 0,
+                unlock_mode: UnlockMode::AutoAttempt,
+                retry_command: cmd,
+                retry_tags: tags,
+                retry_launch_args: args,
+            };
+            return vec![Command::AttemptAutoUnlock { profile }];
+        }
+
+        let message = error.unwrap_or_else(|| "launch failed".into());
+        self.phase = Phase::LaunchError;
+        vec![Command::ShowLaunchError {
+            message,
+            denial: None,
+        }]
+    }
+
+    // -----------------------------------------------------------------------
+    // Unlock result handlers
+    // -----------------------------------------------------------------------
+
+    fn on_auto_unlock_result(
+        &mut self,
+        success: bool,
+        profile: TrustProfileName,
+        needs_touch: bool,
+    ) -> Vec<Command> {
+        if !matches!(self.phase, Phase::Unlocking { .. }) {
+            return Vec::new();
+        }
+        if success {
+            return self.advance_to_next_profile_or_retry();
+        }
+        if needs_touch {
+            if let Phase::Unlocking { unlock_mode, .. } = &mut self.phase {
+                *unlock_mode = UnlockMode::WaitingForTouch;
+            }
+            return vec![Command::ShowTouchPrompt { profile }];
+        }
+        // Fall back to password.
+        if let Phase::Unlocking { unlock_mode, .. } = &mut self.phase {
+            *unlock_mode = UnlockMode::Password;
+        }
+        vec![Command::ShowPasswordPrompt { profile }]
+    }
+
+    fn on_touch_result(&mut self, success: bool, profile: TrustProfileName) -> Vec<Command> {
+        if !matches!(self.phase, Phase::Unlocking { .. }) {
+            return Vec::new();
+        }
+        if success {
+            return self.advance_to_next_profile_or_retry();
+        }
+        // Fall back to password on touch failure.
+        if let Phase::Unlocking { unlock_mode, .. } = &mut self.phase {
+            *unlock_mode = UnlockMode::Password;
+        }
+        vec![
+            Command::ShowPasswordPrompt { profile },
+            Command::ShowUnlockError {
+                message: "Touch verification failed".into(),
+            },
+        ]
+    }
+
+    fn on_unlock_result(&mut self, success: bool, profile: TrustProfileName) -> Vec<Command> {
+        if !matches!(self.phase, Phase::Unlocking { .. }) {
+            return Vec::new();
+        }
+        if success {
+            return self.advance_to_next_profile_or_retry();
+        }
+        // Wrong password — reset for retry.
+        if let Phase::Unlocking {
+            password_len,
+            unlock_mode,
+            ..
+        } = &mut self.phase
+        {
+            *password_len = 0;
+            *unlock_mode = UnlockMode::Password;
+        }
+        vec![
+            Command::ClearPasswordBuffer,
+            Command::ShowPasswordPrompt { profile },
+            Command::ShowUnlockError {
+                message: "Wrong password".into(),
+            },
+        ]
+    }
+
+    /// Advance to the next locked profile, or retry the original launch.
+    fn advance_to_next_profile_or_retry(&mut self) -> Vec<Command> {
+        let mut cmds = vec![Command::ClearPasswordBuffer];
+
+        match std::mem::replace(&mut self.phase, Phase::Idle) {
+            Phase::Unlocking {
+                profiles_to_unlock,
+                current_index,
+                retry_command,
+                retry_tags,
+                retry_launch_args,
+                ..
+            } => {
+                let next_index = current_index + 1;
+                if next_index < profiles_to_unlock.len() {
+                    // More profiles to unlock.
+                    let profile = profiles_to_unlock[next_index].clone();
+                    self.phase = Phase::Unlocking {
+                        profiles_to_unlock,
+                        current_index: next_index,
+                        password_len: 0,
+                        unlock_mode: UnlockMode::AutoAttempt,
+                        retry_command,
+                        retry_tags,
+                        retry_launch_args,
+                    };
+                    cmds.push(Command::AttemptAutoUnlock { profile });
+                } else {
+                    // All profiles unlocked — activate them, then retry the launch.
+                    self.phase = Phase::Launching;
+                    cmds.push(Command::ShowLaunching);
+                    cmds.push(Command::ActivateProfiles {
+                        profiles: profiles_to_unlock,
+                    });
+                    cmds.push(Command::LaunchApp {
+                        command: retry_command,
+                        tags: retry_tags,
+                        launch_args: retry_launch_args,
+                    });
+                }
+            }
+            other => {
+                self.phase = other;
+            }
+        }
+
+        cmds
+    }
+
+    // -----------------------------------------------------------------------
+    // Command palette
+    // -----------------------------------------------------------------------
+
+    /// Current input, if the palette is enabled and the input is palette input.
+    fn palette_input(&self) -> Option<String> {
+        match &self.phase {
+            Phase::Armed { input, snap, .. } | Phase::Picking { input, snap, .. }
+                if snap.command_palette && palette::parse(input).is_some() =>
+            {
+                Some(input.clone())
+            }
+            _ => None,
+        }
+    }
+
+    /// Palette input changed: show the picker (if still armed) and the
+    /// calculator result or command prompt.
+    fn update_palette(&mut self) -> Vec<Command> {
+        self.clear_pending_launch();
+        let Some(input) = self.palette_input() else {
+            return Vec::new();
+        };
+        let mut cmds = if matches!(self.phase, Phase::Armed { .. }) {
+            self.transition_armed_to_picking()
+        } else {
+            vec![Command::UpdatePicker {
+                input: input.clone(),
+                selection: self.current_selection(),
+            }]
+        };
+        if let Some(parsed) = palette::parse(&input) {
+            cmds.push(Command::ShowPalette {
+                message: palette::message(&parsed, false),
+            });
+        }
+        cmds
+    }
+
+    /// Enter on palette input. A calculator result just closes the overlay.
+    /// A shell command needs two presses on the same input before it runs.
+    fn on_palette_confirm(&mut self, input: String) -> Vec<Command> {
+        match palette::parse(&input) {
+            Some(PaletteInput::Calc(_)) => {
+                self.phase = Phase::Idle;
+                self.last_ipc_advance = None;
+                vec![
+                    Command::Hide,
+                    Command::Publish(EventKind::WmOverlayDismissed, SecurityLevel::Internal),
+                ]
+            }
+            Some(PaletteInput::Shell(command)) if !command.is_empty() => {
+                if self.palette_confirm.as_deref() == Some(input.as_str()) {
+                    tracing::info!(%command, "command palette: running confirmed shell command");
+                    let command = command.to_string();
+                    self.palette_confirm = None;
+                    self.phase = Phase::Launching;
+                    self.last_ipc_advance = None;
+                    vec![Command::ShowLaunching, Command::RunShellCommand { command }]
+                } else {
+                    let message = palette::message(&PaletteInput::Shell(command), true);
+                    self.palette_confirm = Some(input);
+                    vec![Command::ShowPalette { message }]
+                }
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    // -----------------------------------------------------------------------
+    // Window search
+    // -----------------------------------------------------------------------
+
+    /// Indices of the windows matching the current search, if the input is
+    /// search input.
+    fn search_matches(&self) -> Option<Vec<usize>> {
+        match &self.phase {
+            Phase::Armed { input, snap, .. } | Phase::Picking { input, snap, .. } => {
+                search::parse(input)
+                    .map(|query| search::matching_indices(query, &snap.overlay_windows))
+            }
+            _ => None,
+        }
+    }
+
+    /// Search input changed: keep the selection on a matching window and
+    /// show the filtered picker.
+    fn update_search(&mut self) -> Vec<Command> {
+        self.clear_pending_launch();
+        match &mut self.phase {
+            Phase::Armed {
+                input,
+                snap,
+                selection,
+                ..
+            }
+            | Phase::Picking {
+                input,
+                snap,
+                selection,
+                ..
+            } => {
+                if let Some(query) = search::parse(input) {
+                    select_first_match(query, snap, selection);
+                }
+            }
+            _ => return Vec::new(),
+        }
+        if matches!(self.phase, Phase::Armed { .. }) {
+            self.transition_armed_to_picking()
+        } else {
+            match &self.phase {
+                Phase::Picking {
+                    input, selection, ..
+                } => vec![Command::UpdatePicker {
+                    input: input.clone(),
+                    selection: *selection,
+                }],
+                _ => Vec::new(),
+            }
+        }
+    }
+
+    // -----------------------------------------------------------------------
+    // Helpers
+    // -----------------------------------------------------------------------
+
+    fn current_selection(&self) -> usize {
+        match &self.phase {
+            Phase::Armed { selection, .. } | Phase::Picking { selection, .. } => *selection,
+            _ => 0,
+        }
+    }
+
+    fn update_selection(&mut self, idx: usize) {
+        match &mut self.phase {
+            Phase::Armed { selection, .. } | Phase::Picking { selection, .. } => {
+                *selection = idx;
+            }
+            _ => {}
+        }
+    }
+
+    fn set_pending_launch(&mut self, launch: PendingLaunch) {
+        match &mut self.phase {
+            Phase::Armed { pending_launch, .. } | Phase::Picking { pending_launch, .. } => {
+                *pending_launch = Some(launch);
+            }
+            _ => {}
+        }
+    }
+
+    fn clear_pending_launch(&mut self) {
+        match &mut self.phase {
+            Phase::Armed { pending_launch, .. } | Phase::Picking { pending_launch, .. } => {
+                *pending_launch = None;
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Default for OverlayController {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// An event as the row menu and help overlay see it: typing is off while
+/// they are open, so a character that triggers an action on empty input
+/// (`.`, `?`) is that action.
+fn popup_event(event: Event) -> Event {
+    match event {
+        Event::Char(ch) => actions::typed(ch).map_or(event, |action| action.event.clone()),
+        event => event,
+    }
+}
+
+/// Move `selection` to the first window matching `query` unless it already
+/// points at a match. Left unchanged when nothing matches.
+fn select_first_match(query: &str, snap: &Snapshot, selection: &mut usize) {
+    let matches = search::matching_indices(query, &snap.overlay_windows);
+    if !matches.contains(selection)
+        && let Some(&first) = matches.first()
+    {
+        *selection = first;
+    }
+}
+
+/// The windows a close action at `selection` applies to: the selected
+/// window, or with `whole_app` every listed window of its app. Multiplexer
+/// rows are never closed themselves — closing one would close its terminal
+/// — but count for `whole_app`. Workspace rows close nothing.
+fn close_targets(snap: &Snapshot, selection: usize, whole_app: bool) -> Vec<Window> {
+    let Some(selected) = snap.windows.get(selection) else {
+        return Vec::new();
+    };
+    if !whole_app && snap.items.get(selection) != Some(&SelectableItem::Window) {
+        return Vec::new();
+    }
+    snap.windows
+        .iter()
+        .zip(&snap.items)
+        .filter(|(w, item)| {
+            **item == SelectableItem::Window
+                && if whole_app {
+                    w.app_id == selected.app_id
+                } else {
+                    w.id == selected.id
+                }
+        })
+        .map(|(w, _)| w.clone())
+        .collect()
+}
+
+/// The next (or previous) entry of `matches` after `current`, wrapping.
+/// Returns `current` when there are no matches.
+fn step_within(matches: &[usize], current: usize, forward: bool) -> usize {
+    let Some(pos) = matches.iter().position(|&i| i == current) else {
+        return matches.first().copied().unwrap_or(current);
+    };
+    let next = if forward {
+        (pos + 1) % matches.len()
+    } else {
+        (pos + matches.len() - 1) % matches.len()
+    };
+    matches[next]
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core_config::WmKeyBinding;
+
+    fn test_config() -> WmConfig {
+        WmConfig {
+            quick_switch_threshold_ms: 250,
+            activation_delay_ms: 200,
+            max_visible_windows: 20,
+            hint_keys: "asdfghjkl".into(),
+            key_bindings: [
+                ("g", vec!["com.mitchellh.ghostty"], Some("ghostty")),
+                ("f", vec!["firefox"], Some("firefox")),
+                ("e", vec!["microsoft-edge"], Some("microsoft-edge")),
+            ]
+            .into_iter()
+            .map(|(k, apps, launch)| {
+                (
+                    k.to_string(),
+                    WmKeyBinding {
+                        apps: apps.into_iter().map(String::from).collect(),
+                        launch: launch.map(String::from),
+                        ..Default::default()
+                    },
+                )
+            })
+            .collect(),
+            ..Default::default()
+        }
+    }
+
+    fn test_windows() -> Vec<Window> {
+        vec![
+            Window {
+                id: core_types::WindowId::new(),
+                app_id: core_types::AppId::new("com.mitchellh.ghostty"),
+                title: "Terminal".into(),
+                workspace_id: core_types::CompositorWorkspaceId::new(),
+                monitor_id: core_types::MonitorId::new(),
+                geometry: core_types::Geometry {
+                    x: 0,
+                    y: 0,
+                    width: 800,
+                    height: 600,
+                },
+                is_focused: true,
+                is_minimized: false,
+                is_fullscreen: false,
+                is_maximized: false,
+                profile_id: core_types::ProfileId::new(),
+                parent: None,
+                is_sticky: false,
+                is_keep_above: false,
+            },
+            Window {
+                id: core_types::WindowId::new(),
+                app_id: core_types::AppId::new("firefox"),
+                title: "Firefox".into(),
+                workspace_id: core_types::CompositorWorkspaceId::new(),
+                monitor_id: core_types::MonitorId::new(),
+                geometry: core_types::Geometry {
+                    x: 0,
+                    y: 0,
+                    width: 800,
+                    height: 600,
+                },
+                is_focused: false,
+                is_minimized: false,
+                is_fullscreen: false,
+                is_maximized: false,
+                profile_id: core_types::ProfileId::new(),
+                parent: None,
+                is_sticky: false,
+                is_keep_above: false,
+            },
+            Window {
+                id: core_types::WindowId::new(),
+                app_id: core_types::AppId::new("microsoft-edge"),
+                title: "Edge".into(),
+                workspace_id: core_types::CompositorWorkspaceId::new(),
+                monitor_id: core_types::MonitorId::new(),
+                geometry: core_types::Geometry {
+                    x: 0,
+                    y: 0,
+                    width: 800,
+                    height: 600,
+                },
+                is_focused: false,
+                is_minimized: false,
+                is_fullscreen: false,
+                is_maximized: false,
+                profile_id: core_types::ProfileId::new(),
+                parent: None,
+                is_sticky: false,
+                is_keep_above: false,
+            },
+        ]
+    }
+
+    // === Redacted state dump ===
+
+    #[test]
+    fn redacted_state_leaves_out_titles_and_input() {
+        let windows = test_windows();
+        let config = test_config();
+        let mut ctrl = OverlayController::new();
+        assert!(ctrl.redacted_state().starts_with("Idle"));
+
+        ctrl.handle(Event::ActivateLauncher, &windows, &config);
+        ctrl.handle(Event::Char('z'), &windows, &config);
+        let state = ctrl.redacted_state();
+        assert!(state.contains("input_len: 1"), "{state}");
+        assert!(!state.contains('z'), "{state}");
+        for window in &windows {
+            assert!(!state.contains(&window.title), "{state}");
+        }
+    }
+
+    // === Forward activation ===
+
+    #[test]
+    fn activate_emits_show_border() {
+        let mut ctrl = OverlayController::new();
+        let cmds = ctrl.handle(Event::Activate, &test_windows(), &test_config());
+        assert!(cmds.iter().any(|c| matches!(c, Command::ShowBorder { .. })));
+        assert!(!ctrl.is_idle());
+    }
+
+    #[test]
+    fn activate_sets_dwell_deadline() {
+        let mut ctrl = OverlayController::new();
+        ctrl.handle(Event::Activate, &test_windows(), &test_config());
+        assert!(ctrl.next_deadline().is_some());
+    }
+
+    #[test]
+    fn delay_indicator_carries_dwell_when_enabled() {
+        let indicator = |event, config: &WmConfig| {
+            let mut ctrl = OverlayController::new();
+            ctrl.handle(event, &test_windows(), config)
+                .into_iter()
+                .find_map(|c| match c {
+                    Command::ShowBorder {
+                        delay_indicator_ms, ..
+                    } => Some(delay_indicator_ms),
+                    _ => None,
+                })
+                .unwrap()
+        };
+        assert_eq!(indicator(Event::Activate, &test_config()), None);
+
+        let config = WmConfig {
+            show_delay_indicator: true,
+            ..test_config()
+        };
+        assert_eq!(indicator(Event::Activate, &config), Some(250));
+        assert_eq!(indicator(Event::ActivateBackward, &config), Some(250));
+        assert_eq!(indicator(Event::ActivateLauncher, &config), None);
+    }
+
+    #[test]
+    fn forward_initial_selection_is_not_origin() {
+        let mut ctrl = OverlayController::new();
+        ctrl.handle(Event::Activate, &test_windows(), &test_config());
+        if let Phase::Armed {
+            selection, snap, ..
+        } = &ctrl.phase
+        {
+            assert_ne!(
+                Some(*selection),
+                snap.origin_index,
                 "forward should not default-select origin"
             );
         }
-    }
+    }
+
+    // === Backward activation ===
+
+    #[test]
+    fn backward_initial_selection_is_not_origin() {
+        let mut ctrl = OverlayController::new();
+        ctrl.handle(Event::ActivateBackward, &test_windows(), &test_config());
+        if let Phase::Armed {
+            selection, snap, ..
+        } = &ctrl.phase
+        {
+            assert_ne!(
+                Some(*selection),
+                snap.origin_index,
+                "backward should not default-select origin"
+            );
+        }
+    }
+
+    // === Launcher activation ===
+
+    #[test]
+    fn launcher_enters_armed_with_short_dwell() {
+        let mut ctrl = OverlayController::new();
+        let cmds = ctrl.handle(Event::ActivateLauncher, &test_windows(), &test_config());
+        assert!(cmds.iter().any(|c| matches!(c, Command::ShowBorder { .. })));
+        assert!(matches!(ctrl.phase, Phase::Armed { .. }));
+        // Short dwell means a deadline is set.
+        assert!(ctrl.next_deadline().is_some());
+    }
+
+    #[test]
+    fn launcher_overrides_apply_until_the_next_switcher_activation() {
+        let mut config = test_config();
+        config.launcher.max_visible_windows = Some(1);
+        let windows = test_windows();
+        let rows = |ctrl: &OverlayController| match &ctrl.phase {
+            Phase::Armed { snap, .. } => snap.windows.len(),
+            _ => panic!("expected Armed"),
+        };
+
+        let mut ctrl = OverlayController::new();
+        ctrl.handle(Event::ActivateLauncher, &windows, &config);
+        assert_eq!(rows(&ctrl), 1);
+        ctrl.handle(Event::Escape, &windows, &config);
+        ctrl.handle(Event::Activate, &windows, &config);
+        assert_eq!(rows(&ctrl), windows.len());
+    }
+
+    #[test]
+    fn launcher_activation_upgrades_an_open_switcher() {
+        let mut config = test_config();
+        config.launcher.max_visible_windows = Some(1);
+        let windows = test_windows();
+
+        let mut ctrl = OverlayController::new();
+        ctrl.handle(Event::Activate, &windows, &config);
+        assert_eq!(ctrl.invocation(), InvocationMode::Switcher);
+        let cmds = ctrl.handle(Event::ActivateLauncher, &windows, &config);
+        assert_eq!(ctrl.invocation(), InvocationMode::Launcher);
+        assert!(cmds.iter().any(|c| matches!(c, Command::ShowPicker { .. })));
+        assert!(cmds.iter().any(|c| matches!(c, Command::ResetGrace)));
+        match &ctrl.phase {
+            Phase::Picking { snap, .. } => assert_eq!(snap.windows.len(), 1),
+            _ => panic!("expected Picking"),
+        }
+
+        // Once upgraded, further launcher activations just cycle.
+        ctrl.handle(Event::ActivateLauncher, &windows, &config);
+        assert_eq!(ctrl.invocation(), InvocationMode::Launcher);
+        assert!(matches!(ctrl.phase, Phase::Picking { .. }));
+    }
+
+    #[test]
+    fn launcher_dwell_transitions_to_picking() {
+        let mut ctrl = OverlayController::new();
+        let windows = test_windows();
+        ctrl.handle(Event::ActivateLauncher, &windows, &test_config());
+        let cmds = ctrl.handle(Event::DwellTimeout, &windows, &test_config());
+        assert!(cmds.iter().any(|c| matches!(c, Command::ShowPicker { .. })));
+        assert!(matches!(ctrl.phase, Phase::Picking { .. }));
+    }
+
+    #[test]
+    fn launcher_initial_selection_is_not_origin() {
+        let mut ctrl = OverlayController::new();
+        ctrl.handle(Event::ActivateLauncher, &test_windows(), &test_config());
+        if let Phase::Armed {
+            selection, snap, ..
+        } = &ctrl.phase
+        {
+            assert_ne!(Some(*selection), snap.origin_index);
+        }
+    }
+
+    #[test]
+    fn launcher_prefill_selects_hint() {
+        let mut ctrl = OverlayController::new();
+        let windows = test_windows();
+        ctrl.handle(Event::ActivateLauncher, &windows, &test_config());
+        ctrl.handle(Event::Prefill("f".into()), &windows, &test_config());
+        if let Phase::Picking {
+            input,
+            selection,
+            snap,
+            ..
+        } = &ctrl.phase
+        {
+            assert_eq!(input, "f");
+            assert_eq!(snap.windows[*selection].app_id.as_str(), "firefox");
+        } else {
+            panic!("expected Picking after exact hint, got {:?}", ctrl.phase);
+        }
+    }
+
+    // === Activation failure ===
+
+    #[test]
+    fn commit_verifies_window_before_hiding() {
+        let mut ctrl = OverlayController::new();
+        let windows = test_windows();
+        ctrl.handle(Event::Activate, &windows, &test_config());
+        let cmds = ctrl.handle(Event::ModifierReleased, &windows, &test_config());
+        let ensure = cmds
+            .iter()
+            .position(|c| matches!(c, Command::EnsureWindowAlive { .. }));
+        let hide = cmds.iter().position(|c| matches!(c, Command::HideAndSync));
+        assert!(ensure.is_some() && ensure < hide);
+    }
+
+    #[test]
+    fn activation_failure_reshows_picker_with_fresh_windows() {
+        let mut ctrl = OverlayController::new();
+        let windows = test_windows();
+        ctrl.handle(Event::Activate, &windows, &test_config());
+        ctrl.handle(Event::ModifierReleased, &windows, &test_config());
+        assert!(ctrl.is_idle());
+
+        let gone = windows[1].id;
+        let remaining = vec![windows[0].clone()];
+        let cmds = ctrl.handle(
+            Event::ActivationFailed {
+                window_id: gone,
+                error: None,
+            },
+            &remaining,
+            &test_config(),
+        );
+        assert!(
+            cmds.iter()
+                .any(|c| matches!(c, Command::ShowRetry { windows, .. } if windows.len() == 1))
+        );
+        assert!(matches!(ctrl.phase, Phase::Picking { .. }));
+    }
+
+    #[test]
+    fn compositor_refusal_keeps_the_picker_with_its_reason() {
+        let mut ctrl = OverlayController::new();
+        let windows = test_windows();
+        ctrl.handle(Event::Activate, &windows, &test_config());
+        ctrl.handle(Event::ModifierReleased, &windows, &test_config());
+
+        let cmds = ctrl.handle(
+            Event::ActivationFailed {
+                window_id: windows[1].id,
+                error: Some("Could not switch to firefox: denied".into()),
+            },
+            &windows,
+            &test_config(),
+        );
+        assert!(cmds.iter().any(|c| matches!(
+            c,
+            Command::ShowRetry { message, .. }
+                if message.starts_with("Could not switch to firefox: denied")
+        )));
+        assert!(matches!(ctrl.phase, Phase::Picking { .. }));
+    }
+
+    #[test]
+    fn activation_failure_with_no_windows_dismisses() {
+        let mut ctrl = OverlayController::new();
+        let cmds = ctrl.handle(
+            Event::ActivationFailed {
+                window_id: WindowId::new(),
+                error: None,
+            },
+            &[],
+            &test_config(),
+        );
+        assert!(cmds.iter().any(|c| matches!(c, Command::Hide)));
+        assert!(ctrl.is_idle());
+    }
+
+    // === Quick-switch ===
+
+    #[test]
+    fn fast_release_quick_switches_to_non_origin() {
+        let mut ctrl = OverlayController::new();
+        let windows = test_windows();
+        ctrl.handle(Event::Activate, &windows, &test_config());
+        let cmds = ctrl.handle(Event::ModifierReleased, &windows, &test_config());
+        assert!(cmds.iter().any(|c| matches!(c, Command::HideAndSync)));
+        assert!(
+            cmds.iter()
+                .any(|c| matches!(c, Command::ActivateWindow { .. }))
+        );
+        assert!(ctrl.is_idle());
+    }
+
+    #[test]
+    fn no_targets_is_noop_for_switcher() {
+        let mut ctrl = OverlayController::new();
+        let cmds = ctrl.handle(Event::Activate, &[], &test_config());
+        assert!(cmds.is_empty());
+        assert!(ctrl.is_idle());
+    }
+
+    #[test]
+    fn launcher_activates_with_zero_windows() {
+        let mut ctrl = OverlayController::new();
+        let cmds = ctrl.handle(Event::ActivateLauncher, &[], &test_config());
+        assert!(
+            cmds.iter().any(|c| matches!(c, Command::ShowBorder { .. })),
+            "launcher must activate even with zero windows"
+        );
+        assert!(!ctrl.is_idle());
+    }
+
+    #[test]
+    fn unsupported_activation_hides_window_rows() {
+        let mut ctrl = OverlayController::new();
+        ctrl.set_can_activate(false);
+        let config = test_config();
+        let windows = test_windows();
+        assert!(ctrl.handle(Event::Activate, &windows, &config).is_empty());
+        assert!(ctrl.is_idle());
+
+        // The launcher still opens, but 'g' launches instead of focusing
+        // the running ghostty.
+        ctrl.handle(Event::ActivateLauncher, &windows, &config);
+        let cmds = ctrl.handle(Event::Char('g'), &windows, &config);
+        assert!(
+            cmds.iter()
+                .any(|c| matches!(c, Command::ShowLaunchStaged { .. })),
+            "got: {cmds:?}"
+        );
+    }
+
+    #[test]
+    fn launcher_zero_windows_can_stage_launch() {
+        let mut ctrl = OverlayController::new();
+        let config = test_config();
+        ctrl.handle(Event::ActivateLauncher, &[], &config);
+        // Type 'g' — no ghostty window, but launch binding exists.
+        let cmds = ctrl.handle(Event::Char('g'), &[], &config);
+        assert!(
+            cmds.iter()
+                .any(|c| matches!(c, Command::ShowLaunchStaged { .. })),
+            "must stage ghostty launch with zero windows, got: {cmds:?}"
+        );
+        // Alt release executes.
+        let cmds = ctrl.handle(Event::ModifierReleased, &[], &config);
+        assert!(
+            cmds.iter()
+                .any(|c| matches!(c, Command::LaunchApp { command, .. } if command == "ghostty"))
+        );
+    }
+
+    // === Full-circle cycling reaches origin ===
+
+    #[test]
+    fn full_circle_cycling_visits_all_indices() {
+        let mut ctrl = OverlayController::new();
+        let windows = test_windows();
+        ctrl.handle(Event::ActivateLauncher, &windows, &test_config());
+        ctrl.handle(Event::DwellTimeout, &windows, &test_config());
+
+        let snap_len = if let Phase::Picking { snap, .. } = &ctrl.phase {
+            snap.windows.len()
+        } else {
+            panic!("expected Picking");
+        };
+
+        // Collect every selection index visited over a full cycle.
+        let mut visited = std::collections::HashSet::new();
+        for _ in 0..snap_len {
+            if let Phase::Picking { selection, .. } = &ctrl.phase {
+                visited.insert(*selection);
+            }
+            ctrl.handle(Event::SelectionDown, &windows, &test_config());
+        }
+        // After full cycle we're back at start — check that too.
+        if let Phase::Picking { selection, .. } = &ctrl.phase {
+            visited.insert(*selection);
+        }
+
+        // Every index must be reachable (including origin when it exists).
+        assert_eq!(
+            visited.len(),
+            snap_len,
+            "full-circle cycling must visit all {snap_len} indices, visited {visited:?}"
+        );
+    }
+
+    // === Hint match selects, does NOT commit (commit on Alt release) ===
+
+    #[test]
+    fn hint_match_selects_origin_without_commit() {
+        let mut ctrl = OverlayController::new();
+        let windows = test_windows();
+        ctrl.handle(Event::Activate, &windows, &test_config());
+        // 'g' is the hint for ghostty (the origin/focused window).
+        // Typing it SELECTS ghostty but does NOT commit.
+        let cmds = ctrl.handle(Event::Char('g'), &windows, &test_config());
+        assert!(
+            !cmds
+                .iter()
+                .any(|c| matches!(c, Command::ActivateWindow { .. })),
+            "hint match must NOT activate on keypress"
+        );
+        assert!(!ctrl.is_idle(), "must stay in Picking, not Idle");
+        // Alt release commits the selection.
+        let cmds = ctrl.handle(Event::ModifierReleased, &windows, &test_config());
+        assert!(
+            cmds.iter()
+                .any(|c| matches!(c, Command::ActivateWindow { .. })),
+            "Alt release must commit the hint selection"
+        );
+        assert!(ctrl.is_idle());
+    }
+
+    // === Dwell timeout ===
+
+    #[test]
+    fn dwell_timeout_shows_picker() {
+        let mut ctrl = OverlayController::new();
+        let windows = test_windows();
+        ctrl.handle(Event::Activate, &windows, &test_config());
+        let cmds = ctrl.handle(Event::DwellTimeout, &windows, &test_config());
+        assert!(cmds.iter().any(|c| matches!(c, Command::ShowPicker { .. })));
+        assert!(matches!(ctrl.phase, Phase::Picking { .. }));
+    }
+
+    // === Char input — stage launch or select, never commit on keypress ===
+
+    #[test]
+    fn char_stages_launch_when_no_window() {
+        let mut ctrl = OverlayController::new();
+        let windows = vec![test_windows()[0].clone()]; // only ghostty
+        ctrl.handle(Event::Activate, &windows, &test_config());
+        // 'e' has no window → stages launch, does NOT execute.
+        let cmds = ctrl.handle(Event::Char('e'), &windows, &test_config());
+        assert!(
+            cmds.iter()
+                .any(|c| matches!(c, Command::ShowLaunchStaged { .. })),
+            "must show staged launch, got: {cmds:?}"
+        );
+        assert!(
+            !cmds.iter().any(|c| matches!(c, Command::LaunchApp { .. })),
+            "must NOT execute launch on keypress"
+        );
+        assert!(matches!(ctrl.phase, Phase::Picking { .. }));
+        // Alt release executes the staged launch.
+        let cmds = ctrl.handle(Event::ModifierReleased, &windows, &test_config());
+        assert!(
+            cmds.iter().any(
+                |c| matches!(c, Command::LaunchApp { command, .. } if command == "microsoft-edge")
+            ),
+            "Alt release must execute staged launch"
+        );
+    }
+
+    fn palette_config() -> WmConfig {
+        WmConfig {
+            command_palette: true,
+            ..test_config()
+        }
+    }
+
+    fn type_str(ctrl: &mut OverlayController, s: &str, windows: &[Window], config: &WmConfig) {
+        for ch in s.chars() {
+            ctrl.handle(Event::Char(ch), windows, config);
+        }
+    }
+
+    #[test]
+    fn palette_disabled_ignores_prefix() {
+        let mut ctrl = OverlayController::new();
+        let windows = test_windows();
+        ctrl.handle(Event::ActivateLauncher, &windows, &test_config());
+        let cmds = ctrl.handle(Event::Char('='), &windows, &test_config());
+        assert!(cmds.is_empty(), "'=' is filtered without command_palette");
+    }
+
+    #[test]
+    fn palette_calc_shows_result_and_never_commits_on_release() {
+        let config = palette_config();
+        let mut ctrl = OverlayController::new();
+        let windows = test_windows();
+        ctrl.handle(Event::ActivateLauncher, &windows, &config);
+        type_str(&mut ctrl, "=2+", &windows, &config);
+        let cmds = ctrl.handle(Event::Char('2'), &windows, &config);
+        assert!(
+            cmds.iter()
+                .any(|c| matches!(c, Command::ShowPalette { message } if message == "2+2 = 4")),
+            "got: {cmds:?}"
+        );
+        let cmds = ctrl.handle(Event::ModifierReleased, &windows, &config);
+        assert!(cmds.is_empty(), "release must not activate: {cmds:?}");
+        assert!(matches!(ctrl.phase, Phase::Picking { .. }));
+    }
+
+    #[test]
+    fn palette_shell_requires_confirmation() {
+        let config = palette_config();
+        let mut ctrl = OverlayController::new();
+        let windows = test_windows();
+        ctrl.handle(Event::ActivateLauncher, &windows, &config);
+        type_str(&mut ctrl, ">systemctl suspend", &windows, &config);
+
+        let cmds = ctrl.handle(Event::Confirm, &windows, &config);
+        assert!(
+            !cmds
+                .iter()
+                .any(|c| matches!(c, Command::RunShellCommand { .. })),
+            "first Enter only asks for confirmation"
+        );
+        assert!(
+            cmds.iter()
+                .any(|c| matches!(c, Command::ShowPalette { .. }))
+        );
+
+        let cmds = ctrl.handle(Event::Confirm, &windows, &config);
+        assert!(
+            cmds.iter().any(|c| matches!(
+                c,
+                Command::RunShellCommand { command } if command == "systemctl suspend"
+            )),
+            "second Enter runs the command: {cmds:?}"
+        );
+        assert!(matches!(ctrl.phase, Phase::Launching));
+    }
+
+    #[test]
+    fn palette_edit_resets_confirmation() {
+        let config = palette_config();
+        let mut ctrl = OverlayController::new();
+        let windows = test_windows();
+        ctrl.handle(Event::ActivateLauncher, &windows, &config);
+        type_str(&mut ctrl, ">ls", &windows, &config);
+        ctrl.handle(Event::Confirm, &windows, &config);
+        ctrl.handle(Event::Backspace, &windows, &config);
+        ctrl.handle(Event::Char('s'), &windows, &config);
+        let cmds = ctrl.handle(Event::Confirm, &windows, &config);
+        assert!(
+            !cmds
+                .iter()
+                .any(|c| matches!(c, Command::RunShellCommand { .. })),
+            "edited input needs a fresh confirmation"
+        );
+    }
+
+    #[test]
+    fn search_selects_and_cycles_matches() {
+        let config = test_config();
+        let mut ctrl = OverlayController::new();
+        let windows = test_windows();
+        ctrl.handle(Event::ActivateLauncher, &windows, &config);
+        // "f" is in Firefox and in "microsoft-edge", not in Ghostty/"Terminal".
+        type_str(&mut ctrl, "/f", &windows, &config);
+        let selected_app = |ctrl: &OverlayController| match &ctrl.phase {
+            Phase::Picking {
+                snap, selection, ..
+            } => snap.windows[*selection].app_id.to_string(),
+            other => panic!("expected Picking, got {other:?}"),
+        };
+        let first = selected_app(&ctrl);
+        ctrl.handle(Event::SelectionDown, &windows, &config);
+        let second = selected_app(&ctrl);
+        ctrl.handle(Event::SelectionDown, &windows, &config);
+        assert_eq!(selected_app(&ctrl), first, "cycling wraps within matches");
+
+        let mut visited = [first, second];
+        visited.sort();
+        assert_eq!(visited, ["firefox", "microsoft-edge"]);
+    }
+
+    #[test]
+    fn search_without_matches_never_commits() {
+        let config = test_config();
+        let mut ctrl = OverlayController::new();
+        let windows = test_windows();
+        ctrl.handle(Event::ActivateLauncher, &windows, &config);
+        type_str(&mut ctrl, "/zzz", &windows, &config);
+        assert!(ctrl.handle(Event::Confirm, &windows, &config).is_empty());
+        assert!(
+            ctrl.handle(Event::ModifierReleased, &windows, &config)
+                .is_empty()
+        );
+        assert!(matches!(ctrl.phase, Phase::Picking { .. }));
+    }
+
+    #[test]
+    fn char_selects_hint_without_commit() {
+        let mut ctrl = OverlayController::new();
+        let windows = test_windows();
+        ctrl.handle(Event::Activate, &windows, &test_config());
+        // 'e' matches edge window hint → selects, does NOT commit.
+        let cmds = ctrl.handle(Event::Char('e'), &windows, &test_config());
+        assert!(
+            !cmds
+                .iter()
+                .any(|c| matches!(c, Command::ActivateWindow { .. })),
+            "hint match must NOT activate on keypress"
+        );
+        assert!(!ctrl.is_idle());
+        // Alt release commits.
+        let cmds = ctrl.handle(Event::ModifierReleased, &windows, &test_config());
+        assert!(
+            cmds.iter()
+                .any(|c| matches!(c, Command::ActivateWindow { .. }))
+        );
+        assert!(ctrl.is_idle());
+    }
+
+    #[test]
+    fn repeat_key_cycles_an_apps_windows() {
+        let mut ctrl = OverlayController::new();
+        let mut windows = test_windows();
+        for title in ["Build", "Logs"] {
+            windows.push(Window {
+                id: core_types::WindowId::new(),
+                title: title.into(),
+                is_focused: false,
+                ..windows[0].clone()
+            });
+        }
+        let config = WmConfig {
+            repeat_key_cycles: true,
+            ..test_config()
+        };
+        ctrl.handle(Event::Activate, &windows, &config);
+        let mut hints = Vec::new();
+        let mut visited = Vec::new();
+        for _ in 0..4 {
+            for cmd in ctrl.handle(Event::Char('g'), &windows, &config) {
+                match cmd {
+                    Command::ShowPicker { hints: shown, .. } => hints = shown,
+                    Command::UpdatePicker { input, selection } => {
+                        assert_eq!(input, "g");
+                        visited.push(hints[selection].clone());
+                    }
+                    _ => {}
+                }
+            }
+        }
+        assert_eq!(visited, ["g", "gg", "ggg", "g"]);
+    }
+
+    #[test]
+    fn launcher_char_f_stages_launch_when_not_running() {
+        let mut ctrl = OverlayController::new();
+        // Only ghostty and edge — no firefox window.
+        let windows = vec![test_windows()[0].clone(), test_windows()[2].clone()];
+        ctrl.handle(Event::ActivateLauncher, &windows, &test_config());
+        assert!(matches!(ctrl.phase, Phase::Armed { .. }));
+        // 'f' has no window → stages launch, does NOT execute.
+        let cmds = ctrl.handle(Event::Char('f'), &windows, &test_config());
+        assert!(
+            cmds.iter()
+                .any(|c| matches!(c, Command::ShowLaunchStaged { .. })),
+            "expected ShowLaunchStaged for firefox, got: {cmds:?}"
+        );
+        assert!(matches!(ctrl.phase, Phase::Picking { .. }));
+        // Alt release executes.
+        let cmds = ctrl.handle(Event::ModifierReleased, &windows, &test_config());
+        assert!(
+            cmds.iter()
+                .any(|c| matches!(c, Command::LaunchApp { command, .. } if command == "firefox"))
+        );
+    }
+
+    #[test]
+    fn launcher_command_key_runs_shell_command() {
+        let mut ctrl = OverlayController::new();
+        let mut config = test_config();
+        config.key_bindings.insert(
+            "p".into(),
+            WmKeyBinding {
+                command: Some("grim -g \"$(slurp)\"".into()),
+                ..Default::default()
+            },
+        );
+        let windows = test_windows();
+        ctrl.handle(Event::ActivateLauncher, &windows, &config);
+        let cmds = ctrl.handle(Event::Char('p'), &windows, &config);
+        assert!(
+            cmds.iter()
+                .any(|c| matches!(c, Command::ShowPalette { message } if message.contains("grim"))),
+            "expected the staged command to be shown, got: {cmds:?}"
+        );
+        let cmds = ctrl.handle(Event::ModifierReleased, &windows, &config);
+        assert!(
+            cmds.iter().any(|c| matches!(
+                c,
+                Command::RunShellCommand { command } if command == "grim -g \"$(slurp)\""
+            )),
+            "Alt release must run the command, got: {cmds:?}"
+        );
+        assert!(
+            !cmds
+                .iter()
+                .any(|c| matches!(c, Command::ActivateWindow { .. }))
+        );
+        assert!(matches!(ctrl.phase, Phase::Launching));
+    }
+
+    #[test]
+    fn launcher_char_f_selects_firefox_when_running() {
+        let mut ctrl = OverlayController::new();
+        let windows = test_windows(); // includes firefox
+        ctrl.handle(Event::ActivateLauncher, &windows, &test_config());
+        // 'f' matches firefox hint → selects, does NOT commit.
+        let cmds = ctrl.handle(Event::Char('f'), &windows, &test_config());
+        assert!(
+            !cmds
+                .iter()
+                .any(|c| matches!(c, Command::ActivateWindow { .. })),
+            "hint match must NOT activate on keypress, got: {cmds:?}"
+        );
+        assert!(!ctrl.is_idle());
+        // Alt release commits.
+        let cmds = ctrl.handle(Event::ModifierReleased, &windows, &test_config());
+        assert!(
+            cmds.iter()
+                .any(|c| matches!(c, Command::ActivateWindow { .. }))
+        );
+        assert!(ctrl.is_idle());
+    }
+
+    #[test]
+    fn backspace_cancels_staged_launch() {
+        let mut ctrl = OverlayController::new();
+        let windows = vec![test_windows()[0].clone()]; // only ghostty
+        ctrl.handle(Event::Activate, &windows, &test_config());
+        ctrl.handle(Event::DwellTimeout, &windows, &test_config());
+        // Stage a launch.
+        ctrl.handle(Event::Char('e'), &windows, &test_config());
+        assert!(matches!(
+            ctrl.phase,
+            Phase::Picking {
+                pending_launch: Some(_),
+                ..
+            }
+        ));
+        // Backspace clears input and pending launch.
+        ctrl.handle(Event::Backspace, &windows, &test_config());
+        assert!(matches!(
+            ctrl.phase,
+            Phase::Picking {
+                pending_launch: None,
+                ..
+            }
+        ));
+        // Alt release now activates a window, not a launch.
+        let cmds = ctrl.handle(Event::ModifierReleased, &windows, &test_config());
+        assert!(
+            !cmds.iter().any(|c| matches!(c, Command::LaunchApp { .. })),
+            "backspace must cancel staged launch"
+        );
+    }
+
+    // === Navigation shows picker ===
+
+    #[test]
+    fn tab_in_armed_shows_picker() {
+        let mut ctrl = OverlayController::new();
+        let windows = test_windows();
+        ctrl.handle(Event::Activate, &windows, &test_config());
+        let cmds = ctrl.handle(Event::SelectionDown, &windows, &test_config());
+        assert!(cmds.iter().any(|c| matches!(c, Command::ShowPicker { .. })));
+        assert!(matches!(ctrl.phase, Phase::Picking { .. }));
+    }
+
+    // === Escape ===
+
+    #[test]
+    fn escape_from_armed_dismisses() {
+        let mut ctrl = OverlayController::new();
+        ctrl.handle(Event::Activate, &test_windows(), &test_config());
+        let cmds = ctrl.handle(Event::Escape, &test_windows(), &test_config());
+        assert!(cmds.iter().any(|c| matches!(c, Command::Hide)));
+        assert!(ctrl.is_idle());
+    }
+
+    #[test]
+    fn usage_records_activations_and_cancels() {
+        let usage = |cmds: &[Command]| {
+            cmds.iter().find_map(|c| match c {
+                Command::RecordUsage { kind, app, .. } => Some((*kind, app.clone())),
+                _ => None,
+            })
+        };
+        let mut ctrl = OverlayController::new();
+        let windows = test_windows();
+        ctrl.handle(Event::Activate, &windows, &test_config());
+        let cmds = ctrl.handle(Event::Escape, &windows, &test_config());
+        assert_eq!(usage(&cmds), Some((UsageKind::Cancel, None)));
+
+        ctrl.handle(Event::ActivateLauncher, &windows, &test_config());
+        ctrl.handle(Event::Char('f'), &windows, &test_config());
+        let cmds = ctrl.handle(Event::Confirm, &windows, &test_config());
+        assert_eq!(
+            usage(&cmds),
+            Some((UsageKind::Activate, Some("firefox".into())))
+        );
+    }
+
+    #[test]
+    fn adaptive_quick_switch_times_plain_releases_and_uses_learned_dwell() {
+        let records = |cmds: &[Command]| {
+            cmds.iter()
+                .filter(|c| matches!(c, Command::RecordReleaseTime(_)))
+                .count()
+        };
+        let dwell = |ctrl: &OverlayController| match &ctrl.phase {
+            Phase::Armed { dwell_ms, .. } => *dwell_ms,
+            _ => panic!("expected Armed"),
+        };
+        let mut config = test_config();
+        let windows = test_windows();
+        let mut ctrl = OverlayController::new();
+        ctrl.set_learned_quick_switch(Some(400));
+
+        // Off: configured dwell, nothing recorded.
+        ctrl.handle(Event::Activate, &windows, &config);
+        assert_eq!(dwell(&ctrl), 250);
+        let cmds = ctrl.handle(Event::ModifierReleased, &windows, &config);
+        assert_eq!(records(&cmds), 0);
+
+        config.adaptive_quick_switch = true;
+        ctrl.handle(Event::Activate, &windows, &config);
+        assert_eq!(dwell(&ctrl), 400);
+        let cmds = ctrl.handle(Event::ModifierReleased, &windows, &config);
+        assert_eq!(records(&cmds), 1);
+
+        // Moving the selection makes it a pick, not a quick switch.
+        ctrl.handle(Event::Activate, &windows, &config);
+        ctrl.handle(Event::SelectionDown, &windows, &config);
+        let cmds = ctrl.handle(Event::ModifierReleased, &windows, &config);
+        assert_eq!(records(&cmds), 0);
+
+        // Alt+Shift+Tab is not timed.
+        ctrl.handle(Event::ActivateBackward, &windows, &config);
+        let cmds = ctrl.handle(Event::ModifierReleased, &windows, &config);
+        assert_eq!(records(&cmds), 0);
+    }
+
+    #[test]
+    fn escape_from_idle_is_noop() {
+        let mut ctrl = OverlayController::new();
+        let cmds = ctrl.handle(Event::Escape, &[], &test_config());
+        assert!(cmds.is_empty());
+    }
+
+    // === Confirm ===
+
+    #[test]
+    fn confirm_in_picking_activates() {
+        let mut ctrl = OverlayController::new();
+        let windows = test_windows();
+        ctrl.handle(Event::Activate, &windows, &test_config());
+        ctrl.handle(Event::DwellTimeout, &windows, &test_config());
+        let cmds = ctrl.handle(Event::Confirm, &windows, &test_config());
+        assert!(
+            cmds.iter()
+                .any(|c| matches!(c, Command::ActivateWindow { .. }))
+        );
+        assert!(ctrl.is_idle());
+    }
+
+    // === Re-activation cycles all windows ===
+
+    #[test]
+    fn reactivate_transitions_armed_to_picking() {
+        let mut ctrl = OverlayController::new();
+        let windows = test_windows();
+        ctrl.handle(Event::Activate, &windows, &test_config());
+        let cmds = ctrl.handle(Event::Activate, &windows, &test_config());
+        // Re-activation in Armed transitions to Picking with picker visible.
+        assert!(cmds.iter().any(|c| matches!(c, Command::ShowPicker { .. })));
+        assert!(cmds.iter().any(|c| matches!(c, Command::ResetGrace)));
+        if let Phase::Picking {
+            selection, snap, ..
+        } = &ctrl.phase
+        {
+            assert!(*selection < snap.windows.len());
+        } else {
+            panic!("expected Picking after re-activation");
+        }
+    }
+
+    // === Re-activation grace reset (prevents modifier poll premature commit) ===
+
+    #[test]
+    fn reactivate_armed_emits_reset_grace() {
+        let mut ctrl = OverlayController::new();
+        let windows = test_windows();
+        ctrl.handle(Event::Activate, &windows, &test_config());
+        let cmds = ctrl.handle(Event::Activate, &windows, &test_config());
+        assert!(
+            cmds.iter().any(|c| matches!(c, Command::ResetGrace)),
+            "re-activation in Armed must emit ResetGrace to prevent modifier poll commit"
+        );
+    }
+
+    #[test]
+    fn reactivate_picking_emits_reset_grace() {
+        let mut ctrl = OverlayController::new();
+        let windows = test_windows();
+        ctrl.handle(Event::Activate, &windows, &test_config());
+        ctrl.handle(Event::DwellTimeout, &windows, &test_config());
+        assert!(matches!(ctrl.phase, Phase::Picking { .. }));
+        let cmds = ctrl.handle(Event::Activate, &windows, &test_config());
+        assert!(
+            cmds.iter().any(|c| matches!(c, Command::ResetGrace)),
+            "re-activation in Picking must emit ResetGrace to prevent modifier poll commit"
+        );
+    }
+
+    #[test]
+    fn reactivate_launcher_emits_reset_grace() {
+        let mut ctrl = OverlayController::new();
+        let windows = test_windows();
+        ctrl.handle(Event::ActivateLauncher, &windows, &test_config());
+        let cmds = ctrl.handle(Event::ActivateLauncher, &windows, &test_config());
+        assert!(
+            cmds.iter().any(|c| matches!(c, Command::ResetGrace)),
+            "launcher re-activation must emit ResetGrace"
+        );
+    }
+
+    #[test]
+    fn reactivate_backward_emits_reset_grace() {
+        let mut ctrl = OverlayController::new();
+        let windows = test_windows();
+        ctrl.handle(Event::Activate, &windows, &test_config());
+        let cmds = ctrl.handle(Event::ActivateBackward, &windows, &test_config());
+        assert!(
+            cmds.iter().any(|c| matches!(c, Command::ResetGrace)),
+            "backward re-activation must emit ResetGrace"
+        );
+    }
+
+    // === Rapid re-activation cycling (Alt+Space+Space+Space...) ===
+
+    #[test]
+    fn rapid_reactivation_cycles_all_windows() {
+        let mut ctrl = OverlayController::new();
+        let windows = test_windows();
+        ctrl.handle(Event::ActivateLauncher, &windows, &test_config());
+
+        let snap_len = match &ctrl.phase {
+            Phase::Armed { snap, .. } => snap.windows.len(),
+            _ => panic!("expected Armed"),
+        };
+
+        // Simulate Alt+Space repeated — each re-activation should cycle
+        // forward through all windows without committing.
+        let mut visited = std::collections::HashSet::new();
+        for _ in 0..snap_len {
+            let cmds = ctrl.handle(Event::ActivateLauncher, &windows, &test_config());
+            // Must never commit (no ActivateWindow or HideAndSync).
+            assert!(
+                !cmds
+                    .iter()
+                    .any(|c| matches!(c, Command::ActivateWindow { .. } | Command::HideAndSync)),
+                "re-activation must not commit"
+            );
+            if let Phase::Picking { selection, .. } = &ctrl.phase {
+                visited.insert(*selection);
+            }
+        }
 
-    // === Backward activation ===
+        assert_eq!(
+            visited.len(),
+            snap_len,
+            "rapid re-activation must visit all {snap_len} windows, visited {visited:?}"
+        );
+    }
 
     #[test]
-    fn backward_initial_selection_is_not_origin() {
+    fn rapid_reactivation_no_commit_until_modifier_released() {
         let mut ctrl = OverlayController::new();
-        ctrl.handle(Event::ActivateBackward, &test_windows(), &test_config());
-        if let Phase::Armed {
-            selection, snap, ..
-        } = &ctrl.phase
-        {
-            assert_ne!(
-                Some(*selection),
-                snap.origin_index,
-                "backward should not default-select origin"
+        let windows = test_windows();
+        ctrl.handle(Event::ActivateLauncher, &windows, &test_config());
+
+        // 20 rapid re-activations — none should commit.
+        for i in 0..20 {
+            let cmds = ctrl.handle(Event::ActivateLauncher, &windows, &test_config());
+            assert!(
+                !cmds
+                    .iter()
+                    .any(|c| matches!(c, Command::ActivateWindow { .. })),
+                "re-activation #{i} must not activate a window"
             );
+            assert!(!ctrl.is_idle(), "must not return to Idle during cycling");
+        }
+
+        // Only ModifierReleased commits.
+        let cmds = ctrl.handle(Event::ModifierReleased, &windows, &test_config());
+        assert!(
+            cmds.iter()
+                .any(|c| matches!(c, Command::ActivateWindow { .. }))
+        );
+        assert!(ctrl.is_idle());
+    }
+
+    #[test]
+    fn rapid_reactivation_forward_then_backward() {
+        let mut ctrl = OverlayController::new();
+        let windows = test_windows();
+        ctrl.handle(Event::ActivateLauncher, &windows, &test_config());
+
+        // Forward 3 times.
+        ctrl.handle(Event::ActivateLauncher, &windows, &test_config());
+        ctrl.handle(Event::ActivateLauncher, &windows, &test_config());
+        ctrl.handle(Event::ActivateLauncher, &windows, &test_config());
+        let sel_after_forward = match &ctrl.phase {
+            Phase::Picking { selection, .. } => *selection,
+            _ => panic!("expected Picking"),
+        };
+
+        // Backward 2 times (simulates adding Shift mid-cycle).
+        ctrl.handle(Event::ActivateBackward, &windows, &test_config());
+        ctrl.handle(Event::ActivateBackward, &windows, &test_config());
+        let sel_after_backward = match &ctrl.phase {
+            Phase::Picking { selection, .. } => *selection,
+            _ => panic!("expected Picking"),
+        };
+
+        // Net movement: 3 forward - 2 backward = 1 forward from initial.
+        // Initial Armed selection is initial_forward(), first re-activation
+        // cycles +1, so after 3 forward we're at initial+3, minus 2 backward = initial+1.
+        assert_ne!(
+            sel_after_forward, sel_after_backward,
+            "backward must change selection from forward position"
+        );
+    }
+
+    // === Release after interaction ===
+
+    #[test]
+    fn release_after_tab_activates_selection() {
+        let mut ctrl = OverlayController::new();
+        let windows = test_windows();
+        ctrl.handle(Event::Activate, &windows, &test_config());
+        ctrl.handle(Event::DwellTimeout, &windows, &test_config());
+        ctrl.handle(Event::SelectionDown, &windows, &test_config());
+        ctrl.handle(Event::SelectionDown, &windows, &test_config());
+        let cmds = ctrl.handle(Event::ModifierReleased, &windows, &test_config());
+        assert!(
+            cmds.iter()
+                .any(|c| matches!(c, Command::ActivateWindow { .. }))
+        );
+    }
+
+    #[test]
+    fn launcher_modifier_release_commits() {
+        let mut ctrl = OverlayController::new();
+        let windows = test_windows();
+        ctrl.handle(Event::ActivateLauncher, &windows, &test_config());
+        ctrl.handle(Event::SelectionDown, &windows, &test_config());
+        // Releasing Alt commits the selection, same as Alt+Tab mode.
+        let cmds = ctrl.handle(Event::ModifierReleased, &windows, &test_config());
+        assert!(
+            cmds.iter()
+                .any(|c| matches!(c, Command::ActivateWindow { .. }))
+        );
+        assert!(ctrl.is_idle());
+    }
+
+    #[test]
+    fn launcher_confirm_activates_selection() {
+        let mut ctrl = OverlayController::new();
+        let windows = test_windows();
+        ctrl.handle(Event::ActivateLauncher, &windows, &test_config());
+        ctrl.handle(Event::SelectionDown, &windows, &test_config());
+        let cmds = ctrl.handle(Event::Confirm, &windows, &test_config());
+        assert!(
+            cmds.iter()
+                .any(|c| matches!(c, Command::ActivateWindow { .. }))
+        );
+        assert!(ctrl.is_idle());
+    }
+
+    // === Confirm on origin is valid (user navigated there) ===
+
+    #[test]
+    fn confirm_on_origin_is_valid() {
+        let mut ctrl = OverlayController::new();
+        let windows = test_windows();
+        ctrl.handle(Event::ActivateLauncher, &windows, &test_config());
+        // DwellTimeout transitions Armed → Picking.
+        ctrl.handle(Event::DwellTimeout, &windows, &test_config());
+
+        // Navigate to origin by cycling all the way around.
+        let snap_len = if let Phase::Picking { snap, .. } = &ctrl.phase {
+            snap.windows.len()
+        } else {
+            panic!("expected Picking")
+        };
+
+        for _ in 0..snap_len {
+            ctrl.handle(Event::SelectionDown, &windows, &test_config());
         }
+        // After full circle we're back at the initial (non-origin) position.
+        // Navigate one more step back to reach origin (which is last).
+        ctrl.handle(Event::SelectionUp, &windows, &test_config());
+
+        // Confirm — should activate whatever is selected, even if origin.
+        let cmds = ctrl.handle(Event::Confirm, &windows, &test_config());
+        assert!(
+            cmds.iter()
+                .any(|c| matches!(c, Command::ActivateWindow { .. }))
+        );
+        assert!(ctrl.is_idle());
+    }
+
+    // ===================================================================
+    // Snapshot unit tests — origin_index explicitly set
+    // ===================================================================
+
+    #[test]
+    fn pinned_apps_list_first_but_quick_switch_keeps_mru_target() {
+        let config = WmConfig {
+            pinned_apps: vec!["microsoft-edge".into()],
+            ..test_config()
+        };
+        let windows = test_windows();
+        // Fresh window IDs are unknown to the MRU stack, so MRU order is
+        // compositor order and the previous window is Ghostty.
+        let snap = Snapshot::build(
+            &windows,
+            &config,
+            &TitleRewriter::default(),
+            &MultiplexerTargets::new(),
+            &[],
+        );
+        assert_eq!(snap.windows[0].app_id.as_str(), "microsoft-edge");
+        assert!(snap.overlay_windows[0].pinned);
+        assert!(!snap.overlay_windows[1].pinned);
+        assert_eq!(
+            snap.windows[snap.initial_forward()].app_id.as_str(),
+            "com.mitchellh.ghostty"
+        );
+    }
+
+    #[test]
+    fn minimized_windows_are_dimmed_hidden_or_listed_last() {
+        let mut windows = test_windows();
+        windows[0].is_minimized = true;
+        // Fresh window IDs are unknown to the MRU stack, so MRU order is
+        // compositor order: Ghostty first.
+        let show = Snapshot::build(
+            &windows,
+            &test_config(),
+            &TitleRewriter::default(),
+            &MultiplexerTargets::new(),
+            &[],
+        );
+        assert_eq!(show.windows[0].app_id.as_str(), "com.mitchellh.ghostty");
+        assert!(show.overlay_windows[0].minimized);
+        assert!(!show.overlay_windows[1].minimized);
+
+        let config = WmConfig {
+            minimized_windows: MinimizedWindows::Hide,
+            ..test_config()
+        };
+        let hide = Snapshot::build(
+            &windows,
+            &config,
+            &TitleRewriter::default(),
+            &MultiplexerTargets::new(),
+            &[],
+        );
+        assert_eq!(hide.windows.len(), windows.len() - 1);
+        assert!(hide.windows.iter().all(|w| !w.is_minimized));
+
+        let config = WmConfig {
+            minimized_windows: MinimizedWindows::Last,
+            ..test_config()
+        };
+        let last = Snapshot::build(
+            &windows,
+            &config,
+            &TitleRewriter::default(),
+            &MultiplexerTargets::new(),
+            &[],
+        );
+        assert_eq!(
+            last.windows.last().unwrap().app_id.as_str(),
+            "com.mitchellh.ghostty"
+        );
+        assert_ne!(
+            last.windows[last.initial_forward()].app_id.as_str(),
+            "com.mitchellh.ghostty"
+        );
+    }
+
+    #[test]
+    fn window_hint_placement_places_windows_shown_with_the_focused_one() {
+        let mut windows = test_windows();
+        windows[1].monitor_id = windows[0].monitor_id;
+        windows[1].workspace_id = windows[0].workspace_id;
+        windows[2].workspace_id = windows[0].workspace_id;
+        let list = Snapshot::build(
+            &windows,
+            &test_config(),
+            &TitleRewriter::default(),
+            &MultiplexerTargets::new(),
+            &[],
+        );
+        assert!(list.overlay_windows.iter().all(|w| w.geometry.is_none()));
+
+        let config = WmConfig {
+            hint_placement: core_config::HintPlacement::Windows,
+            ..test_config()
+        };
+        let snap = Snapshot::build(
+            &windows,
+            &config,
+            &TitleRewriter::default(),
+            &MultiplexerTargets::new(),
+            &[],
+        );
+        let placed: Vec<&str> = snap
+            .windows
+            .iter()
+            .zip(&snap.overlay_windows)
+            .filter(|(_, info)| info.geometry.is_some())
+            .map(|(w, _)| w.app_id.as_str())
+            .collect();
+        // Edge is on the same workspace, but on another output.
+        assert_eq!(placed.len(), 2);
+        assert!(!placed.contains(&"microsoft-edge"));
+    }
+
+    #[test]
+    fn snapshot_initial_forward_skips_origin_at_0() {
+        let windows = test_windows();
+        let snap = Snapshot::with_origin(&windows, &test_config(), Some(0));
+        assert_eq!(snap.initial_forward(), 1);
+    }
+
+    #[test]
+    fn snapshot_initial_forward_returns_0_when_origin_elsewhere() {
+        let windows = test_windows();
+        let snap = Snapshot::with_origin(&windows, &test_config(), Some(2));
+        assert_eq!(snap.initial_forward(), 0);
+    }
+
+    #[test]
+    fn snapshot_initial_selection_skips_stand_in_rows() {
+        let windows = test_windows();
+        let workspace = SelectableItem::Workspace(Workspace {
+            id: core_types::CompositorWorkspaceId::new(),
+            name: "Mail".into(),
+            is_active: false,
+        });
+        // Only the origin window, with a workspace row above it.
+        let mut snap = Snapshot::with_origin(&windows[..1], &test_config(), Some(0));
+        items::append_rows(
+            &mut snap.windows,
+            &mut snap.items,
+            Some(0),
+            &[workspace.clone()],
+            10,
+        );
+        snap.origin_index = Some(1);
+        assert!(snap.has_targets());
+        assert_eq!(snap.initial_forward(), 1);
+        assert_eq!(snap.initial_backward(), 1);
+
+        // No window at all: the workspace row is not a target.
+        let mut snap = Snapshot::with_origin(&[], &test_config(), None);
+        items::append_rows(&mut snap.windows, &mut snap.items, None, &[workspace], 10);
+        assert!(!snap.has_targets());
+    }
+
+    #[test]
+    fn snapshot_initial_forward_returns_0_when_no_origin() {
+        let windows = test_windows();
+        let snap = Snapshot::with_origin(&windows, &test_config(), None);
+        assert_eq!(snap.initial_forward(), 0);
+    }
+
+    #[test]
+    fn snapshot_initial_backward_skips_origin_at_last() {
+        let windows = test_windows(); // 3 windows, last = index 2
+        let snap = Snapshot::with_origin(&windows, &test_config(), Some(2));
+        assert_eq!(snap.initial_backward(), 1);
+    }
+
+    #[test]
+    fn snapshot_initial_backward_returns_last_when_origin_elsewhere() {
+        let windows = test_windows();
+        let snap = Snapshot::with_origin(&windows, &test_config(), Some(0));
+        assert_eq!(snap.initial_backward(), 2);
     }
 
-    // === Launcher activation ===
-
     #[test]
-    fn launcher_enters_armed_with_short_dwell() {
-        let mut ctrl = OverlayController::new();
-        let cmds = ctrl.handle(Event::ActivateLauncher, &test_windows(), &test_config());
-        assert!(cmds.iter().any(|c| matches!(c, Command::ShowBorder { .. })));
-        assert!(matches!(ctrl.phase, Phase::Armed { .. }));
-        // Short dwell means a deadline is set.
-        assert!(ctrl.next_deadline().is_some());
+    fn snapshot_initial_backward_returns_last_when_no_origin() {
+        let windows = test_windows();
+        let snap = Snapshot::with_origin(&windows, &test_config(), None);
+        assert_eq!(snap.initial_backward(), 2);
     }
 
     #[test]
-    fn launcher_dwell_transitions_to_picking() {
-        let mut ctrl = OverlayController::new();
-        let windows = test_windows();
-        ctrl.handle(Event::ActivateLauncher, &windows, &test_config());
-        let cmds = ctrl.handle(Event::DwellTimeout, &windows, &test_config());
-        assert!(cmds.iter().any(|c| matches!(c, Command::ShowPicker { .. })));
-        assert!(matches!(ctrl.phase, Phase::Picking { .. }));
+    fn snapshot_has_targets_single_window_with_origin() {
+        let single = vec![test_windows()[0].clone()];
+        let snap = Snapshot::with_origin(&single, &test_config(), Some(0));
+        assert!(
+            snap.has_targets(),
+            "single window is a valid target (user may be on a different workspace)"
+        );
     }
 
     #[test]
-    fn launcher_initial_selection_is_not_origin() {
-        let mut ctrl = OverlayController::new();
-        ctrl.handle(Event::ActivateLauncher, &test_windows(), &test_config());
-        if let Phase::Armed {
-            selection, snap, ..
-        } = &ctrl.phase
-        {
-            assert_ne!(Some(*selection), snap.origin_index);
-        }
+    fn snapshot_has_targets_with_origin_and_others() {
+        let windows = test_windows();
+        let snap = Snapshot::with_origin(&windows, &test_config(), Some(2));
+        assert!(snap.has_targets());
     }
 
-    // === Quick-switch ===
-
     #[test]
-    fn fast_release_quick_switches_to_non_origin() {
-        let mut ctrl = OverlayController::new();
+    fn snapshot_has_targets_no_origin() {
         let windows = test_windows();
-        ctrl.handle(Event::Activate, &windows, &test_config());
-        let cmds = ctrl.handle(Event::ModifierReleased, &windows, &test_config());
-        assert!(cmds.iter().any(|c| matches!(c, Command::HideAndSync)));
-        assert!(
-            cmds.iter()
-                .any(|c| matches!(c, Command::ActivateWindow { .. }))
-        );
-        assert!(ctrl.is_idle());
+        let snap = Snapshot::with_origin(&windows, &test_config(), None);
+        assert!(snap.has_targets());
     }
 
     #[test]
-    fn no_targets_is_noop_for_switcher() {
-        let mut ctrl = OverlayController::new();
-        let cmds = ctrl.handle(Event::Activate, &[], &test_config());
-        assert!(cmds.is_empty());
-        assert!(ctrl.is_idle());
+    fn snapshot_has_targets_empty() {
+        let empty: Vec<Window> = vec![];
+        let snap = Snapshot::with_origin(&empty, &test_config(), None);
+        assert!(!snap.has_targets());
     }
 
+    // === Live window changes ===
+
     #[test]
-    fn launcher_activates_with_zero_windows() {
+    fn window_changes_update_picker_rows_in_place() {
+        let windows = test_windows();
+        let config = test_config();
         let mut ctrl = OverlayController::new();
-        let cmds = ctrl.handle(Event::ActivateLauncher, &[], &test_config());
+        let changed = |id: &core_types::WindowId| Event::WindowChanged {
+            window_id: *id,
+            app_id: "com.mitchellh.ghostty".into(),
+            title: "~/src/open-sesame".into(),
+        };
         assert!(
-            cmds.iter().any(|c| matches!(c, Command::ShowBorder { .. })),
-            "launcher must activate even with zero windows"
+            ctrl.handle(changed(&windows[1].id), &windows, &config)
+                .is_empty()
         );
-        assert!(!ctrl.is_idle());
-    }
 
-    #[test]
-    fn launcher_zero_windows_can_stage_launch() {
-        let mut ctrl = OverlayController::new();
-        let config = test_config();
-        ctrl.handle(Event::ActivateLauncher, &[], &config);
-        // Type 'g' — no ghostty window, but launch binding exists.
-        let cmds = ctrl.handle(Event::Char('g'), &[], &config);
+        // Behind the armed border only the snapshot changes.
+        ctrl.handle(Event::Activate, &windows, &config);
         assert!(
-            cmds.iter()
-                .any(|c| matches!(c, Command::ShowLaunchStaged { .. })),
-            "must stage ghostty launch with zero windows, got: {cmds:?}"
+            ctrl.handle(changed(&windows[1].id), &windows, &config)
+                .is_empty()
         );
-        // Alt release executes.
-        let cmds = ctrl.handle(Event::ModifierReleased, &[], &config);
+        let Phase::Armed { snap, .. } = &ctrl.phase else {
+            panic!("expected Armed");
+        };
         assert!(
-            cmds.iter()
-                .any(|c| matches!(c, Command::LaunchApp { command, .. } if command == "ghostty"))
+            snap.overlay_windows
+                .iter()
+                .any(|r| r.title == "~/src/open-sesame")
         );
-    }
 
-    // === Full-circle cycling reaches origin ===
+        ctrl.pick_with_snapshot(Snapshot::with_origin(&windows, &config, Some(0)));
+        let last_activity = ctrl.last_activity;
+        let cmds = ctrl.handle(changed(&windows[1].id), &windows, &config);
+        let [Command::UpdateRows { windows: rows }] = &cmds[..] else {
+            panic!("got: {cmds:?}");
+        };
+        let titles: Vec<&str> = rows.iter().map(|r| r.title.as_str()).collect();
+        assert!(titles.contains(&"~/src/open-sesame"), "{titles:?}");
+        assert!(!titles.contains(&windows[1].title.as_str()), "{titles:?}");
+        assert_eq!(ctrl.last_activity, last_activity, "not user activity");
+
+        assert!(
+            ctrl.handle(changed(&core_types::WindowId::new()), &windows, &config)
+                .is_empty(),
+            "unlisted windows are ignored"
+        );
+    }
 
     #[test]
-    fn full_circle_cycling_visits_all_indices() {
-        let mut ctrl = OverlayController::new();
+    fn app_id_changes_bring_the_new_apps_label_and_icon() {
         let windows = test_windows();
-        ctrl.handle(Event::ActivateLauncher, &windows, &test_config());
-        ctrl.handle(Event::DwellTimeout, &windows, &test_config());
+        let mut config = test_config();
+        let firefox = config.key_bindings.get_mut("f").unwrap();
+        firefox.label = Some("Browser".into());
+        firefox.icon = Some("🦊".into());
+        let mut ctrl = OverlayController::new();
+        ctrl.pick_with_snapshot(Snapshot::build(
+            &windows,
+            &config,
+            &TitleRewriter::default(),
+            &MultiplexerTargets::new(),
+            &[],
+        ));
+        let cmds = ctrl.handle(
+            Event::WindowChanged {
+                window_id: windows[0].id,
+                app_id: "firefox".into(),
+                title: "Mozilla Firefox".into(),
+            },
+            &windows,
+            &config,
+        );
+        let [Command::UpdateRows { windows: rows }] = &cmds[..] else {
+            panic!("got: {cmds:?}");
+        };
+        let row = rows.iter().find(|r| r.title == "Mozilla Firefox").unwrap();
+        assert_eq!(row.app_id, "firefox");
+        assert_eq!(row.label.as_deref(), Some("Browser"));
+        assert_eq!(row.icon.as_deref(), Some("🦊"));
+    }
 
-        let snap_len = if let Phase::Picking { snap, .. } = &ctrl.phase {
-            snap.windows.len()
-        } else {
-            panic!("expected Picking");
+    // === Config reload ===
+
+    #[test]
+    fn title_rules_follow_the_latest_config() {
+        let windows = test_windows();
+        let mut config = test_config();
+        config.title_rules = vec![TitleRule {
+            pattern: "^~/src/".into(),
+            replace: String::new(),
+        }];
+        let mut ctrl = OverlayController::new();
+        let changed = || Event::WindowChanged {
+            window_id: windows[1].id,
+            app_id: "com.mitchellh.ghostty".into(),
+            title: "~/src/open-sesame".into(),
+        };
+        let row_title = |cmds: &[Command]| match cmds {
+            [Command::UpdateRows { windows: rows }] => rows
+                .iter()
+                .find(|r| r.title.contains("open-sesame"))
+                .map(|r| r.title.clone()),
+            _ => None,
         };
 
-        // Collect every selection index visited over a full cycle.
-        let mut visited = std::collections::HashSet::new();
-        for _ in 0..snap_len {
-            if let Phase::Picking { selection, .. } = &ctrl.phase {
-                visited.insert(*selection);
-            }
-            ctrl.handle(Event::SelectionDown, &windows, &test_config());
-        }
-        // After full cycle we're back at start — check that too.
-        if let Phase::Picking { selection, .. } = &ctrl.phase {
-            visited.insert(*selection);
-        }
+        ctrl.handle(Event::Activate, &windows, &config);
+        ctrl.pick_with_snapshot(Snapshot::with_origin(&windows, &config, Some(0)));
+        let cmds = ctrl.handle(changed(), &windows, &config);
+        assert_eq!(row_title(&cmds).as_deref(), Some("open-sesame"));
 
-        // Every index must be reachable (including origin when it exists).
-        assert_eq!(
-            visited.len(),
-            snap_len,
-            "full-circle cycling must visit all {snap_len} indices, visited {visited:?}"
-        );
+        // A reload that drops the rule applies from the next event on.
+        config.title_rules.clear();
+        let cmds = ctrl.handle(changed(), &windows, &config);
+        assert_eq!(row_title(&cmds).as_deref(), Some("~/src/open-sesame"));
     }
 
-    // === Hint match selects, does NOT commit (commit on Alt release) ===
+    // ===================================================================
+    // Controller with injected origin — exercises origin-skipping paths
+    // ===================================================================
 
     #[test]
-    fn hint_match_selects_origin_without_commit() {
+    fn armed_with_origin_at_last_skips_origin_on_quick_switch() {
         let mut ctrl = OverlayController::new();
         let windows = test_windows();
-        ctrl.handle(Event::Activate, &windows, &test_config());
-        // 'g' is the hint for ghostty (the origin/focused window).
-        // Typing it SELECTS ghostty but does NOT commit.
-        let cmds = ctrl.handle(Event::Char('g'), &windows, &test_config());
-        assert!(
-            !cmds
-                .iter()
-                .any(|c| matches!(c, Command::ActivateWindow { .. })),
-            "hint match must NOT activate on keypress"
-        );
-        assert!(!ctrl.is_idle(), "must stay in Picking, not Idle");
-        // Alt release commits the selection.
+        // Origin at index 2 (last) — typical after mru::reorder.
+        let snap = Snapshot::with_origin(&windows, &test_config(), Some(2));
+        ctrl.arm_with_snapshot(snap, 250);
+
+        // Fast release → quick-switch to index 0 (not origin at 2).
         let cmds = ctrl.handle(Event::ModifierReleased, &windows, &test_config());
-        assert!(
-            cmds.iter()
-                .any(|c| matches!(c, Command::ActivateWindow { .. })),
-            "Alt release must commit the hint selection"
+        let activated = cmds.iter().find_map(|c| match c {
+            Command::ActivateWindow { window, .. } => Some(window.clone()),
+            _ => None,
+        });
+        assert!(activated.is_some(), "should activate a window");
+        assert_eq!(
+            activated.unwrap().id,
+            windows[0].id,
+            "should activate index 0, not origin"
         );
         assert!(ctrl.is_idle());
     }
 
-    // === Dwell timeout ===
+    #[test]
+    fn armed_with_origin_at_0_skips_origin_on_quick_switch() {
+        let mut ctrl = OverlayController::new();
+        let windows = test_windows();
+        // Edge case: origin at index 0 — forward should skip to 1.
+        let snap = Snapshot::with_origin(&windows, &test_config(), Some(0));
+        ctrl.arm_with_snapshot(snap, 250);
+
+        let cmds = ctrl.handle(Event::ModifierReleased, &windows, &test_config());
+        let activated = cmds.iter().find_map(|c| match c {
+            Command::ActivateWindow { window, .. } => Some(window.clone()),
+            _ => None,
+        });
+        assert!(activated.is_some());
+        assert_eq!(
+            activated.unwrap().id,
+            windows[1].id,
+            "should activate index 1, skipping origin at 0"
+        );
+    }
 
     #[test]
-    fn dwell_timeout_shows_picker() {
+    fn picking_with_origin_starts_at_non_origin() {
         let mut ctrl = OverlayController::new();
         let windows = test_windows();
-        ctrl.handle(Event::Activate, &windows, &test_config());
-        let cmds = ctrl.handle(Event::DwellTimeout, &windows, &test_config());
-        assert!(cmds.iter().any(|c| matches!(c, Command::ShowPicker { .. })));
-        assert!(matches!(ctrl.phase, Phase::Picking { .. }));
+        let snap = Snapshot::with_origin(&windows, &test_config(), Some(2));
+        ctrl.pick_with_snapshot(snap);
+
+        if let Phase::Picking {
+            selection, snap, ..
+        } = &ctrl.phase
+        {
+            assert_ne!(*selection, 2, "should not start at origin");
+            assert_ne!(Some(*selection), snap.origin_index);
+            assert_eq!(*selection, 0);
+        } else {
+            panic!("expected Picking");
+        }
     }
 
-    // === Char input — stage launch or select, never commit on keypress ===
+    #[test]
+    fn single_window_is_origin_still_activatable() {
+        let single = vec![test_windows()[0].clone()];
+        let snap = Snapshot::with_origin(&single, &test_config(), Some(0));
+        assert!(snap.has_targets());
+    }
 
     #[test]
-    fn char_stages_launch_when_no_window() {
+    fn cycling_with_origin_visits_origin_too() {
         let mut ctrl = OverlayController::new();
-        let windows = vec![test_windows()[0].clone()]; // only ghostty
-        ctrl.handle(Event::Activate, &windows, &test_config());
-        // 'e' has no window → stages launch, does NOT execute.
-        let cmds = ctrl.handle(Event::Char('e'), &windows, &test_config());
-        assert!(
-            cmds.iter()
-                .any(|c| matches!(c, Command::ShowLaunchStaged { .. })),
-            "must show staged launch, got: {cmds:?}"
-        );
-        assert!(
-            !cmds.iter().any(|c| matches!(c, Command::LaunchApp { .. })),
-            "must NOT execute launch on keypress"
-        );
-        assert!(matches!(ctrl.phase, Phase::Picking { .. }));
-        // Alt release executes the staged launch.
-        let cmds = ctrl.handle(Event::ModifierReleased, &windows, &test_config());
-        assert!(
-            cmds.iter().any(
-                |c| matches!(c, Command::LaunchApp { command, .. } if command == "microsoft-edge")
-            ),
-            "Alt release must execute staged launch"
-        );
+        let windows = test_windows();
+        let snap = Snapshot::with_origin(&windows, &test_config(), Some(2));
+        ctrl.pick_with_snapshot(snap);
+
+        let mut visited = std::collections::HashSet::new();
+        for _ in 0..windows.len() {
+            if let Phase::Picking { selection, .. } = &ctrl.phase {
+                visited.insert(*selection);
+            }
+            ctrl.handle(Event::SelectionDown, &windows, &test_config());
+        }
+        if let Phase::Picking { selection, .. } = &ctrl.phase {
+            visited.insert(*selection);
+        }
+
+        assert!(visited.contains(&2), "cycling must visit origin at index 2");
+        assert_eq!(visited.len(), windows.len(), "must visit all indices");
     }
 
+    // === Origin rotation in Snapshot::build layout ===
+
     #[test]
-    fn char_selects_hint_without_commit() {
-        let mut ctrl = OverlayController::new();
-        let windows = test_windows();
-        ctrl.handle(Event::Activate, &windows, &test_config());
-        // 'e' matches edge window hint → selects, does NOT commit.
-        let cmds = ctrl.handle(Event::Char('e'), &windows, &test_config());
-        assert!(
-            !cmds
-                .iter()
-                .any(|c| matches!(c, Command::ActivateWindow { .. })),
-            "hint match must NOT activate on keypress"
-        );
-        assert!(!ctrl.is_idle());
-        // Alt release commits.
-        let cmds = ctrl.handle(Event::ModifierReleased, &windows, &test_config());
-        assert!(
-            cmds.iter()
-                .any(|c| matches!(c, Command::ActivateWindow { .. }))
-        );
-        assert!(ctrl.is_idle());
+    fn origin_at_last_display_order() {
+        // Simulates what build() does: origin rotated to end.
+        // Given 3 windows [A=origin, B, C] after MRU reorder,
+        // rotation produces [B, C, A] with origin_index = 2.
+        let windows = test_windows(); // ghostty, vivaldi, edge
+        let snap = Snapshot::with_origin(&windows, &test_config(), Some(2));
+        // Origin is at the end.
+        assert_eq!(snap.origin_index, Some(2));
+        // Forward selection starts at 0 (top of picker, not origin).
+        assert_eq!(snap.initial_forward(), 0);
+        // Backward selection starts at 1 (last non-origin).
+        assert_eq!(snap.initial_backward(), 1);
     }
 
     #[test]
-    fn launcher_char_f_stages_launch_when_not_running() {
-        let mut ctrl = OverlayController::new();
-        // Only ghostty and edge — no firefox window.
-        let windows = vec![test_windows()[0].clone(), test_windows()[2].clone()];
-        ctrl.handle(Event::ActivateLauncher, &windows, &test_config());
-        assert!(matches!(ctrl.phase, Phase::Armed { .. }));
-        // 'f' has no window → stages launch, does NOT execute.
-        let cmds = ctrl.handle(Event::Char('f'), &windows, &test_config());
-        assert!(
-            cmds.iter()
-                .any(|c| matches!(c, Command::ShowLaunchStaged { .. })),
-            "expected ShowLaunchStaged for firefox, got: {cmds:?}"
-        );
-        assert!(matches!(ctrl.phase, Phase::Picking { .. }));
-        // Alt release executes.
-        let cmds = ctrl.handle(Event::ModifierReleased, &windows, &test_config());
-        assert!(
-            cmds.iter()
-                .any(|c| matches!(c, Command::LaunchApp { command, .. } if command == "firefox"))
-        );
+    fn origin_rotation_preserves_mru_order() {
+        // After rotation: [MRU previous, ..., origin]
+        // The non-origin windows maintain their relative MRU order.
+        let windows = test_windows(); // ghostty(0), firefox(1), edge(2)
+        // If ghostty is origin (index 0 pre-rotation), rotation produces:
+        // [firefox, edge, ghostty] with origin_index = 2
+        let mut rotated = windows.clone();
+        let origin = rotated.remove(0);
+        rotated.push(origin);
+        let snap = Snapshot::with_origin(&rotated, &test_config(), Some(2));
+
+        assert_eq!(snap.windows[0].app_id.as_str(), "firefox");
+        assert_eq!(snap.windows[1].app_id.as_str(), "microsoft-edge");
+        assert_eq!(snap.windows[2].app_id.as_str(), "com.mitchellh.ghostty");
+        assert_eq!(snap.origin_index, Some(2));
     }
 
     #[test]
-    fn launcher_char_f_selects_firefox_when_running() {
+    fn origin_at_last_picker_top_is_switch_target() {
+        // The top of the picker (index 0) should be the quick-switch
+        // target, not the origin.
+        let windows = test_windows();
+        let snap = Snapshot::with_origin(&windows, &test_config(), Some(2));
         let mut ctrl = OverlayController::new();
-        let windows = test_windows(); // includes firefox
-        ctrl.handle(Event::ActivateLauncher, &windows, &test_config());
-        // 'f' matches firefox hint → selects, does NOT commit.
-        let cmds = ctrl.handle(Event::Char('f'), &windows, &test_config());
-        assert!(
-            !cmds
-                .iter()
-                .any(|c| matches!(c, Command::ActivateWindow { .. })),
-            "hint match must NOT activate on keypress, got: {cmds:?}"
-        );
-        assert!(!ctrl.is_idle());
-        // Alt release commits.
+        ctrl.arm_with_snapshot(snap, 5000);
+
+        // Quick-switch (fast release) should activate index 0.
         let cmds = ctrl.handle(Event::ModifierReleased, &windows, &test_config());
-        assert!(
-            cmds.iter()
-                .any(|c| matches!(c, Command::ActivateWindow { .. }))
+        let activated = cmds.iter().find_map(|c| match c {
+            Command::ActivateWindow { window, .. } => Some(window),
+            _ => None,
+        });
+        assert!(activated.is_some());
+        assert_eq!(
+            activated.unwrap().id,
+            windows[0].id,
+            "quick-switch should activate top of picker (index 0), not origin"
         );
-        assert!(ctrl.is_idle());
     }
 
+    // === In-flight direction change (Alt+Tab then Alt+Shift+Tab) ===
+
     #[test]
-    fn backspace_cancels_staged_launch() {
+    fn armed_forward_then_backward_reverses() {
         let mut ctrl = OverlayController::new();
-        let windows = vec![test_windows()[0].clone()]; // only ghostty
+        let windows = test_windows(); // 3 windows, origin at 2
+        let snap = Snapshot::with_origin(&windows, &test_config(), Some(2));
+        ctrl.arm_with_snapshot(snap, 5000);
+
+        // Initial forward selection is 0.
+        if let Phase::Armed { selection, .. } = &ctrl.phase {
+            assert_eq!(*selection, 0);
+        }
+
+        // Forward re-activation: selection moves to 1.
         ctrl.handle(Event::Activate, &windows, &test_config());
-        ctrl.handle(Event::DwellTimeout, &windows, &test_config());
-        // Stage a launch.
-        ctrl.handle(Event::Char('e'), &windows, &test_config());
-        assert!(matches!(
-            ctrl.phase,
-            Phase::Picking {
-                pending_launch: Some(_),
-                ..
-            }
-        ));
-        // Backspace clears input and pending launch.
-        ctrl.handle(Event::Backspace, &windows, &test_config());
-        assert!(matches!(
-            ctrl.phase,
-            Phase::Picking {
-                pending_launch: None,
-                ..
-            }
-        ));
-        // Alt release now activates a window, not a launch.
-        let cmds = ctrl.handle(Event::ModifierReleased, &windows, &test_config());
-        assert!(
-            !cmds.iter().any(|c| matches!(c, Command::LaunchApp { .. })),
-            "backspace must cancel staged launch"
-        );
-    }
+        if let Phase::Armed { selection, .. } = &ctrl.phase {
+            assert_eq!(*selection, 1);
+        }
 
-    // === Navigation shows picker ===
+        // Backward re-activation: selection moves back to 0.
+        ctrl.handle(Event::ActivateBackward, &windows, &test_config());
+        if let Phase::Armed { selection, .. } = &ctrl.phase {
+            assert_eq!(*selection, 0);
+        }
+    }
 
     #[test]
-    fn tab_in_armed_shows_picker() {
+    fn picking_forward_then_backward_reverses() {
         let mut ctrl = OverlayController::new();
         let windows = test_windows();
+        let snap = Snapshot::with_origin(&windows, &test_config(), Some(2));
+        ctrl.pick_with_snapshot(snap);
+
+        // Initial forward selection is 0.
+        if let Phase::Picking { selection, .. } = &ctrl.phase {
+            assert_eq!(*selection, 0);
+        }
+
+        // Forward re-activation: 0 → 1.
         ctrl.handle(Event::Activate, &windows, &test_config());
-        let cmds = ctrl.handle(Event::SelectionDown, &windows, &test_config());
-        assert!(cmds.iter().any(|c| matches!(c, Command::ShowPicker { .. })));
-        assert!(matches!(ctrl.phase, Phase::Picking { .. }));
+        if let Phase::Picking { selection, .. } = &ctrl.phase {
+            assert_eq!(*selection, 1);
+        }
+
+        // Backward re-activation: 1 → 0.
+        ctrl.handle(Event::ActivateBackward, &windows, &test_config());
+        if let Phase::Picking { selection, .. } = &ctrl.phase {
+            assert_eq!(*selection, 0);
+        }
+
+        // Backward again: 0 → 2 (wraps).
+        ctrl.handle(Event::ActivateBackward, &windows, &test_config());
+        if let Phase::Picking { selection, .. } = &ctrl.phase {
+            assert_eq!(*selection, 2);
+        }
     }
 
-    // === Escape ===
+    // ===================================================================
+    // Vault unlock flow tests
+    // ===================================================================
 
-    #[test]
-    fn escape_from_armed_dismisses() {
-        let mut ctrl = OverlayController::new();
-        ctrl.handle(Event::Activate, &test_windows(), &test_config());
-        let cmds = ctrl.handle(Event::Escape, &test_windows(), &test_config());
-        assert!(cmds.iter().any(|c| matches!(c, Command::Hide)));
-        assert!(ctrl.is_idle());
+    /// Helper: drive the controller to Phase::Launching with a staged ghostty launch.
+    /// Uses an empty window list so 'g' hits the launch binding (not a hint match).
+    fn drive_to_launching(ctrl: &mut OverlayController, _windows: &[Window], config: &WmConfig) {
+        let no_windows: Vec<Window> = vec![];
+        ctrl.handle(Event::ActivateLauncher, &no_windows, config);
+        ctrl.handle(Event::Char('g'), &no_windows, config);
+        let cmds = ctrl.handle(Event::ModifierReleased, &no_windows, config);
+        assert!(
+            matches!(ctrl.phase, Phase::Launching),
+            "expected Phase::Launching after modifier release with staged launch, \
+             phase={:?}, cmds={cmds:?}",
+            ctrl.phase
+        );
+    }
+
+    /// Helper: construct a VaultsLocked LaunchResult event.
+    fn vaults_locked_event(profiles: &[&str]) -> Event {
+        Event::LaunchResult {
+            success: false,
+            error: Some("VaultsLocked".into()),
+            denial: Some(LaunchDenial::VaultsLocked {
+                locked_profiles: profiles
+                    .iter()
+                    .map(|p| TrustProfileName::try_from(*p).unwrap())
+                    .collect(),
+            }),
+            original_command: Some("ghostty".into()),
+            original_tags: Some(vec!["dev".into()]),
+            original_launch_args: Some(vec![]),
+        }
+    }
+
+    /// Helper: drive to Phase::Unlocking (Password mode) for a single profile.
+    fn drive_to_password_prompt(
+        ctrl: &mut OverlayController,
+        windows: &[Window],
+        config: &WmConfig,
+        profile: &str,
+    ) {
+        drive_to_launching(ctrl, windows, config);
+        ctrl.handle(vaults_locked_event(&[profile]), windows, config);
+        ctrl.handle(
+            Event::AutoUnlockResult {
+                success: false,
+                profile: TrustProfileName::try_from(profile).unwrap(),
+                needs_touch: false,
+            },
+            windows,
+            config,
+        );
+        assert!(
+            matches!(
+                ctrl.phase,
+                Phase::Unlocking {
+                    unlock_mode: UnlockMode::Password,
+                    ..
+                }
+            ),
+            "expected Phase::Unlocking(Password) after auto-unlock failure"
+        );
     }
 
     #[test]
-    fn escape_from_idle_is_noop() {
+    fn vaults_locked_transitions_to_unlocking() {
         let mut ctrl = OverlayController::new();
-        let cmds = ctrl.handle(Event::Escape, &[], &test_config());
-        assert!(cmds.is_empty());
-    }
+        let windows = test_windows();
+        let config = test_config();
 
-    // === Confirm ===
+        drive_to_launching(&mut ctrl, &windows, &config);
 
-    #[test]
-    fn confirm_in_picking_activates() {
-        let mut ctrl = OverlayController::new();
-        let windows = test_windows();
-        ctrl.handle(Event::Activate, &windows, &test_config());
-        ctrl.handle(Event::DwellTimeout, &windows, &test_config());
-        let cmds = ctrl.handle(Event::Confirm, &windows, &test_config());
+        let cmds = ctrl.handle(vaults_locked_event(&["default", "work"]), &windows, &config);
+
+        assert!(
+            matches!(ctrl.phase, Phase::Unlocking { .. }),
+            "VaultsLocked must transition to Phase::Unlocking"
+        );
         assert!(
             cmds.iter()
-                .any(|c| matches!(c, Command::ActivateWindow { .. }))
+                .any(|c| matches!(c, Command::AttemptAutoUnlock { .. })),
+            "VaultsLocked must emit AttemptAutoUnlock, got: {cmds:?}"
         );
-        assert!(ctrl.is_idle());
     }
 
-    // === Re-activation cycles all windows ===
-
     #[test]
-    fn reactivate_transitions_armed_to_picking() {
+    fn auto_unlock_failure_falls_back_to_password() {
         let mut ctrl = OverlayController::new();
         let windows = test_windows();
-        ctrl.handle(Event::Activate, &windows, &test_config());
-        let cmds = ctrl.handle(Event::Activate, &windows, &test_config());
-        // Re-activation in Armed transitions to Picking with picker visible.
-        assert!(cmds.iter().any(|c| matches!(c, Command::ShowPicker { .. })));
-        assert!(cmds.iter().any(|c| matches!(c, Command::ResetGrace)));
-        if let Phase::Picking {
-            selection, snap, ..
-        } = &ctrl.phase
-        {
-            assert!(*selection < snap.windows.len());
-        } else {
-            panic!("expected Picking after re-activation");
-        }
-    }
+        let config = test_config();
 
-    // === Re-activation grace reset (prevents modifier poll premature commit) ===
+        drive_to_launching(&mut ctrl, &windows, &config);
+        ctrl.handle(vaults_locked_event(&["default"]), &windows, &config);
 
-    #[test]
-    fn reactivate_armed_emits_reset_grace() {
-        let mut ctrl = OverlayController::new();
-        let windows = test_windows();
-        ctrl.handle(Event::Activate, &windows, &test_config());
-        let cmds = ctrl.handle(Event::Activate, &windows, &test_config());
-        assert!(
-            cmds.iter().any(|c| matches!(c, Command::ResetGrace)),
-            "re-activation in Armed must emit ResetGrace to prevent modifier poll commit"
+        let cmds = ctrl.handle(
+            Event::AutoUnlockResult {
+                success: false,
+                profile: TrustProfileName::try_from("default").unwrap(),
+                needs_touch: false,
+            },
+            &windows,
+            &config,
         );
-    }
 
-    #[test]
-    fn reactivate_picking_emits_reset_grace() {
-        let mut ctrl = OverlayController::new();
-        let windows = test_windows();
-        ctrl.handle(Event::Activate, &windows, &test_config());
-        ctrl.handle(Event::DwellTimeout, &windows, &test_config());
-        assert!(matches!(ctrl.phase, Phase::Picking { .. }));
-        let cmds = ctrl.handle(Event::Activate, &windows, &test_config());
         assert!(
-            cmds.iter().any(|c| matches!(c, Command::ResetGrace)),
-            "re-activation in Picking must emit ResetGrace to prevent modifier poll commit"
+            cmds.iter()
+                .any(|c| matches!(c, Command::ShowPasswordPrompt { .. })),
+            "auto-unlock failure must fall back to password prompt, got: {cmds:?}"
         );
+        if let Phase::Unlocking { unlock_mode, .. } = &ctrl.phase {
+            assert_eq!(*unlock_mode, UnlockMode::Password);
+        } else {
+            panic!("expected Phase::Unlocking");
+        }
     }
 
     #[test]
-    fn reactivate_launcher_emits_reset_grace() {
+    fn password_chars_tracked_in_unlock_phase() {
         let mut ctrl = OverlayController::new();
         let windows = test_windows();
-        ctrl.handle(Event::ActivateLauncher, &windows, &test_config());
-        let cmds = ctrl.handle(Event::ActivateLauncher, &windows, &test_config());
-        assert!(
-            cmds.iter().any(|c| matches!(c, Command::ResetGrace)),
-            "launcher re-activation must emit ResetGrace"
-        );
+        let config = test_config();
+
+        drive_to_password_prompt(&mut ctrl, &windows, &config, "default");
+
+        let cmds = ctrl.handle(Event::Char('p'), &windows, &config);
+        assert!(cmds.iter().any(|c| matches!(c, Command::PasswordChar('p'))));
+
+        let cmds = ctrl.handle(Event::Char('a'), &windows, &config);
+        assert!(cmds.iter().any(|c| matches!(c, Command::PasswordChar('a'))));
+
+        if let Phase::Unlocking { password_len, .. } = &ctrl.phase {
+            assert_eq!(*password_len, 2);
+        } else {
+            panic!("expected Phase::Unlocking");
+        }
     }
 
     #[test]
-    fn reactivate_backward_emits_reset_grace() {
+    fn confirm_during_password_emits_submit() {
         let mut ctrl = OverlayController::new();
         let windows = test_windows();
-        ctrl.handle(Event::Activate, &windows, &test_config());
-        let cmds = ctrl.handle(Event::ActivateBackward, &windows, &test_config());
+        let config = test_config();
+
+        drive_to_password_prompt(&mut ctrl, &windows, &config, "default");
+        ctrl.handle(Event::Char('p'), &windows, &config);
+
+        let cmds = ctrl.handle(Event::Confirm, &windows, &config);
         assert!(
-            cmds.iter().any(|c| matches!(c, Command::ResetGrace)),
-            "backward re-activation must emit ResetGrace"
+            cmds.iter()
+                .any(|c| matches!(c, Command::SubmitPasswordUnlock { .. })),
+            "Confirm during password must emit SubmitPasswordUnlock, got: {cmds:?}"
         );
+        // ShowVerifying is now emitted by the main.rs handler before IPC,
+        // not by the controller, so we only check SubmitPasswordUnlock here.
     }
 
-    // === Rapid re-activation cycling (Alt+Space+Space+Space...) ===
-
     #[test]
-    fn rapid_reactivation_cycles_all_windows() {
+    fn successful_unlock_retries_launch() {
         let mut ctrl = OverlayController::new();
         let windows = test_windows();
-        ctrl.handle(Event::ActivateLauncher, &windows, &test_config());
+        let config = test_config();
 
-        let snap_len = match &ctrl.phase {
-            Phase::Armed { snap, .. } => snap.windows.len(),
-            _ => panic!("expected Armed"),
-        };
+        drive_to_password_prompt(&mut ctrl, &windows, &config, "default");
 
-        // Simulate Alt+Space repeated — each re-activation should cycle
-        // forward through all windows without committing.
-        let mut visited = std::collections::HashSet::new();
-        for _ in 0..snap_len {
-            let cmds = ctrl.handle(Event::ActivateLauncher, &windows, &test_config());
-            // Must never commit (no ActivateWindow or HideAndSync).
-            assert!(
-                !cmds
-                    .iter()
-                    .any(|c| matches!(c, Command::ActivateWindow { .. } | Command::HideAndSync)),
-                "re-activation must not commit"
-            );
-            if let Phase::Picking { selection, .. } = &ctrl.phase {
-                visited.insert(*selection);
-            }
-        }
+        let cmds = ctrl.handle(
+            Event::UnlockResult {
+                success: true,
+                profile: TrustProfileName::try_from("default").unwrap(),
+            },
+            &windows,
+            &config,
+        );
 
-        assert_eq!(
-            visited.len(),
-            snap_len,
-            "rapid re-activation must visit all {snap_len} windows, visited {visited:?}"
+        assert!(
+            cmds.iter()
+                .any(|c| matches!(c, Command::ActivateProfiles { .. })),
+            "successful unlock must activate profiles before retry, got: {cmds:?}"
+        );
+        assert!(
+            cmds.iter()
+                .any(|c| matches!(c, Command::LaunchApp { command, .. } if command == "ghostty")),
+            "successful unlock must retry original launch, got: {cmds:?}"
         );
+        assert!(matches!(ctrl.phase, Phase::Launching));
     }
 
     #[test]
-    fn rapid_reactivation_no_commit_until_modifier_released() {
+    fn multi_profile_sequential_unlock() {
         let mut ctrl = OverlayController::new();
         let windows = test_windows();
-        ctrl.handle(Event::ActivateLauncher, &windows, &test_config());
+        let config = test_config();
 
-        // 20 rapid re-activations — none should commit.
-        for i in 0..20 {
-            let cmds = ctrl.handle(Event::ActivateLauncher, &windows, &test_config());
-            assert!(
-                !cmds
-                    .iter()
-                    .any(|c| matches!(c, Command::ActivateWindow { .. })),
-                "re-activation #{i} must not activate a window"
-            );
-            assert!(!ctrl.is_idle(), "must not return to Idle during cycling");
+        drive_to_launching(&mut ctrl, &windows, &config);
+        ctrl.handle(vaults_locked_event(&["default", "work"]), &windows, &config);
+
+        // First profile: auto-unlock fails, password succeeds.
+        ctrl.handle(
+            Event::AutoUnlockResult {
+                success: false,
+                profile: TrustProfileName::try_from("default").unwrap(),
+                needs_touch: false,
+            },
+            &windows,
+            &config,
+        );
+        let cmds = ctrl.handle(
+            Event::UnlockResult {
+                success: true,
+                profile: TrustProfileName::try_from("default").unwrap(),
+            },
+            &windows,
+            &config,
+        );
+
+        // Must advance to second profile, NOT retry launch yet.
+        assert!(
+            cmds.iter()
+                .any(|c| matches!(c, Command::AttemptAutoUnlock { profile }
+            if profile.as_ref() == "work")),
+            "must advance to next locked profile, got: {cmds:?}"
+        );
+        if let Phase::Unlocking { current_index, .. } = &ctrl.phase {
+            assert_eq!(*current_index, 1);
+        } else {
+            panic!("expected Phase::Unlocking at index 1");
         }
 
-        // Only ModifierReleased commits.
-        let cmds = ctrl.handle(Event::ModifierReleased, &windows, &test_config());
+        // Second profile: auto-unlock fails, password succeeds.
+        ctrl.handle(
+            Event::AutoUnlockResult {
+                success: false,
+                profile: TrustProfileName::try_from("work").unwrap(),
+                needs_touch: false,
+            },
+            &windows,
+            &config,
+        );
+        let cmds = ctrl.handle(
+            Event::UnlockResult {
+                success: true,
+                profile: TrustProfileName::try_from("work").unwrap(),
+            },
+            &windows,
+            &config,
+        );
+
+        // NOW activate profiles and retry the launch.
         assert!(
             cmds.iter()
-                .any(|c| matches!(c, Command::ActivateWindow { .. }))
+                .any(|c| matches!(c, Command::ActivateProfiles { .. })),
+            "all profiles unlocked must activate profiles before retry, got: {cmds:?}"
+        );
+        assert!(
+            cmds.iter()
+                .any(|c| matches!(c, Command::LaunchApp { command, .. } if command == "ghostty")),
+            "all profiles unlocked must retry launch, got: {cmds:?}"
         );
-        assert!(ctrl.is_idle());
+        assert!(matches!(ctrl.phase, Phase::Launching));
     }
 
     #[test]
-    fn rapid_reactivation_forward_then_backward() {
+    fn wrong_password_resets_for_retry() {
         let mut ctrl = OverlayController::new();
         let windows = test_windows();
-        ctrl.handle(Event::ActivateLauncher, &windows, &test_config());
-
-        // Forward 3 times.
-        ctrl.handle(Event::ActivateLauncher, &windows, &test_config());
-        ctrl.handle(Event::ActivateLauncher, &windows, &test_config());
-        ctrl.handle(Event::ActivateLauncher, &windows, &test_config());
-        let sel_after_forward = match &ctrl.phase {
-            Phase::Picking { selection, .. } => *selection,
-            _ => panic!("expected Picking"),
-        };
+        let config = test_config();
 
-        // Backward 2 times (simulates adding Shift mid-cycle).
-        ctrl.handle(Event::ActivateBackward, &windows, &test_config());
-        ctrl.handle(Event::ActivateBackward, &windows, &test_config());
-        let sel_after_backward = match &ctrl.phase {
-            Phase::Picking { selection, .. } => *selection,
-            _ => panic!("expected Picking"),
-        };
+        drive_to_password_prompt(&mut ctrl, &windows, &config, "default");
+        ctrl.handle(Event::Char('x'), &windows, &config);
 
-        // Net movement: 3 forward - 2 backward = 1 forward from initial.
-        // Initial Armed selection is initial_forward(), first re-activation
-        // cycles +1, so after 3 forward we're at initial+3, minus 2 backward = initial+1.
-        assert_ne!(
-            sel_after_forward, sel_after_backward,
-            "backward must change selection from forward position"
+        let cmds = ctrl.handle(
+            Event::UnlockResult {
+                success: false,
+                profile: TrustProfileName::try_from("default").unwrap(),
+            },
+            &windows,
+            &config,
         );
-    }
-
-    // === Release after interaction ===
 
-    #[test]
-    fn release_after_tab_activates_selection() {
-        let mut ctrl = OverlayController::new();
-        let windows = test_windows();
-        ctrl.handle(Event::Activate, &windows, &test_config());
-        ctrl.handle(Event::DwellTimeout, &windows, &test_config());
-        ctrl.handle(Event::SelectionDown, &windows, &test_config());
-        ctrl.handle(Event::SelectionDown, &windows, &test_config());
-        let cmds = ctrl.handle(Event::ModifierReleased, &windows, &test_config());
         assert!(
             cmds.iter()
-                .any(|c| matches!(c, Command::ActivateWindow { .. }))
+                .any(|c| matches!(c, Command::ClearPasswordBuffer))
         );
-    }
-
-    #[test]
-    fn launcher_modifier_release_commits() {
-        let mut ctrl = OverlayController::new();
-        let windows = test_windows();
-        ctrl.handle(Event::ActivateLauncher, &windows, &test_config());
-        ctrl.handle(Event::SelectionDown, &windows, &test_config());
-        // Releasing Alt commits the selection, same as Alt+Tab mode.
-        let cmds = ctrl.handle(Event::ModifierReleased, &windows, &test_config());
         assert!(
             cmds.iter()
-                .any(|c| matches!(c, Command::ActivateWindow { .. }))
+                .any(|c| matches!(c, Command::ShowPasswordPrompt { .. }))
         );
-        assert!(ctrl.is_idle());
+        assert!(
+            cmds.iter()
+                .any(|c| matches!(c, Command::ShowUnlockError { .. }))
+        );
+
+        if let Phase::Unlocking {
+            password_len,
+            unlock_mode,
+            ..
+        } = &ctrl.phase
+        {
+            assert_eq!(*password_len, 0);
+            assert_eq!(*unlock_mode, UnlockMode::Password);
+        } else {
+            panic!("expected Phase::Unlocking after wrong password");
+        }
     }
 
     #[test]
-    fn launcher_confirm_activates_selection() {
+    fn escape_during_unlock_dismisses_and_clears() {
         let mut ctrl = OverlayController::new();
         let windows = test_windows();
-        ctrl.handle(Event::ActivateLauncher, &windows, &test_config());
-        ctrl.handle(Event::SelectionDown, &windows, &test_config());
-        let cmds = ctrl.handle(Event::Confirm, &windows, &test_config());
+        let config = test_config();
+
+        drive_to_launching(&mut ctrl, &windows, &config);
+        ctrl.handle(vaults_locked_event(&["default"]), &windows, &config);
+
+        let cmds = ctrl.handle(Event::Escape, &windows, &config);
+
         assert!(
             cmds.iter()
-                .any(|c| matches!(c, Command::ActivateWindow { .. }))
+                .any(|c| matches!(c, Command::ClearPasswordBuffer)),
+            "escape during unlock must clear password buffer"
         );
+        assert!(cmds.iter().any(|c| matches!(c, Command::Hide)));
         assert!(ctrl.is_idle());
     }
 
-    // === Confirm on origin is valid (user navigated there) ===
-
     #[test]
-    fn confirm_on_origin_is_valid() {
+    fn vaults_locked_without_original_command_shows_error() {
         let mut ctrl = OverlayController::new();
         let windows = test_windows();
-        ctrl.handle(Event::ActivateLauncher, &windows, &test_config());
-        // DwellTimeout transitions Armed → Picking.
-        ctrl.handle(Event::DwellTimeout, &windows, &test_config());
+        let config = test_config();
 
-        // Navigate to origin by cycling all the way around.
-        let snap_len = if let Phase::Picking { snap, .. } = &ctrl.phase {
-            snap.windows.len()
-        } else {
-            panic!("expected Picking")
-        };
+        drive_to_launching(&mut ctrl, &windows, &config);
 
-        for _ in 0..snap_len {
-            ctrl.handle(Event::SelectionDown, &windows, &test_config());
-        }
-        // After full circle we're back at the initial (non-origin) position.
-        // Navigate one more step back to reach origin (which is last).
-        ctrl.handle(Event::SelectionUp, &windows, &test_config());
+        // VaultsLocked but missing original_command — can't retry.
+        let cmds = ctrl.handle(
+            Event::LaunchResult {
+                success: false,
+                error: Some("VaultsLocked".into()),
+                denial: Some(LaunchDenial::VaultsLocked {
+                    locked_profiles: vec![TrustProfileName::try_from("default").unwrap()],
+                }),
+                original_command: None,
+                original_tags: None,
+                original_launch_args: None,
+            },
+            &windows,
+            &config,
+        );
 
-        // Confirm — should activate whatever is selected, even if origin.
-        let cmds = ctrl.handle(Event::Confirm, &windows, &test_config());
+        // Must fall through to error display, not unlock flow.
         assert!(
             cmds.iter()
-                .any(|c| matches!(c, Command::ActivateWindow { .. }))
+                .any(|c| matches!(c, Command::ShowLaunchError { .. })),
+            "VaultsLocked without retry context must show error, got: {cmds:?}"
         );
-        assert!(ctrl.is_idle());
     }
 
-    // ===================================================================
-    // Snapshot unit tests — origin_index explicitly set
-    // ===================================================================
-
     #[test]
-    fn snapshot_initial_forward_skips_origin_at_0() {
+    fn launch_result_in_wrong_phase_is_noop() {
+        let mut ctrl = OverlayController::new();
         let windows = test_windows();
-        let snap = Snapshot::with_origin(&windows, &test_config(), Some(0));
-        assert_eq!(snap.initial_forward(), 1);
-    }
+        let config = test_config();
 
-    #[test]
-    fn snapshot_initial_forward_returns_0_when_origin_elsewhere() {
-        let windows = test_windows();
-        let snap = Snapshot::with_origin(&windows, &test_config(), Some(2));
-        assert_eq!(snap.initial_forward(), 0);
+        // Controller is Idle — LaunchResult should be ignored.
+        let cmds = ctrl.handle(
+            Event::LaunchResult {
+                success: false,
+                error: None,
+                denial: Some(LaunchDenial::VaultsLocked {
+                    locked_profiles: vec![TrustProfileName::try_from("default").unwrap()],
+                }),
+                original_command: Some("ghostty".into()),
+                original_tags: Some(vec![]),
+                original_launch_args: Some(vec![]),
+            },
+            &windows,
+            &config,
+        );
+
+        assert!(cmds.is_empty());
+        assert!(ctrl.is_idle());
     }
 
     #[test]
-    fn snapshot_initial_forward_returns_0_when_no_origin() {
+    fn profiles_activated_before_retry_launch_after_unlock() {
+        // Regression: after unlocking all vaults, the retry launch would fail
+        // with "denied-profile-not-active" because profiles were never activated.
+        // The controller must emit ActivateProfiles BEFORE LaunchApp.
+        let mut ctrl = OverlayController::new();
         let windows = test_windows();
-        let snap = Snapshot::with_origin(&windows, &test_config(), None);
-        assert_eq!(snap.initial_forward(), 0);
-    }
+        let config = test_config();
 
-    #[test]
-    fn snapshot_initial_backward_skips_origin_at_last() {
-        let windows = test_windows(); // 3 windows, last = index 2
-        let snap = Snapshot::with_origin(&windows, &test_config(), Some(2));
-        assert_eq!(snap.initial_backward(), 1);
-    }
+        drive_to_launching(&mut ctrl, &windows, &config);
+        ctrl.handle(vaults_locked_event(&["alpha", "beta"]), &windows, &config);
 
-    #[test]
-    fn snapshot_initial_backward_returns_last_when_origin_elsewhere() {
-        let windows = test_windows();
-        let snap = Snapshot::with_origin(&windows, &test_config(), Some(0));
-        assert_eq!(snap.initial_backward(), 2);
+        // Unlock both profiles.
+        ctrl.handle(
+            Event::AutoUnlockResult {
+                success: false,
+                profile: TrustProfileName::try_from("alpha").unwrap(),
+                needs_touch: false,
+            },
+            &windows,
+            &config,
+        );
+        ctrl.handle(
+            Event::UnlockResult {
+                success: true,
+                profile: TrustProfileName::try_from("alpha").unwrap(),
+            },
+            &windows,
+            &config,
+        );
+        ctrl.handle(
+            Event::AutoUnlockResult {
+                success: false,
+                profile: TrustProfileName::try_from("beta").unwrap(),
+                needs_touch: false,
+            },
+            &windows,
+            &config,
+        );
+        let cmds = ctrl.handle(
+            Event::UnlockResult {
+                success: true,
+                profile: TrustProfileName::try_from("beta").unwrap(),
+            },
+            &windows,
+            &config,
+        );
+
+        // Find positions to verify ordering.
+        let activate_pos = cmds
+            .iter()
+            .position(|c| matches!(c, Command::ActivateProfiles { .. }));
+        let launch_pos = cmds
+            .iter()
+            .position(|c| matches!(c, Command::LaunchApp { .. }));
+
+        assert!(
+            activate_pos.is_some(),
+            "ActivateProfiles must be emitted before launch retry, got: {cmds:?}"
+        );
+        assert!(
+            launch_pos.is_some(),
+            "LaunchApp must be emitted for retry, got: {cmds:?}"
+        );
+        assert!(
+            activate_pos.unwrap() < launch_pos.unwrap(),
+            "ActivateProfiles must come before LaunchApp, got: {cmds:?}"
+        );
+
+        // Verify the profiles list contains both unlocked profiles.
+        if let Some(Command::ActivateProfiles { profiles }) = cmds
+            .iter()
+            .find(|c| matches!(c, Command::ActivateProfiles { .. }))
+        {
+            assert_eq!(profiles.len(), 2);
+            assert!(profiles.iter().any(|p| p.as_ref() == "alpha"));
+            assert!(profiles.iter().any(|p| p.as_ref() == "beta"));
+        }
     }
 
-    #[test]
-    fn snapshot_initial_backward_returns_last_when_no_origin() {
-        let windows = test_windows();
-        let snap = Snapshot::with_origin(&windows, &test_config(), None);
-        assert_eq!(snap.initial_backward(), 2);
-    }
+    // === Zero-window launcher mode ===
 
     #[test]
-    fn snapshot_has_targets_single_window_with_origin() {
-        let single = vec![test_windows()[0].clone()];
-        let snap = Snapshot::with_origin(&single, &test_config(), Some(0));
+    fn launcher_zero_windows_emits_show_border_with_flag() {
+        let mut ctrl = OverlayController::new();
+        let cmds = ctrl.handle(Event::ActivateLauncher, &[], &test_config());
         assert!(
-            snap.has_targets(),
-            "single window is a valid target (user may be on a different workspace)"
+            cmds.iter().any(|c| matches!(
+                c,
+                Command::ShowBorder {
+                    zero_window_launcher: true,
+                    ..
+                }
+            )),
+            "launcher with zero windows must set zero_window_launcher flag, got: {cmds:?}"
         );
+        assert!(!ctrl.is_idle());
     }
 
     #[test]
-    fn snapshot_has_targets_with_origin_and_others() {
-        let windows = test_windows();
-        let snap = Snapshot::with_origin(&windows, &test_config(), Some(2));
-        assert!(snap.has_targets());
+    fn launcher_zero_windows_dwell_shows_picker() {
+        let mut ctrl = OverlayController::new();
+        ctrl.handle(Event::ActivateLauncher, &[], &test_config());
+        let cmds = ctrl.handle(Event::DwellTimeout, &[], &test_config());
+        assert!(cmds.iter().any(|c| matches!(c, Command::ShowPicker { .. })));
     }
 
     #[test]
-    fn snapshot_has_targets_no_origin() {
-        let windows = test_windows();
-        let snap = Snapshot::with_origin(&windows, &test_config(), None);
-        assert!(snap.has_targets());
+    fn launcher_zero_windows_alt_release_is_noop_in_picking() {
+        let mut ctrl = OverlayController::new();
+        ctrl.handle(Event::ActivateLauncher, &[], &test_config());
+        ctrl.handle(Event::DwellTimeout, &[], &test_config());
+        let cmds = ctrl.handle(Event::ModifierReleased, &[], &test_config());
+        assert!(
+            !cmds.iter().any(|c| matches!(
+                c,
+                Command::Hide | Command::HideAndSync | Command::ActivateWindow { .. }
+            )),
+            "Alt release in zero-window launcher should be no-op, got: {cmds:?}"
+        );
+        assert!(!ctrl.is_idle());
     }
 
     #[test]
-    fn snapshot_has_targets_empty() {
-        let empty: Vec<Window> = vec![];
-        let snap = Snapshot::with_origin(&empty, &test_config(), None);
-        assert!(!snap.has_targets());
+    fn launcher_zero_windows_alt_release_in_armed_transitions_to_picking() {
+        let mut ctrl = OverlayController::new();
+        ctrl.handle(Event::ActivateLauncher, &[], &test_config());
+        let cmds = ctrl.handle(Event::ModifierReleased, &[], &test_config());
+        assert!(
+            cmds.iter().any(|c| matches!(c, Command::ShowPicker { .. })),
+            "Alt release from Armed with zero windows should show picker, got: {cmds:?}"
+        );
+        assert!(!ctrl.is_idle());
     }
 
-    // ===================================================================
-    // Controller with injected origin — exercises origin-skipping paths
-    // ===================================================================
-
     #[test]
-    fn armed_with_origin_at_last_skips_origin_on_quick_switch() {
+    fn launcher_zero_windows_escape_dismisses() {
         let mut ctrl = OverlayController::new();
-        let windows = test_windows();
-        // Origin at index 2 (last) — typical after mru::reorder.
-        let snap = Snapshot::with_origin(&windows, &test_config(), Some(2));
-        ctrl.arm_with_snapshot(snap, 250);
-
-        // Fast release → quick-switch to index 0 (not origin at 2).
-        let cmds = ctrl.handle(Event::ModifierReleased, &windows, &test_config());
-        let activated = cmds.iter().find_map(|c| match c {
-            Command::ActivateWindow { window, .. } => Some(window.clone()),
-            _ => None,
-        });
-        assert!(activated.is_some(), "should activate a window");
-        assert_eq!(
-            activated.unwrap().id,
-            windows[0].id,
-            "should activate index 0, not origin"
-        );
+        ctrl.handle(Event::ActivateLauncher, &[], &test_config());
+        ctrl.handle(Event::DwellTimeout, &[], &test_config());
+        let cmds = ctrl.handle(Event::Escape, &[], &test_config());
+        assert!(cmds.iter().any(|c| matches!(c, Command::Hide)));
         assert!(ctrl.is_idle());
     }
 
     #[test]
-    fn armed_with_origin_at_0_skips_origin_on_quick_switch() {
+    fn launcher_zero_windows_char_stages_launch() {
         let mut ctrl = OverlayController::new();
-        let windows = test_windows();
-        // Edge case: origin at index 0 — forward should skip to 1.
-        let snap = Snapshot::with_origin(&windows, &test_config(), Some(0));
-        ctrl.arm_with_snapshot(snap, 250);
-
-        let cmds = ctrl.handle(Event::ModifierReleased, &windows, &test_config());
-        let activated = cmds.iter().find_map(|c| match c {
-            Command::ActivateWindow { window, .. } => Some(window.clone()),
-            _ => None,
-        });
-        assert!(activated.is_some());
-        assert_eq!(
-            activated.unwrap().id,
-            windows[1].id,
-            "should activate index 1, skipping origin at 0"
+        ctrl.handle(Event::ActivateLauncher, &[], &test_config());
+        ctrl.handle(Event::DwellTimeout, &[], &test_config());
+        let cmds = ctrl.handle(Event::Char('g'), &[], &test_config());
+        assert!(
+            cmds.iter()
+                .any(|c| matches!(c, Command::ShowLaunchStaged { .. })),
+            "typing configured key in zero-window launcher should stage launch, got: {cmds:?}"
         );
     }
 
     #[test]
-    fn picking_with_origin_starts_at_non_origin() {
+    fn launcher_zero_windows_alt_release_executes_staged_launch() {
         let mut ctrl = OverlayController::new();
-        let windows = test_windows();
-        let snap = Snapshot::with_origin(&windows, &test_config(), Some(2));
-        ctrl.pick_with_snapshot(snap);
-
-        if let Phase::Picking {
-            selection, snap, ..
-        } = &ctrl.phase
-        {
-            assert_ne!(*selection, 2, "should not start at origin");
-            assert_ne!(Some(*selection), snap.origin_index);
-            assert_eq!(*selection, 0);
-        } else {
-            panic!("expected Picking");
-        }
-    }
-
-    #[test]
-    fn single_window_is_origin_still_activatable() {
-        let single = vec![test_windows()[0].clone()];
-        let snap = Snapshot::with_origin(&single, &test_config(), Some(0));
-        assert!(snap.has_targets());
+        ctrl.handle(Event::ActivateLauncher, &[], &test_config());
+        ctrl.handle(Event::DwellTimeout, &[], &test_config());
+        ctrl.handle(Event::Char('g'), &[], &test_config());
+        let cmds = ctrl.handle(Event::ModifierReleased, &[], &test_config());
+        assert!(
+            cmds.iter()
+                .any(|c| matches!(c, Command::LaunchApp { command, .. } if command == "ghostty")),
+            "Alt release with staged launch should execute it, got: {cmds:?}"
+        );
     }
 
     #[test]
-    fn cycling_with_origin_visits_origin_too() {
+    fn switcher_zero_windows_still_noop() {
         let mut ctrl = OverlayController::new();
-        let windows = test_windows();
-        let snap = Snapshot::with_origin(&windows, &test_config(), Some(2));
-        ctrl.pick_with_snapshot(snap);
-
-        let mut visited = std::collections::HashSet::new();
-        for _ in 0..windows.len() {
-            if let Phase::Picking { selection, .. } = &ctrl.phase {
-                visited.insert(*selection);
-            }
-            ctrl.handle(Event::SelectionDown, &windows, &test_config());
-        }
-        if let Phase::Picking { selection, .. } = &ctrl.phase {
-            visited.insert(*selection);
-        }
-
-        assert!(visited.contains(&2), "cycling must visit origin at index 2");
-        assert_eq!(visited.len(), windows.len(), "must visit all indices");
+        let cmds = ctrl.handle(Event::Activate, &[], &test_config());
+        assert!(cmds.is_empty(), "Alt+Tab with zero windows must be no-op");
+        assert!(ctrl.is_idle());
     }
 
-    // === Origin rotation in Snapshot::build layout ===
-
     #[test]
-    fn origin_at_last_display_order() {
-        // Simulates what build() does: origin rotated to end.
-        // Given 3 windows [A=origin, B, C] after MRU reorder,
-        // rotation produces [B, C, A] with origin_index = 2.
-        let windows = test_windows(); // ghostty, vivaldi, edge
-        let snap = Snapshot::with_origin(&windows, &test_config(), Some(2));
-        // Origin is at the end.
-        assert_eq!(snap.origin_index, Some(2));
-        // Forward selection starts at 0 (top of picker, not origin).
-        assert_eq!(snap.initial_forward(), 0);
-        // Backward selection starts at 1 (last non-origin).
-        assert_eq!(snap.initial_backward(), 1);
+    fn launcher_with_windows_flag_is_false() {
+        let mut ctrl = OverlayController::new();
+        let cmds = ctrl.handle(Event::ActivateLauncher, &test_windows(), &test_config());
+        assert!(
+            cmds.iter().any(|c| matches!(
+                c,
+                Command::ShowBorder {
+                    zero_window_launcher: false,
+                    ..
+                }
+            )),
+            "launcher with windows should NOT set zero_window_launcher, got: {cmds:?}"
+        );
     }
 
     #[test]
-    fn origin_rotation_preserves_mru_order() {
-        // After rotation: [MRU previous, ..., origin]
-        // The non-origin windows maintain their relative MRU order.
-        let windows = test_windows(); // ghostty(0), firefox(1), edge(2)
-        // If ghostty is origin (index 0 pre-rotation), rotation produces:
-        // [firefox, edge, ghostty] with origin_index = 2
-        let mut rotated = windows.clone();
-        let origin = rotated.remove(0);
-        rotated.push(origin);
-        let snap = Snapshot::with_origin(&rotated, &test_config(), Some(2));
-
-        assert_eq!(snap.windows[0].app_id.as_str(), "firefox");
-        assert_eq!(snap.windows[1].app_id.as_str(), "microsoft-edge");
-        assert_eq!(snap.windows[2].app_id.as_str(), "com.mitchellh.ghostty");
-        assert_eq!(snap.origin_index, Some(2));
+    fn launcher_with_windows_alt_release_activates() {
+        let mut ctrl = OverlayController::new();
+        let windows = test_windows();
+        ctrl.handle(Event::ActivateLauncher, &windows, &test_config());
+        ctrl.handle(Event::DwellTimeout, &windows, &test_config());
+        let cmds = ctrl.handle(Event::ModifierReleased, &windows, &test_config());
+        assert!(
+            cmds.iter()
+                .any(|c| matches!(c, Command::ActivateWindow { .. })),
+            "Alt release with windows should activate, got: {cmds:?}"
+        );
+        assert!(ctrl.is_idle());
     }
 
     #[test]
-    fn origin_at_last_picker_top_is_switch_target() {
-        // The top of the picker (index 0) should be the quick-switch
-        // target, not the origin.
-        let windows = test_windows();
-        let snap = Snapshot::with_origin(&windows, &test_config(), Some(2));
+    fn copy_selected_window_fields_only_while_picking() {
         let mut ctrl = OverlayController::new();
-        ctrl.arm_with_snapshot(snap, 5000);
+        let windows = test_windows();
+        let config = test_config();
+        ctrl.handle(Event::Activate, &windows, &config);
+        assert!(
+            ctrl.handle(Event::CopyTitle, &windows, &config).is_empty(),
+            "nothing is visible to copy while armed"
+        );
 
-        // Quick-switch (fast release) should activate index 0.
-        let cmds = ctrl.handle(Event::ModifierReleased, &windows, &test_config());
-        let activated = cmds.iter().find_map(|c| match c {
-            Command::ActivateWindow { window, .. } => Some(window),
-            _ => None,
-        });
-        assert!(activated.is_some());
-        assert_eq!(
-            activated.unwrap().id,
-            windows[0].id,
-            "quick-switch should activate top of picker (index 0), not origin"
+        ctrl.handle(Event::DwellTimeout, &windows, &config);
+        let selected = ctrl.current_selection();
+        let Phase::Picking { snap, .. } = &ctrl.phase else {
+            panic!("expected Picking");
+        };
+        let target = snap.windows[selected].clone();
+
+        let cmds = ctrl.handle(Event::CopyTitle, &windows, &config);
+        assert!(
+            matches!(&cmds[..], [Command::CopyToClipboard { text }] if *text == target.title),
+            "got: {cmds:?}"
+        );
+        let cmds = ctrl.handle(Event::CopyAppId, &windows, &config);
+        assert!(
+            matches!(&cmds[..], [Command::CopyToClipboard { text }] if text == target.app_id.as_str()),
+            "got: {cmds:?}"
+        );
+        assert!(
+            matches!(ctrl.phase, Phase::Picking { .. }),
+            "overlay stays open"
         );
     }
 
-    // === In-flight direction change (Alt+Tab then Alt+Shift+Tab) ===
-
     #[test]
-    fn armed_forward_then_backward_reverses() {
+    fn close_window_asks_before_closing() {
         let mut ctrl = OverlayController::new();
-        let windows = test_windows(); // 3 windows, origin at 2
-        let snap = Snapshot::with_origin(&windows, &test_config(), Some(2));
-        ctrl.arm_with_snapshot(snap, 5000);
+        let windows = test_windows();
+        let config = test_config();
+        ctrl.handle(Event::Activate, &windows, &config);
+        ctrl.handle(Event::DwellTimeout, &windows, &config);
+        let selected = ctrl.current_selection();
+        let Phase::Picking { snap, .. } = &ctrl.phase else {
+            panic!("expected Picking");
+        };
+        let target = snap.windows[selected].clone();
 
-        // Initial forward selection is 0.
-        if let Phase::Armed { selection, .. } = &ctrl.phase {
-            assert_eq!(*selection, 0);
-        }
+        let cmds = ctrl.handle(Event::CloseWindow, &windows, &config);
+        let prompt = format!("Close \u{2018}{}\u{2019}? y/n", target.title);
+        assert!(
+            matches!(&cmds[..], [Command::ShowPalette { message }] if *message == prompt),
+            "got: {cmds:?}"
+        );
+        assert!(
+            ctrl.handle(Event::ModifierReleased, &windows, &config)
+                .is_empty(),
+            "releasing Alt does not answer the prompt"
+        );
 
-        // Forward re-activation: selection moves to 1.
-        ctrl.handle(Event::Activate, &windows, &test_config());
-        if let Phase::Armed { selection, .. } = &ctrl.phase {
-            assert_eq!(*selection, 1);
-        }
+        // `n` returns to the picker with nothing closed.
+        let cmds = ctrl.handle(Event::Char('n'), &windows, &config);
+        assert!(
+            matches!(&cmds[..], [Command::UpdatePicker { .. }]),
+            "got: {cmds:?}"
+        );
+        assert!(matches!(ctrl.phase, Phase::Picking { .. }));
 
-        // Backward re-activation: selection moves back to 0.
-        ctrl.handle(Event::ActivateBackward, &windows, &test_config());
-        if let Phase::Armed { selection, .. } = &ctrl.phase {
-            assert_eq!(*selection, 0);
-        }
+        // `y` closes it and the picker is rebuilt without it.
+        ctrl.handle(Event::CloseWindow, &windows, &config);
+        let cmds = ctrl.handle(Event::Char('y'), &windows, &config);
+        assert!(
+            matches!(&cmds[0], Command::CloseWindows { windows } if windows.len() == 1 && windows[0].id == target.id),
+            "got: {cmds:?}"
+        );
+        assert!(
+            cmds.iter()
+                .any(|c| matches!(c, Command::ShowPicker { windows, .. } if windows.len() == 2)),
+            "got: {cmds:?}"
+        );
+        assert!(matches!(ctrl.phase, Phase::Picking { .. }));
     }
 
-    #[test]
-    fn picking_forward_then_backward_reverses() {
+    /// Picker over `test_windows()` with the selection off the origin.
+    fn picking_with_origin() -> (OverlayController, Vec<Window>, Window, Window) {
         let mut ctrl = OverlayController::new();
         let windows = test_windows();
         let snap = Snapshot::with_origin(&windows, &test_config(), Some(2));
         ctrl.pick_with_snapshot(snap);
+        let Phase::Picking {
+            snap, selection, ..
+        } = &ctrl.phase
+        else {
+            panic!("expected Picking");
+        };
+        let selected = snap.windows[*selection].clone();
+        let origin = snap.windows[snap.origin_index.unwrap()].clone();
+        assert_ne!(selected.id, origin.id);
+        (ctrl, windows, selected, origin)
+    }
 
-        // Initial forward selection is 0.
-        if let Phase::Picking { selection, .. } = &ctrl.phase {
-            assert_eq!(*selection, 0);
-        }
-
-        // Forward re-activation: 0 → 1.
-        ctrl.handle(Event::Activate, &windows, &test_config());
-        if let Phase::Picking { selection, .. } = &ctrl.phase {
-            assert_eq!(*selection, 1);
-        }
+    #[test]
+    fn peek_raises_selection_and_restores_origin_on_navigation() {
+        let (mut ctrl, windows, selected, origin) = picking_with_origin();
+        let config = test_config();
 
-        // Backward re-activation: 1 → 0.
-        ctrl.handle(Event::ActivateBackward, &windows, &test_config());
-        if let Phase::Picking { selection, .. } = &ctrl.phase {
-            assert_eq!(*selection, 0);
-        }
+        let cmds = ctrl.handle(Event::Peek, &windows, &config);
+        assert!(
+            matches!(&cmds[..], [Command::PeekWindow { window }] if window.id == selected.id),
+            "got: {cmds:?}"
+        );
+        assert!(ctrl.handle(Event::Peek, &windows, &config).is_empty());
 
-        // Backward again: 0 → 2 (wraps).
-        ctrl.handle(Event::ActivateBackward, &windows, &test_config());
-        if let Phase::Picking { selection, .. } = &ctrl.phase {
-            assert_eq!(*selection, 2);
-        }
+        let cmds = ctrl.handle(Event::SelectionDown, &windows, &config);
+        assert!(
+            matches!(cmds.last(), Some(Command::PeekWindow { window }) if window.id == origin.id),
+            "got: {cmds:?}"
+        );
+        assert!(ctrl.peek.is_none());
     }
 
-    // ===================================================================
-    // Vault unlock flow tests
-    // ===================================================================
+    #[test]
+    fn peek_restores_origin_on_escape() {
+        let (mut ctrl, windows, _, origin) = picking_with_origin();
+        let config = test_config();
+        ctrl.handle(Event::Peek, &windows, &config);
 
-    /// Helper: drive the controller to Phase::Launching with a staged ghostty launch.
-    /// Uses an empty window list so 'g' hits the launch binding (not a hint match).
-    fn drive_to_launching(ctrl: &mut OverlayController, _windows: &[Window], config: &WmConfig) {
-        let no_windows: Vec<Window> = vec![];
-        ctrl.handle(Event::ActivateLauncher, &no_windows, config);
-        ctrl.handle(Event::Char('g'), &no_windows, config);
-        let cmds = ctrl.handle(Event::ModifierReleased, &no_windows, config);
+        let cmds = ctrl.handle(Event::Escape, &windows, &config);
+        assert!(cmds.iter().any(|c| matches!(c, Command::Hide)));
         assert!(
-            matches!(ctrl.phase, Phase::Launching),
-            "expected Phase::Launching after modifier release with staged launch, \
-             phase={:?}, cmds={cmds:?}",
-            ctrl.phase
+            matches!(cmds.last(), Some(Command::PeekWindow { window }) if window.id == origin.id),
+            "got: {cmds:?}"
         );
     }
 
-    /// Helper: construct a VaultsLocked LaunchResult event.
-    fn vaults_locked_event(profiles: &[&str]) -> Event {
-        Event::LaunchResult {
-            success: false,
-            error: Some("VaultsLocked".into()),
-            denial: Some(LaunchDenial::VaultsLocked {
-                locked_profiles: profiles
-                    .iter()
-                    .map(|p| TrustProfileName::try_from(*p).unwrap())
-                    .collect(),
-            }),
-            original_command: Some("ghostty".into()),
-            original_tags: Some(vec!["dev".into()]),
-            original_launch_args: Some(vec![]),
-        }
+    #[test]
+    fn committing_a_peek_does_not_restore_origin() {
+        let (mut ctrl, windows, selected, _) = picking_with_origin();
+        let config = test_config();
+        ctrl.handle(Event::Peek, &windows, &config);
+
+        let cmds = ctrl.handle(Event::ModifierReleased, &windows, &config);
+        assert!(
+            cmds.iter().any(
+                |c| matches!(c, Command::ActivateWindow { window, .. } if window.id == selected.id)
+            ),
+            "got: {cmds:?}"
+        );
+        assert!(!cmds.iter().any(|c| matches!(c, Command::PeekWindow { .. })));
+        assert!(ctrl.peek.is_none());
     }
 
-    /// Helper: drive to Phase::Unlocking (Password mode) for a single profile.
-    fn drive_to_password_prompt(
-        ctrl: &mut OverlayController,
-        windows: &[Window],
-        config: &WmConfig,
-        profile: &str,
-    ) {
-        drive_to_launching(ctrl, windows, config);
-        ctrl.handle(vaults_locked_event(&[profile]), windows, config);
-        ctrl.handle(
-            Event::AutoUnlockResult {
-                success: false,
-                profile: TrustProfileName::try_from(profile).unwrap(),
-                needs_touch: false,
-            },
-            windows,
-            config,
+    #[test]
+    fn keep_open_activates_without_dismissing_until_switched_off() {
+        let (mut ctrl, windows, selected, _) = picking_with_origin();
+        let config = test_config();
+
+        let cmds = ctrl.handle(Event::KeepOpen, &windows, &config);
+        assert!(
+            matches!(&cmds[..], [Command::ShowPalette { .. }]),
+            "got: {cmds:?}"
         );
         assert!(
-            matches!(
-                ctrl.phase,
-                Phase::Unlocking {
-                    unlock_mode: UnlockMode::Password,
-                    ..
-                }
+            ctrl.handle(Event::ModifierReleased, &windows, &config)
+                .is_empty(),
+            "Alt release commits nothing while kept open"
+        );
+
+        let cmds = ctrl.handle(Event::Confirm, &windows, &config);
+        assert!(
+            cmds.iter().any(
+                |c| matches!(c, Command::ActivateWindow { window, .. } if window.id == selected.id)
             ),
-            "expected Phase::Unlocking(Password) after auto-unlock failure"
+            "got: {cmds:?}"
+        );
+        assert!(matches!(cmds.last(), Some(Command::RefreshPicker)));
+        assert!(!cmds.iter().any(|c| matches!(
+            c,
+            Command::Hide | Command::HideAndSync | Command::EnsureWindowAlive { .. }
+        )));
+        assert!(matches!(ctrl.phase, Phase::Picking { .. }));
+
+        let cmds = ctrl.handle(Event::Refresh, &windows, &config);
+        assert!(matches!(cmds.first(), Some(Command::ShowPicker { .. })));
+        assert!(matches!(ctrl.phase, Phase::Picking { .. }));
+
+        ctrl.handle(Event::KeepOpen, &windows, &config);
+        let cmds = ctrl.handle(Event::Confirm, &windows, &config);
+        assert!(cmds.iter().any(|c| matches!(c, Command::HideAndSync)));
+        assert!(ctrl.is_idle());
+        assert!(
+            ctrl.handle(Event::Refresh, &windows, &config).is_empty(),
+            "a late refresh does not reopen the picker"
         );
     }
 
     #[test]
-    fn vaults_locked_transitions_to_unlocking() {
-        let mut ctrl = OverlayController::new();
-        let windows = test_windows();
+    fn keep_open_resets_on_fresh_activation() {
+        let (mut ctrl, windows, _, _) = picking_with_origin();
         let config = test_config();
+        ctrl.handle(Event::KeepOpen, &windows, &config);
+        ctrl.handle(Event::Escape, &windows, &config);
+        ctrl.handle(Event::Activate, &windows, &config);
+        assert!(!ctrl.keep_open);
+    }
 
-        drive_to_launching(&mut ctrl, &windows, &config);
+    #[test]
+    fn place_switches_then_places_selected_window() {
+        let (mut ctrl, windows, selected, _) = picking_with_origin();
+        ctrl.set_placements(true, false);
+        let config = test_config();
 
-        let cmds = ctrl.handle(vaults_locked_event(&["default", "work"]), &windows, &config);
+        let cmds = ctrl.handle(Event::Place(WindowPlacement::Maximized), &windows, &config);
+        let activate = cmds.iter().position(
+            |c| matches!(c, Command::ActivateWindow { window, .. } if window.id == selected.id),
+        );
+        let place = cmds.iter().position(|c| {
+            matches!(c, Command::PlaceWindow { window, placement: WindowPlacement::Maximized }
+                if window.id == selected.id)
+        });
+        assert!(
+            activate.is_some() && place == activate.map(|i| i + 1),
+            "got: {cmds:?}"
+        );
+        assert!(ctrl.is_idle());
+    }
+
+    #[test]
+    fn row_menu_runs_the_highlighted_verb() {
+        let (mut ctrl, windows, selected, _) = picking_with_origin();
+        ctrl.set_placements(true, false);
+        let config = test_config();
+        let labels = |cmds: &[Command]| match cmds {
+            [Command::SetRowMenu { menu: Some(menu) }] => (
+                menu.entries
+                    .iter()
+                    .map(|e| e.label.clone())
+                    .collect::<Vec<_>>(),
+                menu.selected,
+            ),
+            _ => panic!("got: {cmds:?}"),
+        };
 
+        let (entries, selected_entry) = labels(&ctrl.handle(Event::Char('.'), &windows, &config));
+        assert_eq!(
+            entries,
+            [
+                "Switch to",
+                "Close",
+                "Maximize",
+                "Mark…",
+                "Copy title",
+                "Copy app ID"
+            ]
+        );
+        assert_eq!(selected_entry, 0);
         assert!(
-            matches!(ctrl.phase, Phase::Unlocking { .. }),
-            "VaultsLocked must transition to Phase::Unlocking"
+            ctrl.handle(Event::ModifierReleased, &windows, &config)
+                .is_empty(),
+            "Alt release commits nothing while the menu is open"
         );
+        assert!(ctrl.handle(Event::Char('a'), &windows, &config).is_empty());
+        assert_eq!(
+            labels(&ctrl.handle(Event::SelectionUp, &windows, &config)).1,
+            5
+        );
+        ctrl.handle(Event::SelectionDown, &windows, &config);
+        ctrl.handle(Event::SelectionDown, &windows, &config);
+        ctrl.handle(Event::SelectionDown, &windows, &config);
+
+        let cmds = ctrl.handle(Event::Confirm, &windows, &config);
         assert!(
-            cmds.iter()
-                .any(|c| matches!(c, Command::AttemptAutoUnlock { .. })),
-            "VaultsLocked must emit AttemptAutoUnlock, got: {cmds:?}"
+            matches!(cmds.first(), Some(Command::SetRowMenu { menu: None })),
+            "got: {cmds:?}"
         );
+        assert!(cmds.iter().any(|c| matches!(
+            c,
+            Command::PlaceWindow { window, placement: WindowPlacement::Maximized }
+                if window.id == selected.id
+        )));
+        assert!(ctrl.is_idle());
     }
 
     #[test]
-    fn auto_unlock_failure_falls_back_to_password() {
+    fn escape_closes_the_row_menu_before_the_picker() {
+        let (mut ctrl, windows, _, _) = picking_with_origin();
+        let config = test_config();
+
+        ctrl.handle(Event::RowMenu, &windows, &config);
+        let cmds = ctrl.handle(Event::Escape, &windows, &config);
+        assert!(
+            matches!(&cmds[..], [Command::SetRowMenu { menu: None }]),
+            "got: {cmds:?}"
+        );
+        assert!(matches!(ctrl.phase, Phase::Picking { .. }));
+        // With the menu closed, `.` opens it again and Ctrl+C closes it on
+        // the way to copying.
+        ctrl.handle(Event::Char('.'), &windows, &config);
+        let cmds = ctrl.handle(Event::CopyTitle, &windows, &config);
+        assert!(matches!(
+            cmds.first(),
+            Some(Command::SetRowMenu { menu: None })
+        ));
+        assert!(ctrl.row_menu.is_none());
+    }
+
+    #[test]
+    fn question_mark_shows_help_over_the_armed_picker() {
         let mut ctrl = OverlayController::new();
         let windows = test_windows();
         let config = test_config();
+        ctrl.handle(Event::Activate, &windows, &config);
 
-        drive_to_launching(&mut ctrl, &windows, &config);
-        ctrl.handle(vaults_locked_event(&["default"]), &windows, &config);
+        let cmds = ctrl.handle(Event::Char('?'), &windows, &config);
+        assert!(matches!(ctrl.phase, Phase::Picking { .. }));
+        let Some(Command::SetHelp { help: Some(help) }) = cmds.last() else {
+            panic!("expected help, got: {cmds:?}");
+        };
+        assert!(help.sections[0].entries.iter().any(|(k, _)| k == "Ctrl+W"));
+        // Typing and moving do nothing while help is shown, and releasing
+        // Alt does not commit.
+        assert!(ctrl.handle(Event::Char('a'), &windows, &config).is_empty());
+        assert!(
+            ctrl.handle(Event::SelectionDown, &windows, &config)
+                .is_empty()
+        );
+        assert!(
+            ctrl.handle(Event::ModifierReleased, &windows, &config)
+                .is_empty()
+        );
+        let cmds = ctrl.handle(Event::Char('?'), &windows, &config);
+        assert!(
+            matches!(&cmds[..], [Command::SetHelp { help: None }]),
+            "got: {cmds:?}"
+        );
+        assert!(matches!(ctrl.phase, Phase::Picking { .. }));
+    }
 
-        let cmds = ctrl.handle(
-            Event::AutoUnlockResult {
-                success: false,
-                profile: TrustProfileName::try_from("default").unwrap(),
-                needs_touch: false,
-            },
-            &windows,
+    #[test]
+    fn first_activation_shows_the_walkthrough_until_done() {
+        let mut ctrl = OverlayController::new();
+        let windows = test_windows();
+        let config = test_config();
+        ctrl.start_walkthrough(Walkthrough::new(onboarding::pages(
             &config,
+            std::path::Path::new("config.toml"),
+        )));
+
+        let cmds = ctrl.handle(Event::Activate, &windows, &config);
+        assert!(matches!(ctrl.phase, Phase::Picking { .. }));
+        assert!(
+            matches!(
+                cmds.last(),
+                Some(Command::SetWalkthrough { page: Some(page) }) if page.index == 0
+            ),
+            "got: {cmds:?}"
         );
+        // Releasing Alt does not switch, and hint letters do not type.
+        assert!(
+            ctrl.handle(Event::ModifierReleased, &windows, &config)
+                .is_empty()
+        );
+        assert!(ctrl.handle(Event::Char('a'), &windows, &config).is_empty());
+        let cmds = ctrl.handle(Event::Confirm, &windows, &config);
+        assert!(matches!(
+            &cmds[..],
+            [Command::SetWalkthrough { page: Some(page) }] if page.index == 1
+        ));
+        // Escape leaves the picker, and the walkthrough is not shown again
+        // this run, nor recorded as done.
+        let cmds = ctrl.handle(Event::Escape, &windows, &config);
+        assert!(matches!(
+            &cmds[..],
+            [Command::SetWalkthrough { page: None }]
+        ));
+        assert!(matches!(ctrl.phase, Phase::Picking { .. }));
 
+        let mut ctrl = OverlayController::new();
+        ctrl.start_walkthrough(Walkthrough::new(onboarding::pages(
+            &config,
+            std::path::Path::new("config.toml"),
+        )));
+        ctrl.handle(Event::Activate, &windows, &config);
+        let cmds = ctrl.handle(Event::Char('d'), &windows, &config);
+        assert!(matches!(
+            &cmds[..],
+            [
+                Command::SetWalkthrough { page: None },
+                Command::FinishWalkthrough
+            ]
+        ));
+        ctrl.handle(Event::Escape, &windows, &config);
+        let cmds = ctrl.handle(Event::Activate, &windows, &config);
         assert!(
-            cmds.iter()
-                .any(|c| matches!(c, Command::ShowPasswordPrompt { .. })),
-            "auto-unlock failure must fall back to password prompt, got: {cmds:?}"
+            !cmds
+                .iter()
+                .any(|c| matches!(c, Command::SetWalkthrough { .. }))
         );
-        if let Phase::Unlocking { unlock_mode, .. } = &ctrl.phase {
-            assert_eq!(*unlock_mode, UnlockMode::Password);
-        } else {
-            panic!("expected Phase::Unlocking");
-        }
     }
 
     #[test]
-    fn password_chars_tracked_in_unlock_phase() {
-        let mut ctrl = OverlayController::new();
-        let windows = test_windows();
+    fn a_verb_hides_help_and_runs() {
+        let (mut ctrl, windows, _, _) = picking_with_origin();
         let config = test_config();
 
-        drive_to_password_prompt(&mut ctrl, &windows, &config, "default");
+        ctrl.handle(Event::Help, &windows, &config);
+        let cmds = ctrl.handle(Event::Escape, &windows, &config);
+        assert!(matches!(&cmds[..], [Command::SetHelp { help: None }]));
+        ctrl.handle(Event::Help, &windows, &config);
+        let cmds = ctrl.handle(Event::CopyTitle, &windows, &config);
+        assert!(matches!(
+            &cmds[..],
+            [
+                Command::SetHelp { help: None },
+                Command::CopyToClipboard { .. }
+            ]
+        ));
+        assert!(!ctrl.help_open);
+    }
+
+    #[test]
+    fn session_lock_dismisses_and_blocks_activation() {
+        let (mut ctrl, windows, _, _) = picking_with_origin();
+        let config = test_config();
 
-        let cmds = ctrl.handle(Event::Char('p'), &windows, &config);
-        assert!(cmds.iter().any(|c| matches!(c, Command::PasswordChar('p'))));
+        let cmds = ctrl.handle(Event::SessionLock(true), &windows, &config);
+        assert!(
+            cmds.iter().any(|c| matches!(c, Command::Hide))
+                && cmds
+                    .iter()
+                    .any(|c| matches!(c, Command::Publish(EventKind::WmOverlayDismissed, _))),
+            "got: {cmds:?}"
+        );
+        assert!(ctrl.is_idle());
 
-        let cmds = ctrl.handle(Event::Char('a'), &windows, &config);
-        assert!(cmds.iter().any(|c| matches!(c, Command::PasswordChar('a'))));
+        assert!(ctrl.handle(Event::Activate, &windows, &config).is_empty());
+        assert!(ctrl.is_idle());
 
-        if let Phase::Unlocking { password_len, .. } = &ctrl.phase {
-            assert_eq!(*password_len, 2);
-        } else {
-            panic!("expected Phase::Unlocking");
-        }
+        ctrl.handle(Event::SessionLock(false), &windows, &config);
+        assert!(!ctrl.handle(Event::Activate, &windows, &config).is_empty());
+        assert!(!ctrl.is_idle());
     }
 
     #[test]
-    fn confirm_during_password_emits_submit() {
-        let mut ctrl = OverlayController::new();
-        let windows = test_windows();
+    fn lock_request_dismisses_without_blocking_activation() {
+        let (mut ctrl, windows, _, _) = picking_with_origin();
         let config = test_config();
 
-        drive_to_password_prompt(&mut ctrl, &windows, &config, "default");
-        ctrl.handle(Event::Char('p'), &windows, &config);
-
-        let cmds = ctrl.handle(Event::Confirm, &windows, &config);
+        let cmds = ctrl.handle(Event::SessionLockRequested, &windows, &config);
         assert!(
-            cmds.iter()
-                .any(|c| matches!(c, Command::SubmitPasswordUnlock { .. })),
-            "Confirm during password must emit SubmitPasswordUnlock, got: {cmds:?}"
+            cmds.iter().any(|c| matches!(c, Command::Hide)),
+            "got: {cmds:?}"
         );
-        // ShowVerifying is now emitted by the main.rs handler before IPC,
-        // not by the controller, so we only check SubmitPasswordUnlock here.
+        assert!(ctrl.is_idle());
+
+        // No Unlock or LockedHint follows on lockers such as swaylock.
+        assert!(!ctrl.handle(Event::Activate, &windows, &config).is_empty());
+        assert!(!ctrl.is_idle());
     }
 
     #[test]
-    fn successful_unlock_retries_launch() {
-        let mut ctrl = OverlayController::new();
-        let windows = test_windows();
-        let config = test_config();
-
-        drive_to_password_prompt(&mut ctrl, &windows, &config, "default");
+    fn shortcut_inhibiting_focus_warns_or_skips() {
+        let mut windows = test_windows();
+        windows[0].app_id = core_types::AppId::new("org.remmina.Remmina");
+        let mut config = test_config();
 
-        let cmds = ctrl.handle(
-            Event::UnlockResult {
-                success: true,
-                profile: TrustProfileName::try_from("default").unwrap(),
-            },
-            &windows,
-            &config,
+        let mut ctrl = OverlayController::new();
+        let cmds = ctrl.handle(Event::Activate, &windows, &config);
+        assert!(
+            cmds.iter().any(|c| matches!(c, Command::ShowRetry { .. })),
+            "got: {cmds:?}"
         );
-
+        assert!(matches!(ctrl.phase, Phase::Picking { .. }));
+        // Alt release does not switch; an explicit pick does.
+        assert!(
+            ctrl.handle(Event::ModifierReleased, &windows, &config)
+                .is_empty()
+        );
+        let cmds = ctrl.handle(Event::Confirm, &windows, &config);
         assert!(
             cmds.iter()
-                .any(|c| matches!(c, Command::ActivateProfiles { .. })),
-            "successful unlock must activate profiles before retry, got: {cmds:?}"
+                .any(|c| matches!(c, Command::ActivateWindow { .. }))
         );
+
+        // The next cycle over an ordinary window quick-switches again.
+        let cmds = ctrl.handle(Event::Activate, &test_windows(), &config);
+        assert!(!cmds.iter().any(|c| matches!(c, Command::ShowRetry { .. })));
+        let cmds = ctrl.handle(Event::ModifierReleased, &test_windows(), &config);
         assert!(
             cmds.iter()
-                .any(|c| matches!(c, Command::LaunchApp { command, .. } if command == "ghostty")),
-            "successful unlock must retry original launch, got: {cmds:?}"
+                .any(|c| matches!(c, Command::ActivateWindow { .. }))
         );
-        assert!(matches!(ctrl.phase, Phase::Launching));
+
+        config.shortcut_inhibit = ShortcutInhibit::Skip;
+        assert!(ctrl.handle(Event::Activate, &windows, &config).is_empty());
+        assert!(ctrl.is_idle());
     }
 
     #[test]
-    fn multi_profile_sequential_unlock() {
-        let mut ctrl = OverlayController::new();
-        let windows = test_windows();
-        let config = test_config();
+    fn unsupported_placement_keeps_picker_open() {
+        let (mut ctrl, windows, _, _) = picking_with_origin();
+        ctrl.set_placements(true, false);
+        let cmds = ctrl.handle(
+            Event::Place(WindowPlacement::LeftHalf),
+            &windows,
+            &test_config(),
+        );
+        assert!(
+            matches!(&cmds[..], [Command::ShowPalette { message }] if message.contains("tile")),
+            "got: {cmds:?}"
+        );
+        assert!(matches!(ctrl.phase, Phase::Picking { .. }));
+    }
 
-        drive_to_launching(&mut ctrl, &windows, &config);
-        ctrl.handle(vaults_locked_event(&["default", "work"]), &windows, &config);
+    #[test]
+    fn toggle_flips_selected_row_and_keeps_picker_open() {
+        let (mut ctrl, windows, selected, _) = picking_with_origin();
+        ctrl.set_toggles(true, false);
+        let config = test_config();
 
-        // First profile: auto-unlock fails, password succeeds.
-        ctrl.handle(
-            Event::AutoUnlockResult {
-                success: false,
-                profile: TrustProfileName::try_from("default").unwrap(),
-                needs_touch: false,
-            },
-            &windows,
-            &config,
+        let cmds = ctrl.handle(Event::Toggle(WindowToggle::Sticky), &windows, &config);
+        assert!(
+            matches!(&cmds[0], Command::SetWindowToggle { window, toggle: WindowToggle::Sticky, on: true }
+                if window.id == selected.id && window.is_sticky),
+            "got: {cmds:?}"
         );
-        let cmds = ctrl.handle(
-            Event::UnlockResult {
-                success: true,
-                profile: TrustProfileName::try_from("default").unwrap(),
-            },
-            &windows,
-            &config,
+        let marked = cmds.iter().find_map(|c| match c {
+            Command::ShowPicker { windows, .. } => {
+                Some(windows.iter().filter(|w| w.sticky).count())
+            }
+            _ => None,
+        });
+        assert_eq!(marked, Some(1), "got: {cmds:?}");
+        assert!(matches!(ctrl.phase, Phase::Picking { .. }));
+
+        let cmds = ctrl.handle(Event::Toggle(WindowToggle::Sticky), &windows, &config);
+        assert!(
+            matches!(&cmds[0], Command::SetWindowToggle { on: false, .. }),
+            "got: {cmds:?}"
         );
 
-        // Must advance to second profile, NOT retry launch yet.
+        let cmds = ctrl.handle(Event::Toggle(WindowToggle::KeepAbove), &windows, &config);
         assert!(
-            cmds.iter()
-                .any(|c| matches!(c, Command::AttemptAutoUnlock { profile }
-            if profile.as_ref() == "work")),
-            "must advance to next locked profile, got: {cmds:?}"
+            matches!(&cmds[..], [Command::ShowPalette { message }] if message.contains("above")),
+            "got: {cmds:?}"
         );
-        if let Phase::Unlocking { current_index, .. } = &ctrl.phase {
-            assert_eq!(*current_index, 1);
-        } else {
-            panic!("expected Phase::Unlocking at index 1");
-        }
+    }
 
-        // Second profile: auto-unlock fails, password succeeds.
-        ctrl.handle(
-            Event::AutoUnlockResult {
-                success: false,
-                profile: TrustProfileName::try_from("work").unwrap(),
-                needs_touch: false,
-            },
-            &windows,
-            &config,
+    #[test]
+    fn mark_takes_the_next_letter_instead_of_input() {
+        let (mut ctrl, windows, selected, _) = picking_with_origin();
+        let config = test_config();
+
+        let cmds = ctrl.handle(Event::Mark, &windows, &config);
+        assert!(
+            matches!(&cmds[..], [Command::ShowPalette { .. }]),
+            "got: {cmds:?}"
         );
-        let cmds = ctrl.handle(
-            Event::UnlockResult {
-                success: true,
-                profile: TrustProfileName::try_from("work").unwrap(),
-            },
-            &windows,
-            &config,
+        let cmds = ctrl.handle(Event::Char('a'), &windows, &config);
+        assert!(
+            matches!(&cmds[0], Command::MarkWindow { mark: 'a', window } if window.id == selected.id),
+            "got: {cmds:?}"
         );
+        assert!(matches!(&ctrl.phase, Phase::Picking { input, .. } if input.is_empty()));
 
-        // NOW activate profiles and retry the launch.
+        // Only the first character after Ctrl+M names a mark.
+        ctrl.handle(Event::Mark, &windows, &config);
+        let cmds = ctrl.handle(Event::Char('1'), &windows, &config);
         assert!(
-            cmds.iter()
-                .any(|c| matches!(c, Command::ActivateProfiles { .. })),
-            "all profiles unlocked must activate profiles before retry, got: {cmds:?}"
+            matches!(&cmds[..], [Command::ShowPalette { .. }]),
+            "got: {cmds:?}"
         );
+        let cmds = ctrl.handle(Event::Char('a'), &windows, &config);
         assert!(
-            cmds.iter()
-                .any(|c| matches!(c, Command::LaunchApp { command, .. } if command == "ghostty")),
-            "all profiles unlocked must retry launch, got: {cmds:?}"
+            !cmds.iter().any(|c| matches!(c, Command::MarkWindow { .. })),
+            "got: {cmds:?}"
         );
-        assert!(matches!(ctrl.phase, Phase::Launching));
     }
 
     #[test]
-    fn wrong_password_resets_for_retry() {
-        let mut ctrl = OverlayController::new();
-        let windows = test_windows();
+    fn move_to_output_takes_the_next_digit_and_follows_activation() {
+        let (mut ctrl, windows, selected, _) = picking_with_origin();
         let config = test_config();
+        let outputs: Vec<Output> = ["DP-1", "HDMI-A-1"]
+            .into_iter()
+            .map(|name| Output {
+                id: core_types::MonitorId::new(),
+                name: name.into(),
+                description: String::new(),
+            })
+            .collect();
+        ctrl.set_outputs(outputs.clone());
 
-        drive_to_password_prompt(&mut ctrl, &windows, &config, "default");
-        ctrl.handle(Event::Char('x'), &windows, &config);
-
-        let cmds = ctrl.handle(
-            Event::UnlockResult {
-                success: false,
-                profile: TrustProfileName::try_from("default").unwrap(),
-            },
-            &windows,
-            &config,
+        let cmds = ctrl.handle(Event::MoveToOutput, &windows, &config);
+        assert!(
+            matches!(&cmds[..], [Command::ShowPalette { message }] if message.contains("cannot")),
+            "got: {cmds:?}"
         );
+        assert!(matches!(ctrl.phase, Phase::Picking { .. }));
 
+        ctrl.set_can_move_to_output(true);
+        let cmds = ctrl.handle(Event::MoveToOutput, &windows, &config);
         assert!(
-            cmds.iter()
-                .any(|c| matches!(c, Command::ClearPasswordBuffer))
+            matches!(&cmds[..], [Command::ShowPalette { message }] if message.contains("2 HDMI-A-1")),
+            "got: {cmds:?}"
+        );
+        let cmds = ctrl.handle(Event::Char('2'), &windows, &config);
+        let activate = cmds.iter().position(
+            |c| matches!(c, Command::ActivateWindow { window, .. } if window.id == selected.id),
+        );
+        let moved = cmds.iter().position(|c| {
+            matches!(c, Command::MoveWindowToOutput { window, output }
+                if window.id == selected.id && *output == outputs[1])
+        });
+        assert_eq!(moved, activate.map(|i| i + 1), "got: {cmds:?}");
+        assert!(ctrl.is_idle());
+    }
+
+    #[test]
+    fn output_row_focuses_the_latest_window_on_it() {
+        let windows = test_windows();
+        let config = test_config();
+        let mut snap = Snapshot::with_origin(&windows, &config, Some(2));
+        let output = Output {
+            id: snap.windows[1].monitor_id,
+            name: "DP-1".into(),
+            description: String::new(),
+        };
+        let empty = Output {
+            id: core_types::MonitorId::new(),
+            name: "HDMI-A-1".into(),
+            description: String::new(),
+        };
+        items::append_rows(
+            &mut snap.windows,
+            &mut snap.items,
+            Some(2),
+            &[
+                SelectableItem::Output(output),
+                SelectableItem::Output(empty),
+            ],
+            10,
         );
+        let target = snap.windows[1].id;
+        assert!(snap.window(2).is_none());
+
+        let mut ctrl = OverlayController::new();
+        let cmds = ctrl.activate_index(2, &snap);
         assert!(
-            cmds.iter()
-                .any(|c| matches!(c, Command::ShowPasswordPrompt { .. }))
+            cmds.iter().any(
+                |c| matches!(c, Command::ActivateWindow { window, .. } if window.id == target)
+            ),
+            "got: {cmds:?}"
         );
+        let cmds = ctrl.activate_index(3, &snap);
         assert!(
-            cmds.iter()
-                .any(|c| matches!(c, Command::ShowUnlockError { .. }))
+            matches!(
+                &cmds[..],
+                [
+                    Command::Hide,
+                    Command::Publish(EventKind::WmOverlayDismissed, _)
+                ]
+            ),
+            "got: {cmds:?}"
         );
-
-        if let Phase::Unlocking {
-            password_len,
-            unlock_mode,
-            ..
-        } = &ctrl.phase
-        {
-            assert_eq!(*password_len, 0);
-            assert_eq!(*unlock_mode, UnlockMode::Password);
-        } else {
-            panic!("expected Phase::Unlocking after wrong password");
-        }
     }
 
     #[test]
-    fn escape_during_unlock_dismisses_and_clears() {
+    fn timeline_orders_rows_by_focus_time_and_back() {
         let mut ctrl = OverlayController::new();
         let windows = test_windows();
         let config = test_config();
+        let mut snap = Snapshot::with_origin(&windows, &config, Some(2));
+        let now = mru::now_secs();
+        snap.focused_at = vec![Some(now - 600), None, Some(now - 5)];
+        ctrl.pick_with_snapshot(snap);
+        let selected = |ctrl: &OverlayController| match &ctrl.phase {
+            Phase::Picking {
+                snap, selection, ..
+            } => snap.windows[*selection].id,
+            _ => panic!("expected Picking"),
+        };
+        let before = selected(&ctrl);
+        let rows = |cmds: &[Command]| {
+            cmds.iter()
+                .find_map(|c| match c {
+                    Command::ShowPicker { windows, .. } => Some(
+                        windows
+                            .iter()
+                            .map(|w| (w.title.clone(), w.focused_ago.clone()))
+                            .collect::<Vec<_>>(),
+                    ),
+                    _ => None,
+                })
+                .expect("picker redrawn")
+        };
 
-        drive_to_launching(&mut ctrl, &windows, &config);
-        ctrl.handle(vaults_locked_event(&["default"]), &windows, &config);
+        let cmds = ctrl.handle(Event::Timeline, &windows, &config);
+        let ago = |s: &str| Some(s.to_string());
+        assert_eq!(
+            rows(&cmds),
+            [
+                (windows[2].title.clone(), ago("current")),
+                (windows[0].title.clone(), ago("10 min ago")),
+                (windows[1].title.clone(), ago("earlier")),
+            ]
+        );
+        assert_eq!(selected(&ctrl), before);
+        let Phase::Picking { snap, .. } = &ctrl.phase else {
+            panic!("expected Picking");
+        };
+        assert_eq!(snap.origin_index, Some(0));
 
-        let cmds = ctrl.handle(Event::Escape, &windows, &config);
+        let cmds = ctrl.handle(Event::Timeline, &windows, &config);
+        let titles: Vec<_> = windows.iter().map(|w| (w.title.clone(), None)).collect();
+        assert_eq!(rows(&cmds), titles);
+        assert_eq!(selected(&ctrl), before);
+    }
+
+    #[test]
+    fn close_app_windows_without_confirmation() {
+        let mut ctrl = OverlayController::new();
+        let mut windows = test_windows();
+        let mut second = windows[1].clone();
+        second.id = core_types::WindowId::new();
+        second.title = "Firefox 2".into();
+        windows.push(second);
+        let config = WmConfig {
+            confirm_close: false,
+            ..test_config()
+        };
+        ctrl.handle(Event::Activate, &windows, &config);
+        ctrl.handle(Event::DwellTimeout, &windows, &config);
+        let Phase::Picking { snap, .. } = &ctrl.phase else {
+            panic!("expected Picking");
+        };
+        let firefox = snap
+            .windows
+            .iter()
+            .position(|w| w.app_id.as_str() == "firefox")
+            .unwrap();
+        ctrl.handle(Event::SelectionDown, &windows, &config);
+        while ctrl.current_selection() != firefox {
+            ctrl.handle(Event::SelectionDown, &windows, &config);
+        }
 
+        let cmds = ctrl.handle(Event::CloseAppWindows, &windows, &config);
         assert!(
-            cmds.iter()
-                .any(|c| matches!(c, Command::ClearPasswordBuffer)),
-            "escape during unlock must clear password buffer"
+            matches!(&cmds[0], Command::CloseWindows { windows } if windows.len() == 2
+                && windows.iter().all(|w| w.app_id.as_str() == "firefox")),
+            "got: {cmds:?}"
         );
-        assert!(cmds.iter().any(|c| matches!(c, Command::Hide)));
-        assert!(ctrl.is_idle());
+        assert!(matches!(ctrl.phase, Phase::Picking { .. }));
     }
 
     #[test]
-    fn vaults_locked_without_original_command_shows_error() {
-        let mut ctrl = OverlayController::new();
+    fn bang_stages_a_new_instance_that_enter_launches() {
         let windows = test_windows();
         let config = test_config();
+        let mut ctrl = OverlayController::new();
+        ctrl.pick_with_snapshot(Snapshot::with_origin(&windows, &config, Some(2)));
 
-        drive_to_launching(&mut ctrl, &windows, &config);
+        ctrl.handle(Event::Char('f'), &windows, &config);
+        let staged = |cmds: &[Command]| {
+            cmds.iter().find_map(|c| match c {
+                Command::ShowLaunchStaged { command, instances } if command == "firefox" => {
+                    Some(*instances)
+                }
+                _ => None,
+            })
+        };
+        let cmds = ctrl.handle(Event::Char('!'), &windows, &config);
+        assert_eq!(staged(&cmds), Some(1), "got: {cmds:?}");
+        let cmds = ctrl.handle(Event::Char('!'), &windows, &config);
+        assert_eq!(staged(&cmds), Some(2), "got: {cmds:?}");
 
-        // VaultsLocked but missing original_command — can't retry.
-        let cmds = ctrl.handle(
+        let cmds = ctrl.handle(Event::Confirm, &windows, &config);
+        let launches = cmds
+            .iter()
+            .filter(|c| matches!(c, Command::LaunchApp { command, .. } if command == "firefox"))
+            .count();
+        assert_eq!(launches, 2, "got: {cmds:?}");
+        assert!(
+            !cmds
+                .iter()
+                .any(|c| matches!(c, Command::ActivateWindow { .. }))
+        );
+
+        // The key is configurable, and capped.
+        let config = WmConfig {
+            launch_new_instance_key: "+".into(),
+            ..test_config()
+        };
+        let mut ctrl = OverlayController::new();
+        ctrl.pick_with_snapshot(Snapshot::with_origin(&windows, &config, Some(2)));
+        ctrl.handle(Event::Char('f'), &windows, &config);
+        assert!(ctrl.handle(Event::Char('!'), &windows, &config).is_empty());
+        for _ in 0..hints::MAX_NEW_INSTANCES {
+            ctrl.handle(Event::Char('+'), &windows, &config);
+        }
+        assert!(ctrl.handle(Event::Char('+'), &windows, &config).is_empty());
+        let cmds = ctrl.handle(Event::LaunchNew, &windows, &config);
+        let launches = cmds
+            .iter()
+            .filter(|c| matches!(c, Command::LaunchApp { .. }))
+            .count();
+        assert_eq!(launches, hints::MAX_NEW_INSTANCES);
+    }
+
+    #[test]
+    fn app_still_starting_is_not_launched_again() {
+        let windows = vec![test_windows()[0].clone()];
+        let config = test_config();
+        let launches = |cmds: &[Command]| {
+            cmds.iter()
+                .filter(|c| matches!(c, Command::LaunchApp { .. }))
+                .count()
+        };
+        let mut ctrl = OverlayController::new();
+        ctrl.handle(Event::Activate, &windows, &config);
+        ctrl.handle(Event::Char('e'), &windows, &config);
+        let cmds = ctrl.handle(Event::ModifierReleased, &windows, &config);
+        assert_eq!(launches(&cmds), 1);
+        ctrl.handle(
             Event::LaunchResult {
-                success: false,
-                error: Some("VaultsLocked".into()),
-                denial: Some(LaunchDenial::VaultsLocked {
-                    locked_profiles: vec![TrustProfileName::try_from("default").unwrap()],
-                }),
+                success: true,
+                error: None,
+                denial: None,
                 original_command: None,
                 original_tags: None,
                 original_launch_args: None,
@@ -2779,246 +7467,397 @@ mod tests {
             &config,
         );
 
-        // Must fall through to error display, not unlock flow.
+        // Edge has no window yet: `e` waits for it, `e!` launches another.
+        ctrl.handle(Event::Activate, &windows, &config);
+        let cmds = ctrl.handle(Event::Char('e'), &windows, &config);
         assert!(
             cmds.iter()
-                .any(|c| matches!(c, Command::ShowLaunchError { .. })),
-            "VaultsLocked without retry context must show error, got: {cmds:?}"
+                .any(|c| matches!(c, Command::ShowPalette { message }
+                if message.contains("is starting; type e! to launch another"))),
+            "got: {cmds:?}"
         );
+        let cmds = ctrl.handle(Event::ModifierReleased, &windows, &config);
+        assert_eq!(launches(&cmds), 0, "got: {cmds:?}");
+        assert!(cmds.iter().any(|c| matches!(c, Command::Hide)));
+        assert!(ctrl.is_idle());
+
+        ctrl.handle(Event::Activate, &windows, &config);
+        type_str(&mut ctrl, "e!", &windows, &config);
+        let cmds = ctrl.handle(Event::ModifierReleased, &windows, &config);
+        assert_eq!(launches(&cmds), 1, "got: {cmds:?}");
+
+        // Once the guard has passed, `e` launches again.
+        for (_, at) in &mut ctrl.recent_launches {
+            *at -= std::time::Duration::from_millis(config.relaunch_guard_ms.into());
+        }
+        ctrl.phase = Phase::Idle;
+        ctrl.handle(Event::Activate, &windows, &config);
+        ctrl.handle(Event::Char('e'), &windows, &config);
+        let cmds = ctrl.handle(Event::ModifierReleased, &windows, &config);
+        assert_eq!(launches(&cmds), 1, "got: {cmds:?}");
     }
 
     #[test]
-    fn launch_result_in_wrong_phase_is_noop() {
-        let mut ctrl = OverlayController::new();
-        let windows = test_windows();
+    fn failed_launch_can_be_retried_within_the_guard() {
+        let windows = vec![test_windows()[0].clone()];
         let config = test_config();
-
-        // Controller is Idle — LaunchResult should be ignored.
-        let cmds = ctrl.handle(
+        let mut ctrl = OverlayController::new();
+        ctrl.handle(Event::Activate, &windows, &config);
+        ctrl.handle(Event::Char('e'), &windows, &config);
+        ctrl.handle(Event::ModifierReleased, &windows, &config);
+        ctrl.handle(
             Event::LaunchResult {
                 success: false,
-                error: None,
-                denial: Some(LaunchDenial::VaultsLocked {
-                    locked_profiles: vec![TrustProfileName::try_from("default").unwrap()],
-                }),
-                original_command: Some("ghostty".into()),
-                original_tags: Some(vec![]),
-                original_launch_args: Some(vec![]),
+                error: Some("not found".into()),
+                denial: None,
+                original_command: None,
+                original_tags: None,
+                original_launch_args: None,
             },
             &windows,
             &config,
         );
+        assert!(ctrl.recent_launches.is_empty());
 
-        assert!(cmds.is_empty());
-        assert!(ctrl.is_idle());
+        ctrl.phase = Phase::Idle;
+        ctrl.handle(Event::Activate, &windows, &config);
+        ctrl.handle(Event::Char('e'), &windows, &config);
+        let cmds = ctrl.handle(Event::ModifierReleased, &windows, &config);
+        assert!(
+            cmds.iter().any(|c| matches!(c, Command::LaunchApp { .. })),
+            "got: {cmds:?}"
+        );
     }
 
     #[test]
-    fn profiles_activated_before_retry_launch_after_unlock() {
-        // Regression: after unlocking all vaults, the retry launch would fail
-        // with "denied-profile-not-active" because profiles were never activated.
-        // The controller must emit ActivateProfiles BEFORE LaunchApp.
-        let mut ctrl = OverlayController::new();
+    fn shift_enter_launches_the_selected_app() {
         let windows = test_windows();
         let config = test_config();
+        let mut ctrl = OverlayController::new();
+        ctrl.pick_with_snapshot(Snapshot::with_origin(&windows, &config, Some(2)));
+        let app = match &ctrl.phase {
+            Phase::Picking {
+                snap, selection, ..
+            } => snap.windows[*selection].app_id.to_string(),
+            _ => panic!("expected Picking"),
+        };
+        let launch = hints::binding_for_app(&app, &config.key_bindings)
+            .and_then(|(_, b)| b.launch.clone())
+            .unwrap();
 
-        drive_to_launching(&mut ctrl, &windows, &config);
-        ctrl.handle(vaults_locked_event(&["alpha", "beta"]), &windows, &config);
-
-        // Unlock both profiles.
-        ctrl.handle(
-            Event::AutoUnlockResult {
-                success: false,
-                profile: TrustProfileName::try_from("alpha").unwrap(),
-                needs_touch: false,
-            },
-            &windows,
-            &config,
-        );
-        ctrl.handle(
-            Event::UnlockResult {
-                success: true,
-                profile: TrustProfileName::try_from("alpha").unwrap(),
-            },
-            &windows,
-            &config,
-        );
-        ctrl.handle(
-            Event::AutoUnlockResult {
-                success: false,
-                profile: TrustProfileName::try_from("beta").unwrap(),
-                needs_touch: false,
-            },
-            &windows,
-            &config,
-        );
-        let cmds = ctrl.handle(
-            Event::UnlockResult {
-                success: true,
-                profile: TrustProfileName::try_from("beta").unwrap(),
-            },
-            &windows,
-            &config,
-        );
-
-        // Find positions to verify ordering.
-        let activate_pos = cmds
-            .iter()
-            .position(|c| matches!(c, Command::ActivateProfiles { .. }));
-        let launch_pos = cmds
-            .iter()
-            .position(|c| matches!(c, Command::LaunchApp { .. }));
-
+        let cmds = ctrl.handle(Event::LaunchNew, &windows, &config);
         assert!(
-            activate_pos.is_some(),
-            "ActivateProfiles must be emitted before launch retry, got: {cmds:?}"
-        );
-        assert!(
-            launch_pos.is_some(),
-            "LaunchApp must be emitted for retry, got: {cmds:?}"
+            cmds.iter()
+                .any(|c| matches!(c, Command::LaunchApp { command, .. } if *command == launch)),
+            "got: {cmds:?}"
         );
+        assert!(matches!(ctrl.phase, Phase::Launching));
+
+        // A hint whose key has no launch binding leaves the picker open.
+        let mut ctrl = OverlayController::new();
+        let config = WmConfig {
+            key_bindings: BTreeMap::new(),
+            ..test_config()
+        };
+        ctrl.pick_with_snapshot(Snapshot::with_origin(&windows, &config, Some(2)));
+        let cmds = ctrl.handle(Event::LaunchNew, &windows, &config);
         assert!(
-            activate_pos.unwrap() < launch_pos.unwrap(),
-            "ActivateProfiles must come before LaunchApp, got: {cmds:?}"
+            matches!(&cmds[..], [Command::ShowPalette { .. }]),
+            "got: {cmds:?}"
         );
-
-        // Verify the profiles list contains both unlocked profiles.
-        if let Some(Command::ActivateProfiles { profiles }) = cmds
-            .iter()
-            .find(|c| matches!(c, Command::ActivateProfiles { .. }))
-        {
-            assert_eq!(profiles.len(), 2);
-            assert!(profiles.iter().any(|p| p.as_ref() == "alpha"));
-            assert!(profiles.iter().any(|p| p.as_ref() == "beta"));
-        }
+        assert!(matches!(ctrl.phase, Phase::Picking { .. }));
     }
 
-    // === Zero-window launcher mode ===
-
     #[test]
-    fn launcher_zero_windows_emits_show_border_with_flag() {
+    fn single_match_activates_after_delay() {
+        let windows = test_windows();
+        let config = WmConfig {
+            auto_activate_single_match: true,
+            ..test_config()
+        };
         let mut ctrl = OverlayController::new();
-        let cmds = ctrl.handle(Event::ActivateLauncher, &[], &test_config());
+        ctrl.pick_with_snapshot(Snapshot::with_origin(&windows, &config, Some(2)));
+
+        ctrl.handle(Event::Char('f'), &windows, &config);
+        let deadline = ctrl.next_deadline().expect("single match scheduled");
+        assert!(deadline > Instant::now());
+        // Expiry of another deadline does not restart the delay.
+        ctrl.handle(Event::DwellTimeout, &windows, &config);
+        assert_eq!(ctrl.next_deadline(), Some(deadline));
+
+        ctrl.single_match_at = ctrl
+            .single_match_at
+            .map(|at| at - std::time::Duration::from_secs(1));
+        let cmds = ctrl.handle(Event::DwellTimeout, &windows, &config);
         assert!(
-            cmds.iter().any(|c| matches!(
-                c,
-                Command::ShowBorder {
-                    zero_window_launcher: true
-                }
-            )),
-            "launcher with zero windows must set zero_window_launcher flag, got: {cmds:?}"
+            cmds.iter()
+                .any(|c| matches!(c, Command::ActivateWindow { window, .. }
+                if window.app_id.as_str() == "firefox")),
+            "got: {cmds:?}"
         );
-        assert!(!ctrl.is_idle());
+        assert!(ctrl.is_idle());
+        assert!(ctrl.next_deadline().is_none());
     }
 
     #[test]
-    fn launcher_zero_windows_dwell_shows_picker() {
+    fn single_match_waits_for_a_key_when_off_or_moved() {
+        let windows = test_windows();
         let mut ctrl = OverlayController::new();
-        ctrl.handle(Event::ActivateLauncher, &[], &test_config());
-        let cmds = ctrl.handle(Event::DwellTimeout, &[], &test_config());
-        assert!(cmds.iter().any(|c| matches!(c, Command::ShowPicker { .. })));
-    }
+        ctrl.pick_with_snapshot(Snapshot::with_origin(&windows, &test_config(), Some(2)));
+        ctrl.handle(Event::Char('f'), &windows, &test_config());
+        assert!(ctrl.single_match_at.is_none());
 
-    #[test]
-    fn launcher_zero_windows_alt_release_is_noop_in_picking() {
-        let mut ctrl = OverlayController::new();
-        ctrl.handle(Event::ActivateLauncher, &[], &test_config());
-        ctrl.handle(Event::DwellTimeout, &[], &test_config());
-        let cmds = ctrl.handle(Event::ModifierReleased, &[], &test_config());
+        let config = WmConfig {
+            auto_activate_single_match: true,
+            ..test_config()
+        };
+        ctrl.handle(Event::Char('f'), &windows, &config);
+        assert!(ctrl.single_match_at.is_none(), "\"ff\" matches nothing");
+        ctrl.handle(Event::Backspace, &windows, &config);
+        assert!(ctrl.single_match_at.is_some());
+        ctrl.handle(Event::SelectionDown, &windows, &config);
         assert!(
-            !cmds.iter().any(|c| matches!(
-                c,
-                Command::Hide | Command::HideAndSync | Command::ActivateWindow { .. }
-            )),
-            "Alt release in zero-window launcher should be no-op, got: {cmds:?}"
+            ctrl.single_match_at.is_none(),
+            "selection moved off the match"
         );
-        assert!(!ctrl.is_idle());
     }
 
     #[test]
-    fn launcher_zero_windows_alt_release_in_armed_transitions_to_picking() {
+    fn inactivity_timeout_dismisses_overlay() {
         let mut ctrl = OverlayController::new();
-        ctrl.handle(Event::ActivateLauncher, &[], &test_config());
-        let cmds = ctrl.handle(Event::ModifierReleased, &[], &test_config());
+        let config = WmConfig {
+            overlay_timeout_ms: 1000,
+            ..test_config()
+        };
+        ctrl.handle(Event::ActivateLauncher, &[], &config);
+        ctrl.handle(Event::DwellTimeout, &[], &config);
+        assert!(matches!(ctrl.phase, Phase::Picking { .. }));
+
+        // Dwell expiry is not activity: the inactivity clock keeps running.
+        ctrl.last_activity -= std::time::Duration::from_secs(2);
+        assert!(ctrl.next_deadline().is_some_and(|d| d <= Instant::now()));
+        let cmds = ctrl.handle(Event::DwellTimeout, &[], &config);
         assert!(
-            cmds.iter().any(|c| matches!(c, Command::ShowPicker { .. })),
-            "Alt release from Armed with zero windows should show picker, got: {cmds:?}"
+            cmds.iter().any(|c| matches!(c, Command::Hide)),
+            "got: {cmds:?}"
         );
-        assert!(!ctrl.is_idle());
+        assert!(ctrl.is_idle());
+        assert!(ctrl.next_deadline().is_none());
     }
 
     #[test]
-    fn launcher_zero_windows_escape_dismisses() {
+    fn zero_overlay_timeout_never_dismisses() {
         let mut ctrl = OverlayController::new();
-        ctrl.handle(Event::ActivateLauncher, &[], &test_config());
-        ctrl.handle(Event::DwellTimeout, &[], &test_config());
-        let cmds = ctrl.handle(Event::Escape, &[], &test_config());
-        assert!(cmds.iter().any(|c| matches!(c, Command::Hide)));
-        assert!(ctrl.is_idle());
+        let config = WmConfig {
+            overlay_timeout_ms: 0,
+            ..test_config()
+        };
+        ctrl.handle(Event::ActivateLauncher, &[], &config);
+        ctrl.handle(Event::DwellTimeout, &[], &config);
+        ctrl.last_activity -= std::time::Duration::from_secs(3600);
+        assert!(ctrl.next_deadline().is_none());
+        assert!(!ctrl.is_idle());
     }
-
     #[test]
-    fn launcher_zero_windows_char_stages_launch() {
+    fn dead_input_is_cleared_after_delay() {
         let mut ctrl = OverlayController::new();
-        ctrl.handle(Event::ActivateLauncher, &[], &test_config());
-        ctrl.handle(Event::DwellTimeout, &[], &test_config());
-        let cmds = ctrl.handle(Event::Char('g'), &[], &test_config());
+        let config = WmConfig {
+            overlay_timeout_ms: 0,
+            ..test_config()
+        };
+        let windows = test_windows();
+        ctrl.handle(Event::ActivateLauncher, &windows, &config);
+        ctrl.handle(Event::DwellTimeout, &windows, &config);
+
+        // A window hint and a launch key are live; "z" matches nothing.
+        ctrl.handle(Event::Char('g'), &windows, &config);
+        assert!(ctrl.dead_input_at.is_none());
+        ctrl.handle(Event::Backspace, &windows, &config);
+        ctrl.handle(Event::Char('e'), &windows, &config);
+        assert!(ctrl.dead_input_at.is_none());
+        ctrl.handle(Event::Backspace, &windows, &config);
+        ctrl.handle(Event::Char('z'), &windows, &config);
+        assert!(ctrl.next_deadline().is_some());
+
+        ctrl.dead_input_at = Some(Instant::now() - std::time::Duration::from_secs(1));
+        let cmds = ctrl.handle(Event::DwellTimeout, &windows, &config);
         assert!(
-            cmds.iter()
-                .any(|c| matches!(c, Command::ShowLaunchStaged { .. })),
-            "typing configured key in zero-window launcher should stage launch, got: {cmds:?}"
+            matches!(&cmds[..], [Command::UpdatePicker { input, .. }] if input.is_empty()),
+            "got: {cmds:?}"
         );
+        assert!(matches!(&ctrl.phase, Phase::Picking { input, .. } if input.is_empty()));
+        assert!(ctrl.next_deadline().is_none());
+
+        // Erasing the dead key before the delay stops the clock.
+        ctrl.handle(Event::Char('z'), &windows, &config);
+        ctrl.handle(Event::Backspace, &windows, &config);
+        assert!(ctrl.next_deadline().is_none());
     }
 
     #[test]
-    fn launcher_zero_windows_alt_release_executes_staged_launch() {
+    fn zero_dead_input_clear_keeps_input() {
         let mut ctrl = OverlayController::new();
-        ctrl.handle(Event::ActivateLauncher, &[], &test_config());
-        ctrl.handle(Event::DwellTimeout, &[], &test_config());
-        ctrl.handle(Event::Char('g'), &[], &test_config());
-        let cmds = ctrl.handle(Event::ModifierReleased, &[], &test_config());
-        assert!(
-            cmds.iter()
-                .any(|c| matches!(c, Command::LaunchApp { command, .. } if command == "ghostty")),
-            "Alt release with staged launch should execute it, got: {cmds:?}"
-        );
+        let config = WmConfig {
+            overlay_timeout_ms: 0,
+            dead_input_clear_ms: 0,
+            ..test_config()
+        };
+        ctrl.handle(Event::ActivateLauncher, &[], &config);
+        ctrl.handle(Event::DwellTimeout, &[], &config);
+        ctrl.handle(Event::Char('z'), &[], &config);
+        assert!(ctrl.next_deadline().is_none());
     }
 
     #[test]
-    fn switcher_zero_windows_still_noop() {
+    fn workspace_row_switches_workspace_and_ignores_window_actions() {
+        let windows = test_windows();
+        let config = test_config();
+        let workspace = Workspace {
+            id: core_types::CompositorWorkspaceId::new(),
+            name: "Mail".into(),
+            is_active: false,
+        };
+        let mut snap = Snapshot::with_origin(&windows, &config, Some(2));
+        items::append_rows(
+            &mut snap.windows,
+            &mut snap.items,
+            Some(2),
+            &[SelectableItem::Workspace(workspace.clone())],
+            10,
+        );
+        snap.hints.insert(2, "w".into());
+        snap.overlay_windows
+            .insert(2, snap.overlay_windows[0].clone());
+        snap.focused_at.insert(2, None);
+        snap.origin_index = Some(3);
+        assert_eq!(snap.windows[3].app_id.as_str(), "microsoft-edge");
+        assert!(close_targets(&snap, 2, false).is_empty());
+        assert!(close_targets(&snap, 2, true).is_empty());
+
         let mut ctrl = OverlayController::new();
-        let cmds = ctrl.handle(Event::Activate, &[], &test_config());
-        assert!(cmds.is_empty(), "Alt+Tab with zero windows must be no-op");
+        ctrl.phase = Phase::Picking {
+            snap,
+            selection: 2,
+            input: String::new(),
+            pending_launch: None,
+        };
+        for event in [Event::CopyTitle, Event::Peek, Event::CloseWindow] {
+            assert!(ctrl.handle(event, &windows, &config).is_empty());
+        }
+        let cmds = ctrl.handle(Event::Confirm, &windows, &config);
+        assert!(matches!(
+            &cmds[..],
+            [
+                Command::Hide,
+                Command::ActivateWorkspace { workspace: ws },
+                Command::Publish(EventKind::WmOverlayDismissed, _),
+            ] if *ws == workspace
+        ));
         assert!(ctrl.is_idle());
     }
 
+    // === Multiplexer rows ===
+
+    fn tmux_config_and_targets() -> (WmConfig, MultiplexerTargets) {
+        let mut config = test_config();
+        config.key_bindings.get_mut("g").unwrap().multiplexer =
+            Some(core_types::MultiplexerKind::Tmux);
+        let targets = [(
+            core_types::MultiplexerKind::Tmux,
+            ["vim", "logs"]
+                .into_iter()
+                .zip(1..)
+                .map(|(name, index)| MultiplexerTarget {
+                    kind: core_types::MultiplexerKind::Tmux,
+                    session: "main".into(),
+                    index,
+                    name: name.into(),
+                    active: false,
+                })
+                .collect(),
+        )]
+        .into();
+        (config, targets)
+    }
+
+    /// The window and item of the selected row.
+    fn selected_row(ctrl: &OverlayController) -> (WindowId, SelectableItem) {
+        let (Phase::Armed {
+            snap, selection, ..
+        }
+        | Phase::Picking {
+            snap, selection, ..
+        }) = &ctrl.phase
+        else {
+            panic!("expected Armed or Picking");
+        };
+        (snap.windows[*selection].id, snap.items[*selection].clone())
+    }
+
     #[test]
-    fn launcher_with_windows_flag_is_false() {
+    fn multiplexer_rows_merge_into_the_open_overlay() {
+        let windows = test_windows();
+        let (config, targets) = tmux_config_and_targets();
+        let listed = || Event::MultiplexerListed(targets.clone());
         let mut ctrl = OverlayController::new();
-        let cmds = ctrl.handle(Event::ActivateLauncher, &test_windows(), &test_config());
-        assert!(
-            cmds.iter().any(|c| matches!(
-                c,
-                Command::ShowBorder {
-                    zero_window_launcher: false
-                }
-            )),
-            "launcher with windows should NOT set zero_window_launcher, got: {cmds:?}"
-        );
+
+        // A list arriving after the cycle ended is dropped.
+        assert!(ctrl.handle(listed(), &windows, &config).is_empty());
+        assert!(ctrl.multiplexer_targets.is_empty());
+
+        // Behind the armed border only the snapshot changes.
+        ctrl.handle(Event::Activate, &windows, &config);
+        let selected = selected_row(&ctrl);
+        assert!(ctrl.handle(listed(), &windows, &config).is_empty());
+        assert_eq!(selected_row(&ctrl), selected);
+        let cmds = ctrl.handle(Event::DwellTimeout, &windows, &config);
+        let Some(Command::ShowPicker { windows: rows, .. }) = cmds.first() else {
+            panic!("got: {cmds:?}");
+        };
+        assert_eq!(rows.len(), 5);
+        assert!(rows.iter().any(|r| r.title == "tmux main:1 vim"));
+
+        // The visible picker is redrawn with the rows, keeping the selection.
+        let mut ctrl = OverlayController::new();
+        ctrl.handle(Event::ActivateLauncher, &windows, &config);
+        ctrl.handle(Event::DwellTimeout, &windows, &config);
+        let selected = selected_row(&ctrl);
+        let cmds = ctrl.handle(listed(), &windows, &config);
+        let [
+            Command::ShowPicker {
+                windows: rows,
+                hints,
+            },
+            Command::UpdatePicker { input, selection },
+        ] = &cmds[..]
+        else {
+            panic!("got: {cmds:?}");
+        };
+        assert_eq!((rows.len(), hints.len()), (5, 5));
+        assert!(input.is_empty());
+        assert_eq!(selected_row(&ctrl), selected);
+        let Phase::Picking { snap, .. } = &ctrl.phase else {
+            panic!("expected Picking");
+        };
+        assert_eq!(snap.windows[*selection].id, selected.0);
     }
 
     #[test]
-    fn launcher_with_windows_alt_release_activates() {
-        let mut ctrl = OverlayController::new();
+    fn multiplexer_rows_wait_while_hints_are_typed() {
         let windows = test_windows();
-        ctrl.handle(Event::ActivateLauncher, &windows, &test_config());
-        ctrl.handle(Event::DwellTimeout, &windows, &test_config());
-        let cmds = ctrl.handle(Event::ModifierReleased, &windows, &test_config());
-        assert!(
-            cmds.iter()
-                .any(|c| matches!(c, Command::ActivateWindow { .. })),
-            "Alt release with windows should activate, got: {cmds:?}"
-        );
-        assert!(ctrl.is_idle());
+        let (config, targets) = tmux_config_and_targets();
+        let mut ctrl = OverlayController::new();
+        ctrl.handle(Event::ActivateLauncher, &windows, &config);
+        ctrl.handle(Event::DwellTimeout, &windows, &config);
+        ctrl.handle(Event::Char('f'), &windows, &config);
+
+        let cmds = ctrl.handle(Event::MultiplexerListed(targets), &windows, &config);
+        assert!(cmds.is_empty(), "got: {cmds:?}");
+        let Phase::Picking { snap, input, .. } = &ctrl.phase else {
+            panic!("expected Picking");
+        };
+        assert_eq!((snap.windows.len(), input.as_str()), (3, "f"));
+        // Later snapshots of the cycle list them.
+        assert!(!ctrl.multiplexer_targets.is_empty());
     }
 }
@@ -0,0 +1,357 @@
+//! Crash reports.
+//!
+//! [`install`] sets a panic hook that writes a JSON report for any panic in
+//! the daemon (event loop or overlay thread) to
+//! `$XDG_STATE_HOME/open-sesame/crashes/` and prints where it went, so a
+//! daemon that "just disappeared" leaves something to attach to an issue.
+//! A report holds the version, the panic message and location, a
+//! backtrace, the last [`LOG_TAIL_LINES`] log lines ([`TailLayer`]) and
+//! the overlay phase last recorded with [`set_state`]. The phase is
+//! recorded through [`OverlayController::redacted_state`], which leaves out
+//! window titles and typed text. The log tail leaves out trace events (key
+//! presses) and the [`PRIVATE_FIELDS`] of the rest: search queries,
+//! commands and their arguments, and typed input.
+//!
+//! [`OverlayController::redacted_state`]: crate::controller::OverlayController::redacted_state
+
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Log lines kept for a report.
+pub const LOG_TAIL_LINES: usize = 50;
+
+/// Reports kept in the crash directory; older ones are deleted at startup.
+const MAX_REPORTS: usize = 10;
+
+/// Log fields kept out of the log tail: they carry what the user typed or
+/// ran.
+pub const PRIVATE_FIELDS: &[&str] = &[
+    "query",
+    "command",
+    "args",
+    "launch_args",
+    "input",
+    "key",
+    "event",
+    "title",
+];
+
+/// Where to report crashes.
+const ISSUES_URL: &str = concat!(env!("CARGO_PKG_REPOSITORY"), "/issues");
+
+/// The newest log lines, oldest first.
+static LOG_TAIL: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+
+/// The overlay phase as last recorded.
+static STATE: Mutex<String> = Mutex::new(String::new());
+
+/// A crash report, written as JSON.
+#[derive(Debug, Serialize)]
+pub struct CrashReport {
+    pub daemon: &'static str,
+    pub version: &'static str,
+    /// Unix time in seconds.
+    pub at: u64,
+    pub thread: String,
+    pub message: String,
+    /// `file:line:column` of the panic, when known.
+    pub location: Option<String>,
+    /// The overlay phase when the panic happened, without titles.
+    pub state: String,
+    pub log_tail: Vec<String>,
+    pub backtrace: Vec<String>,
+}
+
+/// The crash report directory, next to the state database.
+#[must_use]
+pub fn report_dir() -> Option<PathBuf> {
+    let db = core_state::StateStore::default_path()?;
+    Some(db.parent()?.join("crashes"))
+}
+
+/// Install the panic hook. Call before the sandbox: it creates the report
+/// directory and deletes reports beyond the newest [`MAX_REPORTS`]. The
+/// previous hook still runs after the report is written.
+pub fn install() {
+    let Some(dir) = report_dir() else {
+        tracing::warn!("crash: no state directory, crash reports are off");
+        return;
+    };
+    if let Err(e) = create_dir(&dir) {
+        tracing::warn!(dir = %dir.display(), error = %e, "crash: report directory unavailable");
+        return;
+    }
+    prune(&dir, MAX_REPORTS);
+
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let report = CrashReport::capture(info);
+        match write_report(&dir, &report) {
+            Ok(path) => eprintln!(
+                "daemon-wm crashed. This is a bug in open-sesame.\n\n\
+                 A crash report was written to:\n  {}\n\
+                 It leaves out typed text, search queries and commands, but log lines can\n\
+                 still name windows. Please check it, then attach it to an issue at\n  {ISSUES_URL}\n",
+                path.display()
+            ),
+            Err(e) => eprintln!("daemon-wm crashed; the crash report could not be written: {e}"),
+        }
+        previous(info);
+    }));
+}
+
+/// Record the overlay phase for a report. Called by the event loop before
+/// each event.
+pub fn set_state(state: String) {
+    if let Ok(mut current) = STATE.lock() {
+        *current = state;
+    }
+}
+
+impl CrashReport {
+    fn capture(info: &std::panic::PanicHookInfo<'_>) -> Self {
+        let payload = info.payload();
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|s| (*s).to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "non-string panic payload".into());
+        // try_lock: the panic may have happened while a lock was held on
+        // this thread.
+        let state = STATE
+            .try_lock()
+            .map(|state| state.clone())
+            .unwrap_or_default();
+        let log_tail = LOG_TAIL
+            .try_lock()
+            .map(|tail| tail.iter().cloned().collect())
+            .unwrap_or_default();
+        Self {
+            daemon: "daemon-wm",
+            version: env!("CARGO_PKG_VERSION"),
+            at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs()),
+            thread: std::thread::current()
+                .name()
+                .unwrap_or("unnamed")
+                .to_string(),
+            message,
+            location: info
+                .location()
+                .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column())),
+            state,
+            log_tail,
+            backtrace: std::backtrace::Backtrace::force_capture()
+                .to_string()
+                .lines()
+                .map(str::to_string)
+                .collect(),
+        }
+    }
+}
+
+/// Write `report` to `dir` and return its path.
+fn write_report(dir: &Path, report: &CrashReport) -> std::io::Result<PathBuf> {
+    let path = dir.join(format!(
+        "{}-{}-{}.json",
+        report.daemon,
+        report.at,
+        std::process::id()
+    ));
+    let json = serde_json::to_vec_pretty(report).map_err(std::io::Error::other)?;
+    std::fs::write(&path, json)?;
+    Ok(path)
+}
+
+/// Delete all but the newest `keep` reports. Names start with the Unix
+/// time, so name order is age order.
+fn prune(dir: &Path, keep: usize) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    let mut reports: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    reports.sort();
+    let excess = reports.len().saturating_sub(keep);
+    for path in &reports[..excess] {
+        if let Err(e) = std::fs::remove_file(path) {
+            tracing::debug!(path = %path.display(), error = %e, "crash: old report not removed");
+        }
+    }
+}
+
+fn create_dir(dir: &Path) -> std::io::Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::DirBuilderExt;
+        std::fs::DirBuilder::new()
+            .mode(0o700)
+            .recursive(true)
+            .create(dir)
+    }
+    #[cfg(not(unix))]
+    {
+        std::fs::create_dir_all(dir)
+    }
+}
+
+/// Keep `line` for the next report.
+fn remember(line: String) {
+    let Ok(mut tail) = LOG_TAIL.lock() else {
+        return;
+    };
+    if tail.len() == LOG_TAIL_LINES {
+        tail.pop_front();
+    }
+    tail.push_back(line);
+}
+
+/// The log tail line for `event`: level, target, message and every field
+/// but the [`PRIVATE_FIELDS`]. `None` for trace events.
+fn tail_line(event: &tracing::Event<'_>) -> Option<String> {
+    struct Fields(String);
+
+    impl tracing::field::Visit for Fields {
+        fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+            use std::fmt::Write as _;
+            match field.name() {
+                "message" => {
+                    let _ = write!(self.0, " {value:?}");
+                }
+                name if PRIVATE_FIELDS.contains(&name) => {
+                    let _ = write!(self.0, " {name}=<redacted>");
+                }
+                name => {
+                    let _ = write!(self.0, " {name}={value:?}");
+                }
+            }
+        }
+    }
+
+    let meta = event.metadata();
+    if *meta.level() == tracing::Level::TRACE {
+        return None;
+    }
+    let mut fields = Fields(format!("{} {}:", meta.level(), meta.target()));
+    event.record(&mut fields);
+    Some(fields.0)
+}
+
+/// Log layer that keeps the last [`LOG_TAIL_LINES`] events for a report,
+/// without trace events or [`PRIVATE_FIELDS`]. Sits next to the layer
+/// that prints the log.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TailLayer;
+
+impl<S: tracing::Subscriber> tracing_subscriber::Layer<S> for TailLayer {
+    fn on_event(
+        &self,
+        event: &tracing::Event<'_>,
+        _ctx: tracing_subscriber::layer::Context<'_, S>,
+    ) {
+        if let Some(line) = tail_line(event) {
+            remember(line);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn log_tail_keeps_the_newest_lines() {
+        for i in 0..LOG_TAIL_LINES + 5 {
+            remember(format!("line {i}"));
+        }
+        let tail = LOG_TAIL.lock().unwrap();
+        assert_eq!(tail.len(), LOG_TAIL_LINES);
+        assert_eq!(tail.front().map(String::as_str), Some("line 5"));
+        assert_eq!(
+            tail.back().map(String::as_str),
+            Some(format!("line {}", LOG_TAIL_LINES + 4).as_str())
+        );
+    }
+
+    #[test]
+    fn tail_lines_leave_out_private_fields_and_trace_events() {
+        use std::sync::{Arc, Mutex};
+        use tracing_subscriber::layer::SubscriberExt;
+
+        struct Capture(Arc<Mutex<Vec<String>>>);
+
+        impl<S: tracing::Subscriber> tracing_subscriber::Layer<S> for Capture {
+            fn on_event(
+                &self,
+                event: &tracing::Event<'_>,
+                _ctx: tracing_subscriber::layer::Context<'_, S>,
+            ) {
+                self.0.lock().unwrap().extend(tail_line(event));
+            }
+        }
+
+        let lines = Arc::new(Mutex::new(Vec::new()));
+        let subscriber = tracing_subscriber::registry().with(Capture(Arc::clone(&lines)));
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!(command = %"secret-tool --label bank", pid = 42, "launching");
+            tracing::info!(query = ?Some("diary"), "activation requested");
+            tracing::trace!(event = ?'x', "overlay event received");
+        });
+
+        let lines = lines.lock().unwrap();
+        assert_eq!(lines.len(), 2, "{lines:?}");
+        assert!(lines[0].starts_with("INFO "), "{}", lines[0]);
+        assert!(lines[0].contains("launching") && lines[0].contains("pid=42"));
+        assert!(lines[0].contains("command=<redacted>"));
+        assert!(
+            !lines
+                .iter()
+                .any(|l| l.contains("secret-tool") || l.contains("diary"))
+        );
+    }
+
+    #[test]
+    fn reports_are_written_and_pruned_oldest_first() {
+        let dir = tempfile::tempdir().unwrap();
+        for at in [100, 300, 200] {
+            let report = CrashReport {
+                daemon: "daemon-wm",
+                version: "0.0.0",
+                at,
+                thread: "main".into(),
+                message: "boom".into(),
+                location: None,
+                state: "Idle".into(),
+                log_tail: Vec::new(),
+                backtrace: Vec::new(),
+            };
+            let path = write_report(dir.path(), &report).unwrap();
+            let json: serde_json::Value =
+                serde_json::from_slice(&std::fs::read(path).unwrap()).unwrap();
+            assert_eq!(json["message"], "boom");
+        }
+        std::fs::write(dir.path().join("notes.txt"), "kept").unwrap();
+
+        prune(dir.path(), 2);
+        let mut left: Vec<String> = std::fs::read_dir(dir.path())
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        left.sort();
+        let pid = std::process::id();
+        assert_eq!(
+            left,
+            [
+                format!("daemon-wm-200-{pid}.json"),
+                format!("daemon-wm-300-{pid}.json"),
+                "notes.txt".to_string(),
+            ]
+        );
+    }
+}
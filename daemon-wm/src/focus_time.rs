@@ -0,0 +1,79 @@
+//! Focus-time log for `sesame stats --focus-time` (`wm.focus_time`).
+//!
+//! Off by default. When on, every window switch made through sesame closes
+//! the interval opened by the previous one and appends it to the state
+//! store (`focus_intervals` table): start and end in Unix seconds and the
+//! app ID that had focus. A session lock or daemon exit closes the
+//! open interval without starting another, so time away is not counted.
+//! Switches made outside sesame (clicking, compositor shortcuts) are not
+//! seen; their time goes to the last app sesame switched to.
+//!
+//! The log is never trimmed or uploaded: it is the user's to keep or
+//! delete.
+
+use core_types::FocusInterval;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The app sesame last switched to and when, while recording.
+static OPEN: Mutex<Option<(String, u64)>> = Mutex::new(None);
+
+/// A switch to `app_id` went through. Records the time since the previous
+/// switch, then starts timing `app_id` if `enabled`.
+pub fn switched(app_id: &str, enabled: bool) {
+    let now = unix_now();
+    let Ok(mut open) = OPEN.lock() else {
+        return;
+    };
+    if let Some(interval) = close(open.take(), now) {
+        write(interval);
+    }
+    if enabled {
+        *open = Some((app_id.to_string(), now));
+    }
+}
+
+/// Stop timing without starting another interval (session lock, exit).
+pub fn stop() {
+    let Ok(mut open) = OPEN.lock() else {
+        return;
+    };
+    if let Some(interval) = close(open.take(), unix_now()) {
+        write(interval);
+    }
+}
+
+/// The finished interval for `open`, if any time passed.
+fn close(open: Option<(String, u64)>, now: u64) -> Option<FocusInterval> {
+    let (app_id, start) = open?;
+    (now > start).then_some(FocusInterval {
+        start,
+        end: now,
+        app_id,
+    })
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+fn write(interval: FocusInterval) {
+    crate::state::write(move |store| store.append_focus_interval(&interval));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zero_length_intervals_are_dropped() {
+        assert_eq!(close(None, 10), None);
+        assert_eq!(close(Some(("kitty".into(), 10)), 10), None);
+        assert_eq!(
+            close(Some(("kitty".into(), 10)), 70).map(|i| i.seconds()),
+            Some(60)
+        );
+    }
+}
@@ -2,6 +2,8 @@
 //!
 //! Assigns repeated-letter hints to windows based on a configurable key set.
 //! Supports numeric shorthand: "a2" matches "aa", "a3" matches "aaa".
+//! Keys may be any letter, not just Latin ones (`ф`, `λ`), and are compared
+//! after [`fold_key`].
 
 use core_config::WmKeyBinding;
 use std::collections::{BTreeMap, HashMap};
@@ -30,6 +32,25 @@ pub fn assign_hints(count: usize, hint_keys: &str) -> Vec<String> {
     hints
 }
 
+/// Case-fold one key: its lowercase form when that is a single character
+/// (`S` → `s`, `Ф` → `ф`, `Λ` → `λ`), else the key unchanged.
+#[must_use]
+pub fn fold_key(key: char) -> char {
+    let mut lower = key.to_lowercase();
+    match (lower.next(), lower.next()) {
+        (Some(c), None) => c,
+        _ => key,
+    }
+}
+
+/// Whether a typed character is overlay input (a hint key, or search or
+/// palette text): any printable character, in any script. Space is
+/// handled separately by the callers.
+#[must_use]
+pub fn is_input_char(c: char) -> bool {
+    !c.is_control() && !c.is_whitespace()
+}
+
 /// Result of matching user input against assigned hints.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum MatchResult {
@@ -83,9 +104,88 @@ pub fn match_input(input: &str, hints: &[String]) -> MatchResult {
     }
 }
 
-/// Normalize input: lowercase, expand numeric shorthand (a2 -> aa).
+/// With `repeat_key_cycles`, the window a repeated press of `key` selects:
+/// the one after `current` among the windows hinted only with `key` (`g`,
+/// `gg`, `ggg`), wrapping around, or the first of them when `current` is
+/// `None` or not one of them. `None` when no window has the key.
+#[must_use]
+pub fn next_in_key_group(key: char, hints: &[String], current: Option<usize>) -> Option<usize> {
+    let key = fold_key(key);
+    let mut group: Vec<usize> = (0..hints.len())
+        .filter(|&i| !hints[i].is_empty() && hints[i].chars().all(|c| c == key))
+        .collect();
+    group.sort_by_key(|&i| hints[i].len());
+    let next = current
+        .and_then(|cur| group.iter().position(|&i| i == cur))
+        .map_or(0, |pos| (pos + 1) % group.len());
+    group.get(next).copied()
+}
+
+/// Most new instances one hint asks for (`f!!!` launches three).
+pub const MAX_NEW_INSTANCES: usize = 9;
+
+/// The `launch_new_instance_key` setting as the character typed after a
+/// hint (`f!`) to launch a new instance of the key's app even though it
+/// has windows. `None` when empty, or a character that could be typed as
+/// part of a hint.
+#[must_use]
+pub fn new_instance_suffix(setting: &str) -> Option<char> {
+    let mut chars = setting.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if !c.is_alphanumeric() && !c.is_whitespace() && !c.is_control() => Some(c),
+        _ => None,
+    }
+}
+
+/// How many new instances typed `input` asks for: one per trailing
+/// `suffix`.
+#[must_use]
+pub fn new_instance_count(input: &str, suffix: Option<char>) -> usize {
+    suffix.map_or(0, |suffix| {
+        input.chars().rev().take_while(|&c| c == suffix).count()
+    })
+}
+
+/// The key whose app hint `input` names, with or without trailing
+/// `suffix`es: `f`, `ff`, `f2`, `f!` and `f!!` all name `f`.
+#[must_use]
+pub fn new_instance_key(input: &str, suffix: Option<char>) -> Option<char> {
+    let hint = normalize_input(suffix.map_or(input, |suffix| input.trim_end_matches(suffix)));
+    let key = hint.chars().next()?;
+    hint.chars().all(|c| c == key).then_some(key)
+}
+
+/// Whether typed `input` can never resolve: no hint starts with it and it
+/// is not a single key with a launch or command binding, nor a hint with
+/// the new-instance `suffix` whose key has a launch binding. Typing more
+/// only narrows the candidates, so the input is dead until it is erased.
+#[must_use]
+pub fn is_dead_end(
+    input: &str,
+    hints: &[String],
+    key_bindings: &BTreeMap<String, WmKeyBinding>,
+    suffix: Option<char>,
+) -> bool {
+    if input.is_empty() || match_input(input, hints) != MatchResult::NoMatch {
+        return false;
+    }
+    if new_instance_count(input, suffix) > 0 {
+        return new_instance_key(input, suffix)
+            .is_none_or(|key| launch_for_key(key, key_bindings).is_none());
+    }
+    let mut chars = input.chars();
+    match (chars.next(), chars.next()) {
+        (Some(key), None) => {
+            command_for_key(key, key_bindings).is_none()
+                && launch_for_key(key, key_bindings).is_none()
+        }
+        _ => true,
+    }
+}
+
+/// Normalize input: case-fold, expand numeric shorthand (a2 -> aa).
 fn normalize_input(input: &str) -> String {
-    let input = input.to_lowercase();
+    let input: String = input.chars().map(fold_key).collect();
 
     if input.len() >= 2 {
         let chars: Vec<char> = input.chars().collect();
@@ -120,42 +220,99 @@ fn normalize_input(input: &str) -> String {
 #[must_use]
 pub fn auto_key_for_app(app_id: &str) -> Option<char> {
     let segment = app_id.rsplit('.').next().unwrap_or(app_id);
-    segment
-        .chars()
-        .find(|c| c.is_ascii_alphabetic())
-        .map(|c| c.to_ascii_lowercase())
+    segment.chars().find(|c| c.is_alphabetic()).map(fold_key)
 }
 
 /// Look up the configured hint key for an app_id, falling back to auto-detection.
+///
+/// Keys of command-only bindings are never used as hints, so the automatic
+/// key skips to the next letter of the app name that is free, then to the
+/// first free letter of the alphabet.
 #[must_use]
 pub fn key_for_app(app_id: &str, key_bindings: &BTreeMap<String, WmKeyBinding>) -> Option<char> {
-    let app_lower = app_id.to_lowercase();
-    let last_segment = app_id.rsplit('.').next().map(|s| s.to_lowercase());
-
-    for (key, binding) in key_bindings {
-        for pattern in &binding.apps {
-            if pattern == app_id
-                || pattern.to_lowercase() == app_lower
-                || last_segment.as_deref() == Some(&pattern.to_lowercase())
-            {
-                return key.chars().next();
-            }
+    if let Some((key, _)) = binding_for_app(app_id, key_bindings) {
+        return key.chars().next();
+    }
+    let is_free = |c: &char| command_for_key(*c, key_bindings).is_none();
+    match auto_key_for_app(app_id) {
+        Some(key) if is_free(&key) => Some(key),
+        Some(_) => {
+            let segment = app_id.rsplit('.').next().unwrap_or(app_id);
+            segment
+                .chars()
+                .filter(|c| c.is_alphabetic())
+                .map(fold_key)
+                .chain('a'..='z')
+                .find(is_free)
         }
+        None => None,
     }
-    auto_key_for_app(app_id)
+}
+
+/// Whether an app pattern from the config names `app_id`.
+///
+/// Matches exactly, case-insensitively, or against the last reverse-DNS segment.
+#[must_use]
+pub fn app_matches(pattern: &str, app_id: &str) -> bool {
+    let last_segment = app_id.rsplit('.').next().unwrap_or(app_id);
+    let pattern_lower = pattern.to_lowercase();
+    pattern == app_id
+        || pattern_lower == app_id.to_lowercase()
+        || pattern_lower == last_segment.to_lowercase()
+}
+
+/// Find the key binding whose `apps` patterns match an app_id.
+///
+/// Patterns match as in [`app_matches`].
+#[must_use]
+pub fn binding_for_app<'a>(
+    app_id: &str,
+    key_bindings: &'a BTreeMap<String, WmKeyBinding>,
+) -> Option<(&'a str, &'a WmKeyBinding)> {
+    key_bindings.iter().find_map(|(key, binding)| {
+        (binding.command.is_none()
+            && binding
+                .apps
+                .iter()
+                .any(|pattern| app_matches(pattern, app_id)))
+        .then_some((key.as_str(), binding))
+    })
+}
+
+/// The `label` and `icon` of the key binding matching `app_id`, if set.
+#[must_use]
+pub fn label_and_icon(
+    app_id: &str,
+    key_bindings: &BTreeMap<String, WmKeyBinding>,
+) -> (Option<String>, Option<String>) {
+    binding_for_app(app_id, key_bindings).map_or((None, None), |(_, binding)| {
+        (binding.label.clone(), binding.icon.clone())
+    })
 }
 
 /// Look up the launch command for a key character.
 #[must_use]
 pub fn launch_for_key(key: char, key_bindings: &BTreeMap<String, WmKeyBinding>) -> Option<&str> {
-    let key_str = key.to_lowercase().to_string();
-    key_bindings.get(&key_str).and_then(|b| b.launch.as_deref())
+    let key_str = fold_key(key).to_string();
+    key_bindings
+        .get(&key_str)
+        .filter(|b| b.command.is_none())
+        .and_then(|b| b.launch.as_deref())
+}
+
+/// Look up the shell command of a command-only binding for a key character.
+#[must_use]
+pub fn command_for_key(key: char, key_bindings: &BTreeMap<String, WmKeyBinding>) -> Option<&str> {
+    let key_str = fold_key(key).to_string();
+    key_bindings
+        .get(&key_str)
+        .and_then(|b| b.command.as_deref())
 }
 
 /// Look up the launch profile tags for a key character.
 #[must_use]
 pub fn tags_for_key(key: char, key_bindings: &BTreeMap<String, WmKeyBinding>) -> Vec<String> {
-    let key_str = key.to_lowercase().to_string();
+    let key_str = fold_key(key).to_string();
     key_bindings
         .get(&key_str)
         .map(|b| b.tags.clone())
@@ -168,7 +325,7 @@ pub fn launch_args_for_key(
     key: char,
     key_bindings: &BTreeMap<String, WmKeyBinding>,
 ) -> Vec<String> {
-    let key_str = key.to_lowercase().to_string();
+    let key_str = fold_key(key).to_string();
     key_bindings
         .get(&key_str)
         .map(|b| b.launch_args.clone())
@@ -188,6 +345,20 @@ pub fn launch_args_for_key(
 pub fn assign_app_hints(
     app_ids: &[&str],
     key_bindings: &BTreeMap<String, WmKeyBinding>,
+) -> Vec<(String, usize)> {
+    let ranks: Vec<usize> = (0..app_ids.len()).collect();
+    assign_app_hints_ranked(app_ids, &ranks, key_bindings)
+}
+
+/// Like [`assign_app_hints`], but repetitions within a key group follow
+/// `ranks` (parallel to `app_ids`, lower first; ties keep window order)
+/// instead of window order. With ranks from `window_order::sync`, `ff`
+/// keeps naming the same window while the MRU display order changes.
+#[must_use]
+pub fn assign_app_hints_ranked(
+    app_ids: &[&str],
+    ranks: &[usize],
+    key_bindings: &BTreeMap<String, WmKeyBinding>,
 ) -> Vec<(String, usize)> {
     if app_ids.is_empty() {
         return Vec::new();
@@ -203,7 +374,8 @@ pub fn assign_app_hints(
 
     let mut result: Vec<(String, usize)> = Vec::with_capacity(app_ids.len());
 
-    for (base, indices) in &by_key {
+    for (base, indices) in &mut by_key {
+        indices.sort_by_key(|&idx| ranks.get(idx).copied().unwrap_or(usize::MAX));
         for (count, &idx) in indices.iter().enumerate() {
             let hint: String = std::iter::repeat_n(*base, count + 1).collect();
             result.push((hint, idx));
@@ -215,6 +387,40 @@ pub fn assign_app_hints(
     result
 }
 
+/// Where a window sits among the windows of its app.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct AppPosition {
+    /// 1-based position within the app's windows.
+    pub index: usize,
+    /// Number of windows the app has.
+    pub count: usize,
+}
+
+/// Group windows by app ID and number each app's windows.
+///
+/// Numbering follows `ranks` (parallel to `app_ids`, lower first; ties keep
+/// window order), like [`assign_app_hints_ranked`], so for an app with its
+/// own hint key the window numbered 2 is the one hinted `gg`.
+#[must_use]
+pub fn app_positions(app_ids: &[&str], ranks: &[usize]) -> Vec<AppPosition> {
+    let mut by_app: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (i, app_id) in app_ids.iter().enumerate() {
+        by_app.entry(app_id).or_default().push(i);
+    }
+
+    let mut positions = vec![AppPosition::default(); app_ids.len()];
+    for indices in by_app.values_mut() {
+        indices.sort_by_key(|&idx| ranks.get(idx).copied().unwrap_or(usize::MAX));
+        for (n, &idx) in indices.iter().enumerate() {
+            positions[idx] = AppPosition {
+                index: n + 1,
+                count: indices.len(),
+            };
+        }
+    }
+    positions
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -254,6 +460,72 @@ mod tests {
         assert_eq!(match_input("z", &hints), MatchResult::NoMatch);
     }
 
+    #[test]
+    fn dead_end_needs_no_hint_and_no_binding() {
+        let hints = vec!["a".into(), "s".into()];
+        let bindings: BTreeMap<String, WmKeyBinding> = [(
+            "f".to_string(),
+            WmKeyBinding {
+                apps: vec!["firefox".into()],
+                launch: Some("firefox".into()),
+                ..Default::default()
+            },
+        )]
+        .into_iter()
+        .collect();
+        let dead = |input| is_dead_end(input, &hints, &bindings, Some('!'));
+        assert!(!dead(""));
+        assert!(!dead("a"));
+        // A launch key is a staged launch, not a dead end.
+        assert!(!dead("F"));
+        assert!(dead("z"));
+        assert!(dead("ff"));
+        assert!(dead("az"));
+        // `!` asks for a new instance, which needs a launch binding.
+        assert!(!dead("f!"));
+        assert!(!dead("f2!!"));
+        assert!(dead("a!"));
+        assert!(dead("fa!"));
+        assert!(is_dead_end("f!", &hints, &bindings, None));
+    }
+
+    #[test]
+    fn new_instance_key_names_the_hint_key() {
+        let bang = Some('!');
+        assert_eq!(new_instance_key("f!", bang), Some('f'));
+        assert_eq!(new_instance_key("FF!", bang), Some('f'));
+        assert_eq!(new_instance_key("f3!!", bang), Some('f'));
+        assert_eq!(new_instance_key("gg", bang), Some('g'));
+        assert_eq!(new_instance_key("f+", Some('+')), Some('f'));
+        assert_eq!(new_instance_key("fg!", bang), None);
+        assert_eq!(new_instance_key("!", bang), None);
+        assert_eq!(new_instance_key("f!", None), None);
+    }
+
+    #[test]
+    fn new_instances_count_trailing_suffixes() {
+        assert_eq!(new_instance_count("f", Some('!')), 0);
+        assert_eq!(new_instance_count("f!!!", Some('!')), 3);
+        assert_eq!(new_instance_count("f!!", None), 0);
+        assert_eq!(new_instance_suffix("!"), Some('!'));
+        assert_eq!(new_instance_suffix("+"), Some('+'));
+        for setting in ["", "a", "7", " ", "!!"] {
+            assert_eq!(new_instance_suffix(setting), None, "{setting:?}");
+        }
+    }
+
+    #[test]
+    fn key_group_cycles_in_hint_order_and_wraps() {
+        let hints: Vec<String> = ["gg", "f", "g", "ggg"].map(String::from).to_vec();
+        assert_eq!(next_in_key_group('g', &hints, None), Some(2));
+        assert_eq!(next_in_key_group('g', &hints, Some(2)), Some(0));
+        assert_eq!(next_in_key_group('G', &hints, Some(0)), Some(3));
+        assert_eq!(next_in_key_group('g', &hints, Some(3)), Some(2));
+        // A selection outside the group restarts it.
+        assert_eq!(next_in_key_group('g', &hints, Some(1)), Some(2));
+        assert_eq!(next_in_key_group('x', &hints, None), None);
+    }
+
     #[test]
     fn match_numeric_shorthand() {
         let hints = vec!["a".into(), "aa".into(), "aaa".into()];
@@ -267,6 +539,41 @@ mod tests {
         assert_eq!(match_input("S", &hints), MatchResult::Exact(1));
     }
 
+    #[test]
+    fn non_latin_keys_fold_case_and_expand() {
+        let hints: Vec<String> = ["ф", "фф", "λ"].map(String::from).to_vec();
+        assert_eq!(match_input("Ф", &hints), MatchResult::Partial(vec![0, 1]));
+        assert_eq!(match_input("ФФ", &hints), MatchResult::Exact(1));
+        assert_eq!(match_input("ф2", &hints), MatchResult::Exact(1));
+        assert_eq!(match_input("Λ", &hints), MatchResult::Exact(2));
+        assert_eq!(next_in_key_group('Ф', &hints, Some(0)), Some(1));
+        assert_eq!(auto_key_for_app("org.example.Телеграм"), Some('т'));
+
+        let mut bindings = BTreeMap::new();
+        bindings.insert(
+            "т".to_string(),
+            WmKeyBinding {
+                apps: vec!["telegram".into()],
+                launch: Some("telegram-desktop".into()),
+                ..Default::default()
+            },
+        );
+        assert_eq!(launch_for_key('Т', &bindings), Some("telegram-desktop"));
+        assert_eq!(key_for_app("telegram", &bindings), Some('т'));
+    }
+
+    #[test]
+    fn input_chars_accept_any_script() {
+        for c in ['a', '7', '/', 'ф', 'λ', 'ß'] {
+            assert!(is_input_char(c), "{c:?}");
+        }
+        for c in [' ', '\t', '\u{7f}', '\u{8}'] {
+            assert!(!is_input_char(c), "{c:?}");
+        }
+        // 'İ' lowercases to two characters, so it folds to itself.
+        assert_eq!(fold_key('İ'), 'İ');
+    }
+
     #[test]
     fn auto_key_reverse_dns() {
         assert_eq!(auto_key_for_app("com.mitchellh.ghostty"), Some('g'));
@@ -293,6 +600,16 @@ mod tests {
         assert_eq!(result.len(), 3);
     }
 
+    #[test]
+    fn ranked_hints_follow_rank_not_position() {
+        // MRU order puts the newer firefox window first; ranks keep "f" on
+        // the window that was seen first.
+        let apps = vec!["firefox", "ghostty", "firefox"];
+        let result = assign_app_hints_ranked(&apps, &[7, 0, 3], &empty_bindings());
+        let hint_strs: Vec<&str> = result.iter().map(|(h, _)| h.as_str()).collect();
+        assert_eq!(hint_strs, vec!["ff", "g", "f"]);
+    }
+
     #[test]
     fn launch_for_key_case_insensitive() {
         let mut bindings = BTreeMap::new();
@@ -301,8 +618,7 @@ mod tests {
             WmKeyBinding {
                 apps: vec!["app-f".into()],
                 launch: Some("app-f".into()),
-                tags: Vec::new(),
-                launch_args: Vec::new(),
+                ..Default::default()
             },
         );
         assert_eq!(launch_for_key('f', &bindings), Some("app-f"));
@@ -310,6 +626,24 @@ mod tests {
         assert_eq!(launch_for_key('z', &bindings), None);
     }
 
+    #[test]
+    fn command_keys_are_never_hints() {
+        let mut bindings = BTreeMap::new();
+        bindings.insert(
+            "p".to_string(),
+            WmKeyBinding {
+                command: Some("grim".into()),
+                ..Default::default()
+            },
+        );
+        assert_eq!(command_for_key('P', &bindings), Some("grim"));
+        assert_eq!(launch_for_key('p', &bindings), None);
+        // pwvucontrol skips to the next letter of its name, `pp` to the alphabet.
+        assert_eq!(key_for_app("pwvucontrol", &bindings), Some('w'));
+        assert_eq!(key_for_app("pp", &bindings), Some('a'));
+        assert_eq!(key_for_app("firefox", &bindings), Some('f'));
+    }
+
     #[test]
     fn assign_app_hints_uses_first_letter() {
         let apps = vec!["vivaldi", "com.mitchellh.ghostty", "microsoft-edge"];
@@ -320,6 +654,14 @@ mod tests {
         assert!(hint_strs.contains(&"m"), "microsoft-edge should get 'm'");
     }
 
+    #[test]
+    fn app_positions_number_windows_per_app() {
+        let apps = ["firefox", "ghostty", "firefox", "firefox"];
+        let positions = app_positions(&apps, &[2, 0, 0, 1]);
+        let pairs: Vec<(usize, usize)> = positions.iter().map(|p| (p.index, p.count)).collect();
+        assert_eq!(pairs, vec![(3, 3), (1, 1), (1, 3), (2, 3)]);
+    }
+
     #[test]
     fn tags_for_key_found() {
         let mut bindings = BTreeMap::new();
@@ -329,7 +671,7 @@ mod tests {
                 apps: vec!["ghostty".into()],
                 launch: Some("ghostty".into()),
                 tags: vec!["dev-rust".into(), "ai-tools".into()],
-                launch_args: Vec::new(),
+                ..Default::default()
             },
         );
         let tags = tags_for_key('g', &bindings);
@@ -351,8 +693,7 @@ mod tests {
             WmKeyBinding {
                 apps: vec!["firefox".into()],
                 launch: Some("firefox".into()),
-                tags: Vec::new(),
-                launch_args: Vec::new(),
+                ..Default::default()
             },
         );
         let tags = tags_for_key('f', &bindings);
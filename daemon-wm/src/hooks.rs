@@ -0,0 +1,141 @@
+//! Activation and cancel hooks (`[wm.hooks]`).
+//!
+//! A quick Alt+Tab switches windows without ever drawing the overlay, so a
+//! sound or a user command on activation gives feedback where there is
+//! none on screen, which also helps users who cannot see the overlay.
+//! Hooks run on the same outcomes the usage log records: a window
+//! activation (with per-app overrides) or a dismissal. App launches have
+//! their own feedback and run none.
+//!
+//! daemon-wm's seccomp profile has no `execve`, so hook commands are sent
+//! to daemon-launcher as `LaunchShellCommand`s without waiting for the
+//! result. daemon-launcher runs only commands that appear in `[wm.hooks]`.
+
+use core_config::{HookAction, WmHooks};
+use core_ipc::BusClient;
+use core_types::{EventKind, SecurityLevel, TrustProfileName, UsageKind};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// When a hook last ran, for `min_interval_ms`.
+static LAST_RUN: Mutex<Option<Instant>> = Mutex::new(None);
+
+/// The hook for an outcome: the first `apps` entry matching the activated
+/// app, else `activate`; `cancel` for a dismissal.
+#[must_use]
+pub fn action_for<'a>(hooks: &'a WmHooks, kind: UsageKind, app: Option<&str>) -> &'a HookAction {
+    static NONE: HookAction = HookAction {
+        command: None,
+        sound: None,
+    };
+    match kind {
+        UsageKind::Activate => app
+            .and_then(|app| {
+                hooks
+                    .apps
+                    .iter()
+                    .find(|(pattern, _)| crate::hints::app_matches(pattern, app))
+            })
+            .map_or(&hooks.activate, |(_, action)| action),
+        UsageKind::Cancel => &hooks.cancel,
+        UsageKind::Launch => &NONE,
+    }
+}
+
+/// Whether a hook may run at `now`, given when one last ran.
+#[must_use]
+pub fn due(last: Option<Instant>, now: Instant, min_interval_ms: u32) -> bool {
+    last.is_none_or(|last| {
+        now.duration_since(last) >= Duration::from_millis(u64::from(min_interval_ms))
+    })
+}
+
+/// Run the hook for an outcome, unless the last one ran too recently.
+pub async fn run(
+    client: &BusClient,
+    hooks: &WmHooks,
+    kind: UsageKind,
+    app: Option<&str>,
+    profile: Option<TrustProfileName>,
+) {
+    let commands = action_for(hooks, kind, app).command_lines();
+    if commands.is_empty() {
+        return;
+    }
+    let now = Instant::now();
+    {
+        let mut last = LAST_RUN.lock().unwrap_or_else(|e| e.into_inner());
+        if !due(*last, now, hooks.min_interval_ms) {
+            tracing::debug!(?kind, "hook skipped, ran too recently");
+            return;
+        }
+        *last = Some(now);
+    }
+    for command in commands {
+        tracing::debug!(?kind, %command, "running hook");
+        let event = EventKind::LaunchShellCommand {
+            command,
+            profile: profile.clone(),
+        };
+        if let Err(e) = client.publish(event, SecurityLevel::Internal).await {
+            tracing::warn!(error = %e, "hook not sent to daemon-launcher");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hooks() -> WmHooks {
+        let action = |command: &str| HookAction {
+            command: Some(command.into()),
+            sound: None,
+        };
+        WmHooks {
+            activate: action("activate"),
+            cancel: action("cancel"),
+            apps: [("firefox".to_string(), action("firefox"))].into(),
+            ..WmHooks::default()
+        }
+    }
+
+    #[test]
+    fn app_hooks_replace_the_activate_hook() {
+        let hooks = hooks();
+        let command = |kind, app| action_for(&hooks, kind, app).command.clone();
+        assert_eq!(
+            command(UsageKind::Activate, Some("org.mozilla.firefox")),
+            Some("firefox".into())
+        );
+        assert_eq!(
+            command(UsageKind::Activate, Some("kitty")),
+            Some("activate".into())
+        );
+        assert_eq!(command(UsageKind::Cancel, None), Some("cancel".into()));
+        assert_eq!(command(UsageKind::Launch, Some("firefox")), None);
+    }
+
+    #[test]
+    fn hooks_are_rate_limited() {
+        let start = Instant::now();
+        assert!(due(None, start, 300));
+        assert!(!due(Some(start), start + Duration::from_millis(299), 300));
+        assert!(due(Some(start), start + Duration::from_millis(300), 300));
+        assert!(due(Some(start), start, 0));
+    }
+
+    #[test]
+    fn sounds_play_before_commands_and_are_quoted() {
+        let action = HookAction {
+            command: Some("notify-send switched".into()),
+            sound: Some("/home/me/it's.oga".into()),
+        };
+        assert_eq!(
+            action.command_lines(),
+            ["paplay '/home/me/it'\\''s.oga'", "notify-send switched"]
+        );
+        let all: Vec<_> = hooks().command_lines().collect();
+        assert_eq!(all, ["activate", "cancel", "firefox"]);
+    }
+}
@@ -1,6 +1,11 @@
-//! IPC keyboard event deduplication and keysym mapping.
+//! IPC keyboard event deduplication, re-activation coalescing and keysym
+//! mapping.
 
+use crate::actions::{self, keysym};
 use crate::controller::Event;
+use core_config::InvocationMode;
+use core_types::EventKind;
+use std::time::Duration;
 
 /// Tracks recently processed key events to deduplicate overlay and IPC sources.
 ///
@@ -46,23 +51,81 @@ impl KeyDeduplicator {
     }
 }
 
+/// Minimum gap between applied re-activation steps: one frame at 60 Hz.
+pub const CYCLE_STEP_INTERVAL: Duration = Duration::from_millis(16);
+
+/// Folds duplicate overlay re-activations (Alt+Tab sent twice for one
+/// press).
+///
+/// A step is applied only if its sender sent it at least
+/// [`CYCLE_STEP_INTERVAL`] after the last applied one. Only the sender's
+/// clock counts: a deliberate Tab, or live autorepeat, that queues behind
+/// a slow activation is applied late rather than lost, at the cadence it
+/// was pressed.
+#[derive(Debug, Default)]
+pub struct CycleDebouncer {
+    /// Sender wall-clock milliseconds of the last applied step.
+    last_sent: Option<u64>,
+}
+
+impl CycleDebouncer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A fresh activation, sent at `sent_ms`, opened the overlay.
+    pub fn start(&mut self, sent_ms: u64) {
+        self.last_sent = Some(sent_ms);
+    }
+
+    /// Returns true if a re-activation sent at `sent_ms` should step the
+    /// selection, false if it duplicates the last step.
+    pub fn accept(&mut self, sent_ms: u64) -> bool {
+        let interval = CYCLE_STEP_INTERVAL.as_millis() as u64;
+        let spaced = self
+            .last_sent
+            .is_none_or(|last_sent| sent_ms.saturating_sub(last_sent) >= interval);
+        if spaced {
+            self.last_sent = Some(sent_ms);
+        }
+        spaced
+    }
+}
+
+/// Whether `payload` would only step the selection of an overlay open in
+/// `invocation` mode. A query, an output, or a launcher request while the
+/// switcher is open does more, and is never coalesced.
+pub fn is_cycle_step(payload: &EventKind, invocation: InvocationMode) -> bool {
+    let launcher = match payload {
+        EventKind::WmActivateOverlay | EventKind::WmActivateOverlayBackward => false,
+        EventKind::WmActivateOverlayLauncher | EventKind::WmActivateOverlayLauncherBackward => true,
+        EventKind::WmActivateOverlayIntent {
+            launcher,
+            query: None,
+            output: None,
+            ..
+        } => *launcher,
+        _ => return false,
+    };
+    !launcher || invocation == InvocationMode::Launcher
+}
+
 /// Map an IPC keyboard event (XKB keysym) to a controller Event.
 ///
 /// Uses X11 keysym values which are identical to GDK key constants.
-/// Returns None for keys that the overlay does not handle (space, modifiers, etc.).
-pub fn map_ipc_key_to_event(keyval: u32, _modifiers: u32, unicode: Option<char>) -> Option<Event> {
-    const ESCAPE: u32 = 0xFF1B;
-    const RETURN: u32 = 0xFF0D;
-    const KP_ENTER: u32 = 0xFF8D;
-    const TAB: u32 = 0xFF09;
-    const DOWN: u32 = 0xFF54;
-    const UP: u32 = 0xFF52;
-    const BACKSPACE: u32 = 0xFF08;
-    const SPACE: u32 = 0x0020;
+/// Returns None for keys that the overlay does not handle (Tab, modifiers,
+/// etc.). `modifiers` is a GDK-style mask; only Control and Shift are
+/// consulted, to pick the [`actions::ACTIONS`] entry the key triggers.
+pub fn map_ipc_key_to_event(keyval: u32, modifiers: u32, unicode: Option<char>) -> Option<Event> {
+    const SHIFT_MASK: u32 = 1;
+    const CONTROL_MASK: u32 = 1 << 2;
+    let ctrl = modifiers & CONTROL_MASK != 0;
+    let shift = modifiers & SHIFT_MASK != 0;
+    if let Some(action) = actions::lookup(keyval, ctrl, shift) {
+        return Some(action.event.clone());
+    }
     match keyval {
-        ESCAPE => Some(Event::Escape),
-        RETURN | KP_ENTER => Some(Event::Confirm),
-        TAB => {
+        keysym::TAB => {
             // Tab-based cycling is handled entirely by IPC re-activation
             // (WmActivateOverlay / WmActivateOverlayBackward). The compositor
             // intercepts Alt+Tab and spawns a new sesame process. Suppress
@@ -70,12 +133,9 @@ pub fn map_ipc_key_to_event(keyval: u32, _modifiers: u32, unicode: Option<char>)
             // available for non-Alt navigation.
             None
         }
-        DOWN => Some(Event::SelectionDown),
-        UP => Some(Event::SelectionUp),
-        BACKSPACE => Some(Event::Backspace),
-        SPACE => Some(Event::Char(' ')),
+        keysym::SPACE => Some(Event::Char(' ')),
         _ => unicode
-            .filter(|ch| ch.is_ascii_graphic() || *ch == ' ')
+            .filter(|ch| crate::hints::is_input_char(*ch) || *ch == ' ')
             .map(Event::Char),
     }
 }
@@ -83,6 +143,7 @@ pub fn map_ipc_key_to_event(keyval: u32, _modifiers: u32, unicode: Option<char>)
 #[cfg(test)]
 mod tests {
     use super::*;
+    use core_types::{WindowPlacement, WindowToggle};
 
     // ============================================================================
     // map_ipc_key_to_event
@@ -173,12 +234,215 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn map_non_latin_letter_accepted() {
+        // Cyrillic_ef on a Russian layout.
+        assert!(matches!(
+            map_ipc_key_to_event(0x06C6, 0, Some('ф')),
+            Some(Event::Char('ф'))
+        ));
+    }
+
+    #[test]
+    fn map_ctrl_enter_keeps_open() {
+        assert!(matches!(
+            map_ipc_key_to_event(0xFF0D, 1 << 2, None),
+            Some(Event::KeepOpen)
+        ));
+        assert!(matches!(
+            map_ipc_key_to_event(0xFF0D, 0, None),
+            Some(Event::Confirm)
+        ));
+    }
+
+    #[test]
+    fn map_shift_enter_launches_new() {
+        assert!(matches!(
+            map_ipc_key_to_event(0xFF0D, 1, None),
+            Some(Event::LaunchNew)
+        ));
+        assert!(matches!(
+            map_ipc_key_to_event(0xFF8D, 1 | (1 << 2), None),
+            Some(Event::KeepOpen)
+        ));
+    }
+
+    #[test]
+    fn map_ctrl_c_copies() {
+        assert!(matches!(
+            map_ipc_key_to_event(0x0063, 1 << 2, None),
+            Some(Event::CopyTitle)
+        ));
+        assert!(matches!(
+            map_ipc_key_to_event(0x0043, (1 << 2) | 1, None),
+            Some(Event::CopyAppId)
+        ));
+        assert!(matches!(
+            map_ipc_key_to_event(0x0063, 0, Some('c')),
+            Some(Event::Char('c'))
+        ));
+    }
+
+    #[test]
+    fn map_ctrl_w_closes() {
+        assert!(matches!(
+            map_ipc_key_to_event(0x0077, 1 << 2, None),
+            Some(Event::CloseWindow)
+        ));
+        assert!(matches!(
+            map_ipc_key_to_event(0x0057, (1 << 2) | 1, None),
+            Some(Event::CloseAppWindows)
+        ));
+        assert!(matches!(
+            map_ipc_key_to_event(0x0077, 0, Some('w')),
+            Some(Event::Char('w'))
+        ));
+    }
+
+    #[test]
+    fn map_ctrl_p_peeks() {
+        assert!(matches!(
+            map_ipc_key_to_event(0x0070, 1 << 2, None),
+            Some(Event::Peek)
+        ));
+        assert!(matches!(
+            map_ipc_key_to_event(0x0070, 0, Some('p')),
+            Some(Event::Char('p'))
+        ));
+    }
+
+    #[test]
+    fn map_ctrl_h_l_f_place() {
+        assert!(matches!(
+            map_ipc_key_to_event(0x0068, 1 << 2, None),
+            Some(Event::Place(WindowPlacement::LeftHalf))
+        ));
+        assert!(matches!(
+            map_ipc_key_to_event(0x006C, 1 << 2, None),
+            Some(Event::Place(WindowPlacement::RightHalf))
+        ));
+        assert!(matches!(
+            map_ipc_key_to_event(0x0066, 1 << 2, None),
+            Some(Event::Place(WindowPlacement::Maximized))
+        ));
+        assert!(matches!(
+            map_ipc_key_to_event(0x0066, 0, Some('f')),
+            Some(Event::Char('f'))
+        ));
+    }
+
+    #[test]
+    fn map_ctrl_s_t_toggle() {
+        assert!(matches!(
+            map_ipc_key_to_event(0x0073, 1 << 2, None),
+            Some(Event::Toggle(WindowToggle::Sticky))
+        ));
+        assert!(matches!(
+            map_ipc_key_to_event(0x0074, 1 << 2, None),
+            Some(Event::Toggle(WindowToggle::KeepAbove))
+        ));
+        assert!(matches!(
+            map_ipc_key_to_event(0x0074, 0, Some('t')),
+            Some(Event::Char('t'))
+        ));
+    }
+
+    #[test]
+    fn map_ctrl_m_mark() {
+        assert!(matches!(
+            map_ipc_key_to_event(0x006D, 1 << 2, None),
+            Some(Event::Mark)
+        ));
+        assert!(matches!(
+            map_ipc_key_to_event(0x006D, 0, Some('m')),
+            Some(Event::Char('m'))
+        ));
+    }
+
+    #[test]
+    fn map_menu_key_row_menu() {
+        assert!(matches!(
+            map_ipc_key_to_event(0xFF67, 0, None),
+            Some(Event::RowMenu)
+        ));
+    }
+
+    #[test]
+    fn map_ctrl_o_move_to_output() {
+        assert!(matches!(
+            map_ipc_key_to_event(0x006F, 1 << 2, None),
+            Some(Event::MoveToOutput)
+        ));
+        assert!(matches!(
+            map_ipc_key_to_event(0x006F, 0, Some('o')),
+            Some(Event::Char('o'))
+        ));
+    }
+
+    #[test]
+    fn map_ctrl_r_timeline() {
+        assert!(matches!(
+            map_ipc_key_to_event(0x0072, 1 << 2, None),
+            Some(Event::Timeline)
+        ));
+        assert!(matches!(
+            map_ipc_key_to_event(0x0072, 0, Some('r')),
+            Some(Event::Char('r'))
+        ));
+    }
+
     #[test]
     fn map_modifier_key_ignored() {
         // Alt_L keysym — no unicode, should be None.
         assert!(map_ipc_key_to_event(0xFFE9, 0, None).is_none());
     }
 
+    // ============================================================================
+    // CycleDebouncer
+    // ============================================================================
+
+    #[test]
+    fn queued_burst_steps_at_its_send_cadence() {
+        let mut debounce = CycleDebouncer::new();
+        debounce.start(1_000);
+        // Autorepeat sent 33ms apart and processed all at once behind a slow
+        // activation: every step still applies.
+        let applied = (1..=12).filter(|i| debounce.accept(1_000 + i * 33)).count();
+        assert_eq!(applied, 12);
+    }
+
+    #[test]
+    fn duplicate_sends_fold() {
+        let mut debounce = CycleDebouncer::new();
+        debounce.start(1_000);
+        assert!(!debounce.accept(1_005));
+        assert!(debounce.accept(1_040));
+        assert!(!debounce.accept(1_050));
+    }
+
+    #[test]
+    fn only_plain_reactivations_are_cycle_steps() {
+        use InvocationMode::{Launcher, Switcher};
+        assert!(is_cycle_step(&EventKind::WmActivateOverlay, Switcher));
+        assert!(is_cycle_step(
+            &EventKind::WmActivateOverlayLauncher,
+            Launcher
+        ));
+        // Upgrades the switcher to a launcher.
+        assert!(!is_cycle_step(
+            &EventKind::WmActivateOverlayLauncher,
+            Switcher
+        ));
+        let with_query = EventKind::WmActivateOverlayIntent {
+            launcher: true,
+            backward: false,
+            query: Some("g".into()),
+            output: None,
+        };
+        assert!(!is_cycle_step(&with_query, Launcher));
+        assert!(!is_cycle_step(&EventKind::WmListWindows, Switcher));
+    }
+
     // ============================================================================
     // KeyDeduplicator
     // ============================================================================
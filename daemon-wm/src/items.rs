@@ -0,0 +1,178 @@
+//! Selectable items: what the picker's rows stand for.
+//!
+//! Windows come from the compositor; every other source of rows
+//! (multiplexer targets, workspaces, outputs) lists its
+//! entries as [`SelectableItem`]s. A row that is not a window is listed
+//! with a stand-in [`Window`] carrying its title, so hint assignment,
+//! search and rendering run one pipeline for every row. [`label`] names
+//! the kind of item in its row, and the controller dispatches activation
+//! on the item.
+//!
+//! Multiplexer rows are the exception: they sit beneath their terminal's
+//! window and are listed with a clone of it (see `multiplexer`).
+
+use crate::{outputs, workspaces};
+use core_types::{
+    AppId, CompositorWorkspaceId, Geometry, MonitorId, ProfileId, SelectableItem, Window, WindowId,
+};
+
+/// Append a row for each item of `extra` that has a stand-in, at most
+/// `max_rows`, keeping the origin (when it is the last row) at the bottom.
+/// `items` is parallel to `windows`.
+pub fn append_rows(
+    windows: &mut Vec<Window>,
+    items: &mut Vec<SelectableItem>,
+    origin_index: Option<usize>,
+    extra: &[SelectableItem],
+    max_rows: usize,
+) {
+    let at = match origin_index {
+        Some(origin) if origin + 1 == windows.len() => origin,
+        _ => windows.len(),
+    };
+    let (rows, row_items): (Vec<_>, Vec<_>) = extra
+        .iter()
+        .filter_map(|item| Some((row_window(item)?, item.clone())))
+        .take(max_rows)
+        .unzip();
+    windows.splice(at..at, rows);
+    items.splice(at..at, row_items);
+}
+
+/// The stand-in window `item` is listed with, or `None` for windows and
+/// multiplexer targets, which are listed with a real window.
+#[must_use]
+pub fn row_window(item: &SelectableItem) -> Option<Window> {
+    match item {
+        SelectableItem::Window | SelectableItem::Multiplexer(_) => None,
+        SelectableItem::Workspace(ws) => Some(workspaces::row_window(ws)),
+        SelectableItem::Output(output) => Some(outputs::row_window(output)),
+    }
+}
+
+/// The label of `item`'s row, when the kind of item names it rather than
+/// the app.
+#[must_use]
+pub fn label(item: &SelectableItem) -> Option<&'static str> {
+    match item {
+        SelectableItem::Workspace(_) => Some("Workspace"),
+        SelectableItem::Output(_) => Some("Output"),
+        SelectableItem::Window | SelectableItem::Multiplexer(_) => None,
+    }
+}
+
+/// Where an on-window badge (`hint_placement = "windows"`) goes for a
+/// row: its window's rectangle, when the row is a window that is not
+/// minimized, whose size the compositor reports, and that is shown with
+/// the `focused` window (any window when unknown): on its output, and on
+/// its workspace unless sticky.
+#[must_use]
+pub fn on_screen(
+    window: &Window,
+    item: &SelectableItem,
+    focused: Option<&Window>,
+) -> Option<Geometry> {
+    let shown = |focused: &Window| {
+        window.monitor_id == focused.monitor_id
+            && (window.is_sticky || window.workspace_id == focused.workspace_id)
+    };
+    let placed = *item == SelectableItem::Window
+        && !window.is_minimized
+        && window.geometry.width > 0
+        && window.geometry.height > 0
+        && focused.is_none_or(shown);
+    placed.then_some(window.geometry)
+}
+
+/// A window listing a row that is not a window, with `app_id` and `title`.
+pub(crate) fn stand_in(app_id: &str, title: String) -> Window {
+    Window {
+        id: WindowId::new(),
+        app_id: AppId::new(app_id),
+        title,
+        workspace_id: CompositorWorkspaceId::new(),
+        monitor_id: MonitorId::new(),
+        geometry: Geometry {
+            x: 0,
+            y: 0,
+            width: 0,
+            height: 0,
+        },
+        is_focused: false,
+        is_minimized: false,
+        is_fullscreen: false,
+        is_maximized: false,
+        profile_id: ProfileId::new(),
+        parent: None,
+        is_sticky: false,
+        is_keep_above: false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core_types::Workspace;
+
+    fn workspace(name: &str) -> SelectableItem {
+        SelectableItem::Workspace(Workspace {
+            id: CompositorWorkspaceId::new(),
+            name: name.into(),
+            is_active: false,
+        })
+    }
+
+    #[test]
+    fn rows_go_before_a_trailing_origin_and_respect_the_budget() {
+        let mut windows = vec![stand_in("firefox", "Inbox".into())];
+        windows.push(stand_in("com.mitchellh.ghostty", "~".into()));
+        let mut items = vec![SelectableItem::Window; 2];
+        let extra = [workspace("1"), workspace("Mail"), workspace("3")];
+        append_rows(&mut windows, &mut items, Some(1), &extra, 2);
+
+        let titles: Vec<&str> = windows.iter().map(|w| w.title.as_str()).collect();
+        assert_eq!(titles[1..3], ["1", "Mail"]);
+        assert_eq!(windows[3].app_id.as_str(), "com.mitchellh.ghostty");
+        assert_eq!(items[2], extra[1]);
+        assert_eq!(items[3], SelectableItem::Window);
+
+        let mut windows = vec![stand_in("firefox", "Inbox".into())];
+        let mut items = vec![SelectableItem::Window];
+        append_rows(&mut windows, &mut items, None, &extra, 10);
+        assert_eq!(windows.len(), 4);
+        assert_eq!(windows[3].app_id.as_str(), workspaces::WORKSPACE_APP_ID);
+    }
+
+    #[test]
+    fn only_sized_windows_shown_with_the_focused_one_are_on_screen() {
+        let mut window = stand_in("firefox", "Inbox".into());
+        assert_eq!(on_screen(&window, &SelectableItem::Window, None), None);
+
+        window.geometry = Geometry {
+            x: 40,
+            y: 30,
+            width: 800,
+            height: 600,
+        };
+        let mut focused = window.clone();
+        assert_eq!(
+            on_screen(&window, &SelectableItem::Window, Some(&focused)),
+            Some(window.geometry)
+        );
+        focused.workspace_id = CompositorWorkspaceId::new();
+        assert_eq!(
+            on_screen(&window, &SelectableItem::Window, Some(&focused)),
+            None
+        );
+        window.is_sticky = true;
+        assert!(on_screen(&window, &SelectableItem::Window, Some(&focused)).is_some());
+        focused.monitor_id = MonitorId::new();
+        assert_eq!(
+            on_screen(&window, &SelectableItem::Window, Some(&focused)),
+            None
+        );
+        assert_eq!(on_screen(&window, &workspace("1"), None), None);
+        window.is_minimized = true;
+        assert_eq!(on_screen(&window, &SelectableItem::Window, None), None);
+    }
+}
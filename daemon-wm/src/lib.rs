@@ -1,15 +1,45 @@
+pub mod actions;
+pub mod app_names;
+#[cfg(all(target_os = "linux", feature = "dbus"))]
+pub mod applet;
 pub mod commands;
 mod commands_unlock;
 pub mod controller;
+pub mod crash;
+pub mod focus_time;
 pub mod hints;
+pub mod hooks;
 pub mod ipc_keys;
+pub mod items;
+pub mod marks;
 pub mod mru;
+pub mod multiplexer;
+pub mod onboarding;
+pub mod outputs;
+pub mod palette;
+pub mod power;
+#[cfg(feature = "thumbnails")]
+pub mod preview;
+pub mod release_timing;
 #[cfg(feature = "wayland")]
 pub mod render;
+pub mod row_menu;
 #[cfg(target_os = "linux")]
 pub mod sandbox;
+pub mod search;
+#[cfg(feature = "wayland")]
+pub mod session;
+pub mod state;
+pub mod stats;
 #[cfg(feature = "wayland")]
 pub mod surface;
+#[cfg(feature = "test-util")]
+pub mod testing;
+pub mod timeline;
+pub mod timings;
+pub mod toast;
+pub mod window_order;
+pub mod workspaces;
 
 // Re-export surface types at the old path for main.rs compatibility.
 #[cfg(feature = "wayland")]
@@ -5,19 +5,23 @@
 //! encrypted IPC bus. Overlay lifecycle is driven by [`OverlayController`] —
 //! a single owner of all state, timing, and decisions.
 //!
-//! Landlock: Wayland socket, fontconfig, cache dir (MRU state).
+//! Landlock: Wayland socket, fontconfig, font cache, state dir (state
+//! database, crash reports).
 //! No network access beyond local IPC.
 
 use anyhow::Context;
 use clap::Parser;
 use core_crypto::SecureVec;
-use core_ipc::{BusClient, Message};
+use core_ipc::{BusClient, Message, MessageContext};
 use core_types::{DaemonId, EventKind, SecurityLevel, Window};
 use daemon_wm::controller::{Event, OverlayController};
-use daemon_wm::ipc_keys::{KeyDeduplicator, map_ipc_key_to_event};
+use daemon_wm::ipc_keys::{CycleDebouncer, KeyDeduplicator, is_cycle_step, map_ipc_key_to_event};
 use daemon_wm::mru;
 use daemon_wm::overlay::{self, OverlayCmd, OverlayEvent};
+use daemon_wm::power;
 use daemon_wm::render::OverlayTheme;
+use daemon_wm::session::controller_event;
+use daemon_wm::timings::{ActivationTimer, Stage};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
@@ -28,6 +32,16 @@ const OVERLAY_RESPAWN_WINDOW_SECS: u64 = 120;
 /// Delay (seconds) before respawning overlay thread after death.
 const OVERLAY_RESPAWN_DELAY_SECS: u64 = 2;
 
+/// How long shutdown waits for the overlay thread to destroy its surface.
+const OVERLAY_QUIT_TIMEOUT: std::time::Duration = std::time::Duration::from_millis(500);
+/// How long shutdown waits for queued state store writes.
+const STATE_FLUSH_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(1);
+/// Exit code after SIGINT (128 + signal number, as shells report it).
+const EXIT_SIGINT: i32 = 130;
+/// Exit code after SIGTERM. Listed in `SuccessExitStatus=` so systemd does
+/// not treat a requested stop as a failure.
+const EXIT_SIGTERM: i32 = 143;
+
 /// Window manager daemon.
 #[derive(Parser, Debug)]
 #[command(name = "daemon-wm", about = "Window manager overlay daemon")]
@@ -39,10 +53,14 @@ struct Cli {
 
 #[tokio::main(flavor = "current_thread")]
 async fn main() -> anyhow::Result<()> {
+    let process_start = std::time::Instant::now();
     let cli = Cli::parse();
 
     init_logging(&cli.log_format)?;
 
+    // Crash reports: the hook's directory is created BEFORE sandbox.
+    daemon_wm::crash::install();
+
     tracing::info!("daemon-wm starting");
 
     // -- Process hardening --
@@ -60,6 +78,7 @@ async fn main() -> anyhow::Result<()> {
 
     // Load config.
     let config = core_config::load_config(None).context("failed to load config")?;
+    core_types::set_redact_titles(config.global.logging.redact_titles_in_logs);
     let wm_config = config
         .profiles
         .values()
@@ -115,10 +134,29 @@ async fn main() -> anyhow::Result<()> {
     // Probe memfd_secret and initialize secure memory BEFORE sandbox.
     core_types::init_secure_memory();
 
+    // Open the state store BEFORE sandbox: creates its directory and imports
+    // the cache files of older versions.
+    let state_available = daemon_wm::state::init();
+
+    // Read the icon theme setting BEFORE sandbox: GTK's settings are outside
+    // the directories it allows.
+    #[cfg(feature = "wayland")]
+    daemon_wm::render::icons::init();
+
     // Sandbox (Linux) — applied AFTER keypair read + connect, BEFORE IPC traffic.
     #[cfg(target_os = "linux")]
     daemon_wm::sandbox::apply_sandbox();
 
+    if state_available {
+        daemon_wm::state::start_writer();
+    }
+
+    // Index desktop entries off the event loop, so the first activation
+    // does not read every `.desktop` file.
+    let _ = std::thread::Builder::new()
+        .name("app-names".into())
+        .spawn(daemon_wm::app_names::warm);
+
     // Announce startup.
     client
         .publish(
@@ -219,6 +257,8 @@ async fn main() -> anyhow::Result<()> {
                     .expect("failed to spawn window list poll thread");
                 tokio::spawn(async move {
                     while let Some(win_list) = win_rx.recv().await {
+                        #[cfg(feature = "dbus")]
+                        daemon_wm::applet::set_window_count(win_list.len());
                         *win_ref.lock().await = win_list;
                         win_gen_ref.fetch_add(1, std::sync::atomic::Ordering::Release);
                     }
@@ -257,7 +297,11 @@ async fn main() -> anyhow::Result<()> {
                                         is_focused: true,
                                         is_minimized: false,
                                         is_fullscreen: false,
+                                        is_maximized: false,
                                         profile_id: core_types::ProfileId::new(),
+                                        parent: None,
+                                        is_sticky: false,
+                                        is_keep_above: false,
                                     });
                                 } else {
                                     for w in guard.iter_mut() {
@@ -285,6 +329,8 @@ async fn main() -> anyhow::Result<()> {
         match b.list_windows().await {
             Ok(win_list) => {
                 mru::seed_if_empty(&win_list);
+                #[cfg(feature = "dbus")]
+                daemon_wm::applet::set_window_count(win_list.len());
                 *windows.lock().await = win_list;
             }
             Err(e) => tracing::warn!(error = %e, "initial window enumeration failed"),
@@ -293,7 +339,43 @@ async fn main() -> anyhow::Result<()> {
 
     // -- Overlay lifecycle --
     let mut controller = OverlayController::new();
+    controller.set_learned_quick_switch(daemon_wm::release_timing::load().learned_threshold_ms());
+    if daemon_wm::onboarding::pending(!config_paths.is_empty()) {
+        tracing::info!("first run: the walkthrough is shown on the next activation");
+        let pages = daemon_wm::onboarding::pages(
+            &*wm_config.lock().await,
+            &core_config::config_dir().join("config.toml"),
+        );
+        controller.start_walkthrough(daemon_wm::onboarding::Walkthrough::new(pages));
+    }
+    #[cfg(target_os = "linux")]
+    if let Some(ref b) = backend {
+        let caps = b.capabilities();
+        tracing::info!(
+            backend = caps.backend,
+            can_activate = caps.can_activate,
+            can_close = caps.can_close,
+            can_maximize = caps.can_maximize,
+            can_tile = caps.can_tile,
+            can_stick = caps.can_stick,
+            can_keep_above = caps.can_keep_above,
+            has_workspace_info = caps.has_workspace_info,
+            can_switch_workspace = caps.can_switch_workspace,
+            can_move_to_output = caps.can_move_to_output,
+            has_screencopy = caps.has_screencopy,
+            protocols = ?caps.protocols,
+            "compositor capabilities"
+        );
+        if !caps.can_activate {
+            tracing::warn!("compositor cannot activate windows; overlay will only offer launches");
+        }
+        controller.set_can_activate(caps.can_activate);
+        controller.set_placements(caps.can_maximize, caps.can_tile);
+        controller.set_toggles(caps.can_stick, caps.can_keep_above);
+        controller.set_can_move_to_output(caps.can_move_to_output);
+    }
     let mut dedup = KeyDeduplicator::new();
+    let mut cycle_debounce = CycleDebouncer::new();
     let mut ipc_keyboard_confirmed = false;
 
     let (mut overlay_cmd_tx, mut overlay_event_rx) = {
@@ -301,10 +383,134 @@ async fn main() -> anyhow::Result<()> {
         let theme = OverlayTheme::from_config(&cfg);
         let show_app_id = cfg.show_app_id;
         let show_title = cfg.show_title;
+        let keyboard_mode = cfg.keyboard_mode;
+        let all_outputs = cfg.show_on_all_outputs;
         drop(cfg);
-        overlay::spawn_overlay(theme, show_app_id, show_title)
+        overlay::spawn_overlay(theme, show_app_id, show_title, keyboard_mode, all_outputs)
     };
 
+    // Non-fatal startup problems, shown over the overlay on first use.
+    let mut startup_problems = theme_problems(&*wm_config.lock().await);
+    #[cfg(target_os = "linux")]
+    match backend.as_ref().map(|b| b.capabilities().can_activate) {
+        None => startup_problems
+            .push("Compositor window protocols missing: windows cannot be switched".into()),
+        Some(false) => startup_problems
+            .push("Compositor cannot activate windows: only launching apps is available".into()),
+        Some(true) => {}
+    }
+    if !state_available {
+        startup_problems
+            .push("Recently used order is off: the state database could not be opened".into());
+    }
+    for message in startup_problems {
+        let _ = overlay_cmd_tx.send(OverlayCmd::Toast { message });
+    }
+
+    // Power profile (Linux): `power_saving = "auto"` throttles the overlay
+    // while power-profiles-daemon reports the power-saver profile.
+    let (power_tx, mut power_rx) = tokio::sync::mpsc::channel::<String>(4);
+    #[cfg(all(target_os = "linux", feature = "dbus"))]
+    tokio::spawn(platform_linux::dbus::power_profile_monitor(power_tx));
+    #[cfg(not(all(target_os = "linux", feature = "dbus")))]
+    drop(power_tx);
+    let mut power_profile: Option<String> = None;
+    let mut power_saving = power::throttled(wm_config.lock().await.power_saving, None);
+    if power_saving {
+        let _ = overlay_cmd_tx.send(OverlayCmd::SetPowerSaving(true));
+    }
+
+    // Session lock (Linux): dismiss the overlay when the session locks so it
+    // never sits over the lock screen holding the keyboard grab.
+    let (lock_tx, mut lock_rx) = tokio::sync::mpsc::channel::<Event>(4);
+    #[cfg(all(target_os = "linux", feature = "dbus"))]
+    {
+        use platform_linux::dbus::SessionLockChange;
+        let (signal_tx, mut signal_rx) = tokio::sync::mpsc::channel(4);
+        tokio::spawn(platform_linux::dbus::session_lock_monitor(signal_tx));
+        tokio::spawn(async move {
+            while let Some(change) = signal_rx.recv().await {
+                let event = match change {
+                    SessionLockChange::LockRequested => Event::SessionLockRequested,
+                    SessionLockChange::Locked(locked) => Event::SessionLock(locked),
+                };
+                if lock_tx.send(event).await.is_err() {
+                    break;
+                }
+            }
+        });
+    }
+    #[cfg(not(all(target_os = "linux", feature = "dbus")))]
+    drop(lock_tx);
+
+    // Title and app ID changes (Linux): kept in the cached window list and,
+    // while the overlay is open, shown in its rows.
+    let (change_tx, mut change_rx) = tokio::sync::mpsc::channel::<Event>(16);
+    #[cfg(target_os = "linux")]
+    if let Some(ref b) = backend {
+        use tokio::sync::broadcast::error::RecvError;
+        let mut changes = b.subscribe_changes();
+        tokio::spawn(async move {
+            loop {
+                let change = match changes.recv().await {
+                    Ok(change) => change,
+                    Err(RecvError::Lagged(missed)) => {
+                        tracing::debug!(missed, "window changes lagged; the next poll catches up");
+                        continue;
+                    }
+                    Err(RecvError::Closed) => break,
+                };
+                let event = Event::WindowChanged {
+                    window_id: change.id,
+                    app_id: change.app_id.to_string(),
+                    title: change.title,
+                };
+                if change_tx.send(event).await.is_err() {
+                    break;
+                }
+            }
+        });
+    }
+    #[cfg(not(target_os = "linux"))]
+    drop(change_tx);
+
+    // Multiplexer rows (tmux/zellij), listed after each fresh activation
+    // and merged into the overlay when they arrive. Lists are tagged with
+    // their activation so a late one never lands in a later cycle.
+    let (mux_tx, mut mux_rx) =
+        tokio::sync::mpsc::channel::<(u64, daemon_wm::multiplexer::MultiplexerTargets)>(1);
+    let mut mux_cycle: u64 = 0;
+
+    // Panel applet interface (Linux): `ShowOverlay` calls become overlay
+    // intents, sent as if from another daemon so the bus arm handles them.
+    let (applet_tx, mut applet_rx) = tokio::sync::mpsc::channel::<EventKind>(4);
+    let applet_ctx = MessageContext::new(DaemonId::new());
+    #[cfg(all(target_os = "linux", feature = "dbus"))]
+    if wm_config.lock().await.applet_interface {
+        use platform_linux::applet::{AppletRequest, applet_server};
+        let (request_tx, mut request_rx) = tokio::sync::mpsc::channel(4);
+        tokio::spawn(applet_server(
+            daemon_wm::applet::subscribe(),
+            daemon_wm::applet::subscribe_switches(),
+            request_tx,
+        ));
+        tokio::spawn(async move {
+            while let Some(AppletRequest::ShowOverlay { launcher }) = request_rx.recv().await {
+                let intent = EventKind::WmActivateOverlayIntent {
+                    launcher,
+                    backward: false,
+                    query: None,
+                    output: None,
+                };
+                if applet_tx.send(intent).await.is_err() {
+                    break;
+                }
+            }
+        });
+    }
+    #[cfg(not(all(target_os = "linux", feature = "dbus")))]
+    drop(applet_tx);
+
     // Password buffer for inline vault unlock. Pre-allocated and mlock'd
     // to prevent password bytes from being swapped to disk or included in
     // core dumps. Lives in the tokio executor context — never crosses thread
@@ -321,7 +527,12 @@ async fn main() -> anyhow::Result<()> {
     #[cfg(target_os = "linux")]
     platform_linux::systemd::notify_ready();
 
-    tracing::info!("daemon-wm ready, entering event loop");
+    let daemon_startup = process_start.elapsed();
+    let mut activation_timer: Option<ActivationTimer> = None;
+    tracing::info!(
+        startup_ms = daemon_startup.as_millis() as u64,
+        "daemon-wm ready, entering event loop"
+    );
 
     // Watchdog timer: half the WatchdogSec=30 interval.
     let mut watchdog = tokio::time::interval(std::time::Duration::from_secs(15));
@@ -330,7 +541,9 @@ async fn main() -> anyhow::Result<()> {
     // Event loop — thin orchestrator
     // -----------------------------------------------------------------------
     let mut watchdog_count: u64 = 0;
-    loop {
+    let exit_code = loop {
+        daemon_wm::crash::set_state(controller.redacted_state());
+
         // Compute the controller's next deadline for dwell/activation timeout.
         let deadline = controller.next_deadline();
 
@@ -362,11 +575,22 @@ async fn main() -> anyhow::Result<()> {
                     let theme = OverlayTheme::from_config(&cfg);
                     let show_app_id = cfg.show_app_id;
                     let show_title = cfg.show_title;
+                    let keyboard_mode = cfg.keyboard_mode;
+                    let all_outputs = cfg.show_on_all_outputs;
                     drop(cfg);
-                    overlay::spawn_overlay(theme, show_app_id, show_title)
+                    overlay::spawn_overlay(
+                        theme,
+                        show_app_id,
+                        show_title,
+                        keyboard_mode,
+                        all_outputs,
+                    )
                 };
                 overlay_cmd_tx = new_cmd_tx;
                 overlay_event_rx = new_event_rx;
+                if power_saving {
+                    let _ = overlay_cmd_tx.send(OverlayCmd::SetPowerSaving(true));
+                }
 
                 tracing::info!(
                     respawn_count = overlay_respawn_times.len(),
@@ -429,57 +653,26 @@ async fn main() -> anyhow::Result<()> {
                 // Log at trace level — event may contain keystroke content (KeyChar).
                 tracing::trace!(?event, "overlay event received");
                 let ctrl_event = match event {
-                    OverlayEvent::KeyChar(ch) => {
-                        if dedup.accept(ch as u32, true) {
-                            Some(Event::Char(ch))
-                        } else {
-                            None
-                        }
-                    }
-                    OverlayEvent::Backspace => {
-                        if dedup.accept(0xFF08, true) {
-                            Some(Event::Backspace)
-                        } else {
-                            None
-                        }
-                    }
-                    OverlayEvent::SelectionDown => {
-                        if dedup.accept(0xFF54, true) {
-                            Some(Event::SelectionDown)
-                        } else {
-                            None
-                        }
-                    }
-                    OverlayEvent::SelectionUp => {
-                        if dedup.accept(0xFF52, true) {
-                            Some(Event::SelectionUp)
-                        } else {
-                            None
-                        }
-                    }
-                    OverlayEvent::Confirm => {
-                        if dedup.accept(0xFF0D, true) {
-                            Some(Event::Confirm)
-                        } else {
-                            None
+                    OverlayEvent::FrameCommitted(at) => {
+                        if let Some(ref mut timer) = activation_timer {
+                            timer.mark(Stage::FirstFrame, at);
                         }
+                        None
                     }
-                    OverlayEvent::Escape => {
-                        if dedup.accept(0xFF1B, true) {
-                            Some(Event::Escape)
-                        } else {
-                            None
+                    OverlayEvent::KeyboardEntered(at) => {
+                        if let Some(ref mut timer) = activation_timer {
+                            timer.mark(Stage::InputReady, at);
                         }
+                        None
                     }
-                    OverlayEvent::ModifierReleased => {
-                        if dedup.accept(0xFFE9, false) {
-                            Some(Event::ModifierReleased)
-                        } else {
-                            None
+                    other => match controller_event(&other) {
+                        Some((evt, Some(keyval))) => {
+                            let pressed = !matches!(evt, Event::ModifierReleased);
+                            dedup.accept(keyval, pressed).then_some(evt)
                         }
-                    }
-                    OverlayEvent::Dismiss => Some(Event::Dismiss),
-                    OverlayEvent::SurfaceUnmapped => None,
+                        Some((evt, None)) => Some(evt),
+                        None => None,
+                    },
                 };
                 if let Some(evt) = ctrl_event {
                     let win_list = windows.lock().await;
@@ -521,7 +714,7 @@ async fn main() -> anyhow::Result<()> {
             }
 
             // IPC bus messages.
-            msg_opt = client.recv() => {
+            msg_opt = next_message(&mut client, &mut applet_rx, &applet_ctx) => {
                 let Some(msg) = msg_opt else {
                     tracing::error!("IPC bus disconnected — exiting for systemd restart");
                     std::process::exit(1);
@@ -530,6 +723,16 @@ async fn main() -> anyhow::Result<()> {
                 if msg.sender == daemon_id {
                     continue;
                 }
+                // Fold a duplicate re-activation into the previous step;
+                // Alt is evidently still held, so keep the grace alive.
+                if !controller.is_idle()
+                    && is_cycle_step(&msg.payload, controller.invocation())
+                    && !cycle_debounce.accept(msg.timestamp.wall_ms)
+                {
+                    tracing::debug!("duplicate re-activation folded into the previous step");
+                    let _ = overlay_cmd_tx.send(OverlayCmd::ResetGrace);
+                    continue;
+                }
 
                 let response_event = match &msg.payload {
                     EventKind::WmListWindows => {
@@ -539,25 +742,117 @@ async fn main() -> anyhow::Result<()> {
                         Some(EventKind::WmListWindowsResponse { windows: win_list })
                     }
 
-                    EventKind::WmActivateWindow { window_id } => {
+                    EventKind::WmResidentHandoff { pid } => {
+                        tracing::warn!(pid, "unresponsive fast-path resident replaced");
+                        None
+                    }
+
+                    EventKind::WmTimingsRequest => Some(EventKind::WmTimingsResponse {
+                        timings: activation_timer.as_ref().map(|t| t.timings().clone()),
+                    }),
+
+                    EventKind::WmWarmup => {
+                        #[cfg(target_os = "linux")]
+                        if let Some(ref wake) = poll_wake_tx
+                            && !refresh_window_list(wake, &win_generation).await
+                        {
+                            tracing::debug!("warm-up: fresh window list not ready in 200ms, using cached");
+                        }
                         let win_list = windows.lock().await;
-                        let found_window_id = win_list.iter().find(|w| {
-                            w.id.to_string() == *window_id
-                                || w.app_id.as_str() == window_id
-                        }).map(|w| w.id);
+                        let window_count = u32::try_from(win_list.len()).unwrap_or(u32::MAX);
+                        // Never redraw over an overlay the user is looking at.
+                        let rendered = controller.is_idle() && {
+                            let cfg = wm_config.lock().await;
+                            let theme = OverlayTheme::from_config(&cfg);
+                            let (rows, hints) = controller.preview_picker(&win_list, &cfg);
+                            drop(cfg);
+                            overlay_cmd_tx.send(OverlayCmd::UpdateTheme(Box::new(theme))).is_ok()
+                                && overlay_cmd_tx.send(OverlayCmd::Warmup { windows: rows, hints }).is_ok()
+                        };
+                        drop(win_list);
+                        tracing::info!(window_count, rendered, "overlay warm-up requested via IPC");
+                        Some(EventKind::WmWarmupResponse { window_count, rendered })
+                    }
 
-                        if let Some(wid) = found_window_id {
+                    #[cfg(feature = "thumbnails")]
+                    EventKind::WmThemePreview { colors } => {
+                        let mut cfg = wm_config.lock().await.clone();
+                        cfg.set_theme_colors(colors.clone());
+                        let theme = OverlayTheme::from_config(&cfg);
+                        let (rows, hints) = daemon_wm::preview::sample_picker(&cfg);
+                        let (reply, frame) = tokio::sync::oneshot::channel();
+                        let png = if overlay_cmd_tx
+                            .send(OverlayCmd::RenderPreview { theme: Box::new(theme), windows: rows, hints, reply })
+                            .is_ok()
+                        {
+                            tokio::time::timeout(std::time::Duration::from_secs(2), frame)
+                                .await
+                                .ok()
+                                .and_then(Result::ok)
+                                .unwrap_or_default()
+                        } else {
+                            Vec::new()
+                        };
+                        tracing::info!(bytes = png.len(), "theme preview rendered via IPC");
+                        let error = png.is_empty().then(|| "overlay renderer produced no frame".to_string());
+                        Some(EventKind::WmThemePreviewResponse { png, error })
+                    }
+
+                    #[cfg(not(feature = "thumbnails"))]
+                    EventKind::WmThemePreview { .. } => Some(EventKind::WmThemePreviewResponse {
+                        png: Vec::new(),
+                        error: Some("daemon-wm was built without theme previews (`thumbnails` feature)".into()),
+                    }),
+
+                    EventKind::WmActivateWindow { .. } | EventKind::WmActivateMark { .. } => {
+                        let (window_id, silent) = match &msg.payload {
+                            EventKind::WmActivateWindow { window_id, silent } => (window_id.clone(), *silent),
+                            EventKind::WmActivateMark { mark } => {
+                                (daemon_wm::marks::window_for(*mark).unwrap_or_default(), false)
+                            }
+                            _ => unreachable!(),
+                        };
+                        let window_id = &window_id;
+                        let win_list = windows.lock().await;
+                        // An unset mark leaves `window_id` empty and matches nothing.
+                        let found_window = win_list.iter().find(|w| {
+                            !window_id.is_empty()
+                                && (w.id.to_string() == *window_id || w.app_id.as_str() == window_id)
+                        }).cloned();
+                        // A mark whose window closed is stale; drop it now
+                        // rather than at the next overlay activation.
+                        if found_window.is_none()
+                            && matches!(msg.payload, EventKind::WmActivateMark { .. })
+                            && !win_list.is_empty()
+                        {
+                            daemon_wm::marks::prune(&win_list.iter().map(|w| w.id.to_string()).collect());
+                        }
+
+                        if let Some(window) = found_window {
+                            #[cfg(all(target_os = "linux", feature = "dbus"))]
+                            let origin = daemon_wm::applet::origin_of(&win_list, &window).cloned();
                             drop(win_list);
-                            mru::save(window_id);
+                            let policy = if silent {
+                                mru::UpdatePolicy::Skip
+                            } else {
+                                mru::UpdatePolicy::Record
+                            };
+                            mru::save_activated_window(window_id, policy);
 
                             #[cfg(target_os = "linux")]
-                            if let Some(ref backend) = backend
-                                && let Err(e) = backend.activate_window(&wid).await
-                            {
-                                tracing::warn!(error = %e, "compositor activate_window failed");
+                            if let Some(ref backend) = backend {
+                                match backend.activate_window(&window.id).await {
+                                    Ok(()) => {
+                                        #[cfg(feature = "dbus")]
+                                        daemon_wm::applet::record_switch(origin.as_ref(), &window);
+                                        let focus_time = wm_config.lock().await.focus_time;
+                                        daemon_wm::focus_time::switched(window.app_id.as_str(), focus_time);
+                                    }
+                                    Err(e) => tracing::warn!(error = %e, "compositor activate_window failed"),
+                                }
                             }
 
-                            tracing::info!(window_id, "window activated");
+                            tracing::info!(window_id, silent, "window activated");
                             Some(EventKind::WmActivateWindowResponse { success: true })
                         } else {
                             drop(win_list);
@@ -569,25 +864,108 @@ async fn main() -> anyhow::Result<()> {
                     EventKind::WmActivateOverlay
                     | EventKind::WmActivateOverlayBackward
                     | EventKind::WmActivateOverlayLauncher
-                    | EventKind::WmActivateOverlayLauncherBackward => {
-                        let (event, label) = match &msg.payload {
-                            EventKind::WmActivateOverlay => (Event::Activate, "overlay activation"),
-                            EventKind::WmActivateOverlayBackward => (Event::ActivateBackward, "overlay activation (backward)"),
-                            EventKind::WmActivateOverlayLauncher => (Event::ActivateLauncher, "launcher-mode overlay activation"),
-                            EventKind::WmActivateOverlayLauncherBackward => (Event::ActivateLauncherBackward, "launcher-mode overlay activation (backward)"),
+                    | EventKind::WmActivateOverlayLauncherBackward
+                    | EventKind::WmActivateOverlayIntent { .. } => {
+                        let requested_at = std::time::Instant::now();
+                        let (launcher, backward, query, output) = match &msg.payload {
+                            EventKind::WmActivateOverlay => (false, false, None, None),
+                            EventKind::WmActivateOverlayBackward => (false, true, None, None),
+                            EventKind::WmActivateOverlayLauncher => (true, false, None, None),
+                            EventKind::WmActivateOverlayLauncherBackward => (true, true, None, None),
+                            EventKind::WmActivateOverlayIntent { launcher, backward, query, output } => {
+                                (*launcher, *backward, query.clone(), output.clone())
+                            }
                             _ => unreachable!(),
                         };
-                        tracing::info!("{label} requested via IPC");
+                        let (event, label, mode) = match (launcher, backward) {
+                            (false, false) => (Event::Activate, "overlay activation", "overlay"),
+                            (false, true) => (Event::ActivateBackward, "overlay activation (backward)", "overlay-backward"),
+                            (true, false) => (Event::ActivateLauncher, "launcher-mode overlay activation", "launcher"),
+                            (true, true) => (Event::ActivateLauncherBackward, "launcher-mode overlay activation (backward)", "launcher-backward"),
+                        };
+                        tracing::info!(query = ?query, output = ?output, "{label} requested via IPC");
 
                         // Refresh theme on each new activation cycle so dark/light
                         // mode changes, accent color tweaks, and frosted glass toggles
                         // are picked up immediately without requiring daemon restart.
                         // Only on fresh activation (idle → armed), not re-activations.
                         if controller.is_idle() {
+                            cycle_debounce.start(msg.timestamp.wall_ms);
+                            activation_timer = Some(ActivationTimer::start(
+                                requested_at,
+                                activation_timer.as_ref(),
+                                daemon_startup,
+                                mode,
+                            ));
                             let cfg = wm_config.lock().await;
-                            let theme = OverlayTheme::from_config(&cfg);
+                            let invocation = event
+                                .invocation_mode()
+                                .unwrap_or(core_config::InvocationMode::Switcher);
+                            let appearance = mode_appearance(&cfg, invocation);
+                            let mux_kinds = daemon_wm::multiplexer::configured_kinds(&cfg);
+                            let workspace_rows = cfg.workspace_rows;
+                            let output_rows = cfg.output_rows;
                             drop(cfg);
-                            let _ = overlay_cmd_tx.send(OverlayCmd::UpdateTheme(Box::new(theme)));
+                            for cmd in appearance {
+                                let _ = overlay_cmd_tx.send(cmd);
+                            }
+                            let _ = overlay_cmd_tx.send(OverlayCmd::SetOutput(output));
+
+                            // Multiplexer rows are listed per activation (tmux
+                            // windows come and go without compositor events),
+                            // but the overlay does not wait for them.
+                            mux_cycle += 1;
+                            controller.set_multiplexer_targets(
+                                daemon_wm::multiplexer::MultiplexerTargets::new(),
+                            );
+                            if !mux_kinds.is_empty() {
+                                let requester = client.requester();
+                                let mux_tx = mux_tx.clone();
+                                let cycle = mux_cycle;
+                                tokio::spawn(async move {
+                                    let targets =
+                                        daemon_wm::multiplexer::fetch(&requester, &mux_kinds).await;
+                                    let _ = mux_tx.send((cycle, targets)).await;
+                                });
+                            }
+
+                            // Workspace rows, likewise listed per activation.
+                            #[cfg(target_os = "linux")]
+                            let workspaces = match backend.as_ref() {
+                                Some(b) if workspace_rows => {
+                                    b.list_workspaces().await.unwrap_or_else(|e| {
+                                        tracing::warn!(error = %e, "listing workspaces failed");
+                                        Vec::new()
+                                    })
+                                }
+                                _ => Vec::new(),
+                            };
+                            #[cfg(not(target_os = "linux"))]
+                            let workspaces = {
+                                let _ = workspace_rows;
+                                Vec::new()
+                            };
+                            controller.set_workspaces(workspaces);
+
+                            // Outputs, for output rows and Ctrl+O.
+                            #[cfg(target_os = "linux")]
+                            let outputs = match backend.as_ref() {
+                                Some(b)
+                                    if output_rows || b.capabilities().can_move_to_output =>
+                                {
+                                    b.list_outputs().await.unwrap_or_else(|e| {
+                                        tracing::warn!(error = %e, "listing outputs failed");
+                                        Vec::new()
+                                    })
+                                }
+                                _ => Vec::new(),
+                            };
+                            #[cfg(not(target_os = "linux"))]
+                            let outputs = {
+                                let _ = output_rows;
+                                Vec::new()
+                            };
+                            controller.set_outputs(outputs);
                         }
 
                         // Request fresh window list from the poll thread before
@@ -595,34 +973,44 @@ async fn main() -> anyhow::Result<()> {
                         // list can be up to 2 seconds stale and include windows
                         // that have already been closed.
                         #[cfg(target_os = "linux")]
-                        if let Some(ref wake) = poll_wake_tx {
-                            let gen_before = win_generation.load(std::sync::atomic::Ordering::Acquire);
-                            let _ = wake.send(());
-                            // Wait up to 200ms for the poll thread to enumerate
-                            // and the background task to update the mutex.
-                            let deadline = tokio::time::Instant::now() + std::time::Duration::from_millis(200);
-                            while win_generation.load(std::sync::atomic::Ordering::Acquire) == gen_before {
-                                if tokio::time::Instant::now() >= deadline {
-                                    tracing::debug!("fresh window list not ready in 200ms, using cached");
-                                    break;
+                        if let Some(ref wake) = poll_wake_tx
+                            && !refresh_window_list(wake, &win_generation).await
+                        {
+                            tracing::debug!("fresh window list not ready in 200ms, using cached");
+                        }
+                        if let Some(ref mut timer) = activation_timer {
+                            timer.mark(Stage::WindowsEnumerated, std::time::Instant::now());
+                        }
+
+                        // The query is applied right after activation, as if
+                        // typed during the Armed dwell.
+                        let events = std::iter::once(event).chain(query.map(Event::Prefill));
+                        for event in events {
+                            if matches!(event, Event::Prefill(_)) && controller.is_idle() {
+                                break;
+                            }
+                            let win_list = windows.lock().await;
+                            let cfg = wm_config.lock().await;
+                            let invocation = controller.invocation();
+                            let was_idle = controller.is_idle();
+                            let cmds = controller.handle(event, &win_list, &cfg);
+                            // Upgraded from switcher to launcher mid-cycle.
+                            if !was_idle && controller.invocation() != invocation {
+                                for cmd in mode_appearance(&cfg, controller.invocation()) {
+                                    let _ = overlay_cmd_tx.send(cmd);
                                 }
-                                tokio::task::yield_now().await;
                             }
+                            drop(cfg);
+                            drop(win_list);
+                            daemon_wm::commands::execute_commands(
+                                cmds, &overlay_cmd_tx, &mut overlay_event_rx,
+                                #[cfg(target_os = "linux")] &backend,
+                                &mut client, &config_state,
+                                &mut controller, &windows, &wm_config,
+                                &mut ipc_keyboard_confirmed,
+                                &mut password_buffer,
+                            ).await;
                         }
-
-                        let win_list = windows.lock().await;
-                        let cfg = wm_config.lock().await;
-                        let cmds = controller.handle(event, &win_list, &cfg);
-                        drop(cfg);
-                        drop(win_list);
-                        daemon_wm::commands::execute_commands(
-                            cmds, &overlay_cmd_tx, &mut overlay_event_rx,
-                            #[cfg(target_os = "linux")] &backend,
-                            &mut client, &config_state,
-                            &mut controller, &windows, &wm_config,
-                            &mut ipc_keyboard_confirmed,
-                            &mut password_buffer,
-                        ).await;
                         None
                     }
 
@@ -697,10 +1085,81 @@ async fn main() -> anyhow::Result<()> {
                     }
                 }
             }
+            Some(event) = lock_rx.recv() => {
+                if matches!(event, Event::SessionLock(true) | Event::SessionLockRequested) {
+                    daemon_wm::focus_time::stop();
+                }
+                let win_list = windows.lock().await;
+                let cfg = wm_config.lock().await;
+                let cmds = controller.handle(event, &win_list, &cfg);
+                drop(cfg);
+                drop(win_list);
+                daemon_wm::commands::execute_commands(
+                    cmds, &overlay_cmd_tx, &mut overlay_event_rx,
+                    #[cfg(target_os = "linux")] &backend,
+                    &mut client, &config_state,
+                    &mut controller, &windows, &wm_config,
+                    &mut ipc_keyboard_confirmed,
+                    &mut password_buffer,
+                ).await;
+            }
+            Some(event) = change_rx.recv() => {
+                if let Event::WindowChanged { window_id, app_id, title } = &event
+                    && let Some(window) = windows.lock().await.iter_mut().find(|w| w.id == *window_id)
+                {
+                    window.app_id = core_types::AppId::new(app_id);
+                    window.title.clone_from(title);
+                }
+                if controller.is_idle() {
+                    continue;
+                }
+                let win_list = windows.lock().await;
+                let cfg = wm_config.lock().await;
+                let cmds = controller.handle(event, &win_list, &cfg);
+                drop(cfg);
+                drop(win_list);
+                daemon_wm::commands::execute_commands(
+                    cmds, &overlay_cmd_tx, &mut overlay_event_rx,
+                    #[cfg(target_os = "linux")] &backend,
+                    &mut client, &config_state,
+                    &mut controller, &windows, &wm_config,
+                    &mut ipc_keyboard_confirmed,
+                    &mut password_buffer,
+                ).await;
+            }
+            Some((cycle, targets)) = mux_rx.recv() => {
+                if cycle != mux_cycle || controller.is_idle() {
+                    continue;
+                }
+                let win_list = windows.lock().await;
+                let cfg = wm_config.lock().await;
+                let cmds = controller.handle(Event::MultiplexerListed(targets), &win_list, &cfg);
+                drop(cfg);
+                drop(win_list);
+                daemon_wm::commands::execute_commands(
+                    cmds, &overlay_cmd_tx, &mut overlay_event_rx,
+                    #[cfg(target_os = "linux")] &backend,
+                    &mut client, &config_state,
+                    &mut controller, &windows, &wm_config,
+                    &mut ipc_keyboard_confirmed,
+                    &mut password_buffer,
+                ).await;
+            }
+            Some(profile) = power_rx.recv() => {
+                let mode = wm_config.lock().await.power_saving;
+                let throttled = power::throttled(mode, Some(&profile));
+                power_profile = Some(profile);
+                if throttled != power_saving {
+                    power_saving = throttled;
+                    tracing::info!(power_saving, profile = ?power_profile, "power saving changed by power profile");
+                    let _ = overlay_cmd_tx.send(OverlayCmd::SetPowerSaving(power_saving));
+                }
+            }
             Some(()) = reload_rx.recv() => {
                 tracing::info!("config reloaded");
                 let new_wm = {
                     let guard = config_state.read().map_err(|e| anyhow::anyhow!("{e}"))?;
+                    core_types::set_redact_titles(guard.global.logging.redact_titles_in_logs);
                     guard
                         .profiles
                         .values()
@@ -711,6 +1170,17 @@ async fn main() -> anyhow::Result<()> {
 
                 let theme = OverlayTheme::from_config(&new_wm);
                 let _ = overlay_cmd_tx.send(OverlayCmd::UpdateTheme(Box::new(theme)));
+                for message in theme_problems(&new_wm) {
+                    let _ = overlay_cmd_tx.send(OverlayCmd::Toast { message });
+                }
+                let _ = overlay_cmd_tx.send(OverlayCmd::SetKeyboardMode(new_wm.keyboard_mode));
+                let _ = overlay_cmd_tx.send(OverlayCmd::SetAllOutputs(new_wm.show_on_all_outputs));
+                let throttled = power::throttled(new_wm.power_saving, power_profile.as_deref());
+                if throttled != power_saving {
+                    power_saving = throttled;
+                    tracing::info!(power_saving, "power saving changed by config");
+                    let _ = overlay_cmd_tx.send(OverlayCmd::SetPowerSaving(power_saving));
+                }
 
                 *wm_config.lock().await = new_wm;
 
@@ -724,17 +1194,44 @@ async fn main() -> anyhow::Result<()> {
             }
             _ = tokio::signal::ctrl_c() => {
                 tracing::info!("SIGINT received, shutting down");
-                break;
+                break EXIT_SIGINT;
             }
             _ = sigterm() => {
                 tracing::info!("SIGTERM received, shutting down");
-                break;
+                break EXIT_SIGTERM;
             }
         }
+    };
+
+    daemon_wm::focus_time::stop();
+
+    // Abandon any activation in progress so the keyboard grab is not left
+    // held by a daemon that is going away.
+    if !controller.is_idle() {
+        controller.force_idle();
+        password_buffer.clear();
+        client
+            .publish(
+                EventKind::InputGrabRelease {
+                    requester: client.daemon_id(),
+                },
+                SecurityLevel::Internal,
+            )
+            .await
+            .ok();
     }
 
-    // Shutdown overlay thread.
+    // Shutdown overlay thread. It destroys the layer surface before exiting,
+    // which closes the event channel; wait for that so a visible overlay is
+    // not left frozen on screen.
     let _ = overlay_cmd_tx.send(OverlayCmd::Quit);
+    let overlay_closed = tokio::time::timeout(OVERLAY_QUIT_TIMEOUT, async {
+        while overlay_event_rx.recv().await.is_some() {}
+    })
+    .await;
+    if overlay_closed.is_err() {
+        tracing::warn!("overlay thread did not exit in time");
+    }
 
     // Shutdown.
     client
@@ -748,8 +1245,73 @@ async fn main() -> anyhow::Result<()> {
         .await
         .ok();
 
-    tracing::info!("daemon-wm shut down");
-    Ok(())
+    client.shutdown().await;
+
+    daemon_wm::state::flush(STATE_FLUSH_TIMEOUT);
+
+    tracing::info!(exit_code, "daemon-wm shut down");
+    std::process::exit(exit_code);
+}
+
+/// Theme and column commands for the `invocation` overrides of `cfg`.
+fn mode_appearance(
+    cfg: &core_config::WmConfig,
+    invocation: core_config::InvocationMode,
+) -> [OverlayCmd; 2] {
+    let mode_cfg = cfg.for_mode(invocation);
+    [
+        OverlayCmd::UpdateTheme(Box::new(OverlayTheme::from_config(&mode_cfg))),
+        OverlayCmd::SetColumns {
+            show_app_id: mode_cfg.show_app_id,
+            show_title: mode_cfg.show_title,
+        },
+    ]
+}
+
+/// Toasts for theme colors in `cfg` that fell back to the default.
+fn theme_problems(cfg: &core_config::WmConfig) -> Vec<String> {
+    OverlayTheme::invalid_colors(cfg)
+        .into_iter()
+        .map(|key| format!("Invalid {key} in config: using the theme default"))
+        .collect()
+}
+
+/// Wake the poll thread for an immediate re-enumeration and wait up to
+/// 200ms for the background task to store the result.
+///
+/// Returns `false` if the list was not refreshed in time; the cached list
+/// (up to 2 seconds stale) is then all there is.
+#[cfg(target_os = "linux")]
+async fn refresh_window_list(
+    wake: &std::sync::mpsc::Sender<()>,
+    generation: &std::sync::atomic::AtomicU64,
+) -> bool {
+    let gen_before = generation.load(std::sync::atomic::Ordering::Acquire);
+    let _ = wake.send(());
+    let deadline = tokio::time::Instant::now() + std::time::Duration::from_millis(200);
+    while generation.load(std::sync::atomic::Ordering::Acquire) == gen_before {
+        if tokio::time::Instant::now() >= deadline {
+            return false;
+        }
+        tokio::task::yield_now().await;
+    }
+    true
+}
+
+/// Next bus message, or a queued applet intent wrapped as a message from
+/// `applet_ctx`.
+async fn next_message(
+    client: &mut BusClient,
+    applet_rx: &mut tokio::sync::mpsc::Receiver<EventKind>,
+    applet_ctx: &MessageContext,
+) -> Option<Message<EventKind>> {
+    let epoch = client.epoch();
+    tokio::select! {
+        msg = client.recv() => msg,
+        Some(intent) = applet_rx.recv() => {
+            Some(Message::new(applet_ctx, intent, SecurityLevel::Internal, epoch))
+        }
+    }
 }
 
 /// Wait for SIGTERM (Unix).
@@ -768,18 +1330,29 @@ async fn sigterm() {
 
 fn init_logging(format: &str) -> anyhow::Result<()> {
     use tracing_subscriber::EnvFilter;
+    use tracing_subscriber::fmt::format::FmtSpan;
+    use tracing_subscriber::layer::SubscriberExt;
+    use tracing_subscriber::util::SubscriberInitExt;
 
     let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+    let registry = tracing_subscriber::registry()
+        .with(filter)
+        .with(daemon_wm::crash::TailLayer);
 
     match format {
         "json" => {
-            tracing_subscriber::fmt()
-                .with_env_filter(filter)
-                .json()
+            registry
+                .with(
+                    tracing_subscriber::fmt::layer()
+                        .with_span_events(FmtSpan::CLOSE)
+                        .json(),
+                )
                 .init();
         }
         _ => {
-            tracing_subscriber::fmt().with_env_filter(filter).init();
+            registry
+                .with(tracing_subscriber::fmt::layer().with_span_events(FmtSpan::CLOSE))
+                .init();
         }
     }
 
@@ -0,0 +1,105 @@
+//! Window marks: Vim-style marks (`a`–`z`) that name a window.
+//!
+//! Ctrl+M then a letter in the picker marks the selected window;
+//! `sesame wm mark <letter>` later activates it without showing the overlay.
+//! Marks are kept by window ID in the state store (`marks` table), so they
+//! survive daemon restarts but not the window: marks whose window is gone
+//! are pruned on each overlay activation, and when a mark is used.
+
+use core_state::StateStore;
+use std::collections::{BTreeMap, HashSet};
+
+/// Marks by letter.
+pub type Marks = BTreeMap<char, String>;
+
+/// Whether `ch` can name a mark.
+#[must_use]
+pub fn is_mark(ch: char) -> bool {
+    ch.is_ascii_lowercase()
+}
+
+/// Load the marks from the store.
+#[must_use]
+pub fn load() -> Marks {
+    crate::state::with(|store| store.marks()).unwrap_or_default()
+}
+
+/// The window ID `mark` names, if set.
+#[must_use]
+pub fn window_for(mark: char) -> Option<String> {
+    load().remove(&mark)
+}
+
+/// Point `mark` at `window_id`, replacing the window it named before.
+pub fn set(mark: char, window_id: &str) {
+    let window_id = window_id.to_owned();
+    crate::state::write(move |store| set_in(store, mark, &window_id));
+}
+
+/// Remove marks whose window is not in `live_ids`.
+///
+/// Called during overlay activation, like `mru::prune`.
+pub fn prune(live_ids: &HashSet<String>) {
+    let live_ids = live_ids.clone();
+    crate::state::write(move |store| prune_in(store, &live_ids));
+}
+
+fn set_in(store: &mut StateStore, mark: char, window_id: &str) -> core_types::Result<()> {
+    store.update_marks(|marks| {
+        tracing::info!(%mark, window_id, "marks: setting mark");
+        marks.insert(mark, window_id.to_string()).as_deref() != Some(window_id)
+    })?;
+    Ok(())
+}
+
+fn prune_in(store: &mut StateStore, live_ids: &HashSet<String>) -> core_types::Result<()> {
+    store.update_marks(|marks| {
+        let before = marks.len();
+        marks.retain(|_, id| live_ids.contains(id));
+        let removed = before - marks.len();
+        if removed > 0 {
+            tracing::info!(
+                removed,
+                remaining = marks.len(),
+                "marks: pruned stale marks"
+            );
+        }
+        removed > 0
+    })?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_replaces_the_window_a_mark_names() {
+        let mut store = StateStore::open_in_memory().unwrap();
+        set_in(&mut store, 'b', "win-2").unwrap();
+        set_in(&mut store, 'a', "win-1").unwrap();
+        set_in(&mut store, 'b', "win-3").unwrap();
+        assert_eq!(
+            store.marks().unwrap().into_iter().collect::<Vec<_>>(),
+            vec![('a', "win-1".to_string()), ('b', "win-3".to_string())]
+        );
+    }
+
+    #[test]
+    fn prune_drops_marks_of_closed_windows() {
+        let mut store = StateStore::open_in_memory().unwrap();
+        set_in(&mut store, 'a', "win-1").unwrap();
+        set_in(&mut store, 'b', "win-2").unwrap();
+        let live: HashSet<String> = ["win-2".to_string()].into();
+        prune_in(&mut store, &live).unwrap();
+        let marks = store.marks().unwrap();
+        assert_eq!(marks.get(&'b').map(String::as_str), Some("win-2"));
+        assert!(!marks.contains_key(&'a'));
+    }
+
+    #[test]
+    fn mark_letters_are_lowercase_ascii() {
+        assert!(is_mark('a') && is_mark('z'));
+        assert!(!is_mark('B') && !is_mark('1') && !is_mark('é'));
+    }
+}
@@ -1,18 +1,20 @@
 //! MRU (Most Recently Used) window stack.
 //!
-//! Maintains a fully ordered stack of window IDs on disk, most recently
-//! focused first. Used to sort the Alt+Tab window list so that:
+//! Maintains a fully ordered stack of window IDs in the state store
+//! (`mru` table), most recently focused first. Used to sort the Alt+Tab
+//! window list so that:
 //!
 //! - Index 0 = most recently used (after reorder: the quick-switch target)
 //! - Last index = least recently used
-//! - Origin (currently focused) is demoted to end by `reorder()`
+//! - Origin (currently focused) is demoted to end by `reorder()`, or left
+//!   out by `remove_current()` (`hide_current_window`)
 //!
-//! File format: one window ID per line, most recent first. Capped at 64
-//! entries. Uses advisory file locking (flock) for atomic read-modify-write.
+//! Each entry also keeps the Unix time it was last focused (the focus
+//! timeline). Capped at 64 entries. Every change is one store transaction.
 
-use std::fs::{File, OpenOptions};
-use std::io::{Read, Seek, Write};
-use std::path::PathBuf;
+use core_state::{MruEntry, StateStore};
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 /// Maximum entries in the MRU stack.
 const MAX_ENTRIES: usize = 64;
@@ -22,6 +24,8 @@ const MAX_ENTRIES: usize = 64;
 pub struct MruState {
     /// Ordered window IDs. Index 0 = most recently focused (current).
     pub stack: Vec<String>,
+    /// Unix time each window was last focused, where recorded.
+    pub focused_at: HashMap<String, u64>,
 }
 
 impl MruState {
@@ -39,61 +43,59 @@ impl MruState {
     pub fn position(&self, id: &str) -> Option<usize> {
         self.stack.iter().position(|s| s == id)
     }
+
+    /// Unix time a window was last focused, if recorded.
+    pub fn focused_at(&self, id: &str) -> Option<u64> {
+        self.focused_at.get(id).copied()
+    }
+
+    /// Move `target` to the top of the stack, focused at `now`.
+    fn promote(&mut self, target: &str, now: u64) {
+        self.stack.retain(|s| s != target);
+        self.stack.insert(0, target.to_string());
+        self.stack.truncate(MAX_ENTRIES);
+        self.focused_at.insert(target.to_string(), now);
+    }
+
+    /// The stack as store rows.
+    fn entries(&self) -> Vec<MruEntry> {
+        self.stack
+            .iter()
+            .map(|id| MruEntry {
+                window_id: id.clone(),
+                focused_at: self.focused_at(id),
+            })
+            .collect()
+    }
 }
 
-/// Resolve the MRU state file path.
-///
-/// `~/.cache/open-sesame/mru`, with directory created at 0o700 if missing.
-fn mru_path() -> Option<PathBuf> {
-    let cache = dirs::cache_dir()?.join("open-sesame");
-    if !cache.exists() {
-        #[cfg(unix)]
-        {
-            use std::fs::DirBuilder;
-            use std::os::unix::fs::DirBuilderExt;
-            if DirBuilder::new()
-                .mode(0o700)
-                .recursive(true)
-                .create(&cache)
-                .is_err()
-            {
-                return None;
-            }
-        }
-        #[cfg(not(unix))]
-        {
-            if std::fs::create_dir_all(&cache).is_err() {
-                return None;
+impl From<Vec<MruEntry>> for MruState {
+    fn from(entries: Vec<MruEntry>) -> Self {
+        let mut state = Self::default();
+        for entry in entries {
+            if let Some(at) = entry.focused_at {
+                state.focused_at.insert(entry.window_id.clone(), at);
             }
+            state.stack.push(entry.window_id);
         }
+        state
     }
-    Some(cache.join("mru"))
 }
 
-/// Load MRU state from disk with shared lock.
+/// The current Unix time in seconds.
+#[must_use]
+pub fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// Load the MRU state from the store.
 #[must_use]
 pub fn load() -> MruState {
-    let Some(path) = mru_path() else {
-        tracing::debug!("mru: no cache path available");
-        return MruState::default();
-    };
-    let Ok(mut file) = File::open(&path) else {
-        tracing::debug!("mru: file not found, returning empty state");
+    let Some(state) = crate::state::with(load_in) else {
         return MruState::default();
     };
-
-    if !lock_shared(&file) {
-        tracing::warn!("mru: failed to acquire shared lock");
-        return MruState::default();
-    }
-
-    let mut contents = String::new();
-    if file.read_to_string(&mut contents).is_err() {
-        tracing::warn!("mru: failed to read file");
-        return MruState::default();
-    }
-
-    let state = parse(&contents);
     tracing::debug!(
         stack_len = state.stack.len(),
         current = state.current().unwrap_or("<none>"),
@@ -109,96 +111,39 @@ pub fn load() -> MruState {
 /// removed from its old position first. Stack is capped at MAX_ENTRIES.
 /// No-op if target is already at position 0.
 pub fn save(target: &str) {
-    let Some(path) = mru_path() else {
-        return;
-    };
-
-    let Ok(mut file) = OpenOptions::new()
-        .read(true)
-        .write(true)
-        .create(true)
-        .truncate(false)
-        .open(&path)
-    else {
-        return;
-    };
-
-    if !lock_exclusive(&file) {
-        return;
-    }
+    let target = target.to_owned();
+    let now = now_secs();
+    crate::state::write(move |store| save_in(store, &target, now));
+}
 
-    // Read existing stack.
-    let mut contents = String::new();
-    let _ = file.read_to_string(&mut contents);
-    let mut state = parse(&contents);
+/// Whether an activation is recorded in the MRU stack.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpdatePolicy {
+    /// Promote the activated window (user-initiated switches).
+    Record,
+    /// Leave the stack untouched (silent, scripted activations).
+    Skip,
+}
 
-    // Already at top — no-op.
-    if state.current() == Some(target) {
-        tracing::debug!(target, "mru: already at top, skipping");
-        return;
+/// Apply `policy` to an activation of `target`.
+pub fn save_activated_window(target: &str, policy: UpdatePolicy) {
+    match policy {
+        UpdatePolicy::Record => save(target),
+        UpdatePolicy::Skip => tracing::debug!(target, "mru: silent activation, not recorded"),
     }
-
-    tracing::info!(
-        target,
-        stack_len = state.stack.len(),
-        "mru: promoting to top"
-    );
-
-    // Remove target from current position (if present) and insert at front.
-    state.stack.retain(|s| s != target);
-    state.stack.insert(0, target.to_string());
-    state.stack.truncate(MAX_ENTRIES);
-
-    // Write back.
-    let serialized = state.stack.join("\n");
-    let _ = file.seek(std::io::SeekFrom::Start(0));
-    let _ = file.set_len(0);
-    let _ = file.write_all(serialized.as_bytes());
 }
 
 /// Remove MRU entries that no longer correspond to live windows.
 ///
-/// Called during overlay activation to keep the MRU file accurate.
+/// Called during overlay activation to keep the MRU stack accurate.
 /// `live_ids` is the set of window ID strings currently reported by the
-/// compositor. Any MRU entry not in this set is removed.
-pub fn prune(live_ids: &std::collections::HashSet<String>) {
-    let Some(path) = mru_path() else {
-        return;
-    };
-
-    let Ok(mut file) = OpenOptions::new()
-        .read(true)
-        .write(true)
-        .create(false)
-        .truncate(false)
-        .open(&path)
-    else {
-        return;
-    };
-
-    if !lock_exclusive(&file) {
-        return;
-    }
-
-    let mut contents = String::new();
-    let _ = file.read_to_string(&mut contents);
-    let mut state = parse(&contents);
-
-    let before = state.stack.len();
+/// compositor. Any MRU entry not in this set is removed from the store and
+/// from `state`, the stack the caller already loaded.
+pub fn prune(state: &mut MruState, live_ids: &std::collections::HashSet<String>) {
     state.stack.retain(|id| live_ids.contains(id));
-    let removed = before - state.stack.len();
-
-    if removed > 0 {
-        tracing::info!(
-            removed,
-            remaining = state.stack.len(),
-            "mru: pruned dead entries"
-        );
-        let serialized = state.stack.join("\n");
-        let _ = file.seek(std::io::SeekFrom::Start(0));
-        let _ = file.set_len(0);
-        let _ = file.write_all(serialized.as_bytes());
-    }
+    state.focused_at.retain(|id, _| live_ids.contains(id));
+    let live_ids = live_ids.clone();
+    crate::state::write(move |store| prune_in(store, &live_ids));
 }
 
 /// Seed MRU stack from a window list if empty.
@@ -242,9 +187,8 @@ pub fn seed_if_empty(windows: &[core_types::Window]) {
 /// (e.g. `WindowId`) format via `Display` without storing a `String` field
 /// that could be borrowed.
 ///
-/// Accepts a pre-loaded `MruState` to avoid redundant file I/O — the caller
-/// is responsible for loading the state (ideally off the async runtime via
-/// `spawn_blocking`).
+/// Accepts a pre-loaded `MruState` to avoid redundant store reads — the
+/// caller is responsible for loading the state.
 pub fn reorder<T, F>(windows: &mut [T], get_id: F, state: &MruState)
 where
     F: Fn(&T) -> String,
@@ -260,142 +204,143 @@ where
     });
 }
 
-fn parse(contents: &str) -> MruState {
-    let stack: Vec<String> = contents
-        .lines()
-        .map(|s| s.trim().to_string())
-        .filter(|s| !s.is_empty())
-        .collect();
-    MruState { stack }
-}
-
-#[cfg(unix)]
-fn lock_shared(file: &File) -> bool {
-    use std::os::unix::io::AsRawFd;
-    // LOCK_NB: never block the tokio thread waiting for another process.
-    unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_SH | libc::LOCK_NB) == 0 }
+/// Remove the currently focused window (top of the stack) from `windows`.
+/// Windows the stack does not know are kept.
+pub fn remove_current<T, F>(windows: &mut Vec<T>, get_id: F, state: &MruState)
+where
+    F: Fn(&T) -> String,
+{
+    if let Some(current) = state.current() {
+        windows.retain(|w| get_id(w) != current);
+    }
 }
 
-#[cfg(unix)]
-fn lock_exclusive(file: &File) -> bool {
-    use std::os::unix::io::AsRawFd;
-    // LOCK_NB: never block the tokio thread waiting for another process.
-    unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) == 0 }
+fn load_in(store: &mut StateStore) -> core_types::Result<MruState> {
+    store.mru().map(MruState::from)
 }
 
-#[cfg(not(unix))]
-fn lock_shared(_file: &File) -> bool {
-    true
+fn save_in(store: &mut StateStore, target: &str, now: u64) -> core_types::Result<()> {
+    store.update_mru(|entries| {
+        let mut state = MruState::from(std::mem::take(entries));
+        // Already at top — no-op.
+        if state.current() == Some(target) {
+            tracing::debug!(target, "mru: already at top, skipping");
+            return false;
+        }
+        tracing::info!(
+            target,
+            stack_len = state.stack.len(),
+            "mru: promoting to top"
+        );
+        state.promote(target, now);
+        *entries = state.entries();
+        true
+    })?;
+    Ok(())
 }
 
-#[cfg(not(unix))]
-fn lock_exclusive(_file: &File) -> bool {
-    true
+fn prune_in(
+    store: &mut StateStore,
+    live_ids: &std::collections::HashSet<String>,
+) -> core_types::Result<()> {
+    store.update_mru(|entries| {
+        let before = entries.len();
+        entries.retain(|entry| live_ids.contains(&entry.window_id));
+        let removed = before - entries.len();
+        if removed > 0 {
+            tracing::info!(
+                removed,
+                remaining = entries.len(),
+                "mru: pruned dead entries"
+            );
+        }
+        removed > 0
+    })?;
+    Ok(())
 }
 
-// ---------------------------------------------------------------------------
-// Test helpers
-// ---------------------------------------------------------------------------
-
 #[cfg(test)]
-fn load_from(path: &std::path::Path) -> MruState {
-    let Ok(mut file) = File::open(path) else {
-        return MruState::default();
-    };
-    let mut contents = String::new();
-    if file.read_to_string(&mut contents).is_err() {
-        return MruState::default();
-    }
-    parse(&contents)
-}
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
 
-#[cfg(test)]
-fn save_to(path: &std::path::Path, target: &str) {
-    let Ok(mut file) = OpenOptions::new()
-        .read(true)
-        .write(true)
-        .create(true)
-        .truncate(false)
-        .open(path)
-    else {
-        return;
-    };
-    if !lock_exclusive(&file) {
-        return;
+    /// A state with `ids` stacked in order, without focus times.
+    fn stack_of(ids: &[&str]) -> MruState {
+        MruState {
+            stack: ids.iter().map(|s| (*s).to_string()).collect(),
+            focused_at: HashMap::new(),
+        }
     }
 
-    let mut contents = String::new();
-    let _ = file.read_to_string(&mut contents);
-    let mut state = parse(&contents);
-
-    if state.current() == Some(target) {
-        return;
+    fn store() -> StateStore {
+        StateStore::open_in_memory().unwrap()
     }
 
-    state.stack.retain(|s| s != target);
-    state.stack.insert(0, target.to_string());
-    state.stack.truncate(MAX_ENTRIES);
-
-    let serialized = state.stack.join("\n");
-    let _ = file.seek(std::io::SeekFrom::Start(0));
-    let _ = file.set_len(0);
-    let _ = file.write_all(serialized.as_bytes());
-}
-
-#[cfg(test)]
-fn prune_from(path: &std::path::Path, live_ids: &std::collections::HashSet<String>) {
-    let Ok(mut file) = OpenOptions::new()
-        .read(true)
-        .write(true)
-        .create(false)
-        .truncate(false)
-        .open(path)
-    else {
-        return;
-    };
-    if !lock_exclusive(&file) {
-        return;
+    fn stack(store: &mut StateStore) -> Vec<String> {
+        load_in(store).unwrap().stack
     }
-    let mut contents = String::new();
-    let _ = file.read_to_string(&mut contents);
-    let mut state = parse(&contents);
-    state.stack.retain(|id| live_ids.contains(id));
-    let serialized = state.stack.join("\n");
-    let _ = file.seek(std::io::SeekFrom::Start(0));
-    let _ = file.set_len(0);
-    let _ = file.write_all(serialized.as_bytes());
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    fn live(ids: &[&str]) -> HashSet<String> {
+        ids.iter().map(|s| (*s).to_string()).collect()
+    }
 
     #[test]
-    fn parse_empty() {
-        let state = parse("");
+    fn empty_state() {
+        let state = load_in(&mut store()).unwrap();
         assert!(state.stack.is_empty());
         assert!(state.current().is_none());
         assert!(state.previous().is_none());
     }
 
     #[test]
-    fn parse_stack() {
-        let state = parse("win-A\nwin-B\nwin-C");
-        assert_eq!(state.stack, vec!["win-A", "win-B", "win-C"]);
+    fn current_and_previous() {
+        let state = stack_of(&["win-A", "win-B", "win-C"]);
         assert_eq!(state.current(), Some("win-A"));
         assert_eq!(state.previous(), Some("win-B"));
     }
 
     #[test]
-    fn parse_whitespace() {
-        let state = parse("  win-A  \n  win-B  ");
-        assert_eq!(state.current(), Some("win-A"));
-        assert_eq!(state.previous(), Some("win-B"));
+    fn focus_times_round_trip_through_the_store() {
+        let mut store = store();
+        save_in(&mut store, "win-B", 1_700_000_100).unwrap();
+        save_in(&mut store, "win-A", 1_700_000_300).unwrap();
+        let state = load_in(&mut store).unwrap();
+        assert_eq!(state.stack, vec!["win-A", "win-B"]);
+        assert_eq!(state.focused_at("win-A"), Some(1_700_000_300));
+        assert_eq!(state.focused_at("win-B"), Some(1_700_000_100));
+    }
+
+    #[test]
+    fn entries_without_a_time_are_kept() {
+        let state = MruState::from(vec![
+            MruEntry {
+                window_id: "win-A".into(),
+                focused_at: Some(5),
+            },
+            MruEntry {
+                window_id: "win-B".into(),
+                focused_at: None,
+            },
+        ]);
+        assert_eq!(state.stack, vec!["win-A", "win-B"]);
+        assert_eq!(state.focused_at("win-B"), None);
+        assert_eq!(state.entries()[1].focused_at, None);
+    }
+
+    #[test]
+    fn promote_stamps_focus_time() {
+        let mut state = stack_of(&["A", "B"]);
+        state.focused_at.insert("A".into(), 100);
+        state.focused_at.insert("B".into(), 50);
+        state.promote("B", 200);
+        assert_eq!(state.stack, vec!["B", "A"]);
+        assert_eq!(state.focused_at("B"), Some(200));
+        assert_eq!(state.focused_at("A"), Some(100));
     }
 
     #[test]
     fn position_lookup() {
-        let state = parse("A\nB\nC\nD");
+        let state = stack_of(&["A", "B", "C", "D"]);
         assert_eq!(state.position("A"), Some(0));
         assert_eq!(state.position("C"), Some(2));
         assert_eq!(state.position("Z"), None);
@@ -403,181 +348,165 @@ mod tests {
 
     #[test]
     fn promote_to_top() {
-        let dir = tempfile::tempdir().unwrap();
-        let path = dir.path().join("mru");
+        let mut store = store();
 
-        save_to(&path, "A");
-        assert_eq!(load_from(&path).stack, vec!["A"]);
+        save_in(&mut store, "A", 0).unwrap();
+        assert_eq!(stack(&mut store), vec!["A"]);
 
-        save_to(&path, "B");
-        assert_eq!(load_from(&path).stack, vec!["B", "A"]);
+        save_in(&mut store, "B", 0).unwrap();
+        assert_eq!(stack(&mut store), vec!["B", "A"]);
 
-        save_to(&path, "C");
-        assert_eq!(load_from(&path).stack, vec!["C", "B", "A"]);
+        save_in(&mut store, "C", 0).unwrap();
+        assert_eq!(stack(&mut store), vec!["C", "B", "A"]);
 
         // Re-promote A: moves from position 2 to 0.
-        save_to(&path, "A");
-        assert_eq!(load_from(&path).stack, vec!["A", "C", "B"]);
+        save_in(&mut store, "A", 0).unwrap();
+        assert_eq!(stack(&mut store), vec!["A", "C", "B"]);
     }
 
     #[test]
     fn promote_noop_when_already_top() {
-        let dir = tempfile::tempdir().unwrap();
-        let path = dir.path().join("mru");
+        let mut store = store();
 
-        save_to(&path, "A");
-        save_to(&path, "B");
-        save_to(&path, "B"); // already at top
-        assert_eq!(load_from(&path).stack, vec!["B", "A"]);
+        save_in(&mut store, "A", 1).unwrap();
+        save_in(&mut store, "B", 2).unwrap();
+        save_in(&mut store, "B", 3).unwrap(); // already at top
+        let state = load_in(&mut store).unwrap();
+        assert_eq!(state.stack, vec!["B", "A"]);
+        assert_eq!(state.focused_at("B"), Some(2), "no-op is not written");
     }
 
     #[test]
     fn alt_tab_ping_pong() {
-        let dir = tempfile::tempdir().unwrap();
-        let path = dir.path().join("mru");
+        let mut store = store();
 
         // Start: A focused, B and C exist.
-        save_to(&path, "C");
-        save_to(&path, "B");
-        save_to(&path, "A");
-        assert_eq!(load_from(&path).stack, vec!["A", "B", "C"]);
+        save_in(&mut store, "C", 0).unwrap();
+        save_in(&mut store, "B", 0).unwrap();
+        save_in(&mut store, "A", 0).unwrap();
+        assert_eq!(stack(&mut store), vec!["A", "B", "C"]);
 
         // Alt+Tab: switch to B (MRU previous).
-        save_to(&path, "B");
-        assert_eq!(load_from(&path).stack, vec!["B", "A", "C"]);
+        save_in(&mut store, "B", 0).unwrap();
+        assert_eq!(stack(&mut store), vec!["B", "A", "C"]);
 
         // Alt+Tab again: switch back to A.
-        save_to(&path, "A");
-        assert_eq!(load_from(&path).stack, vec!["A", "B", "C"]);
+        save_in(&mut store, "A", 0).unwrap();
+        assert_eq!(stack(&mut store), vec!["A", "B", "C"]);
 
         // 500 more times: still ping-ponging A↔B, C stays at position 2.
         for _ in 0..500 {
-            save_to(&path, "B");
-            assert_eq!(load_from(&path).current(), Some("B"));
-            assert_eq!(load_from(&path).previous(), Some("A"));
-
-            save_to(&path, "A");
-            assert_eq!(load_from(&path).current(), Some("A"));
-            assert_eq!(load_from(&path).previous(), Some("B"));
+            save_in(&mut store, "B", 0).unwrap();
+            let state = load_in(&mut store).unwrap();
+            assert_eq!(state.current(), Some("B"));
+            assert_eq!(state.previous(), Some("A"));
+
+            save_in(&mut store, "A", 0).unwrap();
+            let state = load_in(&mut store).unwrap();
+            assert_eq!(state.current(), Some("A"));
+            assert_eq!(state.previous(), Some("B"));
         }
         // C never moved.
-        assert_eq!(load_from(&path).stack[2], "C");
+        assert_eq!(stack(&mut store)[2], "C");
     }
 
     #[test]
     fn reorder_sorts_by_mru() {
-        // Simulate reorder logic directly (since reorder() calls load()
-        // which reads from the real path, not testable here).
-        let state = parse("B\nA\nC");
+        let state = stack_of(&["B", "A", "C"]);
 
         let mut items = vec!["A", "C", "B"]; // arbitrary compositor order
-        items.sort_by(|a, b| {
-            let pa = state.position(a).unwrap_or(usize::MAX);
-            let pb = state.position(b).unwrap_or(usize::MAX);
-            pa.cmp(&pb)
-        });
+        reorder(&mut items, |s| (*s).to_string(), &state);
         assert_eq!(items, vec!["B", "A", "C"]); // MRU order
     }
 
+    #[test]
+    fn remove_current_drops_only_the_focused_window() {
+        let state = stack_of(&["B", "A"]);
+        let mut items = vec!["A", "X", "B"];
+        remove_current(&mut items, |s| (*s).to_string(), &state);
+        assert_eq!(items, vec!["A", "X"]);
+
+        let mut items = vec!["A", "B"];
+        remove_current(&mut items, |s| (*s).to_string(), &MruState::default());
+        assert_eq!(items, vec!["A", "B"]);
+    }
+
     #[test]
     fn reorder_unknown_windows_go_last() {
-        let state = parse("B\nA");
+        let state = stack_of(&["B", "A"]);
 
         let mut items = ["X", "A", "B", "Y"];
-        items.sort_by(|a, b| {
-            let pa = state.position(a).unwrap_or(usize::MAX);
-            let pb = state.position(b).unwrap_or(usize::MAX);
-            pa.cmp(&pb)
-        });
-        // B(0), A(1), then X and Y (both MAX, stable relative order).
-        assert_eq!(items[0], "B");
-        assert_eq!(items[1], "A");
-        // X and Y are after, order between them is stable.
-        assert!(items[2..].contains(&"X"));
-        assert!(items[2..].contains(&"Y"));
+        reorder(&mut items, |s| (*s).to_string(), &state);
+        // B(0), A(1), then X and Y (both unknown, stable relative order).
+        assert_eq!(items, ["B", "A", "X", "Y"]);
     }
 
     #[test]
     fn stack_capped_at_max() {
-        let dir = tempfile::tempdir().unwrap();
-        let path = dir.path().join("mru");
+        let mut store = store();
 
         for i in 0..100 {
-            save_to(&path, &format!("win-{i}"));
+            save_in(&mut store, &format!("win-{i}"), 0).unwrap();
         }
-        let state = load_from(&path);
+        let state = load_in(&mut store).unwrap();
         assert_eq!(state.stack.len(), MAX_ENTRIES);
         assert_eq!(state.current(), Some("win-99"));
     }
 
     #[test]
     fn prune_removes_dead_entries() {
-        let dir = tempfile::tempdir().unwrap();
-        let path = dir.path().join("mru");
+        let mut store = store();
 
-        save_to(&path, "A");
-        save_to(&path, "B");
-        save_to(&path, "C");
-        assert_eq!(load_from(&path).stack, vec!["C", "B", "A"]);
+        save_in(&mut store, "A", 0).unwrap();
+        save_in(&mut store, "B", 0).unwrap();
+        save_in(&mut store, "C", 0).unwrap();
+        assert_eq!(stack(&mut store), vec!["C", "B", "A"]);
 
         // B was closed — only A and C are live.
-        let live: std::collections::HashSet<String> =
-            ["A", "C"].iter().map(|s| s.to_string()).collect();
-        prune_from(&path, &live);
-        assert_eq!(load_from(&path).stack, vec!["C", "A"]);
+        prune_in(&mut store, &live(&["A", "C"])).unwrap();
+        assert_eq!(stack(&mut store), vec!["C", "A"]);
     }
 
     #[test]
     fn prune_preserves_order() {
-        let dir = tempfile::tempdir().unwrap();
-        let path = dir.path().join("mru");
+        let mut store = store();
 
-        save_to(&path, "A");
-        save_to(&path, "B");
-        save_to(&path, "C");
-        save_to(&path, "D");
+        save_in(&mut store, "A", 0).unwrap();
+        save_in(&mut store, "B", 0).unwrap();
+        save_in(&mut store, "C", 0).unwrap();
+        save_in(&mut store, "D", 0).unwrap();
         // Stack: D, C, B, A. Remove C and A.
-        let live: std::collections::HashSet<String> =
-            ["D", "B"].iter().map(|s| s.to_string()).collect();
-        prune_from(&path, &live);
-        assert_eq!(load_from(&path).stack, vec!["D", "B"]);
+        prune_in(&mut store, &live(&["D", "B"])).unwrap();
+        assert_eq!(stack(&mut store), vec!["D", "B"]);
     }
 
     #[test]
     fn prune_all_dead_leaves_empty() {
-        let dir = tempfile::tempdir().unwrap();
-        let path = dir.path().join("mru");
+        let mut store = store();
 
-        save_to(&path, "A");
-        save_to(&path, "B");
+        save_in(&mut store, "A", 0).unwrap();
+        save_in(&mut store, "B", 0).unwrap();
 
-        let live: std::collections::HashSet<String> = std::collections::HashSet::new();
-        prune_from(&path, &live);
-        assert!(load_from(&path).stack.is_empty());
+        prune_in(&mut store, &HashSet::new()).unwrap();
+        assert!(stack(&mut store).is_empty());
     }
 
     #[test]
     fn prune_all_live_is_noop() {
-        let dir = tempfile::tempdir().unwrap();
-        let path = dir.path().join("mru");
+        let mut store = store();
 
-        save_to(&path, "A");
-        save_to(&path, "B");
-        save_to(&path, "C");
+        save_in(&mut store, "A", 0).unwrap();
+        save_in(&mut store, "B", 0).unwrap();
+        save_in(&mut store, "C", 0).unwrap();
 
-        let live: std::collections::HashSet<String> =
-            ["A", "B", "C"].iter().map(|s| s.to_string()).collect();
-        prune_from(&path, &live);
-        assert_eq!(load_from(&path).stack, vec!["C", "B", "A"]);
+        prune_in(&mut store, &live(&["A", "B", "C"])).unwrap();
+        assert_eq!(stack(&mut store), vec!["C", "B", "A"]);
     }
 
     #[test]
-    fn prune_no_file_is_noop() {
-        let dir = tempfile::tempdir().unwrap();
-        let path = dir.path().join("mru");
-        // File doesn't exist — prune should not panic or create it.
-        let live: std::collections::HashSet<String> = ["A"].iter().map(|s| s.to_string()).collect();
-        prune_from(&path, &live);
-        assert!(load_from(&path).stack.is_empty());
+    fn prune_empty_store_is_noop() {
+        let mut store = store();
+        prune_in(&mut store, &live(&["A"])).unwrap();
+        assert!(stack(&mut store).is_empty());
     }
 }
@@ -0,0 +1,319 @@
+//! Terminal multiplexer (tmux/zellij) rows.
+//!
+//! Key bindings with `multiplexer = "tmux"` (or `"zellij"`) get one extra
+//! overlay row per multiplexer window/tab, placed directly after the most
+//! recent window of the bound terminal app. Activating such a row focuses
+//! that terminal window and asks daemon-launcher to switch the multiplexer.
+//!
+//! daemon-wm's seccomp profile has no `execve`, so listing and switching are
+//! delegated to daemon-launcher over IPC (`MultiplexerList`/`MultiplexerSelect`).
+
+use crate::hints;
+use core_config::WmConfig;
+use core_ipc::{BusClient, BusRequester};
+use core_types::{EventKind, MultiplexerKind, MultiplexerTarget, SecurityLevel, Window};
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::Duration;
+
+/// Listed targets per multiplexer, fetched at activation time.
+pub type MultiplexerTargets = BTreeMap<MultiplexerKind, Vec<MultiplexerTarget>>;
+
+/// Upper bound on the list round-trip. The overlay shows without waiting;
+/// rows listed later than this are left out of the cycle.
+const LIST_TIMEOUT_MS: u64 = 150;
+
+/// Upper bound on the select round-trip (runs after the overlay is hidden).
+const SELECT_TIMEOUT_MS: u64 = 2000;
+
+/// Multiplexers referenced by any key binding.
+#[must_use]
+pub fn configured_kinds(config: &WmConfig) -> BTreeSet<MultiplexerKind> {
+    config
+        .key_bindings
+        .values()
+        .filter_map(|b| b.multiplexer)
+        .collect()
+}
+
+/// Fetch targets for each multiplexer from daemon-launcher, listing all of
+/// them concurrently.
+///
+/// Failures (launcher down, multiplexer not installed, no server running)
+/// yield no rows for that multiplexer — the switcher must never block on them.
+pub async fn fetch(client: &BusRequester, kinds: &BTreeSet<MultiplexerKind>) -> MultiplexerTargets {
+    let lists = futures_util::future::join_all(kinds.iter().map(|&kind| list(client, kind))).await;
+    kinds
+        .iter()
+        .copied()
+        .zip(lists)
+        .filter_map(|(kind, list)| Some((kind, list?)))
+        .collect()
+}
+
+/// Targets of one multiplexer, or `None` if listing failed.
+async fn list(client: &BusRequester, kind: MultiplexerKind) -> Option<Vec<MultiplexerTarget>> {
+    let result = client
+        .request(
+            EventKind::MultiplexerList { kind },
+            SecurityLevel::Internal,
+            Duration::from_millis(LIST_TIMEOUT_MS),
+        )
+        .await;
+    match result {
+        Ok(msg) => match msg.payload {
+            EventKind::MultiplexerListResponse {
+                targets,
+                error: None,
+            } => {
+                tracing::debug!(%kind, count = targets.len(), "multiplexer targets listed");
+                Some(targets)
+            }
+            EventKind::MultiplexerListResponse { error: Some(e), .. } => {
+                tracing::debug!(%kind, error = %e, "multiplexer list failed");
+                None
+            }
+            other => {
+                tracing::warn!(%kind, ?other, "unexpected response to MultiplexerList");
+                None
+            }
+        },
+        Err(e) => {
+            tracing::debug!(%kind, error = %e, "multiplexer list request failed");
+            None
+        }
+    }
+}
+
+/// Ask daemon-launcher to switch the multiplexer to `target`.
+pub async fn select(client: &BusClient, target: MultiplexerTarget) {
+    let label = format!("{}:{}", target.session, target.index);
+    let result = client
+        .request(
+            EventKind::MultiplexerSelect { target },
+            SecurityLevel::Internal,
+            Duration::from_millis(SELECT_TIMEOUT_MS),
+        )
+        .await;
+    match result {
+        Ok(msg) => match msg.payload {
+            EventKind::MultiplexerSelectResponse { success: true, .. } => {
+                tracing::info!(target = %label, "multiplexer window selected");
+            }
+            EventKind::MultiplexerSelectResponse { error, .. } => {
+                tracing::warn!(target = %label, ?error, "multiplexer select failed");
+            }
+            other => {
+                tracing::warn!(?other, "unexpected response to MultiplexerSelect");
+            }
+        },
+        Err(e) => tracing::warn!(target = %label, error = %e, "multiplexer select request failed"),
+    }
+}
+
+/// Title shown for a multiplexer row, e.g. `tmux main:2 vim`.
+#[must_use]
+pub fn row_title(target: &MultiplexerTarget) -> String {
+    format!(
+        "{} {}:{} {}",
+        target.kind, target.session, target.index, target.name
+    )
+}
+
+/// Insert multiplexer rows into an MRU-ordered window list.
+///
+/// Rows for a multiplexer follow the first non-origin window of an app bound
+/// to it; if only the origin matches, they go directly before the origin so
+/// the origin stays last. Rows are clones of the host window (same id, so
+/// activation focuses the terminal) with the title replaced. At most
+/// `max_rows` rows are added in total.
+///
+/// Returns the parallel multiplexer column (`None` for real windows).
+pub fn insert_rows(
+    windows: &mut Vec<Window>,
+    origin_index: Option<usize>,
+    config: &WmConfig,
+    targets: &MultiplexerTargets,
+    max_rows: usize,
+) -> Vec<Option<MultiplexerTarget>> {
+    let kind_of = |w: &Window| {
+        hints::binding_for_app(w.app_id.as_str(), &config.key_bindings)
+            .and_then(|(_, b)| b.multiplexer)
+    };
+
+    let mut hosts: BTreeMap<usize, MultiplexerKind> = BTreeMap::new();
+    for &kind in targets.keys() {
+        let host = windows
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| Some(*i) != origin_index)
+            .find(|(_, w)| kind_of(w) == Some(kind))
+            .map(|(i, _)| i)
+            .or_else(|| origin_index.filter(|&o| kind_of(&windows[o]) == Some(kind)));
+        if let Some(i) = host {
+            hosts.insert(i, kind);
+        }
+    }
+
+    let mut budget = max_rows;
+    let mut out_windows = Vec::with_capacity(windows.len());
+    let mut out_targets = Vec::with_capacity(windows.len());
+    for (i, w) in std::mem::take(windows).into_iter().enumerate() {
+        let rows: Vec<(Window, MultiplexerTarget)> = hosts
+            .get(&i)
+            .and_then(|kind| targets.get(kind))
+            .map(|list| {
+                let take = list.len().min(budget);
+                budget -= take;
+                list[..take]
+                    .iter()
+                    .map(|t| {
+                        let mut row = w.clone();
+                        row.title = row_title(t);
+                        row.is_focused = false;
+                        (row, t.clone())
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let (rows, row_targets): (Vec<_>, Vec<_>) =
+            rows.into_iter().map(|(row, t)| (row, Some(t))).unzip();
+        if Some(i) == origin_index {
+            out_windows.extend(rows);
+            out_targets.extend(row_targets);
+            out_windows.push(w);
+            out_targets.push(None);
+        } else {
+            out_windows.push(w);
+            out_targets.push(None);
+            out_windows.extend(rows);
+            out_targets.extend(row_targets);
+        }
+    }
+    *windows = out_windows;
+    out_targets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core_config::WmKeyBinding;
+
+    fn window(app_id: &str) -> Window {
+        Window {
+            id: core_types::WindowId::new(),
+            app_id: core_types::AppId::new(app_id),
+            title: app_id.into(),
+            workspace_id: core_types::CompositorWorkspaceId::new(),
+            monitor_id: core_types::MonitorId::new(),
+            geometry: core_types::Geometry {
+                x: 0,
+                y: 0,
+                width: 800,
+                height: 600,
+            },
+            is_focused: false,
+            is_minimized: false,
+            is_fullscreen: false,
+            is_maximized: false,
+            profile_id: core_types::ProfileId::new(),
+            parent: None,
+            is_sticky: false,
+            is_keep_above: false,
+        }
+    }
+
+    fn config() -> WmConfig {
+        let mut config = WmConfig::default();
+        config.key_bindings.insert(
+            "g".into(),
+            WmKeyBinding {
+                apps: vec!["com.mitchellh.ghostty".into()],
+                launch: Some("ghostty".into()),
+                multiplexer: Some(MultiplexerKind::Tmux),
+                ..Default::default()
+            },
+        );
+        config
+    }
+
+    fn tmux(session: &str, index: u32, name: &str) -> MultiplexerTarget {
+        MultiplexerTarget {
+            kind: MultiplexerKind::Tmux,
+            session: session.into(),
+            index,
+            name: name.into(),
+            active: false,
+        }
+    }
+
+    fn targets() -> MultiplexerTargets {
+        [(
+            MultiplexerKind::Tmux,
+            vec![tmux("main", 1, "vim"), tmux("main", 2, "logs")],
+        )]
+        .into_iter()
+        .collect()
+    }
+
+    #[test]
+    fn configured_kinds_collects_bindings() {
+        let kinds = configured_kinds(&config());
+        assert_eq!(
+            kinds.into_iter().collect::<Vec<_>>(),
+            vec![MultiplexerKind::Tmux]
+        );
+        assert!(configured_kinds(&WmConfig::default()).is_empty());
+    }
+
+    #[test]
+    fn rows_follow_host_window() {
+        let mut windows = vec![
+            window("firefox"),
+            window("com.mitchellh.ghostty"),
+            window("microsoft-edge"),
+        ];
+        let host_id = windows[1].id;
+        let column = insert_rows(&mut windows, Some(2), &config(), &targets(), 10);
+
+        assert_eq!(windows.len(), 5);
+        assert_eq!(column.len(), 5);
+        assert_eq!(windows[2].id, host_id, "row activates the terminal window");
+        assert_eq!(windows[2].title, "tmux main:1 vim");
+        assert_eq!(windows[3].title, "tmux main:2 logs");
+        assert_eq!(
+            windows[4].app_id.as_str(),
+            "microsoft-edge",
+            "origin stays last"
+        );
+        assert!(column[0].is_none() && column[1].is_none() && column[4].is_none());
+        assert_eq!(column[3].as_ref().map(|t| t.index), Some(2));
+    }
+
+    #[test]
+    fn rows_precede_origin_host() {
+        let mut windows = vec![window("firefox"), window("com.mitchellh.ghostty")];
+        let column = insert_rows(&mut windows, Some(1), &config(), &targets(), 10);
+
+        assert_eq!(windows.len(), 4);
+        assert_eq!(windows[3].title, "com.mitchellh.ghostty");
+        assert!(column[3].is_none());
+        assert!(column[1].is_some() && column[2].is_some());
+    }
+
+    #[test]
+    fn rows_respect_budget() {
+        let mut windows = vec![window("com.mitchellh.ghostty")];
+        let column = insert_rows(&mut windows, None, &config(), &targets(), 1);
+        assert_eq!(windows.len(), 2);
+        assert_eq!(column.iter().filter(|t| t.is_some()).count(), 1);
+    }
+
+    #[test]
+    fn no_host_no_rows() {
+        let mut windows = vec![window("firefox")];
+        let column = insert_rows(&mut windows, None, &config(), &targets(), 10);
+        assert_eq!(windows.len(), 1);
+        assert_eq!(column, vec![None]);
+    }
+}
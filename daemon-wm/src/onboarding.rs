@@ -0,0 +1,212 @@
+//! First-run walkthrough shown in the overlay.
+//!
+//! When daemon-wm starts with no config file and an empty state store, the
+//! first activation shows a few pages in place of the picker: how the
+//! switcher opens, how hints work, how to bind keys to apps, and where the
+//! config lives. Enter (or Down, or Space) turns the page, Up or Backspace
+//! goes back, Escape closes the walkthrough until the daemon next starts,
+//! and `d` — or finishing the last page — closes it for good.
+//!
+//! Progress is the `onboarding` setting in the state store: `pending` from
+//! the first run until the walkthrough is done, then `done`, so it comes
+//! back after an Escape and a restart but never once dismissed.
+
+use crate::actions;
+use crate::controller::Event;
+use crate::search::SEARCH_PREFIX;
+use core_config::WmConfig;
+use core_state::StateStore;
+use std::path::Path;
+
+/// State store setting holding the walkthrough's progress.
+const SETTING: &str = "onboarding";
+const PENDING: &str = "pending";
+const DONE: &str = "done";
+
+/// Typed on any page, closes the walkthrough for good.
+pub const DONT_SHOW_AGAIN: char = 'd';
+
+/// Whether the walkthrough is to be shown: it is pending from an earlier
+/// run, or this is the first run (`config_found` is false and nothing is
+/// recorded yet), which marks it pending. False if the store is
+/// unavailable, as the choice could not be remembered.
+pub fn pending(config_found: bool) -> bool {
+    crate::state::with(|store| pending_in(store, config_found)).unwrap_or(false)
+}
+
+fn pending_in(store: &StateStore, config_found: bool) -> core_types::Result<bool> {
+    match store.setting(SETTING)?.as_deref() {
+        Some(DONE) => Ok(false),
+        Some(_) => Ok(true),
+        None if !config_found && store.is_empty()? => {
+            store.set_setting(SETTING, PENDING)?;
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+/// Never show the walkthrough again.
+pub fn mark_done() {
+    crate::state::write(|store| store.set_setting(SETTING, DONE));
+}
+
+/// A walkthrough page.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Page {
+    pub title: String,
+    pub lines: Vec<String>,
+}
+
+/// The walkthrough for `config`, naming `config_file` as the place to
+/// change it.
+#[must_use]
+pub fn pages(config: &WmConfig, config_file: &Path) -> Vec<Page> {
+    let page = |title: &str, lines: &[&str]| Page {
+        title: title.into(),
+        lines: lines.iter().map(|&line| line.into()).collect(),
+    };
+    let first_hints: String = config.hint_keys.chars().take(3).collect();
+    let help_keys = actions::keys_for(&Event::Help)
+        .unwrap_or_default()
+        .replace(", ", " or ");
+    vec![
+        page(
+            "Welcome to Open Sesame",
+            &[
+                "Alt+Tab switches to the window you used before this one.",
+                "Keep holding Alt to see every window, then let go to switch",
+                "to the highlighted one. Alt+Space opens the picker without switching.",
+            ],
+        ),
+        Page {
+            title: "Hints".into(),
+            lines: vec![
+                format!("Every window gets a letter hint ({first_hints}…)."),
+                "Type it to switch to that window at once.".into(),
+                format!("Type {SEARCH_PREFIX} to search window titles instead."),
+            ],
+        },
+        page(
+            "Your keys",
+            &[
+                "Give an app its own letter in the config, and it keeps it:",
+                "",
+                "[profiles.default.wm.key_bindings.f]",
+                "apps = [\"firefox\"]",
+                "launch = \"firefox\"",
+                "",
+                "Alt+Tab, then f, switches to Firefox, or starts it.",
+            ],
+        ),
+        Page {
+            title: "Settings".into(),
+            lines: vec![
+                "The config lives at".into(),
+                config_file.display().to_string(),
+                "and changes apply as soon as it is saved.".into(),
+                String::new(),
+                format!("Press {help_keys} in the picker to see every key."),
+            ],
+        },
+    ]
+}
+
+/// The walkthrough page as the overlay draws it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WalkthroughView {
+    pub page: Page,
+    /// Zero-based page number.
+    pub index: usize,
+    pub count: usize,
+}
+
+/// The walkthrough's state in the controller.
+#[derive(Debug, Clone)]
+pub struct Walkthrough {
+    pages: Vec<Page>,
+    index: usize,
+}
+
+impl Walkthrough {
+    #[must_use]
+    pub fn new(pages: Vec<Page>) -> Self {
+        Self { pages, index: 0 }
+    }
+
+    /// Turn to the next page. False on the last page.
+    pub fn turn_forward(&mut self) -> bool {
+        if self.index + 1 < self.pages.len() {
+            self.index += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Turn back a page, staying on the first.
+    pub fn turn_back(&mut self) {
+        self.index = self.index.saturating_sub(1);
+    }
+
+    /// The current page, or `None` for a walkthrough without pages.
+    #[must_use]
+    pub fn view(&self) -> Option<WalkthroughView> {
+        Some(WalkthroughView {
+            page: self.pages.get(self.index)?.clone(),
+            index: self.index,
+            count: self.pages.len(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_run_stays_pending_until_done() {
+        let store = StateStore::open_in_memory().unwrap();
+        assert!(
+            !pending_in(&store, true).unwrap(),
+            "a config file means this is not a first run"
+        );
+        assert!(pending_in(&store, false).unwrap());
+        assert!(
+            pending_in(&store, true).unwrap(),
+            "pending survives the config being written"
+        );
+        store.set_setting(SETTING, DONE).unwrap();
+        assert!(!pending_in(&store, false).unwrap());
+
+        let mut used = StateStore::open_in_memory().unwrap();
+        used.update_marks(|marks| marks.insert('a', "win".into()).is_none())
+            .unwrap();
+        assert!(!pending_in(&used, false).unwrap(), "state is recorded");
+    }
+
+    #[test]
+    fn pages_turn_within_bounds() {
+        let mut walkthrough = Walkthrough::new(pages(
+            &WmConfig::default(),
+            Path::new("/home/me/.config/pds/config.toml"),
+        ));
+        walkthrough.turn_back();
+        assert_eq!(walkthrough.view().unwrap().index, 0);
+        while walkthrough.turn_forward() {}
+        let last = walkthrough.view().unwrap();
+        assert_eq!(last.index + 1, last.count);
+        assert!(
+            last.page
+                .lines
+                .iter()
+                .any(|line| line.ends_with("config.toml"))
+        );
+        assert!(
+            last.page
+                .lines
+                .iter()
+                .any(|line| line == "Press F1 or ? in the picker to see every key.")
+        );
+    }
+}
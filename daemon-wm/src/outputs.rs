@@ -0,0 +1,86 @@
+//! Output rows (`output_rows`) and moving windows between outputs (Ctrl+O).
+//!
+//! With `output_rows = true` the overlay lists the outputs (monitors) after
+//! the window and workspace rows as [`SelectableItem::Output`] items (see
+//! `items`), by connector name and description ("DP-1 — Dell U2720Q").
+//! Activating one focuses the most recently used window on that output, so
+//! focus crosses monitors with a hint and Enter.
+//!
+//! Ctrl+O on a window row, followed by an output's number, moves the
+//! window to the active workspace of that output and switches to it. Only
+//! compositors with a toplevel move-to-output request support it (COSMIC's
+//! `move_to_ext_workspace`).
+
+use crate::items::stand_in;
+use core_types::{MonitorId, Output, SelectableItem, Window, WindowId};
+
+/// App id of output rows, for hints and the row label.
+pub const OUTPUT_APP_ID: &str = "output";
+
+/// The first window row on `output`, in row order: the most recently used
+/// window there, or the origin when it is the only one.
+#[must_use]
+pub fn first_window_on(
+    windows: &[Window],
+    items: &[SelectableItem],
+    output: &MonitorId,
+) -> Option<usize> {
+    windows
+        .iter()
+        .zip(items)
+        .position(|(w, item)| *item == SelectableItem::Window && w.monitor_id == *output)
+}
+
+/// The title of an output's row.
+#[must_use]
+pub fn title(output: &Output) -> String {
+    if output.description.is_empty() {
+        output.name.clone()
+    } else {
+        format!("{} — {}", output.name, output.description)
+    }
+}
+
+/// The stand-in window an output row is listed with.
+pub(crate) fn row_window(output: &Output) -> Window {
+    Window {
+        id: WindowId::from_uuid(*output.id.as_uuid()),
+        monitor_id: output.id,
+        ..stand_in(OUTPUT_APP_ID, title(output))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn output(name: &str, description: &str) -> Output {
+        Output {
+            id: MonitorId::new(),
+            name: name.into(),
+            description: description.into(),
+        }
+    }
+
+    #[test]
+    fn rows_are_titled_and_find_the_windows_on_their_output() {
+        let outputs = [output("DP-1", "Dell U2720Q"), output("HDMI-A-1", "")];
+        let mut firefox = stand_in("firefox", "Inbox".into());
+        firefox.monitor_id = outputs[1].id;
+        let mut windows = vec![firefox];
+        let mut items = vec![SelectableItem::Window];
+        let extra: Vec<_> = outputs
+            .iter()
+            .cloned()
+            .map(SelectableItem::Output)
+            .collect();
+        crate::items::append_rows(&mut windows, &mut items, None, &extra, 10);
+
+        let titles: Vec<&str> = windows.iter().map(|w| w.title.as_str()).collect();
+        assert_eq!(titles, ["Inbox", "DP-1 — Dell U2720Q", "HDMI-A-1"]);
+        assert_eq!(items[2], SelectableItem::Output(outputs[1].clone()));
+        // Output rows carry their output's id, but are not windows on it.
+        assert_eq!(first_window_on(&windows, &items, &outputs[1].id), Some(0));
+        assert_eq!(first_window_on(&windows, &items, &outputs[0].id), None);
+    }
+}
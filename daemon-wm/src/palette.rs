@@ -0,0 +1,235 @@
+//! Command palette: calculator and shell commands typed into the overlay.
+//!
+//! Enabled by `command_palette = true` in the WM config. Input starting with
+//! `=` is evaluated as an arithmetic expression and the result shown inline;
+//! input starting with `>` is a shell command that runs only after Enter is
+//! pressed twice. Nothing here executes anything — the controller stages the
+//! command and daemon-launcher runs it.
+
+/// Prefix for calculator expressions.
+pub const CALC_PREFIX: char = '=';
+
+/// Prefix for shell commands.
+pub const SHELL_PREFIX: char = '>';
+
+/// Parsed command-palette input.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PaletteInput<'a> {
+    /// `=expr`
+    Calc(&'a str),
+    /// `>command` (trimmed; may be empty while typing)
+    Shell(&'a str),
+}
+
+/// Classify overlay input. Returns `None` for ordinary hint/launch input.
+#[must_use]
+pub fn parse(input: &str) -> Option<PaletteInput<'_>> {
+    if let Some(expr) = input.strip_prefix(CALC_PREFIX) {
+        Some(PaletteInput::Calc(expr))
+    } else {
+        input
+            .strip_prefix(SHELL_PREFIX)
+            .map(|cmd| PaletteInput::Shell(cmd.trim()))
+    }
+}
+
+/// Message shown in the overlay for palette input.
+///
+/// `confirming` is true once Enter has been pressed for a shell command.
+#[must_use]
+pub fn message(input: &PaletteInput<'_>, confirming: bool) -> String {
+    match input {
+        PaletteInput::Calc(expr) if expr.trim().is_empty() => "Type an expression".into(),
+        PaletteInput::Calc(expr) => match evaluate(expr) {
+            Ok(value) => format!("{} = {}", expr.trim(), format_number(value)),
+            Err(e) => format!("{}: {e}", expr.trim()),
+        },
+        PaletteInput::Shell("") => "Type a command".into(),
+        PaletteInput::Shell(cmd) if confirming => {
+            format!("Run \u{2018}{cmd}\u{2019}? Enter to confirm, Esc to cancel")
+        }
+        PaletteInput::Shell(cmd) => format!("Press Enter to run \u{2018}{cmd}\u{2019}"),
+    }
+}
+
+/// Evaluate an arithmetic expression.
+///
+/// Supports `+ - * / %`, `^` (right-associative), unary minus, parentheses,
+/// and decimal literals.
+pub fn evaluate(expr: &str) -> Result<f64, String> {
+    let mut parser = Parser {
+        chars: expr.chars().filter(|c| !c.is_whitespace()).collect(),
+        pos: 0,
+    };
+    let value = parser.expr()?;
+    if let Some(c) = parser.peek() {
+        return Err(format!("unexpected \u{2018}{c}\u{2019}"));
+    }
+    if value.is_finite() {
+        Ok(value)
+    } else {
+        Err("result is not a finite number".into())
+    }
+}
+
+/// Format a result without a trailing `.0` for integral values.
+#[must_use]
+pub fn format_number(value: f64) -> String {
+    if value.fract() == 0.0 && value.abs() < 1e15 {
+        format!("{value:.0}")
+    } else {
+        let s = format!("{value:.10}");
+        s.trim_end_matches('0').trim_end_matches('.').to_string()
+    }
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expr(&mut self) -> Result<f64, String> {
+        let mut value = self.term()?;
+        loop {
+            if self.eat('+') {
+                value += self.term()?;
+            } else if self.eat('-') {
+                value -= self.term()?;
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+
+    fn term(&mut self) -> Result<f64, String> {
+        let mut value = self.unary()?;
+        loop {
+            if self.eat('*') {
+                value *= self.unary()?;
+            } else if self.eat('/') {
+                let rhs = self.unary()?;
+                if rhs == 0.0 {
+                    return Err("division by zero".into());
+                }
+                value /= rhs;
+            } else if self.eat('%') {
+                let rhs = self.unary()?;
+                if rhs == 0.0 {
+                    return Err("division by zero".into());
+                }
+                value %= rhs;
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+
+    fn unary(&mut self) -> Result<f64, String> {
+        if self.eat('-') {
+            Ok(-self.unary()?)
+        } else if self.eat('+') {
+            self.unary()
+        } else {
+            self.power()
+        }
+    }
+
+    fn power(&mut self) -> Result<f64, String> {
+        let base = self.primary()?;
+        if self.eat('^') {
+            Ok(base.powf(self.unary()?))
+        } else {
+            Ok(base)
+        }
+    }
+
+    fn primary(&mut self) -> Result<f64, String> {
+        if self.eat('(') {
+            let value = self.expr()?;
+            if !self.eat(')') {
+                return Err("missing \u{2018})\u{2019}".into());
+            }
+            return Ok(value);
+        }
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_ascii_digit() || c == '.') {
+            self.pos += 1;
+        }
+        if start == self.pos {
+            return match self.peek() {
+                Some(c) => Err(format!("unexpected \u{2018}{c}\u{2019}")),
+                None => Err("incomplete expression".into()),
+            };
+        }
+        let literal: String = self.chars[start..self.pos].iter().collect();
+        literal
+            .parse()
+            .map_err(|_| format!("invalid number \u{2018}{literal}\u{2019}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_prefixes() {
+        assert_eq!(parse("=2+2"), Some(PaletteInput::Calc("2+2")));
+        assert_eq!(
+            parse(">systemctl suspend "),
+            Some(PaletteInput::Shell("systemctl suspend"))
+        );
+        assert_eq!(parse("gg"), None);
+        assert_eq!(parse(""), None);
+    }
+
+    #[test]
+    fn evaluate_precedence() {
+        assert_eq!(evaluate("2+2"), Ok(4.0));
+        assert_eq!(evaluate("2 + 3 * 4"), Ok(14.0));
+        assert_eq!(evaluate("(2+3)*4"), Ok(20.0));
+        assert_eq!(evaluate("2^3^2"), Ok(512.0));
+        assert_eq!(evaluate("-2^2"), Ok(-4.0));
+        assert_eq!(evaluate("10 % 4"), Ok(2.0));
+        assert_eq!(evaluate("1.5*2"), Ok(3.0));
+    }
+
+    #[test]
+    fn evaluate_errors() {
+        assert!(evaluate("1/0").is_err());
+        assert!(evaluate("2+").is_err());
+        assert!(evaluate("(1").is_err());
+        assert!(evaluate("1)").is_err());
+        assert!(evaluate("abc").is_err());
+        assert!(evaluate("1..2").is_err());
+    }
+
+    #[test]
+    fn number_formatting() {
+        assert_eq!(format_number(4.0), "4");
+        assert_eq!(format_number(-12.0), "-12");
+        assert_eq!(format_number(0.5), "0.5");
+        assert_eq!(format_number(1.0 / 3.0), "0.3333333333");
+    }
+
+    #[test]
+    fn messages() {
+        assert_eq!(message(&PaletteInput::Calc("2+2"), false), "2+2 = 4");
+        assert!(message(&PaletteInput::Shell("ls"), false).contains("Enter to run"));
+        assert!(message(&PaletteInput::Shell("ls"), true).contains("confirm"));
+    }
+}
@@ -0,0 +1,39 @@
+//! Power-saver throttling (`wm.power_saving`).
+//!
+//! While throttled, the overlay thread polls for input less often and
+//! skips the armed-phase delay ring animation. The main loop combines the
+//! config mode with the power profile reported by power-profiles-daemon
+//! and sends `OverlayCmd::SetPowerSaving` when the outcome changes.
+
+use core_config::PowerSaving;
+
+/// `ActiveProfile` of power-profiles-daemon when the battery saver is on.
+pub const POWER_SAVER_PROFILE: &str = "power-saver";
+
+/// Whether the overlay should throttle rendering under `mode`, given the
+/// active power profile (`None` when power-profiles-daemon is unavailable).
+pub fn throttled(mode: PowerSaving, profile: Option<&str>) -> bool {
+    match mode {
+        PowerSaving::Auto => profile == Some(POWER_SAVER_PROFILE),
+        PowerSaving::On => true,
+        PowerSaving::Off => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn auto_follows_power_saver_profile() {
+        assert!(throttled(PowerSaving::Auto, Some("power-saver")));
+        assert!(!throttled(PowerSaving::Auto, Some("balanced")));
+        assert!(!throttled(PowerSaving::Auto, None));
+    }
+
+    #[test]
+    fn config_overrides_profile() {
+        assert!(throttled(PowerSaving::On, None));
+        assert!(!throttled(PowerSaving::Off, Some("power-saver")));
+    }
+}
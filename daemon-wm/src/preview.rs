@@ -0,0 +1,91 @@
+//! Sample picker contents for theme previews (`sesame theme preview`).
+//!
+//! Previews use a fixed window list rather than the live one, so images of
+//! different themes are comparable and never contain real window titles.
+//! Building them does not touch the MRU or window-order state.
+
+use crate::app_names;
+use crate::hints;
+use crate::overlay::WindowInfo;
+use core_config::{TransientWindows, WmConfig};
+
+/// App ID, title and whether the window is a dialog of the row above.
+const SAMPLE_WINDOWS: &[(&str, &str, bool)] = &[
+    (
+        "org.mozilla.firefox",
+        "The Rust Programming Language - Mozilla Firefox",
+        false,
+    ),
+    ("com.mitchellh.ghostty", "~/src/open-sesame", false),
+    (
+        "code",
+        "controller.rs - open-sesame - Visual Studio Code",
+        false,
+    ),
+    ("org.mozilla.firefox", "Inbox - Mozilla Firefox", false),
+    ("org.gnome.Nautilus", "Downloads", false),
+    ("org.gnome.Nautilus", "Copying 3 files", true),
+    ("slack", "#general - Slack", false),
+];
+
+/// Picker rows and hints for the sample windows under `config`'s key
+/// bindings.
+#[must_use]
+pub fn sample_picker(config: &WmConfig) -> (Vec<WindowInfo>, Vec<String>) {
+    let samples: Vec<&(&str, &str, bool)> = SAMPLE_WINDOWS
+        .iter()
+        .filter(|(_, _, dialog)| !(*dialog && config.transient_windows == TransientWindows::Hide))
+        .collect();
+    let app_ids: Vec<&str> = samples.iter().map(|(app, _, _)| *app).collect();
+    let ranks: Vec<usize> = (0..app_ids.len()).collect();
+    let hint_strings = hints::assign_app_hints(&app_ids, &config.key_bindings)
+        .into_iter()
+        .map(|(hint, _)| hint)
+        .collect();
+    let titles = core_config::TitleRewriter::new(&config.title_rules);
+    let names = app_names::current();
+    let windows = samples
+        .into_iter()
+        .zip(hints::app_positions(&app_ids, &ranks))
+        .map(|(&(app_id, title, dialog), position)| {
+            let (label, icon) = hints::label_and_icon(app_id, &config.key_bindings);
+            let label = label.or_else(|| names.name_for(app_id));
+            WindowInfo {
+                app_id: app_id.into(),
+                title: titles.rewrite(title).into_owned(),
+                position,
+                pinned: false,
+                nested: dialog && config.transient_windows == TransientWindows::Nest,
+                sticky: false,
+                keep_above: false,
+                minimized: false,
+                label,
+                icon,
+                focused_ago: None,
+                geometry: None,
+            }
+        })
+        .collect();
+    (windows, hint_strings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sample_rows_use_configured_hints() {
+        let (windows, hints) = sample_picker(&WmConfig::default());
+        assert_eq!(windows.len(), hints.len());
+        assert_eq!(hints[0], "f");
+        assert_eq!(hints[3], "ff");
+        assert_eq!(windows[3].position.index, 2);
+        assert!(windows[5].nested);
+
+        let hide = WmConfig {
+            transient_windows: TransientWindows::Hide,
+            ..WmConfig::default()
+        };
+        assert_eq!(sample_picker(&hide).0.len(), SAMPLE_WINDOWS.len() - 1);
+    }
+}
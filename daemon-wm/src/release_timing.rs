@@ -0,0 +1,32 @@
+//! Learned Alt-release timing for `wm.adaptive_quick_switch`.
+//!
+//! Each plain Alt+Tab — released on the initial selection with nothing
+//! typed — adds how long Alt was held to the state store
+//! (`release_timings` table). Once enough are recorded,
+//! [`ReleaseTimings::learned_threshold_ms`] replaces the configured
+//! quick-switch threshold, so slow releasers stop seeing the picker flash
+//! up and fast releasers see it sooner when they do hold Alt.
+
+use core_types::ReleaseTimings;
+use std::time::Duration;
+
+/// The recorded timings, or none if the store is unavailable.
+pub fn load() -> ReleaseTimings {
+    crate::state::with(|store| store.release_timings()).unwrap_or_default()
+}
+
+/// Add a release held for `elapsed` and return the updated timings.
+pub fn record(elapsed: Duration) -> ReleaseTimings {
+    let ms = u32::try_from(elapsed.as_millis()).unwrap_or(u32::MAX);
+    let mut timings = load();
+    timings.record(ms);
+    crate::state::write(move |store| {
+        store.update_release_timings(|timings| {
+            let before = timings.clone();
+            timings.record(ms);
+            *timings != before
+        })?;
+        Ok(())
+    });
+    timings
+}
@@ -0,0 +1,296 @@
+//! Color primitives shared by the theme and the renderer.
+//!
+//! Channels are `f64` in `0.0..=1.0` holding sRGB-encoded values, the same
+//! encoding as config hex strings (`#RRGGBB[AA]`) and as drawn. Conversions
+//! to tiny-skia, cosmic-text and COSMIC theme colors are `From` impls.
+//! [`Color::over`] composites a translucent color onto a backdrop, the HSL
+//! helpers adjust lightness without shifting hue, and [`contrast_ratio`]
+//! implements the WCAG 2 contrast formula.
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Color {
+    pub r: f64,
+    pub g: f64,
+    pub b: f64,
+    pub a: f64,
+}
+
+impl Color {
+    pub const TRANSPARENT: Self = Self::rgba(0, 0, 0, 0);
+
+    pub const fn rgba(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Self {
+            r: r as f64 / 255.0,
+            g: g as f64 / 255.0,
+            b: b as f64 / 255.0,
+            a: a as f64 / 255.0,
+        }
+    }
+
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Self::rgba(r, g, b, 255)
+    }
+
+    pub fn from_hex(hex: &str) -> Option<Self> {
+        let hex = hex.strip_prefix('#').unwrap_or(hex);
+        match hex.len() {
+            6 => {
+                let r = u8::from_str_radix(&hex[0..2], 16).ok()?;
+                let g = u8::from_str_radix(&hex[2..4], 16).ok()?;
+                let b = u8::from_str_radix(&hex[4..6], 16).ok()?;
+                Some(Self::rgba(r, g, b, 255))
+            }
+            8 => {
+                let r = u8::from_str_radix(&hex[0..2], 16).ok()?;
+                let g = u8::from_str_radix(&hex[2..4], 16).ok()?;
+                let b = u8::from_str_radix(&hex[4..6], 16).ok()?;
+                let a = u8::from_str_radix(&hex[6..8], 16).ok()?;
+                Some(Self::rgba(r, g, b, a))
+            }
+            _ => None,
+        }
+    }
+
+    /// `#rrggbb`, or `#rrggbbaa` when not fully opaque.
+    pub fn to_hex(self) -> String {
+        let [r, g, b, a] = self.to_rgba8();
+        if a == 255 {
+            format!("#{r:02x}{g:02x}{b:02x}")
+        } else {
+            format!("#{r:02x}{g:02x}{b:02x}{a:02x}")
+        }
+    }
+
+    /// Channels rounded to 8 bits.
+    pub fn to_rgba8(self) -> [u8; 4] {
+        [
+            channel_u8(self.r),
+            channel_u8(self.g),
+            channel_u8(self.b),
+            channel_u8(self.a),
+        ]
+    }
+
+    pub fn brightened(&self, amount: f64) -> Self {
+        Self {
+            r: (self.r + amount).min(1.0),
+            g: (self.g + amount).min(1.0),
+            b: (self.b + amount).min(1.0),
+            a: self.a,
+        }
+    }
+
+    /// The same color with alpha `a`.
+    pub fn with_alpha(self, a: f64) -> Self {
+        Self {
+            a: a.clamp(0.0, 1.0),
+            ..self
+        }
+    }
+
+    /// The same color with its alpha multiplied by `factor`.
+    pub fn faded(self, factor: f64) -> Self {
+        self.with_alpha(self.a * factor)
+    }
+
+    /// Composite `self` onto `backdrop` (Porter-Duff source-over).
+    pub fn over(self, backdrop: Self) -> Self {
+        let a = self.a + backdrop.a * (1.0 - self.a);
+        if a <= 0.0 {
+            return Self::TRANSPARENT;
+        }
+        let mix = |src: f64, dst: f64| (src * self.a + dst * backdrop.a * (1.0 - self.a)) / a;
+        Self {
+            r: mix(self.r, backdrop.r),
+            g: mix(self.g, backdrop.g),
+            b: mix(self.b, backdrop.b),
+            a,
+        }
+    }
+
+    /// Hue in degrees (`0.0..360.0`), saturation and lightness.
+    pub fn to_hsl(self) -> (f64, f64, f64) {
+        let max = self.r.max(self.g).max(self.b);
+        let min = self.r.min(self.g).min(self.b);
+        let l = (max + min) / 2.0;
+        let d = max - min;
+        if d <= f64::EPSILON {
+            return (0.0, 0.0, l);
+        }
+        let s = d / (1.0 - (2.0 * l - 1.0).abs());
+        let h = if (max - self.r).abs() <= f64::EPSILON {
+            ((self.g - self.b) / d).rem_euclid(6.0)
+        } else if (max - self.g).abs() <= f64::EPSILON {
+            (self.b - self.r) / d + 2.0
+        } else {
+            (self.r - self.g) / d + 4.0
+        };
+        (h * 60.0, s, l)
+    }
+
+    /// Color from hue in degrees, saturation, lightness and alpha.
+    pub fn from_hsl(h: f64, s: f64, l: f64, a: f64) -> Self {
+        let s = s.clamp(0.0, 1.0);
+        let l = l.clamp(0.0, 1.0);
+        let c = (1.0 - (2.0 * l - 1.0).abs()) * s;
+        let h = h.rem_euclid(360.0) / 60.0;
+        let x = c * (1.0 - (h.rem_euclid(2.0) - 1.0).abs());
+        let (r, g, b) = match h {
+            h if h < 1.0 => (c, x, 0.0),
+            h if h < 2.0 => (x, c, 0.0),
+            h if h < 3.0 => (0.0, c, x),
+            h if h < 4.0 => (0.0, x, c),
+            h if h < 5.0 => (x, 0.0, c),
+            _ => (c, 0.0, x),
+        };
+        let m = l - c / 2.0;
+        Self {
+            r: r + m,
+            g: g + m,
+            b: b + m,
+            a: a.clamp(0.0, 1.0),
+        }
+    }
+
+    /// Shift HSL lightness by `delta` (negative darkens), keeping hue,
+    /// saturation and alpha.
+    pub fn lightened(self, delta: f64) -> Self {
+        let (h, s, l) = self.to_hsl();
+        Self::from_hsl(h, s, l + delta, self.a)
+    }
+
+    /// WCAG relative luminance of the RGB channels; alpha is ignored.
+    pub fn relative_luminance(self) -> f64 {
+        core_config::relative_luminance(self.r, self.g, self.b)
+    }
+}
+
+/// WCAG contrast ratio of two colors, from 1 (identical) to 21 (black on
+/// white). Alpha is ignored, so composite translucent colors onto what is
+/// behind them with [`Color::over`] first.
+pub fn contrast_ratio(a: Color, b: Color) -> f64 {
+    let (la, lb) = (a.relative_luminance(), b.relative_luminance());
+    (la.max(lb) + 0.05) / (la.min(lb) + 0.05)
+}
+
+/// `text` with its lightness shifted away from `background` until their
+/// contrast reaches `min`, or `None` if it already does.
+///
+/// Text lightens on backgrounds darker than mid-grey and darkens on lighter
+/// ones, keeping its hue. Pure white or black clears any `min` up to 4.5
+/// against the respective side, so the loop always terminates in range.
+pub fn ensure_contrast(text: Color, background: Color, min: f64) -> Option<Color> {
+    if contrast_ratio(text, background) >= min {
+        return None;
+    }
+    // Luminance at which white and black contrast equally.
+    let step = if background.relative_luminance() < 0.179 {
+        0.02
+    } else {
+        -0.02
+    };
+    let mut adjusted = text;
+    for _ in 0..50 {
+        adjusted = adjusted.lightened(step);
+        if contrast_ratio(adjusted, background) >= min {
+            break;
+        }
+    }
+    Some(adjusted)
+}
+
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)] // clamped to 0..=255
+fn channel_u8(value: f64) -> u8 {
+    (value.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+impl From<Color> for tiny_skia::Color {
+    #[allow(clippy::cast_possible_truncation)] // f32 precision is plenty for 8-bit output
+    fn from(c: Color) -> Self {
+        Self::from_rgba(c.r as f32, c.g as f32, c.b as f32, c.a as f32).unwrap_or(Self::TRANSPARENT)
+    }
+}
+
+impl From<Color> for cosmic_text::Color {
+    fn from(c: Color) -> Self {
+        let [r, g, b, a] = c.to_rgba8();
+        Self::rgba(r, g, b, a)
+    }
+}
+
+#[cfg(all(target_os = "linux", feature = "cosmic"))]
+impl From<platform_linux::cosmic_theme::ThemeColor> for Color {
+    fn from(c: platform_linux::cosmic_theme::ThemeColor) -> Self {
+        Self::rgba(c.r, c.g, c.b, c.a)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-3
+    }
+
+    #[test]
+    fn hex_round_trips() {
+        assert_eq!(Color::from_hex("#89b4fa").unwrap().to_hex(), "#89b4fa");
+        assert_eq!(Color::from_hex("1e1e1e80").unwrap().to_hex(), "#1e1e1e80");
+        assert!(Color::from_hex("#12345").is_none());
+    }
+
+    #[test]
+    fn over_composites_source_over() {
+        let black = Color::rgb(0, 0, 0);
+        let half_white = Color::rgb(255, 255, 255).with_alpha(0.5);
+        let grey = half_white.over(black);
+        assert!(close(grey.r, 0.5) && close(grey.a, 1.0));
+        // Two half-transparent layers cover three quarters.
+        assert!(close(half_white.over(half_white).a, 0.75));
+        assert_eq!(
+            Color::TRANSPARENT.over(Color::TRANSPARENT),
+            Color::TRANSPARENT
+        );
+    }
+
+    #[test]
+    fn hsl_round_trips() {
+        for hex in ["#89b4fa", "#4caf50", "#ff0000", "#1e1e1e", "#ffffff"] {
+            let c = Color::from_hex(hex).unwrap();
+            let (h, s, l) = c.to_hsl();
+            assert_eq!(Color::from_hsl(h, s, l, c.a).to_hex(), hex);
+        }
+        let (h, _, l) = Color::from_hex("#4caf50").unwrap().lightened(0.1).to_hsl();
+        assert!(close(h, 122.424) && close(l, 0.592), "h {h}, l {l}");
+    }
+
+    #[test]
+    fn ensure_contrast_moves_text_away_from_background() {
+        let white = Color::rgb(255, 255, 255);
+        let light_card = Color::from_hex("#e0e0e0").unwrap();
+        let fixed = ensure_contrast(white, light_card, 3.0).unwrap();
+        assert!(contrast_ratio(fixed, light_card) >= 3.0);
+        assert!(fixed.relative_luminance() < light_card.relative_luminance());
+
+        let green = Color::from_hex("#4caf50").unwrap();
+        let dark_card = Color::from_hex("#2e7d32").unwrap();
+        let fixed = ensure_contrast(green, dark_card, 3.0).unwrap();
+        assert!(contrast_ratio(fixed, dark_card) >= 3.0);
+        assert!(close(fixed.to_hsl().0, green.to_hsl().0));
+
+        assert_eq!(ensure_contrast(white, Color::rgb(0, 0, 0), 3.0), None);
+    }
+
+    #[test]
+    fn contrast_matches_wcag() {
+        let (black, white) = (Color::rgb(0, 0, 0), Color::rgb(255, 255, 255));
+        assert!(close(contrast_ratio(black, white), 21.0));
+        assert!(close(contrast_ratio(white, white), 1.0));
+        // #777777 on white is the classic just-below-AA grey.
+        assert!(close(
+            contrast_ratio(Color::rgb(0x77, 0x77, 0x77), white),
+            4.478
+        ));
+    }
+}
@@ -0,0 +1,429 @@
+//! Persistent font database cache.
+//!
+//! `Database::load_system_fonts()` resolves the fontconfig configuration,
+//! walks every font directory and parses the name and OS/2 tables of every
+//! face — double-digit milliseconds on a typical desktop, paid before the
+//! first overlay frame. The resulting metadata only changes when fonts are
+//! installed or removed, so it is persisted to `~/.cache/open-sesame/fonts`
+//! and replayed into a fresh `Database` on the next start. Font files are
+//! still read lazily by cosmic-text when a face is first shaped.
+//!
+//! Invalidation: the first line of the cache records the mtimes of the
+//! fontconfig configuration and cache directories plus the standard font
+//! directories. `fc-cache` (run by package managers after installing or
+//! removing fonts) rewrites the fontconfig caches, so any change there
+//! triggers a full rescan and a rewrite of this cache.
+//!
+//! Fallback: fontdb finds fonts through the fontconfig configuration
+//! (`/etc/fonts/fonts.conf`), parsed in Rust without libfontconfig. A
+//! static build on a system without fontconfig installed has no such file
+//! and would find no fonts at all, so when the configuration lists none the
+//! standard font directories are scanned instead and the generic families
+//! set to common faces found there.
+//!
+//! File format: line-oriented, tab-separated text. Family names are stored
+//! without their name-table language; fontdb matches families by name only.
+
+use cosmic_text::fontdb::{
+    Database, FaceInfo, Family, ID, Language, Source, Stretch, Style, Weight,
+};
+use std::path::{Path, PathBuf};
+
+/// Bump when the line format changes.
+const FORMAT_VERSION: u32 = 1;
+
+/// Generic families fontconfig may alias, with their cache-file names.
+const GENERIC_FAMILIES: [(&str, Family<'static>); 5] = [
+    ("serif", Family::Serif),
+    ("sans-serif", Family::SansSerif),
+    ("monospace", Family::Monospace),
+    ("cursive", Family::Cursive),
+    ("fantasy", Family::Fantasy),
+];
+
+/// Faces preferred for the generic families when there is no fontconfig
+/// configuration to alias them.
+const FALLBACK_GENERICS: [(Family<'static>, &[&str]); 3] = [
+    (
+        Family::SansSerif,
+        &["DejaVu Sans", "Noto Sans", "Liberation Sans", "Cantarell"],
+    ),
+    (
+        Family::Serif,
+        &["DejaVu Serif", "Noto Serif", "Liberation Serif"],
+    ),
+    (
+        Family::Monospace,
+        &["DejaVu Sans Mono", "Noto Sans Mono", "Liberation Mono"],
+    ),
+];
+
+/// Build the system font database, from cache when it is still valid.
+#[must_use]
+pub fn load_system_fonts() -> Database {
+    let start = std::time::Instant::now();
+    let stamp = stamp(&stamp_sources());
+    let path = cache_path();
+
+    if let Some(db) = path
+        .as_deref()
+        .and_then(|p| std::fs::read_to_string(p).ok())
+        .and_then(|contents| decode(&contents, &stamp))
+    {
+        tracing::debug!(
+            faces = db.len(),
+            elapsed_us = start.elapsed().as_micros() as u64,
+            "font cache: hit"
+        );
+        return db;
+    }
+
+    let mut db = Database::new();
+    db.load_system_fonts();
+    if db.is_empty() {
+        for dir in font_dirs() {
+            db.load_fonts_dir(dir);
+        }
+        set_fallback_generics(&mut db);
+        tracing::info!(
+            faces = db.len(),
+            "font cache: fontconfig configuration lists no fonts, scanned standard font directories"
+        );
+    }
+    tracing::debug!(
+        faces = db.len(),
+        elapsed_us = start.elapsed().as_micros() as u64,
+        "font cache: miss, scanned system fonts"
+    );
+
+    match (path, encode(&db, &stamp)) {
+        (Some(path), Some(contents)) => {
+            if let Err(e) = core_config::atomic_write(&path, contents.as_bytes()) {
+                tracing::debug!(error = %e, "font cache: write failed");
+            }
+        }
+        (_, None) => tracing::debug!("font cache: database not representable, not cached"),
+        (None, _) => {}
+    }
+    db
+}
+
+/// `~/.cache/open-sesame/fonts`, with the directory created at 0o700.
+fn cache_path() -> Option<PathBuf> {
+    let cache = dirs::cache_dir()?.join("open-sesame");
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::DirBuilderExt;
+        std::fs::DirBuilder::new()
+            .mode(0o700)
+            .recursive(true)
+            .create(&cache)
+            .ok()?;
+    }
+    #[cfg(not(unix))]
+    std::fs::create_dir_all(&cache).ok()?;
+    Some(cache.join("fonts"))
+}
+
+/// Standard font directories, scanned when fontconfig lists none.
+fn font_dirs() -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = ["/usr/share/fonts", "/usr/local/share/fonts"]
+        .iter()
+        .map(PathBuf::from)
+        .collect();
+    if let Some(dir) = dirs::data_dir() {
+        paths.push(dir.join("fonts"));
+    }
+    if let Some(dir) = dirs::home_dir() {
+        paths.push(dir.join(".fonts"));
+    }
+    paths
+}
+
+/// Directories whose mtimes decide whether the cache is still valid.
+fn stamp_sources() -> Vec<PathBuf> {
+    let mut sources: Vec<PathBuf> = ["/etc/fonts", "/etc/fonts/conf.d", "/var/cache/fontconfig"]
+        .iter()
+        .map(PathBuf::from)
+        .collect();
+    if let Some(dir) = dirs::config_dir() {
+        sources.push(dir.join("fontconfig"));
+    }
+    if let Some(dir) = dirs::cache_dir() {
+        sources.push(dir.join("fontconfig"));
+    }
+    sources.extend(font_dirs());
+    sources
+}
+
+/// Set each generic family to the first of its [`FALLBACK_GENERICS`] in
+/// `db`, or else to any face of the right kind (monospaced or not).
+fn set_fallback_generics(db: &mut Database) {
+    for (generic, preferred) in &FALLBACK_GENERICS {
+        let has = |name: &str| {
+            db.faces()
+                .any(|f| f.families.iter().any(|(family, _)| family == name))
+        };
+        let monospace = matches!(generic, Family::Monospace);
+        let Some(name) = preferred
+            .iter()
+            .find(|name| has(name))
+            .map(|name| (*name).to_string())
+            .or_else(|| {
+                db.faces()
+                    .find(|f| f.monospaced == monospace)
+                    .map(|f| f.families[0].0.clone())
+            })
+        else {
+            continue;
+        };
+        match generic {
+            Family::Serif => db.set_serif_family(name),
+            Family::Monospace => db.set_monospace_family(name),
+            _ => db.set_sans_serif_family(name),
+        }
+    }
+}
+
+/// Cache header: format version plus the mtime of each source (`-` if absent).
+fn stamp(sources: &[PathBuf]) -> String {
+    let mut stamp = format!("v{FORMAT_VERSION}");
+    for source in sources {
+        let mtime = std::fs::metadata(source)
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok());
+        match mtime {
+            Some(d) => stamp.push_str(&format!(" {}.{:09}", d.as_secs(), d.subsec_nanos())),
+            None => stamp.push_str(" -"),
+        }
+    }
+    stamp
+}
+
+/// Serialize the database. Returns `None` if any face cannot be represented
+/// (in-memory source, non-UTF-8 path, or a tab/newline in a name), so a
+/// partial cache never hides fonts.
+fn encode(db: &Database, stamp: &str) -> Option<String> {
+    let mut out = format!("{stamp}\n");
+    for (name, family) in &GENERIC_FAMILIES {
+        out.push_str(&format!(
+            "generic\t{name}\t{}\n",
+            field(db.family_name(family))?
+        ));
+    }
+    for face in db.faces() {
+        let Source::File(path) = &face.source else {
+            return None;
+        };
+        let style = match face.style {
+            Style::Normal => 'n',
+            Style::Italic => 'i',
+            Style::Oblique => 'o',
+        };
+        out.push_str(&format!(
+            "face\t{}\t{}\t{}\t{style}\t{}\t{}\t{}",
+            field(path.to_str()?)?,
+            face.index,
+            face.weight.0,
+            face.stretch.to_number(),
+            u8::from(face.monospaced),
+            field(&face.post_script_name)?,
+        ));
+        for (family, _) in &face.families {
+            out.push('\t');
+            out.push_str(field(family)?);
+        }
+        out.push('\n');
+    }
+    Some(out)
+}
+
+/// A value safe to embed in a tab-separated line.
+fn field(s: &str) -> Option<&str> {
+    (!s.contains(['\t', '\n', '\r'])).then_some(s)
+}
+
+/// Rebuild a database from cache contents. Returns `None` if the stamp does
+/// not match or any line is malformed.
+fn decode(contents: &str, stamp: &str) -> Option<Database> {
+    let mut lines = contents.lines();
+    if lines.next()? != stamp {
+        return None;
+    }
+    let mut db = Database::new();
+    for line in lines {
+        let mut fields = line.split('\t');
+        match fields.next()? {
+            "generic" => {
+                let (kind, name) = (fields.next()?, fields.next()?);
+                match kind {
+                    "serif" => db.set_serif_family(name),
+                    "sans-serif" => db.set_sans_serif_family(name),
+                    "monospace" => db.set_monospace_family(name),
+                    "cursive" => db.set_cursive_family(name),
+                    "fantasy" => db.set_fantasy_family(name),
+                    _ => return None,
+                }
+            }
+            "face" => {
+                db.push_face_info(decode_face(fields)?);
+            }
+            _ => return None,
+        }
+    }
+    Some(db)
+}
+
+fn decode_face<'a>(mut fields: impl Iterator<Item = &'a str>) -> Option<FaceInfo> {
+    let path = Path::new(fields.next()?).to_path_buf();
+    let index = fields.next()?.parse().ok()?;
+    let weight = Weight(fields.next()?.parse().ok()?);
+    let style = match fields.next()? {
+        "n" => Style::Normal,
+        "i" => Style::Italic,
+        "o" => Style::Oblique,
+        _ => return None,
+    };
+    let stretch = match fields.next()? {
+        "1" => Stretch::UltraCondensed,
+        "2" => Stretch::ExtraCondensed,
+        "3" => Stretch::Condensed,
+        "4" => Stretch::SemiCondensed,
+        "5" => Stretch::Normal,
+        "6" => Stretch::SemiExpanded,
+        "7" => Stretch::Expanded,
+        "8" => Stretch::ExtraExpanded,
+        "9" => Stretch::UltraExpanded,
+        _ => return None,
+    };
+    let monospaced = match fields.next()? {
+        "0" => false,
+        "1" => true,
+        _ => return None,
+    };
+    let post_script_name = fields.next()?.to_string();
+    // fontdb puts the English (US) name first; the rest keep their order.
+    let families: Vec<(String, Language)> = fields
+        .map(|f| (f.to_string(), Language::English_UnitedStates))
+        .collect();
+    if families.is_empty() {
+        return None;
+    }
+    Some(FaceInfo {
+        id: ID::dummy(),
+        source: Source::File(path),
+        index,
+        families,
+        post_script_name,
+        style,
+        weight,
+        stretch,
+        monospaced,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn face(path: &str, family: &str, weight: u16, monospaced: bool) -> FaceInfo {
+        FaceInfo {
+            id: ID::dummy(),
+            source: Source::File(PathBuf::from(path)),
+            index: 0,
+            families: vec![(family.to_string(), Language::English_UnitedStates)],
+            post_script_name: family.replace(' ', ""),
+            style: Style::Italic,
+            weight: Weight(weight),
+            stretch: Stretch::Condensed,
+            monospaced,
+        }
+    }
+
+    fn sample_db() -> Database {
+        let mut db = Database::new();
+        db.push_face_info(face("/usr/share/fonts/Inter.ttf", "Inter", 600, false));
+        db.push_face_info(face(
+            "/usr/share/fonts/Fira Mono.otf",
+            "Fira Mono",
+            400,
+            true,
+        ));
+        db.set_monospace_family("Fira Mono");
+        db
+    }
+
+    #[test]
+    fn round_trip_preserves_faces_and_generics() {
+        let encoded = encode(&sample_db(), "v1 stamp").unwrap();
+        let db = decode(&encoded, "v1 stamp").unwrap();
+
+        assert_eq!(db.len(), 2);
+        assert_eq!(db.family_name(&Family::Monospace), "Fira Mono");
+        let mono = db.faces().find(|f| f.monospaced).unwrap();
+        assert_eq!(mono.families[0].0, "Fira Mono");
+        assert_eq!(mono.post_script_name, "FiraMono");
+        assert_eq!(mono.weight, Weight(400));
+        assert_eq!(mono.style, Style::Italic);
+        assert_eq!(mono.stretch, Stretch::Condensed);
+        assert!(matches!(&mono.source, Source::File(p) if p.ends_with("Fira Mono.otf")));
+    }
+
+    #[test]
+    fn stamp_mismatch_or_garbage_is_a_miss() {
+        let encoded = encode(&sample_db(), "v1 old").unwrap();
+        assert!(decode(&encoded, "v1 new").is_none());
+        assert!(decode("v1 stamp\nface\tbroken", "v1 stamp").is_none());
+        assert!(decode("", "v1 stamp").is_none());
+    }
+
+    #[test]
+    fn unrepresentable_database_is_not_cached() {
+        let mut db = sample_db();
+        db.push_face_info(face("/fonts/bad\tname.ttf", "Bad", 400, false));
+        assert!(encode(&db, "v1").is_none());
+
+        let mut db = Database::new();
+        db.push_face_info(FaceInfo {
+            source: Source::Binary(std::sync::Arc::new(Vec::<u8>::new())),
+            ..face("", "Memory", 400, false)
+        });
+        assert!(encode(&db, "v1").is_none());
+    }
+
+    #[test]
+    fn fallback_generics_prefer_common_faces() {
+        let mut db = sample_db();
+        db.push_face_info(face(
+            "/usr/share/fonts/DejaVuSans.ttf",
+            "DejaVu Sans",
+            400,
+            false,
+        ));
+        set_fallback_generics(&mut db);
+        assert_eq!(db.family_name(&Family::SansSerif), "DejaVu Sans");
+        assert_eq!(
+            db.family_name(&Family::Serif),
+            "Inter",
+            "any proportional face"
+        );
+        assert_eq!(db.family_name(&Family::Monospace), "Fira Mono");
+    }
+
+    #[test]
+    fn stamp_tracks_directory_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let sources = vec![dir.path().to_path_buf(), dir.path().join("missing")];
+        let before = stamp(&sources);
+        assert!(before.starts_with(&format!("v{FORMAT_VERSION} ")));
+        assert!(before.ends_with(" -"));
+
+        let old = std::time::SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1);
+        std::fs::File::open(dir.path())
+            .unwrap()
+            .set_modified(old)
+            .unwrap();
+        assert_ne!(stamp(&sources), before);
+    }
+}
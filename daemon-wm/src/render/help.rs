@@ -0,0 +1,169 @@
+//! Help overlay: the keys the picker answers, drawn in place of it.
+//!
+//! One centered card with a keys column and a description column per
+//! section. When the sections are taller than the output allows, they flow
+//! into further columns side by side. The card is sized by its text, not
+//! by the picker's blur region, so it is drawn near-opaque
+//! ([`tooltip::opaque`]) and nothing behind it is blurred.
+
+use super::layout::{Direction, Layout};
+use super::text::{draw_text, measure_text};
+use super::{OverlayTheme, draw_card, tooltip};
+use crate::actions::HelpView;
+use cosmic_text::{Attrs, Family, FontSystem, SwashCache, Weight};
+
+/// A line of the card: a section title, or keys and what they do.
+enum Line<'a> {
+    Title(&'a str),
+    Entry(&'a str, &'a str),
+}
+
+/// Lines of one card column, with its keys and description widths.
+struct Column<'a> {
+    lines: Vec<Line<'a>>,
+    keys_w: f32,
+    desc_w: f32,
+}
+
+/// Draw `help` centered on a `width`×`height` output.
+#[allow(clippy::too_many_arguments)]
+pub fn draw_help(
+    pixmap: &mut tiny_skia::Pixmap,
+    font_system: &mut FontSystem,
+    swash_cache: &mut SwashCache,
+    width: f32,
+    height: f32,
+    scale: f32,
+    help: &HelpView,
+    theme: &OverlayTheme,
+) {
+    let layout = Layout::new(scale);
+    pixmap.fill(tiny_skia::Color::TRANSPARENT);
+    let attrs = Attrs::new().family(Family::SansSerif);
+    let bold = attrs.weight(Weight::BOLD);
+    let pad = layout.padding * 2.0;
+    let (_, line_h) = measure_text(font_system, "Ag", layout.text_size, attrs, None);
+    let line_h = line_h + layout.row_spacing;
+    let max_lines = (((height * 0.9 - pad * 2.0) / line_h).floor() as usize).max(2);
+
+    let mut columns: Vec<Column> = Vec::new();
+    for section in &help.sections {
+        let lines = std::iter::once(Line::Title(&section.title)).chain(
+            section
+                .entries
+                .iter()
+                .map(|(keys, what)| Line::Entry(keys, what)),
+        );
+        for line in lines {
+            let full = columns.last().is_none_or(|c| {
+                // A title is not left alone at the bottom of a column.
+                let room = if matches!(line, Line::Title(_)) { 2 } else { 1 };
+                c.lines.len() + room > max_lines
+            });
+            if full {
+                columns.push(Column {
+                    lines: Vec::new(),
+                    keys_w: 0.0,
+                    desc_w: 0.0,
+                });
+            }
+            let column = columns.last_mut().expect("pushed above");
+            match line {
+                Line::Title(title) => {
+                    let (w, _) = measure_text(font_system, title, layout.text_size, bold, None);
+                    column.keys_w = column.keys_w.max(w);
+                }
+                Line::Entry(keys, what) => {
+                    let (w, _) = measure_text(font_system, keys, layout.text_size, bold, None);
+                    column.keys_w = column.keys_w.max(w);
+                    let (w, _) = measure_text(font_system, what, layout.text_size, attrs, None);
+                    column.desc_w = column.desc_w.max(w);
+                }
+            }
+            column.lines.push(line);
+        }
+    }
+
+    let column_w = |c: &Column| c.keys_w + layout.column_gap + c.desc_w;
+    let cw = columns.iter().map(column_w).sum::<f32>()
+        + pad * 2.0
+        + pad * columns.len().saturating_sub(1) as f32;
+    let rows = columns.iter().map(|c| c.lines.len()).max().unwrap_or(0);
+    let ch = line_h * rows as f32 + pad * 2.0;
+    let cx = ((width - cw) / 2.0).max(0.0);
+    let cy = ((height - ch) / 2.0).max(0.0);
+    draw_card(
+        pixmap,
+        cx,
+        cy,
+        cw,
+        ch,
+        layout.corner_radius,
+        &tooltip::opaque(theme),
+    );
+
+    let mut x = match theme.direction {
+        Direction::Ltr => cx + pad,
+        Direction::Rtl => cx + cw - pad,
+    };
+    for column in &columns {
+        let w = column_w(column);
+        let left = match theme.direction {
+            Direction::Ltr => x,
+            Direction::Rtl => x - w,
+        };
+        let (keys_x, desc_x) = match theme.direction {
+            Direction::Ltr => (left, left + column.keys_w + layout.column_gap),
+            Direction::Rtl => (left + column.desc_w + layout.column_gap, left),
+        };
+        for (i, line) in column.lines.iter().enumerate() {
+            let y = cy + pad + line_h * i as f32;
+            match line {
+                Line::Title(title) => {
+                    draw_text(
+                        pixmap,
+                        font_system,
+                        swash_cache,
+                        keys_x,
+                        y,
+                        title,
+                        layout.text_size,
+                        bold,
+                        theme.text_secondary,
+                        None,
+                    );
+                }
+                Line::Entry(keys, what) => {
+                    draw_text(
+                        pixmap,
+                        font_system,
+                        swash_cache,
+                        keys_x,
+                        y,
+                        keys,
+                        layout.text_size,
+                        bold,
+                        theme.text_primary,
+                        None,
+                    );
+                    draw_text(
+                        pixmap,
+                        font_system,
+                        swash_cache,
+                        desc_x,
+                        y,
+                        what,
+                        layout.text_size,
+                        attrs,
+                        theme.text_secondary,
+                        None,
+                    );
+                }
+            }
+        }
+        x = match theme.direction {
+            Direction::Ltr => x + w + pad,
+            Direction::Rtl => x - w - pad,
+        };
+    }
+}
@@ -0,0 +1,136 @@
+//! Key binding icons from the XDG icon theme.
+//!
+//! A binding's `icon` can name a themed icon (`utilities-terminal`) instead
+//! of a glyph. It is looked up in the user's icon theme, then in `hicolor`
+//! and `/usr/share/pixmaps` as the icon theme spec asks, and rasterized once
+//! per pixel size. PNG and SVG icons are drawn; other formats are left out.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock, Mutex, OnceLock};
+
+/// The user's icon theme, read once before the sandbox is applied.
+static THEME: OnceLock<Option<String>> = OnceLock::new();
+
+/// Rasterized icons by name and pixel size; `None` records a miss.
+type IconCache = HashMap<(String, u32), Option<Arc<tiny_skia::Pixmap>>>;
+
+static ICONS: LazyLock<Mutex<IconCache>> = LazyLock::new(Mutex::default);
+
+/// Read the icon theme setting. Call before `apply_sandbox`: the GTK
+/// settings files are outside the directories the sandbox allows.
+pub fn init() {
+    let theme = THEME.get_or_init(user_theme);
+    tracing::debug!(theme = theme.as_deref(), "icons: using icon theme");
+}
+
+/// The icon theme from the COSMIC toolkit config, else from GTK's
+/// `settings.ini`. `None` leaves lookups to `hicolor`.
+fn user_theme() -> Option<String> {
+    let config = dirs::config_dir()?;
+    // COSMIC stores the name as a RON string.
+    if let Ok(name) =
+        std::fs::read_to_string(config.join("cosmic/com.system76.CosmicTk/v1/icon_theme"))
+    {
+        let name = name.trim().trim_matches('"');
+        if !name.is_empty() {
+            return Some(name.to_string());
+        }
+    }
+    ["gtk-4.0", "gtk-3.0"].iter().find_map(|dir| {
+        let settings = std::fs::read_to_string(config.join(dir).join("settings.ini")).ok()?;
+        settings.lines().find_map(|line| {
+            let (key, value) = line.split_once('=')?;
+            let value = value.trim().trim_matches('"');
+            (key.trim() == "gtk-icon-theme-name" && !value.is_empty()).then(|| value.to_string())
+        })
+    })
+}
+
+/// The icon `name` rasterized to `size` pixels square, or `None` if no
+/// theme has it or it cannot be decoded. Each name and size is looked up
+/// once; a missing icon is logged the first time.
+pub fn load(name: &str, size: u32) -> Option<Arc<tiny_skia::Pixmap>> {
+    let mut icons = ICONS
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    icons
+        .entry((name.to_string(), size))
+        .or_insert_with(|| {
+            let icon = find(name, size).and_then(|path| rasterize(&path, size));
+            if icon.is_none() {
+                tracing::warn!(icon = name, "key binding icon not found in the icon theme");
+            }
+            icon.map(Arc::new)
+        })
+        .clone()
+}
+
+/// The path of the theme's icon closest to `size`.
+fn find(name: &str, size: u32) -> Option<PathBuf> {
+    let lookup = freedesktop_icons::lookup(name)
+        .with_size(u16::try_from(size).unwrap_or(u16::MAX))
+        .with_cache();
+    match THEME.get().and_then(Option::as_deref) {
+        Some(theme) => lookup.with_theme(theme).find(),
+        None => lookup.find(),
+    }
+}
+
+/// Decode the PNG or SVG at `path` and scale it to fit `size` pixels square.
+#[allow(clippy::cast_precision_loss)] // icon sizes are far below 2^24
+fn rasterize(path: &Path, size: u32) -> Option<tiny_skia::Pixmap> {
+    let data = std::fs::read(path).ok()?;
+    let mut pixmap = tiny_skia::Pixmap::new(size, size)?;
+    if path.extension().is_some_and(|ext| ext == "svg") {
+        let tree = resvg::usvg::Tree::from_data(&data, &resvg::usvg::Options::default()).ok()?;
+        let scale = size as f32 / tree.size().width().max(tree.size().height());
+        resvg::render(
+            &tree,
+            tiny_skia::Transform::from_scale(scale, scale),
+            &mut pixmap.as_mut(),
+        );
+    } else {
+        let image = tiny_skia::Pixmap::decode_png(&data).ok()?;
+        let scale = size as f32 / image.width().max(image.height()) as f32;
+        pixmap.draw_pixmap(
+            0,
+            0,
+            image.as_ref(),
+            &tiny_skia::PixmapPaint {
+                quality: tiny_skia::FilterQuality::Bicubic,
+                ..tiny_skia::PixmapPaint::default()
+            },
+            tiny_skia::Transform::from_scale(scale, scale),
+            None,
+        );
+    }
+    Some(pixmap)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rasterizes_svg_and_png_icons_to_the_requested_size() {
+        let dir = tempfile::tempdir().unwrap();
+        let svg = dir.path().join("square.svg");
+        std::fs::write(
+            &svg,
+            r##"<svg xmlns="http://www.w3.org/2000/svg" width="16" height="16"><rect width="16" height="16" fill="#ff0000"/></svg>"##,
+        )
+        .unwrap();
+        let icon = rasterize(&svg, 32).unwrap();
+        assert_eq!((icon.width(), icon.height()), (32, 32));
+        assert_eq!(icon.pixel(31, 31).unwrap().alpha(), 255);
+
+        let png = dir.path().join("square.png");
+        std::fs::write(&png, icon.encode_png().unwrap()).unwrap();
+        let icon = rasterize(&png, 16).unwrap();
+        assert_eq!((icon.width(), icon.height()), (16, 16));
+        assert_eq!(icon.pixel(8, 8).unwrap().red(), 255);
+
+        assert!(rasterize(&dir.path().join("missing.svg"), 16).is_none());
+    }
+}
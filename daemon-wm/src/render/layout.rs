@@ -12,6 +12,14 @@ pub const BASE_TEXT_SIZE: f32 = 16.0;
 pub const BASE_BORDER_WIDTH: f32 = 3.0;
 pub const BASE_CORNER_RADIUS: f32 = 16.0;
 pub const BASE_COLUMN_GAP: f32 = 16.0;
+pub const BASE_DIVIDER_WIDTH: f32 = 1.0;
+pub const BASE_CARD_MIN_WIDTH: f32 = 400.0;
+pub const BASE_CARD_MAX_WIDTH: f32 = 700.0;
+/// Title column width the card is sized to fit.
+pub const BASE_TITLE_MIN_WIDTH: f32 = 200.0;
+/// Narrower title columns are not drawn at all.
+pub const BASE_TITLE_HIDE_WIDTH: f32 = 50.0;
+pub const BASE_MESSAGE_MAX_WIDTH: f32 = 500.0;
 
 /// Scaled layout values for a given HiDPI factor.
 pub struct Layout {
@@ -26,6 +34,12 @@ pub struct Layout {
     pub border_width: f32,
     pub corner_radius: f32,
     pub column_gap: f32,
+    pub divider_width: f32,
+    pub card_min_width: f32,
+    pub card_max_width: f32,
+    pub title_min_width: f32,
+    pub title_hide_width: f32,
+    pub message_max_width: f32,
 }
 
 impl Layout {
@@ -42,8 +56,49 @@ impl Layout {
             border_width: BASE_BORDER_WIDTH * scale,
             corner_radius: BASE_CORNER_RADIUS * scale,
             column_gap: BASE_COLUMN_GAP * scale,
+            divider_width: BASE_DIVIDER_WIDTH * scale,
+            card_min_width: BASE_CARD_MIN_WIDTH * scale,
+            card_max_width: BASE_CARD_MAX_WIDTH * scale,
+            title_min_width: BASE_TITLE_MIN_WIDTH * scale,
+            title_hide_width: BASE_TITLE_HIDE_WIDTH * scale,
+            message_max_width: BASE_MESSAGE_MAX_WIDTH * scale,
         }
     }
+
+    /// Height of one row plus the gap below it.
+    pub fn row_pitch(&self) -> f32 {
+        self.row_height + self.row_spacing
+    }
+
+    /// Most rows a card can show on a screen `screen_h` pixels tall, leaving
+    /// room above and below for the input pill. Always at least one.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)] // floor of a clamped non-negative value
+    pub fn max_rows(&self, screen_h: f32) -> usize {
+        // The pill sits `padding` below the card and is `text_size +
+        // padding` tall; the card is centered, so reserve that on both sides.
+        let pill = self.padding * 2.0 + self.text_size;
+        let available = screen_h - pill * 2.0 - self.padding * 2.0 + self.row_spacing;
+        ((available / self.row_pitch()).floor().max(1.0)) as usize
+    }
+
+    /// Top edge of the row drawn in `slot` (0 = first shown row).
+    pub fn row_y(&self, card: &CardRect, slot: usize) -> f32 {
+        card.y + self.padding + slot as f32 * self.row_pitch()
+    }
+
+    /// Distance from the card's leading edge to the title column.
+    pub fn title_offset(&self, show_app_id: bool) -> f32 {
+        let mut offset = self.padding + self.badge_width + self.column_gap;
+        if show_app_id {
+            offset += self.app_column_width + self.column_gap;
+        }
+        offset
+    }
+
+    /// Width left for titles in `card`.
+    pub fn title_width(&self, card: &CardRect, show_app_id: bool) -> f32 {
+        card.width - self.title_offset(show_app_id) - self.padding
+    }
 }
 
 /// Computed card rectangle in pixel coordinates.
@@ -54,7 +109,94 @@ pub struct CardRect {
     pub height: f32,
 }
 
-/// Calculate centered card geometry for N visible rows.
+impl CardRect {
+    /// Left edge of the span `[x, x + w]` reflected about the card's
+    /// vertical center line.
+    pub fn mirror_x(&self, x: f32, w: f32) -> f32 {
+        2.0 * self.x + self.width - x - w
+    }
+}
+
+/// Resolved row layout direction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Ltr,
+    Rtl,
+}
+
+/// Languages written right to left (ISO 639 codes, including the legacy
+/// `iw` for Hebrew).
+const RTL_LANGUAGES: &[&str] = &[
+    "ar", "arc", "ckb", "dv", "fa", "he", "iw", "ks", "ku_IQ", "ps", "sd", "ug", "ur", "yi",
+];
+
+impl Direction {
+    /// Resolve the configured direction, consulting the locale for `auto`.
+    pub fn resolve(setting: core_config::TextDirection) -> Self {
+        match setting {
+            core_config::TextDirection::Ltr => Self::Ltr,
+            core_config::TextDirection::Rtl => Self::Rtl,
+            core_config::TextDirection::Auto => ["LC_ALL", "LC_MESSAGES", "LANG"]
+                .iter()
+                .filter_map(|var| std::env::var(var).ok())
+                .find(|value| !value.is_empty())
+                .map_or(Self::Ltr, |locale| Self::from_locale(&locale)),
+        }
+    }
+
+    /// Direction of a POSIX locale name such as `ar_EG.UTF-8` or `he_IL@euro`.
+    pub fn from_locale(locale: &str) -> Self {
+        let name = locale.split(['.', '@']).next().unwrap_or(locale);
+        let language = name.split('_').next().unwrap_or(name);
+        if RTL_LANGUAGES.contains(&language) || RTL_LANGUAGES.contains(&name) {
+            Self::Rtl
+        } else {
+            Self::Ltr
+        }
+    }
+}
+
+/// The run of rows drawn out of `total` matching rows.
+///
+/// Rows are drawn from `first`, scrolled so the selection stays shown. With
+/// a page size, rows past it are counted in a "+N more" footer that takes
+/// one more row of the card; without one, the card fits the screen and
+/// scrolls with no footer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Page {
+    pub first: usize,
+    pub shown: usize,
+    /// Matching rows not drawn, for the footer. `0` draws no footer.
+    pub more: usize,
+}
+
+impl Page {
+    /// Page `total` rows around `selection`. `max_rows` is what fits on the
+    /// output ([`Layout::max_rows`]); `page_rows` (`0` = no limit) caps it.
+    pub fn new(total: usize, selection: usize, max_rows: usize, page_rows: usize) -> Self {
+        let max_rows = max_rows.max(1);
+        let (shown, more) = if page_rows == 0 || total <= page_rows.min(max_rows) {
+            (total.min(max_rows), 0)
+        } else {
+            // Leave room for the footer row on short outputs.
+            let shown = page_rows.min(max_rows - 1).max(1);
+            (shown, total - shown)
+        };
+        Self {
+            first: (selection.min(total.saturating_sub(1)) + 1).saturating_sub(shown),
+            shown,
+            more,
+        }
+    }
+
+    /// Rows the card is sized for: the drawn rows plus the footer.
+    pub fn card_rows(&self) -> usize {
+        self.shown + usize::from(self.more > 0)
+    }
+}
+
+/// Calculate centered card geometry for N visible rows. Rows beyond
+/// [`Layout::max_rows`] do not grow the card; the renderer scrolls them.
 pub fn calculate_card(
     row_count: usize,
     screen_w: f32,
@@ -63,21 +205,16 @@ pub fn calculate_card(
     show_app_id: bool,
     show_title: bool,
 ) -> CardRect {
-    let min_title_width: f32 = 200.0;
-    let mut content_width = layout.padding * 2.0 + layout.badge_width + layout.column_gap;
-    if show_app_id {
-        content_width += layout.app_column_width + layout.column_gap;
-    }
+    let mut content_width = layout.title_offset(show_app_id) + layout.padding;
     if show_title {
-        content_width += min_title_width;
+        content_width += layout.title_min_width;
     }
 
-    let max_width = (screen_w * 0.9).min(700.0);
-    let card_width = content_width.max(400.0).min(max_width);
+    let max_width = (screen_w * 0.9).min(layout.card_max_width);
+    let card_width = content_width.max(layout.card_min_width).min(max_width);
 
-    let rows = row_count.max(1);
-    let content_height =
-        rows as f32 * (layout.row_height + layout.row_spacing) - layout.row_spacing;
+    let rows = row_count.clamp(1, layout.max_rows(screen_h));
+    let content_height = rows as f32 * layout.row_pitch() - layout.row_spacing;
     let card_height = content_height + layout.padding * 2.0;
 
     CardRect {
@@ -87,3 +224,174 @@ pub fn calculate_card(
         height: card_height,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locale_direction() {
+        assert_eq!(Direction::from_locale("ar_EG.UTF-8"), Direction::Rtl);
+        assert_eq!(Direction::from_locale("he_IL@euro"), Direction::Rtl);
+        assert_eq!(Direction::from_locale("fa"), Direction::Rtl);
+        assert_eq!(Direction::from_locale("en_US.UTF-8"), Direction::Ltr);
+        assert_eq!(Direction::from_locale("C"), Direction::Ltr);
+        // Kurdish is RTL in Iraq (Sorani) but LTR in Turkey (Kurmanji).
+        assert_eq!(Direction::from_locale("ku_IQ.UTF-8"), Direction::Rtl);
+        assert_eq!(Direction::from_locale("ku_TR.UTF-8"), Direction::Ltr);
+    }
+
+    /// Output sizes in logical pixels. The renderer works in physical
+    /// pixels, so each is multiplied by the scale.
+    const SCREENS: [(f32, f32); 3] = [(1280.0, 720.0), (1920.0, 1080.0), (2560.0, 1440.0)];
+    const SCALES: [f32; 4] = [1.0, 1.25, 1.5, 2.0];
+    const COLUMNS: [(bool, bool); 3] = [(true, true), (true, false), (false, true)];
+    /// Tolerance for accumulated f32 rounding.
+    const EPS: f32 = 0.01;
+
+    /// Every (scale, physical screen size, row count, columns) combination.
+    fn cases() -> impl Iterator<Item = (f32, f32, f32, usize, (bool, bool))> {
+        SCALES.into_iter().flat_map(|scale| {
+            SCREENS.into_iter().flat_map(move |(w, h)| {
+                (1..=50).flat_map(move |rows| {
+                    COLUMNS
+                        .into_iter()
+                        .map(move |columns| (scale, w * scale, h * scale, rows, columns))
+                })
+            })
+        })
+    }
+
+    #[test]
+    fn card_and_pill_fit_on_screen() {
+        for (scale, w, h, rows, (show_app_id, show_title)) in cases() {
+            let layout = Layout::new(scale);
+            let card = calculate_card(rows, w, h, &layout, show_app_id, show_title);
+            let case = format!("scale {scale}, {w}x{h}, {rows} rows");
+            assert!(card.x >= 0.0 && card.y >= 0.0, "{case}");
+            assert!(card.x + card.width <= w + EPS, "{case}: too wide");
+            // The input pill is drawn `padding` below the card.
+            let pill_bottom = card.y + card.height + layout.padding * 2.0 + layout.text_size;
+            assert!(pill_bottom <= h + EPS, "{case}: pill off screen");
+        }
+    }
+
+    #[test]
+    fn rows_stay_inside_card_without_overlap() {
+        for (scale, w, h, rows, (show_app_id, show_title)) in cases() {
+            let layout = Layout::new(scale);
+            let card = calculate_card(rows, w, h, &layout, show_app_id, show_title);
+            let case = format!("scale {scale}, {w}x{h}, {rows} rows");
+            let shown = rows.min(layout.max_rows(h));
+            for slot in 0..shown {
+                let top = layout.row_y(&card, slot);
+                assert!(top >= card.y + layout.padding - EPS, "{case}: slot {slot}");
+                assert!(
+                    top + layout.row_height <= card.y + card.height - layout.padding + EPS,
+                    "{case}: slot {slot} past the card"
+                );
+                if slot > 0 {
+                    let above = layout.row_y(&card, slot - 1) + layout.row_height;
+                    assert!(
+                        top - above >= layout.row_spacing - EPS,
+                        "{case}: slot {slot}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn column_widths_stay_positive() {
+        for (scale, w, h, rows, (show_app_id, show_title)) in cases() {
+            let layout = Layout::new(scale);
+            let card = calculate_card(rows, w, h, &layout, show_app_id, show_title);
+            let case = format!("scale {scale}, {w}x{h}, {rows} rows");
+            assert!(
+                layout.title_offset(show_app_id) + layout.padding <= card.width + EPS,
+                "{case}: badge and app columns overflow"
+            );
+            if show_title {
+                assert!(
+                    layout.title_width(&card, show_app_id) >= layout.title_min_width - EPS,
+                    "{case}: title column squeezed"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn page_without_limit_scrolls_to_selection() {
+        let page = Page::new(30, 3, 16, 0);
+        assert_eq!((page.first, page.shown, page.more), (0, 16, 0));
+        let page = Page::new(30, 20, 16, 0);
+        assert_eq!((page.first, page.shown, page.more), (5, 16, 0));
+        assert_eq!(page.card_rows(), 16);
+    }
+
+    #[test]
+    fn page_limit_counts_the_rest_in_a_footer() {
+        let page = Page::new(20, 0, 16, 8);
+        assert_eq!((page.first, page.shown, page.more), (0, 8, 12));
+        assert_eq!(page.card_rows(), 9);
+        // Moving past the page scrolls it.
+        assert_eq!(Page::new(20, 10, 16, 8).first, 3);
+        // Narrowed to fit: no footer.
+        let page = Page::new(8, 0, 16, 8);
+        assert_eq!((page.shown, page.more, page.card_rows()), (8, 0, 8));
+        // A page taller than the output keeps a row for the footer.
+        let page = Page::new(20, 0, 6, 8);
+        assert_eq!((page.shown, page.more, page.card_rows()), (5, 15, 6));
+    }
+
+    #[test]
+    fn every_output_pages_the_same_selection() {
+        // With `show_on_all_outputs`, each mirror pages the same filtered
+        // rows and selection for its own size. The selected row must be on
+        // every output's page, however few rows fit there.
+        for scale in SCALES {
+            let layout = Layout::new(scale);
+            for (_, h) in SCREENS {
+                let max_rows = layout.max_rows(h * scale);
+                for total in 1..=40 {
+                    for selection in 0..total {
+                        for page_rows in [0, 8] {
+                            let page = Page::new(total, selection, max_rows, page_rows);
+                            let case = format!(
+                                "scale {scale}, height {h}, {total} rows, selection {selection}, page {page_rows}"
+                            );
+                            assert!(
+                                (page.first..page.first + page.shown).contains(&selection),
+                                "{case}"
+                            );
+                            assert!(page.first + page.shown <= total, "{case}");
+                            assert!(page.card_rows() <= max_rows, "{case}");
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn max_rows_scales_with_output() {
+        // 1080p at 1x fits 16 rows; the same output at 2x has twice the
+        // pixels and fits the same number.
+        assert_eq!(Layout::new(1.0).max_rows(1080.0), 16);
+        assert_eq!(Layout::new(2.0).max_rows(2160.0), 16);
+        assert_eq!(Layout::new(1.0).max_rows(100.0), 1);
+    }
+
+    #[test]
+    fn mirror_x_reflects_within_card() {
+        let card = CardRect {
+            x: 100.0,
+            y: 0.0,
+            width: 400.0,
+            height: 100.0,
+        };
+        // A 48px badge at the left padding lands at the right padding.
+        assert!((card.mirror_x(120.0, 48.0) - 432.0).abs() < f32::EPSILON);
+        assert!((card.mirror_x(card.mirror_x(120.0, 48.0), 48.0) - 120.0).abs() < f32::EPSILON);
+    }
+}
@@ -5,84 +5,34 @@
 //! No screen-edge borders. No intermediate visual phases. The card appears
 //! fully formed or not at all.
 
+pub mod color;
+pub mod font_cache;
+pub mod help;
+pub mod icons;
 pub mod layout;
+pub mod onboarding;
+#[cfg(feature = "thumbnails")]
+pub mod png;
 pub mod primitives;
+pub mod row_menu;
+pub mod scratch;
 pub mod text;
+pub mod tooltip;
+pub mod window_badges;
 
-use cosmic_text::{Attrs, Family, FontSystem, SwashCache, Weight};
-use layout::{CardRect, Layout};
-use primitives::{fill_rounded_rect, stroke_rounded_rect};
-use text::{draw_text, ellipsize_text, measure_text};
-
-// ---------------------------------------------------------------------------
-// Color
-// ---------------------------------------------------------------------------
-
-#[derive(Debug, Clone, Copy)]
-pub struct Color {
-    pub r: f64,
-    pub g: f64,
-    pub b: f64,
-    pub a: f64,
-}
-
-impl Color {
-    pub const fn rgba(r: u8, g: u8, b: u8, a: u8) -> Self {
-        Self {
-            r: r as f64 / 255.0,
-            g: g as f64 / 255.0,
-            b: b as f64 / 255.0,
-            a: a as f64 / 255.0,
-        }
-    }
-
-    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
-        Self::rgba(r, g, b, 255)
-    }
-
-    pub fn from_hex(hex: &str) -> Option<Self> {
-        let hex = hex.strip_prefix('#').unwrap_or(hex);
-        match hex.len() {
-            6 => {
-                let r = u8::from_str_radix(&hex[0..2], 16).ok()?;
-                let g = u8::from_str_radix(&hex[2..4], 16).ok()?;
-                let b = u8::from_str_radix(&hex[4..6], 16).ok()?;
-                Some(Self::rgba(r, g, b, 255))
-            }
-            8 => {
-                let r = u8::from_str_radix(&hex[0..2], 16).ok()?;
-                let g = u8::from_str_radix(&hex[2..4], 16).ok()?;
-                let b = u8::from_str_radix(&hex[4..6], 16).ok()?;
-                let a = u8::from_str_radix(&hex[6..8], 16).ok()?;
-                Some(Self::rgba(r, g, b, a))
-            }
-            _ => None,
-        }
-    }
-
-    pub fn brightened(&self, amount: f64) -> Self {
-        Self {
-            r: (self.r + amount).min(1.0),
-            g: (self.g + amount).min(1.0),
-            b: (self.b + amount).min(1.0),
-            a: self.a,
-        }
-    }
-
-    pub fn to_tiny_skia(self) -> tiny_skia::Color {
-        tiny_skia::Color::from_rgba(self.r as f32, self.g as f32, self.b as f32, self.a as f32)
-            .unwrap_or(tiny_skia::Color::TRANSPARENT)
-    }
+pub use color::Color;
 
-    pub fn to_cosmic_text(self) -> cosmic_text::Color {
-        cosmic_text::Color::rgba(
-            (self.r * 255.0) as u8,
-            (self.g * 255.0) as u8,
-            (self.b * 255.0) as u8,
-            (self.a * 255.0) as u8,
-        )
-    }
-}
+use crate::hints::{AppPosition, fold_key};
+use crate::search::{self, WindowMatch};
+use bumpalo::Bump;
+use bumpalo::collections::{String as BumpString, Vec as BumpVec};
+use cosmic_text::{Attrs, Family, FontSystem, SwashCache, Weight};
+use layout::{CardRect, Direction, Layout, Page};
+use primitives::{fill_rounded_rect, stroke_arc, stroke_rounded_rect};
+use scratch::{FrameScratch, TruncationCache};
+use std::ops::Range;
+use std::time::Duration;
+use text::{TextSpan, draw_spans, draw_text, ellipsize_text, measure_spans, measure_text};
 
 // ---------------------------------------------------------------------------
 // Theme
@@ -109,8 +59,18 @@ pub struct OverlayTheme {
     pub badge_matched_text: Color,
     /// Selection highlight: subtle white wash over the row.
     pub selection_highlight: Color,
+    /// Typed input that matches nothing, until it is cleared.
+    pub input_rejected: Color,
     /// Corner radius from system theme.
     pub corner_radius: f64,
+    /// Row layout direction (`text_direction`, resolved against the locale).
+    pub direction: Direction,
+    /// Most rows drawn at once (`page_rows`); `0` fits the card to the
+    /// screen.
+    pub page_rows: usize,
+    /// Selection dwell after which a cut-off title is shown in full in a
+    /// tooltip (`title_tooltip_ms`); `None` disables it.
+    pub title_tooltip: Option<Duration>,
 }
 
 impl Default for OverlayTheme {
@@ -125,7 +85,11 @@ impl Default for OverlayTheme {
             badge_matched_background: Color::rgba(76, 175, 80, 255),
             badge_matched_text: Color::rgb(255, 255, 255),
             selection_highlight: Color::rgba(255, 255, 255, 25),
+            input_rejected: INPUT_REJECTED,
             corner_radius: layout::BASE_CORNER_RADIUS as f64,
+            direction: Direction::Ltr,
+            page_rows: 0,
+            title_tooltip: None,
         }
     }
 }
@@ -136,6 +100,10 @@ impl OverlayTheme {
     pub fn from_config(cfg: &core_config::WmConfig) -> Self {
         let mut theme = Self::from_cosmic().unwrap_or_default();
         let defaults = core_config::WmConfig::default();
+        theme.direction = Direction::resolve(cfg.text_direction);
+        theme.page_rows = cfg.page_rows as usize;
+        theme.title_tooltip =
+            (cfg.title_tooltip_ms > 0).then(|| Duration::from_millis(cfg.title_tooltip_ms.into()));
 
         if cfg.card_color != defaults.card_color
             && let Some(c) = Color::from_hex(&cfg.card_color)
@@ -164,46 +132,109 @@ impl OverlayTheme {
         {
             theme.badge_matched_background = c;
         }
+
+        // Keep user-set colors readable; the config validator warns about
+        // the same pairs.
+        let text_set = cfg.text_color != defaults.text_color;
+        let pairs = [
+            (
+                text_set || cfg.card_color != defaults.card_color,
+                &mut theme.text_primary,
+                theme.card_background,
+            ),
+            (
+                text_set || cfg.card_color != defaults.card_color,
+                &mut theme.text_secondary,
+                theme.card_background,
+            ),
+            (
+                text_set || cfg.hint_color != defaults.hint_color,
+                &mut theme.badge_text,
+                theme.badge_background,
+            ),
+            (
+                text_set || cfg.hint_matched_color != defaults.hint_matched_color,
+                &mut theme.badge_matched_text,
+                theme.badge_matched_background,
+            ),
+        ];
+        for (user_set, text, background) in pairs {
+            if !user_set {
+                continue;
+            }
+            if let Some(fixed) =
+                color::ensure_contrast(*text, background, core_config::MIN_TEXT_CONTRAST)
+            {
+                tracing::warn!(
+                    text = %text.to_hex(),
+                    background = %background.to_hex(),
+                    adjusted = %fixed.to_hex(),
+                    "theme: low text contrast, adjusted text lightness"
+                );
+                *text = fixed;
+            }
+        }
         theme
     }
 
+    /// Color settings in `cfg` that are not valid hex, for which
+    /// [`from_config`](Self::from_config) falls back to the system or
+    /// built-in color.
+    pub fn invalid_colors(cfg: &core_config::WmConfig) -> Vec<&'static str> {
+        [
+            ("border_color", &cfg.border_color),
+            ("card_color", &cfg.card_color),
+            ("text_color", &cfg.text_color),
+            ("hint_color", &cfg.hint_color),
+            ("hint_matched_color", &cfg.hint_matched_color),
+        ]
+        .into_iter()
+        .filter(|(_, value)| Color::from_hex(value).is_none())
+        .map(|(key, _)| key)
+        .collect()
+    }
+
     /// Build theme from COSMIC desktop system theme via `cosmic-theme` crate.
-    #[cfg(target_os = "linux")]
+    #[cfg(all(target_os = "linux", feature = "cosmic"))]
     fn from_cosmic() -> Option<Self> {
         let t = platform_linux::cosmic_theme::CosmicTheme::load()?;
 
-        let pb = t.primary_base;
-        let po = t.primary_on;
-        let sb = t.secondary_component_base;
-        let so = t.secondary_component_on;
-        let ab = t.accent_base;
-        let ao = t.accent_on;
+        let pb = Color::from(t.primary_base);
+        let po = Color::from(t.primary_on);
+        let sb = Color::from(t.secondary_component_base);
+        let so = Color::from(t.secondary_component_on);
+        let ab = Color::from(t.accent_base);
+        let ao = Color::from(t.accent_on);
         let corner_radius = t.radius_m[0] as f64;
 
         // Card alpha: always semi-transparent so blur shows through.
         // When frosted, use the theme's blur alpha. Otherwise, 50% opacity
         // so the compositor blur is always visible behind the card.
         let card_alpha = if t.frosted_panel || t.frosted_windows {
-            (t.blur_alpha * 255.0) as u8
+            f64::from(t.blur_alpha)
         } else {
-            128
+            0.5
         };
 
         Some(Self {
-            card_background: Color::rgba(pb.r, pb.g, pb.b, card_alpha),
-            card_border: Color::rgba(ab.r, ab.g, ab.b, 120),
-            text_primary: Color::rgba(po.r, po.g, po.b, po.a),
-            text_secondary: Color::rgba(po.r, po.g, po.b, ((po.a as f64) * 0.7) as u8),
-            badge_background: Color::rgba(sb.r, sb.g, sb.b, 255),
-            badge_text: Color::rgba(so.r, so.g, so.b, so.a),
-            badge_matched_background: Color::rgba(ab.r, ab.g, ab.b, 255),
-            badge_matched_text: Color::rgba(ao.r, ao.g, ao.b, ao.a),
+            card_background: pb.with_alpha(card_alpha),
+            card_border: ab.with_alpha(120.0 / 255.0),
+            text_primary: po,
+            text_secondary: po.faded(0.7),
+            badge_background: sb.with_alpha(1.0),
+            badge_text: so,
+            badge_matched_background: ab.with_alpha(1.0),
+            badge_matched_text: ao,
             selection_highlight: Color::rgba(255, 255, 255, 25),
+            input_rejected: INPUT_REJECTED,
             corner_radius,
+            direction: Direction::Ltr,
+            page_rows: 0,
+            title_tooltip: None,
         })
     }
 
-    #[cfg(not(target_os = "linux"))]
+    #[cfg(not(all(target_os = "linux", feature = "cosmic")))]
     fn from_cosmic() -> Option<Self> {
         None
     }
@@ -218,6 +249,29 @@ pub struct HintRow<'a> {
     pub hint: &'a str,
     pub app_id: &'a str,
     pub title: &'a str,
+    /// Position among the app's windows; a count badge is drawn when the
+    /// app has more than one.
+    pub position: AppPosition,
+    /// Part of the pinned section; a divider follows its last row.
+    pub pinned: bool,
+    /// A dialog listed below its parent window; drawn with an arrow.
+    pub nested: bool,
+    /// Shown on every workspace; drawn with a state icon.
+    pub sticky: bool,
+    /// Kept above other windows; drawn with a state icon.
+    pub keep_above: bool,
+    /// Minimized; its name and title are drawn dimmed.
+    pub minimized: bool,
+    /// Key binding `label`, shown instead of the name derived from `app_id`.
+    pub label: Option<&'a str>,
+    /// Key binding `icon`, drawn before the name: a glyph, or an icon
+    /// theme name drawn as the theme's image.
+    pub icon: Option<&'a str>,
+    /// Focus timeline time ("2 min ago"), drawn after the title.
+    pub focused_ago: Option<&'a str>,
+    /// The window's rectangle on this output; its badge is drawn over the
+    /// window instead of in the card.
+    pub geometry: Option<core_types::Geometry>,
 }
 
 // ---------------------------------------------------------------------------
@@ -244,22 +298,60 @@ fn draw_card(
 
 /// Armed phase: render nothing. The overlay surface exists (for keyboard
 /// exclusivity) but is visually invisible. No border, no card, pure transparency.
+///
+/// Exception: with `show_delay_indicator`, `delay_progress` (fraction of the
+/// dwell elapsed) draws a small ring where the card will appear, filling
+/// clockwise until the card replaces it.
 pub fn draw_border_only(
     pixmap: &mut tiny_skia::Pixmap,
-    _width: f32,
-    _height: f32,
-    _scale: f32,
-    _theme: &OverlayTheme,
+    width: f32,
+    height: f32,
+    scale: f32,
+    theme: &OverlayTheme,
+    delay_progress: Option<f32>,
 ) {
     pixmap.fill(tiny_skia::Color::TRANSPARENT);
+    let Some(progress) = delay_progress else {
+        return;
+    };
+    let l = Layout::new(scale);
+    let (cx, cy) = (width / 2.0, height / 2.0);
+    let radius = l.badge_height / 2.0;
+    let track = theme.card_border.faded(0.4);
+    stroke_arc(pixmap, cx, cy, radius, 1.0, track, l.border_width);
+    stroke_arc(
+        pixmap,
+        cx,
+        cy,
+        radius,
+        progress,
+        theme.badge_matched_background,
+        l.border_width,
+    );
 }
 
 /// Draw the full overlay: centered card with hint rows on transparent background.
+///
+/// When the input matches no hint, `notice` (staged launch, command-palette
+/// result) replaces the "No matches" card. With no input, it is shown in the
+/// pill beneath the card (e.g. "That window has closed").
+///
+/// With `title_tooltip`, a selected row whose title was cut off gets a
+/// tooltip with the full title. An open `row_menu` is drawn next to the
+/// selected row instead.
+///
+/// When a matching row has a `geometry`, hint input is drawn with
+/// [`window_badges`]: badges over the windows, the other rows in a card at
+/// the bottom. Search input always uses the centered card.
+///
+/// Per-frame strings and lists are allocated in `scratch`'s arena, which is
+/// reset first.
 #[allow(clippy::too_many_arguments)]
 pub fn draw_full_overlay(
     pixmap: &mut tiny_skia::Pixmap,
     font_system: &mut FontSystem,
     swash_cache: &mut SwashCache,
+    scratch: &mut FrameScratch,
     width: f32,
     height: f32,
     scale: f32,
@@ -270,41 +362,55 @@ pub fn draw_full_overlay(
     theme: &OverlayTheme,
     show_app_id: bool,
     show_title: bool,
-    staged_launch: Option<&str>,
+    notice: Option<&str>,
+    title_tooltip: bool,
+    row_menu: Option<&crate::row_menu::MenuView>,
 ) {
     let layout = Layout::new(scale);
     pixmap.fill(tiny_skia::Color::TRANSPARENT);
+    scratch.reset();
+    let arena = &scratch.arena;
+    let truncations = &mut scratch.truncations;
 
-    let visible: Vec<(usize, &HintRow<'_>)> = rows
-        .iter()
-        .enumerate()
-        .filter(|(i, _)| {
-            if input.is_empty() {
-                return true;
-            }
-            if *i < hints.len() {
-                hints[*i].starts_with(&input.to_lowercase())
-            } else {
-                false
-            }
-        })
-        .collect();
+    // Search input (`/query`) filters by title and app name and carries the
+    // match ranges; otherwise rows are filtered by hint prefix.
+    let query = search::parse(input);
+    let mut norm = BumpString::new_in(arena);
+    norm.extend(input.chars().map(fold_key));
+    // Every row is filtered, but only the page drawn below is laid out,
+    // shaped and given match ranges, so frame cost does not grow with the
+    // number of windows.
+    let visible: BumpVec<'_, usize> = BumpVec::from_iter_in(
+        rows.iter().enumerate().filter_map(|(i, row)| {
+            let shown = match query {
+                Some(query) => {
+                    let name = search::window_name(row.app_id, row.label);
+                    search::window_matches(query, name, row.app_id, row.title)
+                }
+                None => input.is_empty() || hints.get(i).is_some_and(|h| h.starts_with(&*norm)),
+            };
+            shown.then_some(i)
+        }),
+        arena,
+    );
 
+    // Hint input that matches no row and stages nothing is a dead end; the
+    // controller clears it after `dead_input_clear_ms`.
+    let rejected = visible.is_empty() && !input.is_empty() && query.is_none() && notice.is_none();
     if visible.is_empty() && !input.is_empty() {
-        if let Some(command) = staged_launch {
-            let message = format!("Launch {command}");
+        if let Some(message) = notice {
             draw_message_card(
                 pixmap,
                 font_system,
                 swash_cache,
                 width,
                 height,
-                &message,
+                message,
                 &layout,
                 theme,
             );
         } else {
-            let message = format!("No matches for \u{2018}{input}\u{2019}");
+            let message = bumpalo::format!(in arena, "No matches for \u{2018}{}\u{2019}", input);
             draw_message_card(
                 pixmap,
                 font_system,
@@ -319,9 +425,47 @@ pub fn draw_full_overlay(
         return;
     }
 
-    let selection = selection.min(visible.len().saturating_sub(1));
-    let card = layout::calculate_card(
+    // In search mode the controller's selection is a window index that
+    // always points at a match.
+    let selection = if query.is_some() {
+        visible.iter().position(|&i| i == selection).unwrap_or(0)
+    } else {
+        selection.min(visible.len().saturating_sub(1))
+    };
+    // The menu needs the selected row drawn in the card.
+    if query.is_none() && row_menu.is_none() && visible.iter().any(|&i| rows[i].geometry.is_some())
+    {
+        window_badges::draw(
+            pixmap,
+            font_system,
+            swash_cache,
+            arena,
+            truncations,
+            width,
+            height,
+            scale,
+            rows,
+            &visible,
+            selection,
+            hints,
+            input,
+            &norm,
+            notice,
+            &layout,
+            theme,
+            show_app_id,
+            show_title,
+        );
+        return;
+    }
+    let page = Page::new(
         visible.len(),
+        selection,
+        layout.max_rows(height),
+        theme.page_rows,
+    );
+    let card = layout::calculate_card(
+        page.card_rows(),
         width,
         height,
         &layout,
@@ -339,44 +483,142 @@ pub fn draw_full_overlay(
         theme,
     );
 
-    for (vi, &(orig_idx, row)) in visible.iter().enumerate() {
-        let row_y = card.y + layout.padding + vi as f32 * (layout.row_height + layout.row_spacing);
+    // Rows past the page or the output scroll so the selection stays shown.
+    let mut tooltip = None;
+    let mut selected_y = None;
+    for (slot, vi) in (page.first..page.first + page.shown).enumerate() {
+        let orig_idx = visible[vi];
+        let row = &rows[orig_idx];
+        let highlight = query.and_then(|query| {
+            let name = search::window_name(row.app_id, row.label);
+            search::match_window(query, name, row.app_id, row.title)
+        });
+        let row_y = layout.row_y(&card, slot);
+        // Timeline rows are in focus order and have no pinned section.
+        if slot > 0 && rows[visible[vi - 1]].pinned && !row.pinned && row.focused_ago.is_none() {
+            // Divider between pinned and MRU-ordered rows, centered in the
+            // row gap.
+            fill_rounded_rect(
+                pixmap,
+                card.x + layout.padding,
+                row_y - (layout.row_spacing + layout.divider_width) / 2.0,
+                card.width - layout.padding * 2.0,
+                layout.divider_width,
+                0.0,
+                theme.card_border,
+            );
+        }
         let is_selected = vi == selection;
-        let match_state = if !input.is_empty() && orig_idx < hints.len() {
-            let norm = input.to_lowercase();
-            if hints[orig_idx] == norm {
-                HintMatchState::Exact
-            } else if hints[orig_idx].starts_with(&norm) {
-                HintMatchState::Partial
-            } else {
-                HintMatchState::None
-            }
-        } else {
-            HintMatchState::None
-        };
-        draw_hint_row(
+        let match_state = hint_match_state(hints.get(orig_idx), &norm);
+        let title_cut = draw_hint_row(
             pixmap,
             font_system,
             swash_cache,
+            arena,
+            truncations,
             &card,
             row_y,
             row,
             is_selected,
             match_state,
+            highlight.as_ref(),
             &layout,
             theme,
             show_app_id,
             show_title,
         );
+        if is_selected {
+            selected_y = Some(row_y);
+        }
+        if title_tooltip && is_selected && title_cut && row_menu.is_none() {
+            tooltip = Some((row.title, row_y));
+        }
+    }
+    if page.more > 0 {
+        draw_more_footer(
+            pixmap,
+            font_system,
+            swash_cache,
+            arena,
+            &card,
+            layout.row_y(&card, page.shown),
+            page.more,
+            &layout,
+            theme,
+        );
     }
 
     if !input.is_empty() {
-        draw_input_indicator(
+        let indicator = input_indicator(arena, input, theme);
+        let color = if rejected {
+            theme.input_rejected
+        } else {
+            theme.text_primary
+        };
+        draw_pill(
             pixmap,
             font_system,
             swash_cache,
             &card,
-            input,
+            indicator,
+            color,
+            &layout,
+            theme,
+        );
+    } else if let Some(message) = notice {
+        draw_pill(
+            pixmap,
+            font_system,
+            swash_cache,
+            &card,
+            message,
+            theme.text_primary,
+            &layout,
+            theme,
+        );
+    }
+
+    // Drawn last so it covers the rows and pill beneath the selection.
+    if let Some((title, row_y)) = tooltip {
+        let offset = layout.title_offset(show_app_id);
+        let title_w = layout.title_width(&card, show_app_id);
+        let x = match theme.direction {
+            Direction::Ltr => card.x + offset,
+            Direction::Rtl => card.mirror_x(card.x + offset, title_w),
+        };
+        let anchor = CardRect {
+            x,
+            y: row_y,
+            width: title_w,
+            height: layout.row_height,
+        };
+        tooltip::draw_tooltip(
+            pixmap,
+            font_system,
+            swash_cache,
+            &anchor,
+            title,
+            width,
+            height,
+            &layout,
+            theme,
+        );
+    }
+    if let (Some(menu), Some(row_y)) = (row_menu, selected_y) {
+        let anchor = CardRect {
+            x: card.x + layout.padding,
+            y: row_y,
+            width: card.width - layout.padding * 2.0,
+            height: layout.row_height,
+        };
+        row_menu::draw_row_menu(
+            pixmap,
+            font_system,
+            swash_cache,
+            &anchor,
+            menu,
+            width,
+            height,
             &layout,
             theme,
         );
@@ -480,10 +722,65 @@ pub fn draw_unlock_prompt(
     );
 }
 
+/// Draw a one-line banner for a non-fatal problem at the top of the output,
+/// over whatever the current phase drew.
+///
+/// The banner sits outside the blurred card region, so its material is
+/// made near-opaque to stay readable over any backdrop.
+#[allow(clippy::too_many_arguments)]
+pub fn draw_toast(
+    pixmap: &mut tiny_skia::Pixmap,
+    font_system: &mut FontSystem,
+    swash_cache: &mut SwashCache,
+    width: f32,
+    scale: f32,
+    message: &str,
+    theme: &OverlayTheme,
+) {
+    let layout = Layout::new(scale);
+    let attrs = Attrs::new().family(Family::SansSerif);
+    let pad_h = layout.padding;
+    let pad_v = layout.padding / 2.0;
+    let max_text = (width - pad_h * 4.0).min(layout.message_max_width);
+    let text = ellipsize_text(font_system, message, layout.text_size, attrs, max_text);
+    let (tw, _) = measure_text(font_system, &text, layout.text_size, attrs, None);
+
+    let w = tw + pad_h * 2.0;
+    let h = layout.text_size + pad_v * 2.0;
+    let x = (width - w) / 2.0;
+    let y = layout.padding * 2.0;
+    let banner = OverlayTheme {
+        card_background: theme
+            .card_background
+            .with_alpha(theme.card_background.a.max(TOAST_MIN_ALPHA)),
+        ..theme.clone()
+    };
+    draw_card(pixmap, x, y, w, h, h / 2.0, &banner);
+    draw_text(
+        pixmap,
+        font_system,
+        swash_cache,
+        x + pad_h,
+        y + pad_v,
+        &text,
+        layout.text_size,
+        attrs,
+        theme.text_primary,
+        None,
+    );
+}
+
 // ---------------------------------------------------------------------------
 // Internal helpers
 // ---------------------------------------------------------------------------
 
+/// Input pill text when the typed hint matches nothing.
+const INPUT_REJECTED: Color = Color::rgb(239, 83, 80);
+
+/// Minimum opacity of the toast banner and title tooltips, which have no
+/// blur behind them.
+const TOAST_MIN_ALPHA: f64 = 0.9;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum HintMatchState {
     None,
@@ -491,6 +788,25 @@ enum HintMatchState {
     Exact,
 }
 
+/// How the hint of a row matches `typed`, the lowercased input.
+fn hint_match_state(hint: Option<&String>, typed: &str) -> HintMatchState {
+    match hint {
+        Some(hint) if !typed.is_empty() && hint == typed => HintMatchState::Exact,
+        Some(hint) if !typed.is_empty() && hint.starts_with(typed) => HintMatchState::Partial,
+        _ => HintMatchState::None,
+    }
+}
+
+/// The typed input as shown in its pill. The prompt chevron leads the
+/// input on the reading side.
+fn input_indicator<'a>(arena: &'a Bump, input: &str, theme: &OverlayTheme) -> &'a str {
+    match theme.direction {
+        Direction::Ltr => bumpalo::format!(in arena, "\u{203a} {}", input),
+        Direction::Rtl => bumpalo::format!(in arena, "{} \u{2039}", input),
+    }
+    .into_bump_str()
+}
+
 /// Shared message card: same material, centered text.
 #[allow(clippy::too_many_arguments)]
 fn draw_message_card(
@@ -507,7 +823,7 @@ fn draw_message_card(
         .family(Family::SansSerif)
         .weight(Weight::NORMAL);
     let font_size = layout.text_size * 1.2;
-    let max_width = (width * 0.6).min(500.0);
+    let max_width = (width * 0.6).min(layout.message_max_width);
     let (tw, th) = measure_text(font_system, message, font_size, attrs, Some(max_width));
 
     let pad = layout.padding * 2.0;
@@ -531,21 +847,81 @@ fn draw_message_card(
     );
 }
 
+/// Draw one hint row. Returns whether its title was cut off.
 #[allow(clippy::too_many_arguments)]
 fn draw_hint_row(
     pixmap: &mut tiny_skia::Pixmap,
     font_system: &mut FontSystem,
     swash_cache: &mut SwashCache,
+    arena: &Bump,
+    truncations: &mut TruncationCache,
     card: &CardRect,
     row_y: f32,
     row: &HintRow<'_>,
     is_selected: bool,
     match_state: HintMatchState,
+    highlight: Option<&WindowMatch>,
     layout: &Layout,
     theme: &OverlayTheme,
     show_app_id: bool,
     show_title: bool,
-) {
+) -> bool {
+    // Columns are laid out left to right, then reflected about the card's
+    // center line for RTL. The highlight inset is symmetric, so it needs no
+    // mirroring.
+    let place = |x: f32, w: f32| match theme.direction {
+        Direction::Ltr => x,
+        Direction::Rtl => card.mirror_x(x, w),
+    };
+    // Nested dialogs mark their first text column on the badge side.
+    let nest_marker = row.nested.then_some(match theme.direction {
+        Direction::Ltr => "\u{21b3} ",
+        Direction::Rtl => " \u{21b2}",
+    });
+    // Sticky and keep-above windows carry small state icons at the end of
+    // their last text column.
+    let mut states = BumpString::new_in(arena);
+    states.extend(
+        [(row.sticky, '\u{25ce}'), (row.keep_above, '\u{25b2}')]
+            .into_iter()
+            .filter_map(|(on, icon)| on.then_some(icon)),
+    );
+    let state_marker = (!states.is_empty()).then(|| match theme.direction {
+        Direction::Ltr => bumpalo::format!(in arena, " {}", states),
+        Direction::Rtl => bumpalo::format!(in arena, "{} ", states),
+    });
+    // The focus timeline's time ends the last text column.
+    let time_marker = row.focused_ago.map(|ago| match theme.direction {
+        Direction::Ltr => bumpalo::format!(in arena, " \u{2014} {}", ago),
+        Direction::Rtl => bumpalo::format!(in arena, "{} \u{2014} ", ago),
+    });
+    // Minimized windows have their name and title dimmed.
+    let (text_primary, text_secondary) = if row.minimized {
+        (
+            theme.text_primary.faded(0.5),
+            theme.text_secondary.faded(0.5),
+        )
+    } else {
+        (theme.text_primary, theme.text_secondary)
+    };
+    // A key binding's icon leads the first text column: a glyph as text,
+    // an icon theme name as an image one text size square.
+    let icon_marker = row
+        .icon
+        .filter(|icon| !core_config::is_icon_theme_name(icon))
+        .map(|icon| match theme.direction {
+            Direction::Ltr => bumpalo::format!(in arena, "{} ", icon),
+            Direction::Rtl => bumpalo::format!(in arena, " {}", icon),
+        });
+    // Text sizes are small and positive.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let icon_image = row
+        .icon
+        .filter(|icon| core_config::is_icon_theme_name(icon))
+        .and_then(|name| icons::load(name, layout.text_size.round() as u32));
+    // The image and the space after it, as wide as a glyph marker.
+    let icon_image_w = icon_image.as_ref().map_or(0.0, |_| layout.text_size * 1.25);
+
     if is_selected {
         let hx = card.x + layout.padding / 2.0;
         let hw = card.width - layout.padding;
@@ -561,7 +937,7 @@ fn draw_hint_row(
     }
 
     let badge_x = card.x + layout.padding;
-    let mut next_x = badge_x + layout.badge_width + layout.column_gap;
+    let next_x = badge_x + layout.badge_width + layout.column_gap;
 
     let badge_bg = match match_state {
         HintMatchState::Exact => theme.badge_matched_background,
@@ -574,6 +950,7 @@ fn draw_hint_row(
     };
 
     let badge_y = row_y + (layout.row_height - layout.badge_height) / 2.0;
+    let badge_x = place(badge_x, layout.badge_width);
     fill_rounded_rect(
         pixmap,
         badge_x,
@@ -584,7 +961,8 @@ fn draw_hint_row(
         badge_bg,
     );
 
-    let hint_text = row.hint.to_uppercase();
+    let mut hint_text = BumpString::new_in(arena);
+    hint_text.extend(row.hint.chars().flat_map(char::to_uppercase));
     let badge_attrs = Attrs::new()
         .family(Family::SansSerif)
         .weight(Weight::SEMIBOLD);
@@ -605,70 +983,270 @@ fn draw_hint_row(
     );
 
     if show_app_id {
-        let app_name = extract_app_name(row.app_id);
+        let app_name = row
+            .label
+            .unwrap_or_else(|| extract_app_name_in(arena, row.app_id));
         let attrs = Attrs::new()
             .family(Family::SansSerif)
             .weight(Weight::NORMAL);
-        let truncated = ellipsize_text(
+        // Apps with several windows show "Name 2" and a "×3" count badge, so
+        // it is clear why this row's hint is `gg` rather than `g`.
+        let count_size = layout.text_size * 0.75;
+        let count_attrs = attrs.weight(Weight::SEMIBOLD);
+        let counter = (row.position.count > 1).then(|| {
+            let text = bumpalo::format!(in arena, "\u{d7}{}", row.position.count);
+            let (tw, _) = measure_text(font_system, &text, count_size, count_attrs, None);
+            (text, tw, tw + count_size)
+        });
+        let index_label = counter
+            .as_ref()
+            .map(|_| bumpalo::format!(in arena, " {}", row.position.index));
+        let mut reserved = nest_marker.map_or(0.0, |m| {
+            measure_text(font_system, m, layout.text_size, attrs, None).0
+        });
+        if let (Some((_, _, pill_w)), Some(label)) = (&counter, &index_label) {
+            let (label_w, _) = measure_text(font_system, label, layout.text_size, attrs, None);
+            reserved += label_w + layout.column_gap / 2.0 + pill_w;
+        }
+        if let Some(marker) = &icon_marker {
+            reserved += measure_text(font_system, marker, layout.text_size, attrs, None).0;
+        }
+        reserved += icon_image_w;
+        let app_states = state_marker.as_deref().filter(|_| !show_title);
+        let app_time = time_marker.as_deref().filter(|_| !show_title);
+        for marker in app_states.into_iter().chain(app_time) {
+            reserved += measure_text(font_system, marker, layout.text_size, attrs, None).0;
+        }
+        let truncated = truncations.ellipsize(
             font_system,
-            &app_name,
+            app_name,
             layout.text_size,
             attrs,
-            layout.app_column_width,
+            (layout.app_column_width - reserved).max(0.0),
         );
         let ty = row_y + (layout.row_height - layout.text_size) / 2.0;
-        draw_text(
+        let mut spans = highlight_spans(
+            truncated,
+            truncated != app_name,
+            highlight.map_or(&[], |m| m.app_name.as_slice()),
+            text_primary,
+            theme.badge_matched_background,
+        );
+        if let Some(label) = &index_label {
+            spans.push(TextSpan::new(label, text_secondary));
+        }
+        if let Some(marker) = &icon_marker {
+            add_icon_marker(&mut spans, marker, theme);
+        }
+        if let Some(marker) = nest_marker {
+            add_nest_marker(&mut spans, marker, theme);
+        }
+        if let Some(marker) = app_states {
+            add_state_marker(&mut spans, marker, theme);
+        }
+        if let Some(marker) = app_time {
+            add_state_marker(&mut spans, marker, theme);
+        }
+        if let Some(image) = &icon_image {
+            draw_icon(pixmap, image, place(next_x, layout.text_size), ty);
+        }
+        let text_x = next_x + icon_image_w;
+        let (text_w, _) = measure_spans(font_system, &spans, layout.text_size, attrs, None);
+        draw_spans(
             pixmap,
             font_system,
             swash_cache,
-            next_x,
+            place(text_x, text_w),
             ty,
-            &truncated,
+            &spans,
             layout.text_size,
             attrs,
-            theme.text_primary,
             None,
         );
-        next_x += layout.app_column_width + layout.column_gap;
+        if let Some((text, tw, pill_w)) = &counter {
+            let pill_h = count_size * 1.5;
+            let pill_x = place(text_x + text_w + layout.column_gap / 2.0, *pill_w);
+            let pill_y = row_y + (layout.row_height - pill_h) / 2.0;
+            fill_rounded_rect(
+                pixmap,
+                pill_x,
+                pill_y,
+                *pill_w,
+                pill_h,
+                pill_h / 2.0,
+                theme.badge_background,
+            );
+            draw_text(
+                pixmap,
+                font_system,
+                swash_cache,
+                pill_x + (pill_w - tw) / 2.0,
+                pill_y + (pill_h - count_size) / 2.0,
+                text,
+                count_size,
+                count_attrs,
+                theme.badge_text,
+                None,
+            );
+        }
     }
 
+    let mut title_cut = false;
     if show_title {
-        let title_max = card.x + card.width - next_x - layout.padding;
-        if title_max > 50.0 {
+        let next_x = card.x + layout.title_offset(show_app_id);
+        let title_max = layout.title_width(card, show_app_id);
+        if title_max > layout.title_hide_width {
             let attrs = Attrs::new()
                 .family(Family::SansSerif)
                 .weight(Weight::NORMAL);
-            let truncated =
-                ellipsize_text(font_system, row.title, layout.text_size, attrs, title_max);
+            let title_marker = nest_marker.filter(|_| !show_app_id);
+            let title_icon = icon_marker.as_deref().filter(|_| !show_app_id);
+            let title_image = icon_image.as_ref().filter(|_| !show_app_id);
+            let image_w = title_image.map_or(0.0, |_| icon_image_w);
+            let marker_w = title_marker
+                .into_iter()
+                .chain(title_icon)
+                .chain(state_marker.as_deref())
+                .chain(time_marker.as_deref())
+                .map(|m| measure_text(font_system, m, layout.text_size, attrs, None).0)
+                .sum::<f32>()
+                + image_w;
+            let truncated = truncations.ellipsize(
+                font_system,
+                row.title,
+                layout.text_size,
+                attrs,
+                title_max - marker_w,
+            );
+            title_cut = truncated != row.title;
             let ty = row_y + (layout.row_height - layout.text_size) / 2.0;
-            draw_text(
+            let mut spans = highlight_spans(
+                truncated,
+                title_cut,
+                highlight.map_or(&[], |m| m.title.as_slice()),
+                text_secondary,
+                theme.badge_matched_background,
+            );
+            if let Some(marker) = title_icon {
+                add_icon_marker(&mut spans, marker, theme);
+            }
+            if let Some(marker) = title_marker {
+                add_nest_marker(&mut spans, marker, theme);
+            }
+            if let Some(marker) = &state_marker {
+                add_state_marker(&mut spans, marker, theme);
+            }
+            if let Some(marker) = &time_marker {
+                add_state_marker(&mut spans, marker, theme);
+            }
+            if let Some(image) = title_image {
+                draw_icon(pixmap, image, place(next_x, layout.text_size), ty);
+            }
+            let (text_w, _) = measure_spans(font_system, &spans, layout.text_size, attrs, None);
+            draw_spans(
                 pixmap,
                 font_system,
                 swash_cache,
-                next_x,
+                place(next_x + image_w, text_w),
                 ty,
-                &truncated,
+                &spans,
                 layout.text_size,
                 attrs,
-                theme.text_secondary,
                 None,
             );
         }
     }
+    title_cut
 }
 
-fn draw_input_indicator(
+/// Draw the "+N more" footer in the row slot at `row_y`, centered.
+#[allow(clippy::too_many_arguments)]
+fn draw_more_footer(
     pixmap: &mut tiny_skia::Pixmap,
     font_system: &mut FontSystem,
     swash_cache: &mut SwashCache,
+    arena: &Bump,
     card: &CardRect,
-    input: &str,
+    row_y: f32,
+    more: usize,
     layout: &Layout,
     theme: &OverlayTheme,
 ) {
-    let text = format!("\u{203a} {input}");
     let attrs = Attrs::new().family(Family::SansSerif);
+    let text = bumpalo::format!(in arena, "+{} more \u{2014} keep typing", more);
     let (tw, _) = measure_text(font_system, &text, layout.text_size, attrs, None);
+    draw_text(
+        pixmap,
+        font_system,
+        swash_cache,
+        card.x + (card.width - tw) / 2.0,
+        row_y + (layout.row_height - layout.text_size) / 2.0,
+        &text,
+        layout.text_size,
+        attrs,
+        theme.text_secondary,
+        None,
+    );
+}
+
+/// Put the nested-dialog arrow on the badge side of `spans`: before the
+/// text left to right, after it right to left.
+fn add_nest_marker<'t>(spans: &mut Vec<TextSpan<'t>>, marker: &'t str, theme: &OverlayTheme) {
+    let span = TextSpan::new(marker, theme.text_secondary);
+    match theme.direction {
+        Direction::Ltr => spans.insert(0, span),
+        Direction::Rtl => spans.push(span),
+    }
+}
+
+/// Draw a key binding's icon theme image with its top-left corner at
+/// (`x`, `y`), rounded to whole pixels so it stays sharp.
+#[allow(clippy::cast_possible_truncation)] // pixel coordinates on the overlay
+fn draw_icon(pixmap: &mut tiny_skia::Pixmap, image: &tiny_skia::Pixmap, x: f32, y: f32) {
+    pixmap.draw_pixmap(
+        x.round() as i32,
+        y.round() as i32,
+        image.as_ref(),
+        &tiny_skia::PixmapPaint::default(),
+        tiny_skia::Transform::identity(),
+        None,
+    );
+}
+
+/// Put a key binding's icon glyph at the start of `spans`: before the text
+/// left to right, after it right to left.
+fn add_icon_marker<'t>(spans: &mut Vec<TextSpan<'t>>, marker: &'t str, theme: &OverlayTheme) {
+    let span = TextSpan::new(marker, theme.text_primary);
+    match theme.direction {
+        Direction::Ltr => spans.insert(0, span),
+        Direction::Rtl => spans.push(span),
+    }
+}
+
+/// Put the window-state icons at the end of `spans`: after the text left to
+/// right, before it right to left.
+fn add_state_marker<'t>(spans: &mut Vec<TextSpan<'t>>, marker: &'t str, theme: &OverlayTheme) {
+    let span = TextSpan::new(marker, theme.text_secondary);
+    match theme.direction {
+        Direction::Ltr => spans.push(span),
+        Direction::Rtl => spans.insert(0, span),
+    }
+}
+
+/// Draw `text` in a pill centered beneath the card (typed input, notices).
+#[allow(clippy::too_many_arguments)]
+fn draw_pill(
+    pixmap: &mut tiny_skia::Pixmap,
+    font_system: &mut FontSystem,
+    swash_cache: &mut SwashCache,
+    card: &CardRect,
+    text: &str,
+    color: Color,
+    layout: &Layout,
+    theme: &OverlayTheme,
+) {
+    let attrs = Attrs::new().family(Family::SansSerif);
+    let (tw, _) = measure_text(font_system, text, layout.text_size, attrs, None);
 
     let pill_pad_h = layout.padding;
     let pill_pad_v = layout.padding / 2.0;
@@ -686,10 +1264,10 @@ fn draw_input_indicator(
         swash_cache,
         pill_x + pill_pad_h,
         text_y,
-        &text,
+        text,
         layout.text_size,
         attrs,
-        theme.text_primary,
+        color,
         None,
     );
 }
@@ -698,14 +1276,56 @@ fn draw_input_indicator(
 // Utilities
 // ---------------------------------------------------------------------------
 
+/// Split `text` into plain and highlighted spans for the byte `ranges`
+/// (sorted, non-overlapping) of the original string. Highlights are
+/// semibold in the `highlight` color. `truncated` means `text` is a prefix
+/// of the original followed by an ellipsis, which is never highlighted.
+fn highlight_spans<'t>(
+    text: &'t str,
+    truncated: bool,
+    ranges: &[Range<usize>],
+    plain: Color,
+    highlight: Color,
+) -> Vec<TextSpan<'t>> {
+    let limit = if truncated {
+        text.len().saturating_sub('\u{2026}'.len_utf8())
+    } else {
+        text.len()
+    };
+    let mut spans = Vec::new();
+    let mut pos = 0;
+    for range in ranges {
+        let (start, end) = (range.start.min(limit), range.end.min(limit));
+        if start >= end {
+            continue;
+        }
+        if pos < start {
+            spans.push(TextSpan::new(&text[pos..start], plain));
+        }
+        spans.push(TextSpan::new(&text[start..end], highlight).weight(Weight::SEMIBOLD));
+        pos = end;
+    }
+    if pos < text.len() {
+        spans.push(TextSpan::new(&text[pos..], plain));
+    }
+    spans
+}
+
 /// Extract a friendly app name from an app_id (reverse-DNS → last segment, capitalize).
 pub fn extract_app_name(app_id: &str) -> String {
+    extract_app_name_in(&Bump::new(), app_id).to_owned()
+}
+
+/// [`extract_app_name`], allocated in `arena`.
+fn extract_app_name_in<'a>(arena: &'a Bump, app_id: &str) -> &'a str {
     let name = app_id.split('.').next_back().unwrap_or(app_id);
-    let mut chars: Vec<char> = name.chars().collect();
-    if let Some(first) = chars.first_mut() {
-        *first = first.to_ascii_uppercase();
+    let mut chars = name.chars();
+    let mut out = BumpString::with_capacity_in(name.len(), arena);
+    if let Some(first) = chars.next() {
+        out.push(first.to_ascii_uppercase());
     }
-    chars.into_iter().collect()
+    out.extend(chars);
+    out.into_bump_str()
 }
 
 /// Convert tiny-skia RGBA pixel buffer to Wayland ARGB8888 in-place.
@@ -721,11 +1341,13 @@ pub fn compute_card_rect(
     screen_w: f32,
     screen_h: f32,
     scale: f32,
+    theme: &OverlayTheme,
     show_app_id: bool,
     show_title: bool,
 ) -> (f32, f32, f32, f32) {
     let l = Layout::new(scale);
-    let card = layout::calculate_card(row_count, screen_w, screen_h, &l, show_app_id, show_title);
+    let rows = Page::new(row_count, 0, l.max_rows(screen_h), theme.page_rows).card_rows();
+    let card = layout::calculate_card(rows, screen_w, screen_h, &l, show_app_id, show_title);
     (card.x, card.y, card.width, card.height)
 }
 
@@ -755,6 +1377,63 @@ mod tests {
         assert!(theme.corner_radius > 0.0);
     }
 
+    #[test]
+    fn default_badge_text_meets_wcag_aa() {
+        let theme = OverlayTheme::default();
+        let ratio = color::contrast_ratio(theme.badge_text, theme.badge_background);
+        assert!(ratio >= 4.5, "contrast {ratio:.2}");
+    }
+
+    #[test]
+    fn light_card_color_keeps_text_readable() {
+        let cfg = core_config::WmConfig {
+            card_color: "#e8e8e8f0".into(),
+            hint_matched_color: "#c8e6c9".into(),
+            ..Default::default()
+        };
+        let theme = OverlayTheme::from_config(&cfg);
+        for (text, background) in [
+            (theme.text_primary, theme.card_background),
+            (theme.text_secondary, theme.card_background),
+            (theme.badge_matched_text, theme.badge_matched_background),
+        ] {
+            let ratio = color::contrast_ratio(text, background);
+            assert!(
+                ratio >= core_config::MIN_TEXT_CONTRAST,
+                "contrast {ratio:.2}"
+            );
+        }
+    }
+
+    #[test]
+    fn invalid_colors_are_reported() {
+        assert!(OverlayTheme::invalid_colors(&core_config::WmConfig::default()).is_empty());
+        let cfg = core_config::WmConfig {
+            card_color: "#12345".into(),
+            hint_color: "green".into(),
+            ..Default::default()
+        };
+        assert_eq!(
+            OverlayTheme::invalid_colors(&cfg),
+            ["card_color", "hint_color"]
+        );
+    }
+
+    #[test]
+    fn highlight_spans_clip_at_ellipsis() {
+        let (plain, hl) = (Color::rgb(0, 0, 0), Color::rgb(255, 255, 255));
+        fn texts(spans: Vec<TextSpan<'_>>) -> Vec<&str> {
+            spans.into_iter().map(|span| span.text).collect()
+        }
+
+        let spans = highlight_spans("Mozilla Firefox", false, &[0..3, 12..15], plain, hl);
+        assert_eq!(texts(spans), ["Moz", "illa Fire", "fox"]);
+
+        // "Mozilla Fi…": the second match was cut off, the first is kept.
+        let spans = highlight_spans("Mozilla Fi\u{2026}", true, &[0..3, 12..15], plain, hl);
+        assert_eq!(texts(spans), ["Moz", "illa Fi\u{2026}"]);
+    }
+
     #[test]
     fn rgba_to_argb_conversion() {
         let mut buf = [255u8, 0, 0, 128];
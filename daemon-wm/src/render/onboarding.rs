@@ -0,0 +1,128 @@
+//! First-run walkthrough page, drawn in place of the picker.
+//!
+//! A centered card: the page title, its lines, then a row of page dots and
+//! the keys that turn the page. Like the help card it is sized by its text
+//! and drawn near-opaque ([`tooltip::opaque`]).
+
+use super::layout::Layout;
+use super::primitives::fill_rounded_rect;
+use super::text::{draw_text, measure_text};
+use super::{OverlayTheme, draw_card, tooltip};
+use crate::onboarding::{DONT_SHOW_AGAIN, WalkthroughView};
+use cosmic_text::{Attrs, Family, FontSystem, SwashCache, Weight};
+
+/// Draw walkthrough `page` centered on a `width`×`height` output.
+#[allow(clippy::too_many_arguments)]
+pub fn draw_walkthrough(
+    pixmap: &mut tiny_skia::Pixmap,
+    font_system: &mut FontSystem,
+    swash_cache: &mut SwashCache,
+    width: f32,
+    height: f32,
+    scale: f32,
+    page: &WalkthroughView,
+    theme: &OverlayTheme,
+) {
+    let layout = Layout::new(scale);
+    pixmap.fill(tiny_skia::Color::TRANSPARENT);
+    let attrs = Attrs::new().family(Family::SansSerif);
+    let bold = attrs.weight(Weight::BOLD);
+    let title_size = layout.text_size * 1.4;
+    let pad = layout.padding * 2.0;
+    let max_width = (width * 0.8).min(layout.message_max_width * 1.5);
+
+    let last = page.index + 1 == page.count;
+    let keys = format!(
+        "{} · Esc close · {} don't show again",
+        if last { "Enter done" } else { "Enter next" },
+        DONT_SHOW_AGAIN.to_ascii_uppercase(),
+    );
+    let body = page.page.lines.join("\n");
+
+    let (title_w, title_h) = measure_text(
+        font_system,
+        &page.page.title,
+        title_size,
+        bold,
+        Some(max_width),
+    );
+    let (body_w, body_h) =
+        measure_text(font_system, &body, layout.text_size, attrs, Some(max_width));
+    let (keys_w, keys_h) =
+        measure_text(font_system, &keys, layout.text_size, attrs, Some(max_width));
+    let dot = layout.text_size * 0.5;
+    let dots_w = dot * (2 * page.count).saturating_sub(1) as f32;
+
+    let gap = layout.padding;
+    let cw = title_w.max(body_w).max(keys_w + gap + dots_w) + pad * 2.0;
+    let ch = title_h + gap + body_h + gap * 2.0 + keys_h + pad * 2.0;
+    let cx = ((width - cw) / 2.0).max(0.0);
+    let cy = ((height - ch) / 2.0).max(0.0);
+    draw_card(
+        pixmap,
+        cx,
+        cy,
+        cw,
+        ch,
+        layout.corner_radius,
+        &tooltip::opaque(theme),
+    );
+
+    let mut y = cy + pad;
+    draw_text(
+        pixmap,
+        font_system,
+        swash_cache,
+        cx + pad,
+        y,
+        &page.page.title,
+        title_size,
+        bold,
+        theme.text_primary,
+        Some(max_width),
+    );
+    y += title_h + gap;
+    draw_text(
+        pixmap,
+        font_system,
+        swash_cache,
+        cx + pad,
+        y,
+        &body,
+        layout.text_size,
+        attrs,
+        theme.text_primary,
+        Some(max_width),
+    );
+    y += body_h + gap * 2.0;
+
+    for i in 0..page.count {
+        let color = if i == page.index {
+            theme.badge_matched_background
+        } else {
+            theme.badge_background
+        };
+        let x = cx + pad + dot * 2.0 * i as f32;
+        fill_rounded_rect(
+            pixmap,
+            x,
+            y + (keys_h - dot) / 2.0,
+            dot,
+            dot,
+            dot / 2.0,
+            color,
+        );
+    }
+    draw_text(
+        pixmap,
+        font_system,
+        swash_cache,
+        cx + cw - pad - keys_w,
+        y,
+        &keys,
+        layout.text_size,
+        attrs,
+        theme.text_secondary,
+        Some(max_width),
+    );
+}
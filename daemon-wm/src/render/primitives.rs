@@ -32,7 +32,7 @@ pub fn fill_rounded_rect(
         return;
     };
     let mut paint = tiny_skia::Paint::default();
-    paint.set_color(color.to_tiny_skia());
+    paint.set_color(color.into());
     paint.anti_alias = true;
     pixmap.fill_path(
         &path,
@@ -58,7 +58,7 @@ pub fn stroke_rounded_rect(
         return;
     };
     let mut paint = tiny_skia::Paint::default();
-    paint.set_color(color.to_tiny_skia());
+    paint.set_color(color.into());
     paint.anti_alias = true;
     let stroke = tiny_skia::Stroke {
         width: stroke_width,
@@ -74,3 +74,76 @@ pub fn stroke_rounded_rect(
         None,
     );
 }
+
+/// Build an open arc path starting at 12 o'clock and sweeping clockwise by
+/// `fraction` of a full turn. Returns `None` for an empty sweep.
+pub fn arc_path(cx: f32, cy: f32, radius: f32, fraction: f32) -> Option<tiny_skia::Path> {
+    let fraction = fraction.clamp(0.0, 1.0);
+    if fraction <= 0.0 {
+        return None;
+    }
+    // Polyline approximation: 64 segments per full turn is smooth at the
+    // ring sizes used here.
+    let segments = ((64.0 * fraction).ceil() as usize).max(1);
+    let sweep = std::f32::consts::TAU * fraction;
+    let point = |i: usize| {
+        let angle = -std::f32::consts::FRAC_PI_2 + sweep * i as f32 / segments as f32;
+        (cx + radius * angle.cos(), cy + radius * angle.sin())
+    };
+    let mut pb = tiny_skia::PathBuilder::new();
+    let (x, y) = point(0);
+    pb.move_to(x, y);
+    for i in 1..=segments {
+        let (x, y) = point(i);
+        pb.line_to(x, y);
+    }
+    pb.finish()
+}
+
+pub fn stroke_arc(
+    pixmap: &mut tiny_skia::Pixmap,
+    cx: f32,
+    cy: f32,
+    radius: f32,
+    fraction: f32,
+    color: Color,
+    stroke_width: f32,
+) {
+    let Some(path) = arc_path(cx, cy, radius, fraction) else {
+        return;
+    };
+    let mut paint = tiny_skia::Paint::default();
+    paint.set_color(color.into());
+    paint.anti_alias = true;
+    let stroke = tiny_skia::Stroke {
+        width: stroke_width,
+        line_cap: tiny_skia::LineCap::Round,
+        ..Default::default()
+    };
+    pixmap.stroke_path(
+        &path,
+        &paint,
+        &stroke,
+        tiny_skia::Transform::identity(),
+        None,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arc_path_spans_requested_fraction() {
+        assert!(arc_path(50.0, 50.0, 10.0, 0.0).is_none());
+
+        let half = arc_path(50.0, 50.0, 10.0, 0.5).unwrap().bounds();
+        // 12 o'clock clockwise to 6 o'clock: right half of the circle.
+        assert!((half.left() - 50.0).abs() < 0.5);
+        assert!((half.right() - 60.0).abs() < 0.5);
+        assert!((half.height() - 20.0).abs() < 0.5);
+
+        let full = arc_path(50.0, 50.0, 10.0, 2.0).unwrap().bounds();
+        assert!((full.width() - 20.0).abs() < 0.5);
+    }
+}
@@ -0,0 +1,103 @@
+//! Row context menu: the selected row's verbs in a bubble next to it, each
+//! with its shortcut.
+//!
+//! The bubble is placed like the title tooltip ([`tooltip::place`]): below
+//! the row, or above it near the bottom of the output.
+
+use super::layout::{CardRect, Direction, Layout};
+use super::primitives::fill_rounded_rect;
+use super::text::{draw_text, measure_text};
+use super::{OverlayTheme, draw_card, tooltip};
+use crate::row_menu::MenuView;
+use cosmic_text::{Attrs, Family, FontSystem, SwashCache};
+
+/// Draw `menu` for the row at `anchor` on a `width`×`height` output.
+#[allow(clippy::too_many_arguments)]
+pub fn draw_row_menu(
+    pixmap: &mut tiny_skia::Pixmap,
+    font_system: &mut FontSystem,
+    swash_cache: &mut SwashCache,
+    anchor: &CardRect,
+    menu: &MenuView,
+    width: f32,
+    height: f32,
+    layout: &Layout,
+    theme: &OverlayTheme,
+) {
+    let attrs = Attrs::new().family(Family::SansSerif);
+    let pad = layout.padding / 2.0;
+    let gap = layout.row_spacing;
+    let mut label_w: f32 = 0.0;
+    let mut shortcut_w: f32 = 0.0;
+    let mut line_h: f32 = 0.0;
+    for entry in &menu.entries {
+        let (w, h) = measure_text(font_system, &entry.label, layout.text_size, attrs, None);
+        label_w = label_w.max(w);
+        line_h = line_h.max(h);
+        let (w, _) = measure_text(font_system, &entry.shortcut, layout.text_size, attrs, None);
+        shortcut_w = shortcut_w.max(w);
+    }
+    let entry_h = line_h + pad;
+    let bubble = tooltip::place(
+        anchor,
+        label_w + layout.column_gap + shortcut_w + pad * 2.0,
+        entry_h * menu.entries.len() as f32 + pad,
+        gap,
+        width,
+        height,
+        theme.direction,
+    );
+    draw_card(
+        pixmap,
+        bubble.x,
+        bubble.y,
+        bubble.width,
+        bubble.height,
+        layout.badge_radius,
+        &tooltip::opaque(theme),
+    );
+
+    let (label_x, shortcut_x) = match theme.direction {
+        Direction::Ltr => (bubble.x + pad, bubble.x + bubble.width - pad - shortcut_w),
+        Direction::Rtl => (bubble.x + bubble.width - pad - label_w, bubble.x + pad),
+    };
+    for (i, entry) in menu.entries.iter().enumerate() {
+        let y = bubble.y + pad / 2.0 + entry_h * i as f32;
+        if i == menu.selected {
+            fill_rounded_rect(
+                pixmap,
+                bubble.x + pad / 2.0,
+                y,
+                bubble.width - pad,
+                entry_h,
+                layout.badge_radius,
+                theme.selection_highlight,
+            );
+        }
+        let text_y = y + pad / 2.0;
+        draw_text(
+            pixmap,
+            font_system,
+            swash_cache,
+            label_x,
+            text_y,
+            &entry.label,
+            layout.text_size,
+            attrs,
+            theme.text_primary,
+            None,
+        );
+        draw_text(
+            pixmap,
+            font_system,
+            swash_cache,
+            shortcut_x,
+            text_y,
+            &entry.shortcut,
+            layout.text_size,
+            attrs,
+            theme.text_secondary,
+            None,
+        );
+    }
+}
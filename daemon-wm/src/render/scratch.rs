@@ -0,0 +1,127 @@
+//! Memory reused across overlay frames.
+//!
+//! A redraw after a key press lays out the same rows as the last one. The
+//! per-frame strings and lists (visible rows, hint labels, markers) go in a
+//! bump arena that is reset at the start of each frame, so steady-state
+//! redraws reuse one block instead of allocating per row. Ellipsized text
+//! is cached by text, width and font, since it costs a binary search of
+//! shaping passes and rarely changes between frames.
+//!
+//! cosmic-text still allocates its own shaping buffers.
+
+use super::text::ellipsize_text;
+use bumpalo::Bump;
+use cosmic_text::{Attrs, FontSystem};
+use std::collections::HashMap;
+
+/// Cached truncations past which the cache is emptied, bounding it when
+/// window titles keep changing (terminals, media players).
+const MAX_TRUNCATIONS: usize = 1024;
+
+/// Per-frame arena and cross-frame caches, owned by the overlay and passed
+/// to [`super::draw_full_overlay`].
+#[derive(Default)]
+pub struct FrameScratch {
+    pub arena: Bump,
+    pub truncations: TruncationCache,
+}
+
+impl FrameScratch {
+    /// Start a frame: everything allocated in the arena last frame is
+    /// freed, keeping its capacity.
+    pub fn reset(&mut self) {
+        self.arena.reset();
+    }
+}
+
+/// [`ellipsize_text`] results keyed by (max width, font size, weight), then
+/// by text.
+#[derive(Default)]
+pub struct TruncationCache {
+    entries: HashMap<(u32, u32, u16), HashMap<String, String>>,
+    len: usize,
+}
+
+impl TruncationCache {
+    /// `text` ellipsized to `max_width`, shaped only on a cache miss.
+    pub fn ellipsize(
+        &mut self,
+        font_system: &mut FontSystem,
+        text: &str,
+        font_size: f32,
+        attrs: Attrs<'_>,
+        max_width: f32,
+    ) -> &str {
+        let key = (max_width.to_bits(), font_size.to_bits(), attrs.weight.0);
+        self.get_or_insert_with(key, text, || {
+            ellipsize_text(font_system, text, font_size, attrs, max_width)
+        })
+    }
+
+    fn get_or_insert_with(
+        &mut self,
+        key: (u32, u32, u16),
+        text: &str,
+        truncate: impl FnOnce() -> String,
+    ) -> &str {
+        let cached = self
+            .entries
+            .get(&key)
+            .is_some_and(|texts| texts.contains_key(text));
+        if !cached {
+            if self.len >= MAX_TRUNCATIONS {
+                self.entries.clear();
+                self.len = 0;
+            }
+            self.entries
+                .entry(key)
+                .or_default()
+                .insert(text.to_owned(), truncate());
+            self.len += 1;
+        }
+        &self.entries[&key][text]
+    }
+
+    /// Number of cached truncations.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn truncations_are_cached_by_key_and_bounded() {
+        let mut cache = TruncationCache::default();
+        let shaped = std::cell::Cell::new(0);
+        let shape = |key, text: &str, cache: &mut TruncationCache| {
+            cache
+                .get_or_insert_with(key, text, || {
+                    shaped.set(shaped.get() + 1);
+                    format!("{}\u{2026}", &text[..4])
+                })
+                .to_owned()
+        };
+        let title = "~/src/open-sesame/daemon-wm/src/render/scratch.rs - Code";
+        let key = (80.0_f32.to_bits(), 16.0_f32.to_bits(), 400);
+
+        assert_eq!(shape(key, title, &mut cache), "~/sr\u{2026}");
+        assert_eq!(shape(key, title, &mut cache), "~/sr\u{2026}");
+        shape((120.0_f32.to_bits(), key.1, key.2), title, &mut cache);
+        shape(key, "another title", &mut cache);
+        assert_eq!(cache.len(), 3);
+
+        for i in 0..MAX_TRUNCATIONS {
+            shape(key, &format!("title {i}"), &mut cache);
+        }
+        assert!(cache.len() <= MAX_TRUNCATIONS);
+        assert!(!cache.is_empty());
+        assert_eq!(shaped.get(), 3 + MAX_TRUNCATIONS);
+    }
+}
@@ -1,7 +1,8 @@
 //! cosmic-text shaping, measurement, and glyph rasterization.
 
 use super::Color;
-use cosmic_text::{Attrs, Buffer, FontSystem, Metrics, Shaping, SwashCache};
+use super::primitives::fill_rounded_rect;
+use cosmic_text::{Attrs, Buffer, FontSystem, Metrics, Shaping, SwashCache, Weight};
 
 /// Measure text dimensions without rendering.
 pub fn measure_text(
@@ -44,9 +45,176 @@ pub fn draw_text(
     let mut buffer = Buffer::new(font_system, metrics);
     buffer.set_size(font_system, max_width, None);
     buffer.set_text(font_system, text, attrs, Shaping::Advanced);
+    blit_buffer(pixmap, font_system, swash_cache, &mut buffer, x, y, color)
+}
+
+/// A run of text with its own style, drawn by [`draw_spans`].
+#[derive(Debug, Clone, Copy)]
+pub struct TextSpan<'a> {
+    pub text: &'a str,
+    pub color: Color,
+    pub weight: Weight,
+    pub underline: bool,
+}
+
+impl<'a> TextSpan<'a> {
+    /// Normal-weight text without underline.
+    pub fn new(text: &'a str, color: Color) -> Self {
+        Self {
+            text,
+            color,
+            weight: Weight::NORMAL,
+            underline: false,
+        }
+    }
+
+    pub fn weight(mut self, weight: Weight) -> Self {
+        self.weight = weight;
+        self
+    }
+
+    pub fn underline(mut self) -> Self {
+        self.underline = true;
+        self
+    }
+}
+
+/// Build a buffer for `spans` on top of `attrs` (family, style). Each glyph's
+/// `metadata` is the index of the span it came from.
+fn span_buffer(
+    font_system: &mut FontSystem,
+    spans: &[TextSpan<'_>],
+    font_size: f32,
+    attrs: Attrs<'_>,
+    max_width: Option<f32>,
+) -> Buffer {
+    let metrics = Metrics::new(font_size, font_size * 1.3);
+    let mut buffer = Buffer::new(font_system, metrics);
+    buffer.set_size(font_system, max_width, None);
+    buffer.set_rich_text(
+        font_system,
+        spans.iter().enumerate().map(|(i, span)| {
+            let span_attrs = attrs
+                .color(span.color.into())
+                .weight(span.weight)
+                .metadata(i);
+            (span.text, span_attrs)
+        }),
+        attrs,
+        Shaping::Advanced,
+    );
+    buffer
+}
+
+/// Measure a styled line without rendering. Weight changes advances, so this
+/// can differ from `measure_text` on the concatenated string.
+pub fn measure_spans(
+    font_system: &mut FontSystem,
+    spans: &[TextSpan<'_>],
+    font_size: f32,
+    attrs: Attrs<'_>,
+    max_width: Option<f32>,
+) -> (f32, f32) {
+    let mut buffer = span_buffer(font_system, spans, font_size, attrs, max_width);
     buffer.shape_until_scroll(font_system, false);
+    let line_height = buffer.metrics().line_height;
+    let mut total_w: f32 = 0.0;
+    let mut total_h: f32 = 0.0;
+    for run in buffer.layout_runs() {
+        total_w = total_w.max(run.line_w);
+        total_h = run.line_y + line_height;
+    }
+    (total_w, total_h)
+}
 
-    let text_color = color.to_cosmic_text();
+/// Render a line mixing colors, weights, and underlines in one draw call.
+/// The spans are shaped together, so advances and kerning across span
+/// boundaries are the same as for a single string.
+#[allow(clippy::too_many_arguments)]
+pub fn draw_spans(
+    pixmap: &mut tiny_skia::Pixmap,
+    font_system: &mut FontSystem,
+    swash_cache: &mut SwashCache,
+    x: f32,
+    y: f32,
+    spans: &[TextSpan<'_>],
+    font_size: f32,
+    attrs: Attrs<'_>,
+    max_width: Option<f32>,
+) -> (f32, f32) {
+    let mut buffer = span_buffer(font_system, spans, font_size, attrs, max_width);
+    let default = spans
+        .first()
+        .map_or(Color::rgba(0, 0, 0, 0), |span| span.color);
+    let size = blit_buffer(pixmap, font_system, swash_cache, &mut buffer, x, y, default);
+
+    let thickness = (font_size / 14.0).max(1.0);
+    for underline in underlines(&buffer, spans) {
+        fill_rounded_rect(
+            pixmap,
+            x + underline.start,
+            y + underline.line_y + font_size * 0.15,
+            underline.end - underline.start,
+            thickness,
+            0.0,
+            spans[underline.span].color,
+        );
+    }
+    size
+}
+
+/// Horizontal extent of one underline, relative to the buffer origin.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Underline {
+    span: usize,
+    line_y: f32,
+    start: f32,
+    end: f32,
+}
+
+/// One underline for each contiguous run of glyphs from an underlined span
+/// in a shaped `buffer` built by [`span_buffer`].
+fn underlines(buffer: &Buffer, spans: &[TextSpan<'_>]) -> Vec<Underline> {
+    let mut out = Vec::new();
+    for run in buffer.layout_runs() {
+        let mut current: Option<Underline> = None;
+        for glyph in run.glyphs {
+            match &mut current {
+                Some(underline) if underline.span == glyph.metadata => {
+                    underline.end = glyph.x + glyph.w;
+                }
+                _ => {
+                    out.extend(current.take());
+                    if spans.get(glyph.metadata).is_some_and(|s| s.underline) {
+                        current = Some(Underline {
+                            span: glyph.metadata,
+                            line_y: run.line_y,
+                            start: glyph.x,
+                            end: glyph.x + glyph.w,
+                        });
+                    }
+                }
+            }
+        }
+        out.extend(current);
+    }
+    out
+}
+
+/// Shape `buffer` and alpha-blend its glyphs onto the pixmap at `(x, y)`.
+/// Glyphs without a per-span color use `color`. Returns the laid-out size.
+fn blit_buffer(
+    pixmap: &mut tiny_skia::Pixmap,
+    font_system: &mut FontSystem,
+    swash_cache: &mut SwashCache,
+    buffer: &mut Buffer,
+    x: f32,
+    y: f32,
+    color: Color,
+) -> (f32, f32) {
+    buffer.shape_until_scroll(font_system, false);
+
+    let text_color: cosmic_text::Color = color.into();
     let pw = pixmap.width();
     let ph = pixmap.height();
     let data = pixmap.data_mut();
@@ -86,9 +254,10 @@ pub fn draw_text(
         },
     );
 
+    let line_height = buffer.metrics().line_height;
     for run in buffer.layout_runs() {
         total_w = total_w.max(run.line_w);
-        total_h = run.line_y + metrics.line_height;
+        total_h = run.line_y + line_height;
     }
     (total_w, total_h)
 }
@@ -129,3 +298,108 @@ pub fn ellipsize_text(
     let prefix: String = chars[..lo].iter().collect();
     format!("{prefix}{ellipsis}")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmic_text::Family;
+
+    const SIZE: f32 = 16.0;
+    const WHITE: Color = Color::rgba(255, 255, 255, 255);
+
+    /// The system fonts, or `None` on a machine without any (the tests
+    /// that need real advances skip there).
+    fn font_system() -> Option<FontSystem> {
+        let font_system = FontSystem::new();
+        (!font_system.db().is_empty()).then_some(font_system)
+    }
+
+    fn attrs() -> Attrs<'static> {
+        Attrs::new().family(Family::SansSerif)
+    }
+
+    fn shaped(font_system: &mut FontSystem, spans: &[TextSpan<'_>]) -> Buffer {
+        let mut buffer = span_buffer(font_system, spans, SIZE, attrs(), None);
+        buffer.shape_until_scroll(font_system, false);
+        buffer
+    }
+
+    /// Left and right edge of the glyphs that came from `span`.
+    fn glyph_extent(buffer: &Buffer, span: usize) -> (f32, f32) {
+        buffer
+            .layout_runs()
+            .flat_map(|run| run.glyphs.iter())
+            .filter(|glyph| glyph.metadata == span)
+            .fold((f32::MAX, f32::MIN), |(lo, hi), glyph| {
+                (lo.min(glyph.x), hi.max(glyph.x + glyph.w))
+            })
+    }
+
+    #[test]
+    fn normal_spans_measure_like_the_joined_string() {
+        let Some(mut fs) = font_system() else { return };
+        let spans = [
+            TextSpan::new("fire", WHITE),
+            TextSpan::new("fox ", WHITE),
+            TextSpan::new("Terminal", WHITE),
+        ];
+        let (w, h) = measure_spans(&mut fs, &spans, SIZE, attrs(), None);
+        let (tw, th) = measure_text(&mut fs, "firefox Terminal", SIZE, attrs(), None);
+        assert!((w - tw).abs() < 0.01, "{w} vs {tw}");
+        assert!((h - th).abs() < 0.01, "{h} vs {th}");
+    }
+
+    #[test]
+    fn semibold_spans_measure_wider() {
+        let Some(mut fs) = font_system() else { return };
+        let normal = [TextSpan::new("fi", WHITE), TextSpan::new("refox", WHITE)];
+        let bold = [
+            TextSpan::new("fi", WHITE).weight(Weight::SEMIBOLD),
+            TextSpan::new("refox", WHITE),
+        ];
+        let (normal_w, _) = measure_spans(&mut fs, &normal, SIZE, attrs(), None);
+        let (bold_w, _) = measure_spans(&mut fs, &bold, SIZE, attrs(), None);
+        assert!(bold_w > normal_w, "{bold_w} <= {normal_w}");
+    }
+
+    #[test]
+    fn underlined_span_gets_one_underline_over_its_glyphs() {
+        let Some(mut fs) = font_system() else { return };
+        let spans = [
+            TextSpan::new("Fire", WHITE),
+            TextSpan::new("fox", WHITE).underline(),
+            TextSpan::new(" Web", WHITE),
+        ];
+        let buffer = shaped(&mut fs, &spans);
+        let found = underlines(&buffer, &spans);
+        assert_eq!(found.len(), 1, "{found:?}");
+        let (start, end) = glyph_extent(&buffer, 1);
+        assert_eq!(found[0].span, 1);
+        assert!((found[0].start - start).abs() < 0.01);
+        assert!((found[0].end - end).abs() < 0.01);
+    }
+
+    #[test]
+    fn adjacent_underlined_spans_stay_separate() {
+        let Some(mut fs) = font_system() else { return };
+        let spans = [
+            TextSpan::new("fire", WHITE).underline(),
+            TextSpan::new("fox", WHITE).underline(),
+            TextSpan::new(" Web", WHITE),
+        ];
+        let buffer = shaped(&mut fs, &spans);
+        let found = underlines(&buffer, &spans);
+        assert_eq!(
+            found.iter().map(|u| u.span).collect::<Vec<_>>(),
+            [0, 1],
+            "{found:?}"
+        );
+        for underline in &found {
+            let (start, end) = glyph_extent(&buffer, underline.span);
+            assert!((underline.start - start).abs() < 0.01, "{underline:?}");
+            assert!((underline.end - end).abs() < 0.01, "{underline:?}");
+        }
+        // They meet where the spans do, without overlapping.
+        assert!(found[0].end <= found[1].start + 0.01);
+    }
+}
@@ -0,0 +1,155 @@
+//! Tooltip bubble: the full text of a label that was cut off, drawn next to
+//! the label it expands.
+//!
+//! Layout is its own pass: [`place`] positions a measured bubble against
+//! its anchor and the output edges, so the geometry needs no fonts.
+
+use super::layout::{CardRect, Direction, Layout};
+use super::text::{draw_text, measure_text};
+use super::{OverlayTheme, TOAST_MIN_ALPHA, draw_card};
+use cosmic_text::{Attrs, Family, FontSystem, SwashCache};
+
+/// Position a `w`×`h` bubble for `anchor` on a `screen_w`×`screen_h`
+/// output.
+///
+/// The bubble sits `gap` below the anchor with its leading edge aligned to
+/// the anchor's (the trailing edge right to left), and flips above the
+/// anchor when it would run off the bottom. It is kept `gap` inside the
+/// output edges.
+pub fn place(
+    anchor: &CardRect,
+    w: f32,
+    h: f32,
+    gap: f32,
+    screen_w: f32,
+    screen_h: f32,
+    direction: Direction,
+) -> CardRect {
+    let x = match direction {
+        Direction::Ltr => anchor.x,
+        Direction::Rtl => anchor.x + anchor.width - w,
+    };
+    let x = x.min(screen_w - gap - w).max(gap);
+    let below = anchor.y + anchor.height + gap;
+    let y = if below + h <= screen_h - gap {
+        below
+    } else {
+        (anchor.y - gap - h).max(gap)
+    };
+    CardRect {
+        x,
+        y,
+        width: w,
+        height: h,
+    }
+}
+
+/// Draw `text` in a tooltip for `anchor`, wrapped to the card's maximum
+/// width.
+///
+/// Like the toast, the bubble can sit outside the blurred card region, so
+/// its material is made near-opaque.
+#[allow(clippy::too_many_arguments)]
+pub fn draw_tooltip(
+    pixmap: &mut tiny_skia::Pixmap,
+    font_system: &mut FontSystem,
+    swash_cache: &mut SwashCache,
+    anchor: &CardRect,
+    text: &str,
+    width: f32,
+    height: f32,
+    layout: &Layout,
+    theme: &OverlayTheme,
+) {
+    let attrs = Attrs::new().family(Family::SansSerif);
+    let pad = layout.padding / 2.0;
+    let gap = layout.row_spacing;
+    let max_text = (width - (gap + pad) * 2.0).min(layout.card_max_width);
+    let (tw, th) = measure_text(font_system, text, layout.text_size, attrs, Some(max_text));
+    let bubble = place(
+        anchor,
+        tw + pad * 2.0,
+        th + pad * 2.0,
+        gap,
+        width,
+        height,
+        theme.direction,
+    );
+    draw_card(
+        pixmap,
+        bubble.x,
+        bubble.y,
+        bubble.width,
+        bubble.height,
+        layout.badge_radius,
+        &opaque(theme),
+    );
+    draw_text(
+        pixmap,
+        font_system,
+        swash_cache,
+        bubble.x + pad,
+        bubble.y + pad,
+        text,
+        layout.text_size,
+        attrs,
+        theme.text_primary,
+        Some(max_text),
+    );
+}
+
+/// `theme` with a near-opaque card, for bubbles that can sit outside the
+/// blurred card region.
+pub(super) fn opaque(theme: &OverlayTheme) -> OverlayTheme {
+    OverlayTheme {
+        card_background: theme
+            .card_background
+            .with_alpha(theme.card_background.a.max(TOAST_MIN_ALPHA)),
+        ..theme.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ANCHOR: CardRect = CardRect {
+        x: 300.0,
+        y: 400.0,
+        width: 400.0,
+        height: 48.0,
+    };
+
+    fn assert_at(rect: &CardRect, x: f32, y: f32) {
+        assert!(
+            (rect.x - x).abs() < f32::EPSILON && (rect.y - y).abs() < f32::EPSILON,
+            "bubble at ({}, {}), expected ({x}, {y})",
+            rect.x,
+            rect.y
+        );
+    }
+
+    #[test]
+    fn sits_below_the_anchor_on_its_leading_edge() {
+        let ltr = place(&ANCHOR, 200.0, 40.0, 8.0, 1920.0, 1080.0, Direction::Ltr);
+        assert_at(&ltr, 300.0, 456.0);
+        let rtl = place(&ANCHOR, 200.0, 40.0, 8.0, 1920.0, 1080.0, Direction::Rtl);
+        assert_at(&rtl, 500.0, 456.0);
+    }
+
+    #[test]
+    fn flips_above_when_the_bottom_is_too_close() {
+        let rect = place(&ANCHOR, 200.0, 40.0, 8.0, 1920.0, 480.0, Direction::Ltr);
+        assert_at(&rect, 300.0, 352.0);
+    }
+
+    #[test]
+    fn stays_inside_the_output() {
+        let wide = place(&ANCHOR, 900.0, 40.0, 8.0, 1000.0, 1080.0, Direction::Ltr);
+        assert_at(&wide, 92.0, 456.0);
+        let rtl = place(&ANCHOR, 900.0, 40.0, 8.0, 1000.0, 1080.0, Direction::Rtl);
+        assert_at(&rtl, 8.0, 456.0);
+        let tall = place(&ANCHOR, 200.0, 600.0, 8.0, 1920.0, 700.0, Direction::Ltr);
+        assert_at(&tall, 300.0, 8.0);
+    }
+}
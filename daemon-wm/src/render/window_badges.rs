@@ -0,0 +1,420 @@
+//! On-window hint badges (`hint_placement = "windows"`).
+//!
+//! Each matching row whose window's rectangle is known gets its badge drawn
+//! over the center of that window, like Vimium's link hints, instead of in
+//! the card. Rows without one (workspace and output rows, windows the
+//! compositor reports no geometry for) stay in a card along the bottom
+//! edge, with the typed input in a pill beneath it.
+//!
+//! Placement is its own pass: [`place`] positions a badge against its
+//! window, the output edges and the badges already placed, so the geometry
+//! needs no fonts.
+
+use super::layout::{self, CardRect, Layout, Page};
+use super::primitives::{fill_rounded_rect, stroke_rounded_rect};
+use super::scratch::TruncationCache;
+use super::text::{draw_text, measure_text};
+use super::{
+    HintMatchState, HintRow, OverlayTheme, draw_card, draw_hint_row, draw_more_footer, draw_pill,
+    extract_app_name_in, hint_match_state, input_indicator,
+};
+use bumpalo::Bump;
+use bumpalo::collections::{String as BumpString, Vec as BumpVec};
+use cosmic_text::{Attrs, Family, FontSystem, SwashCache, Weight};
+
+/// On-window badges are drawn this much larger than card badges, so they
+/// stand out over window contents.
+const BADGE_SCALE: f32 = 1.5;
+
+/// Position a `w`×`h` badge over `window` (logical pixels, scaled by
+/// `scale`) on a `screen_w`×`screen_h` output.
+///
+/// The badge is centered on the window and kept `gap` inside the output
+/// edges. Where it would cover a badge in `taken` (stacked windows), it
+/// moves down past it, as long as it stays on the output.
+#[allow(clippy::too_many_arguments)]
+pub fn place(
+    window: &core_types::Geometry,
+    scale: f32,
+    w: f32,
+    h: f32,
+    gap: f32,
+    screen_w: f32,
+    screen_h: f32,
+    taken: &[CardRect],
+) -> CardRect {
+    let center_x = (window.x as f32 + window.width as f32 / 2.0) * scale;
+    let center_y = (window.y as f32 + window.height as f32 / 2.0) * scale;
+    let x = (center_x - w / 2.0).min(screen_w - gap - w).max(gap);
+    let mut y = (center_y - h / 2.0).min(screen_h - gap - h).max(gap);
+    let overlaps = |y: f32, other: &CardRect| {
+        x < other.x + other.width
+            && other.x < x + w
+            && y < other.y + other.height
+            && other.y < y + h
+    };
+    while let Some(other) = taken.iter().find(|other| overlaps(y, other)) {
+        let below = other.y + other.height + gap;
+        if below + h > screen_h - gap {
+            break;
+        }
+        y = below;
+    }
+    CardRect {
+        x,
+        y,
+        width: w,
+        height: h,
+    }
+}
+
+/// The card listing `rows` rows that have no on-window badge, along the
+/// bottom edge of a `screen_w`×`screen_h` output, with room beneath it for
+/// the input pill.
+pub fn bottom_card(
+    rows: usize,
+    screen_w: f32,
+    screen_h: f32,
+    layout: &Layout,
+    show_app_id: bool,
+    show_title: bool,
+) -> CardRect {
+    let card = layout::calculate_card(rows, screen_w, screen_h, layout, show_app_id, show_title);
+    let pill = layout.padding * 2.0 + layout.text_size;
+    CardRect {
+        y: screen_h - card.height - pill - layout.padding,
+        ..card
+    }
+}
+
+/// Most rows the bottom card shows, so it leaves most of the output to
+/// the badges.
+pub fn bottom_rows(layout: &Layout, screen_h: f32) -> usize {
+    (layout.max_rows(screen_h) / 2).max(1)
+}
+
+/// The bottom card's rectangle for blur region calculation, when any of
+/// `row_count` rows is listed in it.
+pub fn compute_card_rect(
+    row_count: usize,
+    screen_w: f32,
+    screen_h: f32,
+    scale: f32,
+    theme: &OverlayTheme,
+    show_app_id: bool,
+    show_title: bool,
+) -> Option<(f32, f32, f32, f32)> {
+    if row_count == 0 {
+        return None;
+    }
+    let l = Layout::new(scale);
+    let rows = Page::new(row_count, 0, bottom_rows(&l, screen_h), theme.page_rows).card_rows();
+    let card = bottom_card(rows, screen_w, screen_h, &l, show_app_id, show_title);
+    Some((card.x, card.y, card.width, card.height))
+}
+
+/// Draw the matching rows (`visible`, indices into `rows`): badges over
+/// their windows where known, the rest in the bottom card. `selection` is
+/// a position in `visible`; `typed` is the lowercased input.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn draw(
+    pixmap: &mut tiny_skia::Pixmap,
+    font_system: &mut FontSystem,
+    swash_cache: &mut SwashCache,
+    arena: &Bump,
+    truncations: &mut TruncationCache,
+    width: f32,
+    height: f32,
+    scale: f32,
+    rows: &[HintRow<'_>],
+    visible: &[usize],
+    selection: usize,
+    hints: &[String],
+    input: &str,
+    typed: &str,
+    notice: Option<&str>,
+    layout: &Layout,
+    theme: &OverlayTheme,
+    show_app_id: bool,
+    show_title: bool,
+) {
+    let (placed, listed): (Vec<usize>, Vec<usize>) =
+        (0..visible.len()).partition(|&vi| rows[visible[vi]].geometry.is_some());
+
+    // The card goes first so badges over windows behind it stay on top.
+    let card = if listed.is_empty() {
+        let pill = layout.padding * 2.0 + layout.text_size;
+        CardRect {
+            x: 0.0,
+            y: height - pill - layout.padding,
+            width,
+            height: 0.0,
+        }
+    } else {
+        let listed_selection = listed.iter().position(|&vi| vi == selection);
+        let page = Page::new(
+            listed.len(),
+            listed_selection.unwrap_or(0),
+            bottom_rows(layout, height),
+            theme.page_rows,
+        );
+        let card = bottom_card(
+            page.card_rows(),
+            width,
+            height,
+            layout,
+            show_app_id,
+            show_title,
+        );
+        draw_card(
+            pixmap,
+            card.x,
+            card.y,
+            card.width,
+            card.height,
+            layout.corner_radius,
+            theme,
+        );
+        for (slot, &vi) in listed[page.first..page.first + page.shown]
+            .iter()
+            .enumerate()
+        {
+            let index = visible[vi];
+            draw_hint_row(
+                pixmap,
+                font_system,
+                swash_cache,
+                arena,
+                truncations,
+                &card,
+                layout.row_y(&card, slot),
+                &rows[index],
+                vi == selection,
+                hint_match_state(hints.get(index), typed),
+                None,
+                layout,
+                theme,
+                show_app_id,
+                show_title,
+            );
+        }
+        if page.more > 0 {
+            draw_more_footer(
+                pixmap,
+                font_system,
+                swash_cache,
+                arena,
+                &card,
+                layout.row_y(&card, page.shown),
+                page.more,
+                layout,
+                theme,
+            );
+        }
+        card
+    };
+
+    let mut taken = BumpVec::with_capacity_in(placed.len(), arena);
+    for &vi in &placed {
+        let index = visible[vi];
+        let Some(window) = &rows[index].geometry else {
+            continue;
+        };
+        let badge = draw_badge(
+            pixmap,
+            font_system,
+            swash_cache,
+            arena,
+            window,
+            &rows[index],
+            vi == selection,
+            hint_match_state(hints.get(index), typed),
+            scale,
+            width,
+            height,
+            &taken,
+            layout,
+            theme,
+        );
+        taken.push(badge);
+    }
+
+    if !input.is_empty() {
+        let indicator = input_indicator(arena, input, theme);
+        draw_pill(
+            pixmap,
+            font_system,
+            swash_cache,
+            &card,
+            indicator,
+            theme.text_primary,
+            layout,
+            theme,
+        );
+    } else if let Some(message) = notice {
+        draw_pill(
+            pixmap,
+            font_system,
+            swash_cache,
+            &card,
+            message,
+            theme.text_primary,
+            layout,
+            theme,
+        );
+    }
+}
+
+/// Draw one row's badge over `window`, with the app name beneath the hint.
+/// Returns where it was drawn.
+#[allow(clippy::too_many_arguments)]
+fn draw_badge(
+    pixmap: &mut tiny_skia::Pixmap,
+    font_system: &mut FontSystem,
+    swash_cache: &mut SwashCache,
+    arena: &Bump,
+    window: &core_types::Geometry,
+    row: &HintRow<'_>,
+    is_selected: bool,
+    match_state: HintMatchState,
+    scale: f32,
+    width: f32,
+    height: f32,
+    taken: &[CardRect],
+    layout: &Layout,
+    theme: &OverlayTheme,
+) -> CardRect {
+    let hint_size = layout.text_size * BADGE_SCALE;
+    let name_size = layout.text_size * 0.75;
+    let pad = layout.padding / 2.0;
+    let hint_attrs = Attrs::new()
+        .family(Family::SansSerif)
+        .weight(Weight::SEMIBOLD);
+    let name_attrs = Attrs::new().family(Family::SansSerif);
+
+    let mut hint_text = BumpString::new_in(arena);
+    hint_text.extend(row.hint.chars().flat_map(char::to_uppercase));
+    let name = row
+        .label
+        .unwrap_or_else(|| extract_app_name_in(arena, row.app_id));
+    let (hint_w, _) = measure_text(font_system, &hint_text, hint_size, hint_attrs, None);
+    let (name_w, _) = measure_text(font_system, name, name_size, name_attrs, None);
+    let w = (hint_w.max(name_w) + pad * 2.0).max(layout.badge_width * BADGE_SCALE);
+    let h = hint_size + name_size + pad * 2.5;
+    let rect = place(
+        window,
+        scale,
+        w,
+        h,
+        layout.row_spacing,
+        width,
+        height,
+        taken,
+    );
+
+    let (background, text) = match match_state {
+        HintMatchState::Exact => (theme.badge_matched_background, theme.badge_matched_text),
+        HintMatchState::Partial => (theme.badge_background.brightened(0.12), theme.badge_text),
+        HintMatchState::None => (theme.badge_background, theme.badge_text),
+    };
+    fill_rounded_rect(
+        pixmap,
+        rect.x,
+        rect.y,
+        rect.width,
+        rect.height,
+        layout.badge_radius,
+        background,
+    );
+    if is_selected {
+        stroke_rounded_rect(
+            pixmap,
+            rect.x,
+            rect.y,
+            rect.width,
+            rect.height,
+            layout.badge_radius,
+            theme.badge_matched_background,
+            layout.border_width,
+        );
+    }
+    draw_text(
+        pixmap,
+        font_system,
+        swash_cache,
+        rect.x + (rect.width - hint_w) / 2.0,
+        rect.y + pad,
+        &hint_text,
+        hint_size,
+        hint_attrs,
+        text,
+        None,
+    );
+    draw_text(
+        pixmap,
+        font_system,
+        swash_cache,
+        rect.x + (rect.width - name_w) / 2.0,
+        rect.y + pad * 1.5 + hint_size,
+        name,
+        name_size,
+        name_attrs,
+        text,
+        None,
+    );
+    rect
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core_types::Geometry;
+
+    const WINDOW: Geometry = Geometry {
+        x: 100,
+        y: 100,
+        width: 800,
+        height: 600,
+    };
+
+    fn assert_at(rect: &CardRect, x: f32, y: f32) {
+        assert!(
+            (rect.x - x).abs() < f32::EPSILON && (rect.y - y).abs() < f32::EPSILON,
+            "badge at ({}, {}), expected ({x}, {y})",
+            rect.x,
+            rect.y
+        );
+    }
+
+    #[test]
+    fn centers_on_the_window_at_the_output_scale() {
+        let badge = place(&WINDOW, 1.0, 80.0, 60.0, 8.0, 1920.0, 1080.0, &[]);
+        assert_at(&badge, 460.0, 370.0);
+        let hidpi = place(&WINDOW, 2.0, 80.0, 60.0, 8.0, 3840.0, 2160.0, &[]);
+        assert_at(&hidpi, 960.0, 770.0);
+    }
+
+    #[test]
+    fn stays_inside_the_output() {
+        let corner = Geometry {
+            x: -200,
+            y: 1000,
+            width: 300,
+            height: 200,
+        };
+        let badge = place(&corner, 1.0, 80.0, 60.0, 8.0, 1920.0, 1080.0, &[]);
+        assert_at(&badge, 8.0, 1012.0);
+    }
+
+    #[test]
+    fn stacked_windows_get_badges_below_each_other() {
+        let first = place(&WINDOW, 1.0, 80.0, 60.0, 8.0, 1920.0, 1080.0, &[]);
+        let second = place(&WINDOW, 1.0, 80.0, 60.0, 8.0, 1920.0, 1080.0, &[first]);
+        assert_at(&second, 460.0, 438.0);
+        // No room below: the badge stays where it was.
+        let low = place(&WINDOW, 1.0, 80.0, 60.0, 8.0, 1920.0, 500.0, &[]);
+        assert_at(&low, 460.0, 370.0);
+        let blocked = place(&WINDOW, 1.0, 80.0, 60.0, 8.0, 1920.0, 500.0, &[low]);
+        assert_at(&blocked, 460.0, 370.0);
+    }
+}
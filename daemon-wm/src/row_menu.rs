@@ -0,0 +1,185 @@
+//! Per-row context menu: the window verbs for the selected row as a list.
+//!
+//! The Menu key, or `.` on empty input, opens it over the picker. Up and
+//! Down move through the entries, Enter runs one, Escape or the menu key
+//! again closes it. Every entry runs the same event its shortcut does, and
+//! shows that shortcut as the [action registry](crate::actions) names it,
+//! so the menu doubles as a reminder of the chords. Entries the compositor
+//! cannot perform are left out.
+
+use crate::actions::{self, Capabilities};
+use crate::controller::Event;
+use core_types::{Window, WindowPlacement, WindowToggle};
+
+/// A menu entry: the verb it runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MenuAction {
+    Activate,
+    Close,
+    Place(WindowPlacement),
+    Toggle(WindowToggle),
+    MoveToOutput,
+    Mark,
+    CopyTitle,
+    CopyAppId,
+}
+
+impl MenuAction {
+    /// The event the entry's shortcut sends.
+    #[must_use]
+    pub fn event(self) -> Event {
+        match self {
+            Self::Activate => Event::Confirm,
+            Self::Close => Event::CloseWindow,
+            Self::Place(placement) => Event::Place(placement),
+            Self::Toggle(toggle) => Event::Toggle(toggle),
+            Self::MoveToOutput => Event::MoveToOutput,
+            Self::Mark => Event::Mark,
+            Self::CopyTitle => Event::CopyTitle,
+            Self::CopyAppId => Event::CopyAppId,
+        }
+    }
+
+    /// The entry as shown for `window`; toggles name the state they switch
+    /// to.
+    #[must_use]
+    pub fn entry(self, window: &Window) -> MenuEntry {
+        let label = match self {
+            Self::Activate => "Switch to",
+            Self::Close => "Close",
+            Self::Place(WindowPlacement::LeftHalf) => "Tile left",
+            Self::Place(WindowPlacement::RightHalf) => "Tile right",
+            Self::Place(WindowPlacement::Maximized) => "Maximize",
+            Self::Toggle(WindowToggle::Sticky) if window.is_sticky => "Unpin from all workspaces",
+            Self::Toggle(WindowToggle::Sticky) => "Pin to all workspaces",
+            Self::Toggle(WindowToggle::KeepAbove) if window.is_keep_above => "Stop keeping above",
+            Self::Toggle(WindowToggle::KeepAbove) => "Keep above others",
+            Self::MoveToOutput => "Move to output…",
+            Self::Mark => "Mark…",
+            Self::CopyTitle => "Copy title",
+            Self::CopyAppId => "Copy app ID",
+        };
+        MenuEntry {
+            label: label.into(),
+            shortcut: actions::keys_for(&self.event()).unwrap_or_default().into(),
+        }
+    }
+}
+
+/// The entries offered for a window row, in menu order.
+#[must_use]
+pub fn actions(caps: Capabilities) -> Vec<MenuAction> {
+    let offered = [
+        (MenuAction::Activate, caps.activate),
+        (MenuAction::Close, true),
+        (MenuAction::Place(WindowPlacement::LeftHalf), caps.tile),
+        (MenuAction::Place(WindowPlacement::RightHalf), caps.tile),
+        (MenuAction::Place(WindowPlacement::Maximized), caps.maximize),
+        (MenuAction::Toggle(WindowToggle::Sticky), caps.stick),
+        (MenuAction::Toggle(WindowToggle::KeepAbove), caps.keep_above),
+        (MenuAction::MoveToOutput, caps.move_to_output),
+        (MenuAction::Mark, true),
+        (MenuAction::CopyTitle, true),
+        (MenuAction::CopyAppId, true),
+    ];
+    offered
+        .into_iter()
+        .filter_map(|(action, on)| on.then_some(action))
+        .collect()
+}
+
+/// A row of the open menu.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MenuEntry {
+    pub label: String,
+    /// The shortcut that runs the same verb, drawn after the label.
+    pub shortcut: String,
+}
+
+/// The open menu as the overlay draws it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MenuView {
+    pub entries: Vec<MenuEntry>,
+    /// Highlighted entry.
+    pub selected: usize,
+}
+
+/// The open menu's state in the controller.
+#[derive(Debug, Clone)]
+pub struct RowMenu {
+    pub actions: Vec<MenuAction>,
+    pub selected: usize,
+}
+
+impl RowMenu {
+    #[must_use]
+    pub fn new(actions: Vec<MenuAction>) -> Self {
+        Self {
+            actions,
+            selected: 0,
+        }
+    }
+
+    /// Move the highlight down (or up), wrapping around.
+    pub fn step(&mut self, down: bool) {
+        let len = self.actions.len().max(1);
+        self.selected = if down {
+            (self.selected + 1) % len
+        } else {
+            (self.selected + len - 1) % len
+        };
+    }
+
+    /// The highlighted action.
+    #[must_use]
+    pub fn current(&self) -> Option<MenuAction> {
+        self.actions.get(self.selected).copied()
+    }
+
+    /// The menu as drawn for `window`.
+    #[must_use]
+    pub fn view(&self, window: &Window) -> MenuView {
+        MenuView {
+            entries: self.actions.iter().map(|a| a.entry(window)).collect(),
+            selected: self.selected,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn actions_follow_the_compositor_capabilities() {
+        let bare = actions(Capabilities::default());
+        assert_eq!(
+            bare,
+            [
+                MenuAction::Close,
+                MenuAction::Mark,
+                MenuAction::CopyTitle,
+                MenuAction::CopyAppId
+            ]
+        );
+        let full = actions(Capabilities {
+            activate: true,
+            maximize: true,
+            tile: true,
+            stick: true,
+            keep_above: true,
+            move_to_output: true,
+        });
+        assert_eq!(full.len(), 11);
+        assert_eq!(full[0], MenuAction::Activate);
+    }
+
+    #[test]
+    fn highlight_wraps_both_ways() {
+        let mut menu = RowMenu::new(vec![MenuAction::Close, MenuAction::Mark]);
+        menu.step(false);
+        assert_eq!(menu.current(), Some(MenuAction::Mark));
+        menu.step(true);
+        assert_eq!(menu.current(), Some(MenuAction::Close));
+    }
+}
@@ -12,6 +12,12 @@ pub fn apply_sandbox() {
         .unwrap_or_else(|| std::path::PathBuf::from("/tmp"))
         .join("open-sesame");
 
+    // State store directory: the database and its WAL files. Opened by
+    // `state::init` before the sandbox, which created the directory.
+    let state_dir = core_state::StateStore::default_path()
+        .and_then(|path| path.parent().map(std::path::Path::to_path_buf))
+        .unwrap_or_else(|| std::path::PathBuf::from("/nonexistent"));
+
     let pds_dir = std::path::PathBuf::from(&runtime_dir).join("pds");
     let keys_dir = pds_dir.join("keys");
 
@@ -41,11 +47,16 @@ pub fn apply_sandbox() {
                 .join(std::env::var("WAYLAND_DISPLAY").unwrap_or_else(|_| "wayland-1".into())),
             access: FsAccess::ReadWriteFile,
         },
-        // MRU state file.
+        // Font cache.
         LandlockRule {
             path: cache_dir,
             access: FsAccess::ReadWrite,
         },
+        // State store (MRU, marks, window order, usage and focus time).
+        LandlockRule {
+            path: state_dir,
+            access: FsAccess::ReadWrite,
+        },
         // Fontconfig (read-only).
         LandlockRule {
             path: std::path::PathBuf::from("/etc/fonts"),
@@ -55,6 +66,17 @@ pub fn apply_sandbox() {
             path: std::path::PathBuf::from("/usr/share/fonts"),
             access: FsAccess::ReadOnly,
         },
+        // Font directories scanned when there is no fontconfig configuration.
+        LandlockRule {
+            path: std::path::PathBuf::from("/usr/local/share/fonts"),
+            access: FsAccess::ReadOnly,
+        },
+        LandlockRule {
+            path: dirs::home_dir()
+                .unwrap_or_else(|| std::path::PathBuf::from("/nonexistent"))
+                .join(".fonts"),
+            access: FsAccess::ReadOnly,
+        },
         // COSMIC desktop theme (read-write: cosmic-config creates version
         // subdirectories via fs::create_dir_all on first access).
         LandlockRule {
@@ -227,6 +249,67 @@ pub fn apply_sandbox() {
         });
     }
 
+    // Desktop entries for app names (`app_names`). /usr/share and the user
+    // data directory are already readable; this covers the rest of
+    // $XDG_DATA_DIRS, such as Flatpak exports and Nix profiles.
+    for dir in core_config::application_dirs() {
+        if dir.exists() {
+            rules.push(LandlockRule {
+                path: dir,
+                access: FsAccess::ReadOnly,
+            });
+        }
+    }
+
+    // System bus socket: power-profiles-daemon's active profile
+    // (`wm.power_saving = "auto"`) and logind's session lock state.
+    let system_bus = std::env::var("DBUS_SYSTEM_BUS_ADDRESS")
+        .ok()
+        .and_then(|addr| {
+            let path = addr.strip_prefix("unix:path=")?.split(',').next()?;
+            Some(std::path::PathBuf::from(path))
+        })
+        .unwrap_or_else(|| std::path::PathBuf::from("/run/dbus/system_bus_socket"));
+    if system_bus.exists() {
+        rules.push(LandlockRule {
+            path: system_bus,
+            access: FsAccess::ReadWriteFile,
+        });
+    }
+
+    // Session bus socket: the panel applet interface (`wm.applet_interface`).
+    let session_bus = std::env::var("DBUS_SESSION_BUS_ADDRESS")
+        .ok()
+        .and_then(|addr| {
+            let path = addr.strip_prefix("unix:path=")?.split(',').next()?;
+            Some(std::path::PathBuf::from(path))
+        })
+        .or_else(|| {
+            std::env::var_os("XDG_RUNTIME_DIR").map(|dir| std::path::PathBuf::from(dir).join("bus"))
+        });
+    if let Some(session_bus) = session_bus
+        && session_bus.exists()
+    {
+        rules.push(LandlockRule {
+            path: session_bus,
+            access: FsAccess::ReadWriteFile,
+        });
+    }
+
+    // Mock compositor backend: the fixture is read by detect_compositor(),
+    // which runs after Landlock is applied.
+    if std::env::var(platform_linux::compositor::BACKEND_ENV).as_deref() == Ok("mock")
+        && let Some(fixture) = std::env::var_os(platform_linux::compositor::MOCK_FIXTURE_ENV)
+    {
+        let path = std::path::PathBuf::from(fixture);
+        if path.exists() {
+            rules.push(LandlockRule {
+                path,
+                access: FsAccess::ReadOnly,
+            });
+        }
+    }
+
     let seccomp = SeccompProfile {
         daemon_name: "daemon-wm".into(),
         allowed_syscalls: vec![
@@ -237,6 +320,9 @@ pub fn apply_sandbox() {
             "openat".into(),
             "lseek".into(),
             "pread64".into(),
+            // State store: SQLite writes pages at offsets and grows its WAL.
+            "pwrite64".into(),
+            "fallocate".into(),
             "fstat".into(),
             "stat".into(),
             "newfstatat".into(),
@@ -348,8 +434,10 @@ pub fn apply_sandbox() {
         ],
     };
 
-    // daemon-wm uses Wayland sockets only (no D-Bus). SignalOnly scope blocks
-    // cross-process signals while allowing abstract Unix sockets for Wayland.
+    // daemon-wm uses Wayland sockets, the system bus (power profile, lock)
+    // and the session bus (applet interface).
+    // SignalOnly scope blocks cross-process signals while allowing abstract
+    // Unix sockets for Wayland.
     match apply_sandbox_with_scope(&rules, &seccomp, LandlockScope::SignalOnly) {
         Ok(status) => {
             tracing::info!(?status, "sandbox applied");
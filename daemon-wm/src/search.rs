@@ -0,0 +1,254 @@
+//! Window search: filter the picker by title and app name.
+//!
+//! Input starting with `/` switches the overlay from hint matching to search.
+//! The rest of the input is split on whitespace into terms; a window matches
+//! when every term occurs in its title, its shown name or its app ID.
+//! Matching ignores case character by character ([`fold_key`]), in any
+//! script. The byte ranges of every occurrence in the title and name are
+//! returned so the renderer can highlight why a row matched.
+
+use crate::hints::fold_key;
+use crate::overlay::WindowInfo;
+use std::ops::Range;
+
+/// Prefix that turns overlay input into a window search.
+pub const SEARCH_PREFIX: char = '/';
+
+/// The search query, if `input` is search input. May be empty while typing.
+#[must_use]
+pub fn parse(input: &str) -> Option<&str> {
+    input.strip_prefix(SEARCH_PREFIX)
+}
+
+/// Where a query matched within one window.
+///
+/// Ranges are byte offsets, sorted and non-overlapping. `app_name` ranges
+/// index into the window's [`window_name`], which is also what the overlay
+/// displays (with its first letter capitalised when derived from the app ID).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WindowMatch {
+    pub app_name: Vec<Range<usize>>,
+    pub title: Vec<Range<usize>>,
+}
+
+/// The app name searched and shown for an app ID: the last segment of a
+/// reverse-DNS ID (`org.mozilla.firefox` → `firefox`).
+#[must_use]
+pub fn app_name(app_id: &str) -> &str {
+    app_id.rsplit('.').next().unwrap_or(app_id)
+}
+
+/// The name shown for a window, and searched along with its app ID: its
+/// key binding's `label`, else the [`app_name`] of its app ID.
+#[must_use]
+pub fn window_name<'a>(app_id: &'a str, label: Option<&'a str>) -> &'a str {
+    label.unwrap_or_else(|| app_name(app_id))
+}
+
+/// Match `query` against one window, given its [`window_name`]. Returns
+/// `None` if any term is missing from the title, the name and the app ID.
+/// A term found only in the app ID, which is not shown, highlights
+/// nothing. An empty query matches everything with no highlighted ranges.
+#[must_use]
+pub fn match_window(query: &str, name: &str, app_id: &str, title: &str) -> Option<WindowMatch> {
+    let mut found = WindowMatch::default();
+    for term in query.split_whitespace() {
+        let in_name = find_all(name, term);
+        let in_title = find_all(title, term);
+        if in_name.is_empty() && in_title.is_empty() && !contains(app_id, term) {
+            return None;
+        }
+        found.app_name.extend(in_name);
+        found.title.extend(in_title);
+    }
+    merge(&mut found.app_name);
+    merge(&mut found.title);
+    Some(found)
+}
+
+/// Whether `query` matches a window: [`match_window`] without collecting
+/// the ranges, so filtering hundreds of windows allocates nothing.
+#[must_use]
+pub fn window_matches(query: &str, name: &str, app_id: &str, title: &str) -> bool {
+    query
+        .split_whitespace()
+        .all(|term| contains(name, term) || contains(title, term) || contains(app_id, term))
+}
+
+/// Indices of the windows matching `query`, in display order.
+#[must_use]
+pub fn matching_indices(query: &str, windows: &[WindowInfo]) -> Vec<usize> {
+    windows
+        .iter()
+        .enumerate()
+        .filter(|(_, w)| {
+            let name = window_name(&w.app_id, w.label.as_deref());
+            window_matches(query, name, &w.app_id, &w.title)
+        })
+        .map(|(i, _)| i)
+        .collect()
+}
+
+/// Every occurrence of `needle` in `haystack`, ignoring case.
+///
+/// Matches start and end at character boundaries of `haystack`, so the
+/// ranges are always valid slice indices even where a character and its
+/// folded form differ in length.
+fn find_all(haystack: &str, needle: &str) -> Vec<Range<usize>> {
+    if needle.is_empty() {
+        return Vec::new();
+    }
+    let mut ranges = Vec::new();
+    let mut start = 0;
+    while let Some(c) = haystack[start..].chars().next() {
+        if let Some(len) = prefix_len(&haystack[start..], needle) {
+            ranges.push(start..start + len);
+            start += len;
+        } else {
+            start += c.len_utf8();
+        }
+    }
+    ranges
+}
+
+/// Whether `haystack` contains `needle`, ignoring case.
+fn contains(haystack: &str, needle: &str) -> bool {
+    !needle.is_empty()
+        && haystack
+            .char_indices()
+            .any(|(i, _)| prefix_len(&haystack[i..], needle).is_some())
+}
+
+/// The byte length of the prefix of `haystack` that equals `needle`
+/// ignoring case, if there is one.
+fn prefix_len(haystack: &str, needle: &str) -> Option<usize> {
+    let mut hay = haystack.char_indices();
+    for n in needle.chars() {
+        let (_, h) = hay.next()?;
+        if h != n && fold_key(h) != fold_key(n) {
+            return None;
+        }
+    }
+    Some(hay.next().map_or(haystack.len(), |(i, _)| i))
+}
+
+/// Sort ranges and merge any that overlap or touch.
+fn merge(ranges: &mut Vec<Range<usize>>) {
+    ranges.sort_by_key(|r| r.start);
+    let mut merged: Vec<Range<usize>> = Vec::with_capacity(ranges.len());
+    for range in ranges.drain(..) {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+    *ranges = merged;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(app_id: &str, title: &str) -> WindowInfo {
+        WindowInfo {
+            app_id: app_id.into(),
+            title: title.into(),
+            position: crate::hints::AppPosition::default(),
+            pinned: false,
+            nested: false,
+            sticky: false,
+            keep_above: false,
+            minimized: false,
+            label: None,
+            icon: None,
+            focused_ago: None,
+            geometry: None,
+        }
+    }
+
+    #[test]
+    fn parse_prefix() {
+        assert_eq!(parse("/fire"), Some("fire"));
+        assert_eq!(parse("/"), Some(""));
+        assert_eq!(parse("fire"), None);
+    }
+
+    #[test]
+    fn matches_title_and_app_name() {
+        let id = "org.mozilla.firefox";
+        let m = match_window("fox", app_name(id), id, "Mozilla Firefox").unwrap();
+        assert_eq!(m.app_name, vec![4..7]);
+        assert_eq!(m.title, vec![12..15]);
+
+        let id = "com.mitchellh.ghostty";
+        let m = match_window("ghost", app_name(id), id, "~/src").unwrap();
+        assert_eq!(m.app_name, vec![0..5]);
+        assert!(m.title.is_empty());
+    }
+
+    #[test]
+    fn every_term_must_match() {
+        assert!(match_window("fire docs", "firefox", "firefox", "Rust docs").is_some());
+        assert!(match_window("fire mail", "firefox", "firefox", "Rust docs").is_none());
+    }
+
+    #[test]
+    fn ranges_merge_and_ignore_case() {
+        let m = match_window("RUST ru", "kitty", "kitty", "rust: Rust").unwrap();
+        assert_eq!(m.title, vec![0..4, 6..10]);
+        let m = match_window("ab bc", "kitty", "kitty", "abc").unwrap();
+        assert_eq!(m.title, vec![0..3]);
+    }
+
+    #[test]
+    fn non_latin_terms_ignore_case() {
+        let m = match_window("ПОЧТА", "thunderbird", "thunderbird", "Входящие — Почта").unwrap();
+        assert_eq!(m.title, vec![21..31]);
+        let m = match_window("σελίδα", "firefox", "firefox", "Αρχική ΣΕΛΊΔΑ").unwrap();
+        assert_eq!(&"Αρχική ΣΕΛΊΔΑ"[m.title[0].clone()], "ΣΕΛΊΔΑ");
+        assert!(window_matches(
+            "почта",
+            "thunderbird",
+            "thunderbird",
+            "Входящие — Почта"
+        ));
+    }
+
+    #[test]
+    fn label_and_app_id_both_match() {
+        let mut files = window("org.gnome.Nautilus", "Downloads");
+        files.label = Some("Files".into());
+        let windows = [files, window("firefox", "a")];
+        assert_eq!(matching_indices("fil", &windows), vec![0]);
+        assert_eq!(matching_indices("naut", &windows), vec![0]);
+
+        // The app ID is not shown, so matching it highlights nothing.
+        let m = match_window("naut", "Files", "org.gnome.Nautilus", "Downloads").unwrap();
+        assert_eq!(m, WindowMatch::default());
+    }
+
+    #[test]
+    fn empty_query_matches_all() {
+        let windows = [window("firefox", "a"), window("kitty", "b")];
+        assert_eq!(matching_indices("", &windows), vec![0, 1]);
+        assert_eq!(matching_indices(" kit ", &windows), vec![1]);
+    }
+
+    #[test]
+    fn window_matches_agrees_with_match_window() {
+        let cases = [
+            ("fire", "firefox", "Mozilla Firefox"),
+            ("FOX moz", "firefox", "Mozilla Firefox"),
+            ("fox chrome", "firefox", "Mozilla Firefox"),
+            ("", "kitty", "~"),
+            ("longer than the title", "kitty", "~"),
+        ];
+        for (query, name, title) in cases {
+            assert_eq!(
+                window_matches(query, name, name, title),
+                match_window(query, name, name, title).is_some(),
+                "{query:?}"
+            );
+        }
+    }
+}
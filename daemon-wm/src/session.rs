@@ -0,0 +1,359 @@
+//! Embeddable switcher session: the overlay UI without the daemon.
+//!
+//! [`Session`] runs one activation of the switcher — overlay surface,
+//! [`OverlayController`] and its timers — against a window list the caller
+//! supplies, and reports how it ended. COSMIC tools and other Wayland
+//! clients can show the same picker the daemon does instead of shelling out
+//! to `sesame wm overlay`:
+//!
+//! ```no_run
+//! # async fn demo(config: core_config::WmConfig, windows: Vec<core_types::Window>) {
+//! use daemon_wm::session::{Mode, Outcome, Session};
+//!
+//! let outcome = Session::new(config)
+//!     .windows(windows)
+//!     .mode(Mode::Launcher)
+//!     .on_activate(|window| println!("switch to {}", window.app_id))
+//!     .on_launch(|command| {
+//!         std::process::Command::new(command)
+//!             .spawn()
+//!             .map(drop)
+//!             .map_err(|e| e.to_string())
+//!     })
+//!     .run()
+//!     .await;
+//! if let Outcome::Dismissed = outcome {
+//!     println!("nothing picked");
+//! }
+//! # }
+//! ```
+//!
+//! Overlay commands are drawn by the daemon's executor
+//! ([`commands::draw`]), so the picker looks and behaves the same in both.
+//! The session neither connects to the IPC bus nor talks to the compositor:
+//! activating and launching are left to the hooks, and features that need
+//! other daemons (vault unlock, the command palette's shell commands,
+//! clipboard copies, closing or moving windows, MRU and usage records) are
+//! not available. Keys come from the overlay's own keyboard focus, so an Alt
+//! release commits a [`Mode::Switcher`] session just as it does the daemon's.
+
+use crate::commands;
+use crate::controller::{Command, Event, OverlayController};
+use crate::overlay::{OverlayCmd, OverlayEvent, spawn_overlay};
+use crate::render::OverlayTheme;
+use core_config::WmConfig;
+use core_types::Window;
+
+/// How a session opens, as the daemon's activation events do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Mode {
+    /// Alt+Tab: select the previous window; releasing Alt switches to it.
+    #[default]
+    Switcher,
+    /// Alt+Shift+Tab: select the last window in the list.
+    SwitcherBackward,
+    /// The picker with launching: stays open until a pick or Escape.
+    Launcher,
+}
+
+impl Mode {
+    fn event(self) -> Event {
+        match self {
+            Self::Switcher => Event::Activate,
+            Self::SwitcherBackward => Event::ActivateBackward,
+            Self::Launcher => Event::ActivateLauncher,
+        }
+    }
+}
+
+/// How a session ended.
+#[derive(Debug, Clone)]
+pub enum Outcome {
+    /// The user picked this window.
+    Activated(Window),
+    /// The user picked a launch, and the launch hook accepted it.
+    Launched(String),
+    /// The overlay closed without a pick.
+    Dismissed,
+}
+
+type ActivateHook = Box<dyn FnMut(&Window) + Send>;
+type LaunchHook = Box<dyn FnMut(&str) -> Result<(), String> + Send>;
+type ShownHook = Box<dyn FnMut() + Send>;
+
+/// One switcher activation, built up and then [`run`](Self::run).
+pub struct Session {
+    config: WmConfig,
+    windows: Vec<Window>,
+    mode: Mode,
+    on_activate: Option<ActivateHook>,
+    on_launch: Option<LaunchHook>,
+    on_shown: Option<ShownHook>,
+}
+
+impl Session {
+    /// A session styled and keyed by `config`, with no windows yet.
+    #[must_use]
+    pub fn new(config: WmConfig) -> Self {
+        Self {
+            config,
+            windows: Vec::new(),
+            mode: Mode::default(),
+            on_activate: None,
+            on_launch: None,
+            on_shown: None,
+        }
+    }
+
+    /// Windows to offer, most recently used first.
+    #[must_use]
+    pub fn windows(mut self, windows: Vec<Window>) -> Self {
+        self.windows = windows;
+        self
+    }
+
+    #[must_use]
+    pub fn mode(mut self, mode: Mode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Called with the picked window as the overlay hides; the caller
+    /// activates it.
+    #[must_use]
+    pub fn on_activate(mut self, hook: impl FnMut(&Window) + Send + 'static) -> Self {
+        self.on_activate = Some(Box::new(hook));
+        self
+    }
+
+    /// Called with a key binding's `launch` command when the user picks a
+    /// launch. An error is shown in the overlay, which stays open. Without
+    /// this hook, launches fail with a notice.
+    #[must_use]
+    pub fn on_launch(
+        mut self,
+        hook: impl FnMut(&str) -> Result<(), String> + Send + 'static,
+    ) -> Self {
+        self.on_launch = Some(Box::new(hook));
+        self
+    }
+
+    /// Called when the full picker first appears (after the quick-switch
+    /// dwell in [`Mode::Switcher`]).
+    #[must_use]
+    pub fn on_shown(mut self, hook: impl FnMut() + Send + 'static) -> Self {
+        self.on_shown = Some(Box::new(hook));
+        self
+    }
+
+    /// Show the overlay and run until the user picks or dismisses.
+    ///
+    /// Must be awaited on a tokio runtime with timers enabled. The overlay
+    /// runs on its own thread, which is told to quit as this returns.
+    pub async fn run(mut self) -> Outcome {
+        let (cmd_tx, mut event_rx) = spawn_overlay(
+            OverlayTheme::from_config(&self.config),
+            self.config.show_app_id,
+            self.config.show_title,
+            self.config.keyboard_mode,
+            self.config.show_on_all_outputs,
+        );
+        let mut controller = OverlayController::new();
+        let mut outcome = Outcome::Dismissed;
+        let mut shown = false;
+
+        let mut pending = controller.handle(self.mode.event(), &self.windows, &self.config);
+        loop {
+            while !pending.is_empty() {
+                let cmds = std::mem::take(&mut pending);
+                for cmd in cmds {
+                    if let Some(event) = self
+                        .execute(cmd, &cmd_tx, &mut event_rx, &mut outcome, &mut shown)
+                        .await
+                    {
+                        pending.extend(controller.handle(event, &self.windows, &self.config));
+                    }
+                }
+            }
+            if controller.is_idle() {
+                break;
+            }
+
+            let deadline = controller.next_deadline();
+            let event = tokio::select! {
+                biased;
+                msg = event_rx.recv() => match msg {
+                    Some(msg) => controller_event(&msg).map(|(event, _)| event),
+                    None => {
+                        tracing::error!("overlay thread exited during an embedded session");
+                        break;
+                    }
+                },
+                () = async {
+                    match deadline {
+                        Some(dl) => tokio::time::sleep_until(tokio::time::Instant::from_std(dl)).await,
+                        None => std::future::pending::<()>().await,
+                    }
+                } => Some(Event::DwellTimeout),
+            };
+            if let Some(event) = event {
+                pending = controller.handle(event, &self.windows, &self.config);
+            }
+        }
+
+        let _ = cmd_tx.send(OverlayCmd::Quit);
+        outcome
+    }
+
+    /// Carry out one controller command. Returns an event to feed back.
+    async fn execute(
+        &mut self,
+        cmd: Command,
+        cmd_tx: &std::sync::mpsc::Sender<OverlayCmd>,
+        event_rx: &mut tokio::sync::mpsc::Receiver<OverlayEvent>,
+        outcome: &mut Outcome,
+        shown: &mut bool,
+    ) -> Option<Event> {
+        match cmd {
+            Command::ShowBorder {
+                zero_window_launcher,
+                delay_indicator_ms,
+            } => {
+                let _ = cmd_tx.send(OverlayCmd::ShowBorder {
+                    delay_indicator: delay_indicator_ms
+                        .map(|ms| std::time::Duration::from_millis(u64::from(ms))),
+                });
+                if zero_window_launcher {
+                    let _ = cmd_tx.send(OverlayCmd::ConfirmKeyboardInput);
+                }
+            }
+            Command::ShowPicker { .. } => {
+                if !std::mem::replace(shown, true)
+                    && let Some(hook) = self.on_shown.as_mut()
+                {
+                    hook();
+                }
+                commands::draw(cmd, cmd_tx);
+            }
+            Command::Hide => {
+                if cmd_tx.send(OverlayCmd::Hide).is_err() {
+                    tracing::error!("overlay thread has exited unexpectedly");
+                }
+            }
+            Command::HideAndSync => commands::hide_and_sync(cmd_tx, event_rx).await,
+            Command::ActivateWindow { window, .. } => {
+                if let Some(hook) = self.on_activate.as_mut() {
+                    hook(&window);
+                }
+                *outcome = Outcome::Activated(window);
+            }
+            Command::LaunchApp { command, .. } => {
+                let result = match self.on_launch.as_mut() {
+                    Some(hook) => hook(&command),
+                    None => Err("launching is not available here".into()),
+                };
+                if result.is_ok() {
+                    *outcome = Outcome::Launched(command.clone());
+                }
+                return Some(launch_result(result, Some(command)));
+            }
+            Command::RunShellCommand { .. } => {
+                return Some(launch_result(
+                    Err("commands are not available here".into()),
+                    None,
+                ));
+            }
+            // Overlay draws go through the daemon's own code. Everything
+            // else needs the compositor backend or another daemon; an
+            // embedded session has neither.
+            cmd => {
+                commands::draw(cmd, cmd_tx);
+            }
+        }
+        None
+    }
+}
+
+/// `Event::LaunchResult` for a launch that the hook accepted or refused.
+fn launch_result(result: Result<(), String>, command: Option<String>) -> Event {
+    let success = result.is_ok();
+    Event::LaunchResult {
+        success,
+        error: result.err(),
+        denial: None,
+        original_command: if success { None } else { command },
+        original_tags: None,
+        original_launch_args: None,
+    }
+}
+
+/// The controller event an overlay event stands for, with the keysym the
+/// daemon deduplicates it by against the same key forwarded over IPC
+/// (`None` for events that aren't keys). `None` for events the overlay
+/// reports only for the main loop's own bookkeeping.
+#[must_use]
+pub fn controller_event(event: &OverlayEvent) -> Option<(Event, Option<u32>)> {
+    let keyed = |event, keyval| Some((event, Some(keyval)));
+    match *event {
+        OverlayEvent::KeyChar(ch) => keyed(Event::Char(ch), ch as u32),
+        OverlayEvent::Action(action) => keyed(action.event.clone(), action.keysyms[0]),
+        OverlayEvent::ModifierReleased => keyed(Event::ModifierReleased, 0xFFE9),
+        OverlayEvent::Dismiss => Some((Event::Dismiss, None)),
+        OverlayEvent::SurfaceUnmapped
+        | OverlayEvent::FrameCommitted(_)
+        | OverlayEvent::KeyboardEntered(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overlay_keys_carry_their_dedup_keysym() {
+        assert!(matches!(
+            controller_event(&OverlayEvent::KeyChar('f')),
+            Some((Event::Char('f'), Some(0x66)))
+        ));
+        let launch_new = crate::actions::lookup(0xFF8D, false, true).unwrap();
+        assert!(matches!(
+            controller_event(&OverlayEvent::Action(launch_new)),
+            Some((Event::LaunchNew, Some(0xFF0D)))
+        ));
+        assert!(matches!(
+            controller_event(&OverlayEvent::ModifierReleased),
+            Some((Event::ModifierReleased, Some(0xFFE9)))
+        ));
+        assert!(matches!(
+            controller_event(&OverlayEvent::Dismiss),
+            Some((Event::Dismiss, None))
+        ));
+        assert!(controller_event(&OverlayEvent::SurfaceUnmapped).is_none());
+    }
+
+    #[test]
+    fn refused_launch_keeps_the_command_for_a_retry() {
+        let Event::LaunchResult {
+            success,
+            error,
+            original_command,
+            ..
+        } = launch_result(Err("no".into()), Some("foot".into()))
+        else {
+            panic!("expected a launch result");
+        };
+        assert!(!success);
+        assert_eq!(error.as_deref(), Some("no"));
+        assert_eq!(original_command.as_deref(), Some("foot"));
+
+        let Event::LaunchResult {
+            success,
+            original_command,
+            ..
+        } = launch_result(Ok(()), Some("foot".into()))
+        else {
+            panic!("expected a launch result");
+        };
+        assert!(success && original_command.is_none());
+    }
+}
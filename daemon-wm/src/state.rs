@@ -0,0 +1,137 @@
+//! The daemon's handle on the state store (`core-state`).
+//!
+//! MRU stack, marks, window order, release timings, usage log and focus
+//! time all live in one `SQLite` database under `$XDG_STATE_HOME`. The
+//! connection is opened once by [`init`] — before the sandbox, importing
+//! the flat files older versions left in `~/.cache/open-sesame` — and shared
+//! behind a mutex for reads, which WAL mode never blocks.
+//!
+//! Writes go through [`write`] to a writer thread with its own connection,
+//! so the event loop never waits on a commit or on a CLI transaction.
+//! Callers that need the written value back apply the change to what they
+//! read themselves. Until [`start_writer`] runs, writes run inline.
+//!
+//! Tests get a private in-memory store, so they never touch the user's.
+
+use core_state::StateStore;
+use std::sync::mpsc;
+use std::sync::{Mutex, OnceLock};
+
+/// The open store, or `None` until [`init`] (or the first access) opens it
+/// or if opening failed.
+static STORE: Mutex<Option<StateStore>> = Mutex::new(None);
+
+/// A queued write.
+type Write = Box<dyn FnOnce(&mut StateStore) -> core_types::Result<()> + Send>;
+
+/// The writer thread's queue, once [`start_writer`] has run.
+static WRITER: OnceLock<mpsc::Sender<Write>> = OnceLock::new();
+
+/// Open the state store and import legacy cache files. Returns whether the
+/// store is usable; without it every switch falls back to compositor order,
+/// which the overlay reports once at startup.
+#[must_use]
+pub fn init() -> bool {
+    with(|store| {
+        if let Some(cache) = dirs::cache_dir() {
+            store.import_legacy(&cache.join("open-sesame"))?;
+        }
+        Ok(())
+    })
+    .is_some()
+}
+
+/// Open the writer's connection and start its thread. Call after the
+/// sandbox, so the thread inherits it. On failure writes stay inline.
+pub fn start_writer() {
+    let mut store = match open() {
+        Ok(store) => store,
+        Err(e) => {
+            tracing::warn!(error = %e, "state: writer unavailable, writing inline");
+            return;
+        }
+    };
+    let (tx, rx) = mpsc::channel::<Write>();
+    let spawned = std::thread::Builder::new()
+        .name("state-writer".into())
+        .spawn(move || {
+            for job in rx {
+                if let Err(e) = job(&mut store) {
+                    tracing::warn!(error = %e, "state: store write failed");
+                }
+            }
+        });
+    match spawned {
+        Ok(_) => {
+            let _ = WRITER.set(tx);
+        }
+        Err(e) => tracing::warn!(error = %e, "state: writer unavailable, writing inline"),
+    }
+}
+
+/// Queue `f` on the writer thread. Writes apply in the order queued;
+/// errors are logged.
+pub(crate) fn write(f: impl FnOnce(&mut StateStore) -> core_types::Result<()> + Send + 'static) {
+    let f: Write = Box::new(f);
+    let inline = match WRITER.get() {
+        Some(tx) => tx.send(f).err().map(|mpsc::SendError(f)| f),
+        None => Some(f),
+    };
+    if let Some(f) = inline {
+        with(f);
+    }
+}
+
+/// Wait up to `timeout` for the writes queued so far, so they are not lost
+/// when the daemon exits.
+pub fn flush(timeout: std::time::Duration) {
+    let Some(tx) = WRITER.get() else {
+        return;
+    };
+    let (done_tx, done_rx) = mpsc::channel();
+    let queued = tx
+        .send(Box::new(move |_: &mut StateStore| {
+            let _ = done_tx.send(());
+            Ok(())
+        }))
+        .is_ok();
+    if queued && done_rx.recv_timeout(timeout).is_err() {
+        tracing::warn!("state: queued writes not flushed before exit");
+    }
+}
+
+/// Run `f` on the store, opening it first if needed. Errors are logged and
+/// become `None`, so callers fall back to an empty state.
+pub(crate) fn with<T>(f: impl FnOnce(&mut StateStore) -> core_types::Result<T>) -> Option<T> {
+    let mut guard = STORE.lock().ok()?;
+    if guard.is_none() {
+        match open() {
+            Ok(store) => *guard = Some(store),
+            Err(e) => {
+                tracing::warn!(error = %e, "state: store unavailable");
+                return None;
+            }
+        }
+    }
+    let store = guard.as_mut()?;
+    match f(store) {
+        Ok(value) => Some(value),
+        Err(e) => {
+            tracing::warn!(error = %e, "state: store access failed");
+            None
+        }
+    }
+}
+
+#[cfg(not(test))]
+fn open() -> core_types::Result<StateStore> {
+    let path = StateStore::default_path().ok_or_else(|| {
+        core_types::Error::Platform("no state or data directory for the state store".into())
+    })?;
+    StateStore::open(&path)
+}
+
+#[cfg(test)]
+fn open() -> core_types::Result<StateStore> {
+    StateStore::open_in_memory()
+}
@@ -0,0 +1,82 @@
+//! Local usage log for `sesame stats`.
+//!
+//! Every overlay activation that ends in a window activation, an app launch
+//! or a dismissal appends one [`UsageEvent`] to the state store
+//! (`usage_events` table): the app chosen, the key binding it belongs to,
+//! and how long choosing took. Window titles are never recorded, and
+//! nothing leaves the machine. `wm.usage_stats = false` turns the log off.
+//!
+//! The store keeps the newest [`core_state::MAX_USAGE_EVENTS`], so the log
+//! stays small without a separate cleanup job.
+
+use core_config::WmKeyBinding;
+use core_types::{UsageEvent, UsageKind};
+use std::collections::BTreeMap;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Append an activation outcome to the usage log.
+///
+/// `app` is the activated window's app ID or the launched desktop entry ID;
+/// `elapsed` is the time since the overlay was activated.
+pub fn record(
+    kind: UsageKind,
+    app: Option<&str>,
+    key_bindings: &BTreeMap<String, WmKeyBinding>,
+    elapsed: Duration,
+) {
+    let at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    let event = event(kind, app, key_bindings, at, elapsed);
+    crate::state::write(move |store| store.append_usage(&event));
+}
+
+fn event(
+    kind: UsageKind,
+    app: Option<&str>,
+    key_bindings: &BTreeMap<String, WmKeyBinding>,
+    at: u64,
+    elapsed: Duration,
+) -> UsageEvent {
+    let key = app.and_then(|app| match kind {
+        UsageKind::Activate => {
+            crate::hints::binding_for_app(app, key_bindings).map(|(key, _)| key.to_string())
+        }
+        UsageKind::Launch => key_bindings
+            .iter()
+            .find(|(_, b)| b.command.is_none() && b.launch.as_deref() == Some(app))
+            .map(|(key, _)| key.clone()),
+        UsageKind::Cancel => None,
+    });
+    UsageEvent {
+        at,
+        kind,
+        app: app.map(String::from),
+        key,
+        elapsed_ms: u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bindings() -> BTreeMap<String, WmKeyBinding> {
+        core_config::WmConfig::default().key_bindings
+    }
+
+    #[test]
+    fn events_name_the_key_binding() {
+        let kb = bindings();
+        let ms = Duration::from_millis(420);
+        let activate = event(UsageKind::Activate, Some("org.mozilla.firefox"), &kb, 7, ms);
+        assert_eq!(activate.key.as_deref(), Some("f"));
+        assert_eq!(activate.elapsed_ms, 420);
+        let launch = event(UsageKind::Launch, Some("ghostty"), &kb, 7, ms);
+        assert_eq!(launch.key.as_deref(), Some("g"));
+        let unbound = event(UsageKind::Activate, Some("xterm"), &kb, 7, ms);
+        assert_eq!(unbound.key, None);
+        let cancel = event(UsageKind::Cancel, None, &kb, 7, ms);
+        assert_eq!((cancel.app, cancel.key), (None, None));
+    }
+}
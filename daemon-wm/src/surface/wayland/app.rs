@@ -1,6 +1,8 @@
 //! OverlayApp struct — all state for the SCTK overlay thread.
 
 use crate::render::OverlayTheme;
+use crate::render::scratch::FrameScratch;
+use crate::toast::ToastQueue;
 use cosmic_text::{FontSystem, SwashCache};
 use smithay_client_toolkit::{
     compositor::{CompositorState, Region},
@@ -14,9 +16,13 @@ use smithay_client_toolkit::{
     shm::{Shm, slot::SlotPool},
 };
 use wayland_client::QueueHandle;
+use wayland_client::protocol::wl_output::WlOutput;
 use wayland_protocols::ext::background_effect::v1::client::ext_background_effect_surface_v1::ExtBackgroundEffectSurfaceV1;
+use wayland_protocols::wp::text_input::zv3::client::zwp_text_input_v3::ZwpTextInputV3;
 
 use super::blur::BlurManager;
+use super::mirror::MirrorSurface;
+use super::text_input::TextInputManager;
 use super::{OverlayEvent, OverlayPhase, WindowInfo};
 
 pub(crate) struct OverlayApp {
@@ -32,14 +38,30 @@ pub(crate) struct OverlayApp {
     pub layer_surface: Option<LayerSurface>,
     pub slot_pool: Option<SlotPool>,
     pub configured_size: (u32, u32),
+    /// Output the layer surface was placed on, once entered.
+    pub primary_output: Option<WlOutput>,
+    /// Output named by `sesame wm overlay --output`; the layer surface is
+    /// created on it instead of where the compositor chooses.
+    pub pinned_output: Option<WlOutput>,
+
+    // -- Other outputs (`show_on_all_outputs`) --
+    pub all_outputs: bool,
+    pub mirrors: Vec<MirrorSurface>,
 
     // -- Blur protocol --
     pub blur_manager: Option<BlurManager>,
     pub blur_surface: Option<ExtBackgroundEffectSurfaceV1>,
 
+    // -- Input method (text-input-v3) --
+    pub text_input_manager: Option<TextInputManager>,
+    pub text_input: Option<ZwpTextInputV3>,
+    /// `commit_string` received since the last `done`.
+    pub pending_commit: Option<String>,
+
     // -- Rendering --
     pub font_system: FontSystem,
     pub swash_cache: SwashCache,
+    pub scratch: FrameScratch,
 
     // -- Overlay state --
     pub phase: OverlayPhase,
@@ -50,18 +72,43 @@ pub(crate) struct OverlayApp {
     pub theme: OverlayTheme,
     pub show_app_id: bool,
     pub show_title: bool,
+    /// Interactivity requested while visible (`wm.keyboard_mode`).
+    pub keyboard_mode: KeyboardInteractivity,
     pub activated_at: Option<std::time::Instant>,
     pub received_key_event: bool,
     pub ipc_keyboard_active: bool,
     pub last_real_input_at: Option<std::time::Instant>,
     pub error_message: String,
-    pub staged_launch: Option<String>,
+    /// Staged launch command and how many instances it starts.
+    pub staged_launch: Option<(String, usize)>,
+    pub palette_message: Option<String>,
+    /// Shown under the picker after the selected window closed.
+    pub retry_notice: Option<String>,
+    /// The selected row's context menu, while open.
+    pub row_menu: Option<crate::row_menu::MenuView>,
+    /// The help overlay, drawn in place of the picker while shown.
+    pub help: Option<crate::actions::HelpView>,
+    /// The first-run walkthrough page, drawn in place of the picker while
+    /// shown.
+    pub walkthrough: Option<crate::onboarding::WalkthroughView>,
+    pub delay_indicator: Option<std::time::Duration>,
+    pub delay_indicator_drawn_at: Option<std::time::Instant>,
+    /// When the current selection was made, for the title tooltip dwell.
+    pub selected_at: Option<std::time::Instant>,
+    pub title_tooltip_drawn: bool,
+    /// Throttled for power saving: slower polling, no delay ring.
+    pub power_saving: bool,
     pub unlock_profile: String,
     pub unlock_password_len: usize,
     pub unlock_message: String,
+    /// Non-fatal problems waiting to be shown as a banner.
+    pub toasts: ToastQueue,
+    pub toast_visible: bool,
 
     // -- Modifier tracking --
     pub alt_held: bool,
+    pub ctrl_held: bool,
+    pub shift_held: bool,
     pub modifier_released_sent: bool,
 
     // -- Communication --
@@ -70,7 +117,11 @@ pub(crate) struct OverlayApp {
     // -- Lifecycle --
     pub running: bool,
     pub needs_redraw: bool,
+    /// When the last frame asked for a frame callback that has not fired
+    /// yet. Further redraws wait for it.
+    pub frame_requested_at: Option<std::time::Instant>,
     pub pending_sync: bool,
+    pub first_frame_sent: bool,
 
     // -- HiDPI --
     pub output_scale: f32,
@@ -80,7 +131,81 @@ pub(crate) struct OverlayApp {
     pub empty_input_region: Region,
 }
 
+/// Redraw interval while the armed-phase delay ring animates (~60 fps).
+const DELAY_INDICATOR_FRAME: std::time::Duration = std::time::Duration::from_millis(16);
+
+/// How long to wait for a frame callback before drawing anyway. The
+/// compositor withholds callbacks from surfaces it is not showing, e.g. on
+/// a powered-off output.
+const FRAME_CALLBACK_TIMEOUT: std::time::Duration = std::time::Duration::from_millis(100);
+
 impl OverlayApp {
+    /// Fraction of the armed dwell elapsed, while the delay ring is shown.
+    pub fn delay_progress(&self) -> Option<f32> {
+        let dwell = self.delay_indicator.filter(|d| !d.is_zero())?;
+        if self.phase != OverlayPhase::BorderOnly || self.power_saving {
+            return None;
+        }
+        let elapsed = self.activated_at?.elapsed();
+        Some((elapsed.as_secs_f32() / dwell.as_secs_f32()).min(1.0))
+    }
+
+    /// Schedule a redraw at frame rate while the delay ring animates.
+    pub fn tick_delay_indicator(&mut self) {
+        if self.delay_progress().is_some()
+            && self
+                .delay_indicator_drawn_at
+                .is_none_or(|t| t.elapsed() >= DELAY_INDICATOR_FRAME)
+        {
+            self.needs_redraw = true;
+        }
+    }
+
+    /// Redraw once the toast on screen runs out, to clear it or show the
+    /// next one.
+    pub fn tick_toasts(&mut self) {
+        if self.toast_visible && self.toasts.expired(std::time::Instant::now()) {
+            self.needs_redraw = true;
+        }
+    }
+
+    /// Whether the selection has stayed put long enough to show its full
+    /// title in a tooltip.
+    pub fn title_tooltip_due(&self) -> bool {
+        self.phase == OverlayPhase::Full
+            && self
+                .theme
+                .title_tooltip
+                .zip(self.selected_at)
+                .is_some_and(|(dwell, at)| at.elapsed() >= dwell)
+    }
+
+    /// Redraw once the selection dwell passes, to show the title tooltip.
+    pub fn tick_title_tooltip(&mut self) {
+        if !self.title_tooltip_drawn && self.title_tooltip_due() {
+            self.needs_redraw = true;
+        }
+    }
+
+    /// Draw if anything changed and the compositor is ready for a frame.
+    /// Changes made while a frame is in flight are coalesced into the one
+    /// drawn when its callback fires.
+    pub fn redraw_if_ready(&mut self, qh: &QueueHandle<Self>) {
+        if self.needs_redraw
+            && self
+                .frame_requested_at
+                .is_none_or(|t| t.elapsed() >= FRAME_CALLBACK_TIMEOUT)
+        {
+            self.render_frame(qh);
+        }
+    }
+
+    /// Restart the title tooltip dwell for a new selection.
+    pub fn restart_title_tooltip(&mut self) {
+        self.selected_at = Some(std::time::Instant::now());
+        self.title_tooltip_drawn = false;
+    }
+
     pub fn send_event(&self, event: OverlayEvent) {
         let _ = self.event_tx.blocking_send(event);
     }
@@ -88,6 +213,7 @@ impl OverlayApp {
     pub fn hide_common(&mut self) {
         self.phase = OverlayPhase::Hidden;
         self.pending_sync = false;
+        self.first_frame_sent = false;
         self.input_buffer.clear();
         self.selection = 0;
         self.windows.clear();
@@ -97,6 +223,15 @@ impl OverlayApp {
         self.ipc_keyboard_active = false;
         self.last_real_input_at = None;
         self.staged_launch = None;
+        self.palette_message = None;
+        self.retry_notice = None;
+        self.row_menu = None;
+        self.help = None;
+        self.walkthrough = None;
+        self.delay_indicator = None;
+        self.delay_indicator_drawn_at = None;
+        self.selected_at = None;
+        self.title_tooltip_drawn = false;
         self.needs_redraw = true;
         self.set_keyboard_interactivity(KeyboardInteractivity::None);
         if let Some(ref surface) = self.layer_surface {
@@ -117,7 +252,7 @@ impl OverlayApp {
             surface,
             Layer::Overlay,
             Some("sesame"),
-            None,
+            self.pinned_output.as_ref(),
         );
         layer_surface.set_anchor(Anchor::TOP | Anchor::BOTTOM | Anchor::LEFT | Anchor::RIGHT);
         layer_surface.set_exclusive_zone(-1);
@@ -136,13 +271,50 @@ impl OverlayApp {
         }
 
         self.layer_surface = Some(layer_surface);
+        self.frame_requested_at = None;
         tracing::info!("layer surface recreated");
     }
 
+    /// Place the overlay on the output with connector `name`, or let the
+    /// compositor choose with `None`. An unknown name leaves it unpinned.
+    /// The layer surface is recreated only when the pin changes.
+    pub fn pin_output(&mut self, name: Option<&str>, qh: &QueueHandle<Self>) {
+        let output = name.and_then(|name| {
+            let found = self.output_state.outputs().find(|o| {
+                self.output_state
+                    .info(o)
+                    .and_then(|info| info.name)
+                    .is_some_and(|n| n == name)
+            });
+            if found.is_none() {
+                tracing::warn!(output = name, "no such output, overlay not pinned");
+            }
+            found
+        });
+        if output == self.pinned_output {
+            return;
+        }
+        tracing::info!(output = ?name, "overlay output changed");
+        self.pinned_output = output;
+        if let Some(blur_surface) = self.blur_surface.take() {
+            blur_surface.destroy();
+        }
+        self.layer_surface = None;
+        self.slot_pool = None;
+        self.configured_size = (0, 0);
+        self.primary_output = None;
+        self.recreate_layer_surface(qh);
+    }
+
+    /// Request keyboard focus in the configured `keyboard_mode`.
+    pub fn grab_keyboard(&self) {
+        self.set_keyboard_interactivity(self.keyboard_mode);
+    }
+
     pub fn set_keyboard_interactivity(&self, mode: KeyboardInteractivity) {
         if let Some(ref surface) = self.layer_surface {
             surface.set_keyboard_interactivity(mode);
-            if mode == KeyboardInteractivity::Exclusive {
+            if mode != KeyboardInteractivity::None {
                 surface.wl_surface().set_input_region(None);
             }
             surface.commit();
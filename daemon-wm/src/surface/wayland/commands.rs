@@ -1,5 +1,6 @@
 //! OverlayCmd processing — maps commands to state mutations.
 
+use core_config::KeyboardMode;
 use smithay_client_toolkit::shell::wlr_layer::KeyboardInteractivity;
 use wayland_client::QueueHandle;
 
@@ -9,23 +10,33 @@ use super::{OverlayCmd, OverlayEvent, OverlayPhase};
 impl OverlayApp {
     pub fn process_command(&mut self, cmd: OverlayCmd, qh: &QueueHandle<Self>) {
         match cmd {
-            OverlayCmd::ShowBorder => {
+            OverlayCmd::ShowBorder { delay_indicator } => {
                 self.phase = OverlayPhase::BorderOnly;
+                self.delay_indicator = delay_indicator;
                 self.input_buffer.clear();
                 self.selection = 0;
+                self.selected_at = None;
                 self.activated_at = Some(std::time::Instant::now());
                 self.received_key_event = false;
                 self.ipc_keyboard_active = false;
                 self.last_real_input_at = None;
                 self.staged_launch = None;
+                self.palette_message = None;
+                self.retry_notice = None;
+                self.row_menu = None;
+                self.help = None;
+                self.walkthrough = None;
                 self.modifier_released_sent = false;
                 self.needs_redraw = true;
-                self.set_keyboard_interactivity(KeyboardInteractivity::Exclusive);
+                self.grab_keyboard();
             }
             OverlayCmd::ShowFull { windows, hints } => {
                 self.phase = OverlayPhase::Full;
                 self.windows = windows;
                 self.hints = hints;
+                if self.selected_at.is_none() {
+                    self.restart_title_tooltip();
+                }
                 if self.activated_at.is_none() {
                     self.activated_at = Some(std::time::Instant::now());
                     self.received_key_event = false;
@@ -34,15 +45,63 @@ impl OverlayApp {
                 }
                 self.modifier_released_sent = false;
                 self.needs_redraw = true;
-                self.set_keyboard_interactivity(KeyboardInteractivity::Exclusive);
+                self.grab_keyboard();
             }
             OverlayCmd::UpdateInput { input, selection } => {
+                if input != self.input_buffer || selection != self.selection {
+                    self.restart_title_tooltip();
+                }
                 self.input_buffer = input;
                 self.staged_launch = None;
+                self.palette_message = None;
+                self.retry_notice = None;
                 self.selection = selection;
                 self.last_real_input_at = Some(std::time::Instant::now());
                 self.needs_redraw = true;
             }
+            OverlayCmd::SetRowMenu(menu) => {
+                self.row_menu = menu;
+                self.needs_redraw = true;
+            }
+            OverlayCmd::SetHelp(help) => {
+                self.help = help;
+                self.needs_redraw = true;
+            }
+            OverlayCmd::SetWalkthrough(page) => {
+                self.walkthrough = page;
+                self.needs_redraw = true;
+            }
+            OverlayCmd::UpdateRows { windows } => {
+                if self.phase == OverlayPhase::Full {
+                    self.windows = windows;
+                    self.needs_redraw = true;
+                }
+            }
+            OverlayCmd::ShowRetry {
+                windows,
+                hints,
+                message,
+            } => {
+                self.phase = OverlayPhase::Full;
+                self.windows = windows;
+                self.hints = hints;
+                self.input_buffer.clear();
+                self.selection = 0;
+                self.restart_title_tooltip();
+                self.staged_launch = None;
+                self.palette_message = None;
+                self.retry_notice = Some(message);
+                // Counts as interaction so the IPC idle timeout does not
+                // dismiss the picker the user is now expected to use.
+                self.last_real_input_at = Some(std::time::Instant::now());
+                // The release that committed the vanished window is spent;
+                // re-arming it here would immediately commit the new selection.
+                if !self.alt_held {
+                    self.modifier_released_sent = true;
+                }
+                self.needs_redraw = true;
+                self.grab_keyboard();
+            }
             OverlayCmd::Hide => {
                 self.hide_common();
                 self.render_frame(qh);
@@ -54,8 +113,13 @@ impl OverlayApp {
                 self.send_event(OverlayEvent::SurfaceUnmapped);
                 self.pending_sync = false;
             }
-            OverlayCmd::ShowLaunchStaged { command } => {
-                self.staged_launch = Some(command);
+            OverlayCmd::ShowLaunchStaged { command, instances } => {
+                self.staged_launch = Some((command, instances));
+                self.last_real_input_at = Some(std::time::Instant::now());
+                self.needs_redraw = true;
+            }
+            OverlayCmd::ShowPalette { message } => {
+                self.palette_message = Some(message);
                 self.last_real_input_at = Some(std::time::Instant::now());
                 self.needs_redraw = true;
             }
@@ -64,13 +128,13 @@ impl OverlayApp {
                 self.error_message.clear();
                 self.last_real_input_at = Some(std::time::Instant::now());
                 self.needs_redraw = true;
-                self.set_keyboard_interactivity(KeyboardInteractivity::Exclusive);
+                self.grab_keyboard();
             }
             OverlayCmd::ShowLaunchError { message } => {
                 self.phase = OverlayPhase::LaunchError;
                 self.error_message = message;
                 self.needs_redraw = true;
-                self.set_keyboard_interactivity(KeyboardInteractivity::Exclusive);
+                self.grab_keyboard();
             }
             OverlayCmd::ShowUnlockPrompt {
                 profile,
@@ -86,14 +150,14 @@ impl OverlayApp {
                     self.error_message.clear();
                 }
                 self.needs_redraw = true;
-                self.set_keyboard_interactivity(KeyboardInteractivity::Exclusive);
+                self.grab_keyboard();
             }
             OverlayCmd::ShowUnlockProgress { profile, message } => {
                 self.phase = OverlayPhase::UnlockProgress;
                 self.unlock_profile = profile;
                 self.unlock_message = message;
                 self.needs_redraw = true;
-                self.set_keyboard_interactivity(KeyboardInteractivity::Exclusive);
+                self.grab_keyboard();
             }
             OverlayCmd::ResetGrace => {
                 self.activated_at = Some(std::time::Instant::now());
@@ -106,13 +170,68 @@ impl OverlayApp {
                 self.received_key_event = true;
                 self.ipc_keyboard_active = true;
             }
+            OverlayCmd::Toast { message } => {
+                tracing::info!(%message, "overlay toast queued");
+                self.toasts.push(message);
+                if self.phase != OverlayPhase::Hidden {
+                    self.needs_redraw = true;
+                }
+            }
             OverlayCmd::UpdateTheme(theme) => {
                 self.theme = *theme;
                 self.needs_redraw = true;
             }
+            OverlayCmd::SetColumns {
+                show_app_id,
+                show_title,
+            } => {
+                self.show_app_id = show_app_id;
+                self.show_title = show_title;
+                self.needs_redraw = true;
+            }
+            OverlayCmd::SetKeyboardMode(mode) => {
+                self.keyboard_mode = keyboard_interactivity(mode);
+                if self.phase != OverlayPhase::Hidden {
+                    self.grab_keyboard();
+                }
+            }
+            OverlayCmd::SetAllOutputs(on) => {
+                self.all_outputs = on;
+                self.sync_mirrors(qh);
+            }
+            OverlayCmd::SetOutput(name) => self.pin_output(name.as_deref(), qh),
+            OverlayCmd::SetPowerSaving(on) => {
+                self.power_saving = on;
+            }
+            OverlayCmd::Warmup { windows, hints } => {
+                if self.phase == OverlayPhase::Hidden {
+                    self.render_offscreen(&windows, &hints);
+                }
+            }
+            #[cfg(feature = "thumbnails")]
+            OverlayCmd::RenderPreview {
+                theme,
+                windows,
+                hints,
+                reply,
+            } => {
+                let _ = reply.send(self.render_preview(*theme, &windows, &hints));
+            }
             OverlayCmd::Quit => {
+                if self.phase != OverlayPhase::Hidden {
+                    self.hide_common();
+                    self.render_frame(qh);
+                }
                 self.running = false;
             }
         }
     }
 }
+
+/// Layer-shell interactivity for a configured keyboard mode.
+pub(crate) fn keyboard_interactivity(mode: KeyboardMode) -> KeyboardInteractivity {
+    match mode {
+        KeyboardMode::Exclusive => KeyboardInteractivity::Exclusive,
+        KeyboardMode::OnDemand => KeyboardInteractivity::OnDemand,
+    }
+}
@@ -7,7 +7,10 @@ use smithay_client_toolkit::{
     registry::{ProvidesRegistryState, RegistryState},
     registry_handlers,
     seat::SeatState,
-    shell::wlr_layer::{LayerShellHandler, LayerSurface, LayerSurfaceConfigure},
+    shell::{
+        WaylandSurface,
+        wlr_layer::{LayerShellHandler, LayerSurface, LayerSurfaceConfigure},
+    },
     shm::{Shm, ShmHandler, slot::SlotPool},
 };
 use wayland_client::{
@@ -25,6 +28,11 @@ impl CompositorHandler for OverlayApp {
         surface: &wl_surface::WlSurface,
         new_factor: i32,
     ) {
+        if self.mirror_index(surface).is_some() {
+            // Mirrors read their output's scale on every frame.
+            self.needs_redraw = true;
+            return;
+        }
         self.output_scale = new_factor as f32;
         self.scale_known = true;
         surface.set_buffer_scale(new_factor);
@@ -56,9 +64,21 @@ impl CompositorHandler for OverlayApp {
     ) {
     }
 
-    fn frame(&mut self, _: &Connection, qh: &QueueHandle<Self>, _: &wl_surface::WlSurface, _: u32) {
-        if self.needs_redraw {
-            self.render_frame(qh);
+    fn frame(
+        &mut self,
+        _: &Connection,
+        qh: &QueueHandle<Self>,
+        surface: &wl_surface::WlSurface,
+        _: u32,
+    ) {
+        // Callbacks for a surface since replaced pace nothing.
+        if self
+            .layer_surface
+            .as_ref()
+            .is_some_and(|l| l.wl_surface() == surface)
+        {
+            self.frame_requested_at = None;
+            self.redraw_if_ready(qh);
         }
     }
 
@@ -69,6 +89,13 @@ impl CompositorHandler for OverlayApp {
         surface: &wl_surface::WlSurface,
         output: &wl_output::WlOutput,
     ) {
+        if self.mirror_index(surface).is_some() {
+            return;
+        }
+        if self.primary_output.as_ref() != Some(output) {
+            self.primary_output = Some(output.clone());
+            self.needs_redraw |= !self.mirrors.is_empty();
+        }
         if let Some(info) = self.output_state.info(output) {
             let new_scale = info.scale_factor as f32;
             let scale_changed = (new_scale - self.output_scale).abs() > f32::EPSILON;
@@ -113,9 +140,26 @@ impl OutputHandler for OverlayApp {
     fn output_state(&mut self) -> &mut OutputState {
         &mut self.output_state
     }
-    fn new_output(&mut self, _: &Connection, _: &QueueHandle<Self>, _: wl_output::WlOutput) {}
-    fn update_output(&mut self, _: &Connection, _: &QueueHandle<Self>, _: wl_output::WlOutput) {}
-    fn output_destroyed(&mut self, _: &Connection, _: &QueueHandle<Self>, _: wl_output::WlOutput) {}
+    fn new_output(&mut self, _: &Connection, qh: &QueueHandle<Self>, _: wl_output::WlOutput) {
+        self.sync_mirrors(qh);
+    }
+    fn update_output(&mut self, _: &Connection, _: &QueueHandle<Self>, _: wl_output::WlOutput) {
+        self.needs_redraw |= !self.mirrors.is_empty();
+    }
+    fn output_destroyed(
+        &mut self,
+        _: &Connection,
+        qh: &QueueHandle<Self>,
+        output: wl_output::WlOutput,
+    ) {
+        if self.primary_output.as_ref() == Some(&output) {
+            self.primary_output = None;
+        }
+        if self.pinned_output.as_ref() == Some(&output) {
+            self.pinned_output = None;
+        }
+        self.sync_mirrors(qh);
+    }
 }
 
 impl ShmHandler for OverlayApp {
@@ -125,7 +169,12 @@ impl ShmHandler for OverlayApp {
 }
 
 impl LayerShellHandler for OverlayApp {
-    fn closed(&mut self, _: &Connection, qh: &QueueHandle<Self>, _: &LayerSurface) {
+    fn closed(&mut self, _: &Connection, qh: &QueueHandle<Self>, layer: &LayerSurface) {
+        if let Some(i) = self.mirror_index(layer.wl_surface()) {
+            tracing::info!("compositor closed overlay mirror");
+            self.mirrors.remove(i);
+            return;
+        }
         tracing::warn!("compositor closed layer surface, recreating");
         self.hide_common();
         self.layer_surface = None;
@@ -139,10 +188,16 @@ impl LayerShellHandler for OverlayApp {
         &mut self,
         _: &Connection,
         qh: &QueueHandle<Self>,
-        _: &LayerSurface,
+        layer: &LayerSurface,
         configure: LayerSurfaceConfigure,
         _: u32,
     ) {
+        if let Some(i) = self.mirror_index(layer.wl_surface()) {
+            // Mirrors size their pools when drawn.
+            self.mirrors[i].configured_size = configure.new_size;
+            self.needs_redraw = true;
+            return;
+        }
         let (width, height) = if configure.new_size.0 > 0 && configure.new_size.1 > 0 {
             (configure.new_size.0, configure.new_size.1)
         } else {
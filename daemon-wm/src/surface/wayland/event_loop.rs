@@ -17,9 +17,12 @@ use wayland_client::{Connection, globals::registry_queue_init};
 
 use super::app::OverlayApp;
 use super::blur::BlurManager;
-use super::poll::POLL_INTERVAL_MS;
+use super::poll::{POLL_INTERVAL_MS, POWER_SAVING_POLL_INTERVAL_MS};
+use super::text_input::TextInputManager;
 use super::{OverlayCmd, OverlayEvent, OverlayPhase};
 use crate::render::OverlayTheme;
+use crate::render::scratch::FrameScratch;
+use crate::toast::ToastQueue;
 
 pub fn run_sctk_overlay(
     cmd_rx: mpsc::Receiver<OverlayCmd>,
@@ -27,6 +30,8 @@ pub fn run_sctk_overlay(
     theme: OverlayTheme,
     show_app_id: bool,
     show_title: bool,
+    keyboard_mode: core_config::KeyboardMode,
+    all_outputs: bool,
 ) {
     let conn = match Connection::connect_to_env() {
         Ok(c) => c,
@@ -84,6 +89,12 @@ pub fn run_sctk_overlay(
         tracing::info!("ext_background_effect_v1 manager bound");
     }
 
+    // Bind text-input manager (optional — only needed with an input method running).
+    let text_input_manager = TextInputManager::new(&globals, &qh).ok();
+    if text_input_manager.is_some() {
+        tracing::info!("zwp_text_input_manager_v3 bound");
+    }
+
     // Create layer surface.
     let surface = compositor_state.create_surface(&qh);
     let layer_surface =
@@ -98,11 +109,10 @@ pub fn run_sctk_overlay(
         .as_ref()
         .map(|bm| bm.get_blur_surface(layer_surface.wl_surface(), &qh));
 
-    let font_system = {
-        let mut db = cosmic_text::fontdb::Database::new();
-        db.load_system_fonts();
-        FontSystem::new_with_locale_and_db("en-US".to_string(), db)
-    };
+    let font_system = FontSystem::new_with_locale_and_db(
+        "en-US".to_string(),
+        crate::render::font_cache::load_system_fonts(),
+    );
 
     let mut app = OverlayApp {
         registry_state: RegistryState::new(&globals),
@@ -116,8 +126,12 @@ pub fn run_sctk_overlay(
         configured_size: (0, 0),
         blur_manager,
         blur_surface,
+        text_input_manager,
+        text_input: None,
+        pending_commit: None,
         font_system,
         swash_cache: SwashCache::new(),
+        scratch: FrameScratch::default(),
         phase: OverlayPhase::Hidden,
         windows: Vec::new(),
         hints: Vec::new(),
@@ -126,21 +140,42 @@ pub fn run_sctk_overlay(
         theme,
         show_app_id,
         show_title,
+        keyboard_mode: super::commands::keyboard_interactivity(keyboard_mode),
+        all_outputs,
+        mirrors: Vec::new(),
+        primary_output: None,
+        pinned_output: None,
         activated_at: None,
         received_key_event: false,
         ipc_keyboard_active: false,
         last_real_input_at: None,
         error_message: String::new(),
         staged_launch: None,
+        palette_message: None,
+        retry_notice: None,
+        row_menu: None,
+        help: None,
+        walkthrough: None,
+        delay_indicator: None,
+        delay_indicator_drawn_at: None,
+        selected_at: None,
+        title_tooltip_drawn: false,
+        power_saving: false,
         unlock_profile: String::new(),
         unlock_password_len: 0,
         unlock_message: String::new(),
+        toasts: ToastQueue::default(),
+        toast_visible: false,
         alt_held: false,
+        ctrl_held: false,
+        shift_held: false,
         modifier_released_sent: false,
         event_tx,
         running: true,
         needs_redraw: false,
+        frame_requested_at: None,
         pending_sync: false,
+        first_frame_sent: false,
         output_scale: 1.0,
         scale_known: false,
         empty_input_region,
@@ -167,9 +202,10 @@ pub fn run_sctk_overlay(
                 }
                 drain_commands(&mut app, &qh, &cmd_rx);
                 app.poll_modifiers();
-                if app.needs_redraw {
-                    app.render_frame(&qh);
-                }
+                app.tick_delay_indicator();
+                app.tick_toasts();
+                app.tick_title_tooltip();
+                app.redraw_if_ready(&qh);
                 continue;
             }
         };
@@ -179,9 +215,14 @@ pub fn run_sctk_overlay(
             &fd,
             rustix::event::PollFlags::IN,
         )];
+        let interval_ms = if app.power_saving {
+            POWER_SAVING_POLL_INTERVAL_MS
+        } else {
+            POLL_INTERVAL_MS
+        };
         let timeout = rustix::event::Timespec {
             tv_sec: 0,
-            tv_nsec: (POLL_INTERVAL_MS as i64) * 1_000_000,
+            tv_nsec: (interval_ms as i64) * 1_000_000,
         };
         let _ = rustix::event::poll(&mut poll_fd, Some(&timeout));
 
@@ -208,15 +249,19 @@ pub fn run_sctk_overlay(
 
         drain_commands(&mut app, &qh, &cmd_rx);
         app.poll_modifiers();
-        if app.needs_redraw {
-            app.render_frame(&qh);
-        }
+        app.tick_delay_indicator();
+        app.tick_toasts();
+        app.tick_title_tooltip();
+        app.redraw_if_ready(&qh);
     }
 
-    // Cleanup blur surface on exit.
+    // Cleanup blur and layer surfaces on exit, and flush so the compositor
+    // unmaps them before the process goes away.
     if let Some(blur_surface) = app.blur_surface.take() {
         blur_surface.destroy();
     }
+    drop(app.layer_surface.take());
+    let _ = conn.flush();
 
     tracing::info!("overlay thread exiting");
 }
@@ -234,7 +279,7 @@ pub fn run_stub_loop(
             Ok(OverlayCmd::HideAndSync) => {
                 let _ = event_tx.blocking_send(OverlayEvent::SurfaceUnmapped);
             }
-            Ok(OverlayCmd::ShowBorder) | Ok(OverlayCmd::ShowFull { .. }) => {
+            Ok(OverlayCmd::ShowBorder { .. }) | Ok(OverlayCmd::ShowFull { .. }) => {
                 if !warned {
                     tracing::warn!(
                         "overlay not available: compositor does not support wlr-layer-shell"
@@ -12,8 +12,9 @@ use wayland_client::{
     protocol::{wl_keyboard, wl_seat, wl_surface},
 };
 
-use super::OverlayEvent;
 use super::app::OverlayApp;
+use super::{OverlayEvent, OverlayPhase};
+use crate::actions;
 
 impl SeatHandler for OverlayApp {
     fn seat_state(&mut self) -> &mut SeatState {
@@ -31,6 +32,11 @@ impl SeatHandler for OverlayApp {
     ) {
         if capability == Capability::Keyboard {
             let _ = self.seat_state.get_keyboard(qh, &seat, None);
+            if self.text_input.is_none()
+                && let Some(ref manager) = self.text_input_manager
+            {
+                self.text_input = Some(manager.get_text_input(&seat, qh));
+            }
         }
     }
 
@@ -58,6 +64,9 @@ impl KeyboardHandler for OverlayApp {
     ) {
         self.received_key_event = true;
         self.alt_held = true;
+        if self.phase != OverlayPhase::Hidden {
+            self.send_event(OverlayEvent::KeyboardEntered(std::time::Instant::now()));
+        }
     }
 
     fn leave(
@@ -79,13 +88,10 @@ impl KeyboardHandler for OverlayApp {
         event: KeyEvent,
     ) {
         self.received_key_event = true;
+        let action = actions::lookup(event.keysym.raw(), self.ctrl_held, self.shift_held);
         let ev = match event.keysym {
-            Keysym::Escape => Some(OverlayEvent::Escape),
-            Keysym::Return | Keysym::KP_Enter => Some(OverlayEvent::Confirm),
+            _ if action.is_some() => action.map(OverlayEvent::Action),
             Keysym::Tab | Keysym::ISO_Left_Tab => None,
-            Keysym::Down => Some(OverlayEvent::SelectionDown),
-            Keysym::Up => Some(OverlayEvent::SelectionUp),
-            Keysym::BackSpace => Some(OverlayEvent::Backspace),
             Keysym::space => Some(OverlayEvent::KeyChar(' ')),
             _ => event
                 .utf8
@@ -93,7 +99,7 @@ impl KeyboardHandler for OverlayApp {
                 .and_then(|s| {
                     let mut chars = s.chars();
                     let c = chars.next()?;
-                    if chars.next().is_none() && c.is_ascii_graphic() {
+                    if chars.next().is_none() && crate::hints::is_input_char(c) {
                         Some(c)
                     } else {
                         None
@@ -134,6 +140,8 @@ impl KeyboardHandler for OverlayApp {
         _: u32,
     ) {
         self.alt_held = modifiers.alt;
+        self.ctrl_held = modifiers.ctrl;
+        self.shift_held = modifiers.shift;
     }
 
     fn repeat_key(
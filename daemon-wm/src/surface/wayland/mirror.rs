@@ -0,0 +1,314 @@
+//! Overlay copies on the other outputs (`show_on_all_outputs`).
+//!
+//! The main layer surface is placed by the compositor, normally on the
+//! focused output, and is the only one that takes keyboard focus. With
+//! `show_on_all_outputs`, every output also gets a mirror surface that
+//! draws the same frame (phase, rows, input and selection) at its own size
+//! and scale, and never takes input. The mirror on the main surface's
+//! output stays transparent.
+
+use smithay_client_toolkit::{
+    compositor::Region,
+    shell::{
+        WaylandSurface,
+        wlr_layer::{Anchor, KeyboardInteractivity, Layer, LayerSurface},
+    },
+    shm::slot::SlotPool,
+};
+use wayland_client::QueueHandle;
+use wayland_client::protocol::{wl_output::WlOutput, wl_shm, wl_surface::WlSurface};
+use wayland_protocols::ext::background_effect::v1::client::ext_background_effect_surface_v1::ExtBackgroundEffectSurfaceV1;
+
+use super::OverlayPhase;
+use super::app::OverlayApp;
+use super::render_frame::{Frame, copy_to_canvas};
+
+/// A non-interactive copy of the overlay on one output.
+pub(crate) struct MirrorSurface {
+    pub output: WlOutput,
+    pub layer_surface: LayerSurface,
+    pub blur_surface: Option<ExtBackgroundEffectSurfaceV1>,
+    pub slot_pool: Option<SlotPool>,
+    pub configured_size: (u32, u32),
+}
+
+impl Drop for MirrorSurface {
+    fn drop(&mut self) {
+        if let Some(blur) = self.blur_surface.take() {
+            blur.destroy();
+        }
+    }
+}
+
+/// Drop the mirrors whose output is gone and `create` one for each output
+/// that has none, keeping the survivors in order. Returns how many were
+/// created.
+fn reconcile<M, O: PartialEq + Clone>(
+    mirrors: &mut Vec<M>,
+    outputs: &[O],
+    output_of: impl Fn(&M) -> &O,
+    mut create: impl FnMut(O) -> M,
+) -> usize {
+    mirrors.retain(|m| outputs.contains(output_of(m)));
+    let missing: Vec<O> = outputs
+        .iter()
+        .filter(|output| !mirrors.iter().any(|m| output_of(m) == *output))
+        .cloned()
+        .collect();
+    let created = missing.len();
+    mirrors.extend(missing.into_iter().map(&mut create));
+    created
+}
+
+/// Whether the mirror on `output` draws the overlay. The main surface
+/// already shows it on `primary`, so that mirror stays transparent; until
+/// the main surface enters an output (or after its output is removed) every
+/// mirror draws.
+fn shows_overlay<O: PartialEq>(phase: OverlayPhase, primary: Option<&O>, output: &O) -> bool {
+    phase != OverlayPhase::Hidden && primary != Some(output)
+}
+
+impl OverlayApp {
+    /// Create or destroy mirrors so there is one per output while
+    /// `show_on_all_outputs` is on, and none otherwise.
+    pub fn sync_mirrors(&mut self, qh: &QueueHandle<Self>) {
+        if !self.all_outputs {
+            if !self.mirrors.is_empty() {
+                tracing::info!(count = self.mirrors.len(), "overlay mirrors removed");
+                self.mirrors.clear();
+            }
+            return;
+        }
+        let outputs: Vec<WlOutput> = self.output_state.outputs().collect();
+        let mut mirrors = std::mem::take(&mut self.mirrors);
+        let created = reconcile(
+            &mut mirrors,
+            &outputs,
+            |m| &m.output,
+            |output| self.create_mirror(output, qh),
+        );
+        self.mirrors = mirrors;
+        if created > 0 {
+            tracing::info!(
+                created,
+                count = self.mirrors.len(),
+                "overlay mirrors created"
+            );
+        }
+        self.needs_redraw = true;
+    }
+
+    fn create_mirror(&self, output: WlOutput, qh: &QueueHandle<Self>) -> MirrorSurface {
+        let surface = self.compositor_state.create_surface(qh);
+        let layer_surface = self.layer_shell.create_layer_surface(
+            qh,
+            surface,
+            Layer::Overlay,
+            Some("sesame"),
+            Some(&output),
+        );
+        layer_surface.set_anchor(Anchor::TOP | Anchor::BOTTOM | Anchor::LEFT | Anchor::RIGHT);
+        layer_surface.set_exclusive_zone(-1);
+        layer_surface.set_keyboard_interactivity(KeyboardInteractivity::None);
+        layer_surface
+            .wl_surface()
+            .set_input_region(Some(self.empty_input_region.wl_region()));
+        layer_surface.commit();
+        let blur_surface = self
+            .blur_manager
+            .as_ref()
+            .map(|bm| bm.get_blur_surface(layer_surface.wl_surface(), qh));
+        MirrorSurface {
+            output,
+            layer_surface,
+            blur_surface,
+            slot_pool: None,
+            configured_size: (0, 0),
+        }
+    }
+
+    /// Index of the mirror that owns `surface`, if it is not the main
+    /// surface.
+    pub fn mirror_index(&self, surface: &WlSurface) -> Option<usize> {
+        self.mirrors
+            .iter()
+            .position(|m| m.layer_surface.wl_surface() == surface)
+    }
+
+    /// Draw `frame` on every configured mirror.
+    pub(super) fn render_mirrors(&mut self, frame: &Frame<'_>) {
+        for i in 0..self.mirrors.len() {
+            let (logical_w, logical_h) = self.mirrors[i].configured_size;
+            if logical_w == 0 || logical_h == 0 {
+                continue;
+            }
+            let scale = self
+                .output_state
+                .info(&self.mirrors[i].output)
+                .map_or(1.0, |info| info.scale_factor as f32);
+            let width = (logical_w as f32 * scale) as u32;
+            let height = (logical_h as f32 * scale) as u32;
+            let shown = shows_overlay(
+                self.phase,
+                self.primary_output.as_ref(),
+                &self.mirrors[i].output,
+            );
+            let blur_rect = if shown {
+                self.blur_rect(width as f32, height as f32, scale, false)
+            } else {
+                None
+            };
+            let pixmap = if shown {
+                tiny_skia::Pixmap::new(width, height).map(|mut pixmap| {
+                    self.draw_phase(&mut pixmap, scale, frame, false);
+                    pixmap
+                })
+            } else {
+                None
+            };
+
+            let compositor_state = &self.compositor_state;
+            let shm = &self.shm;
+            let mirror = &mut self.mirrors[i];
+            let buf_size = (width * height * 4) as usize;
+            match mirror.slot_pool.as_mut() {
+                Some(pool) => {
+                    if let Err(e) = pool.resize(buf_size) {
+                        tracing::warn!("failed to resize mirror slot pool: {e}");
+                        continue;
+                    }
+                }
+                None => match SlotPool::new(buf_size, shm) {
+                    Ok(pool) => mirror.slot_pool = Some(pool),
+                    Err(e) => {
+                        tracing::warn!("failed to create mirror slot pool: {e}");
+                        continue;
+                    }
+                },
+            }
+            let Some(pool) = mirror.slot_pool.as_mut() else {
+                continue;
+            };
+            let (buffer, canvas) = match pool.create_buffer(
+                width as i32,
+                height as i32,
+                width as i32 * 4,
+                wl_shm::Format::Argb8888,
+            ) {
+                Ok(created) => created,
+                Err(e) => {
+                    tracing::warn!("failed to create mirror shm buffer: {e}");
+                    continue;
+                }
+            };
+            copy_to_canvas(pixmap, canvas);
+
+            let wl_surface = mirror.layer_surface.wl_surface();
+            if let Err(e) = buffer.attach_to(wl_surface) {
+                tracing::warn!("failed to attach mirror buffer: {e}");
+                continue;
+            }
+            wl_surface.set_buffer_scale(scale.ceil() as i32);
+            wl_surface.damage_buffer(0, 0, width as i32, height as i32);
+            wl_surface.commit();
+
+            if let Some(ref blur_surface) = mirror.blur_surface {
+                match blur_rect {
+                    Some((x, y, w, h)) => {
+                        if let Ok(region) = Region::new(compositor_state) {
+                            region.add(x as i32, y as i32, w as i32, h as i32);
+                            blur_surface.set_blur_region(Some(region.wl_region()));
+                        }
+                    }
+                    None => blur_surface.set_blur_region(None),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A stand-in mirror: the output it is on and a creation serial.
+    type Mirror = (&'static str, usize);
+
+    fn sync(mirrors: &mut Vec<Mirror>, outputs: &[&'static str], serial: &mut usize) -> usize {
+        reconcile(
+            mirrors,
+            outputs,
+            |m| &m.0,
+            |output| {
+                *serial += 1;
+                (output, *serial)
+            },
+        )
+    }
+
+    #[test]
+    fn every_output_gets_one_mirror() {
+        let mut serial = 0;
+        let mut mirrors = Vec::new();
+        assert_eq!(sync(&mut mirrors, &["DP-1", "HDMI-A-1"], &mut serial), 2);
+        assert_eq!(mirrors, [("DP-1", 1), ("HDMI-A-1", 2)]);
+
+        // Syncing again with the same outputs keeps the same surfaces.
+        assert_eq!(sync(&mut mirrors, &["DP-1", "HDMI-A-1"], &mut serial), 0);
+        assert_eq!(mirrors, [("DP-1", 1), ("HDMI-A-1", 2)]);
+    }
+
+    #[test]
+    fn removed_output_drops_only_its_mirror() {
+        let mut serial = 0;
+        let mut mirrors = Vec::new();
+        sync(&mut mirrors, &["eDP-1", "DP-1", "DP-2"], &mut serial);
+
+        assert_eq!(sync(&mut mirrors, &["eDP-1", "DP-2"], &mut serial), 0);
+        assert_eq!(mirrors, [("eDP-1", 1), ("DP-2", 3)]);
+
+        // A monitor plugged back in gets a fresh mirror at the end.
+        assert_eq!(
+            sync(&mut mirrors, &["eDP-1", "DP-2", "DP-1"], &mut serial),
+            1
+        );
+        assert_eq!(mirrors, [("eDP-1", 1), ("DP-2", 3), ("DP-1", 4)]);
+    }
+
+    #[test]
+    fn mirror_on_the_primary_output_stays_transparent() {
+        let primary = Some(&"eDP-1");
+        assert!(!shows_overlay(OverlayPhase::Full, primary, &"eDP-1"));
+        assert!(shows_overlay(OverlayPhase::Full, primary, &"DP-1"));
+        assert!(shows_overlay(OverlayPhase::BorderOnly, primary, &"DP-1"));
+        assert!(!shows_overlay(OverlayPhase::Hidden, primary, &"DP-1"));
+    }
+
+    #[test]
+    fn primary_output_removed_while_shown() {
+        let mut serial = 0;
+        let mut mirrors = Vec::new();
+        sync(&mut mirrors, &["eDP-1", "DP-1"], &mut serial);
+        let mut primary = Some("DP-1");
+
+        // `output_destroyed` forgets the primary output, then syncs.
+        let outputs = ["eDP-1"];
+        primary = primary.filter(|p| outputs.contains(p));
+        sync(&mut mirrors, &outputs, &mut serial);
+        assert_eq!(mirrors, [("eDP-1", 1)]);
+        // The remaining mirror draws until the main surface enters its
+        // new output.
+        assert!(shows_overlay(
+            OverlayPhase::Full,
+            primary.as_ref(),
+            &"eDP-1"
+        ));
+
+        primary = Some("eDP-1");
+        assert!(!shows_overlay(
+            OverlayPhase::Full,
+            primary.as_ref(),
+            &"eDP-1"
+        ));
+    }
+}
@@ -10,8 +10,10 @@ pub mod commands;
 pub mod compositor;
 pub mod event_loop;
 pub mod keyboard;
+pub mod mirror;
 pub mod poll;
 pub mod render_frame;
+pub mod text_input;
 
 use std::sync::mpsc;
 
@@ -22,7 +24,10 @@ use std::sync::mpsc;
 /// Commands sent from the tokio event loop to the overlay thread.
 #[derive(Debug)]
 pub enum OverlayCmd {
-    ShowBorder,
+    ShowBorder {
+        /// Dwell before the card appears, drawn as a progress ring.
+        delay_indicator: Option<std::time::Duration>,
+    },
     ShowFull {
         windows: Vec<WindowInfo>,
         hints: Vec<String>,
@@ -31,11 +36,33 @@ pub enum OverlayCmd {
         input: String,
         selection: usize,
     },
+    /// Replace the picker rows after a title or app ID change; input,
+    /// selection and hints stay. Ignored unless the picker is shown.
+    UpdateRows {
+        windows: Vec<WindowInfo>,
+    },
+    /// Draw the selected row's context menu, or close it.
+    SetRowMenu(Option<crate::row_menu::MenuView>),
+    /// Draw the help overlay in place of the picker, or hide it.
+    SetHelp(Option<crate::actions::HelpView>),
+    /// Draw a first-run walkthrough page in place of the picker, or hide
+    /// the walkthrough.
+    SetWalkthrough(Option<crate::onboarding::WalkthroughView>),
+    /// Replace the picker contents and show `message` beneath the card.
+    ShowRetry {
+        windows: Vec<WindowInfo>,
+        hints: Vec<String>,
+        message: String,
+    },
     Hide,
     HideAndSync,
     ShowLaunching,
     ShowLaunchStaged {
         command: String,
+        instances: usize,
+    },
+    ShowPalette {
+        message: String,
     },
     ShowLaunchError {
         message: String,
@@ -51,7 +78,42 @@ pub enum OverlayCmd {
     },
     ResetGrace,
     ConfirmKeyboardInput,
+    /// Queue a banner for a non-fatal problem, shown over the overlay for
+    /// a couple of seconds (now if visible, else on the next activation).
+    Toast {
+        message: String,
+    },
     UpdateTheme(Box<crate::render::OverlayTheme>),
+    /// Row columns to draw (`wm.show_app_id`, `wm.show_title`).
+    SetColumns {
+        show_app_id: bool,
+        show_title: bool,
+    },
+    /// Interactivity to request while visible (`wm.keyboard_mode`).
+    SetKeyboardMode(core_config::KeyboardMode),
+    /// Mirror the overlay on every output (`wm.show_on_all_outputs`).
+    SetAllOutputs(bool),
+    /// Show the overlay on the named output (`sesame wm overlay --output`),
+    /// or wherever the compositor chooses.
+    SetOutput(Option<String>),
+    /// Poll less often and skip animations (`wm.power_saving`).
+    SetPowerSaving(bool),
+    /// Render a picker frame offscreen without showing it, so fonts are
+    /// loaded and glyphs rasterized before the first real activation.
+    Warmup {
+        windows: Vec<WindowInfo>,
+        hints: Vec<String>,
+    },
+    /// Render a picker frame offscreen with `theme` (leaving the current
+    /// theme in place) and reply with the card as a PNG, or an empty buffer
+    /// if nothing could be drawn.
+    #[cfg(feature = "thumbnails")]
+    RenderPreview {
+        theme: Box<crate::render::OverlayTheme>,
+        windows: Vec<WindowInfo>,
+        hints: Vec<String>,
+        reply: tokio::sync::oneshot::Sender<Vec<u8>>,
+    },
     Quit,
 }
 
@@ -59,14 +121,15 @@ pub enum OverlayCmd {
 #[derive(Debug, Clone)]
 pub enum OverlayEvent {
     KeyChar(char),
-    Backspace,
-    SelectionDown,
-    SelectionUp,
-    Confirm,
-    Escape,
+    /// A key bound in the [action registry](crate::actions).
+    Action(&'static crate::actions::Action),
     ModifierReleased,
     Dismiss,
     SurfaceUnmapped,
+    /// First buffer of an activation committed (latency instrumentation).
+    FrameCommitted(std::time::Instant),
+    /// Compositor delivered keyboard focus to the visible overlay.
+    KeyboardEntered(std::time::Instant),
 }
 
 /// Minimal window info passed to the overlay for display.
@@ -74,6 +137,31 @@ pub enum OverlayEvent {
 pub struct WindowInfo {
     pub app_id: String,
     pub title: String,
+    /// Position among the windows of the same app (for the count badge).
+    pub position: crate::hints::AppPosition,
+    /// Listed in the pinned section (`pinned_apps`).
+    pub pinned: bool,
+    /// A dialog listed directly below its parent window
+    /// (`transient_windows = "nest"`).
+    pub nested: bool,
+    /// Shown on every workspace.
+    pub sticky: bool,
+    /// Kept above other windows.
+    pub keep_above: bool,
+    /// Minimized; drawn dimmed.
+    pub minimized: bool,
+    /// Name shown and searched instead of the app ID's: the app's key
+    /// binding `label`, else the `Name=` of its desktop entry.
+    pub label: Option<String>,
+    /// The app's key binding `icon`: a glyph or an icon theme name, drawn
+    /// before the name.
+    pub icon: Option<String>,
+    /// When the window was last focused ("2 min ago"), drawn after the
+    /// title. Set only in the focus timeline view.
+    pub focused_ago: Option<String>,
+    /// The window's rectangle on the overlay's output, in logical pixels,
+    /// with `hint_placement = "windows"`; its badge is drawn over it.
+    pub geometry: Option<core_types::Geometry>,
 }
 
 // ---------------------------------------------------------------------------
@@ -100,6 +188,8 @@ pub fn spawn_overlay(
     theme: crate::render::OverlayTheme,
     show_app_id: bool,
     show_title: bool,
+    keyboard_mode: core_config::KeyboardMode,
+    all_outputs: bool,
 ) -> (
     mpsc::Sender<OverlayCmd>,
     tokio::sync::mpsc::Receiver<OverlayEvent>,
@@ -110,7 +200,15 @@ pub fn spawn_overlay(
     std::thread::Builder::new()
         .name("overlay-sctk".into())
         .spawn(move || {
-            event_loop::run_sctk_overlay(cmd_rx, event_tx, theme, show_app_id, show_title);
+            event_loop::run_sctk_overlay(
+                cmd_rx,
+                event_tx,
+                theme,
+                show_app_id,
+                show_title,
+                keyboard_mode,
+                all_outputs,
+            );
         })
         .expect("failed to spawn overlay thread");
 
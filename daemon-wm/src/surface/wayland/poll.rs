@@ -2,7 +2,6 @@
 
 use super::app::OverlayApp;
 use super::{OverlayEvent, OverlayPhase};
-use smithay_client_toolkit::shell::wlr_layer::KeyboardInteractivity;
 
 /// Grace period (ms) after activation before modifier polling begins.
 pub const MODIFIER_POLL_GRACE_MS: u128 = 150;
@@ -16,6 +15,9 @@ pub const IPC_IDLE_TIMEOUT_MS: u128 = 30_000;
 /// Modifier poll interval in milliseconds.
 pub const POLL_INTERVAL_MS: u64 = 4;
 
+/// Modifier poll interval in milliseconds while throttled for power saving.
+pub const POWER_SAVING_POLL_INTERVAL_MS: u64 = 16;
+
 impl OverlayApp {
     pub fn poll_modifiers(&mut self) {
         if self.phase == OverlayPhase::Hidden {
@@ -30,7 +32,7 @@ impl OverlayApp {
         let within_grace = elapsed_ms < MODIFIER_POLL_GRACE_MS;
 
         if !self.received_key_event {
-            self.set_keyboard_interactivity(KeyboardInteractivity::Exclusive);
+            self.grab_keyboard();
         }
 
         if !self.received_key_event
@@ -5,12 +5,43 @@ use smithay_client_toolkit::{compositor::Region, shell::WaylandSurface};
 use wayland_client::QueueHandle;
 use wayland_client::protocol::wl_shm;
 
-use super::OverlayPhase;
 use super::app::OverlayApp;
+use super::{OverlayEvent, OverlayPhase, WindowInfo};
 use crate::render::{self, HintRow};
 
+/// Logical output size assumed for a warm-up frame before the layer surface
+/// has been configured.
+const WARMUP_FALLBACK_SIZE: (u32, u32) = (1920, 1080);
+
+/// Logical pixels of backdrop kept around the card in a theme preview.
+#[cfg(feature = "thumbnails")]
+const PREVIEW_MARGIN: f32 = 32.0;
+
+/// Per-frame state shared by every surface drawn in one frame.
+pub(super) struct Frame<'a> {
+    pub toast: Option<&'a str>,
+    pub delay_progress: Option<f32>,
+    pub title_tooltip: bool,
+}
+
+/// Copy a drawn frame into a shm buffer, or clear the buffer when there is
+/// none.
+pub(super) fn copy_to_canvas(pixmap: Option<tiny_skia::Pixmap>, canvas: &mut [u8]) {
+    let Some(pixmap) = pixmap else {
+        canvas.fill(0);
+        return;
+    };
+    let mut pixel_data = pixmap.take();
+    render::convert_rgba_to_argb8888(&mut pixel_data);
+    let len = canvas.len().min(pixel_data.len());
+    canvas[..len].copy_from_slice(&pixel_data[..len]);
+}
+
 impl OverlayApp {
-    pub fn render_frame(&mut self, _qh: &QueueHandle<Self>) {
+    /// Draw and commit a frame now, asking for a frame callback that paces
+    /// the next one. Callers other than `configure` and hiding go through
+    /// `redraw_if_ready`.
+    pub fn render_frame(&mut self, qh: &QueueHandle<Self>) {
         self.needs_redraw = false;
 
         let (logical_w, logical_h) = self.configured_size;
@@ -21,41 +52,43 @@ impl OverlayApp {
         let scale = self.output_scale;
         let width = (logical_w as f32 * scale) as u32;
         let height = (logical_h as f32 * scale) as u32;
-        let wf = width as f32;
-        let hf = height as f32;
 
         // Compute blur region geometry BEFORE borrowing the pool.
-        // Blur covers the card area only — set whenever the card is visible.
-        // The card is the ONLY visual element; it must have consistent blur
-        // from the moment it appears until dismissal.
-        let blur_rect = if self.phase == OverlayPhase::Full
-            || self.phase == OverlayPhase::Launching
-            || self.phase == OverlayPhase::LaunchError
-            || self.phase == OverlayPhase::UnlockPrompt
-            || self.phase == OverlayPhase::UnlockProgress
-        {
-            // For Full, use the window-list card geometry.
-            // For toasts/prompts, use a generous centered region.
-            if self.phase == OverlayPhase::Full {
-                let row_count = self.windows.len();
-                Some(render::compute_card_rect(
-                    row_count,
-                    wf,
-                    hf,
-                    scale,
-                    self.show_app_id,
-                    self.show_title,
-                ))
-            } else {
-                // Toast/prompt: blur a centered region roughly 500x200
-                let cw = (wf * 0.5).min(500.0);
-                let ch = 200.0;
-                Some(((wf - cw) / 2.0, (hf - ch) / 2.0, cw, ch))
-            }
-        } else {
+        let blur_rect = self.blur_rect(width as f32, height as f32, scale, true);
+
+        // Toasts wait out the armed phase: a quick switch never shows the
+        // card, so a banner there would be a flash nobody can read.
+        let toast = if matches!(self.phase, OverlayPhase::Hidden | OverlayPhase::BorderOnly) {
             None
+        } else {
+            self.toasts
+                .show(std::time::Instant::now())
+                .map(str::to_owned)
+        };
+        self.toast_visible = toast.is_some();
+
+        // Armed-phase delay ring, read before the pool borrow.
+        let delay_progress = self.delay_progress();
+        if delay_progress.is_some() {
+            self.delay_indicator_drawn_at = Some(std::time::Instant::now());
+        }
+        let title_tooltip = self.title_tooltip_due();
+        self.title_tooltip_drawn = title_tooltip;
+        let frame = Frame {
+            toast: toast.as_deref(),
+            delay_progress,
+            title_tooltip,
         };
 
+        // Draw before borrowing the pool: drawing needs the font system.
+        if self.slot_pool.is_none() {
+            return;
+        }
+        let pixmap = tiny_skia::Pixmap::new(width, height).map(|mut pixmap| {
+            self.draw_phase(&mut pixmap, scale, &frame, true);
+            pixmap
+        });
+
         let pool = match self.slot_pool.as_mut() {
             Some(p) => p,
             None => return,
@@ -74,125 +107,378 @@ impl OverlayApp {
                 return;
             }
         };
+        copy_to_canvas(pixmap, canvas);
 
-        if let Some(mut pixmap) = tiny_skia::Pixmap::new(width, height) {
-            match self.phase {
-                OverlayPhase::Hidden => {
-                    pixmap.fill(tiny_skia::Color::TRANSPARENT);
-                }
-                OverlayPhase::BorderOnly => {
-                    render::draw_border_only(&mut pixmap, wf, hf, scale, &self.theme);
-                }
-                OverlayPhase::Full => {
-                    let rows: Vec<HintRow<'_>> = self
-                        .windows
-                        .iter()
-                        .zip(self.hints.iter())
-                        .map(|(w, h)| HintRow {
-                            hint: h.as_str(),
-                            app_id: &w.app_id,
-                            title: &w.title,
-                        })
-                        .collect();
-                    render::draw_full_overlay(
-                        &mut pixmap,
-                        &mut self.font_system,
-                        &mut self.swash_cache,
+        // Attach buffer and commit.
+        if let Some(ref surface) = self.layer_surface {
+            let wl_surface = surface.wl_surface();
+            buffer
+                .attach_to(wl_surface)
+                .expect("failed to attach buffer");
+            wl_surface.set_buffer_scale(scale.ceil() as i32);
+            wl_surface.damage_buffer(0, 0, width as i32, height as i32);
+            wl_surface.frame(qh, wl_surface.clone());
+            wl_surface.commit();
+            self.frame_requested_at = Some(std::time::Instant::now());
+        }
+        if self.phase != OverlayPhase::Hidden && !self.first_frame_sent {
+            self.first_frame_sent = true;
+            self.send_event(OverlayEvent::FrameCommitted(std::time::Instant::now()));
+        }
+
+        // Set blur region AFTER buffer commit (pool borrow is dropped).
+        if let Some((cx, cy, cw, ch)) = blur_rect {
+            self.set_blur_region(cx as i32, cy as i32, cw as i32, ch as i32);
+        }
+
+        self.render_mirrors(&frame);
+    }
+
+    /// Card region to blur behind a `wf`×`hf` frame, when a card is shown.
+    /// `on_windows` is as for [`draw_phase`](Self::draw_phase).
+    pub(super) fn blur_rect(
+        &self,
+        wf: f32,
+        hf: f32,
+        scale: f32,
+        on_windows: bool,
+    ) -> Option<(f32, f32, f32, f32)> {
+        // Blur covers the card area only — set whenever the card is visible.
+        // The card is the ONLY visual element; it must have consistent blur
+        // from the moment it appears until dismissal.
+        if self.phase == OverlayPhase::Full
+            || self.phase == OverlayPhase::Launching
+            || self.phase == OverlayPhase::LaunchError
+            || self.phase == OverlayPhase::UnlockPrompt
+            || self.phase == OverlayPhase::UnlockProgress
+        {
+            // For Full, use the window-list card geometry.
+            // For toasts/prompts, use a generous centered region.
+            if self.phase == OverlayPhase::Full
+                && (self.help.is_some() || self.walkthrough.is_some())
+            {
+                // The help and walkthrough cards are opaque and sized by
+                // their text.
+                None
+            } else if self.phase == OverlayPhase::Full {
+                let row_count = self.windows.len();
+                if on_windows && self.windows.iter().any(|w| w.geometry.is_some()) {
+                    // Badges over windows are opaque; only the card of the
+                    // other rows is blurred.
+                    let listed = self.windows.iter().filter(|w| w.geometry.is_none());
+                    return render::window_badges::compute_card_rect(
+                        listed.count(),
                         wf,
                         hf,
                         scale,
-                        &rows,
-                        &self.input_buffer,
-                        self.selection,
-                        &self.hints,
                         &self.theme,
                         self.show_app_id,
                         self.show_title,
-                        self.staged_launch.as_deref(),
                     );
                 }
-                OverlayPhase::Launching => {
-                    render::draw_status_toast(
-                        &mut pixmap,
-                        &mut self.font_system,
-                        &mut self.swash_cache,
-                        wf,
-                        hf,
-                        scale,
-                        "Launching\u{2026}",
-                        &self.theme,
-                    );
-                }
-                OverlayPhase::LaunchError => {
-                    render::draw_error_toast(
-                        &mut pixmap,
-                        &mut self.font_system,
-                        &mut self.swash_cache,
-                        wf,
-                        hf,
-                        scale,
-                        &self.error_message,
-                        &self.theme,
-                    );
-                }
-                OverlayPhase::UnlockPrompt => {
-                    let error = if self.error_message.is_empty() {
-                        None
-                    } else {
-                        Some(self.error_message.as_str())
-                    };
-                    render::draw_unlock_prompt(
-                        &mut pixmap,
+                Some(render::compute_card_rect(
+                    row_count,
+                    wf,
+                    hf,
+                    scale,
+                    &self.theme,
+                    self.show_app_id,
+                    self.show_title,
+                ))
+            } else {
+                // Toast/prompt: blur a centered region roughly 500x200
+                // logical pixels.
+                let cw = (wf * 0.5).min(500.0 * scale);
+                let ch = 200.0 * scale;
+                Some(((wf - cw) / 2.0, (hf - ch) / 2.0, cw, ch))
+            }
+        } else {
+            None
+        }
+    }
+
+    /// Draw the current phase, and the toast over it, into `pixmap`.
+    ///
+    /// `on_windows` draws hint badges over the windows that have a
+    /// rectangle; only the main surface does, as rectangles are relative
+    /// to its output.
+    pub(super) fn draw_phase(
+        &mut self,
+        pixmap: &mut tiny_skia::Pixmap,
+        scale: f32,
+        frame: &Frame<'_>,
+        on_windows: bool,
+    ) {
+        let wf = pixmap.width() as f32;
+        let hf = pixmap.height() as f32;
+        match self.phase {
+            OverlayPhase::Hidden => {
+                pixmap.fill(tiny_skia::Color::TRANSPARENT);
+            }
+            OverlayPhase::BorderOnly => {
+                render::draw_border_only(pixmap, wf, hf, scale, &self.theme, frame.delay_progress);
+            }
+            OverlayPhase::Full if self.walkthrough.is_some() => {
+                if let Some(page) = &self.walkthrough {
+                    render::onboarding::draw_walkthrough(
+                        pixmap,
                         &mut self.font_system,
                         &mut self.swash_cache,
                         wf,
                         hf,
                         scale,
-                        &self.unlock_profile,
-                        self.unlock_password_len,
-                        error,
+                        page,
                         &self.theme,
                     );
                 }
-                OverlayPhase::UnlockProgress => {
-                    render::draw_status_toast(
-                        &mut pixmap,
+            }
+            OverlayPhase::Full if self.help.is_some() => {
+                if let Some(help) = &self.help {
+                    render::help::draw_help(
+                        pixmap,
                         &mut self.font_system,
                         &mut self.swash_cache,
                         wf,
                         hf,
                         scale,
-                        &self.unlock_message,
+                        help,
                         &self.theme,
                     );
                 }
             }
-
-            let mut pixel_data = pixmap.take();
-            render::convert_rgba_to_argb8888(&mut pixel_data);
-            let len = canvas.len().min(pixel_data.len());
-            canvas[..len].copy_from_slice(&pixel_data[..len]);
-        } else {
-            canvas.fill(0);
+            OverlayPhase::Full => {
+                let rows: Vec<HintRow<'_>> = self
+                    .windows
+                    .iter()
+                    .zip(self.hints.iter())
+                    .map(|(w, h)| HintRow {
+                        hint: h.as_str(),
+                        app_id: &w.app_id,
+                        title: &w.title,
+                        position: w.position,
+                        pinned: w.pinned,
+                        nested: w.nested,
+                        sticky: w.sticky,
+                        keep_above: w.keep_above,
+                        minimized: w.minimized,
+                        label: w.label.as_deref(),
+                        icon: w.icon.as_deref(),
+                        focused_ago: w.focused_ago.as_deref(),
+                        geometry: w.geometry.filter(|_| on_windows),
+                    })
+                    .collect();
+                let notice = self
+                    .palette_message
+                    .clone()
+                    .or_else(|| {
+                        self.staged_launch
+                            .as_ref()
+                            .map(|(command, instances)| match instances {
+                                1 => format!("Launch {command}"),
+                                n => format!("Launch {command} \u{00d7}{n}"),
+                            })
+                    })
+                    .or_else(|| self.retry_notice.clone());
+                render::draw_full_overlay(
+                    pixmap,
+                    &mut self.font_system,
+                    &mut self.swash_cache,
+                    &mut self.scratch,
+                    wf,
+                    hf,
+                    scale,
+                    &rows,
+                    &self.input_buffer,
+                    self.selection,
+                    &self.hints,
+                    &self.theme,
+                    self.show_app_id,
+                    self.show_title,
+                    notice.as_deref(),
+                    frame.title_tooltip,
+                    self.row_menu.as_ref(),
+                );
+            }
+            OverlayPhase::Launching => {
+                render::draw_status_toast(
+                    pixmap,
+                    &mut self.font_system,
+                    &mut self.swash_cache,
+                    wf,
+                    hf,
+                    scale,
+                    "Launching\u{2026}",
+                    &self.theme,
+                );
+            }
+            OverlayPhase::LaunchError => {
+                render::draw_error_toast(
+                    pixmap,
+                    &mut self.font_system,
+                    &mut self.swash_cache,
+                    wf,
+                    hf,
+                    scale,
+                    &self.error_message,
+                    &self.theme,
+                );
+            }
+            OverlayPhase::UnlockPrompt => {
+                let error = if self.error_message.is_empty() {
+                    None
+                } else {
+                    Some(self.error_message.as_str())
+                };
+                render::draw_unlock_prompt(
+                    pixmap,
+                    &mut self.font_system,
+                    &mut self.swash_cache,
+                    wf,
+                    hf,
+                    scale,
+                    &self.unlock_profile,
+                    self.unlock_password_len,
+                    error,
+                    &self.theme,
+                );
+            }
+            OverlayPhase::UnlockProgress => {
+                render::draw_status_toast(
+                    pixmap,
+                    &mut self.font_system,
+                    &mut self.swash_cache,
+                    wf,
+                    hf,
+                    scale,
+                    &self.unlock_message,
+                    &self.theme,
+                );
+            }
         }
-
-        // Attach buffer and commit.
-        if let Some(ref surface) = self.layer_surface {
-            let wl_surface = surface.wl_surface();
-            buffer
-                .attach_to(wl_surface)
-                .expect("failed to attach buffer");
-            wl_surface.set_buffer_scale(scale.ceil() as i32);
-            wl_surface.damage_buffer(0, 0, width as i32, height as i32);
-            wl_surface.commit();
+        if let Some(message) = frame.toast {
+            render::draw_toast(
+                pixmap,
+                &mut self.font_system,
+                &mut self.swash_cache,
+                wf,
+                scale,
+                message,
+                &self.theme,
+            );
         }
+    }
 
-        // Set blur region AFTER buffer commit (pool borrow is dropped).
-        if let Some((cx, cy, cw, ch)) = blur_rect {
-            self.set_blur_region(cx as i32, cy as i32, cw as i32, ch as i32);
+    /// Draw the full picker for `windows` into a throwaway pixmap.
+    ///
+    /// Shaping loads the font files the picker uses and fills the glyph
+    /// cache, which is most of the cost of the first full frame. Nothing is
+    /// attached to the surface. Before the surface is first configured a
+    /// 1080p output is assumed.
+    pub fn render_offscreen(&mut self, windows: &[WindowInfo], hints: &[String]) {
+        let started = std::time::Instant::now();
+        if self.draw_picker_offscreen(windows, hints).is_some() {
+            tracing::debug!(
+                rows = windows.len(),
+                elapsed_us = started.elapsed().as_micros() as u64,
+                "overlay warm-up frame rendered offscreen"
+            );
         }
     }
 
+    /// Draw the picker offscreen with `theme` and encode the card, plus a
+    /// margin of the dimmed backdrop, as a PNG. The current theme is
+    /// restored afterwards. Returns an empty buffer if nothing was drawn.
+    #[cfg(feature = "thumbnails")]
+    pub fn render_preview(
+        &mut self,
+        theme: render::OverlayTheme,
+        windows: &[WindowInfo],
+        hints: &[String],
+    ) -> Vec<u8> {
+        let saved = std::mem::replace(&mut self.theme, theme);
+        let frame = self.draw_picker_offscreen(windows, hints);
+        self.theme = saved;
+        let Some(pixmap) = frame else {
+            return Vec::new();
+        };
+
+        let scale = self.output_scale;
+        let (x, y, w, h) = render::compute_card_rect(
+            windows.len(),
+            pixmap.width() as f32,
+            pixmap.height() as f32,
+            scale,
+            &self.theme,
+            self.show_app_id,
+            self.show_title,
+        );
+        let margin = PREVIEW_MARGIN * scale;
+        let crop = tiny_skia::IntRect::from_xywh(
+            (x - margin).max(0.0) as i32,
+            (y - margin).max(0.0) as i32,
+            ((w + margin * 2.0) as u32).min(pixmap.width()),
+            ((h + margin * 2.0) as u32).min(pixmap.height()),
+        );
+        let card = crop.and_then(|rect| pixmap.clone_rect(rect));
+        render::png::encode(card.as_ref().unwrap_or(&pixmap))
+    }
+
+    /// Draw the full picker for `windows` into a new pixmap the size of the
+    /// output (or [`WARMUP_FALLBACK_SIZE`] before the first configure).
+    fn draw_picker_offscreen(
+        &mut self,
+        windows: &[WindowInfo],
+        hints: &[String],
+    ) -> Option<tiny_skia::Pixmap> {
+        let (logical_w, logical_h) = match self.configured_size {
+            (0, _) | (_, 0) => WARMUP_FALLBACK_SIZE,
+            size => size,
+        };
+        let scale = self.output_scale;
+        let width = (logical_w as f32 * scale) as u32;
+        let height = (logical_h as f32 * scale) as u32;
+        let mut pixmap = tiny_skia::Pixmap::new(width, height)?;
+        let rows: Vec<HintRow<'_>> = windows
+            .iter()
+            .zip(hints)
+            .map(|(w, h)| HintRow {
+                hint: h.as_str(),
+                app_id: &w.app_id,
+                title: &w.title,
+                position: w.position,
+                pinned: w.pinned,
+                nested: w.nested,
+                sticky: w.sticky,
+                keep_above: w.keep_above,
+                minimized: w.minimized,
+                label: w.label.as_deref(),
+                icon: w.icon.as_deref(),
+                focused_ago: w.focused_ago.as_deref(),
+                geometry: None,
+            })
+            .collect();
+        render::draw_full_overlay(
+            &mut pixmap,
+            &mut self.font_system,
+            &mut self.swash_cache,
+            &mut self.scratch,
+            width as f32,
+            height as f32,
+            scale,
+            &rows,
+            "",
+            0,
+            hints,
+            &self.theme,
+            self.show_app_id,
+            self.show_title,
+            None,
+            false,
+            None,
+        );
+        Some(pixmap)
+    }
+
     /// Set the blur region on the compositor to cover the card area.
     fn set_blur_region(&self, x: i32, y: i32, w: i32, h: i32) {
         let Some(ref blur_surface) = self.blur_surface else {
@@ -0,0 +1,118 @@
+//! `zwp_text_input_v3` client binding so input methods do not swallow hint keys.
+//!
+//! With fcitx5 or ibus running, the compositor can route key presses through
+//! the input method, which composes them instead of delivering plain keysyms
+//! — typing `f` in a pinyin layout shows a candidate list rather than
+//! selecting the Firefox hint. When the compositor gives the overlay
+//! text-input focus (`enter`), an input method may be active, so the overlay
+//! enables text input with the `password` purpose and `sensitive_data |
+//! latin` hints. Input methods pass keys straight through for password
+//! fields, so hint letters keep arriving on `wl_keyboard`. Anything the
+//! input method still commits arrives as `commit_string` and is forwarded
+//! as typed characters when the batch is applied (`done`).
+
+use smithay_client_toolkit::globals::GlobalData;
+use wayland_client::globals::{BindError, GlobalList};
+use wayland_client::protocol::wl_seat::WlSeat;
+use wayland_client::{Connection, Dispatch, Proxy, QueueHandle, delegate_dispatch};
+use wayland_protocols::wp::text_input::zv3::client::zwp_text_input_manager_v3::ZwpTextInputManagerV3;
+use wayland_protocols::wp::text_input::zv3::client::zwp_text_input_v3::{
+    ContentHint, ContentPurpose, Event, ZwpTextInputV3,
+};
+
+use super::OverlayEvent;
+use super::app::OverlayApp;
+
+/// Handler for the text-input manager global.
+#[derive(Debug, Clone)]
+pub struct TextInputManager {
+    pub manager: ZwpTextInputManagerV3,
+}
+
+impl TextInputManager {
+    /// Bind the text-input manager global. Returns Err if compositor doesn't advertise it.
+    pub(crate) fn new(
+        globals: &GlobalList,
+        qh: &QueueHandle<OverlayApp>,
+    ) -> Result<Self, BindError> {
+        let manager = globals.bind(qh, 1..=1, GlobalData)?;
+        Ok(Self { manager })
+    }
+
+    /// Create the text input object for a seat.
+    pub(crate) fn get_text_input(
+        &self,
+        seat: &WlSeat,
+        qh: &QueueHandle<OverlayApp>,
+    ) -> ZwpTextInputV3 {
+        self.manager.get_text_input(seat, qh, ())
+    }
+}
+
+/// Characters from a committed string that the overlay accepts as input,
+/// matching what `press_key` accepts from `wl_keyboard`.
+fn committed_chars(text: &str) -> impl Iterator<Item = char> + '_ {
+    text.chars()
+        .filter(|c| *c == ' ' || crate::hints::is_input_char(*c))
+}
+
+// Dispatch for the manager — no events defined.
+impl Dispatch<ZwpTextInputManagerV3, GlobalData, OverlayApp> for TextInputManager {
+    fn event(
+        _state: &mut OverlayApp,
+        _proxy: &ZwpTextInputManagerV3,
+        _event: <ZwpTextInputManagerV3 as Proxy>::Event,
+        _data: &GlobalData,
+        _conn: &Connection,
+        _qh: &QueueHandle<OverlayApp>,
+    ) {
+        // No events on this interface.
+    }
+}
+
+impl Dispatch<ZwpTextInputV3, (), OverlayApp> for TextInputManager {
+    fn event(
+        state: &mut OverlayApp,
+        proxy: &ZwpTextInputV3,
+        event: <ZwpTextInputV3 as Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qh: &QueueHandle<OverlayApp>,
+    ) {
+        match event {
+            Event::Enter { .. } => {
+                tracing::debug!("text-input focus entered overlay, requesting direct key input");
+                proxy.enable();
+                proxy.set_content_type(
+                    ContentHint::SensitiveData | ContentHint::Latin,
+                    ContentPurpose::Password,
+                );
+                proxy.commit();
+            }
+            Event::Leave { .. } => {
+                state.pending_commit = None;
+                proxy.disable();
+                proxy.commit();
+            }
+            Event::CommitString { text } => {
+                state.pending_commit = text;
+            }
+            Event::Done { .. } => {
+                let Some(text) = state.pending_commit.take() else {
+                    return;
+                };
+                state.received_key_event = true;
+                for ch in committed_chars(&text) {
+                    state.send_event(OverlayEvent::KeyChar(ch));
+                }
+            }
+            // Pre-edit text is not shown: the overlay only acts on committed
+            // characters. It never sets surrounding text, so there is nothing
+            // to delete.
+            _ => {}
+        }
+    }
+}
+
+delegate_dispatch!(OverlayApp: [ZwpTextInputManagerV3: GlobalData] => TextInputManager);
+delegate_dispatch!(OverlayApp: [ZwpTextInputV3: ()] => TextInputManager);
@@ -0,0 +1,642 @@
+//! Stable fixtures for tools built on this crate (feature `test-util`).
+//!
+//! Status bar applets, plugins and other code embedding the switcher can
+//! test against the same windows, hints, config and compositor the daemon's
+//! own tests use, without a Wayland display or a running bus:
+//!
+//! - [`WindowBuilder`] — a [`Window`] with fresh IDs and sensible defaults.
+//! - [`WindowHint`] / [`hint_windows`] — the hints the overlay would show,
+//!   assigned by the daemon's own [`hints`](crate::hints) rules.
+//! - [`ConfigBuilder`] — a [`WmConfig`], or a full [`Config`] carrying it.
+//! - [`MockWindowManager`] — a scripted compositor backend that records
+//!   every call (Linux only).
+//!
+//! ```
+//! use daemon_wm::testing::{ConfigBuilder, WindowBuilder, hint_windows};
+//!
+//! let config = ConfigBuilder::new().bind('f', &["firefox"], None).wm();
+//! let windows = [
+//!     WindowBuilder::new("firefox").title("Inbox").focused().build(),
+//!     WindowBuilder::new("firefox").title("Docs").build(),
+//! ];
+//! let hints: Vec<String> = hint_windows(&windows, &config)
+//!     .into_iter()
+//!     .map(|h| h.hint)
+//!     .collect();
+//! assert_eq!(hints, ["f", "ff"]);
+//! ```
+
+use core_config::{Config, ProfileConfig, WmConfig, WmKeyBinding};
+use core_types::{AppId, CompositorWorkspaceId, Geometry, MonitorId, ProfileId, Window, WindowId};
+
+use crate::hints;
+
+/// Builds a [`Window`]. Unset fields get fresh IDs, an 800×600 geometry at
+/// the origin, the app ID as title, and no states.
+#[derive(Debug, Clone)]
+pub struct WindowBuilder {
+    window: Window,
+}
+
+impl WindowBuilder {
+    #[must_use]
+    pub fn new(app_id: &str) -> Self {
+        Self {
+            window: Window {
+                id: WindowId::new(),
+                app_id: AppId::new(app_id),
+                title: app_id.to_string(),
+                workspace_id: CompositorWorkspaceId::new(),
+                monitor_id: MonitorId::new(),
+                geometry: Geometry {
+                    x: 0,
+                    y: 0,
+                    width: 800,
+                    height: 600,
+                },
+                is_focused: false,
+                is_minimized: false,
+                is_fullscreen: false,
+                is_maximized: false,
+                profile_id: ProfileId::new(),
+                parent: None,
+                is_sticky: false,
+                is_keep_above: false,
+            },
+        }
+    }
+
+    #[must_use]
+    pub fn id(mut self, id: WindowId) -> Self {
+        self.window.id = id;
+        self
+    }
+
+    #[must_use]
+    pub fn title(mut self, title: &str) -> Self {
+        self.window.title = title.to_string();
+        self
+    }
+
+    #[must_use]
+    pub fn workspace(mut self, workspace: CompositorWorkspaceId) -> Self {
+        self.window.workspace_id = workspace;
+        self
+    }
+
+    #[must_use]
+    pub fn monitor(mut self, monitor: MonitorId) -> Self {
+        self.window.monitor_id = monitor;
+        self
+    }
+
+    #[must_use]
+    pub fn geometry(mut self, geometry: Geometry) -> Self {
+        self.window.geometry = geometry;
+        self
+    }
+
+    /// Transient for `parent`, like a dialog.
+    #[must_use]
+    pub fn parent(mut self, parent: WindowId) -> Self {
+        self.window.parent = Some(parent);
+        self
+    }
+
+    #[must_use]
+    pub fn focused(mut self) -> Self {
+        self.window.is_focused = true;
+        self
+    }
+
+    #[must_use]
+    pub fn minimized(mut self) -> Self {
+        self.window.is_minimized = true;
+        self
+    }
+
+    #[must_use]
+    pub fn fullscreen(mut self) -> Self {
+        self.window.is_fullscreen = true;
+        self
+    }
+
+    #[must_use]
+    pub fn maximized(mut self) -> Self {
+        self.window.is_maximized = true;
+        self
+    }
+
+    #[must_use]
+    pub fn sticky(mut self) -> Self {
+        self.window.is_sticky = true;
+        self
+    }
+
+    #[must_use]
+    pub fn keep_above(mut self) -> Self {
+        self.window.is_keep_above = true;
+        self
+    }
+
+    #[must_use]
+    pub fn build(self) -> Window {
+        self.window
+    }
+}
+
+/// A window paired with the hint the overlay shows for it.
+#[derive(Debug, Clone)]
+pub struct WindowHint {
+    pub hint: String,
+    pub window: Window,
+}
+
+impl WindowHint {
+    /// A hand-picked hint, for fixtures that don't follow the assignment rules.
+    #[must_use]
+    pub fn new(hint: &str, window: Window) -> Self {
+        Self {
+            hint: hint.to_string(),
+            window,
+        }
+    }
+}
+
+/// Hints for `windows` (MRU order) as the overlay assigns them with
+/// `config`'s key bindings, in window order.
+#[must_use]
+pub fn hint_windows(windows: &[Window], config: &WmConfig) -> Vec<WindowHint> {
+    let app_ids: Vec<&str> = windows.iter().map(|w| w.app_id.as_str()).collect();
+    hints::assign_app_hints(&app_ids, &config.key_bindings)
+        .into_iter()
+        .map(|(hint, index)| WindowHint {
+            hint,
+            window: windows[index].clone(),
+        })
+        .collect()
+}
+
+/// Builds a [`WmConfig`] starting from the defaults, or a [`Config`] whose
+/// default profile carries it.
+#[derive(Debug, Clone, Default)]
+pub struct ConfigBuilder {
+    wm: WmConfig,
+}
+
+impl ConfigBuilder {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    pub fn hint_keys(mut self, keys: &str) -> Self {
+        self.wm.hint_keys = keys.to_string();
+        self
+    }
+
+    #[must_use]
+    pub fn quick_switch_threshold_ms(mut self, ms: u32) -> Self {
+        self.wm.quick_switch_threshold_ms = ms;
+        self
+    }
+
+    #[must_use]
+    pub fn overlay_delay_ms(mut self, ms: u32) -> Self {
+        self.wm.overlay_delay_ms = ms;
+        self
+    }
+
+    #[must_use]
+    pub fn activation_delay_ms(mut self, ms: u32) -> Self {
+        self.wm.activation_delay_ms = ms;
+        self
+    }
+
+    /// Bind `key` to `apps`, optionally launching `launch` when none is open.
+    #[must_use]
+    pub fn bind(mut self, key: char, apps: &[&str], launch: Option<&str>) -> Self {
+        self.wm.key_bindings.insert(
+            key.to_string(),
+            WmKeyBinding {
+                apps: apps.iter().map(|app| (*app).to_string()).collect(),
+                launch: launch.map(String::from),
+                ..Default::default()
+            },
+        );
+        self
+    }
+
+    /// Adjust any other field.
+    #[must_use]
+    pub fn with(mut self, f: impl FnOnce(&mut WmConfig)) -> Self {
+        f(&mut self.wm);
+        self
+    }
+
+    #[must_use]
+    pub fn wm(self) -> WmConfig {
+        self.wm
+    }
+
+    /// A config with one profile, the default, using the built window settings.
+    #[must_use]
+    pub fn build(self) -> Config {
+        let profile = ProfileConfig {
+            wm: self.wm,
+            ..ProfileConfig::default()
+        };
+        let mut config = Config::default();
+        config
+            .profiles
+            .insert(core_types::DEFAULT_PROFILE_NAME.to_string(), profile);
+        config
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub use mock::{MockCall, MockWindowManager};
+
+#[cfg(target_os = "linux")]
+mod mock {
+    use core_types::{
+        CompositorWorkspaceId, Geometry, MonitorId, Output, Window, WindowId, WindowPlacement,
+        WindowToggle, Workspace,
+    };
+    use platform_linux::compositor::{Capabilities, CompositorBackend, MockBackend, WindowChange};
+    use std::collections::VecDeque;
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::sync::Mutex;
+
+    type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+    /// A compositor call recorded by [`MockWindowManager`].
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum MockCall {
+        ListWindows,
+        Activate(WindowId),
+        Focus(WindowId),
+        Close(WindowId),
+        /// Any other [`CompositorBackend`] method, by name.
+        Other(&'static str),
+    }
+
+    /// Scripted in-memory compositor.
+    ///
+    /// Behaves like `platform_linux::compositor::MockBackend` — activation
+    /// moves focus, closing removes the window — and additionally records
+    /// every call, fails calls on demand, and can change the window list
+    /// between calls to `list_windows`.
+    pub struct MockWindowManager {
+        backend: MockBackend,
+        calls: Mutex<Vec<MockCall>>,
+        /// Window lists handed out by successive `list_windows` calls.
+        snapshots: Mutex<VecDeque<Vec<Window>>>,
+        /// Names of methods whose next call fails.
+        failures: Mutex<Vec<&'static str>>,
+    }
+
+    impl MockWindowManager {
+        /// A compositor listing `windows` (MRU order) that supports everything.
+        #[must_use]
+        pub fn new(windows: Vec<Window>) -> Self {
+            Self::with_capabilities(
+                windows,
+                Capabilities {
+                    backend: "mock",
+                    can_activate: true,
+                    can_close: true,
+                    can_maximize: true,
+                    can_tile: true,
+                    can_stick: true,
+                    can_keep_above: true,
+                    has_workspace_info: true,
+                    can_switch_workspace: true,
+                    can_move_to_output: true,
+                    ..Capabilities::default()
+                },
+            )
+        }
+
+        /// A compositor listing `windows` that supports only `capabilities`.
+        #[must_use]
+        pub fn with_capabilities(windows: Vec<Window>, capabilities: Capabilities) -> Self {
+            Self {
+                backend: MockBackend::new(windows, capabilities),
+                calls: Mutex::new(Vec::new()),
+                snapshots: Mutex::new(VecDeque::new()),
+                failures: Mutex::new(Vec::new()),
+            }
+        }
+
+        /// Also list `workspaces`.
+        #[must_use]
+        pub fn with_workspaces(self, workspaces: Vec<Workspace>) -> Self {
+            Self {
+                backend: self.backend.with_workspaces(workspaces),
+                ..self
+            }
+        }
+
+        /// Also list `outputs`.
+        #[must_use]
+        pub fn with_outputs(self, outputs: Vec<Output>) -> Self {
+            Self {
+                backend: self.backend.with_outputs(outputs),
+                ..self
+            }
+        }
+
+        /// Make the next `list_windows` call see `windows`, as if the
+        /// compositor had changed in between. Queued lists are used in order,
+        /// one per call; later calls keep seeing the last one.
+        pub fn then_windows(&self, windows: Vec<Window>) {
+            lock(&self.snapshots).push_back(windows);
+        }
+
+        /// Make the next call to `method` (a [`CompositorBackend`] method
+        /// name such as `"activate_window"`) fail.
+        pub fn fail_next(&self, method: &'static str) {
+            lock(&self.failures).push(method);
+        }
+
+        /// Every call so far, oldest first.
+        #[must_use]
+        pub fn calls(&self) -> Vec<MockCall> {
+            lock(&self.calls).clone()
+        }
+
+        /// Windows passed to `activate_window` or `focus_window`, oldest first.
+        #[must_use]
+        pub fn activations(&self) -> Vec<WindowId> {
+            lock(&self.calls)
+                .iter()
+                .filter_map(|call| match call {
+                    MockCall::Activate(id) | MockCall::Focus(id) => Some(*id),
+                    _ => None,
+                })
+                .collect()
+        }
+
+        /// Record `call` and take a scripted failure for `method`, if any.
+        fn enter(&self, method: &'static str, call: MockCall) -> Option<core_types::Error> {
+            lock(&self.calls).push(call);
+            let mut failures = lock(&self.failures);
+            let pos = failures.iter().position(|m| *m == method)?;
+            failures.remove(pos);
+            Some(core_types::Error::Platform(format!(
+                "mock compositor: scripted failure of {method}"
+            )))
+        }
+
+        fn run<'a, T: Send + 'a>(
+            &'a self,
+            method: &'static str,
+            call: MockCall,
+            inner: impl FnOnce() -> BoxFuture<'a, core_types::Result<T>>,
+        ) -> BoxFuture<'a, core_types::Result<T>> {
+            match self.enter(method, call) {
+                Some(e) => Box::pin(async move { Err(e) }),
+                None => inner(),
+            }
+        }
+    }
+
+    /// Lock, ignoring poisoning: a panicking test already failed.
+    fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+        mutex
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    impl CompositorBackend for MockWindowManager {
+        fn list_windows(&self) -> BoxFuture<'_, core_types::Result<Vec<Window>>> {
+            self.run("list_windows", MockCall::ListWindows, || {
+                if let Some(windows) = lock(&self.snapshots).pop_front() {
+                    self.backend.set_windows(windows);
+                }
+                self.backend.list_windows()
+            })
+        }
+
+        fn list_workspaces(&self) -> BoxFuture<'_, core_types::Result<Vec<Workspace>>> {
+            self.run(
+                "list_workspaces",
+                MockCall::Other("list_workspaces"),
+                || self.backend.list_workspaces(),
+            )
+        }
+
+        fn activate_window(&self, id: &WindowId) -> BoxFuture<'_, core_types::Result<()>> {
+            self.run("activate_window", MockCall::Activate(*id), || {
+                self.backend.activate_window(id)
+            })
+        }
+
+        fn set_window_geometry(
+            &self,
+            id: &WindowId,
+            geom: &Geometry,
+        ) -> BoxFuture<'_, core_types::Result<()>> {
+            self.run(
+                "set_window_geometry",
+                MockCall::Other("set_window_geometry"),
+                || self.backend.set_window_geometry(id, geom),
+            )
+        }
+
+        fn move_to_workspace(
+            &self,
+            id: &WindowId,
+            ws: &CompositorWorkspaceId,
+        ) -> BoxFuture<'_, core_types::Result<()>> {
+            self.run(
+                "move_to_workspace",
+                MockCall::Other("move_to_workspace"),
+                || self.backend.move_to_workspace(id, ws),
+            )
+        }
+
+        fn activate_workspace(
+            &self,
+            id: &CompositorWorkspaceId,
+        ) -> BoxFuture<'_, core_types::Result<()>> {
+            self.run(
+                "activate_workspace",
+                MockCall::Other("activate_workspace"),
+                || self.backend.activate_workspace(id),
+            )
+        }
+
+        fn list_outputs(&self) -> BoxFuture<'_, core_types::Result<Vec<Output>>> {
+            self.run("list_outputs", MockCall::Other("list_outputs"), || {
+                self.backend.list_outputs()
+            })
+        }
+
+        fn move_to_output(
+            &self,
+            id: &WindowId,
+            output: &MonitorId,
+        ) -> BoxFuture<'_, core_types::Result<()>> {
+            self.run("move_to_output", MockCall::Other("move_to_output"), || {
+                self.backend.move_to_output(id, output)
+            })
+        }
+
+        fn focus_window(&self, id: &WindowId) -> BoxFuture<'_, core_types::Result<()>> {
+            self.run("focus_window", MockCall::Focus(*id), || {
+                self.backend.focus_window(id)
+            })
+        }
+
+        fn close_window(&self, id: &WindowId) -> BoxFuture<'_, core_types::Result<()>> {
+            self.run("close_window", MockCall::Close(*id), || {
+                self.backend.close_window(id)
+            })
+        }
+
+        fn place_window(
+            &self,
+            id: &WindowId,
+            placement: WindowPlacement,
+        ) -> BoxFuture<'_, core_types::Result<()>> {
+            self.run("place_window", MockCall::Other("place_window"), || {
+                self.backend.place_window(id, placement)
+            })
+        }
+
+        fn set_window_toggle(
+            &self,
+            id: &WindowId,
+            toggle: WindowToggle,
+            on: bool,
+        ) -> BoxFuture<'_, core_types::Result<()>> {
+            self.run(
+                "set_window_toggle",
+                MockCall::Other("set_window_toggle"),
+                || self.backend.set_window_toggle(id, toggle, on),
+            )
+        }
+
+        fn subscribe_changes(&self) -> tokio::sync::broadcast::Receiver<WindowChange> {
+            self.backend.subscribe_changes()
+        }
+
+        fn capabilities(&self) -> Capabilities {
+            self.backend.capabilities()
+        }
+
+        fn name(&self) -> &str {
+            "mock"
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builders_fill_what_is_not_set() {
+        let window = WindowBuilder::new("firefox").focused().sticky().build();
+        assert_eq!(window.title, "firefox");
+        assert!(window.is_focused && window.is_sticky && !window.is_minimized);
+        assert_ne!(window.id, WindowBuilder::new("firefox").build().id);
+
+        let config = ConfigBuilder::new()
+            .hint_keys("jkl")
+            .bind('g', &["com.mitchellh.ghostty"], Some("ghostty"))
+            .build();
+        let wm = &config.profiles[core_types::DEFAULT_PROFILE_NAME].wm;
+        assert_eq!(wm.hint_keys, "jkl");
+        assert_eq!(wm.key_bindings["g"].launch.as_deref(), Some("ghostty"));
+        assert_eq!(
+            wm.overlay_delay_ms,
+            WmConfig::default().overlay_delay_ms,
+            "unset fields keep their defaults"
+        );
+    }
+
+    #[test]
+    fn hints_follow_the_daemon_rules() {
+        let config = ConfigBuilder::new()
+            .bind('g', &["com.mitchellh.ghostty"], None)
+            .wm();
+        let windows = [
+            WindowBuilder::new("com.mitchellh.ghostty").build(),
+            WindowBuilder::new("firefox").build(),
+            WindowBuilder::new("com.mitchellh.ghostty").build(),
+        ];
+        let hints = hint_windows(&windows, &config);
+        let pairs: Vec<(&str, &str)> = hints
+            .iter()
+            .map(|h| (h.hint.as_str(), h.window.app_id.as_str()))
+            .collect();
+        assert_eq!(
+            pairs,
+            [
+                ("g", "com.mitchellh.ghostty"),
+                ("f", "firefox"),
+                ("gg", "com.mitchellh.ghostty"),
+            ]
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn mock_window_manager_restores_a_minimized_target() {
+        use platform_linux::compositor::CompositorBackend;
+
+        let firefox = WindowBuilder::new("firefox").focused().build();
+        let foot = WindowBuilder::new("foot").minimized().build();
+        let wm = MockWindowManager::new(vec![firefox.clone(), foot.clone()]);
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+
+        rt.block_on(wm.activate_window(&foot.id)).unwrap();
+        let listed = rt.block_on(wm.list_windows()).unwrap();
+        let foot = listed.iter().find(|w| w.id == foot.id).unwrap();
+        assert!(foot.is_focused && !foot.is_minimized);
+        let firefox = listed.iter().find(|w| w.id == firefox.id).unwrap();
+        assert!(!firefox.is_focused && !firefox.is_minimized);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn mock_window_manager_follows_its_script() {
+        use platform_linux::compositor::CompositorBackend;
+
+        let firefox = WindowBuilder::new("firefox").focused().build();
+        let foot = WindowBuilder::new("foot").build();
+        let wm = MockWindowManager::new(vec![firefox.clone(), foot.clone()]);
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+
+        wm.fail_next("activate_window");
+        assert!(rt.block_on(wm.activate_window(&foot.id)).is_err());
+        rt.block_on(wm.activate_window(&foot.id)).unwrap();
+        let listed = rt.block_on(wm.list_windows()).unwrap();
+        assert!(listed.iter().any(|w| w.id == foot.id && w.is_focused));
+
+        wm.then_windows(vec![firefox.clone()]);
+        assert_eq!(rt.block_on(wm.list_windows()).unwrap().len(), 1);
+        assert_eq!(rt.block_on(wm.list_windows()).unwrap().len(), 1);
+
+        assert_eq!(wm.activations(), [foot.id, foot.id]);
+        assert_eq!(
+            wm.calls(),
+            [
+                MockCall::Activate(foot.id),
+                MockCall::Activate(foot.id),
+                MockCall::ListWindows,
+                MockCall::ListWindows,
+                MockCall::ListWindows,
+            ]
+        );
+    }
+}
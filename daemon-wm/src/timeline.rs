@@ -0,0 +1,52 @@
+//! Focus timeline view (Ctrl+R in the picker).
+//!
+//! The picker lists windows in MRU order with the origin last, which puts
+//! the previous window one key away but says nothing about when the others
+//! were used. The timeline lists the same rows by when each was last
+//! focused, newest first, with the time beside the title ("2 min ago"), so
+//! "the window I was on ten minutes ago" can be found by when rather than
+//! by counting rows. Focus times come from the MRU file.
+
+/// Row order for the timeline: indices into `focused_at`, most recently
+/// focused first. Rows with no recorded time keep their order at the end,
+/// and rows with the same time (multiplexer rows and their terminal) stay
+/// together.
+#[must_use]
+pub fn order(focused_at: &[Option<u64>]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..focused_at.len()).collect();
+    order.sort_by_key(|&i| std::cmp::Reverse(focused_at[i].map_or(0, |at| at + 1)));
+    order
+}
+
+/// How long ago a window was focused, `secs` seconds before now.
+#[must_use]
+pub fn relative_time(secs: u64) -> String {
+    match secs {
+        0..60 => "just now".into(),
+        60..3600 => format!("{} min ago", secs / 60),
+        3600..86_400 => format!("{} h ago", secs / 3600),
+        _ => format!("{} d ago", secs / 86_400),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn newest_first_and_unknown_last() {
+        let focused_at = [Some(100), None, Some(300), Some(300), None, Some(0)];
+        assert_eq!(order(&focused_at), [2, 3, 0, 5, 1, 4]);
+        assert!(order(&[]).is_empty());
+    }
+
+    #[test]
+    fn relative_times() {
+        assert_eq!(relative_time(0), "just now");
+        assert_eq!(relative_time(59), "just now");
+        assert_eq!(relative_time(125), "2 min ago");
+        assert_eq!(relative_time(3599), "59 min ago");
+        assert_eq!(relative_time(7200), "2 h ago");
+        assert_eq!(relative_time(3 * 86_400 + 5), "3 d ago");
+    }
+}
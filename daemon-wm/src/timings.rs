@@ -0,0 +1,136 @@
+//! Overlay activation latency instrumentation.
+//!
+//! Each fresh activation starts an `ActivationTimer` that records monotonic
+//! offsets for the pipeline stages (windows enumerated → first frame
+//! committed → input ready). The timer owns an `overlay_activation` tracing
+//! span whose fields are filled in as stages complete; the span closes when
+//! the next activation replaces the timer, so log exporters see one record
+//! per activation. `sesame wm overlay --timings` reads the same numbers over
+//! IPC via `WmTimingsRequest`.
+
+use core_types::{OVERLAY_FIRST_FRAME_BUDGET, OverlayTimings};
+use std::time::{Duration, Instant};
+
+/// Instrumented pipeline stage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    WindowsEnumerated,
+    FirstFrame,
+    InputReady,
+}
+
+impl Stage {
+    fn field(self) -> &'static str {
+        match self {
+            Self::WindowsEnumerated => "windows_enumerated_us",
+            Self::FirstFrame => "first_frame_us",
+            Self::InputReady => "input_ready_us",
+        }
+    }
+}
+
+/// Timings for one overlay activation.
+#[derive(Debug)]
+pub struct ActivationTimer {
+    started: Instant,
+    timings: OverlayTimings,
+    span: tracing::Span,
+}
+
+impl ActivationTimer {
+    /// Start timing an activation requested at `started`.
+    ///
+    /// `previous` is the timer being replaced (its sequence is continued);
+    /// `daemon_startup` is the daemon's own process start → ready time.
+    #[must_use]
+    pub fn start(
+        started: Instant,
+        previous: Option<&Self>,
+        daemon_startup: Duration,
+        mode: &'static str,
+    ) -> Self {
+        let sequence = previous.map_or(0, |p| p.timings.sequence) + 1;
+        let span = tracing::info_span!(
+            "overlay_activation",
+            sequence,
+            mode,
+            windows_enumerated_us = tracing::field::Empty,
+            first_frame_us = tracing::field::Empty,
+            input_ready_us = tracing::field::Empty,
+        );
+        Self {
+            started,
+            timings: OverlayTimings {
+                sequence,
+                daemon_startup_us: duration_us(daemon_startup),
+                ..OverlayTimings::default()
+            },
+            span,
+        }
+    }
+
+    /// Record that `stage` completed at `at`. Only the first occurrence of
+    /// each stage per activation is kept.
+    pub fn mark(&mut self, stage: Stage, at: Instant) {
+        let elapsed = at.saturating_duration_since(self.started);
+        let slot = match stage {
+            Stage::WindowsEnumerated => &mut self.timings.windows_enumerated_us,
+            Stage::FirstFrame => &mut self.timings.first_frame_us,
+            Stage::InputReady => &mut self.timings.input_ready_us,
+        };
+        if slot.is_some() {
+            return;
+        }
+        let us = duration_us(elapsed);
+        *slot = Some(us);
+        self.span.record(stage.field(), us);
+
+        if stage == Stage::FirstFrame && elapsed > OVERLAY_FIRST_FRAME_BUDGET {
+            tracing::warn!(
+                parent: &self.span,
+                first_frame_ms = elapsed.as_millis() as u64,
+                budget_ms = OVERLAY_FIRST_FRAME_BUDGET.as_millis() as u64,
+                "overlay first frame over budget"
+            );
+        }
+    }
+
+    #[must_use]
+    pub fn timings(&self) -> &OverlayTimings {
+        &self.timings
+    }
+}
+
+fn duration_us(d: Duration) -> u64 {
+    u64::try_from(d.as_micros()).unwrap_or(u64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn marks_record_first_occurrence_only() {
+        let t0 = Instant::now();
+        let mut timer = ActivationTimer::start(t0, None, Duration::from_millis(3), "overlay");
+        timer.mark(Stage::WindowsEnumerated, t0 + Duration::from_millis(2));
+        timer.mark(Stage::FirstFrame, t0 + Duration::from_millis(12));
+        timer.mark(Stage::FirstFrame, t0 + Duration::from_millis(40));
+
+        let t = timer.timings();
+        assert_eq!(t.sequence, 1);
+        assert_eq!(t.daemon_startup_us, 3_000);
+        assert_eq!(t.windows_enumerated_us, Some(2_000));
+        assert_eq!(t.first_frame_us, Some(12_000));
+        assert_eq!(t.input_ready_us, None);
+    }
+
+    #[test]
+    fn sequence_continues_across_activations() {
+        let t0 = Instant::now();
+        let first = ActivationTimer::start(t0, None, Duration::ZERO, "overlay");
+        let second = ActivationTimer::start(t0, Some(&first), Duration::ZERO, "launcher");
+        assert_eq!(second.timings().sequence, 2);
+        assert_eq!(second.timings().first_frame_us, None);
+    }
+}
@@ -0,0 +1,103 @@
+//! Queue of short-lived banners for non-fatal problems.
+//!
+//! Problems the user would otherwise only find in the journal (a missing
+//! compositor protocol, a theme color that fell back to the default, the
+//! MRU order being unavailable, a window that could not be activated) are
+//! queued here and shown one at a time over the overlay. A toast's clock
+//! starts when it is first drawn, so a problem found while the overlay is
+//! hidden is still seen on the next activation.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// How long each toast stays on screen.
+pub const TOAST_DURATION: Duration = Duration::from_millis(2500);
+
+/// Queued toasts beyond this drop the oldest, so a burst of failures
+/// cannot keep the overlay busy for long.
+const MAX_QUEUED: usize = 4;
+
+#[derive(Debug, Default)]
+pub struct ToastQueue {
+    /// Messages in display order, with the time the front one was shown.
+    queue: VecDeque<(String, Option<Instant>)>,
+}
+
+impl ToastQueue {
+    /// Queue `message` unless the same text is already waiting.
+    pub fn push(&mut self, message: String) {
+        if self.queue.iter().any(|(m, _)| *m == message) {
+            return;
+        }
+        if self.queue.len() == MAX_QUEUED {
+            self.queue.pop_front();
+        }
+        self.queue.push_back((message, None));
+    }
+
+    /// The toast to draw at `now`, starting its clock on first display and
+    /// dropping any that have run their course.
+    pub fn show(&mut self, now: Instant) -> Option<&str> {
+        self.expire(now);
+        let (message, shown_at) = self.queue.front_mut()?;
+        shown_at.get_or_insert(now);
+        Some(message.as_str())
+    }
+
+    /// Whether the toast on screen ran out at `now` and a redraw is due.
+    #[must_use]
+    pub fn expired(&self, now: Instant) -> bool {
+        self.queue
+            .front()
+            .and_then(|(_, shown_at)| *shown_at)
+            .is_some_and(|t| now.duration_since(t) >= TOAST_DURATION)
+    }
+
+    fn expire(&mut self, now: Instant) {
+        while self.expired(now) {
+            self.queue.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn toasts_show_in_order_for_their_duration() {
+        let mut toasts = ToastQueue::default();
+        toasts.push("first".into());
+        toasts.push("second".into());
+        let start = Instant::now();
+
+        assert_eq!(toasts.show(start), Some("first"));
+        assert!(!toasts.expired(start + TOAST_DURATION / 2));
+        assert!(toasts.expired(start + TOAST_DURATION));
+
+        let later = start + TOAST_DURATION;
+        assert_eq!(toasts.show(later), Some("second"));
+        assert_eq!(toasts.show(later + TOAST_DURATION), None);
+    }
+
+    #[test]
+    fn clock_starts_on_first_display() {
+        let mut toasts = ToastQueue::default();
+        toasts.push("protocol missing".into());
+        // Queued long before the overlay is next shown.
+        let shown = Instant::now() + Duration::from_secs(60);
+        assert!(!toasts.expired(shown));
+        assert_eq!(toasts.show(shown), Some("protocol missing"));
+    }
+
+    #[test]
+    fn duplicates_and_overflow_are_dropped() {
+        let mut toasts = ToastQueue::default();
+        for i in 0..MAX_QUEUED + 2 {
+            toasts.push(format!("problem {i}"));
+            toasts.push(format!("problem {i}"));
+        }
+        assert_eq!(toasts.queue.len(), MAX_QUEUED);
+        assert_eq!(toasts.show(Instant::now()), Some("problem 2"));
+    }
+}
@@ -0,0 +1,263 @@
+//! Stable window ordering for repeated-letter hints.
+//!
+//! Hints for windows of the same app repeat their key (`f`, `ff`, `fff`).
+//! If repetitions followed the MRU display order, `ff` would name a
+//! different Firefox window every time focus moved between them — and
+//! compositors do not enumerate windows in a stable order either. Instead,
+//! each window keeps the position it was first seen at: the order is
+//! persisted in the state store (`window_order` table) and only changes
+//! when windows open (appended) or close (removed).
+//!
+//! Display order is MRU, except that windows of `pinned_apps` are listed
+//! first ([`pin_first`]) and dialogs can be listed below the window they
+//! belong to ([`nest_transients`]) or left out ([`hide_transients`]).
+
+use std::collections::HashMap;
+
+/// First-seen rank of each live window.
+#[derive(Debug, Default, Clone)]
+pub struct WindowOrder {
+    ranks: HashMap<String, usize>,
+}
+
+impl WindowOrder {
+    /// Rank of `id` (lower = seen earlier). Unknown IDs rank after every
+    /// tracked window.
+    #[must_use]
+    pub fn rank(&self, id: &str) -> usize {
+        self.ranks.get(id).copied().unwrap_or(usize::MAX)
+    }
+}
+
+/// Update the persisted order with the live window set and return it.
+///
+/// `live_ids` is in compositor order; windows seen for the first time are
+/// appended in that order. The merge is applied to the order read here and
+/// queued for the store, which merges again on write. If the store cannot
+/// be read, the order falls back to `live_ids` for this activation only.
+#[must_use]
+pub fn sync(live_ids: &[String]) -> WindowOrder {
+    let Some(mut order) = crate::state::with(|store| store.window_order()) else {
+        return from_ids(live_ids);
+    };
+    if merge(&mut order, live_ids) {
+        let live_ids = live_ids.to_vec();
+        crate::state::write(move |store| {
+            store.update_window_order(|order| merge(order, &live_ids))?;
+            Ok(())
+        });
+    }
+    from_ids(&order)
+}
+
+/// Position of `app_id` in the `pinned_apps` config, if it is pinned.
+#[must_use]
+pub fn pinned_rank(app_id: &str, pinned_apps: &[String]) -> Option<usize> {
+    pinned_apps
+        .iter()
+        .position(|pattern| crate::hints::app_matches(pattern, app_id))
+}
+
+/// Move windows of pinned apps to the front, grouped in `pinned_apps`
+/// order. The sort is stable: within each pinned app and among unpinned
+/// windows, the existing (MRU) order is kept. Returns the number of pinned
+/// windows.
+pub fn pin_first<T>(
+    windows: &mut [T],
+    app_id: impl Fn(&T) -> &str,
+    pinned_apps: &[String],
+) -> usize {
+    if pinned_apps.is_empty() {
+        return 0;
+    }
+    windows.sort_by_key(|w| pinned_rank(app_id(w), pinned_apps).unwrap_or(usize::MAX));
+    windows
+        .iter()
+        .take_while(|w| pinned_rank(app_id(w), pinned_apps).is_some())
+        .count()
+}
+
+/// Which windows are transient for another listed window. A window counts
+/// when following its parent chain through the list ends at a window with
+/// no listed parent; windows in a parent cycle count as top-level.
+fn transient_flags<T, K: PartialEq>(
+    windows: &[T],
+    id: impl Fn(&T) -> K,
+    parent: impl Fn(&T) -> Option<K>,
+) -> (Vec<Option<usize>>, Vec<bool>) {
+    let ids: Vec<K> = windows.iter().map(&id).collect();
+    let parent_index: Vec<Option<usize>> = windows
+        .iter()
+        .map(|w| parent(w).and_then(|p| ids.iter().position(|i| *i == p)))
+        .collect();
+    let transient = (0..windows.len())
+        .map(|i| {
+            let mut current = i;
+            for _ in 0..windows.len() {
+                match parent_index[current] {
+                    Some(p) => current = p,
+                    None => return current != i,
+                }
+            }
+            false
+        })
+        .collect();
+    (parent_index, transient)
+}
+
+/// Move each transient window directly below its parent, after any earlier
+/// siblings. The existing order is kept everywhere else. Windows whose
+/// parent is not listed stay where they are. Returns which windows (in the
+/// new order) were nested.
+pub fn nest_transients<T, K: PartialEq>(
+    windows: &mut Vec<T>,
+    id: impl Fn(&T) -> K,
+    parent: impl Fn(&T) -> Option<K>,
+) -> Vec<bool> {
+    fn place(i: usize, parent_index: &[Option<usize>], transient: &[bool], order: &mut Vec<usize>) {
+        order.push(i);
+        for child in (0..transient.len()).filter(|&c| transient[c] && parent_index[c] == Some(i)) {
+            place(child, parent_index, transient, order);
+        }
+    }
+
+    let (parent_index, transient) = transient_flags(windows, id, parent);
+    if !transient.contains(&true) {
+        return transient;
+    }
+    let mut order = Vec::with_capacity(windows.len());
+    for i in (0..windows.len()).filter(|&i| !transient[i]) {
+        place(i, &parent_index, &transient, &mut order);
+    }
+    let mut slots: Vec<Option<T>> = std::mem::take(windows).into_iter().map(Some).collect();
+    *windows = order.iter().filter_map(|&i| slots[i].take()).collect();
+    order.iter().map(|&i| transient[i]).collect()
+}
+
+/// Drop every transient window whose parent is listed. Compositors keep a
+/// dialog above its parent, so activating the parent still shows it.
+pub fn hide_transients<T, K: PartialEq>(
+    windows: &mut Vec<T>,
+    id: impl Fn(&T) -> K,
+    parent: impl Fn(&T) -> Option<K>,
+) {
+    let (_, transient) = transient_flags(windows, id, parent);
+    let mut flags = transient.into_iter();
+    windows.retain(|_| !flags.next().unwrap_or(false));
+}
+
+/// Drop closed windows and append new ones. Returns whether `order` changed.
+fn merge(order: &mut Vec<String>, live_ids: &[String]) -> bool {
+    let before = order.len();
+    order.retain(|id| live_ids.contains(id));
+    let mut changed = order.len() != before;
+    for id in live_ids {
+        if !order.contains(id) {
+            order.push(id.clone());
+            changed = true;
+        }
+    }
+    changed
+}
+
+fn from_ids(ids: &[String]) -> WindowOrder {
+    WindowOrder {
+        ranks: ids
+            .iter()
+            .enumerate()
+            .map(|(rank, id)| (id.clone(), rank))
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| (*s).to_string()).collect()
+    }
+
+    #[test]
+    fn merge_keeps_first_seen_order() {
+        let mut order = ids(&["A", "B", "C"]);
+        // Compositor reports the same windows shuffled: nothing changes.
+        assert!(!merge(&mut order, &ids(&["C", "A", "B"])));
+        assert_eq!(order, ids(&["A", "B", "C"]));
+
+        // B closes, D opens: B is dropped, D goes last.
+        assert!(merge(&mut order, &ids(&["D", "C", "A"])));
+        assert_eq!(order, ids(&["A", "C", "D"]));
+    }
+
+    #[test]
+    fn pin_first_groups_pinned_apps_in_config_order() {
+        let pinned = ids(&["ghostty", "firefox"]);
+        let mut windows = vec![
+            "slack",
+            "org.mozilla.firefox",
+            "code",
+            "com.mitchellh.ghostty",
+            "firefox",
+        ];
+        let count = pin_first(&mut windows, |w| *w, &pinned);
+        assert_eq!(count, 3);
+        assert_eq!(
+            windows,
+            [
+                "com.mitchellh.ghostty",
+                "org.mozilla.firefox",
+                "firefox",
+                "slack",
+                "code"
+            ]
+        );
+    }
+
+    #[test]
+    fn pin_first_without_pins_keeps_order() {
+        let mut windows = vec!["b", "a"];
+        assert_eq!(pin_first(&mut windows, |w| *w, &[]), 0);
+        assert_eq!(windows, ["b", "a"]);
+    }
+
+    #[test]
+    fn nest_transients_lists_dialogs_below_parent() {
+        // (id, parent): dialog 4 belongs to 1, dialog 5 to dialog 4, and
+        // 6 to a window that is not listed.
+        let mut windows = vec![
+            (4, Some(1)),
+            (2, None),
+            (1, None),
+            (5, Some(4)),
+            (6, Some(9)),
+        ];
+        let nested = nest_transients(&mut windows, |w| w.0, |w| w.1);
+        let order: Vec<i32> = windows.iter().map(|w| w.0).collect();
+        assert_eq!(order, [2, 1, 4, 5, 6]);
+        assert_eq!(nested, [false, false, true, true, false]);
+    }
+
+    #[test]
+    fn transient_cycles_stay_top_level() {
+        let mut windows = vec![(1, Some(2)), (2, Some(1)), (3, Some(3))];
+        let nested = nest_transients(&mut windows, |w| w.0, |w| w.1);
+        assert_eq!(nested, [false, false, false]);
+        hide_transients(&mut windows, |w| w.0, |w| w.1);
+        assert_eq!(windows.len(), 3);
+    }
+
+    #[test]
+    fn hide_transients_keeps_orphans() {
+        let mut windows = vec![(4, Some(1)), (1, None), (6, Some(9))];
+        hide_transients(&mut windows, |w| w.0, |w| w.1);
+        assert_eq!(windows, [(1, None), (6, Some(9))]);
+    }
+
+    #[test]
+    fn rank_unknown_sorts_last() {
+        let order = from_ids(&ids(&["A", "B"]));
+        assert_eq!(order.rank("B"), 1);
+        assert_eq!(order.rank("Z"), usize::MAX);
+    }
+}
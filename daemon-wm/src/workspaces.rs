@@ -0,0 +1,28 @@
+//! Workspace rows (`workspace_rows`).
+//!
+//! With `workspace_rows = true` the overlay lists the compositor's
+//! workspaces after the window rows, named as the compositor names them
+//! (COSMIC's "1", "2", … or user-chosen names). They get hints like any
+//! other row; activating one switches to that workspace instead of
+//! focusing a window.
+//!
+//! Workspaces are listed as [`SelectableItem::Workspace`] items (see
+//! `items`), each with the stand-in window built here.
+//!
+//! [`SelectableItem::Workspace`]: core_types::SelectableItem::Workspace
+
+use crate::items::stand_in;
+use core_types::{Window, WindowId, Workspace};
+
+/// App id of workspace rows, for hints and the row label.
+pub const WORKSPACE_APP_ID: &str = "workspace";
+
+/// The stand-in window a workspace row is listed with.
+pub(crate) fn row_window(ws: &Workspace) -> Window {
+    Window {
+        id: WindowId::from_uuid(*ws.id.as_uuid()),
+        workspace_id: ws.id,
+        is_focused: ws.is_active,
+        ..stand_in(WORKSPACE_APP_ID, ws.name.clone())
+    }
+}
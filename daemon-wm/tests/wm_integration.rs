@@ -19,9 +19,7 @@ fn make_bindings(entries: &[(&str, &[&str])]) -> BTreeMap<String, WmKeyBinding>
                 k.to_string(),
                 WmKeyBinding {
                     apps: apps.iter().map(|s| s.to_string()).collect(),
-                    launch: None,
-                    tags: Vec::new(),
-                    launch_args: Vec::new(),
+                    ..Default::default()
                 },
             )
         })
@@ -46,8 +44,7 @@ fn test_config() -> core_config::WmConfig {
                 WmKeyBinding {
                     apps: apps.into_iter().map(String::from).collect(),
                     launch: launch.map(String::from),
-                    tags: Vec::new(),
-                    launch_args: Vec::new(),
+                    ..Default::default()
                 },
             )
         })
@@ -73,7 +70,11 @@ fn test_windows() -> Vec<core_types::Window> {
             is_focused: true,
             is_minimized: false,
             is_fullscreen: false,
+            is_maximized: false,
             profile_id: core_types::ProfileId::new(),
+            parent: None,
+            is_sticky: false,
+            is_keep_above: false,
         },
         core_types::Window {
             id: core_types::WindowId::new(),
@@ -90,7 +91,11 @@ fn test_windows() -> Vec<core_types::Window> {
             is_focused: false,
             is_minimized: false,
             is_fullscreen: false,
+            is_maximized: false,
             profile_id: core_types::ProfileId::new(),
+            parent: None,
+            is_sticky: false,
+            is_keep_above: false,
         },
         core_types::Window {
             id: core_types::WindowId::new(),
@@ -107,7 +112,11 @@ fn test_windows() -> Vec<core_types::Window> {
             is_focused: false,
             is_minimized: false,
             is_fullscreen: false,
+            is_maximized: false,
             profile_id: core_types::ProfileId::new(),
+            parent: None,
+            is_sticky: false,
+            is_keep_above: false,
         },
     ]
 }
@@ -230,20 +239,32 @@ fn app_hints_groups_by_app() {
 }
 
 // ============================================================================
-// MRU State Parsing and File I/O
+// MRU State Persistence
 // ============================================================================
 
 #[test]
-fn mru_file_roundtrip() {
+fn mru_store_roundtrip() {
     let dir = tempfile::tempdir().unwrap();
-    let path = dir.path().join("mru");
-
-    std::fs::write(&path, "prev-window\ncurr-window").unwrap();
+    let path = dir.path().join(core_state::STATE_DB_FILE);
+
+    let mut store = core_state::StateStore::open(&path).unwrap();
+    store
+        .update_mru(|stack| {
+            for id in ["curr-window", "prev-window"] {
+                stack.push(core_state::MruEntry {
+                    window_id: id.into(),
+                    focused_at: None,
+                });
+            }
+            true
+        })
+        .unwrap();
+    drop(store);
 
-    let contents = std::fs::read_to_string(&path).unwrap();
-    let lines: Vec<&str> = contents.lines().collect();
-    assert_eq!(lines[0], "prev-window");
-    assert_eq!(lines[1], "curr-window");
+    let store = core_state::StateStore::open(&path).unwrap();
+    let state = daemon_wm::mru::MruState::from(store.mru().unwrap());
+    assert_eq!(state.current(), Some("curr-window"));
+    assert_eq!(state.previous(), Some("prev-window"));
 }
 
 // ============================================================================
@@ -569,8 +590,7 @@ fn launch_for_key_returns_command() {
         WmKeyBinding {
             apps: vec!["ghostty".to_string()],
             launch: Some("ghostty".to_string()),
-            tags: Vec::new(),
-            launch_args: Vec::new(),
+            ..Default::default()
         },
     );
     assert_eq!(launch_for_key('g', &bindings), Some("ghostty"));
@@ -663,7 +683,8 @@ fn controller_launcher_zero_windows_activates() {
         cmds.iter().any(|c| matches!(
             c,
             Command::ShowBorder {
-                zero_window_launcher: true
+                zero_window_launcher: true,
+                ..
             }
         )),
         "zero-window launcher must emit ShowBorder with flag"
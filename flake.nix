@@ -230,6 +230,7 @@
             ]
             ++ lib.optionals (!isHeadless) [
               "d %h/.cache/fontconfig 0755 - - -"
+              "d %h/.local/state/open-sesame 0700 - - -"
             ];
 
             # === Headless target — always installed ===
@@ -264,6 +265,23 @@
 
             # === Headless daemons ===
 
+            # IPC bus socket — starts daemon-profile on first connection.
+            systemd.user.sockets.open-sesame-profile = {
+              Unit = {
+                Description = "Open Sesame IPC bus socket";
+                Documentation = "https://github.com/scopecreep-zip/open-sesame";
+                PartOf = [ "open-sesame-headless.target" ];
+              };
+              Socket = {
+                ListenStream = "%t/pds/bus.sock";
+                SocketMode = "0600";
+                DirectoryMode = "0700";
+              };
+              Install = {
+                WantedBy = [ "sockets.target" ];
+              };
+            };
+
             # Profile daemon — IPC bus server, must start before all other daemons.
             systemd.user.services.open-sesame-profile = {
               Unit = {
@@ -416,6 +434,7 @@
                 ExecStart = "${desktopPkg}/bin/daemon-wm";
                 Restart = "on-failure";
                 RestartSec = 5;
+                SuccessExitStatus = "130 143";
                 TimeoutStopSec = 5;
                 WatchdogSec = 30;
                 NoNewPrivileges = true;
@@ -425,6 +444,7 @@
                   "%t/pds"
                   "%h/.cache/open-sesame"
                   "%h/.cache/fontconfig"
+                  "%h/.local/state/open-sesame"
                 ];
                 LimitNOFILE = 4096;
                 LimitCORE = 0;
@@ -555,7 +575,6 @@
               libxkbcommon
 
               # System libs
-              fontconfig
               pcsclite
               libseccomp
             ];
@@ -568,7 +587,6 @@
                 wayland.dev
                 wayland-protocols
                 libxkbcommon.dev
-                fontconfig.dev
                 pcsclite.dev
                 libseccomp.dev
               ]
@@ -582,7 +600,6 @@
                 libseccomp
                 openssl
                 pcsclite
-                fontconfig
                 wayland
                 libxkbcommon
               ]
@@ -597,7 +614,6 @@
                 libseccomp
                 openssl
                 pcsclite
-                fontconfig
                 wayland
                 libxkbcommon
               ]
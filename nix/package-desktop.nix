@@ -8,7 +8,6 @@
   makeWrapper,
   perl,
   openssl,
-  fontconfig,
   wayland,
   wayland-protocols,
   libxkbcommon,
@@ -99,7 +98,6 @@ rustPlatform.buildRustPackage {
 
   buildInputs = [
     openssl
-    fontconfig
     wayland
     wayland-protocols
     libxkbcommon
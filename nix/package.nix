@@ -140,6 +140,8 @@ rustPlatform.buildRustPackage {
       install -Dm644 "contrib/systemd/open-sesame-$svc.service" \
         "$out/lib/systemd/user/open-sesame-$svc.service"
     done
+    install -Dm644 contrib/systemd/open-sesame-profile.socket \
+      $out/lib/systemd/user/open-sesame-profile.socket
 
     # Patch systemd unit ExecStart from FHS /usr/bin/ to nix store path.
     # Without this, units fail with 203/EXEC on NixOS and standalone nix.
@@ -0,0 +1,516 @@
+//! `sesame export <bundle>` / `sesame import <bundle>` — portable setup bundles.
+//!
+//! A bundle is a zstd-compressed tar of the user config
+//! (`~/.config/pds/config.toml`, `config.d/*.toml` and per-profile overrides,
+//! which carry key bindings, pinned apps and launch tags) plus a snapshot of
+//! daemon-wm's state database: marks, the MRU stack, window order and the
+//! usage and focus-time logs. Vaults, keys and the audit log are never
+//! included.
+//!
+//! `manifest.json` comes first and records the bundle format and config
+//! version. `import` refuses bundles newer than it understands and entries
+//! outside the known layout, validates every config file, and writes nothing
+//! until the whole bundle has been read and checked. The state snapshot is
+//! copied into the live database in one transaction rather than replacing
+//! the file under a running daemon-wm. Format 1 bundles carried the state
+//! as cache files; they are restored to `~/.cache/open-sesame` and imported
+//! into the database from there.
+
+use anyhow::{Context, bail};
+use owo_colors::OwoColorize;
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+/// Bundle layout version, bumped when entries change incompatibly.
+const BUNDLE_FORMAT: u32 = 2;
+
+const MANIFEST: &str = "manifest.json";
+
+/// The state database snapshot entry (format 2).
+const STATE_ENTRY: &str = "state/state.db";
+
+/// daemon-wm state files carried by format 1 bundles.
+const LEGACY_CACHE_FILES: &[&str] = &["marks", "mru", "window-order", "usage.jsonl"];
+
+/// Largest entry `import` reads; real entries are at most about 1 MiB.
+const MAX_ENTRY_BYTES: u64 = 8 * 1024 * 1024;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Manifest {
+    format: u32,
+    sesame_version: String,
+    config_version: u32,
+    /// Unix time the bundle was written.
+    created: u64,
+    files: Vec<String>,
+}
+
+/// Bundle contents ready to write: destination path and bytes.
+type Restored = Vec<(PathBuf, Vec<u8>)>;
+
+/// Directories bundle entries are read from and restored to.
+struct Roots {
+    config: PathBuf,
+    cache: PathBuf,
+    /// The state database file.
+    state: PathBuf,
+}
+
+impl Roots {
+    fn current() -> anyhow::Result<Self> {
+        Ok(Self {
+            config: core_config::config_dir(),
+            cache: crate::helpers::wm_cache_dir()?,
+            state: core_state::StateStore::default_path().context("no state directory")?,
+        })
+    }
+
+    /// The file a bundle entry maps to, or `None` if a bundle may not
+    /// contain `entry`.
+    fn destination(&self, entry: &str) -> Option<PathBuf> {
+        let plain = |name: &str| !name.is_empty() && !name.starts_with('.') && !name.contains('\\');
+        match entry.split('/').collect::<Vec<_>>().as_slice() {
+            ["config", "config.toml"] => Some(self.config.join("config.toml")),
+            ["config", "config.d", name] if plain(name) && name.ends_with(".toml") => {
+                Some(self.config.join("config.d").join(name))
+            }
+            ["config", "profiles", name, "config.toml"] if plain(name) => {
+                Some(self.config.join("profiles").join(name).join("config.toml"))
+            }
+            ["state", "state.db"] => Some(self.state.clone()),
+            ["cache", name] if LEGACY_CACHE_FILES.contains(name) => Some(self.cache.join(name)),
+            _ => None,
+        }
+    }
+
+    /// Existing files to bundle, as entry name and path.
+    fn entries(&self) -> Vec<(String, PathBuf)> {
+        let mut names = vec!["config/config.toml".to_string()];
+        names.extend(
+            dir_names(&self.config.join("config.d"))
+                .into_iter()
+                .map(|name| format!("config/config.d/{name}")),
+        );
+        names.extend(
+            dir_names(&self.config.join("profiles"))
+                .into_iter()
+                .map(|name| format!("config/profiles/{name}/config.toml")),
+        );
+        names.push(STATE_ENTRY.to_string());
+        names
+            .into_iter()
+            .filter_map(|name| {
+                let path = self.destination(&name)?;
+                path.is_file().then_some((name, path))
+            })
+            .collect()
+    }
+}
+
+fn dir_names(dir: &Path) -> Vec<String> {
+    let Ok(read) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = read
+        .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+        .collect();
+    names.sort();
+    names
+}
+
+fn current_config_version() -> u32 {
+    core_config::Config::default().config_version
+}
+
+pub(crate) fn cmd_export_bundle(bundle: &Path) -> anyhow::Result<()> {
+    let roots = Roots::current()?;
+    if roots.state.is_file() {
+        // Fold in any cache files an older daemon-wm left behind.
+        crate::state::open_store()?;
+    }
+    let entries = roots.entries();
+    if entries.is_empty() {
+        bail!(
+            "nothing to export: no config in {} and no state in {}",
+            roots.config.display(),
+            roots.state.display()
+        );
+    }
+    let mut files = Vec::with_capacity(entries.len());
+    for (name, path) in &entries {
+        let data = if name == STATE_ENTRY {
+            snapshot_state(path)?
+        } else {
+            std::fs::read(path).with_context(|| format!("failed to read {}", path.display()))?
+        };
+        files.push((name.clone(), data));
+    }
+    let manifest = Manifest {
+        format: BUNDLE_FORMAT,
+        sesame_version: env!("CARGO_PKG_VERSION").into(),
+        config_version: current_config_version(),
+        created: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs()),
+        files: entries.iter().map(|(name, _)| name.clone()).collect(),
+    };
+    let out = std::fs::File::create(bundle)
+        .with_context(|| format!("failed to create {}", bundle.display()))?;
+    write_bundle(out, &manifest, &files)
+        .and_then(|out| Ok(out.sync_all()?))
+        .with_context(|| format!("failed to write {}", bundle.display()))?;
+
+    println!(
+        "{} {} ({} files)",
+        "Wrote".green(),
+        bundle.display(),
+        files.len()
+    );
+    for (name, _) in &files {
+        println!("  {}", name.dimmed());
+    }
+    Ok(())
+}
+
+pub(crate) fn cmd_import_bundle(bundle: &Path, force: bool) -> anyhow::Result<()> {
+    let roots = Roots::current()?;
+    let file = std::fs::File::open(bundle)
+        .with_context(|| format!("failed to open {}", bundle.display()))?;
+    let (manifest, files) =
+        read_bundle(file, &roots).with_context(|| format!("cannot import {}", bundle.display()))?;
+    if manifest.sesame_version != env!("CARGO_PKG_VERSION") {
+        eprintln!(
+            "{} bundle written by sesame {}; this is {}",
+            "note:".yellow(),
+            manifest.sesame_version,
+            env!("CARGO_PKG_VERSION")
+        );
+    }
+
+    let replaced: Vec<&Path> = files
+        .iter()
+        .filter(|(path, data)| {
+            if *path == roots.state {
+                has_state(path)
+            } else {
+                std::fs::read(path).is_ok_and(|old| old != *data)
+            }
+        })
+        .map(|(path, _)| path.as_path())
+        .collect();
+    if !force && !replaced.is_empty() {
+        let list: Vec<String> = replaced
+            .iter()
+            .map(|p| format!("  {}", p.display()))
+            .collect();
+        bail!(
+            "import would replace {} file(s):\n{}\npass --force to overwrite them",
+            replaced.len(),
+            list.join("\n")
+        );
+    }
+
+    for (path, data) in &files {
+        if *path == roots.state {
+            restore_state(path, data)?;
+            println!("  {}", path.display().dimmed());
+            continue;
+        }
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("failed to create {}", dir.display()))?;
+        }
+        core_config::atomic_write(path, data)
+            .with_context(|| format!("failed to write {}", path.display()))?;
+        println!("  {}", path.display().dimmed());
+    }
+    if files.iter().any(|(path, _)| path.starts_with(&roots.cache)) {
+        // Format 1 state: move the restored cache files into the database.
+        crate::state::open_store()?;
+    }
+    println!(
+        "{} {} files from {}",
+        "Imported".green(),
+        files.len(),
+        bundle.display()
+    );
+    Ok(())
+}
+
+/// A consistent copy of the state database at `path`, taken through a
+/// scratch file next to it.
+fn snapshot_state(path: &Path) -> anyhow::Result<Vec<u8>> {
+    let scratch = path.with_extension("db.export");
+    let _ = std::fs::remove_file(&scratch);
+    let store = core_state::StateStore::open(path).map_err(|e| anyhow::anyhow!("{e}"))?;
+    store
+        .snapshot_to(&scratch)
+        .map_err(|e| anyhow::anyhow!("{e}"))?;
+    let data = std::fs::read(&scratch);
+    let _ = std::fs::remove_file(&scratch);
+    data.with_context(|| format!("failed to read {}", scratch.display()))
+}
+
+/// Whether the state database at `path` holds anything an import would
+/// replace. Unreadable databases count, so they are not overwritten
+/// without --force.
+fn has_state(path: &Path) -> bool {
+    path.is_file()
+        && core_state::StateStore::open(path)
+            .and_then(|store| store.is_empty())
+            .map_or(true, |empty| !empty)
+}
+
+/// Replace the state database at `path` with the bundled snapshot `data`.
+fn restore_state(path: &Path, data: &[u8]) -> anyhow::Result<()> {
+    let scratch = path.with_extension("db.import");
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("failed to create {}", dir.display()))?;
+    }
+    std::fs::write(&scratch, data)
+        .with_context(|| format!("failed to write {}", scratch.display()))?;
+    let restored = core_state::StateStore::open(path)
+        .and_then(|mut store| store.restore_from(&scratch))
+        .map_err(|e| anyhow::anyhow!("{e}"));
+    let _ = std::fs::remove_file(&scratch);
+    restored.with_context(|| format!("failed to restore {}", path.display()))
+}
+
+/// Write `manifest` and `files` as a bundle, returning the inner writer.
+fn write_bundle<W: Write>(
+    out: W,
+    manifest: &Manifest,
+    files: &[(String, Vec<u8>)],
+) -> anyhow::Result<W> {
+    let mut tar = tar::Builder::new(zstd::Encoder::new(out, 0)?);
+    let mut append = |name: &str, data: &[u8]| {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(data.len() as u64);
+        header.set_mode(0o600);
+        header.set_mtime(manifest.created);
+        header.set_cksum();
+        tar.append_data(&mut header, name, data)
+    };
+    append(MANIFEST, &serde_json::to_vec_pretty(manifest)?)?;
+    for (name, data) in files {
+        append(name, data)?;
+    }
+    Ok(tar.into_inner()?.finish()?)
+}
+
+/// Read and check a bundle, mapping each entry to the file it restores.
+fn read_bundle<R: Read>(reader: R, roots: &Roots) -> anyhow::Result<(Manifest, Restored)> {
+    let mut archive = tar::Archive::new(zstd::Decoder::new(reader)?);
+    let mut manifest = None;
+    let mut files = Vec::new();
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let name = entry.path()?.to_string_lossy().into_owned();
+        if entry.header().entry_type() != tar::EntryType::Regular {
+            bail!("{name}: not a regular file");
+        }
+        if entry.size() > MAX_ENTRY_BYTES {
+            bail!("{name}: larger than {MAX_ENTRY_BYTES} bytes");
+        }
+        let mut data = Vec::new();
+        entry.read_to_end(&mut data)?;
+
+        if manifest.is_none() {
+            if name != MANIFEST {
+                bail!("{MANIFEST} missing or not first");
+            }
+            let parsed: Manifest =
+                serde_json::from_slice(&data).with_context(|| format!("invalid {MANIFEST}"))?;
+            check_manifest(&parsed)?;
+            manifest = Some(parsed);
+            continue;
+        }
+        let path = roots
+            .destination(&name)
+            .with_context(|| format!("unexpected entry '{name}'"))?;
+        if name.starts_with("config/") {
+            check_config(&name, &data)?;
+        }
+        files.push((path, data));
+    }
+    let manifest = manifest.with_context(|| format!("{MANIFEST} missing"))?;
+    Ok((manifest, files))
+}
+
+fn check_manifest(manifest: &Manifest) -> anyhow::Result<()> {
+    if manifest.format > BUNDLE_FORMAT {
+        bail!(
+            "bundle format {} is newer than this sesame reads ({BUNDLE_FORMAT}); \
+             upgrade sesame to import it",
+            manifest.format
+        );
+    }
+    let config_version = current_config_version();
+    if manifest.config_version > config_version {
+        bail!(
+            "bundle config version {} is newer than this sesame reads ({config_version}); \
+             upgrade sesame to import it",
+            manifest.config_version
+        );
+    }
+    Ok(())
+}
+
+/// Config entries must parse; `config.toml` must also validate, as for
+/// `sesame config set`. Fragments and profile overrides are partial layers,
+/// so they are only parsed.
+fn check_config(name: &str, data: &[u8]) -> anyhow::Result<()> {
+    let contents = std::str::from_utf8(data).with_context(|| format!("{name}: not UTF-8"))?;
+    if name == "config/config.toml" {
+        crate::config::check(contents)
+            .map_err(|errors| anyhow::anyhow!("{name}: {}", errors.join("; ")))?;
+    } else {
+        toml::from_str::<core_config::Config>(contents)
+            .with_context(|| format!("{name}: invalid config"))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn roots(dir: &Path) -> Roots {
+        Roots {
+            config: dir.join("config"),
+            cache: dir.join("cache"),
+            state: dir.join("state").join(core_state::STATE_DB_FILE),
+        }
+    }
+
+    fn write(path: &Path, contents: &str) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    }
+
+    fn manifest(format: u32, files: &[(String, Vec<u8>)]) -> Manifest {
+        Manifest {
+            format,
+            sesame_version: env!("CARGO_PKG_VERSION").into(),
+            config_version: current_config_version(),
+            created: 0,
+            files: files.iter().map(|(name, _)| name.clone()).collect(),
+        }
+    }
+
+    fn bundle_of(format: u32, files: &[(String, Vec<u8>)]) -> Vec<u8> {
+        write_bundle(Vec::new(), &manifest(format, files), files).unwrap()
+    }
+
+    #[test]
+    fn entries_round_trip_to_the_same_layout() {
+        let src = tempfile::tempdir().unwrap();
+        let from = roots(src.path());
+        write(
+            &from.config.join("config.toml"),
+            "[profiles.default.wm]\npinned_apps = [\"ghostty\"]\n",
+        );
+        write(&from.config.join("config.d/50-local.toml"), "");
+        write(&from.config.join("config.d/README"), "not a fragment");
+        write(&from.config.join("profiles/work/config.toml"), "");
+        write(&from.config.join("audit.jsonl"), "{}");
+        write(&from.cache.join("marks"), "a 42\n");
+        core_state::StateStore::open(&from.state).unwrap();
+
+        let entries = from.entries();
+        let names: Vec<&str> = entries.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "config/config.toml",
+                "config/config.d/50-local.toml",
+                "config/profiles/work/config.toml",
+                STATE_ENTRY,
+            ]
+        );
+
+        let files: Vec<(String, Vec<u8>)> = entries
+            .iter()
+            .map(|(name, path)| (name.clone(), std::fs::read(path).unwrap()))
+            .collect();
+        let dst = tempfile::tempdir().unwrap();
+        let to = roots(dst.path());
+        let (manifest, restored) = read_bundle(&bundle_of(BUNDLE_FORMAT, &files)[..], &to).unwrap();
+        assert_eq!(manifest.files.len(), 4);
+        assert_eq!(restored[0].0, to.config.join("config.toml"));
+        assert_eq!(restored[3].0, to.state);
+    }
+
+    #[test]
+    fn format_1_cache_files_are_still_restored() {
+        let dir = tempfile::tempdir().unwrap();
+        let to = roots(dir.path());
+        let files = [("cache/marks".to_string(), b"a 42\n".to_vec())];
+        let (_, restored) = read_bundle(&bundle_of(1, &files)[..], &to).unwrap();
+        assert_eq!(restored, [(to.cache.join("marks"), b"a 42\n".to_vec())]);
+    }
+
+    #[test]
+    fn state_snapshot_restores_into_an_existing_database() {
+        let src = tempfile::tempdir().unwrap();
+        let path = src.path().join(core_state::STATE_DB_FILE);
+        core_state::StateStore::open(&path)
+            .unwrap()
+            .update_marks(|marks| marks.insert('a', "42".into()).is_none())
+            .unwrap();
+        let snapshot = snapshot_state(&path).unwrap();
+
+        let dst = tempfile::tempdir().unwrap();
+        let path = dst.path().join(core_state::STATE_DB_FILE);
+        let live = core_state::StateStore::open(&path).unwrap();
+        assert!(!has_state(&path), "an empty database is not replaced");
+        restore_state(&path, &snapshot).unwrap();
+        assert_eq!(
+            live.marks().unwrap().get(&'a').map(String::as_str),
+            Some("42")
+        );
+        assert!(has_state(&path));
+        assert!(!path.with_extension("db.import").exists());
+    }
+
+    #[test]
+    fn destination_rejects_paths_outside_the_layout() {
+        let roots = roots(Path::new("/home/u"));
+        for entry in [
+            "config/profiles/../config.toml",
+            "config/config.d/.hidden.toml",
+            "config/config.d/run.sh",
+            "config/audit.jsonl",
+            "cache/state.db",
+            "state/../config.toml",
+            "cache/../../.bashrc",
+            "/etc/passwd",
+        ] {
+            assert_eq!(roots.destination(entry), None, "{entry}");
+        }
+    }
+
+    #[test]
+    fn read_rejects_newer_formats_and_invalid_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let roots = roots(dir.path());
+        let newer = bundle_of(BUNDLE_FORMAT + 1, &[]);
+        let err = read_bundle(&newer[..], &roots).unwrap_err();
+        assert!(err.to_string().contains("upgrade sesame"), "{err}");
+
+        let invalid = bundle_of(
+            BUNDLE_FORMAT,
+            &[(
+                "config/config.toml".into(),
+                b"config_version = \"x\"".to_vec(),
+            )],
+        );
+        assert!(read_bundle(&invalid[..], &roots).is_err());
+
+        let stray = bundle_of(BUNDLE_FORMAT, &[("cache/vault.db".into(), Vec::new())]);
+        let err = read_bundle(&stray[..], &roots).unwrap_err();
+        assert!(err.to_string().contains("unexpected entry"), "{err}");
+    }
+}
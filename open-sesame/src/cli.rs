@@ -148,6 +148,10 @@ pub(crate) enum Command {
     #[command(subcommand)]
     Audit(AuditCmd),
 
+    /// Read and edit the user config file (`~/.config/pds/config.toml`).
+    #[command(subcommand)]
+    Config(ConfigCmd),
+
     /// Application launcher.
     #[command(subcommand)]
     Launch(LaunchCmd),
@@ -156,6 +160,39 @@ pub(crate) enum Command {
     #[command(subcommand)]
     Wm(WmCmd),
 
+    /// Overlay color themes.
+    #[command(subcommand)]
+    Theme(ThemeCmd),
+
+    /// Summarize local overlay usage: apps chosen, launches, cancels and
+    /// time to select, plus key bindings that were never used.
+    ///
+    /// Usage: sesame stats --since 7d
+    Stats {
+        /// Only count events this recent (e.g. 30m, 12h, 7d, 4w).
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Print the summary as JSON.
+        #[arg(long)]
+        json: bool,
+
+        /// Show the learned Alt-release timing and quick-switch threshold
+        /// (`wm.adaptive_quick_switch`) instead.
+        #[arg(long, conflicts_with = "since")]
+        timing: bool,
+
+        /// Show time spent per app between switches (`wm.focus_time`)
+        /// instead.
+        #[arg(long, conflicts_with = "timing")]
+        focus_time: bool,
+    },
+
+    /// Window switcher state database (MRU order, marks, usage and focus
+    /// time).
+    #[command(subcommand)]
+    State(StateCmd),
+
     /// Clipboard operations.
     #[command(subcommand)]
     Clipboard(ClipboardCmd),
@@ -189,6 +226,17 @@ pub(crate) enum Command {
     #[cfg(all(target_os = "linux", feature = "desktop"))]
     KeybindingStatus,
 
+    /// Run `sesame wm warmup` at login via an XDG autostart entry.
+    ///
+    /// Writes `~/.config/autostart/open-sesame-warmup.desktop` pointing at
+    /// this binary, so the first Alt+Tab after login is not the slowest.
+    #[cfg(all(target_os = "linux", feature = "desktop"))]
+    InstallAutostart,
+
+    /// Remove the login warm-up autostart entry.
+    #[cfg(all(target_os = "linux", feature = "desktop"))]
+    RemoveAutostart,
+
     /// Run a command with profile-scoped secrets as environment variables.
     ///
     /// Each secret key is transformed to an env var: uppercase, hyphens become
@@ -210,7 +258,8 @@ pub(crate) enum Command {
         command: Vec<String>,
     },
 
-    /// Print profile secrets as shell/dotenv/json for eval or piping.
+    /// Print profile secrets as shell/dotenv/json for eval or piping, or
+    /// write a portable setup bundle.
     ///
     /// Formats:
     ///   shell  (default) — export KEY="value"  (eval in bash/zsh/direnv)
@@ -221,7 +270,15 @@ pub(crate) enum Command {
     ///   eval "$(sesame export -p work)"
     ///   sesame export -p work --format dotenv > .env.secrets
     ///   sesame export -p work --format json | jq .
+    ///
+    /// Given a path, writes config, marks, MRU and usage stats (never
+    /// secrets) as a `.tar.zst` bundle for `sesame import`:
+    ///   sesame export sesame.tar.zst
     Export {
+        /// Write a setup bundle to this path instead of printing secrets.
+        #[arg(conflicts_with_all = ["profile", "format", "prefix"])]
+        bundle: Option<std::path::PathBuf>,
+
         /// Profiles to source secrets from (CSV: "default,work" or "org:vault").
         /// Falls back to SESAME_PROFILES env var, then "default".
         #[arg(short, long)]
@@ -236,6 +293,22 @@ pub(crate) enum Command {
         prefix: Option<String>,
     },
 
+    /// Restore a setup bundle written by `sesame export <BUNDLE>`.
+    ///
+    /// Checks the bundle's format and config version and validates its
+    /// config before writing anything. Files that differ from the bundle
+    /// are only replaced with --force.
+    ///
+    /// Usage: sesame import sesame.tar.zst
+    Import {
+        /// Bundle path.
+        bundle: std::path::PathBuf,
+
+        /// Overwrite existing files that differ from the bundle.
+        #[arg(long)]
+        force: bool,
+    },
+
     /// Workspace management (directory-scoped project environments).
     #[command(subcommand, alias = "ws")]
     Workspace(WorkspaceCmd),
@@ -545,6 +618,57 @@ pub(crate) enum SecretCmd {
     },
 }
 
+#[derive(Subcommand)]
+pub(crate) enum ConfigCmd {
+    /// Print the effective value of a dotted key (all layers merged, defaults included).
+    ///
+    /// Usage: sesame config get profiles.default.wm.overlay_delay_ms
+    Get {
+        /// Dotted key path. Quote segments containing dots: `profiles."a.b".color`.
+        key: String,
+    },
+
+    /// Set a key in the user config file, preserving comments and formatting.
+    ///
+    /// The value is parsed as a TOML literal (`200`, `true`, `["a", "b"]`);
+    /// anything else is stored as a string. The file is only written if the
+    /// result still parses and validates.
+    ///
+    /// Usage: sesame config set profiles.default.wm.key_bindings.g.launch ghostty
+    Set {
+        /// Dotted key path.
+        key: String,
+
+        /// New value.
+        value: String,
+    },
+
+    /// Open the user config file in $VISUAL / $EDITOR and validate it on save.
+    Edit,
+
+    /// Write the default config to the user config file and print its path.
+    ///
+    /// Creates the config directory if needed. Refuses to replace an existing
+    /// file unless --force is given.
+    ///
+    /// Usage: sesame config init [--force]
+    Init {
+        /// Overwrite an existing user config file.
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Check the effective config, or FILE on its own, and report problems.
+    ///
+    /// Exits non-zero if any error is found; warnings are printed only.
+    ///
+    /// Usage: sesame config validate [FILE]
+    Validate {
+        /// Config file to check instead of the merged config layers.
+        file: Option<std::path::PathBuf>,
+    },
+}
+
 #[derive(Subcommand)]
 pub(crate) enum AuditCmd {
     /// Verify audit log hash chain integrity.
@@ -578,6 +702,19 @@ pub(crate) enum WmCmd {
     Focus {
         /// Window ID or app ID string.
         window_id: String,
+        /// Don't record the switch in the MRU stack (for scripts), so
+        /// Alt+Tab still returns to the previous window.
+        #[arg(long)]
+        silent: bool,
+    },
+
+    /// Activate the window marked with a letter, without showing the overlay.
+    ///
+    /// Mark a window by pressing Ctrl+M and then a letter (a–z) in the
+    /// overlay. Marks last until their window closes.
+    Mark {
+        /// Mark letter (a–z).
+        mark: char,
     },
 
     /// Activate the window switcher overlay.
@@ -593,8 +730,31 @@ pub(crate) enum WmCmd {
         /// Start with backward direction (previous window in MRU order).
         #[arg(long)]
         backward: bool,
+
+        /// Pre-fill the launcher input as if QUERY had been typed, e.g.
+        /// `--query g` selects the window with hint `g`.
+        #[arg(long, requires = "launcher", value_name = "QUERY")]
+        query: Option<String>,
+
+        /// Show the overlay on this output (connector name, e.g. `DP-1`)
+        /// instead of the one the compositor picks.
+        #[arg(long, value_name = "NAME")]
+        output: Option<String>,
+
+        /// Print the activation latency breakdown (IPC connect, window
+        /// enumeration, first frame, input ready) against the 50ms
+        /// first-frame budget.
+        #[arg(long)]
+        timings: bool,
     },
 
+    /// Warm up the overlay so the first activation after login is fast.
+    ///
+    /// Checks the config, has daemon-wm refresh its window list and render
+    /// a picker frame offscreen, starts the resident fast-path process, and
+    /// exits. `sesame install-autostart` runs this at login.
+    Warmup,
+
     /// Run as resident fast-path process for overlay activation.
     ///
     /// Holds an active IPC connection and listens on a Unix datagram socket
@@ -604,6 +764,44 @@ pub(crate) enum WmCmd {
     OverlayResident,
 }
 
+#[derive(Subcommand)]
+pub(crate) enum ThemeCmd {
+    /// List the built-in themes.
+    List,
+
+    /// Render the window switcher with a theme to a PNG, without changing
+    /// the config.
+    ///
+    /// daemon-wm draws a fixed set of sample windows with the theme's colors
+    /// and the rest of the running config. Without --theme or --config the
+    /// current config's colors are used.
+    ///
+    /// Usage: sesame theme preview --theme dracula -o dracula.png
+    Preview {
+        /// Built-in theme name (see `sesame theme list`).
+        #[arg(long, conflicts_with = "config")]
+        theme: Option<String>,
+
+        /// Config file whose colors to preview.
+        #[arg(long, value_name = "PATH")]
+        config: Option<std::path::PathBuf>,
+
+        /// Where to write the PNG.
+        #[arg(short, long, default_value = "theme-preview.png")]
+        output: std::path::PathBuf,
+    },
+}
+
+#[derive(Subcommand)]
+pub(crate) enum StateCmd {
+    /// Reclaim the space of deleted rows and compact the database.
+    ///
+    /// Safe while daemon-wm is running.
+    ///
+    /// Usage: sesame state vacuum
+    Vacuum,
+}
+
 #[derive(Subcommand)]
 pub(crate) enum LaunchCmd {
     /// Search for applications by name (fuzzy match with frecency ranking).
@@ -0,0 +1,359 @@
+//! `sesame config get|set|edit|init|validate` — read and edit the user config file.
+//!
+//! `get` and `validate` read the effective configuration (all layers
+//! merged), or for `validate` a single given file. `set` and
+//! `edit` modify only `~/.config/pds/config.toml`, round-tripping through
+//! `toml_edit` so comments and formatting survive. Every write is parsed and
+//! validated first; an invalid result never reaches disk. `init` writes the
+//! defaults there in place of the file.
+
+use anyhow::Context;
+use owo_colors::OwoColorize;
+
+use crate::cli::ConfigCmd;
+
+pub(crate) fn cmd_config(cmd: ConfigCmd) -> anyhow::Result<()> {
+    match cmd {
+        ConfigCmd::Get { key } => cmd_config_get(&key),
+        ConfigCmd::Set { key, value } => cmd_config_set(&key, &value),
+        ConfigCmd::Edit => cmd_config_edit(),
+        ConfigCmd::Init { force } => cmd_config_init(force),
+        ConfigCmd::Validate { file } => cmd_config_validate(file.as_deref()),
+    }
+}
+
+fn user_config_path() -> std::path::PathBuf {
+    core_config::config_dir().join("config.toml")
+}
+
+fn cmd_config_get(key: &str) -> anyhow::Result<()> {
+    let config = core_config::load_config(None).map_err(|e| anyhow::anyhow!("{e}"))?;
+    let path = parse_key(key)?;
+    let value = lookup(&config, &path).with_context(|| format!("unknown config key: {key}"))?;
+    match value {
+        toml::Value::String(s) => println!("{s}"),
+        toml::Value::Table(table) => print!("{}", toml::to_string(&table)?),
+        other => println!("{other}"),
+    }
+    Ok(())
+}
+
+fn cmd_config_set(key: &str, raw_value: &str) -> anyhow::Result<()> {
+    let file = user_config_path();
+    let current = match std::fs::read_to_string(&file) {
+        Ok(s) => s,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e).with_context(|| format!("failed to read {}", file.display())),
+    };
+    let path = parse_key(key)?;
+    let updated = set_value(&current, &path, raw_value)?;
+    let config = check(&updated).map_err(|errors| anyhow::anyhow!(errors.join("\n")))?;
+    // serde defaults silently accept unknown keys; a key that does not survive
+    // the round trip through the schema is a typo.
+    if lookup(&config, &path).is_none() {
+        anyhow::bail!("unknown config key: {key}");
+    }
+    write_config(&file, &updated)?;
+    println!("{} {key} = {raw_value}", "Set".green());
+    Ok(())
+}
+
+fn cmd_config_validate(file: Option<&std::path::Path>) -> anyhow::Result<()> {
+    let config: core_config::Config = match file {
+        Some(path) => {
+            let contents = std::fs::read_to_string(path)
+                .with_context(|| format!("failed to read {}", path.display()))?;
+            toml::from_str(&contents)
+                .with_context(|| format!("failed to parse {}", path.display()))?
+        }
+        None => core_config::load_config(None).map_err(|e| anyhow::anyhow!("{e}"))?,
+    };
+    let mut errors = 0;
+    for diag in core_config::validate(&config) {
+        let label = match diag.severity {
+            core_config::DiagnosticSeverity::Error => {
+                errors += 1;
+                "error".red().bold().to_string()
+            }
+            core_config::DiagnosticSeverity::Warning => "warning".yellow().bold().to_string(),
+            core_config::DiagnosticSeverity::Info => "info".dimmed().to_string(),
+        };
+        eprintln!("{label}: {}", diag.message);
+        if let Some(remediation) = diag.remediation {
+            eprintln!("  {}", remediation.dimmed());
+        }
+    }
+    if errors > 0 {
+        anyhow::bail!("config has {errors} error(s)");
+    }
+    println!("{}", "Config is valid.".green());
+    Ok(())
+}
+
+fn cmd_config_edit() -> anyhow::Result<()> {
+    let file = user_config_path();
+    let original = match std::fs::read_to_string(&file) {
+        Ok(s) => s,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e).with_context(|| format!("failed to read {}", file.display())),
+    };
+
+    let scratch = std::env::temp_dir().join(format!("sesame-config-{}.toml", std::process::id()));
+    {
+        use std::io::Write;
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut f = options
+            .open(&scratch)
+            .with_context(|| format!("failed to create {}", scratch.display()))?;
+        f.write_all(original.as_bytes())?;
+    }
+
+    let result = edit_until_valid(&scratch);
+    let _ = std::fs::remove_file(&scratch);
+    let Some(edited) = result? else {
+        println!("{}", "Edit discarded; config unchanged.".dimmed());
+        return Ok(());
+    };
+
+    if edited == original {
+        println!("{}", "No changes.".dimmed());
+        return Ok(());
+    }
+    write_config(&file, &edited)?;
+    println!("{} {}", "Saved".green(), file.display());
+    Ok(())
+}
+
+fn cmd_config_init(force: bool) -> anyhow::Result<()> {
+    let file = user_config_path();
+    if !force && file.exists() {
+        anyhow::bail!(
+            "{} already exists; pass --force to overwrite it",
+            file.display()
+        );
+    }
+    write_config(&file, &default_config_toml()?)?;
+    println!("{} {}", "Wrote".green(), file.display());
+    Ok(())
+}
+
+/// The default config with a `default` profile, as written by `sesame init`
+/// and `sesame config init`.
+pub(crate) fn default_config_toml() -> anyhow::Result<String> {
+    let mut config = core_config::Config::default();
+    config.profiles.insert(
+        core_types::DEFAULT_PROFILE_NAME.into(),
+        core_config::ProfileConfig::default(),
+    );
+    toml::to_string_pretty(&config).context("failed to serialize default config")
+}
+
+/// Run the editor on `scratch` until its contents validate.
+///
+/// Returns `None` if the user declines to re-edit an invalid file.
+fn edit_until_valid(scratch: &std::path::Path) -> anyhow::Result<Option<String>> {
+    loop {
+        run_editor(scratch)?;
+        let edited = std::fs::read_to_string(scratch)
+            .with_context(|| format!("failed to read {}", scratch.display()))?;
+        match check(&edited) {
+            Ok(_) => return Ok(Some(edited)),
+            Err(errors) => {
+                for e in &errors {
+                    eprintln!("{}: {e}", "invalid".red().bold());
+                }
+                let again = dialoguer::Confirm::new()
+                    .with_prompt("Re-open the editor?")
+                    .default(true)
+                    .interact()
+                    .context("failed to read confirmation")?;
+                if !again {
+                    return Ok(None);
+                }
+            }
+        }
+    }
+}
+
+fn run_editor(path: &std::path::Path) -> anyhow::Result<()> {
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".into());
+    // Allow editors with arguments, e.g. EDITOR="code --wait".
+    let mut parts = editor.split_whitespace();
+    let program = parts.next().context("$EDITOR is empty")?;
+    let status = std::process::Command::new(program)
+        .args(parts)
+        .arg(path)
+        .status()
+        .with_context(|| format!("failed to run editor '{editor}'"))?;
+    if !status.success() {
+        anyhow::bail!("editor '{editor}' exited with {status}");
+    }
+    Ok(())
+}
+
+fn write_config(file: &std::path::Path, contents: &str) -> anyhow::Result<()> {
+    if let Some(dir) = file.parent() {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("failed to create {}", dir.display()))?;
+    }
+    core_config::atomic_write(file, contents.as_bytes())
+        .with_context(|| format!("failed to write {}", file.display()))
+}
+
+/// Split a dotted key path, honouring TOML quoting (`profiles."a.b".color`).
+fn parse_key(key: &str) -> anyhow::Result<Vec<String>> {
+    let keys =
+        toml_edit::Key::parse(key).map_err(|e| anyhow::anyhow!("invalid key '{key}': {e}"))?;
+    Ok(keys.iter().map(|k| k.get().to_owned()).collect())
+}
+
+/// Look up a dotted path in the serialized configuration.
+fn lookup(config: &core_config::Config, path: &[String]) -> Option<toml::Value> {
+    let mut value = toml::Value::try_from(config).ok()?;
+    for segment in path {
+        value = value.as_table_mut()?.remove(segment)?;
+    }
+    Some(value)
+}
+
+/// Set `path` to `raw_value` in a TOML document, creating tables as needed.
+///
+/// `raw_value` is parsed as a TOML value; if that fails it is stored as a
+/// string, so `ghostty` and `"ghostty"` are equivalent.
+fn set_value(document: &str, path: &[String], raw_value: &str) -> anyhow::Result<String> {
+    let mut doc: toml_edit::DocumentMut =
+        document.parse().context("config.toml is not valid TOML")?;
+    let (leaf, parents) = path.split_last().context("empty key")?;
+
+    let mut table: &mut dyn toml_edit::TableLike = doc.as_table_mut();
+    for (i, segment) in parents.iter().enumerate() {
+        let item = table.entry(segment).or_insert_with(|| {
+            let mut t = toml_edit::Table::new();
+            t.set_implicit(true);
+            toml_edit::Item::Table(t)
+        });
+        table = item
+            .as_table_like_mut()
+            .with_context(|| format!("{} is not a table", path[..=i].join(".")))?;
+    }
+
+    let mut value: toml_edit::Value = raw_value
+        .parse()
+        .unwrap_or_else(|_| toml_edit::Value::from(raw_value));
+    match table.get_mut(leaf) {
+        Some(toml_edit::Item::Value(old)) => {
+            *value.decor_mut() = old.decor().clone();
+            *old = value;
+        }
+        Some(toml_edit::Item::Table(_) | toml_edit::Item::ArrayOfTables(_)) => {
+            anyhow::bail!("{} is a table; set one of its keys instead", path.join("."));
+        }
+        _ => {
+            table.insert(leaf, toml_edit::Item::Value(value));
+        }
+    }
+    Ok(doc.to_string())
+}
+
+/// Parse and validate a config file. Returns error messages on failure;
+/// warnings are printed and do not block the write.
+pub(crate) fn check(contents: &str) -> Result<core_config::Config, Vec<String>> {
+    let config: core_config::Config = toml::from_str(contents).map_err(|e| vec![e.to_string()])?;
+    let mut errors = Vec::new();
+    for diag in core_config::validate(&config) {
+        match diag.severity {
+            core_config::DiagnosticSeverity::Error => errors.push(diag.message),
+            core_config::DiagnosticSeverity::Warning => {
+                eprintln!("{}: {}", "warning".yellow().bold(), diag.message);
+            }
+            core_config::DiagnosticSeverity::Info => {}
+        }
+    }
+    if errors.is_empty() {
+        Ok(config)
+    } else {
+        Err(errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOC: &str = "\
+# Open Sesame
+[profiles.default.wm]
+overlay_delay_ms = 150 # fast
+
+[profiles.default.wm.key_bindings.g]
+apps = [\"ghostty\"]
+";
+
+    fn path(key: &str) -> Vec<String> {
+        parse_key(key).unwrap()
+    }
+
+    #[test]
+    fn set_preserves_comments_and_decor() {
+        let out = set_value(DOC, &path("profiles.default.wm.overlay_delay_ms"), "300").unwrap();
+        assert!(out.starts_with("# Open Sesame\n"));
+        assert!(out.contains("overlay_delay_ms = 300 # fast"), "{out}");
+    }
+
+    #[test]
+    fn set_bare_word_is_string_and_literal_is_typed() {
+        let out = set_value(
+            DOC,
+            &path("profiles.default.wm.key_bindings.g.launch"),
+            "ghostty",
+        )
+        .unwrap();
+        assert!(out.contains("launch = \"ghostty\""), "{out}");
+        let out = set_value(DOC, &path("profiles.default.wm.show_title"), "false").unwrap();
+        assert!(out.contains("show_title = false"), "{out}");
+    }
+
+    #[test]
+    fn set_creates_missing_tables() {
+        let out = set_value("", &path("profiles.work.wm.overlay_delay_ms"), "0").unwrap();
+        let config = check(&out).unwrap();
+        assert_eq!(
+            lookup(&config, &path("profiles.work.wm.overlay_delay_ms")),
+            Some(toml::Value::Integer(0))
+        );
+    }
+
+    #[test]
+    fn set_refuses_to_replace_table() {
+        assert!(set_value(DOC, &path("profiles.default.wm"), "1").is_err());
+    }
+
+    #[test]
+    fn unknown_key_does_not_round_trip() {
+        let out = set_value(DOC, &path("profiles.default.wm.overlay_dealy_ms"), "1").unwrap();
+        let config = check(&out).unwrap();
+        assert!(lookup(&config, &path("profiles.default.wm.overlay_dealy_ms")).is_none());
+    }
+
+    #[test]
+    fn check_rejects_wrong_type() {
+        let out = set_value(DOC, &path("profiles.default.wm.overlay_delay_ms"), "fast").unwrap();
+        assert!(check(&out).is_err());
+    }
+
+    #[test]
+    fn quoted_key_segments() {
+        assert_eq!(
+            path("profiles.\"a.b\".color"),
+            vec!["profiles", "a.b", "color"]
+        );
+    }
+}
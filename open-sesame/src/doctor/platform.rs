@@ -1,4 +1,5 @@
-//! Platform checks — kernel version, ptrace scope, display server.
+//! Platform checks — kernel version, ptrace scope, display server, compositor
+//! capabilities.
 
 use super::{Check, Status};
 
@@ -59,6 +60,9 @@ pub fn checks() -> Vec<Check> {
         description: "Wayland required for overlay, clipboard, and input capture".into(),
     });
 
+    #[cfg(all(target_os = "linux", feature = "desktop"))]
+    results.extend(compositor_checks());
+
     // platform.input_group — user membership for evdev access.
     let in_input = std::process::Command::new("groups")
         .output()
@@ -84,6 +88,127 @@ pub fn checks() -> Vec<Check> {
     results
 }
 
+/// Capability matrix of the running compositor, as the window manager
+/// negotiates it.
+#[cfg(all(target_os = "linux", feature = "desktop"))]
+fn compositor_checks() -> Vec<Check> {
+    let caps = match platform_linux::compositor::probe_capabilities() {
+        Ok(caps) => caps,
+        Err(e) => {
+            return vec![Check {
+                id: "platform.compositor".into(),
+                category: "platform",
+                status: Status::Warn,
+                value: "unavailable".into(),
+                description: e.to_string(),
+            }];
+        }
+    };
+
+    let protocols: Vec<String> = caps
+        .protocols
+        .iter()
+        .map(|(interface, version)| format!("{interface} v{version}"))
+        .collect();
+    let mut results = vec![Check {
+        id: "platform.compositor".into(),
+        category: "platform",
+        status: Status::Pass,
+        value: if protocols.is_empty() {
+            caps.backend.to_string()
+        } else {
+            format!("{} ({})", caps.backend, protocols.join(", "))
+        },
+        description: "Window-management protocols at their negotiated versions".into(),
+    }];
+
+    // (id, supported, required, what is lost without it)
+    let matrix = [
+        (
+            "activate",
+            caps.can_activate,
+            true,
+            "Overlay cannot switch windows; only launch targets are shown",
+        ),
+        (
+            "close",
+            caps.can_close,
+            true,
+            "Compositor does not allow closing windows",
+        ),
+        (
+            "maximize",
+            caps.can_maximize,
+            false,
+            "Overlay cannot maximize windows (Ctrl+F)",
+        ),
+        (
+            "tile",
+            caps.can_tile,
+            false,
+            "Overlay cannot tile windows to a half (Ctrl+H, Ctrl+L)",
+        ),
+        (
+            "sticky",
+            caps.can_stick,
+            false,
+            "Overlay cannot show windows on every workspace (Ctrl+S)",
+        ),
+        (
+            "keep_above",
+            caps.can_keep_above,
+            false,
+            "Overlay cannot keep windows above others (Ctrl+T)",
+        ),
+        (
+            "workspace_info",
+            caps.has_workspace_info,
+            false,
+            "No workspace protocol advertised",
+        ),
+        (
+            "switch_workspace",
+            caps.can_switch_workspace,
+            false,
+            "Overlay cannot list or switch workspaces (wm.workspace_rows)",
+        ),
+        (
+            "move_to_output",
+            caps.can_move_to_output,
+            false,
+            "Overlay cannot move windows between outputs (Ctrl+O)",
+        ),
+        (
+            "screencopy",
+            caps.has_screencopy,
+            false,
+            "No screen capture protocol advertised",
+        ),
+    ];
+    for (name, supported, required, missing) in matrix {
+        results.push(Check {
+            id: format!("platform.compositor.{name}"),
+            category: "platform",
+            status: if supported || !required {
+                Status::Pass
+            } else {
+                Status::Warn
+            },
+            value: if supported {
+                "supported".into()
+            } else {
+                "unsupported".into()
+            },
+            description: if supported {
+                String::new()
+            } else {
+                missing.into()
+            },
+        });
+    }
+    results
+}
+
 fn kernel_version() -> Option<String> {
     let output = std::process::Command::new("uname")
         .arg("-r")
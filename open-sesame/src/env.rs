@@ -298,6 +298,14 @@ mod tests {
         assert_eq!(secret_key_to_env_var("db.host-name", None), "DB_HOST_NAME");
     }
 
+    #[test]
+    fn dotenv_export_parses_back() {
+        let value = "p@ss \"w$rd\"`!\\\nline2\r\t# end";
+        let line = format!("API_KEY=\"{}\"", shell_escape(value));
+        let parsed = core_config::parse_env_file(&line, |_| None).unwrap();
+        assert_eq!(parsed, [("API_KEY".to_string(), value.to_string())]);
+    }
+
     #[test]
     fn shell_escape_strips_null_bytes() {
         assert_eq!(shell_escape("before\0after"), "beforeafter");
@@ -16,6 +16,15 @@ pub(crate) fn validate_profile_in_config(profile: &str) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// daemon-wm's cache directory (`~/.cache/open-sesame`), where versions
+/// before the state database kept the MRU stack, marks, window order and
+/// the usage log.
+pub(crate) fn wm_cache_dir() -> anyhow::Result<std::path::PathBuf> {
+    dirs::cache_dir()
+        .map(|dir| dir.join("open-sesame"))
+        .ok_or_else(|| anyhow::anyhow!("no cache directory"))
+}
+
 pub(crate) fn format_denial_reason(
     reason: &core_types::SecretDenialReason,
     key: &str,
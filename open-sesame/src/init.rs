@@ -289,13 +289,7 @@ fn init_config() -> anyhow::Result<()> {
 
     std::fs::create_dir_all(&config_dir).context("failed to create config directory")?;
 
-    let mut config = core_config::Config::default();
-    config.profiles.insert(
-        core_types::DEFAULT_PROFILE_NAME.into(),
-        core_config::ProfileConfig::default(),
-    );
-
-    let toml_str = toml::to_string_pretty(&config).context("failed to serialize default config")?;
+    let toml_str = crate::config::default_config_toml()?;
     core_config::atomic_write(&config_path, toml_str.as_bytes())
         .context("failed to write config")?;
 
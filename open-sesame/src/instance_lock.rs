@@ -0,0 +1,186 @@
+//! Single-instance lock for the resident fast-path process.
+//!
+//! The lock file records the holder's PID and its kernel start time
+//! (`/proc/<pid>/stat` field 22). A PID alone is not enough: after a crash
+//! the PID can be reused by an unrelated process, and a plain `kill(pid, 0)`
+//! check would then treat the dead instance as alive forever. The holder
+//! also keeps an exclusive `flock` on the file, which the kernel drops when
+//! the process dies, so two processes racing to replace a stale lock cannot
+//! both win. The file itself is never unlinked: a process blocked on the old
+//! inode and one creating a new file would otherwise both hold "exclusive"
+//! locks. Releasing the lock empties the holder record instead.
+//!
+//! File format: `<pid> <start_time>`, or empty while no holder is published.
+
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, Write};
+use std::os::fd::AsRawFd;
+use std::path::Path;
+
+/// Identity of a lock holder.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Holder {
+    pub pid: i32,
+    pub start_time: u64,
+}
+
+impl Holder {
+    /// The current process.
+    fn current() -> Option<Self> {
+        let pid = i32::try_from(std::process::id()).ok()?;
+        Some(Self {
+            pid,
+            start_time: process_start_time(pid)?,
+        })
+    }
+
+    /// Whether this exact process is still running (PID alive and not
+    /// reused by a process that started later).
+    pub fn is_alive(&self) -> bool {
+        process_start_time(self.pid) == Some(self.start_time)
+    }
+
+    /// Read the holder recorded in `path`, if the file exists and parses.
+    pub fn read(path: &Path) -> Option<Self> {
+        parse(&std::fs::read_to_string(path).ok()?)
+    }
+}
+
+/// Exclusive ownership of a lock file. Released on drop.
+#[derive(Debug)]
+pub(crate) struct InstanceLock {
+    holder: Holder,
+    file: File,
+}
+
+impl InstanceLock {
+    /// Take the lock at `path`, replacing it if its holder has died.
+    ///
+    /// The holder record is cleared, not written: other processes only see
+    /// this instance once it calls `publish`. Returns `Ok(None)` if a live
+    /// instance holds it.
+    pub fn acquire(path: &Path) -> std::io::Result<Option<Self>> {
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)?;
+
+        // SAFETY: `flock` on a valid, owned fd with no pointer arguments.
+        if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) } != 0 {
+            return Ok(None);
+        }
+
+        // The flock is free, so whoever is recorded in the file has exited.
+        let mut contents = String::new();
+        file.read_to_string(&mut contents)?;
+        if let Some(previous) = parse(&contents) {
+            tracing::info!(pid = previous.pid, "replacing stale instance lock");
+        }
+
+        let holder = Holder::current().ok_or_else(|| {
+            std::io::Error::other("cannot read own process start time from /proc")
+        })?;
+        file.set_len(0)?;
+
+        Ok(Some(Self { holder, file }))
+    }
+
+    /// Record this process as the holder, so other processes can find and
+    /// probe it. Call once the holder is ready to serve them.
+    pub fn publish(&mut self) -> std::io::Result<()> {
+        self.file.set_len(0)?;
+        self.file.rewind()?;
+        write!(self.file, "{} {}", self.holder.pid, self.holder.start_time)
+    }
+
+    /// Forcibly release a lock whose holder is alive but unresponsive.
+    ///
+    /// Kills `holder` only if it is still the recorded holder; the kernel
+    /// then drops its `flock`, and the next `acquire` takes over the file.
+    /// `SIGKILL` rather than `SIGTERM`: a hung holder cannot run its signal
+    /// handler.
+    pub fn break_lock(path: &Path, holder: Holder) {
+        if Holder::read(path) == Some(holder) && holder.is_alive() {
+            tracing::warn!(
+                pid = holder.pid,
+                "breaking lock held by unresponsive instance"
+            );
+            // SAFETY: `kill` with a PID we verified against its start time.
+            unsafe {
+                libc::kill(holder.pid, libc::SIGKILL);
+            }
+        }
+    }
+}
+
+impl Drop for InstanceLock {
+    fn drop(&mut self) {
+        // Still under our flock, so the record is ours to clear.
+        let _ = self.file.set_len(0);
+    }
+}
+
+fn parse(contents: &str) -> Option<Holder> {
+    let mut fields = contents.split_whitespace();
+    let pid = fields.next()?.parse().ok()?;
+    let start_time = fields.next()?.parse().ok()?;
+    Some(Holder { pid, start_time })
+}
+
+/// Start time of `pid` in clock ticks since boot, or `None` if it is not
+/// running.
+fn process_start_time(pid: i32) -> Option<u64> {
+    let stat = std::fs::read_to_string(format!("/proc/{pid}/stat")).ok()?;
+    parse_start_time(&stat)
+}
+
+/// Field 22 of `/proc/<pid>/stat`. The command name (field 2) may contain
+/// spaces and parentheses, so fields are counted after its closing `)`.
+fn parse_start_time(stat: &str) -> Option<u64> {
+    let after_comm = &stat[stat.rfind(')')? + 1..];
+    // After `)`: field 3 (state) is index 0, so field 22 is index 19.
+    after_comm.split_whitespace().nth(19)?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn start_time_skips_command_name() {
+        let stat = "4242 (we ird) name) S 1 4242 4242 0 -1 4194560 100 0 0 0 \
+                    1 2 0 0 20 0 1 0 987654 12345 67 18446744073709551615";
+        assert_eq!(parse_start_time(stat), Some(987_654));
+    }
+
+    #[test]
+    fn reused_pid_is_not_alive() {
+        let me = Holder::current().unwrap();
+        assert!(me.is_alive());
+        let reused = Holder {
+            start_time: me.start_time + 1,
+            ..me
+        };
+        assert!(!reused.is_alive());
+    }
+
+    #[test]
+    fn stale_lock_is_replaced() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("instance.pid");
+        // A crashed holder: our PID, but a start time that never matches.
+        std::fs::write(&path, format!("{} 1", std::process::id())).unwrap();
+
+        let mut lock = InstanceLock::acquire(&path)
+            .unwrap()
+            .expect("stale lock taken over");
+        assert_eq!(Holder::read(&path), None);
+        lock.publish().unwrap();
+        assert_eq!(Holder::read(&path), Holder::current());
+        drop(lock);
+        assert!(path.exists());
+        assert_eq!(Holder::read(&path), None);
+    }
+}
@@ -67,24 +67,18 @@ pub(crate) async fn find_ssh_key_index(fingerprint: &str) -> anyhow::Result<usiz
 pub(crate) async fn connect() -> anyhow::Result<BusClient> {
     let socket_path = core_ipc::socket_path().context("failed to resolve IPC socket path")?;
 
-    let server_pub = core_ipc::noise::read_bus_public_key()
-        .await
-        .context("daemon-profile is not running (no bus public key found)")?;
-
-    let daemon_id = DaemonId::new();
-
-    // CLI uses ephemeral keypair — server assigns Open clearance for unknown keys.
-    let client_keypair =
-        core_ipc::generate_keypair().context("failed to generate ephemeral keypair")?;
-
-    let mut client = BusClient::connect_encrypted(
-        daemon_id,
-        &socket_path,
-        &server_pub,
-        client_keypair.as_inner(),
-    )
-    .await
-    .context("failed to connect to IPC bus — is daemon-profile running?")?;
+    // The bus may not be up yet: fresh login, stopped service, or a
+    // socket-activated daemon-profile whose bus.pub is missing or stale.
+    // Start the service and retry once.
+    let mut client = match connect_bus(&socket_path).await {
+        Ok(client) => client,
+        Err(e) => {
+            if !start_bus_service().await {
+                return Err(e);
+            }
+            connect_bus(&socket_path).await?
+        }
+    };
 
     // Populate origin_installation on outbound messages if installation.toml exists.
     if let Ok(install_config) = core_config::load_installation() {
@@ -105,6 +99,49 @@ pub(crate) async fn connect() -> anyhow::Result<BusClient> {
     Ok(client)
 }
 
+async fn connect_bus(socket_path: &std::path::Path) -> anyhow::Result<BusClient> {
+    let server_pub = core_ipc::noise::read_bus_public_key()
+        .await
+        .context("daemon-profile is not running (no bus public key found)")?;
+
+    let daemon_id = DaemonId::new();
+
+    // CLI uses ephemeral keypair — server assigns Open clearance for unknown keys.
+    let client_keypair =
+        core_ipc::generate_keypair().context("failed to generate ephemeral keypair")?;
+
+    BusClient::connect_encrypted(
+        daemon_id,
+        socket_path,
+        &server_pub,
+        client_keypair.as_inner(),
+    )
+    .await
+    .context("failed to connect to IPC bus — is daemon-profile running?")
+}
+
+/// Start daemon-profile through systemd. The unit is `Type=notify`, so
+/// `systemctl start` returns once the bus is bound and bus.pub written.
+/// Returns false if systemd is unavailable or the unit failed to start.
+#[cfg(target_os = "linux")]
+async fn start_bus_service() -> bool {
+    tracing::info!("IPC bus unreachable, starting open-sesame-profile.service");
+    tokio::task::spawn_blocking(|| {
+        std::process::Command::new("systemctl")
+            .args(["--user", "start", "open-sesame-profile.service"])
+            .stderr(std::process::Stdio::null())
+            .status()
+            .is_ok_and(|s| s.success())
+    })
+    .await
+    .unwrap_or(false)
+}
+
+#[cfg(not(target_os = "linux"))]
+async fn start_bus_service() -> bool {
+    false
+}
+
 /// Send an RPC request and wait for the correlated response.
 pub(crate) async fn rpc(
     client: &BusClient,
@@ -9,20 +9,28 @@
 //!   2 — timeout waiting for response
 
 mod audit;
+mod bundle;
 mod cli;
 mod clipboard;
+mod config;
 mod doctor;
 mod env;
 mod helpers;
 mod init;
 mod input;
+mod instance_lock;
 mod ipc;
 mod launch;
+#[cfg(all(target_os = "linux", feature = "desktop"))]
+mod mock;
 mod profile;
 mod secrets;
 mod snippets;
 mod ssh;
+mod state;
+mod stats;
 mod status;
+mod theme;
 mod unlock;
 mod wm;
 mod workspace;
@@ -124,6 +132,7 @@ async fn run(cli: Cli) -> anyhow::Result<()> {
             }
             SecretCmd::List { profile } => secrets::cmd_secret_list(&profile).await,
         },
+        Command::Config(sub) => config::cmd_config(sub),
         Command::Audit(sub) => match sub {
             AuditCmd::Verify => audit::cmd_audit_verify(),
             AuditCmd::Tail { count, follow } => audit::cmd_audit_tail(count, follow).await,
@@ -131,10 +140,43 @@ async fn run(cli: Cli) -> anyhow::Result<()> {
         Command::Wm(sub) => match sub {
             WmCmd::List => wm::cmd_wm_list().await,
             WmCmd::Switch { backward } => wm::cmd_wm_switch(backward).await,
-            WmCmd::Focus { window_id } => wm::cmd_wm_focus(&window_id).await,
-            WmCmd::Overlay { launcher, backward } => wm::cmd_wm_overlay(launcher, backward).await,
+            WmCmd::Focus { window_id, silent } => wm::cmd_wm_focus(&window_id, silent).await,
+            WmCmd::Mark { mark } => wm::cmd_wm_mark(mark).await,
+            WmCmd::Overlay {
+                launcher,
+                backward,
+                query,
+                output,
+                timings,
+            } => wm::cmd_wm_overlay(launcher, backward, query, output, timings).await,
+            WmCmd::Warmup => wm::cmd_wm_warmup().await,
             WmCmd::OverlayResident => wm::cmd_wm_overlay_resident().await,
         },
+        Command::Theme(sub) => match sub {
+            ThemeCmd::List => theme::cmd_theme_list(),
+            ThemeCmd::Preview {
+                theme,
+                config,
+                output,
+            } => theme::cmd_theme_preview(theme.as_deref(), config.as_deref(), &output).await,
+        },
+        Command::Stats {
+            since,
+            json,
+            timing,
+            focus_time,
+        } => {
+            if timing {
+                stats::cmd_stats_timing(json)
+            } else if focus_time {
+                stats::cmd_stats_focus_time(since.as_deref(), json)
+            } else {
+                stats::cmd_stats(since.as_deref(), json)
+            }
+        }
+        Command::State(sub) => match sub {
+            StateCmd::Vacuum => state::cmd_state_vacuum(),
+        },
         Command::Launch(sub) => match sub {
             LaunchCmd::Search {
                 query,
@@ -180,6 +222,16 @@ async fn run(cli: Cli) -> anyhow::Result<()> {
         Command::KeybindingStatus => {
             platform_linux::cosmic_keys::keybinding_status().map_err(|e| anyhow::anyhow!("{e}"))
         }
+        #[cfg(all(target_os = "linux", feature = "desktop"))]
+        Command::InstallAutostart => {
+            let exe = std::env::current_exe()
+                .map_err(|e| anyhow::anyhow!("cannot locate the sesame binary: {e}"))?;
+            platform_linux::autostart::install_autostart(&exe).map_err(|e| anyhow::anyhow!("{e}"))
+        }
+        #[cfg(all(target_os = "linux", feature = "desktop"))]
+        Command::RemoveAutostart => {
+            platform_linux::autostart::remove_autostart().map_err(|e| anyhow::anyhow!("{e}"))
+        }
         Command::Clone {
             url,
             depth,
@@ -211,10 +263,16 @@ async fn run(cli: Cli) -> anyhow::Result<()> {
             command,
         } => env::cmd_env(profile.as_deref(), prefix.as_deref(), &command).await,
         Command::Export {
+            bundle: Some(bundle),
+            ..
+        } => bundle::cmd_export_bundle(&bundle),
+        Command::Export {
+            bundle: None,
             profile,
             format,
             prefix,
         } => env::cmd_export(profile.as_deref(), &format, prefix.as_deref()).await,
+        Command::Import { bundle, force } => bundle::cmd_import_bundle(&bundle, force),
         Command::Workspace(sub) => workspace::cmd_workspace(sub).await,
     }
 }
@@ -0,0 +1,90 @@
+//! `SESAME_BACKEND=mock` — window-manager commands against a fixture file.
+//!
+//! CI runners have no Wayland display and no running daemons. With
+//! `SESAME_BACKEND=mock`, `sesame wm list|switch|focus` ask the platform
+//! mock compositor (`platform_linux::compositor::MockBackend`) for windows
+//! instead of daemon-wm over IPC. It reads the TOML file named by
+//! `SESAME_MOCK_FIXTURE`, the same fixture daemon-wm's compositor backend
+//! and `sesame status --doctor platform` use, with the same format and
+//! checks. Activations focus a window in memory and never change the file.
+
+use core_types::Window;
+use platform_linux::compositor::{BACKEND_ENV, CompositorBackend, MockBackend};
+
+/// The mock compositor when `SESAME_BACKEND=mock`, or `None` for the real
+/// daemons.
+pub(crate) fn fixture() -> anyhow::Result<Option<MockBackend>> {
+    if std::env::var(BACKEND_ENV).as_deref() != Ok("mock") {
+        return Ok(None);
+    }
+    let backend = MockBackend::from_env().map_err(|e| anyhow::anyhow!("{e}"))?;
+    Ok(Some(backend))
+}
+
+/// The fixture windows in MRU order, as daemon-wm lists them.
+pub(crate) async fn windows(backend: &MockBackend) -> anyhow::Result<Vec<Window>> {
+    backend
+        .list_windows()
+        .await
+        .map_err(|e| anyhow::anyhow!("{e}"))
+}
+
+/// Whether activating `window_id` (a window ID or app ID, matched like
+/// daemon-wm does) succeeds.
+pub(crate) async fn activate(backend: &MockBackend, window_id: &str) -> anyhow::Result<bool> {
+    let windows = windows(backend).await?;
+    let Some(target) = windows
+        .iter()
+        .find(|w| w.id.to_string() == window_id || w.app_id.as_str() == window_id)
+    else {
+        return Ok(false);
+    };
+    Ok(backend.activate_window(&target.id).await.is_ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURE: &str = r#"
+[[windows]]
+app_id = "firefox"
+title = "Inbox"
+focused = true
+
+[[windows]]
+app_id = "com.mitchellh.ghostty"
+title = "~/src"
+"#;
+
+    fn backend(contents: &str) -> MockBackend {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("fixture.toml");
+        std::fs::write(&path, contents).unwrap();
+        MockBackend::from_fixture(&path).unwrap()
+    }
+
+    fn block_on<T>(fut: impl std::future::Future<Output = T>) -> T {
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(fut)
+    }
+
+    #[test]
+    fn activation_needs_a_matching_window_and_support() {
+        let fixture = backend(FIXTURE);
+        assert!(block_on(activate(&fixture, "com.mitchellh.ghostty")).unwrap());
+        assert!(
+            block_on(activate(
+                &fixture,
+                "win-00000000-0000-0000-0000-000000000001"
+            ))
+            .unwrap()
+        );
+        assert!(!block_on(activate(&fixture, "slack")).unwrap());
+
+        let fixture = backend(&format!("{FIXTURE}\n[compositor]\ncan_activate = false\n"));
+        assert!(!block_on(activate(&fixture, "firefox")).unwrap());
+    }
+}
@@ -0,0 +1,65 @@
+//! `sesame state vacuum` — maintenance of daemon-wm's state database.
+//!
+//! The MRU stack, marks, window order, release timings, usage log and
+//! focus-time log live in one `SQLite` database under `$XDG_STATE_HOME`
+//! (`core-state`). Deleted rows — trimmed usage events, closed windows —
+//! leave free pages behind; vacuuming rebuilds the file without them. It is
+//! safe while daemon-wm runs: the daemon's writes wait briefly or are
+//! dropped, as when its cache files were locked.
+
+use anyhow::Context;
+use core_state::StateStore;
+use owo_colors::OwoColorize;
+
+/// Open the state database, importing the cache files of older versions
+/// first (as daemon-wm does at startup).
+pub(crate) fn open_store() -> anyhow::Result<StateStore> {
+    let path = StateStore::default_path().context("no state directory")?;
+    let mut store = StateStore::open(&path)
+        .map_err(|e| anyhow::anyhow!("{e}"))
+        .with_context(|| format!("failed to open {}", path.display()))?;
+    if let Ok(cache) = crate::helpers::wm_cache_dir() {
+        store
+            .import_legacy(&cache)
+            .map_err(|e| anyhow::anyhow!("{e}"))?;
+    }
+    Ok(store)
+}
+
+pub(crate) fn cmd_state_vacuum() -> anyhow::Result<()> {
+    let store = open_store()?;
+    let report = store.vacuum().map_err(|e| anyhow::anyhow!("{e}"))?;
+    println!(
+        "{} {}: {} -> {}",
+        "Vacuumed".green(),
+        store.path().unwrap_or("state database"),
+        format_bytes(report.before_bytes),
+        format_bytes(report.after_bytes).bold()
+    );
+    Ok(())
+}
+
+/// `bytes` in KiB or MiB.
+fn format_bytes(bytes: u64) -> String {
+    if bytes < 1024 * 1024 {
+        format!("{} KiB", bytes.div_ceil(1024))
+    } else {
+        format!(
+            "{}.{} MiB",
+            bytes / (1024 * 1024),
+            bytes % (1024 * 1024) * 10 / (1024 * 1024)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sizes_read_in_kib_and_mib() {
+        assert_eq!(format_bytes(4096), "4 KiB");
+        assert_eq!(format_bytes(4097), "5 KiB");
+        assert_eq!(format_bytes(3 * 1024 * 1024 + 512 * 1024), "3.5 MiB");
+    }
+}
@@ -0,0 +1,496 @@
+//! `sesame stats` — summary of daemon-wm's local usage log.
+//!
+//! daemon-wm appends one `UsageEvent` per overlay activation outcome to its
+//! state database (`wm.usage_stats`). This reads it directly, so it works
+//! without the daemons running, and lists the key
+//! bindings that were never used so they can be pruned. `--timing` shows
+//! the Alt-release timings learned for `wm.adaptive_quick_switch` instead,
+//! and `--focus-time` the per-app totals of the opt-in focus-time log
+//! (`wm.focus_time`).
+
+use anyhow::Context;
+use comfy_table::{Table, presets::UTF8_FULL};
+use core_config::WmKeyBinding;
+use core_types::{FocusInterval, ReleaseTimings, UsageEvent, UsageKind};
+use owo_colors::OwoColorize;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Usage summary printed by `sesame stats`, and its `--json` output.
+#[derive(Debug, Default, PartialEq, Eq, Serialize)]
+pub(crate) struct UsageSummary {
+    /// Unix time of the oldest event counted.
+    pub first_event: Option<u64>,
+    pub activations: u64,
+    pub launches: u64,
+    pub cancels: u64,
+    /// Median milliseconds from overlay activation to an activation or
+    /// launch.
+    pub median_select_ms: Option<u64>,
+    /// Apps by use, most used first.
+    pub apps: Vec<AppUsage>,
+    /// Every configured key binding, in key order, with its uses.
+    pub keys: Vec<KeyUsage>,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub(crate) struct AppUsage {
+    pub app: String,
+    pub activations: u64,
+    pub launches: u64,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub(crate) struct KeyUsage {
+    pub key: String,
+    pub uses: u64,
+}
+
+pub(crate) fn cmd_stats(since: Option<&str>, json: bool) -> anyhow::Result<()> {
+    let cutoff = cutoff(since)?;
+    let events = crate::state::open_store()?
+        .usage_events(cutoff)
+        .map_err(|e| anyhow::anyhow!("{e}"))?;
+    let wm = crate::theme::current_wm_config();
+    let summary = summarize(events, &wm.key_bindings);
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&summary)?);
+        return Ok(());
+    }
+    if !wm.usage_stats {
+        eprintln!(
+            "{} wm.usage_stats is off; no new activations are recorded.",
+            "note:".yellow()
+        );
+    }
+    print_summary(&summary);
+    Ok(())
+}
+
+/// Focus-time totals printed by `sesame stats --focus-time`, and its
+/// `--json` output.
+#[derive(Debug, Default, PartialEq, Eq, Serialize)]
+pub(crate) struct FocusSummary {
+    /// Path of the state database holding the focus-time log.
+    pub log: String,
+    /// Seconds across all apps.
+    pub total_secs: u64,
+    /// Apps by time, most first.
+    pub apps: Vec<AppFocus>,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub(crate) struct AppFocus {
+    pub app: String,
+    pub secs: u64,
+    /// Intervals counted: switches to the app through sesame.
+    pub switches: u64,
+}
+
+pub(crate) fn cmd_stats_focus_time(since: Option<&str>, json: bool) -> anyhow::Result<()> {
+    let cutoff = cutoff(since)?;
+    let store = crate::state::open_store()?;
+    let intervals = store
+        .focus_intervals(cutoff)
+        .map_err(|e| anyhow::anyhow!("{e}"))?;
+    let mut summary = summarize_focus(intervals, cutoff);
+    summary.log = store.path().unwrap_or_default().to_string();
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&summary)?);
+        return Ok(());
+    }
+    if !crate::theme::current_wm_config().focus_time {
+        eprintln!(
+            "{} wm.focus_time is off; set it to true to record time between switches.",
+            "note:".yellow()
+        );
+    }
+    print_focus(&summary);
+    Ok(())
+}
+
+/// Per-app totals of `intervals`, counting only the part of each interval
+/// at or after `cutoff`.
+fn summarize_focus(
+    intervals: impl IntoIterator<Item = FocusInterval>,
+    cutoff: Option<u64>,
+) -> FocusSummary {
+    let mut apps: HashMap<String, AppFocus> = HashMap::new();
+    for interval in intervals {
+        let start = cutoff.map_or(interval.start, |cutoff| interval.start.max(cutoff));
+        let secs = interval.end.saturating_sub(start);
+        if secs == 0 {
+            continue;
+        }
+        let app = apps.entry(interval.app_id.clone()).or_insert(AppFocus {
+            app: interval.app_id,
+            secs: 0,
+            switches: 0,
+        });
+        app.secs += secs;
+        app.switches += 1;
+    }
+    let mut apps: Vec<AppFocus> = apps.into_values().collect();
+    apps.sort_by(|a, b| b.secs.cmp(&a.secs).then_with(|| a.app.cmp(&b.app)));
+    FocusSummary {
+        log: String::new(),
+        total_secs: apps.iter().map(|a| a.secs).sum(),
+        apps,
+    }
+}
+
+fn print_focus(summary: &FocusSummary) {
+    if summary.apps.is_empty() {
+        println!("{}", "No focus time recorded yet.".dimmed());
+        println!("Log: {}", summary.log);
+        return;
+    }
+    println!(
+        "{} tracked across {} apps",
+        format_duration(summary.total_secs).bold(),
+        summary.apps.len()
+    );
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL);
+    table.set_header(vec!["App", "Time", "Share", "Switches"]);
+    for app in &summary.apps {
+        let share = app.secs * 100 / summary.total_secs.max(1);
+        table.add_row(vec![
+            app.app.clone(),
+            format_duration(app.secs),
+            format!("{share}%"),
+            app.switches.to_string(),
+        ]);
+    }
+    println!("{table}");
+    println!("Log: {}", summary.log);
+}
+
+/// `secs` as hours and minutes, or seconds under a minute.
+fn format_duration(secs: u64) -> String {
+    match secs {
+        0..60 => format!("{secs}s"),
+        60..3600 => format!("{}m", secs / 60),
+        _ => format!("{}h {:02}m", secs / 3600, secs % 3600 / 60),
+    }
+}
+
+/// Learned release timing printed by `sesame stats --timing`, and its
+/// `--json` output.
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub(crate) struct TimingSummary {
+    /// Whether `wm.adaptive_quick_switch` is on.
+    pub adaptive: bool,
+    pub samples: usize,
+    pub median_ms: Option<u32>,
+    pub p90_ms: Option<u32>,
+    /// `wm.quick_switch_threshold_ms`.
+    pub configured_ms: u32,
+    /// Threshold fitted to the samples, once there are enough.
+    pub learned_ms: Option<u32>,
+}
+
+pub(crate) fn cmd_stats_timing(json: bool) -> anyhow::Result<()> {
+    let timings = crate::state::open_store()?
+        .release_timings()
+        .map_err(|e| anyhow::anyhow!("{e}"))?;
+    let wm = crate::theme::current_wm_config();
+    let summary = summarize_timing(&timings, &wm);
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&summary)?);
+        return Ok(());
+    }
+    if !wm.adaptive_quick_switch {
+        eprintln!(
+            "{} wm.adaptive_quick_switch is off; no releases are recorded.",
+            "note:".yellow()
+        );
+    }
+    print_timing(&summary);
+    Ok(())
+}
+
+fn summarize_timing(timings: &ReleaseTimings, wm: &core_config::WmConfig) -> TimingSummary {
+    TimingSummary {
+        adaptive: wm.adaptive_quick_switch,
+        samples: timings.samples_ms.len(),
+        median_ms: timings.percentile(50),
+        p90_ms: timings.percentile(90),
+        configured_ms: wm.quick_switch_threshold_ms,
+        learned_ms: timings.learned_threshold_ms(),
+    }
+}
+
+fn print_timing(summary: &TimingSummary) {
+    println!(
+        "{} quick-switch releases recorded ({} needed to learn)",
+        summary.samples.bold(),
+        ReleaseTimings::MIN_SAMPLES
+    );
+    if let (Some(median), Some(p90)) = (summary.median_ms, summary.p90_ms) {
+        println!("Alt held: median {median} ms, 90th percentile {p90} ms");
+    }
+    let in_use = summary.learned_ms.filter(|_| summary.adaptive);
+    println!(
+        "Configured threshold: {} ms{}",
+        summary.configured_ms,
+        if in_use.is_none() { " (in use)" } else { "" }
+    );
+    if let Some(learned) = summary.learned_ms {
+        println!(
+            "Learned threshold: {learned} ms{}",
+            if in_use.is_some() { " (in use)" } else { "" }
+        );
+    }
+}
+
+/// The Unix time a `--since` value reaches back to.
+fn cutoff(since: Option<&str>) -> anyhow::Result<Option<u64>> {
+    let Some(since) = since else {
+        return Ok(None);
+    };
+    let secs = parse_since(since)
+        .with_context(|| format!("invalid --since '{since}' (expected e.g. 30m, 12h, 7d or 4w)"))?;
+    Ok(Some(unix_now().saturating_sub(secs)))
+}
+
+/// Seconds in a `--since` value: a number followed by `s`, `m`, `h`, `d`
+/// or `w`.
+fn parse_since(since: &str) -> Option<u64> {
+    let since = since.trim();
+    let unit = since.chars().last()?;
+    let count: u64 = since[..since.len() - unit.len_utf8()].parse().ok()?;
+    let scale = match unit {
+        's' => 1,
+        'm' => 60,
+        'h' => 3600,
+        'd' => 86_400,
+        'w' => 7 * 86_400,
+        _ => return None,
+    };
+    count.checked_mul(scale)
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// Summarize usage `events` (already limited to `--since`).
+fn summarize(
+    events: impl IntoIterator<Item = UsageEvent>,
+    key_bindings: &BTreeMap<String, WmKeyBinding>,
+) -> UsageSummary {
+    let mut summary = UsageSummary::default();
+    let mut apps: HashMap<String, AppUsage> = HashMap::new();
+    let mut key_uses: HashMap<String, u64> = HashMap::new();
+    let mut select_ms = Vec::new();
+    for event in events {
+        summary.first_event = Some(summary.first_event.map_or(event.at, |t| t.min(event.at)));
+        match event.kind {
+            UsageKind::Cancel => {
+                summary.cancels += 1;
+                continue;
+            }
+            UsageKind::Activate => summary.activations += 1,
+            UsageKind::Launch => summary.launches += 1,
+        }
+        select_ms.push(event.elapsed_ms);
+        if let Some(key) = event.key {
+            *key_uses.entry(key).or_default() += 1;
+        }
+        if let Some(app) = event.app {
+            let usage = apps.entry(app.clone()).or_insert(AppUsage {
+                app,
+                activations: 0,
+                launches: 0,
+            });
+            match event.kind {
+                UsageKind::Launch => usage.launches += 1,
+                _ => usage.activations += 1,
+            }
+        }
+    }
+    select_ms.sort_unstable();
+    summary.median_select_ms = select_ms.get(select_ms.len() / 2).copied();
+    summary.apps = apps.into_values().collect();
+    summary.apps.sort_by(|a, b| {
+        (b.activations + b.launches)
+            .cmp(&(a.activations + a.launches))
+            .then_with(|| a.app.cmp(&b.app))
+    });
+    summary.keys = key_bindings
+        .keys()
+        .map(|key| KeyUsage {
+            key: key.clone(),
+            uses: key_uses.get(key).copied().unwrap_or(0),
+        })
+        .collect();
+    summary
+}
+
+fn print_summary(summary: &UsageSummary) {
+    let Some(first) = summary.first_event else {
+        println!("{}", "No overlay usage recorded yet.".dimmed());
+        return;
+    };
+    let days = unix_now().saturating_sub(first) / 86_400;
+    println!(
+        "{} activations, {} launches, {} cancelled over {} day{}",
+        summary.activations.bold(),
+        summary.launches.bold(),
+        summary.cancels.bold(),
+        days.max(1),
+        if days > 1 { "s" } else { "" }
+    );
+    if let Some(ms) = summary.median_select_ms {
+        println!("Median time to select: {ms} ms");
+    }
+
+    if !summary.apps.is_empty() {
+        let mut table = Table::new();
+        table.load_preset(UTF8_FULL);
+        table.set_header(vec!["App", "Activations", "Launches"]);
+        for app in &summary.apps {
+            table.add_row(vec![
+                app.app.clone(),
+                app.activations.to_string(),
+                app.launches.to_string(),
+            ]);
+        }
+        println!("{table}");
+    }
+
+    let unused: Vec<&str> = summary
+        .keys
+        .iter()
+        .filter(|k| k.uses == 0)
+        .map(|k| k.key.as_str())
+        .collect();
+    if !unused.is_empty() {
+        println!(
+            "{} {}",
+            "Key bindings never used:".yellow(),
+            unused.join(", ")
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_since_units() {
+        assert_eq!(parse_since("7d"), Some(7 * 86_400));
+        assert_eq!(parse_since("90m"), Some(5400));
+        assert_eq!(parse_since("2w"), Some(14 * 86_400));
+        assert_eq!(parse_since("7"), None);
+        assert_eq!(parse_since("d"), None);
+        assert_eq!(parse_since("1y"), None);
+    }
+
+    #[test]
+    fn timing_summary_reports_the_learned_threshold() {
+        let mut wm = core_config::WmConfig::default();
+        let mut timings = ReleaseTimings::default();
+        let summary = summarize_timing(&timings, &wm);
+        assert_eq!(
+            (summary.samples, summary.median_ms, summary.learned_ms),
+            (0, None, None)
+        );
+        assert_eq!(summary.configured_ms, 250);
+
+        wm.adaptive_quick_switch = true;
+        for _ in 0..ReleaseTimings::MIN_SAMPLES {
+            timings.record(200);
+        }
+        let summary = summarize_timing(&timings, &wm);
+        assert!(summary.adaptive);
+        assert_eq!((summary.median_ms, summary.p90_ms), (Some(200), Some(200)));
+        assert_eq!(summary.learned_ms, Some(250));
+    }
+
+    #[test]
+    fn summarize_counts_since_cutoff_and_lists_unused_keys() {
+        let store = core_state::StateStore::open_in_memory().unwrap();
+        for line in [
+            r#"{"at":10,"kind":"activate","app":"firefox","key":"f","elapsed_ms":900}"#,
+            r#"{"at":100,"kind":"activate","app":"firefox","key":"f","elapsed_ms":300}"#,
+            r#"{"at":101,"kind":"launch","app":"ghostty","key":"g","elapsed_ms":500}"#,
+            r#"{"at":102,"kind":"activate","app":"kitty","elapsed_ms":100}"#,
+            r#"{"at":103,"kind":"cancel","elapsed_ms":2000}"#,
+        ] {
+            store
+                .append_usage(&serde_json::from_str(line).unwrap())
+                .unwrap();
+        }
+        let key_bindings = core_config::WmConfig::default().key_bindings;
+        let summary = summarize(store.usage_events(Some(100)).unwrap(), &key_bindings);
+        assert_eq!(summary.first_event, Some(100));
+        assert_eq!(
+            (summary.activations, summary.launches, summary.cancels),
+            (2, 1, 1)
+        );
+        assert_eq!(summary.median_select_ms, Some(300));
+        assert_eq!(
+            summary.apps[0],
+            AppUsage {
+                app: "firefox".into(),
+                activations: 1,
+                launches: 0,
+            }
+        );
+        let uses = |key: &str| summary.keys.iter().find(|k| k.key == key).unwrap().uses;
+        assert_eq!((uses("f"), uses("g"), uses("t")), (1, 1, 0));
+        assert_eq!(summary.keys.len(), key_bindings.len());
+
+        let all = summarize(store.usage_events(None).unwrap(), &key_bindings);
+        assert_eq!(all.activations, 3);
+    }
+
+    #[test]
+    fn focus_summary_totals_per_app_and_clips_to_cutoff() {
+        let log: Vec<FocusInterval> = [
+            (0, 100, "firefox"),
+            (100, 400, "kitty"),
+            (400, 460, "firefox"),
+        ]
+        .into_iter()
+        .map(|(start, end, app_id)| FocusInterval {
+            start,
+            end,
+            app_id: app_id.into(),
+        })
+        .collect();
+        let summary = summarize_focus(log.clone(), None);
+        assert_eq!(summary.total_secs, 460);
+        assert_eq!(
+            summary.apps[0],
+            AppFocus {
+                app: "kitty".into(),
+                secs: 300,
+                switches: 1,
+            }
+        );
+        assert_eq!((summary.apps[1].secs, summary.apps[1].switches), (160, 2));
+
+        // The first interval ends before the cutoff; kitty's straddles it.
+        let summary = summarize_focus(log, Some(300));
+        assert_eq!(summary.total_secs, 160);
+        assert_eq!(summary.apps[0].app, "kitty");
+        assert_eq!(summary.apps[1].switches, 1);
+    }
+
+    #[test]
+    fn durations_read_as_hours_and_minutes() {
+        assert_eq!(format_duration(42), "42s");
+        assert_eq!(format_duration(125), "2m");
+        assert_eq!(format_duration(3 * 3600 + 7 * 60 + 5), "3h 07m");
+    }
+}
@@ -0,0 +1,136 @@
+//! `sesame theme list|preview` — built-in overlay color themes.
+//!
+//! Previews are rendered by daemon-wm, which owns the fonts and the
+//! renderer, and come back over IPC as a PNG. Nothing is written to the
+//! config; a preset is used by copying its colors into the `wm` section.
+
+use anyhow::Context;
+use core_types::{EventKind, SecurityLevel, ThemeColors};
+use owo_colors::OwoColorize;
+use std::path::Path;
+
+use crate::ipc::{connect, rpc};
+
+pub(crate) fn cmd_theme_list() -> anyhow::Result<()> {
+    for preset in core_config::THEME_PRESETS {
+        println!(
+            "{:<18} card {}  text {}  hints {} / {}",
+            preset.name.bold(),
+            preset.card_color,
+            preset.text_color,
+            preset.hint_color,
+            preset.hint_matched_color,
+        );
+    }
+    Ok(())
+}
+
+pub(crate) async fn cmd_theme_preview(
+    theme: Option<&str>,
+    config: Option<&Path>,
+    output: &Path,
+) -> anyhow::Result<()> {
+    let mut wm = match config {
+        Some(path) => wm_config_from_file(path)?,
+        None => current_wm_config(),
+    };
+    if let Some(name) = theme {
+        let preset = core_config::theme_preset(name).with_context(|| {
+            let names: Vec<&str> = core_config::THEME_PRESETS.iter().map(|p| p.name).collect();
+            format!("unknown theme '{name}' (available: {})", names.join(", "))
+        })?;
+        wm.set_theme_colors(preset.colors());
+    }
+    for warning in config_warnings(&wm) {
+        eprintln!("{} {warning}", "warning:".yellow());
+    }
+
+    let colors = wm.theme_colors();
+    let client = connect().await?;
+    let result = rpc(
+        &client,
+        EventKind::WmThemePreview {
+            colors: colors.clone(),
+        },
+        SecurityLevel::Internal,
+    )
+    .await;
+    client.shutdown().await;
+    match result? {
+        EventKind::WmThemePreviewResponse { png, error: None } if !png.is_empty() => {
+            std::fs::write(output, &png)
+                .with_context(|| format!("failed to write {}", output.display()))?;
+            println!("{} {}", "Preview written to".green(), output.display());
+        }
+        EventKind::WmThemePreviewResponse { error, .. } => {
+            anyhow::bail!(
+                "daemon-wm could not render the preview: {}",
+                error.unwrap_or_else(|| "empty image".into())
+            );
+        }
+        other => anyhow::bail!("unexpected response: {other:?}"),
+    }
+
+    if theme.is_some() {
+        println!("\nTo use this theme, set in your profile's [wm] section:");
+        print_colors(&colors);
+    }
+    Ok(())
+}
+
+/// The `wm` section daemon-wm uses (first profile), or the defaults.
+pub(crate) fn current_wm_config() -> core_config::WmConfig {
+    match core_config::load_config(None) {
+        Ok(config) => config
+            .profiles
+            .into_values()
+            .next()
+            .map(|p| p.wm)
+            .unwrap_or_default(),
+        Err(e) => {
+            eprintln!("{} config: {e}; using defaults", "warning:".yellow());
+            core_config::WmConfig::default()
+        }
+    }
+}
+
+fn wm_config_from_file(path: &Path) -> anyhow::Result<core_config::WmConfig> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    let config: core_config::Config =
+        toml::from_str(&contents).with_context(|| format!("failed to parse {}", path.display()))?;
+    Ok(config
+        .profiles
+        .into_values()
+        .next()
+        .map(|p| p.wm)
+        .unwrap_or_default())
+}
+
+/// Validator messages for `wm`, e.g. low text contrast.
+fn config_warnings(wm: &core_config::WmConfig) -> Vec<String> {
+    let mut config = core_config::Config::default();
+    let profile = core_config::ProfileConfig {
+        name: core_types::TrustProfileName::try_from("default").expect("valid profile name"),
+        wm: wm.clone(),
+        ..Default::default()
+    };
+    config.profiles.insert("default".into(), profile);
+    core_config::validate(&config)
+        .into_iter()
+        .map(|d| d.message)
+        .collect()
+}
+
+fn print_colors(colors: &ThemeColors) {
+    for (key, value) in [
+        ("border_color", &colors.border_color),
+        ("background_color", &colors.background_color),
+        ("card_color", &colors.card_color),
+        ("text_color", &colors.text_color),
+        ("hint_color", &colors.hint_color),
+        ("hint_matched_color", &colors.hint_matched_color),
+    ] {
+        println!("  {key} = \"{value}\"");
+    }
+}
@@ -4,52 +4,130 @@ use core_types::{EventKind, SecurityLevel};
 use owo_colors::OwoColorize;
 use std::time::Duration;
 
+use crate::instance_lock::{Holder, InstanceLock};
 use crate::ipc::{connect, rpc};
+#[cfg(all(target_os = "linux", feature = "desktop"))]
+use crate::mock;
+
+/// Where window listings and activations come from: daemon-wm over IPC, or
+/// a fixture with `SESAME_BACKEND=mock`.
+enum Backend {
+    Daemon(Box<core_ipc::BusClient>),
+    #[cfg(all(target_os = "linux", feature = "desktop"))]
+    Mock(Box<platform_linux::compositor::MockBackend>),
+}
 
-pub(crate) async fn cmd_wm_list() -> anyhow::Result<()> {
-    let client = connect().await?;
+impl Backend {
+    async fn connect() -> anyhow::Result<Self> {
+        #[cfg(all(target_os = "linux", feature = "desktop"))]
+        if let Some(fixture) = mock::fixture()? {
+            return Ok(Self::Mock(Box::new(fixture)));
+        }
+        Ok(Self::Daemon(Box::new(connect().await?)))
+    }
 
-    match rpc(&client, EventKind::WmListWindows, SecurityLevel::Internal).await? {
-        EventKind::WmListWindowsResponse { windows } => {
-            if windows.is_empty() {
-                println!("{}", "No windows tracked.".dimmed());
-                return Ok(());
+    /// Windows in MRU order (most recent first).
+    async fn list_windows(&self) -> anyhow::Result<Vec<core_types::Window>> {
+        match self {
+            Self::Daemon(client) => {
+                match rpc(client, EventKind::WmListWindows, SecurityLevel::Internal).await? {
+                    EventKind::WmListWindowsResponse { windows } => Ok(windows),
+                    other => anyhow::bail!("unexpected response: {other:?}"),
+                }
             }
+            #[cfg(all(target_os = "linux", feature = "desktop"))]
+            Self::Mock(fixture) => mock::windows(fixture).await,
+        }
+    }
 
-            let mut table = Table::new();
-            table.load_preset(UTF8_FULL);
-            table.set_header(vec!["ID", "App", "Title", "Focused"]);
+    /// Activate a window by ID or app ID; `false` if it was not found.
+    async fn activate(&self, window_id: &str, silent: bool) -> anyhow::Result<bool> {
+        match self {
+            Self::Daemon(client) => match rpc(
+                client,
+                EventKind::WmActivateWindow {
+                    window_id: window_id.to_owned(),
+                    silent,
+                },
+                SecurityLevel::Internal,
+            )
+            .await?
+            {
+                EventKind::WmActivateWindowResponse { success } => Ok(success),
+                other => anyhow::bail!("unexpected response: {other:?}"),
+            },
+            #[cfg(all(target_os = "linux", feature = "desktop"))]
+            Self::Mock(fixture) => mock::activate(fixture, window_id).await,
+        }
+    }
 
-            for w in &windows {
-                let focused = if w.is_focused {
-                    "yes".green().to_string()
-                } else {
-                    "".to_string()
-                };
-                table.add_row(vec![
-                    &w.id.to_string(),
-                    &w.app_id.to_string(),
-                    &w.title,
-                    &focused,
-                ]);
+    /// Activate the window `mark` names; `false` if the mark is not set or
+    /// its window closed.
+    async fn activate_mark(&self, mark: char) -> anyhow::Result<bool> {
+        match self {
+            Self::Daemon(client) => match rpc(
+                client,
+                EventKind::WmActivateMark { mark },
+                SecurityLevel::Internal,
+            )
+            .await?
+            {
+                EventKind::WmActivateWindowResponse { success } => Ok(success),
+                other => anyhow::bail!("unexpected response: {other:?}"),
+            },
+            #[cfg(all(target_os = "linux", feature = "desktop"))]
+            Self::Mock(_) => {
+                anyhow::bail!("marks are kept by daemon-wm; not available in mock mode")
             }
-
-            println!("{table}");
         }
-        other => anyhow::bail!("unexpected response: {other:?}"),
+    }
+}
+
+/// Apply `global.logging.redact_titles_in_logs` to the titles this command
+/// prints. An unreadable config leaves titles shown.
+fn apply_title_redaction() {
+    if let Ok(config) = core_config::load_config(None) {
+        core_types::set_redact_titles(config.global.logging.redact_titles_in_logs);
+    }
+}
+
+pub(crate) async fn cmd_wm_list() -> anyhow::Result<()> {
+    apply_title_redaction();
+    let backend = Backend::connect().await?;
+    let windows = backend.list_windows().await?;
+    if windows.is_empty() {
+        println!("{}", "No windows tracked.".dimmed());
+        return Ok(());
     }
 
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL);
+    table.set_header(vec!["ID", "App", "Title", "Focused"]);
+
+    for w in &windows {
+        let focused = if w.is_focused {
+            "yes".green().to_string()
+        } else {
+            "".to_string()
+        };
+        table.add_row(vec![
+            &w.id.to_string(),
+            &w.app_id.to_string(),
+            &core_types::redacted_title(&w.title).to_string(),
+            &focused,
+        ]);
+    }
+
+    println!("{table}");
     Ok(())
 }
 
 pub(crate) async fn cmd_wm_switch(backward: bool) -> anyhow::Result<()> {
-    let client = connect().await?;
+    apply_title_redaction();
+    let backend = Backend::connect().await?;
 
     // List windows, pick next/previous in MRU order.
-    let windows = match rpc(&client, EventKind::WmListWindows, SecurityLevel::Internal).await? {
-        EventKind::WmListWindowsResponse { windows } => windows,
-        other => anyhow::bail!("unexpected response: {other:?}"),
-    };
+    let windows = backend.list_windows().await?;
 
     if windows.is_empty() {
         println!("{}", "No windows to switch to.".dimmed());
@@ -67,76 +145,159 @@ pub(crate) async fn cmd_wm_switch(backward: bool) -> anyhow::Result<()> {
 
     let target_id = windows[target_idx].id.to_string();
 
-    match rpc(
-        &client,
-        EventKind::WmActivateWindow {
-            window_id: target_id.clone(),
-        },
-        SecurityLevel::Internal,
-    )
-    .await?
-    {
-        EventKind::WmActivateWindowResponse { success: true } => {
-            println!(
-                "Switched to: {} ({})",
-                windows[target_idx].title.green(),
-                windows[target_idx].app_id,
-            );
-        }
-        EventKind::WmActivateWindowResponse { success: false } => {
-            anyhow::bail!("failed to activate window '{target_id}'");
-        }
-        other => anyhow::bail!("unexpected response: {other:?}"),
+    if !backend.activate(&target_id, false).await? {
+        anyhow::bail!("failed to activate window '{target_id}'");
     }
+    println!(
+        "Switched to: {} ({})",
+        core_types::redacted_title(&windows[target_idx].title).green(),
+        windows[target_idx].app_id,
+    );
 
     Ok(())
 }
 
-pub(crate) async fn cmd_wm_focus(window_id: &str) -> anyhow::Result<()> {
-    let client = connect().await?;
+pub(crate) async fn cmd_wm_focus(window_id: &str, silent: bool) -> anyhow::Result<()> {
+    let backend = Backend::connect().await?;
 
-    match rpc(
-        &client,
-        EventKind::WmActivateWindow {
-            window_id: window_id.to_owned(),
-        },
-        SecurityLevel::Internal,
-    )
-    .await?
-    {
-        EventKind::WmActivateWindowResponse { success: true } => {
-            println!("Focused window: {}", window_id.green());
-        }
-        EventKind::WmActivateWindowResponse { success: false } => {
-            anyhow::bail!("window '{window_id}' not found");
+    if !backend.activate(window_id, silent).await? {
+        anyhow::bail!("window '{window_id}' not found");
+    }
+    println!("Focused window: {}", window_id.green());
+
+    Ok(())
+}
+
+pub(crate) async fn cmd_wm_mark(mark: char) -> anyhow::Result<()> {
+    if !mark.is_ascii_lowercase() {
+        anyhow::bail!("marks are letters a-z, got '{mark}'");
+    }
+    let backend = Backend::connect().await?;
+
+    if !backend.activate_mark(mark).await? {
+        anyhow::bail!("mark '{mark}' is not set, or its window has closed");
+    }
+    println!("Focused mark: {}", mark.green());
+
+    Ok(())
+}
+
+/// Prepare for a fast first overlay activation, then exit.
+///
+/// Parses the config (so a broken file is reported now, not on the first
+/// Alt+Tab), asks daemon-wm to refresh its window list and render a picker
+/// frame offscreen (loading fonts and rasterizing glyphs), and starts the
+/// resident fast-path process. Run at login by the autostart entry from
+/// `sesame install-autostart`.
+pub(crate) async fn cmd_wm_warmup() -> anyhow::Result<()> {
+    if let Err(e) = core_config::load_config(None) {
+        eprintln!("{} config: {e}", "warning:".yellow());
+    }
+
+    let client = connect().await?;
+    let result = rpc(&client, EventKind::WmWarmup, SecurityLevel::Internal).await;
+    client.shutdown().await;
+    match result? {
+        EventKind::WmWarmupResponse {
+            window_count,
+            rendered,
+        } => {
+            spawn_resident();
+            let frame = if rendered {
+                "frame pre-rendered"
+            } else {
+                "overlay in use, frame skipped"
+            };
+            println!("Overlay warmed up: {window_count} windows, {frame}.");
         }
         other => anyhow::bail!("unexpected response: {other:?}"),
     }
-
     Ok(())
 }
 
-pub(crate) async fn cmd_wm_overlay(launcher: bool, backward: bool) -> anyhow::Result<()> {
-    let variant = match (launcher, backward) {
-        (true, true) => "overlay-launcher-backward",
-        (true, false) => "overlay-launcher",
-        (false, true) => "overlay-backward",
-        (false, false) => "overlay",
+/// Everything one `sesame wm overlay` invocation asks for. Sent to the
+/// resident as a JSON datagram, and to daemon-wm as the matching event.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+struct OverlayIntent {
+    launcher: bool,
+    backward: bool,
+    query: Option<String>,
+    output: Option<String>,
+}
+
+impl OverlayIntent {
+    /// The activation event; the plain variants when there is no query or
+    /// output to carry.
+    fn event(self) -> EventKind {
+        match self {
+            Self {
+                launcher,
+                backward,
+                query: None,
+                output: None,
+            } => match (launcher, backward) {
+                (true, true) => EventKind::WmActivateOverlayLauncherBackward,
+                (true, false) => EventKind::WmActivateOverlayLauncher,
+                (false, true) => EventKind::WmActivateOverlayBackward,
+                (false, false) => EventKind::WmActivateOverlay,
+            },
+            Self {
+                launcher,
+                backward,
+                query,
+                output,
+            } => EventKind::WmActivateOverlayIntent {
+                launcher,
+                backward,
+                query,
+                output,
+            },
+        }
+    }
+}
+
+pub(crate) async fn cmd_wm_overlay(
+    launcher: bool,
+    backward: bool,
+    query: Option<String>,
+    output: Option<String>,
+    timings: bool,
+) -> anyhow::Result<()> {
+    let started = std::time::Instant::now();
+    let intent = OverlayIntent {
+        launcher,
+        backward,
+        query,
+        output,
     };
 
-    // Fast path: send datagram to resident process (~2ms).
-    if try_send_fast_path(variant) {
+    // Fast path: send datagram to resident process (~2ms). Timings need
+    // an IPC connection to read the result back, so they skip it.
+    let fast_path = if timings {
+        FastPath::Unavailable
+    } else {
+        try_send_fast_path(&intent)
+    };
+    if fast_path == FastPath::Sent {
         return Ok(());
     }
 
     // Slow path: full Noise IK connect + publish.
     let client = connect().await?;
-    let event = match variant {
-        "overlay-launcher" => EventKind::WmActivateOverlayLauncher,
-        "overlay-launcher-backward" => EventKind::WmActivateOverlayLauncherBackward,
-        "overlay-backward" => EventKind::WmActivateOverlayBackward,
-        _ => EventKind::WmActivateOverlay,
-    };
+    if let FastPath::TookOver { pid } = fast_path {
+        let _ = client
+            .publish(
+                EventKind::WmResidentHandoff { pid },
+                SecurityLevel::Internal,
+            )
+            .await;
+    }
+    let event = intent.event();
+    if timings {
+        let result = report_overlay_timings(&client, event, started.elapsed()).await;
+        client.shutdown().await;
+        return result;
+    }
     client
         .publish(event, SecurityLevel::Internal)
         .await
@@ -149,52 +310,220 @@ pub(crate) async fn cmd_wm_overlay(launcher: bool, backward: bool) -> anyhow::Re
     Ok(())
 }
 
-/// Send an overlay command to the resident fast-path process via Unix datagram.
+/// How long `--timings` waits for the overlay to report input ready.
+const TIMINGS_WAIT: Duration = Duration::from_secs(2);
+
+/// Activate the overlay and print daemon-wm's latency breakdown for it.
+///
+/// `connect` is the time this process spent reaching the bus (Noise IK
+/// handshake included). Daemon stages are measured from the moment
+/// daemon-wm received the activation request.
+async fn report_overlay_timings(
+    client: &core_ipc::BusClient,
+    event: EventKind,
+    connect: Duration,
+) -> anyhow::Result<()> {
+    let previous = fetch_timings(client).await?.map_or(0, |t| t.sequence);
+    client
+        .publish(event, SecurityLevel::Internal)
+        .await
+        .map_err(|e| anyhow::anyhow!("{e}"))?;
+
+    let deadline = std::time::Instant::now() + TIMINGS_WAIT;
+    let timings = loop {
+        let latest = fetch_timings(client)
+            .await?
+            .filter(|t| t.sequence > previous);
+        let expired = std::time::Instant::now() >= deadline;
+        match latest {
+            Some(t) if t.input_ready_us.is_some() || expired => break t,
+            None if expired => anyhow::bail!(
+                "daemon-wm did not start a new activation (is the overlay already open?)"
+            ),
+            _ => tokio::time::sleep(Duration::from_millis(20)).await,
+        }
+    };
+
+    let ms = |us: Option<u64>| match us {
+        Some(us) => format!("{:.1} ms", us as f64 / 1000.0),
+        None => "not reached".dimmed().to_string(),
+    };
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL);
+    table.set_header(vec!["Stage", "Elapsed"]);
+    table.add_row(vec![
+        "sesame start → bus connected".to_string(),
+        ms(Some(connect.as_micros() as u64)),
+    ]);
+    table.add_row(vec![
+        "request → windows enumerated".to_string(),
+        ms(timings.windows_enumerated_us),
+    ]);
+    table.add_row(vec![
+        "request → first frame committed".to_string(),
+        ms(timings.first_frame_us),
+    ]);
+    table.add_row(vec![
+        "request → input ready".to_string(),
+        ms(timings.input_ready_us),
+    ]);
+    println!("{table}");
+
+    let budget = core_types::OVERLAY_FIRST_FRAME_BUDGET;
+    match timings.first_frame_us {
+        Some(us) if u128::from(us) <= budget.as_micros() => println!(
+            "First frame {} the {}ms budget.",
+            "within".green(),
+            budget.as_millis()
+        ),
+        Some(_) => println!(
+            "First frame {} the {}ms budget.",
+            "over".red().bold(),
+            budget.as_millis()
+        ),
+        None => {}
+    }
+    println!(
+        "{}",
+        format!(
+            "daemon-wm startup (process start → ready): {}",
+            ms(Some(timings.daemon_startup_us))
+        )
+        .dimmed()
+    );
+    Ok(())
+}
+
+async fn fetch_timings(
+    client: &core_ipc::BusClient,
+) -> anyhow::Result<Option<core_types::OverlayTimings>> {
+    match rpc(client, EventKind::WmTimingsRequest, SecurityLevel::Internal).await? {
+        EventKind::WmTimingsResponse { timings } => Ok(timings),
+        other => anyhow::bail!("unexpected response: {other:?}"),
+    }
+}
+
+/// How long the fast path waits for each ping answer from the resident.
+const PING_TIMEOUT: Duration = Duration::from_millis(50);
+
+/// Consecutive unanswered pings before the resident is treated as hung. A
+/// single miss is usually a busy scheduler, not a stuck process.
+const PING_ATTEMPTS: u32 = 3;
+
+/// How long after publishing itself a resident is never killed for missed
+/// pings; within it the fast path only falls back to the slow path.
+const STARTUP_GRACE: Duration = Duration::from_secs(5);
+
+/// The resident's answer to a ping. Carries the datagram format version, so
+/// a resident left running by an older `sesame` is replaced rather than
+/// sent intents it cannot read.
+const PONG: &[u8] = b"pong 2";
+
+/// Largest datagram the resident reads; longer queries take the slow path.
+const DATAGRAM_MAX: usize = 4096;
+
+/// Outcome of `try_send_fast_path`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FastPath {
+    /// The resident took the intent.
+    Sent,
+    /// No usable resident; the slow path spawns one.
+    Unavailable,
+    /// The resident did not answer its pings and was killed, freeing its
+    /// lock for the replacement the slow path spawns.
+    TookOver { pid: i32 },
+}
+
+/// Send an overlay intent to the resident fast-path process via Unix
+/// datagram.
 ///
-/// Returns `true` if the datagram was sent (resident is alive).
-fn try_send_fast_path(variant: &str) -> bool {
+/// The resident must answer a ping with `PONG` within `PING_TIMEOUT`
+/// first. One that misses `PING_ATTEMPTS` pings in a row, or speaks an
+/// older format, is killed so the slow path can spawn a replacement, unless
+/// it published itself within `STARTUP_GRACE`. Files are never removed
+/// here: the replacement takes over the lock through `flock` and rebinds
+/// the socket itself.
+fn try_send_fast_path(intent: &OverlayIntent) -> FastPath {
+    let Ok(datagram) = serde_json::to_vec(intent) else {
+        return FastPath::Unavailable;
+    };
+    if datagram.len() > DATAGRAM_MAX {
+        return FastPath::Unavailable;
+    }
     let Ok(runtime_dir) = std::env::var("XDG_RUNTIME_DIR") else {
-        return false;
+        return FastPath::Unavailable;
     };
-    let pid_path = format!("{runtime_dir}/pds/wm-fast.pid");
+    let pid_path = std::path::PathBuf::from(format!("{runtime_dir}/pds/wm-fast.pid"));
     let sock_path = format!("{runtime_dir}/pds/wm-fast.sock");
 
-    // Check PID file for liveness.
-    let Ok(pid_content) = std::fs::read_to_string(&pid_path) else {
-        return false;
-    };
-    let Ok(pid) = pid_content.trim().parse::<i32>() else {
-        return false;
+    let Some(holder) = Holder::read(&pid_path) else {
+        return FastPath::Unavailable;
     };
 
-    // Verify process is alive via kill(pid, 0).
-    if unsafe { libc::kill(pid, 0) } != 0 {
-        let _ = std::fs::remove_file(&pid_path);
-        let _ = std::fs::remove_file(&sock_path);
-        return false;
+    // A crashed resident, or one whose PID has been reused: the resident
+    // the slow path spawns replaces it.
+    if !holder.is_alive() {
+        return FastPath::Unavailable;
+    }
+
+    if !(0..PING_ATTEMPTS).any(|_| ping_resident(&runtime_dir, &sock_path)) {
+        let published = std::fs::metadata(&pid_path)
+            .and_then(|meta| meta.modified())
+            .ok()
+            .and_then(|at| at.elapsed().ok());
+        if published.is_none_or(|age| age < STARTUP_GRACE) {
+            return FastPath::Unavailable;
+        }
+        InstanceLock::break_lock(&pid_path, holder);
+        return FastPath::TookOver { pid: holder.pid };
     }
 
     // Send datagram (blocking — this is a ~0.1ms operation).
     let Ok(sock) = std::os::unix::net::UnixDatagram::unbound() else {
+        return FastPath::Unavailable;
+    };
+    if sock.send_to(&datagram, &sock_path).is_ok() {
+        FastPath::Sent
+    } else {
+        FastPath::Unavailable
+    }
+}
+
+/// Ping the resident and wait up to `PING_TIMEOUT` for its pong.
+fn ping_resident(runtime_dir: &str, sock_path: &str) -> bool {
+    let reply_path = format!("{runtime_dir}/pds/wm-fast-ping.{}.sock", std::process::id());
+    let _ = std::fs::remove_file(&reply_path);
+    let Ok(sock) = std::os::unix::net::UnixDatagram::bind(&reply_path) else {
         return false;
     };
-    sock.send_to(variant.as_bytes(), &sock_path).is_ok()
+    let mut buf = [0u8; 8];
+    let answered = sock.set_read_timeout(Some(PING_TIMEOUT)).is_ok()
+        && sock.send_to(b"ping", sock_path).is_ok()
+        && matches!(sock.recv(&mut buf), Ok(n) if &buf[..n] == PONG);
+    let _ = std::fs::remove_file(&reply_path);
+    answered
 }
 
-/// Fork a resident fast-path process in the background.
+/// Start a resident fast-path process in the background, in its own
+/// session so it outlives this short-lived one and its terminal.
 fn spawn_resident() {
     let Ok(exe) = std::env::current_exe() else {
         return;
     };
-    let _ = std::process::Command::new(exe)
-        .args(["wm", "overlay-resident"])
-        .stdin(std::process::Stdio::null())
+    let mut cmd = std::process::Command::new(exe);
+    cmd.args(["wm", "overlay-resident"]);
+    #[cfg(target_os = "linux")]
+    platform_linux::process::detach(&mut cmd);
+    #[cfg(not(target_os = "linux"))]
+    cmd.stdin(std::process::Stdio::null())
         .stdout(std::process::Stdio::null())
-        .stderr(std::process::Stdio::null())
-        .spawn();
+        .stderr(std::process::Stdio::null());
+    // The resident is reaped by init once this process exits.
+    let _ = cmd.spawn();
 }
 
-/// Resident fast-path daemon: holds an IPC connection, listens for datagrams.
+/// Resident fast-path daemon: holds an IPC connection, relays intents
+/// received as datagrams.
 ///
 /// Exits on IPC disconnect, datagram error, or 5-minute idle timeout.
 pub(crate) async fn cmd_wm_overlay_resident() -> anyhow::Result<()> {
@@ -203,16 +532,11 @@ pub(crate) async fn cmd_wm_overlay_resident() -> anyhow::Result<()> {
     let pid_path = format!("{pds_dir}/wm-fast.pid");
     let sock_path = format!("{pds_dir}/wm-fast.sock");
 
-    // Check if another resident is already running.
-    if let Ok(existing_pid) = std::fs::read_to_string(&pid_path)
-        && let Ok(pid) = existing_pid.trim().parse::<i32>()
-        && unsafe { libc::kill(pid, 0) } == 0
-    {
+    // Exit if another resident is already running; take over from a
+    // crashed one.
+    let Some(mut lock) = InstanceLock::acquire(std::path::Path::new(&pid_path))? else {
         return Ok(());
-    }
-
-    // Write PID file.
-    std::fs::write(&pid_path, std::process::id().to_string())?;
+    };
 
     // Bind datagram socket with 0600 permissions.
     let _ = std::fs::remove_file(&sock_path);
@@ -227,21 +551,37 @@ pub(crate) async fn cmd_wm_overlay_resident() -> anyhow::Result<()> {
     // Establish IPC connection (full Noise IK handshake — done once).
     let client = connect().await?;
 
+    // Only now can the loop below answer pings: publishing earlier would
+    // let a fast path see a starting resident as hung.
+    lock.publish()?;
+
     // Event loop: receive datagrams, publish to IPC bus.
     let idle_timeout = Duration::from_secs(300);
-    let mut buf = [0u8; 64];
+    let mut buf = vec![0u8; DATAGRAM_MAX];
+
+    // Exit through the cleanup below on SIGTERM/SIGINT (session logout,
+    // Ctrl+C) so the socket and the lock's holder record do not outlive the
+    // process.
+    let mut sigterm = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())?;
 
     loop {
-        match tokio::time::timeout(idle_timeout, dgram.recv(&mut buf)).await {
-            Ok(Ok(n)) => {
-                let variant = std::str::from_utf8(&buf[..n]).unwrap_or("");
-                let event = match variant {
-                    "overlay" => EventKind::WmActivateOverlay,
-                    "overlay-backward" => EventKind::WmActivateOverlayBackward,
-                    "overlay-launcher" => EventKind::WmActivateOverlayLauncher,
-                    "overlay-launcher-backward" => EventKind::WmActivateOverlayLauncherBackward,
-                    _ => continue,
+        let received = tokio::select! {
+            r = tokio::time::timeout(idle_timeout, dgram.recv_from(&mut buf)) => r,
+            _ = sigterm.recv() => break,
+            _ = tokio::signal::ctrl_c() => break,
+        };
+        match received {
+            Ok(Ok((n, from))) => {
+                if &buf[..n] == b"ping" {
+                    if let Some(reply) = from.as_pathname() {
+                        let _ = dgram.send_to(PONG, reply).await;
+                    }
+                    continue;
+                }
+                let Ok(intent) = serde_json::from_slice::<OverlayIntent>(&buf[..n]) else {
+                    continue;
                 };
+                let event = intent.event();
                 if client
                     .publish(event, SecurityLevel::Internal)
                     .await
@@ -257,7 +597,85 @@ pub(crate) async fn cmd_wm_overlay_resident() -> anyhow::Result<()> {
 
     // Cleanup.
     let _ = std::fs::remove_file(&sock_path);
-    let _ = std::fs::remove_file(&pid_path);
+    drop(lock);
     client.shutdown().await;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn intent(query: Option<&str>, output: Option<&str>) -> OverlayIntent {
+        OverlayIntent {
+            launcher: true,
+            backward: true,
+            query: query.map(str::to_owned),
+            output: output.map(str::to_owned),
+        }
+    }
+
+    #[test]
+    fn plain_intents_map_to_plain_events() {
+        assert!(matches!(
+            intent(None, None).event(),
+            EventKind::WmActivateOverlayLauncherBackward
+        ));
+        let switcher = OverlayIntent {
+            launcher: false,
+            backward: false,
+            ..intent(None, None)
+        };
+        assert!(matches!(switcher.event(), EventKind::WmActivateOverlay));
+    }
+
+    #[test]
+    fn query_or_output_carries_the_full_intent() {
+        let EventKind::WmActivateOverlayIntent {
+            launcher,
+            backward,
+            query,
+            output,
+        } = intent(Some("g"), Some("DP-1")).event()
+        else {
+            panic!("expected WmActivateOverlayIntent");
+        };
+        assert!(launcher && backward);
+        assert_eq!(query.as_deref(), Some("g"));
+        assert_eq!(output.as_deref(), Some("DP-1"));
+    }
+
+    #[test]
+    fn only_a_current_pong_answers_the_ping() {
+        let dir = tempfile::tempdir().unwrap();
+        let runtime_dir = dir.path().to_str().unwrap();
+        std::fs::create_dir(dir.path().join("pds")).unwrap();
+        let sock_path = format!("{runtime_dir}/pds/wm-fast.sock");
+        let resident = std::os::unix::net::UnixDatagram::bind(&sock_path).unwrap();
+
+        for (reply, answered) in [(&b"pong"[..], false), (PONG, true)] {
+            let responder = resident.try_clone().unwrap();
+            let thread = std::thread::spawn(move || {
+                let mut buf = [0u8; 8];
+                let (_, from) = responder.recv_from(&mut buf).unwrap();
+                let _ = responder.send_to(reply, from.as_pathname().unwrap());
+            });
+            assert_eq!(ping_resident(runtime_dir, &sock_path), answered);
+            thread.join().unwrap();
+        }
+
+        // Silent: the ping times out.
+        assert!(!ping_resident(runtime_dir, &sock_path));
+    }
+
+    #[test]
+    fn intent_survives_the_datagram_round_trip() {
+        let sent = intent(Some("firefox"), None);
+        let datagram = serde_json::to_vec(&sent).unwrap();
+        assert!(datagram.len() <= DATAGRAM_MAX);
+        assert_eq!(
+            serde_json::from_slice::<OverlayIntent>(&datagram).unwrap(),
+            sent
+        );
+    }
+}
@@ -174,3 +174,222 @@ fn ssh_revoke_help_exits_zero() {
         .success()
         .stdout(predicate::str::contains("Revoke").or(predicate::str::contains("revoke")));
 }
+
+// ===== Config subcommand tests =====
+
+#[test]
+fn config_set_then_get_round_trips() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("pds/config.toml");
+    std::fs::create_dir_all(config.parent().unwrap()).unwrap();
+    std::fs::write(
+        &config,
+        "# keep me\n[profiles.default.wm]\nshow_title = true\n",
+    )
+    .unwrap();
+
+    sesame()
+        .env("XDG_CONFIG_HOME", dir.path())
+        .args([
+            "config",
+            "set",
+            "profiles.default.wm.overlay_delay_ms",
+            "300",
+        ])
+        .assert()
+        .success();
+    sesame()
+        .env("XDG_CONFIG_HOME", dir.path())
+        .args(["config", "get", "profiles.default.wm.overlay_delay_ms"])
+        .assert()
+        .success()
+        .stdout("300\n");
+    assert!(
+        std::fs::read_to_string(&config)
+            .unwrap()
+            .starts_with("# keep me\n")
+    );
+}
+
+#[test]
+fn config_set_rejects_unknown_key() {
+    let dir = tempfile::tempdir().unwrap();
+    sesame()
+        .env("XDG_CONFIG_HOME", dir.path())
+        .args([
+            "config",
+            "set",
+            "profiles.default.wm.overlay_dealy_ms",
+            "300",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("unknown config key"));
+    assert!(!dir.path().join("pds/config.toml").exists());
+}
+
+#[test]
+fn config_init_writes_defaults_once() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("pds/config.toml");
+    sesame()
+        .env("XDG_CONFIG_HOME", dir.path())
+        .args(["config", "init"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(config.display().to_string()));
+    sesame()
+        .args(["config", "validate", config.to_str().unwrap()])
+        .assert()
+        .success();
+
+    std::fs::write(&config, "# mine\n").unwrap();
+    sesame()
+        .env("XDG_CONFIG_HOME", dir.path())
+        .args(["config", "init"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--force"));
+    assert_eq!(std::fs::read_to_string(&config).unwrap(), "# mine\n");
+    sesame()
+        .env("XDG_CONFIG_HOME", dir.path())
+        .args(["config", "init", "--force"])
+        .assert()
+        .success();
+    assert_ne!(std::fs::read_to_string(&config).unwrap(), "# mine\n");
+}
+
+#[test]
+fn config_validate_accepts_valid_file() {
+    sesame()
+        .args(["config", "validate", &fixture("config-valid.toml")])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Config is valid"));
+}
+
+#[test]
+fn config_validate_reports_errors() {
+    sesame()
+        .args(["config", "validate", &fixture("config-invalid.toml")])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("duplicate character 'a'"));
+}
+
+#[test]
+fn config_validate_checks_merged_layers() {
+    let dir = tempfile::tempdir().unwrap();
+    sesame()
+        .env("XDG_CONFIG_HOME", dir.path())
+        .args(["config", "validate"])
+        .assert()
+        .success();
+}
+
+// ===== Headless mock backend (SESAME_BACKEND=mock) =====
+
+fn fixture(name: &str) -> String {
+    format!("{}/tests/fixtures/{name}", env!("CARGO_MANIFEST_DIR"))
+}
+
+/// `sesame` against the mock compositor in `fixtures/<name>`.
+fn mock_sesame(name: &str) -> assert_cmd::Command {
+    let mut cmd = sesame();
+    cmd.env("SESAME_BACKEND", "mock")
+        .env("SESAME_MOCK_FIXTURE", fixture(name));
+    cmd
+}
+
+#[test]
+fn mock_wm_list_shows_fixture_windows() {
+    mock_sesame("windows.toml")
+        .args(["wm", "list"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("Inbox - Mozilla Firefox")
+                .and(predicate::str::contains("com.mitchellh.ghostty"))
+                .and(predicate::str::contains(
+                    "win-00000000-0000-0000-0000-000000000003",
+                )),
+        );
+}
+
+#[test]
+fn mock_wm_switch_picks_previous_window() {
+    mock_sesame("windows.toml")
+        .args(["wm", "switch"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("~/src/open-sesame"));
+    mock_sesame("windows.toml")
+        .args(["wm", "switch", "--backward"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("#general - Slack"));
+}
+
+#[test]
+fn mock_wm_focus_by_app_id_or_window_id() {
+    mock_sesame("windows.toml")
+        .args(["wm", "focus", "slack"])
+        .assert()
+        .success();
+    mock_sesame("windows.toml")
+        .args(["wm", "focus", "win-00000000-0000-0000-0000-000000000002"])
+        .assert()
+        .success();
+    mock_sesame("windows.toml")
+        .args(["wm", "focus", "thunderbird"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("not found"));
+}
+
+#[test]
+fn mock_wm_focus_fails_without_activation_support() {
+    mock_sesame("no-activate.toml")
+        .args(["wm", "focus", "org.mozilla.firefox"])
+        .assert()
+        .failure();
+}
+
+#[test]
+fn mock_wm_mark_rejects_non_letters_and_needs_daemon() {
+    mock_sesame("windows.toml")
+        .args(["wm", "mark", "1"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("letters a-z"));
+    mock_sesame("windows.toml")
+        .args(["wm", "mark", "a"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("kept by daemon-wm"));
+}
+
+#[test]
+fn mock_backend_requires_fixture() {
+    sesame()
+        .env("SESAME_BACKEND", "mock")
+        .env_remove("SESAME_MOCK_FIXTURE")
+        .args(["wm", "list"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("SESAME_MOCK_FIXTURE"));
+}
+
+#[test]
+#[cfg(feature = "desktop")]
+fn mock_doctor_reports_fixture_compositor() {
+    mock_sesame("no-activate.toml")
+        .args(["status", "--doctor", "platform", "--output", "json"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("\"platform.compositor\"")
+                .and(predicate::str::contains("\"mock\""))
+                .and(predicate::str::contains("Overlay cannot switch windows")),
+        );
+}
@@ -0,0 +1,86 @@
+//! Measure window activation latency on the running compositor.
+//!
+//! Alternates activation between the two most recently used windows and
+//! reports how long `activate_window` takes, next to the cost of a bare
+//! roundtrip on a fresh Wayland connection. Each roundtrip dropped from the
+//! activation path saves about one bare roundtrip, so comparing runs before
+//! and after a change to the backend shows what it saved.
+//!
+//! ```text
+//! cargo run --release -p platform-linux --features cosmic \
+//!     --example activation_latency -- [ROUNDS]
+//! ```
+//!
+//! `SESAME_BACKEND` selects the backend as it does for the daemon. Focus
+//! ends on the window that had it when the run started.
+
+use std::time::{Duration, Instant};
+
+use platform_linux::compositor::detect_compositor;
+
+const DEFAULT_ROUNDS: usize = 50;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let rounds = match std::env::args().nth(1) {
+        Some(arg) => arg.parse()?,
+        None => DEFAULT_ROUNDS,
+    };
+    if rounds == 0 {
+        return Err("ROUNDS must be at least 1".into());
+    }
+
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?;
+    let backend = detect_compositor()?;
+    let windows = rt.block_on(backend.list_windows())?;
+    let [first, second, ..] = windows.as_slice() else {
+        return Err("need at least two windows to alternate between".into());
+    };
+    println!(
+        "backend {}: alternating between {:?} and {:?}, {rounds} rounds",
+        backend.name(),
+        first.title,
+        second.title
+    );
+
+    let mut activations = Vec::with_capacity(rounds * 2);
+    for _ in 0..rounds {
+        for window in [second, first] {
+            let started = Instant::now();
+            rt.block_on(backend.activate_window(&window.id))?;
+            activations.push(started.elapsed());
+        }
+    }
+    report("activate_window", &mut activations);
+
+    // The mock backend runs without a display; there is nothing to compare.
+    if wayland_client::Connection::connect_to_env().is_err() {
+        println!("no Wayland display, skipping the bare roundtrip");
+        return Ok(());
+    }
+    let mut roundtrips = Vec::with_capacity(rounds);
+    for _ in 0..rounds {
+        let conn = wayland_client::Connection::connect_to_env()?;
+        let started = Instant::now();
+        conn.roundtrip()?;
+        roundtrips.push(started.elapsed());
+    }
+    report("bare roundtrip", &mut roundtrips);
+
+    Ok(())
+}
+
+/// Print min, median, p95 and max of `samples`.
+fn report(label: &str, samples: &mut [Duration]) {
+    samples.sort();
+    let at = |q: f64| samples[((samples.len() - 1) as f64 * q).round() as usize];
+    println!(
+        "{label:>16}: min {:?}  median {:?}  p95 {:?}  max {:?}  (n = {})",
+        samples[0],
+        at(0.5),
+        at(0.95),
+        samples[samples.len() - 1],
+        samples.len()
+    );
+}
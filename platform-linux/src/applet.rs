@@ -0,0 +1,229 @@
+//! Panel applet interface on the session bus.
+//!
+//! A panel applet (a COSMIC panel button, a waybar module) shows the
+//! switcher's state and opens it on click. daemon-wm serves this small
+//! protocol for it:
+//!
+//! | Member | Kind | Meaning |
+//! |--------|------|---------|
+//! | `WindowCount` | property `u` | Windows the switcher currently lists |
+//! | `LastActivation` | property `(sst)` | App ID, title and Unix time in ms of the last window switched to through sesame; empty strings and `0` before the first |
+//! | `ShowOverlay(b launcher)` | method | Open the overlay, in launcher mode if `launcher` |
+//! | `WindowSwitched((sss) origin, (sss) target, t at_ms)` | signal | A switch through sesame: window ID, app ID and title of the window focused before and of the one switched to, and Unix time in ms; `origin` is empty strings if nothing was focused |
+//!
+//! Both properties emit `PropertiesChanged`, so an applet subscribes
+//! instead of polling. Time trackers and focus journals can listen for
+//! `WindowSwitched` alone. [`WindowSwitcherProxy`] is the client side.
+
+use tokio::sync::{broadcast, mpsc, watch};
+use zbus::object_server::SignalEmitter;
+
+/// Well-known name daemon-wm owns on the session bus.
+pub const BUS_NAME: &str = "org.pds.WindowSwitcher";
+/// Object path of the switcher object.
+pub const OBJECT_PATH: &str = "/org/pds/WindowSwitcher";
+
+/// The window a user last switched to through sesame.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LastActivation {
+    pub app_id: String,
+    pub title: String,
+    /// Unix time in milliseconds.
+    pub at_ms: u64,
+}
+
+/// One side of a [`WindowSwitch`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SwitchedWindow {
+    pub id: String,
+    pub app_id: String,
+    pub title: String,
+}
+
+impl SwitchedWindow {
+    fn into_tuple(self) -> (String, String, String) {
+        (self.id, self.app_id, self.title)
+    }
+}
+
+/// A window switch made through sesame, sent as `WindowSwitched`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WindowSwitch {
+    /// The window focused before the switch, if any.
+    pub origin: Option<SwitchedWindow>,
+    pub target: SwitchedWindow,
+    /// Unix time in milliseconds.
+    pub at_ms: u64,
+}
+
+/// What the applet interface publishes.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AppletStatus {
+    pub window_count: u32,
+    pub last_activation: Option<LastActivation>,
+}
+
+/// A request from an applet, for the daemon to act on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AppletRequest {
+    ShowOverlay { launcher: bool },
+}
+
+struct WindowSwitcher {
+    status: AppletStatus,
+    requests: mpsc::Sender<AppletRequest>,
+}
+
+#[zbus::interface(name = "org.pds.WindowSwitcher1")]
+impl WindowSwitcher {
+    async fn show_overlay(&self, launcher: bool) -> zbus::fdo::Result<()> {
+        self.requests
+            .send(AppletRequest::ShowOverlay { launcher })
+            .await
+            .map_err(|_| zbus::fdo::Error::Failed("window switcher is shutting down".into()))
+    }
+
+    #[zbus(property)]
+    fn window_count(&self) -> u32 {
+        self.status.window_count
+    }
+
+    #[zbus(property)]
+    fn last_activation(&self) -> (String, String, u64) {
+        let last = self.status.last_activation.clone().unwrap_or_default();
+        (last.app_id, last.title, last.at_ms)
+    }
+
+    #[zbus(signal)]
+    async fn window_switched(
+        emitter: &SignalEmitter<'_>,
+        origin: (String, String, String),
+        target: (String, String, String),
+        at_ms: u64,
+    ) -> zbus::Result<()>;
+}
+
+/// Client side of the applet interface.
+#[zbus::proxy(
+    interface = "org.pds.WindowSwitcher1",
+    default_service = "org.pds.WindowSwitcher",
+    default_path = "/org/pds/WindowSwitcher",
+    gen_blocking = false
+)]
+pub trait WindowSwitcher {
+    /// Open the overlay, in launcher mode if `launcher`.
+    fn show_overlay(&self, launcher: bool) -> zbus::Result<()>;
+
+    #[zbus(property)]
+    fn window_count(&self) -> zbus::Result<u32>;
+
+    /// App ID, title and Unix time in ms of the last switch.
+    #[zbus(property)]
+    fn last_activation(&self) -> zbus::Result<(String, String, u64)>;
+
+    /// Window ID, app ID and title of the window focused before a switch
+    /// and of the target, and the switch's Unix time in ms.
+    #[zbus(signal)]
+    fn window_switched(
+        &self,
+        origin: (String, String, String),
+        target: (String, String, String),
+        at_ms: u64,
+    ) -> zbus::Result<()>;
+}
+
+/// Serves the applet interface on the session bus.
+///
+/// Publishes every value `status` takes, sends `WindowSwitched` for each
+/// event from `switches`, and forwards `ShowOverlay` calls through
+/// `requests`. Exits when the session bus is unavailable, the name is
+/// already owned, or `status`'s sender is dropped.
+///
+/// Runs as a long-lived task — spawn with `tokio::spawn`.
+pub async fn applet_server(
+    status: watch::Receiver<AppletStatus>,
+    switches: broadcast::Receiver<WindowSwitch>,
+    requests: mpsc::Sender<AppletRequest>,
+) {
+    if let Err(e) = applet_server_inner(status, switches, requests).await {
+        tracing::info!(error = %e, "applet interface exiting");
+    }
+}
+
+async fn applet_server_inner(
+    mut status: watch::Receiver<AppletStatus>,
+    mut switches: broadcast::Receiver<WindowSwitch>,
+    requests: mpsc::Sender<AppletRequest>,
+) -> core_types::Result<()> {
+    let dbus_error =
+        |e: zbus::Error| core_types::Error::Platform(format!("applet interface failed: {e}"));
+
+    let initial = status.borrow_and_update().clone();
+    let conn = zbus::connection::Builder::session()
+        .map_err(dbus_error)?
+        .name(BUS_NAME)
+        .map_err(dbus_error)?
+        .serve_at(
+            OBJECT_PATH,
+            WindowSwitcher {
+                status: initial,
+                requests,
+            },
+        )
+        .map_err(dbus_error)?
+        .build()
+        .await
+        .map_err(dbus_error)?;
+    tracing::info!(name = BUS_NAME, "applet interface serving");
+
+    let iface = conn
+        .object_server()
+        .interface::<_, WindowSwitcher>(OBJECT_PATH)
+        .await
+        .map_err(dbus_error)?;
+    loop {
+        tokio::select! {
+            changed = status.changed() => {
+                if changed.is_err() {
+                    break;
+                }
+                let next = status.borrow_and_update().clone();
+                publish_status(&iface, next).await;
+            }
+            switch = switches.recv() => match switch {
+                Ok(switch) => {
+                    WindowSwitcher::window_switched(
+                        iface.signal_emitter(),
+                        switch.origin.unwrap_or_default().into_tuple(),
+                        switch.target.into_tuple(),
+                        switch.at_ms,
+                    )
+                    .await
+                    .ok();
+                }
+                Err(broadcast::error::RecvError::Lagged(missed)) => {
+                    tracing::debug!(missed, "applet interface dropped switch signals");
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+        }
+    }
+    Ok(())
+}
+
+async fn publish_status(
+    iface: &zbus::object_server::InterfaceRef<WindowSwitcher>,
+    next: AppletStatus,
+) {
+    let mut switcher = iface.get_mut().await;
+    let count_changed = switcher.status.window_count != next.window_count;
+    let last_changed = switcher.status.last_activation != next.last_activation;
+    switcher.status = next;
+    let emitter = iface.signal_emitter();
+    if count_changed {
+        switcher.window_count_changed(emitter).await.ok();
+    }
+    if last_changed {
+        switcher.last_activation_changed(emitter).await.ok();
+    }
+}
@@ -0,0 +1,130 @@
+//! XDG autostart entry for the login warm-up.
+//!
+//! The first overlay activation after login pays for everything that is
+//! cold: font files, glyph rasterization, the window list, and the Noise IK
+//! handshake the resident fast-path process would otherwise have done.
+//! `sesame wm warmup` does that work up front. Installing an entry in
+//! `~/.config/autostart/` has every XDG-compliant session (COSMIC, GNOME,
+//! KDE, and most wlroots setups via `dex` or similar) run it at login.
+//!
+//! The entry is a file this module owns outright, so removal deletes it
+//! instead of editing it.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// File name of the autostart entry.
+const ENTRY_NAME: &str = "open-sesame-warmup.desktop";
+
+/// `~/.config/autostart/open-sesame-warmup.desktop`.
+fn autostart_path() -> core_types::Result<PathBuf> {
+    let base = dirs::config_dir()
+        .or_else(|| dirs::home_dir().map(|h| h.join(".config")))
+        .ok_or_else(|| {
+            core_types::Error::Platform("cannot determine config directory: HOME not set".into())
+        })?;
+    Ok(base.join("autostart").join(ENTRY_NAME))
+}
+
+/// Characters that force quoting of an `Exec` argument, besides whitespace.
+const RESERVED_EXEC_CHARS: &str = "\"'\\><~|&;$*?#()`";
+
+/// Quote one `Exec` argument per the Desktop Entry Specification.
+///
+/// Arguments with reserved characters are double-quoted, escaping `"`,
+/// `` ` ``, `$` and `\` inside the quotes. A literal `%` is always doubled
+/// so it is not read as a field code.
+fn quote_exec_arg(arg: &str) -> String {
+    let arg = arg.replace('%', "%%");
+    let reserved = |c: char| c.is_whitespace() || RESERVED_EXEC_CHARS.contains(c);
+    if !arg.contains(reserved) {
+        return arg;
+    }
+    let mut quoted = String::with_capacity(arg.len() + 2);
+    quoted.push('"');
+    for c in arg.chars() {
+        if matches!(c, '"' | '`' | '$' | '\\') {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Desktop entry that runs `program wm warmup` at login.
+fn desktop_entry(program: &Path) -> String {
+    let exec = quote_exec_arg(&program.to_string_lossy());
+    format!(
+        "[Desktop Entry]\n\
+         Type=Application\n\
+         Name=Open Sesame warm-up\n\
+         Comment=Prepare the window switcher so the first Alt+Tab is fast\n\
+         Exec={exec} wm warmup\n\
+         NoDisplay=true\n\
+         Terminal=false\n\
+         X-GNOME-Autostart-enabled=true\n"
+    )
+}
+
+/// Install (or replace) the autostart entry for `program`, normally the
+/// running `sesame` binary.
+pub fn install_autostart(program: &Path) -> core_types::Result<()> {
+    let path = autostart_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| {
+            core_types::Error::Platform(format!("failed to create {}: {e}", parent.display()))
+        })?;
+    }
+    fs::write(&path, desktop_entry(program)).map_err(|e| {
+        core_types::Error::Platform(format!("failed to write {}: {e}", path.display()))
+    })?;
+
+    tracing::info!(path = %path.display(), "installed autostart entry");
+    println!("Autostart entry installed:");
+    println!("    {} wm warmup (at login)", program.display());
+    println!("  Entry: {}", path.display());
+    Ok(())
+}
+
+/// Remove the autostart entry, if present.
+pub fn remove_autostart() -> core_types::Result<()> {
+    let path = autostart_path()?;
+    match fs::remove_file(&path) {
+        Ok(()) => {
+            tracing::info!(path = %path.display(), "removed autostart entry");
+            println!("Removed autostart entry {}.", path.display());
+            Ok(())
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            println!("No autostart entry found.");
+            Ok(())
+        }
+        Err(e) => Err(core_types::Error::Platform(format!(
+            "failed to remove {}: {e}",
+            path.display()
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_path_is_unquoted() {
+        let entry = desktop_entry(Path::new("/usr/bin/sesame"));
+        assert!(entry.starts_with("[Desktop Entry]\n"));
+        assert!(entry.contains("\nExec=/usr/bin/sesame wm warmup\n"));
+    }
+
+    #[test]
+    fn reserved_characters_are_quoted() {
+        assert_eq!(
+            quote_exec_arg("/home/a b/bin/sesame"),
+            "\"/home/a b/bin/sesame\""
+        );
+        assert_eq!(quote_exec_arg("/opt/$x\\y"), "\"/opt/\\$x\\\\y\"");
+        assert_eq!(quote_exec_arg("/opt/100%/sesame"), "/opt/100%%/sesame");
+    }
+}
@@ -4,19 +4,37 @@
 //!
 //! A dedicated dispatch thread continuously processes Wayland events from:
 //! - `ext_foreign_toplevel_list_v1`: window creation/destruction
-//! - `zcosmic_toplevel_info_v1`: cosmic state (activation, geometry)
+//! - `zcosmic_toplevel_info_v1`: cosmic state (activation, geometry) and the
+//!   output and workspace each window is on
+//! - `ext_workspace_manager_v1`: the workspaces named by `ext_workspace_enter`
 //!
 //! `list_windows()` reads a shared snapshot — zero allocations, zero protocol
 //! operations per call. This eliminates the memory leak caused by the previous
 //! per-poll `registry_queue_init` + `globals.bind` architecture (see #25).
 //!
-//! `activate_window()` and `close_window()` use disposable connections to avoid
-//! crashing cosmic-comp when protocol objects are destroyed in flight.
-
-use crate::compositor::{BoxFuture, CompositorBackend, Workspace};
-use core_types::{CompositorWorkspaceId, Geometry, Window, WindowId};
+//! `activate_window()`, `close_window()` and `move_to_output()` use disposable
+//! connections to avoid crashing cosmic-comp when protocol objects are
+//! destroyed in flight.
+
+use crate::capabilities::{
+    COSMIC_TOPLEVEL_INFO_MAX, COSMIC_TOPLEVEL_MANAGER_MAX, EXT_FOREIGN_TOPLEVEL_LIST_MAX,
+    WL_SEAT_MAX, advertised_globals,
+};
+use crate::compositor::{BoxFuture, Capabilities, CompositorBackend, WindowChange, Workspace};
+use crate::outputs::OutputState;
+use crate::toplevel_state;
+use crate::workspaces::{self, WorkspaceState};
+use core_types::{
+    CompositorWorkspaceId, Geometry, MonitorId, Output, ToplevelState, Window, WindowId,
+    WindowPlacement, WindowToggle,
+};
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
+use wayland_client::protocol::wl_output::WlOutput;
+use wayland_protocols::ext::workspace::v1::client::{
+    ext_workspace_group_handle_v1::ExtWorkspaceGroupHandleV1,
+    ext_workspace_handle_v1::ExtWorkspaceHandleV1, ext_workspace_manager_v1::ExtWorkspaceManagerV1,
+};
 
 /// UUID v5 namespace for deterministic WindowId derivation from COSMIC protocol identifiers.
 const COSMIC_WINDOW_NAMESPACE: uuid::Uuid = uuid::Uuid::from_bytes([
@@ -30,10 +48,16 @@ const COSMIC_WINDOW_NAMESPACE: uuid::Uuid = uuid::Uuid::from_bytes([
 pub(crate) struct CosmicBackend {
     /// Shared snapshot updated by the dispatch thread on every toplevel event.
     state: Arc<Mutex<CosmicState>>,
+    /// Negotiated at connect. The manager is only bound on the disposable
+    /// activation/close connections, so its absence shows up here first.
+    capabilities: Capabilities,
     /// Kept alive so the dispatch thread's connection clone remains valid.
     /// The dispatch thread holds its own clone; this prevents the underlying
     /// Wayland fd from being closed if the backend outlives the thread.
     _conn: wayland_client::Connection,
+    changes: tokio::sync::broadcast::Sender<WindowChange>,
+    /// Outputs as tracked by the dispatch thread.
+    outputs: Arc<Mutex<Vec<Output>>>,
 }
 
 /// Published snapshot of all toplevel windows, shared between the dispatch
@@ -47,7 +71,14 @@ struct CosmicState {
 struct CosmicToplevelSnapshot {
     app_id: String,
     title: String,
-    activated: bool,
+    /// From the latest `zcosmic_toplevel_handle_v1::State`.
+    state: ToplevelState,
+    /// Output the window was last reported on.
+    monitor: Option<MonitorId>,
+    /// Position and size relative to `monitor`, from `geometry` (v2).
+    geometry: Option<Geometry>,
+    /// Workspace from `ext_workspace_enter` (v3), as in `list_workspaces`.
+    workspace: Option<CompositorWorkspaceId>,
 }
 
 impl CosmicBackend {
@@ -66,24 +97,43 @@ impl CosmicBackend {
 
         let qh = event_queue.handle();
 
+        // Bind outputs before the toplevel protocols, so their names arrive
+        // before the toplevels' output_enter events.
+        let mut outputs = OutputState::default();
+        outputs.bind_all(&globals, &qh);
+        let published_outputs = outputs.published();
+        // Likewise workspaces, for ext_workspace_enter. Without the global
+        // windows are listed without a workspace.
+        if let Err(e) = workspaces::bind(&globals, &qh) {
+            tracing::debug!(error = %e, "cosmic: window workspaces unavailable");
+        }
+
         // Bind protocol objects ONCE. These live for the connection lifetime.
-        let _list: ExtForeignToplevelListV1 = globals.bind(&qh, 1..=1, ()).map_err(|e| {
-            core_types::Error::Platform(format!("ext_foreign_toplevel_list bind: {e}"))
-        })?;
+        let _list: ExtForeignToplevelListV1 = globals
+            .bind(&qh, 1..=EXT_FOREIGN_TOPLEVEL_LIST_MAX, ())
+            .map_err(|e| {
+                core_types::Error::Platform(format!("ext_foreign_toplevel_list bind: {e}"))
+            })?;
         let _info: ZcosmicToplevelInfoV1 = globals
-            .bind(&qh, 2..=3, ())
+            .bind(&qh, 2..=COSMIC_TOPLEVEL_INFO_MAX, ())
             .map_err(|e| core_types::Error::Platform(format!("zcosmic_toplevel_info bind: {e}")))?;
 
+        let capabilities = Capabilities::from_globals("cosmic", &advertised_globals(&globals));
+
         let shared_state = Arc::new(Mutex::new(CosmicState {
             toplevels: HashMap::new(),
         }));
 
+        let changes = crate::compositor::window_changes();
         let mut dispatch_state = CosmicDispatchState {
             pending: HashMap::new(),
             cosmic_pending: HashMap::new(),
             shared: Arc::clone(&shared_state),
+            changes: changes.clone(),
             info: None,
             qh: None,
+            outputs,
+            workspaces: WorkspaceState::default(),
         };
 
         // Store the info proxy and queue handle in dispatch state so the
@@ -114,7 +164,10 @@ impl CosmicBackend {
 
         Ok(Self {
             state: shared_state,
+            capabilities,
             _conn: conn,
+            changes,
+            outputs: published_outputs,
         })
     }
 
@@ -123,12 +176,18 @@ impl CosmicBackend {
     /// cosmic-comp panics when protocol objects are destroyed while activation
     /// is in flight, so we use a separate connection that is dropped (leaked)
     /// after the operation. This isolates the shared dispatch connection.
+    ///
+    /// Two roundtrips: one to enumerate toplevels on the new connection, and
+    /// one after the cosmic handle request and the activation, which go out
+    /// in a single flush.
     fn activate(&self, target_id: &WindowId) -> core_types::Result<()> {
         use wayland_client::{Connection, globals::registry_queue_init};
         use wayland_protocols::ext::foreign_toplevel_list::v1::client::ext_foreign_toplevel_list_v1::ExtForeignToplevelListV1;
         use cosmic_client_toolkit::cosmic_protocols::toplevel_info::v1::client::zcosmic_toplevel_info_v1::ZcosmicToplevelInfoV1;
         use cosmic_client_toolkit::cosmic_protocols::toplevel_management::v1::client::zcosmic_toplevel_manager_v1::ZcosmicToplevelManagerV1;
 
+        let started = std::time::Instant::now();
+
         let activate_conn = Connection::connect_to_env().map_err(|e| {
             core_types::Error::Platform(format!("Wayland activation connection failed: {e}"))
         })?;
@@ -142,23 +201,29 @@ impl CosmicBackend {
             })?;
         let qh = event_queue.handle();
 
-        let _list: ExtForeignToplevelListV1 = globals.bind(&qh, 1..=1, ()).map_err(|e| {
-            core_types::Error::Platform(format!("ext_foreign_toplevel_list bind: {e}"))
-        })?;
+        let _list: ExtForeignToplevelListV1 = globals
+            .bind(&qh, 1..=EXT_FOREIGN_TOPLEVEL_LIST_MAX, ())
+            .map_err(|e| {
+                core_types::Error::Platform(format!("ext_foreign_toplevel_list bind: {e}"))
+            })?;
         let info: ZcosmicToplevelInfoV1 = globals
-            .bind(&qh, 2..=3, ())
+            .bind(&qh, 2..=COSMIC_TOPLEVEL_INFO_MAX, ())
             .map_err(|e| core_types::Error::Platform(format!("zcosmic_toplevel_info bind: {e}")))?;
-        let manager: ZcosmicToplevelManagerV1 = globals.bind(&qh, 1..=4, ()).map_err(|e| {
-            core_types::Error::Platform(format!("zcosmic_toplevel_manager bind: {e}"))
-        })?;
+        let manager: ZcosmicToplevelManagerV1 = globals
+            .bind(&qh, 1..=COSMIC_TOPLEVEL_MANAGER_MAX, ())
+            .map_err(|e| {
+                core_types::Error::Platform(format!("zcosmic_toplevel_manager bind: {e}"))
+            })?;
         let seat: wayland_client::protocol::wl_seat::WlSeat = globals
-            .bind(&qh, 1..=9, ())
+            .bind(&qh, 1..=WL_SEAT_MAX, ())
             .map_err(|e| core_types::Error::Platform(format!("wl_seat bind: {e}")))?;
 
         let mut state = CosmicEnumState {
             pending: HashMap::new(),
             cosmic_pending: HashMap::new(),
             toplevels: Vec::new(),
+            outputs: OutputState::default(),
+            workspaces: WorkspaceState::default(),
         };
 
         cosmic_roundtrip(&activate_conn, &mut event_queue, &mut state)?;
@@ -179,15 +244,32 @@ impl CosmicBackend {
         let target_handle = target_handle
             .ok_or_else(|| core_types::Error::Platform(format!("window {target_id} not found")))?;
 
+        // Requests are processed in order, so the new handle is valid by the
+        // time the manager requests name it; no roundtrip in between.
         let cosmic_handle = info.get_cosmic_toplevel(&target_handle, &qh, ());
 
-        cosmic_roundtrip(&activate_conn, &mut event_queue, &mut state)?;
-
+        // Activation need not restore a minimized window. The disposable
+        // connection never records toplevel state; the dispatch connection's
+        // snapshot does.
+        let minimized = self
+            .state
+            .lock()
+            .map_err(|e| core_types::Error::Platform(format!("lock poisoned: {e}")))?
+            .toplevels
+            .get(target_id)
+            .is_some_and(|tl| tl.state.contains(ToplevelState::MINIMIZED));
+        if minimized {
+            manager.unset_minimized(&cosmic_handle);
+        }
         manager.activate(&cosmic_handle, &seat);
 
         cosmic_roundtrip(&activate_conn, &mut event_queue, &mut state)?;
 
-        tracing::info!(window_id = %target_id, "cosmic: window activated");
+        tracing::info!(
+            window_id = %target_id,
+            elapsed_us = started.elapsed().as_micros(),
+            "cosmic: window activated"
+        );
 
         // DO NOT destroy protocol objects — cosmic-comp panics.
         // The disposable connection is dropped, isolating the shared connection.
@@ -198,41 +280,120 @@ impl CosmicBackend {
 
     /// Close a window using a disposable connection.
     fn close(&self, target_id: &WindowId) -> core_types::Result<()> {
+        self.manage(target_id, "closed", |manager, handle, _| {
+            manager.close(handle);
+            Ok(())
+        })
+    }
+
+    /// Maximize a window using a disposable connection.
+    fn maximize(&self, target_id: &WindowId) -> core_types::Result<()> {
+        self.manage(target_id, "maximized", |manager, handle, _| {
+            manager.set_maximized(handle);
+            Ok(())
+        })
+    }
+
+    /// Show a window on every workspace, or only its own, using a
+    /// disposable connection.
+    fn set_sticky(&self, target_id: &WindowId, on: bool) -> core_types::Result<()> {
+        if !self.capabilities.can_stick {
+            return Err(core_types::Error::Platform(
+                "sticky windows need zcosmic_toplevel_manager_v1 version 4".into(),
+            ));
+        }
+        let done = if on { "made sticky" } else { "unstuck" };
+        self.manage(target_id, done, |manager, handle, _| {
+            if on {
+                manager.set_sticky(handle);
+            } else {
+                manager.unset_sticky(handle);
+            }
+            Ok(())
+        })
+    }
+
+    /// Move a window to the active workspace of another output, using a
+    /// disposable connection.
+    fn move_to(&self, target_id: &WindowId, output: &MonitorId) -> core_types::Result<()> {
+        if !self.capabilities.can_move_to_output {
+            return Err(core_types::Error::Platform(
+                "moving windows between outputs needs zcosmic_toplevel_manager_v1 version 4 \
+                 and ext_workspace_manager_v1"
+                    .into(),
+            ));
+        }
+        self.manage(target_id, "moved to output", |manager, handle, state| {
+            let proxy = state
+                .outputs
+                .proxy(output)
+                .ok_or_else(|| core_types::Error::Platform("output not found".into()))?;
+            let workspace = state.workspaces.active_on(proxy).ok_or_else(|| {
+                core_types::Error::Platform("output has no active workspace".into())
+            })?;
+            manager.move_to_ext_workspace(handle, workspace, proxy);
+            Ok(())
+        })
+    }
+
+    /// Send a `zcosmic_toplevel_manager_v1` request for a window on a
+    /// disposable connection. `done` names the result for the log. Like
+    /// [`Self::activate`], the handle request and `request` share a flush.
+    fn manage(
+        &self,
+        target_id: &WindowId,
+        done: &str,
+        request: impl FnOnce(
+            &cosmic_client_toolkit::cosmic_protocols::toplevel_management::v1::client::zcosmic_toplevel_manager_v1::ZcosmicToplevelManagerV1,
+            &cosmic_client_toolkit::cosmic_protocols::toplevel_info::v1::client::zcosmic_toplevel_handle_v1::ZcosmicToplevelHandleV1,
+            &CosmicEnumState,
+        ) -> core_types::Result<()>,
+    ) -> core_types::Result<()> {
         use wayland_client::{Connection, globals::registry_queue_init};
         use wayland_protocols::ext::foreign_toplevel_list::v1::client::ext_foreign_toplevel_list_v1::ExtForeignToplevelListV1;
         use cosmic_client_toolkit::cosmic_protocols::toplevel_info::v1::client::zcosmic_toplevel_info_v1::ZcosmicToplevelInfoV1;
         use cosmic_client_toolkit::cosmic_protocols::toplevel_management::v1::client::zcosmic_toplevel_manager_v1::ZcosmicToplevelManagerV1;
 
-        let close_conn = Connection::connect_to_env().map_err(|e| {
-            core_types::Error::Platform(format!("Wayland close connection failed: {e}"))
-        })?;
+        let manage_conn = Connection::connect_to_env()
+            .map_err(|e| core_types::Error::Platform(format!("Wayland connection failed: {e}")))?;
 
-        let (globals, mut event_queue) = registry_queue_init::<CosmicEnumState>(&close_conn)
+        let (globals, mut event_queue) = registry_queue_init::<CosmicEnumState>(&manage_conn)
             .map_err(|e| {
-                let proto_err = close_conn.protocol_error();
+                let proto_err = manage_conn.protocol_error();
                 core_types::Error::Platform(format!(
                     "registry init failed: {e} (protocol_error: {proto_err:?})"
                 ))
             })?;
         let qh = event_queue.handle();
 
-        let _list: ExtForeignToplevelListV1 = globals.bind(&qh, 1..=1, ()).map_err(|e| {
-            core_types::Error::Platform(format!("ext_foreign_toplevel_list bind: {e}"))
-        })?;
+        let _list: ExtForeignToplevelListV1 = globals
+            .bind(&qh, 1..=EXT_FOREIGN_TOPLEVEL_LIST_MAX, ())
+            .map_err(|e| {
+                core_types::Error::Platform(format!("ext_foreign_toplevel_list bind: {e}"))
+            })?;
         let info: ZcosmicToplevelInfoV1 = globals
-            .bind(&qh, 2..=3, ())
+            .bind(&qh, 2..=COSMIC_TOPLEVEL_INFO_MAX, ())
             .map_err(|e| core_types::Error::Platform(format!("zcosmic_toplevel_info bind: {e}")))?;
-        let manager: ZcosmicToplevelManagerV1 = globals.bind(&qh, 1..=4, ()).map_err(|e| {
-            core_types::Error::Platform(format!("zcosmic_toplevel_manager bind: {e}"))
-        })?;
+        let manager: ZcosmicToplevelManagerV1 = globals
+            .bind(&qh, 1..=COSMIC_TOPLEVEL_MANAGER_MAX, ())
+            .map_err(|e| {
+                core_types::Error::Platform(format!("zcosmic_toplevel_manager bind: {e}"))
+            })?;
 
         let mut state = CosmicEnumState {
             pending: HashMap::new(),
             cosmic_pending: HashMap::new(),
             toplevels: Vec::new(),
+            outputs: OutputState::default(),
+            workspaces: WorkspaceState::default(),
         };
+        // Outputs and their workspaces name the destination of
+        // move_to_ext_workspace; outputs first, so workspace groups can
+        // report the outputs they are shown on.
+        state.outputs.bind_all(&globals, &qh);
+        let _workspaces = crate::workspaces::bind(&globals, &qh).ok();
 
-        cosmic_roundtrip(&close_conn, &mut event_queue, &mut state)?;
+        cosmic_roundtrip(&manage_conn, &mut event_queue, &mut state)?;
 
         let target_handle = state
             .toplevels
@@ -251,16 +412,13 @@ impl CosmicBackend {
             .ok_or_else(|| core_types::Error::Platform(format!("window {target_id} not found")))?;
 
         let cosmic_handle = info.get_cosmic_toplevel(&target_handle, &qh, ());
+        request(&manager, &cosmic_handle, &state)?;
 
-        cosmic_roundtrip(&close_conn, &mut event_queue, &mut state)?;
+        cosmic_roundtrip(&manage_conn, &mut event_queue, &mut state)?;
 
-        manager.close(&cosmic_handle);
+        tracing::info!(window_id = %target_id, "cosmic: window {done}");
 
-        cosmic_roundtrip(&close_conn, &mut event_queue, &mut state)?;
-
-        tracing::info!(window_id = %target_id, "cosmic: window closed");
-
-        let _ = close_conn.flush();
+        let _ = manage_conn.flush();
         Ok(())
     }
 }
@@ -279,18 +437,27 @@ impl CompositorBackend for CosmicBackend {
                     id: *wid,
                     app_id: core_types::AppId::new(&tl.app_id),
                     title: tl.title.clone(),
-                    workspace_id: CompositorWorkspaceId::from_uuid(uuid::Uuid::nil()),
-                    monitor_id: core_types::MonitorId::from_uuid(uuid::Uuid::nil()),
-                    geometry: Geometry {
+                    workspace_id: tl
+                        .workspace
+                        .unwrap_or_else(|| CompositorWorkspaceId::from_uuid(uuid::Uuid::nil())),
+                    monitor_id: tl
+                        .monitor
+                        .unwrap_or_else(|| MonitorId::from_uuid(uuid::Uuid::nil())),
+                    geometry: tl.geometry.unwrap_or(Geometry {
                         x: 0,
                         y: 0,
                         width: 0,
                         height: 0,
-                    },
-                    is_focused: tl.activated,
-                    is_minimized: false,
-                    is_fullscreen: false,
+                    }),
+                    is_focused: tl.state.contains(ToplevelState::ACTIVATED),
+                    is_minimized: tl.state.contains(ToplevelState::MINIMIZED),
+                    is_fullscreen: tl.state.contains(ToplevelState::FULLSCREEN),
+                    is_maximized: tl.state.contains(ToplevelState::MAXIMIZED),
                     profile_id: core_types::ProfileId::from_uuid(uuid::Uuid::nil()),
+                    // ext-foreign-toplevel-list does not report parents.
+                    parent: None,
+                    is_sticky: tl.state.contains(ToplevelState::STICKY),
+                    is_keep_above: false,
                 })
                 .collect();
             // MRU reorder: focused window to end.
@@ -303,7 +470,38 @@ impl CompositorBackend for CosmicBackend {
     }
 
     fn list_workspaces(&self) -> BoxFuture<'_, core_types::Result<Vec<Workspace>>> {
-        Box::pin(async { Ok(vec![]) })
+        Box::pin(async move {
+            if !self.capabilities.can_switch_workspace {
+                return Ok(vec![]);
+            }
+            crate::workspaces::list()
+        })
+    }
+
+    fn activate_workspace(
+        &self,
+        id: &CompositorWorkspaceId,
+    ) -> BoxFuture<'_, core_types::Result<()>> {
+        let id = *id;
+        Box::pin(async move { crate::workspaces::activate(&id) })
+    }
+
+    fn list_outputs(&self) -> BoxFuture<'_, core_types::Result<Vec<Output>>> {
+        Box::pin(async move {
+            self.outputs
+                .lock()
+                .map(|outputs| outputs.clone())
+                .map_err(|e| core_types::Error::Platform(format!("lock poisoned: {e}")))
+        })
+    }
+
+    fn move_to_output(
+        &self,
+        id: &WindowId,
+        output: &MonitorId,
+    ) -> BoxFuture<'_, core_types::Result<()>> {
+        let (id, output) = (*id, *output);
+        Box::pin(async move { self.move_to(&id, &output) })
     }
 
     fn activate_window(&self, id: &WindowId) -> BoxFuture<'_, core_types::Result<()>> {
@@ -344,6 +542,47 @@ impl CompositorBackend for CosmicBackend {
         Box::pin(async move { self.close(&id) })
     }
 
+    fn place_window(
+        &self,
+        id: &WindowId,
+        placement: WindowPlacement,
+    ) -> BoxFuture<'_, core_types::Result<()>> {
+        let id = *id;
+        Box::pin(async move {
+            match placement {
+                WindowPlacement::Maximized => self.maximize(&id),
+                WindowPlacement::LeftHalf | WindowPlacement::RightHalf => Err(
+                    core_types::Error::Platform("tiling not supported by cosmic protocol".into()),
+                ),
+            }
+        })
+    }
+
+    fn set_window_toggle(
+        &self,
+        id: &WindowId,
+        toggle: WindowToggle,
+        on: bool,
+    ) -> BoxFuture<'_, core_types::Result<()>> {
+        let id = *id;
+        Box::pin(async move {
+            match toggle {
+                WindowToggle::Sticky => self.set_sticky(&id, on),
+                WindowToggle::KeepAbove => Err(core_types::Error::Platform(
+                    "keep-above not supported by cosmic protocol".into(),
+                )),
+            }
+        })
+    }
+
+    fn subscribe_changes(&self) -> tokio::sync::broadcast::Receiver<WindowChange> {
+        self.changes.subscribe()
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        self.capabilities.clone()
+    }
+
     fn name(&self) -> &str {
         "cosmic"
     }
@@ -360,7 +599,14 @@ struct CosmicPendingToplevel {
     identifier: Option<String>,
     app_id: Option<String>,
     title: Option<String>,
-    is_activated: bool,
+    state: ToplevelState,
+    /// Last output entered, as bound in `CosmicDispatchState::outputs`.
+    output: Option<WlOutput>,
+    /// Last `geometry` event: the output it is relative to, and the
+    /// rectangle.
+    geometry: Option<(WlOutput, Geometry)>,
+    /// Last workspace entered, as bound in `CosmicDispatchState::workspaces`.
+    workspace: Option<ExtWorkspaceHandleV1>,
     /// The cosmic handle proxy, set after `get_cosmic_toplevel` response.
     has_cosmic_state: bool,
 }
@@ -374,13 +620,38 @@ struct CosmicDispatchState {
     cosmic_pending: HashMap<u32, u32>,
     /// Shared snapshot for API callers.
     shared: Arc<Mutex<CosmicState>>,
+    /// Title and app ID changes, for `subscribe_changes`.
+    changes: tokio::sync::broadcast::Sender<WindowChange>,
     /// Persistent zcosmic_toplevel_info_v1 proxy — bound once, reused for
     /// every `get_cosmic_toplevel` call on new toplevels.
     info: Option<cosmic_client_toolkit::cosmic_protocols::toplevel_info::v1::client::zcosmic_toplevel_info_v1::ZcosmicToplevelInfoV1>,
     /// Queue handle for creating child objects via `get_cosmic_toplevel`.
     qh: Option<wayland_client::QueueHandle<CosmicDispatchState>>,
+    /// Outputs bound on the dispatch connection, so `output_enter` events
+    /// can be resolved to output names.
+    outputs: OutputState,
+    /// Workspaces bound on the dispatch connection, so `ext_workspace_enter`
+    /// events can be resolved to workspace ids.
+    workspaces: WorkspaceState,
+}
+
+impl AsMut<OutputState> for CosmicDispatchState {
+    fn as_mut(&mut self) -> &mut OutputState {
+        &mut self.outputs
+    }
+}
+
+impl AsMut<WorkspaceState> for CosmicDispatchState {
+    fn as_mut(&mut self) -> &mut WorkspaceState {
+        &mut self.workspaces
+    }
 }
 
+wayland_client::delegate_dispatch!(CosmicDispatchState: [WlOutput: ()] => OutputState);
+wayland_client::delegate_dispatch!(CosmicDispatchState: [ExtWorkspaceManagerV1: ()] => WorkspaceState);
+wayland_client::delegate_dispatch!(CosmicDispatchState: [ExtWorkspaceGroupHandleV1: ()] => WorkspaceState);
+wayland_client::delegate_dispatch!(CosmicDispatchState: [ExtWorkspaceHandleV1: ()] => WorkspaceState);
+
 impl CosmicDispatchState {
     /// Publish the current pending state to the shared snapshot.
     fn publish(&self) {
@@ -392,7 +663,7 @@ impl CosmicDispatchState {
             }
         };
 
-        shared.toplevels.clear();
+        let before = std::mem::take(&mut shared.toplevels);
         for pending in self.pending.values() {
             let Some(identifier) = pending.identifier.as_deref() else {
                 continue;
@@ -406,12 +677,37 @@ impl CosmicDispatchState {
                 identifier.as_bytes(),
             ));
 
+            // The geometry names the output it is relative to, so that
+            // output is the window's while it is known.
+            let output = pending
+                .geometry
+                .as_ref()
+                .map(|(output, _)| output)
+                .or(pending.output.as_ref());
+            let title = pending.title.clone().unwrap_or_default();
+            if let Some(change) = WindowChange::between(
+                window_id,
+                before
+                    .get(&window_id)
+                    .map(|b| (b.app_id.as_str(), b.title.as_str())),
+                app_id,
+                &title,
+            ) {
+                // No subscribers is not an error.
+                let _ = self.changes.send(change);
+            }
             shared.toplevels.insert(
                 window_id,
                 CosmicToplevelSnapshot {
                     app_id: app_id.to_string(),
-                    title: pending.title.clone().unwrap_or_default(),
-                    activated: pending.is_activated,
+                    title,
+                    state: pending.state,
+                    monitor: output.and_then(|o| self.outputs.id_of(o)),
+                    geometry: pending.geometry.as_ref().map(|(_, geometry)| *geometry),
+                    workspace: pending
+                        .workspace
+                        .as_ref()
+                        .and_then(|ws| self.workspaces.id_of(ws)),
                 },
             );
         }
@@ -492,13 +788,15 @@ impl
     > for CosmicDispatchState
 {
     fn event(
-        _: &mut Self,
-        _: &wayland_client::protocol::wl_registry::WlRegistry,
-        _: wayland_client::protocol::wl_registry::Event,
+        state: &mut Self,
+        registry: &wayland_client::protocol::wl_registry::WlRegistry,
+        event: wayland_client::protocol::wl_registry::Event,
         _: &wayland_client::globals::GlobalListContents,
         _: &wayland_client::Connection,
-        _: &wayland_client::QueueHandle<Self>,
+        qh: &wayland_client::QueueHandle<Self>,
     ) {
+        // Outputs plugged in after startup.
+        state.outputs.registry_event(registry, event, qh);
     }
 }
 
@@ -605,25 +903,74 @@ impl wayland_client::Dispatch<cosmic_client_toolkit::cosmic_protocols::toplevel_
         _qh: &wayland_client::QueueHandle<Self>,
     ) {
         use wayland_client::Proxy;
+        use cosmic_client_toolkit::cosmic_protocols::toplevel_info::v1::client::zcosmic_toplevel_handle_v1;
         let cosmic_id = proxy.id().protocol_id();
+        let Some(&foreign_id) = state.cosmic_pending.get(&cosmic_id) else {
+            return;
+        };
 
-        if let Some(&foreign_id) = state.cosmic_pending.get(&cosmic_id)
-            && let cosmic_client_toolkit::cosmic_protocols::toplevel_info::v1::client::zcosmic_toplevel_handle_v1::Event::State { state: state_bytes } = &event
-        {
-            if state_bytes.len() % 4 != 0 {
+        match &event {
+            zcosmic_toplevel_handle_v1::Event::OutputEnter { output } => {
+                if let Some(pending) = state.pending.get_mut(&foreign_id) {
+                    pending.output = Some(output.clone());
+                    state.publish();
+                }
                 return;
             }
-            let activated = state_bytes
-                .chunks_exact(4)
-                .any(|chunk| {
-                    let val = u32::from_ne_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
-                    val == cosmic_client_toolkit::cosmic_protocols::toplevel_info::v1::client::zcosmic_toplevel_handle_v1::State::Activated as u32
-                });
-            if let Some(pending) = state.pending.get_mut(&foreign_id) {
-                pending.is_activated = activated;
-                pending.has_cosmic_state = true;
-                state.publish();
+            zcosmic_toplevel_handle_v1::Event::OutputLeave { output } => {
+                if let Some(pending) = state.pending.get_mut(&foreign_id)
+                    && pending.output.as_ref() == Some(output)
+                {
+                    pending.output = None;
+                    state.publish();
+                }
+                return;
             }
+            zcosmic_toplevel_handle_v1::Event::Geometry {
+                output,
+                x,
+                y,
+                width,
+                height,
+            } => {
+                if let Some(pending) = state.pending.get_mut(&foreign_id) {
+                    let geometry = Geometry {
+                        x: *x,
+                        y: *y,
+                        width: u32::try_from(*width).unwrap_or(0),
+                        height: u32::try_from(*height).unwrap_or(0),
+                    };
+                    pending.geometry = Some((output.clone(), geometry));
+                    state.publish();
+                }
+                return;
+            }
+            zcosmic_toplevel_handle_v1::Event::ExtWorkspaceEnter { workspace } => {
+                if let Some(pending) = state.pending.get_mut(&foreign_id) {
+                    pending.workspace = Some(workspace.clone());
+                    state.publish();
+                }
+                return;
+            }
+            zcosmic_toplevel_handle_v1::Event::ExtWorkspaceLeave { workspace } => {
+                if let Some(pending) = state.pending.get_mut(&foreign_id)
+                    && pending.workspace.as_ref() == Some(workspace)
+                {
+                    pending.workspace = None;
+                    state.publish();
+                }
+                return;
+            }
+            _ => {}
+        }
+
+        if let zcosmic_toplevel_handle_v1::Event::State { state: state_bytes } = &event
+            && let Some(toplevel_state) = toplevel_state::decode(state_bytes)
+            && let Some(pending) = state.pending.get_mut(&foreign_id)
+        {
+            pending.state = toplevel_state;
+            pending.has_cosmic_state = true;
+            state.publish();
         }
     }
 }
@@ -639,14 +986,35 @@ struct CosmicEnumState {
     pending: HashMap<u32, CosmicEnumPending>,
     cosmic_pending: HashMap<u32, u32>,
     toplevels: Vec<(wayland_protocols::ext::foreign_toplevel_list::v1::client::ext_foreign_toplevel_handle_v1::ExtForeignToplevelHandleV1, CosmicEnumPending)>,
+    /// Outputs, bound by `manage` only.
+    outputs: OutputState,
+    /// Workspaces, bound by `manage` only.
+    workspaces: WorkspaceState,
+}
+
+impl AsMut<OutputState> for CosmicEnumState {
+    fn as_mut(&mut self) -> &mut OutputState {
+        &mut self.outputs
+    }
+}
+
+impl AsMut<WorkspaceState> for CosmicEnumState {
+    fn as_mut(&mut self) -> &mut WorkspaceState {
+        &mut self.workspaces
+    }
 }
 
+wayland_client::delegate_dispatch!(CosmicEnumState: [WlOutput: ()] => OutputState);
+wayland_client::delegate_dispatch!(CosmicEnumState: [wayland_protocols::ext::workspace::v1::client::ext_workspace_manager_v1::ExtWorkspaceManagerV1: ()] => WorkspaceState);
+wayland_client::delegate_dispatch!(CosmicEnumState: [wayland_protocols::ext::workspace::v1::client::ext_workspace_group_handle_v1::ExtWorkspaceGroupHandleV1: ()] => WorkspaceState);
+wayland_client::delegate_dispatch!(CosmicEnumState: [wayland_protocols::ext::workspace::v1::client::ext_workspace_handle_v1::ExtWorkspaceHandleV1: ()] => WorkspaceState);
+
 #[derive(Debug, Default)]
 struct CosmicEnumPending {
     identifier: Option<String>,
     app_id: Option<String>,
     title: Option<String>,
-    is_activated: bool,
+    state: ToplevelState,
 }
 
 impl
@@ -746,16 +1114,10 @@ impl wayland_client::Dispatch<cosmic_client_toolkit::cosmic_protocols::toplevel_
 
         if let Some(&foreign_id) = state.cosmic_pending.get(&cosmic_id)
             && let cosmic_client_toolkit::cosmic_protocols::toplevel_info::v1::client::zcosmic_toplevel_handle_v1::Event::State { state: state_bytes } = &event
+            && let Some(toplevel_state) = toplevel_state::decode(state_bytes)
+            && let Some((_h, p)) = state.toplevels.iter_mut().find(|(h, _)| h.id().protocol_id() == foreign_id)
         {
-            if state_bytes.len() % 4 != 0 { return; }
-            for chunk in state_bytes.chunks_exact(4) {
-                let val = u32::from_ne_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
-                if val == cosmic_client_toolkit::cosmic_protocols::toplevel_info::v1::client::zcosmic_toplevel_handle_v1::State::Activated as u32
-                    && let Some((_h, p)) = state.toplevels.iter_mut().find(|(h, _)| h.id().protocol_id() == foreign_id)
-                {
-                    p.is_activated = true;
-                }
-            }
+            p.state = toplevel_state;
         }
     }
 }
@@ -0,0 +1,698 @@
+//! Mock compositor backend for runs without a display (`SESAME_BACKEND=mock`).
+//!
+//! Serves windows from a fixture instead of a Wayland connection, so the
+//! window manager's full flow — enumeration, activation, closing — runs in
+//! CI. Activating a window focuses it in memory and closing removes it;
+//! the fixture file is never written. The format is shared with the
+//! `sesame` CLI's mock mode:
+//!
+//! ```toml
+//! # Windows in MRU order, most recent first.
+//! [[windows]]
+//! app_id = "org.mozilla.firefox"
+//! title = "Inbox - Mozilla Firefox"
+//! focused = true
+//! output = "DP-1"
+//! geometry = { x = 0, y = 0, width = 1280, height = 1440 }
+//! workspace = "1"
+//!
+//! # Workspaces, listed with `can_switch_workspace = true`.
+//! [[workspaces]]
+//! name = "1"
+//! active = true
+//!
+//! # Outputs, by connector name.
+//! [[outputs]]
+//! name = "DP-1"
+//! description = "Dell U2720Q"
+//!
+//! [compositor]
+//! can_activate = true
+//! can_switch_workspace = true
+//! can_move_to_output = true
+//! ```
+//!
+//! IDs are stable across runs: the first window is
+//! `win-00000000-0000-0000-0000-000000000001` and the first workspace
+//! `ws-00000000-0000-0000-0000-000000000001`. Output ids follow their names,
+//! as on the Wayland backends.
+
+use crate::compositor::{
+    BoxFuture, Capabilities, CompositorBackend, WindowChange, Workspace, output_id,
+};
+use core_types::{
+    CompositorWorkspaceId, Geometry, MonitorId, Output, Window, WindowId, WindowPlacement,
+    WindowToggle,
+};
+use serde::Deserialize;
+use std::path::Path;
+use std::sync::Mutex;
+
+/// Path of the fixture read when `SESAME_BACKEND=mock`.
+pub const MOCK_FIXTURE_ENV: &str = "SESAME_MOCK_FIXTURE";
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Fixture {
+    #[serde(default)]
+    windows: Vec<FixtureWindow>,
+    #[serde(default)]
+    workspaces: Vec<FixtureWorkspace>,
+    #[serde(default)]
+    outputs: Vec<FixtureOutput>,
+    #[serde(default)]
+    compositor: FixtureCompositor,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FixtureOutput {
+    name: String,
+    #[serde(default)]
+    description: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FixtureWorkspace {
+    name: String,
+    #[serde(default)]
+    active: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FixtureWindow {
+    app_id: String,
+    title: String,
+    #[serde(default)]
+    focused: bool,
+    #[serde(default)]
+    minimized: bool,
+    #[serde(default)]
+    sticky: bool,
+    #[serde(default)]
+    keep_above: bool,
+    /// Name of the output the window is on.
+    #[serde(default)]
+    output: Option<String>,
+    /// Position and size on its output.
+    #[serde(default)]
+    geometry: Option<Geometry>,
+    /// Name of the workspace the window is on, from `[[workspaces]]`.
+    #[serde(default)]
+    workspace: Option<String>,
+}
+
+/// Activating, closing and maximizing are supported unless turned off;
+/// tiling, the window toggles and the optional protocols are absent, as on
+/// most real compositors.
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct FixtureCompositor {
+    can_activate: bool,
+    can_close: bool,
+    can_maximize: bool,
+    can_tile: bool,
+    can_stick: bool,
+    can_keep_above: bool,
+    has_workspace_info: bool,
+    can_switch_workspace: bool,
+    can_move_to_output: bool,
+    has_screencopy: bool,
+}
+
+impl Default for FixtureCompositor {
+    fn default() -> Self {
+        Self {
+            can_activate: true,
+            can_close: true,
+            can_maximize: true,
+            can_tile: false,
+            can_stick: false,
+            can_keep_above: false,
+            has_workspace_info: false,
+            can_switch_workspace: false,
+            can_move_to_output: false,
+            has_screencopy: false,
+        }
+    }
+}
+
+/// In-memory compositor serving a fixed set of windows.
+pub struct MockBackend {
+    windows: Mutex<Vec<Window>>,
+    workspaces: Mutex<Vec<Workspace>>,
+    outputs: Vec<Output>,
+    capabilities: Capabilities,
+    changes: tokio::sync::broadcast::Sender<WindowChange>,
+}
+
+impl MockBackend {
+    /// A backend listing `windows` (MRU order) with `capabilities`.
+    #[must_use]
+    pub fn new(windows: Vec<Window>, capabilities: Capabilities) -> Self {
+        Self {
+            windows: Mutex::new(windows),
+            workspaces: Mutex::new(Vec::new()),
+            outputs: Vec::new(),
+            capabilities,
+            changes: crate::compositor::window_changes(),
+        }
+    }
+
+    /// Also list `workspaces`, if `capabilities` allow switching them.
+    #[must_use]
+    pub fn with_workspaces(self, workspaces: Vec<Workspace>) -> Self {
+        Self {
+            workspaces: Mutex::new(workspaces),
+            ..self
+        }
+    }
+
+    /// Also list `outputs`.
+    #[must_use]
+    pub fn with_outputs(self, outputs: Vec<Output>) -> Self {
+        Self { outputs, ..self }
+    }
+
+    /// Replace the listed windows, as if the compositor had opened, closed
+    /// or reordered some. Windows kept with a new title or app ID are sent
+    /// to `subscribe_changes`. A poisoned lock is ignored.
+    pub fn set_windows(&self, windows: Vec<Window>) {
+        if let Ok(mut current) = self.windows.lock() {
+            for window in &windows {
+                let before = current.iter().find(|w| w.id == window.id);
+                if let Some(change) = WindowChange::between(
+                    window.id,
+                    before.map(|b| (b.app_id.as_str(), b.title.as_str())),
+                    window.app_id.as_str(),
+                    &window.title,
+                ) {
+                    let _ = self.changes.send(change);
+                }
+            }
+            *current = windows;
+        }
+    }
+
+    /// Load the fixture at `path`.
+    pub fn from_fixture(path: &Path) -> core_types::Result<Self> {
+        let contents = std::fs::read_to_string(path).map_err(|e| {
+            core_types::Error::Platform(format!(
+                "failed to read mock fixture {}: {e}",
+                path.display()
+            ))
+        })?;
+        Self::parse(&contents).map_err(|e| {
+            core_types::Error::Platform(format!(
+                "failed to parse mock fixture {}: {e}",
+                path.display()
+            ))
+        })
+    }
+
+    /// Load the fixture named by `SESAME_MOCK_FIXTURE`.
+    pub fn from_env() -> core_types::Result<Self> {
+        let path = std::env::var_os(MOCK_FIXTURE_ENV).ok_or_else(|| {
+            core_types::Error::Platform(format!(
+                "{}=mock requires {MOCK_FIXTURE_ENV}",
+                crate::compositor::BACKEND_ENV
+            ))
+        })?;
+        Self::from_fixture(Path::new(&path))
+    }
+
+    fn parse(contents: &str) -> Result<Self, toml::de::Error> {
+        let fixture: Fixture = toml::from_str(contents)?;
+        let workspaces: Vec<Workspace> = fixture
+            .workspaces
+            .into_iter()
+            .zip(1u128..)
+            .map(|(ws, n)| Workspace {
+                id: CompositorWorkspaceId::from_uuid(uuid::Uuid::from_u128(n)),
+                name: ws.name,
+                is_active: ws.active,
+            })
+            .collect();
+        let workspace_id = |name: Option<&str>| {
+            workspaces
+                .iter()
+                .find(|ws| Some(ws.name.as_str()) == name)
+                .map_or_else(
+                    || CompositorWorkspaceId::from_uuid(uuid::Uuid::nil()),
+                    |ws| ws.id,
+                )
+        };
+        let windows = fixture
+            .windows
+            .into_iter()
+            .zip(1u128..)
+            .map(|(w, n)| Window {
+                id: WindowId::from_uuid(uuid::Uuid::from_u128(n)),
+                app_id: core_types::AppId::new(w.app_id),
+                title: w.title,
+                workspace_id: workspace_id(w.workspace.as_deref()),
+                monitor_id: w
+                    .output
+                    .as_deref()
+                    .map_or_else(|| MonitorId::from_uuid(uuid::Uuid::nil()), output_id),
+                geometry: w.geometry.unwrap_or(Geometry {
+                    x: 0,
+                    y: 0,
+                    width: 0,
+                    height: 0,
+                }),
+                is_focused: w.focused,
+                is_minimized: w.minimized,
+                is_fullscreen: false,
+                is_maximized: false,
+                profile_id: core_types::ProfileId::from_uuid(uuid::Uuid::nil()),
+                parent: None,
+                is_sticky: w.sticky,
+                is_keep_above: w.keep_above,
+            })
+            .collect();
+        let outputs = fixture
+            .outputs
+            .into_iter()
+            .map(|o| Output {
+                id: output_id(&o.name),
+                name: o.name,
+                description: o.description,
+            })
+            .collect();
+        let c = fixture.compositor;
+        Ok(Self::new(
+            windows,
+            Capabilities {
+                backend: "mock",
+                can_activate: c.can_activate,
+                can_close: c.can_close,
+                can_maximize: c.can_maximize,
+                can_tile: c.can_tile,
+                can_stick: c.can_stick,
+                can_keep_above: c.can_keep_above,
+                has_workspace_info: c.has_workspace_info,
+                can_switch_workspace: c.can_switch_workspace,
+                can_move_to_output: c.can_move_to_output,
+                has_screencopy: c.has_screencopy,
+                protocols: Vec::new(),
+            },
+        )
+        .with_workspaces(workspaces)
+        .with_outputs(outputs))
+    }
+
+    fn with_windows<T>(
+        &self,
+        f: impl FnOnce(&mut Vec<Window>) -> core_types::Result<T>,
+    ) -> core_types::Result<T> {
+        let mut windows = self
+            .windows
+            .lock()
+            .map_err(|e| core_types::Error::Platform(format!("lock poisoned: {e}")))?;
+        f(&mut windows)
+    }
+
+    fn with_workspaces_locked<T>(
+        &self,
+        f: impl FnOnce(&mut Vec<Workspace>) -> core_types::Result<T>,
+    ) -> core_types::Result<T> {
+        let mut workspaces = self
+            .workspaces
+            .lock()
+            .map_err(|e| core_types::Error::Platform(format!("lock poisoned: {e}")))?;
+        f(&mut workspaces)
+    }
+
+    fn unsupported(&self, action: &str) -> core_types::Error {
+        core_types::Error::Platform(format!("mock compositor: {action} not supported"))
+    }
+}
+
+impl CompositorBackend for MockBackend {
+    fn list_windows(&self) -> BoxFuture<'_, core_types::Result<Vec<Window>>> {
+        Box::pin(async move { self.with_windows(|windows| Ok(windows.clone())) })
+    }
+
+    fn list_workspaces(&self) -> BoxFuture<'_, core_types::Result<Vec<Workspace>>> {
+        Box::pin(async move {
+            if !self.capabilities.can_switch_workspace {
+                return Ok(vec![]);
+            }
+            self.with_workspaces_locked(|workspaces| Ok(workspaces.clone()))
+        })
+    }
+
+    fn activate_workspace(
+        &self,
+        id: &CompositorWorkspaceId,
+    ) -> BoxFuture<'_, core_types::Result<()>> {
+        let id = *id;
+        Box::pin(async move {
+            if !self.capabilities.can_switch_workspace {
+                return Err(self.unsupported("switching workspaces"));
+            }
+            self.with_workspaces_locked(|workspaces| {
+                if !workspaces.iter().any(|ws| ws.id == id) {
+                    return Err(core_types::Error::Platform("workspace not found".into()));
+                }
+                for ws in workspaces.iter_mut() {
+                    ws.is_active = ws.id == id;
+                }
+                Ok(())
+            })
+        })
+    }
+
+    fn activate_window(&self, id: &WindowId) -> BoxFuture<'_, core_types::Result<()>> {
+        let id = *id;
+        Box::pin(async move {
+            if !self.capabilities.can_activate {
+                return Err(self.unsupported("activation"));
+            }
+            self.with_windows(|windows| {
+                if !windows.iter().any(|w| w.id == id) {
+                    return Err(core_types::Error::Platform("window not found".into()));
+                }
+                for w in windows.iter_mut() {
+                    w.is_focused = w.id == id;
+                    w.is_minimized &= !w.is_focused;
+                }
+                Ok(())
+            })
+        })
+    }
+
+    fn set_window_geometry(
+        &self,
+        _id: &WindowId,
+        _geom: &Geometry,
+    ) -> BoxFuture<'_, core_types::Result<()>> {
+        Box::pin(async move { Err(self.unsupported("set_window_geometry")) })
+    }
+
+    fn move_to_workspace(
+        &self,
+        _id: &WindowId,
+        _ws: &CompositorWorkspaceId,
+    ) -> BoxFuture<'_, core_types::Result<()>> {
+        Box::pin(async move { Err(self.unsupported("move_to_workspace")) })
+    }
+
+    fn list_outputs(&self) -> BoxFuture<'_, core_types::Result<Vec<Output>>> {
+        Box::pin(async move { Ok(self.outputs.clone()) })
+    }
+
+    fn move_to_output(
+        &self,
+        id: &WindowId,
+        output: &MonitorId,
+    ) -> BoxFuture<'_, core_types::Result<()>> {
+        let (id, output) = (*id, *output);
+        Box::pin(async move {
+            if !self.capabilities.can_move_to_output {
+                return Err(self.unsupported("moving windows between outputs"));
+            }
+            if !self.outputs.iter().any(|o| o.id == output) {
+                return Err(core_types::Error::Platform("output not found".into()));
+            }
+            self.with_windows(|windows| {
+                let window = windows
+                    .iter_mut()
+                    .find(|w| w.id == id)
+                    .ok_or_else(|| core_types::Error::Platform("window not found".into()))?;
+                window.monitor_id = output;
+                Ok(())
+            })
+        })
+    }
+
+    fn focus_window(&self, id: &WindowId) -> BoxFuture<'_, core_types::Result<()>> {
+        self.activate_window(id)
+    }
+
+    fn close_window(&self, id: &WindowId) -> BoxFuture<'_, core_types::Result<()>> {
+        let id = *id;
+        Box::pin(async move {
+            if !self.capabilities.can_close {
+                return Err(self.unsupported("closing windows"));
+            }
+            self.with_windows(|windows| {
+                let before = windows.len();
+                windows.retain(|w| w.id != id);
+                if windows.len() == before {
+                    return Err(core_types::Error::Platform("window not found".into()));
+                }
+                Ok(())
+            })
+        })
+    }
+
+    fn place_window(
+        &self,
+        id: &WindowId,
+        placement: WindowPlacement,
+    ) -> BoxFuture<'_, core_types::Result<()>> {
+        let id = *id;
+        Box::pin(async move {
+            let supported = match placement {
+                WindowPlacement::Maximized => self.capabilities.can_maximize,
+                WindowPlacement::LeftHalf | WindowPlacement::RightHalf => {
+                    self.capabilities.can_tile
+                }
+            };
+            if !supported {
+                return Err(self.unsupported("placing windows"));
+            }
+            self.with_windows(|windows| {
+                if windows.iter().any(|w| w.id == id) {
+                    Ok(())
+                } else {
+                    Err(core_types::Error::Platform("window not found".into()))
+                }
+            })
+        })
+    }
+
+    fn set_window_toggle(
+        &self,
+        id: &WindowId,
+        toggle: WindowToggle,
+        on: bool,
+    ) -> BoxFuture<'_, core_types::Result<()>> {
+        let id = *id;
+        Box::pin(async move {
+            let supported = match toggle {
+                WindowToggle::Sticky => self.capabilities.can_stick,
+                WindowToggle::KeepAbove => self.capabilities.can_keep_above,
+            };
+            if !supported {
+                return Err(self.unsupported("window toggles"));
+            }
+            self.with_windows(|windows| {
+                let window = windows
+                    .iter_mut()
+                    .find(|w| w.id == id)
+                    .ok_or_else(|| core_types::Error::Platform("window not found".into()))?;
+                match toggle {
+                    WindowToggle::Sticky => window.is_sticky = on,
+                    WindowToggle::KeepAbove => window.is_keep_above = on,
+                }
+                Ok(())
+            })
+        })
+    }
+
+    fn subscribe_changes(&self) -> tokio::sync::broadcast::Receiver<WindowChange> {
+        self.changes.subscribe()
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        self.capabilities.clone()
+    }
+
+    fn name(&self) -> &str {
+        "mock"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURE: &str = r#"
+[[windows]]
+app_id = "firefox"
+title = "Inbox"
+focused = true
+
+[[windows]]
+app_id = "com.mitchellh.ghostty"
+title = "~/src"
+"#;
+
+    fn block_on<T>(fut: BoxFuture<'_, T>) -> T {
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(fut)
+    }
+
+    #[test]
+    fn activation_moves_focus_and_close_removes() {
+        let backend = MockBackend::parse(FIXTURE).unwrap();
+        let windows = block_on(backend.list_windows()).unwrap();
+        assert_eq!(
+            windows[1].id.to_string(),
+            "win-00000000-0000-0000-0000-000000000002"
+        );
+
+        block_on(backend.activate_window(&windows[1].id)).unwrap();
+        let after = block_on(backend.list_windows()).unwrap();
+        assert!(!after[0].is_focused && after[1].is_focused);
+
+        block_on(backend.close_window(&windows[0].id)).unwrap();
+        assert_eq!(block_on(backend.list_windows()).unwrap().len(), 1);
+        assert!(block_on(backend.close_window(&windows[0].id)).is_err());
+    }
+
+    #[test]
+    fn retitled_windows_are_sent_to_subscribers() {
+        let backend = MockBackend::parse(FIXTURE).unwrap();
+        let mut changes = backend.subscribe_changes();
+        let mut windows = block_on(backend.list_windows()).unwrap();
+        windows[1].title = "~/src/open-sesame".into();
+        let closed = windows.remove(0);
+        backend.set_windows(windows.clone());
+
+        let change = changes.try_recv().unwrap();
+        assert_eq!(change.id, windows[0].id);
+        assert_eq!(change.title, "~/src/open-sesame");
+        assert_eq!(change.app_id.as_str(), "com.mitchellh.ghostty");
+        // Closing or reopening a window is not a change.
+        let mut reopened = closed;
+        reopened.title = "Drafts".into();
+        windows.push(reopened);
+        backend.set_windows(windows);
+        assert!(changes.try_recv().is_err());
+    }
+
+    #[test]
+    fn activation_restores_a_minimized_window() {
+        let backend = MockBackend::parse(
+            r#"
+[[windows]]
+app_id = "firefox"
+title = "Inbox"
+minimized = true
+"#,
+        )
+        .unwrap();
+        let windows = block_on(backend.list_windows()).unwrap();
+        assert!(windows[0].is_minimized);
+
+        block_on(backend.activate_window(&windows[0].id)).unwrap();
+        let after = block_on(backend.list_windows()).unwrap();
+        assert!(after[0].is_focused && !after[0].is_minimized);
+    }
+
+    #[test]
+    fn fixture_capabilities_gate_actions() {
+        let backend =
+            MockBackend::parse(&format!("{FIXTURE}\n[compositor]\ncan_activate = false\n"))
+                .unwrap();
+        let caps = backend.capabilities();
+        assert_eq!(caps.backend, "mock");
+        assert!(!caps.can_activate && caps.can_close);
+        let id = block_on(backend.list_windows()).unwrap()[1].id;
+        assert!(block_on(backend.activate_window(&id)).is_err());
+        assert!(block_on(backend.place_window(&id, WindowPlacement::Maximized)).is_ok());
+        assert!(block_on(backend.place_window(&id, WindowPlacement::LeftHalf)).is_err());
+    }
+
+    #[test]
+    fn toggles_update_the_listed_window() {
+        let backend = MockBackend::parse(&format!(
+            "{FIXTURE}sticky = true\n\n[compositor]\ncan_stick = true\n"
+        ))
+        .unwrap();
+        let id = block_on(backend.list_windows()).unwrap()[1].id;
+        assert!(block_on(backend.list_windows()).unwrap()[1].is_sticky);
+
+        block_on(backend.set_window_toggle(&id, WindowToggle::Sticky, false)).unwrap();
+        assert!(!block_on(backend.list_windows()).unwrap()[1].is_sticky);
+        assert!(block_on(backend.set_window_toggle(&id, WindowToggle::KeepAbove, true)).is_err());
+    }
+
+    #[test]
+    fn workspaces_are_listed_and_switched_when_supported() {
+        let workspaces =
+            "[[workspaces]]\nname = \"1\"\nactive = true\n\n[[workspaces]]\nname = \"Mail\"\n";
+        let backend = MockBackend::parse(&format!("{FIXTURE}{workspaces}")).unwrap();
+        assert!(block_on(backend.list_workspaces()).unwrap().is_empty());
+
+        let backend = MockBackend::parse(&format!(
+            "{FIXTURE}{workspaces}\n[compositor]\ncan_switch_workspace = true\n"
+        ))
+        .unwrap();
+        let listed = block_on(backend.list_workspaces()).unwrap();
+        assert_eq!(listed[1].name, "Mail");
+        assert_eq!(
+            listed[1].id.to_string(),
+            "ws-00000000-0000-0000-0000-000000000002"
+        );
+        block_on(backend.activate_workspace(&listed[1].id)).unwrap();
+        let after = block_on(backend.list_workspaces()).unwrap();
+        assert!(!after[0].is_active && after[1].is_active);
+        assert!(block_on(backend.activate_workspace(&CompositorWorkspaceId::new())).is_err());
+    }
+
+    #[test]
+    fn windows_carry_their_geometry_and_workspace() {
+        let backend = MockBackend::parse(&format!(
+            "{FIXTURE}geometry = {{ x = 10, y = 20, width = 800, height = 600 }}\n\
+             workspace = \"Mail\"\n\n[[workspaces]]\nname = \"1\"\n\n\
+             [[workspaces]]\nname = \"Mail\"\n"
+        ))
+        .unwrap();
+        let windows = block_on(backend.list_windows()).unwrap();
+        assert_eq!(windows[1].geometry.width, 800);
+        assert_eq!(windows[1].geometry.y, 20);
+        assert_eq!(
+            windows[1].workspace_id.to_string(),
+            "ws-00000000-0000-0000-0000-000000000002"
+        );
+        assert_eq!(windows[0].geometry.width, 0);
+    }
+
+    #[test]
+    fn windows_move_between_listed_outputs_when_supported() {
+        let outputs = "output = \"DP-1\"\n\n[[outputs]]\nname = \"DP-1\"\n\n\
+                       [[outputs]]\nname = \"HDMI-A-1\"\ndescription = \"TV\"\n";
+        let backend = MockBackend::parse(&format!("{FIXTURE}{outputs}")).unwrap();
+        let listed = block_on(backend.list_outputs()).unwrap();
+        assert_eq!(listed[1].description, "TV");
+        assert_eq!(listed[0].id, output_id("DP-1"));
+        let window = block_on(backend.list_windows()).unwrap()[1].clone();
+        assert_eq!(window.monitor_id, listed[0].id);
+        assert!(block_on(backend.move_to_output(&window.id, &listed[1].id)).is_err());
+
+        let backend = MockBackend::parse(&format!(
+            "{FIXTURE}{outputs}\n[compositor]\ncan_move_to_output = true\n"
+        ))
+        .unwrap();
+        block_on(backend.move_to_output(&window.id, &listed[1].id)).unwrap();
+        assert_eq!(
+            block_on(backend.list_windows()).unwrap()[1].monitor_id,
+            listed[1].id
+        );
+        assert!(block_on(backend.move_to_output(&window.id, &MonitorId::new())).is_err());
+    }
+
+    #[test]
+    fn unknown_fixture_keys_are_rejected() {
+        assert!(MockBackend::parse("[[windows]]\napp = \"x\"\ntitle = \"y\"").is_err());
+    }
+}
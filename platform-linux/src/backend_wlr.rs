@@ -1,8 +1,10 @@
 //! CompositorBackend implementation using wlr-foreign-toplevel-management-v1.
 //!
-//! Tracks all toplevels via the wlr protocol. Supports list, activate, focus,
-//! and close operations. Compatible with sway, Hyprland, niri, Wayfire, and
-//! COSMIC (backwards-compatible wlr advertisement).
+//! Tracks all toplevels via the wlr protocol, including the parent of dialogs
+//! (protocol v3) and the output each is on. Supports list, activate, focus,
+//! and close operations; the protocol cannot move windows between outputs.
+//! Compatible with sway, Hyprland, niri, Wayfire, and COSMIC
+//! (backwards-compatible wlr advertisement).
 //!
 //! Architecture: a dedicated dispatch thread continuously reads Wayland events
 //! and updates a shared state snapshot on each `Done` event (the protocol's
@@ -10,13 +12,23 @@
 //! and `close_window()` call proxy methods directly (wayland-client 0.31 proxies
 //! are `Send + Sync`) and flush the shared connection.
 
-use crate::compositor::{BoxFuture, CompositorBackend, Workspace};
-use core_types::{CompositorWorkspaceId, Geometry, Window, WindowId};
+use crate::capabilities::{WL_SEAT_MAX, WLR_TOPLEVEL_MANAGER_MAX, advertised_globals};
+use crate::compositor::{BoxFuture, Capabilities, CompositorBackend, WindowChange, Workspace};
+use crate::outputs::OutputState;
+use crate::toplevel_state;
+use core_types::{
+    CompositorWorkspaceId, Geometry, MonitorId, Output, ToplevelState, Window, WindowId,
+    WindowPlacement, WindowToggle,
+};
 
 pub(crate) struct WlrBackend {
     state: std::sync::Arc<std::sync::Mutex<WlrState>>,
     conn: wayland_client::Connection,
     seat: wayland_client::protocol::wl_seat::WlSeat,
+    capabilities: Capabilities,
+    changes: tokio::sync::broadcast::Sender<WindowChange>,
+    /// Outputs as tracked by the dispatch thread.
+    outputs: std::sync::Arc<std::sync::Mutex<Vec<Output>>>,
     /// Kept alive so the protocol manager isn't dropped (which sends `stop` to compositor).
     _manager: wayland_protocols_wlr::foreign_toplevel::v1::client::zwlr_foreign_toplevel_manager_v1::ZwlrForeignToplevelManagerV1,
 }
@@ -30,7 +42,11 @@ struct WlrState {
 struct WlrToplevelSnapshot {
     app_id: String,
     title: String,
-    activated: bool,
+    state: ToplevelState,
+    /// Toplevel this one is transient for (protocol v3 `parent` event).
+    parent: Option<WindowId>,
+    /// Output the toplevel was last reported on.
+    monitor: Option<MonitorId>,
     /// Proxy handle for activate/close — Send+Sync in wayland-client 0.31.
     handle: wayland_protocols_wlr::foreign_toplevel::v1::client::zwlr_foreign_toplevel_handle_v1::ZwlrForeignToplevelHandleV1,
 }
@@ -40,7 +56,10 @@ struct WlrPendingToplevel {
     window_id: WindowId,
     app_id: String,
     title: String,
-    activated: bool,
+    state: ToplevelState,
+    parent: Option<WindowId>,
+    /// Last output entered, as bound in `WlrDispatchState::outputs`.
+    output: Option<wayland_client::protocol::wl_output::WlOutput>,
     handle: wayland_protocols_wlr::foreign_toplevel::v1::client::zwlr_foreign_toplevel_handle_v1::ZwlrForeignToplevelHandleV1,
 }
 
@@ -48,6 +67,40 @@ struct WlrPendingToplevel {
 struct WlrDispatchState {
     pending: std::collections::HashMap<wayland_client::backend::ObjectId, WlrPendingToplevel>,
     shared: std::sync::Arc<std::sync::Mutex<WlrState>>,
+    /// Title and app ID changes, for `subscribe_changes`.
+    changes: tokio::sync::broadcast::Sender<WindowChange>,
+    /// Outputs bound on the dispatch connection, so `output_enter` events
+    /// can be resolved to output names.
+    outputs: OutputState,
+}
+
+impl AsMut<OutputState> for WlrDispatchState {
+    fn as_mut(&mut self) -> &mut OutputState {
+        &mut self.outputs
+    }
+}
+
+wayland_client::delegate_dispatch!(WlrDispatchState: [wayland_client::protocol::wl_output::WlOutput: ()] => OutputState);
+
+impl WlrDispatchState {
+    /// Pending state for `handle`, created on its first event.
+    fn pending_entry(
+        &mut self,
+        handle: &wayland_protocols_wlr::foreign_toplevel::v1::client::zwlr_foreign_toplevel_handle_v1::ZwlrForeignToplevelHandleV1,
+    ) -> &mut WlrPendingToplevel {
+        use wayland_client::Proxy;
+        self.pending
+            .entry(handle.id())
+            .or_insert_with(|| WlrPendingToplevel {
+                window_id: WindowId::new(),
+                app_id: String::new(),
+                title: String::new(),
+                state: ToplevelState::empty(),
+                parent: None,
+                output: None,
+                handle: handle.clone(),
+            })
+    }
 }
 
 /// User-data attached to each toplevel handle proxy (unit — state tracked in WlrDispatchState).
@@ -63,13 +116,15 @@ impl
     > for WlrDispatchState
 {
     fn event(
-        _: &mut Self,
-        _: &wayland_client::protocol::wl_registry::WlRegistry,
-        _: wayland_client::protocol::wl_registry::Event,
+        state: &mut Self,
+        registry: &wayland_client::protocol::wl_registry::WlRegistry,
+        event: wayland_client::protocol::wl_registry::Event,
         _: &wayland_client::globals::GlobalListContents,
         _: &wayland_client::Connection,
-        _: &wayland_client::QueueHandle<Self>,
+        qh: &wayland_client::QueueHandle<Self>,
     ) {
+        // Outputs plugged in after startup.
+        state.outputs.registry_event(registry, event, qh);
     }
 }
 
@@ -119,39 +174,50 @@ impl wayland_client::Dispatch<wayland_protocols_wlr::foreign_toplevel::v1::clien
         let id = handle.id();
         match event {
             zwlr_foreign_toplevel_handle_v1::Event::AppId { app_id } => {
-                state.pending.entry(id).or_insert_with(|| WlrPendingToplevel {
-                    window_id: WindowId::new(), app_id: String::new(),
-                    title: String::new(), activated: false,
-                    handle: handle.clone(),
-                }).app_id = app_id;
+                state.pending_entry(handle).app_id = app_id;
             }
             zwlr_foreign_toplevel_handle_v1::Event::Title { title } => {
-                state.pending.entry(id).or_insert_with(|| WlrPendingToplevel {
-                    window_id: WindowId::new(), app_id: String::new(),
-                    title: String::new(), activated: false,
-                    handle: handle.clone(),
-                }).title = title;
+                state.pending_entry(handle).title = title;
             }
             zwlr_foreign_toplevel_handle_v1::Event::State { state: state_bytes } => {
-                let activated = state_bytes.chunks_exact(4)
-                    .flat_map(TryInto::<[u8; 4]>::try_into)
-                    .map(u32::from_ne_bytes)
-                    .any(|v| v == zwlr_foreign_toplevel_handle_v1::State::Activated as u32);
-                state.pending.entry(id).or_insert_with(|| WlrPendingToplevel {
-                    window_id: WindowId::new(), app_id: String::new(),
-                    title: String::new(), activated: false,
-                    handle: handle.clone(),
-                }).activated = activated;
+                if let Some(toplevel_state) = toplevel_state::decode(&state_bytes) {
+                    state.pending_entry(handle).state = toplevel_state;
+                }
+            }
+            zwlr_foreign_toplevel_handle_v1::Event::Parent { parent } => {
+                let parent = parent.map(|p| state.pending_entry(&p).window_id);
+                state.pending_entry(handle).parent = parent;
+            }
+            zwlr_foreign_toplevel_handle_v1::Event::OutputEnter { output } => {
+                state.pending_entry(handle).output = Some(output);
+            }
+            zwlr_foreign_toplevel_handle_v1::Event::OutputLeave { output } => {
+                let pending = state.pending_entry(handle);
+                if pending.output.as_ref() == Some(&output) {
+                    pending.output = None;
+                }
             }
             zwlr_foreign_toplevel_handle_v1::Event::Done => {
                 // Atomic commit point — publish to shared state.
                 if let Some(tl) = state.pending.get(&id) {
                     match state.shared.lock() {
                         Ok(mut shared) => {
+                            let before = shared.toplevels.get(&tl.window_id);
+                            if let Some(change) = WindowChange::between(
+                                tl.window_id,
+                                before.map(|b| (b.app_id.as_str(), b.title.as_str())),
+                                &tl.app_id,
+                                &tl.title,
+                            ) {
+                                // No subscribers is not an error.
+                                let _ = state.changes.send(change);
+                            }
                             shared.toplevels.insert(tl.window_id, WlrToplevelSnapshot {
                                 app_id: tl.app_id.clone(),
                                 title: tl.title.clone(),
-                                activated: tl.activated,
+                                state: tl.state,
+                                parent: tl.parent,
+                                monitor: tl.output.as_ref().and_then(|o| state.outputs.id_of(o)),
                                 handle: tl.handle.clone(),
                             });
                         }
@@ -189,23 +255,35 @@ impl WlrBackend {
 
         let qh = event_queue.handle();
 
-        let manager: ZwlrForeignToplevelManagerV1 = globals.bind(&qh, 1..=3, ()).map_err(|e| {
-            core_types::Error::Platform(format!(
-                "wlr-foreign-toplevel-management-v1 not available: {e}"
-            ))
-        })?;
+        // Bind outputs before the manager, so their names arrive before
+        // the toplevels' output_enter events.
+        let mut outputs = OutputState::default();
+        outputs.bind_all(&globals, &qh);
+        let published_outputs = outputs.published();
+
+        let manager: ZwlrForeignToplevelManagerV1 = globals
+            .bind(&qh, 1..=WLR_TOPLEVEL_MANAGER_MAX, ())
+            .map_err(|e| {
+                core_types::Error::Platform(format!(
+                    "wlr-foreign-toplevel-management-v1 not available: {e}"
+                ))
+            })?;
 
         let seat: wl_seat::WlSeat = globals
-            .bind(&qh, 1..=9, ())
+            .bind(&qh, 1..=WL_SEAT_MAX, ())
             .map_err(|e| core_types::Error::Platform(format!("wl_seat not available: {e}")))?;
+        let capabilities = Capabilities::from_globals("wlr", &advertised_globals(&globals));
 
         let shared_state = std::sync::Arc::new(std::sync::Mutex::new(WlrState {
             toplevels: std::collections::HashMap::new(),
         }));
 
+        let changes = crate::compositor::window_changes();
         let mut dispatch_state = WlrDispatchState {
             pending: std::collections::HashMap::new(),
             shared: std::sync::Arc::clone(&shared_state),
+            changes: changes.clone(),
+            outputs,
         };
 
         // Initial roundtrip to receive existing toplevels.
@@ -228,6 +306,9 @@ impl WlrBackend {
             state: shared_state,
             conn,
             seat,
+            capabilities,
+            changes,
+            outputs: published_outputs,
             _manager: manager,
         })
     }
@@ -317,17 +398,23 @@ impl CompositorBackend for WlrBackend {
                     app_id: core_types::AppId::new(&tl.app_id),
                     title: tl.title.clone(),
                     workspace_id: CompositorWorkspaceId::from_uuid(uuid::Uuid::nil()),
-                    monitor_id: core_types::MonitorId::from_uuid(uuid::Uuid::nil()),
+                    monitor_id: tl
+                        .monitor
+                        .unwrap_or_else(|| MonitorId::from_uuid(uuid::Uuid::nil())),
                     geometry: Geometry {
                         x: 0,
                         y: 0,
                         width: 0,
                         height: 0,
                     },
-                    is_focused: tl.activated,
-                    is_minimized: false,
-                    is_fullscreen: false,
+                    is_focused: tl.state.contains(ToplevelState::ACTIVATED),
+                    is_minimized: tl.state.contains(ToplevelState::MINIMIZED),
+                    is_fullscreen: tl.state.contains(ToplevelState::FULLSCREEN),
+                    is_maximized: tl.state.contains(ToplevelState::MAXIMIZED),
                     profile_id: core_types::ProfileId::from_uuid(uuid::Uuid::nil()),
+                    parent: tl.parent,
+                    is_sticky: false,
+                    is_keep_above: false,
                 })
                 .collect();
             Ok(windows)
@@ -335,7 +422,20 @@ impl CompositorBackend for WlrBackend {
     }
 
     fn list_workspaces(&self) -> BoxFuture<'_, core_types::Result<Vec<Workspace>>> {
-        Box::pin(async { Ok(vec![]) })
+        Box::pin(async move {
+            if !self.capabilities.can_switch_workspace {
+                return Ok(vec![]);
+            }
+            crate::workspaces::list()
+        })
+    }
+
+    fn activate_workspace(
+        &self,
+        id: &CompositorWorkspaceId,
+    ) -> BoxFuture<'_, core_types::Result<()>> {
+        let id = *id;
+        Box::pin(async move { crate::workspaces::activate(&id) })
     }
 
     fn activate_window(&self, id: &WindowId) -> BoxFuture<'_, core_types::Result<()>> {
@@ -349,6 +449,10 @@ impl CompositorBackend for WlrBackend {
                 .toplevels
                 .get(&id)
                 .ok_or_else(|| core_types::Error::Platform("window not found".into()))?;
+            // Activation need not restore a minimized window.
+            if tl.state.contains(ToplevelState::MINIMIZED) {
+                tl.handle.unset_minimized();
+            }
             tl.handle.activate(&self.seat);
             drop(state);
             self.conn
@@ -382,6 +486,27 @@ impl CompositorBackend for WlrBackend {
         })
     }
 
+    fn list_outputs(&self) -> BoxFuture<'_, core_types::Result<Vec<Output>>> {
+        Box::pin(async move {
+            self.outputs
+                .lock()
+                .map(|outputs| outputs.clone())
+                .map_err(|e| core_types::Error::Platform(format!("lock poisoned: {e}")))
+        })
+    }
+
+    fn move_to_output(
+        &self,
+        _id: &WindowId,
+        _output: &MonitorId,
+    ) -> BoxFuture<'_, core_types::Result<()>> {
+        Box::pin(async {
+            Err(core_types::Error::Platform(
+                "moving windows between outputs not supported by wlr protocol".into(),
+            ))
+        })
+    }
+
     fn focus_window(&self, id: &WindowId) -> BoxFuture<'_, core_types::Result<()>> {
         self.activate_window(id)
     }
@@ -406,6 +531,56 @@ impl CompositorBackend for WlrBackend {
         })
     }
 
+    fn place_window(
+        &self,
+        id: &WindowId,
+        placement: WindowPlacement,
+    ) -> BoxFuture<'_, core_types::Result<()>> {
+        let id = *id;
+        Box::pin(async move {
+            if placement != WindowPlacement::Maximized {
+                return Err(core_types::Error::Platform(
+                    "tiling not supported by wlr protocol".into(),
+                ));
+            }
+            let state = self
+                .state
+                .lock()
+                .map_err(|e| core_types::Error::Platform(format!("lock poisoned: {e}")))?;
+            let tl = state
+                .toplevels
+                .get(&id)
+                .ok_or_else(|| core_types::Error::Platform("window not found".into()))?;
+            tl.handle.set_maximized();
+            drop(state);
+            self.conn
+                .flush()
+                .map_err(|e| core_types::Error::Platform(format!("flush failed: {e}")))?;
+            Ok(())
+        })
+    }
+
+    fn set_window_toggle(
+        &self,
+        _id: &WindowId,
+        _toggle: WindowToggle,
+        _on: bool,
+    ) -> BoxFuture<'_, core_types::Result<()>> {
+        Box::pin(async {
+            Err(core_types::Error::Platform(
+                "window toggles not supported by wlr protocol".into(),
+            ))
+        })
+    }
+
+    fn subscribe_changes(&self) -> tokio::sync::broadcast::Receiver<WindowChange> {
+        self.changes.subscribe()
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        self.capabilities.clone()
+    }
+
     fn name(&self) -> &str {
         "wlr"
     }
@@ -0,0 +1,280 @@
+//! Compositor capability detection.
+//!
+//! Backends bind each Wayland global at the highest version both the
+//! compositor and this crate implement (`globals.bind(&qh, min..=MAX, ())`).
+//! `Capabilities` records the outcome of that negotiation so callers can
+//! hide actions the compositor cannot perform instead of failing on use.
+//!
+//! `probe_capabilities()` reads the same information from a short-lived
+//! registry connection without binding anything, for diagnostics
+//! (`sesame status --doctor platform`).
+
+/// Highest `ext_foreign_toplevel_list_v1` version the backends implement.
+pub(crate) const EXT_FOREIGN_TOPLEVEL_LIST_MAX: u32 = 1;
+/// Highest `zcosmic_toplevel_info_v1` version the COSMIC backend implements.
+pub(crate) const COSMIC_TOPLEVEL_INFO_MAX: u32 = 3;
+/// Highest `zcosmic_toplevel_manager_v1` version the COSMIC backend implements.
+pub(crate) const COSMIC_TOPLEVEL_MANAGER_MAX: u32 = 4;
+/// `zcosmic_toplevel_manager_v1` version that added `set_sticky`.
+pub(crate) const COSMIC_STICKY_SINCE: u32 = 4;
+/// `zcosmic_toplevel_manager_v1` version that added `move_to_ext_workspace`.
+pub(crate) const COSMIC_MOVE_TO_EXT_WORKSPACE_SINCE: u32 = 4;
+/// Highest `zwlr_foreign_toplevel_manager_v1` version the wlr backend implements.
+pub(crate) const WLR_TOPLEVEL_MANAGER_MAX: u32 = 3;
+/// Highest `wl_seat` version the backends implement.
+pub(crate) const WL_SEAT_MAX: u32 = 9;
+/// Highest `ext_workspace_manager_v1` version the backends implement.
+pub(crate) const EXT_WORKSPACE_MANAGER_MAX: u32 = 1;
+/// Highest `wl_output` version the backends implement (4 added `name`).
+pub(crate) const WL_OUTPUT_MAX: u32 = 4;
+
+const COSMIC_TOPLEVEL_MANAGER: &str = "zcosmic_toplevel_manager_v1";
+const WLR_TOPLEVEL_MANAGER: &str = "zwlr_foreign_toplevel_manager_v1";
+pub(crate) const EXT_WORKSPACE_MANAGER: &str = "ext_workspace_manager_v1";
+
+/// Protocols bound by the compositor backends, with the highest version
+/// implemented for each.
+const BACKEND_PROTOCOLS: &[(&str, u32)] = &[
+    (
+        "ext_foreign_toplevel_list_v1",
+        EXT_FOREIGN_TOPLEVEL_LIST_MAX,
+    ),
+    ("zcosmic_toplevel_info_v1", COSMIC_TOPLEVEL_INFO_MAX),
+    (COSMIC_TOPLEVEL_MANAGER, COSMIC_TOPLEVEL_MANAGER_MAX),
+    (WLR_TOPLEVEL_MANAGER, WLR_TOPLEVEL_MANAGER_MAX),
+    ("wl_seat", WL_SEAT_MAX),
+    (EXT_WORKSPACE_MANAGER, EXT_WORKSPACE_MANAGER_MAX),
+    ("wl_output", WL_OUTPUT_MAX),
+];
+
+/// Globals that expose workspace state.
+const WORKSPACE_PROTOCOLS: &[&str] = &[EXT_WORKSPACE_MANAGER, "zcosmic_workspace_manager_v1"];
+
+/// Globals that allow capturing window or output contents.
+const SCREENCOPY_PROTOCOLS: &[&str] = &[
+    "zwlr_screencopy_manager_v1",
+    "ext_image_copy_capture_manager_v1",
+];
+
+/// What the running compositor lets a backend do.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Capabilities {
+    /// Backend these capabilities apply to ("cosmic" or "wlr").
+    pub backend: &'static str,
+    /// Windows can be raised and focused.
+    pub can_activate: bool,
+    /// Windows can be asked to close.
+    pub can_close: bool,
+    /// Windows can be maximized.
+    pub can_maximize: bool,
+    /// Windows can be tiled to the left or right half of their output.
+    pub can_tile: bool,
+    /// Windows can be shown on every workspace.
+    pub can_stick: bool,
+    /// Windows can be kept above other windows.
+    pub can_keep_above: bool,
+    /// The compositor advertises a workspace protocol.
+    pub has_workspace_info: bool,
+    /// Workspaces can be listed by name and switched to.
+    pub can_switch_workspace: bool,
+    /// Windows can be moved to another output.
+    pub can_move_to_output: bool,
+    /// The compositor advertises a screen or window capture protocol.
+    pub has_screencopy: bool,
+    /// Backend protocols the compositor advertises, with the negotiated
+    /// version (highest version supported by both sides).
+    pub protocols: Vec<(String, u32)>,
+}
+
+impl Capabilities {
+    /// Derive capabilities from the advertised `(interface, version)` globals.
+    pub(crate) fn from_globals(backend: &'static str, globals: &[(String, u32)]) -> Self {
+        let advertised = |name: &str| globals.iter().any(|(interface, _)| interface == name);
+        let manager = if backend == "cosmic" {
+            COSMIC_TOPLEVEL_MANAGER
+        } else {
+            WLR_TOPLEVEL_MANAGER
+        };
+        let has_manager = advertised(manager);
+        let protocols: Vec<(String, u32)> = BACKEND_PROTOCOLS
+            .iter()
+            .filter_map(|(name, max)| {
+                globals
+                    .iter()
+                    .find(|(interface, _)| interface == name)
+                    .map(|(_, version)| ((*name).to_string(), (*version).min(*max)))
+            })
+            .collect();
+        let negotiated = |name: &str| {
+            protocols
+                .iter()
+                .find(|(interface, _)| interface == name)
+                .map_or(0, |(_, version)| *version)
+        };
+
+        Self {
+            backend,
+            can_activate: has_manager && advertised("wl_seat"),
+            can_close: has_manager,
+            can_maximize: has_manager,
+            // Neither toplevel manager can position or resize windows.
+            can_tile: false,
+            can_stick: backend == "cosmic"
+                && negotiated(COSMIC_TOPLEVEL_MANAGER) >= COSMIC_STICKY_SINCE,
+            // No toplevel manager protocol has a keep-above request.
+            can_keep_above: false,
+            has_workspace_info: WORKSPACE_PROTOCOLS.iter().any(|p| advertised(p)),
+            can_switch_workspace: advertised(EXT_WORKSPACE_MANAGER),
+            // COSMIC moves a window to an output by moving it to that
+            // output's active workspace; wlr has no such request.
+            can_move_to_output: backend == "cosmic"
+                && negotiated(COSMIC_TOPLEVEL_MANAGER) >= COSMIC_MOVE_TO_EXT_WORKSPACE_SINCE
+                && advertised(EXT_WORKSPACE_MANAGER),
+            has_screencopy: SCREENCOPY_PROTOCOLS.iter().any(|p| advertised(p)),
+            protocols,
+        }
+    }
+}
+
+/// Snapshot the `(interface, version)` pairs of a registry's globals.
+pub(crate) fn advertised_globals(
+    globals: &wayland_client::globals::GlobalList,
+) -> Vec<(String, u32)> {
+    globals.contents().with_list(|list| {
+        list.iter()
+            .map(|g| (g.interface.clone(), g.version))
+            .collect()
+    })
+}
+
+/// Connect to the compositor and report its capabilities.
+///
+/// Selects the backend the same way `detect_compositor()` does — COSMIC
+/// protocols first, then wlr — but only reads the registry. With
+/// `SESAME_BACKEND=mock`, reports the fixture compositor instead.
+pub fn probe_capabilities() -> core_types::Result<Capabilities> {
+    use crate::compositor::CompositorBackend;
+    use wayland_client::{Connection, globals::registry_queue_init};
+
+    if crate::compositor::requested_backend()?.as_deref() == Some("mock") {
+        return Ok(crate::backend_mock::MockBackend::from_env()?.capabilities());
+    }
+
+    let conn = Connection::connect_to_env()
+        .map_err(|e| core_types::Error::Platform(format!("Wayland connection failed: {e}")))?;
+    let (globals, _event_queue) = registry_queue_init::<ProbeState>(&conn)
+        .map_err(|e| core_types::Error::Platform(format!("Wayland registry init failed: {e}")))?;
+
+    let advertised = advertised_globals(&globals);
+    let has = |name: &str| advertised.iter().any(|(interface, _)| interface == name);
+    let backend = if has("ext_foreign_toplevel_list_v1") && has("zcosmic_toplevel_info_v1") {
+        "cosmic"
+    } else if has(WLR_TOPLEVEL_MANAGER) {
+        "wlr"
+    } else {
+        return Err(core_types::Error::Platform(
+            "no supported toplevel protocol advertised".into(),
+        ));
+    };
+    Ok(Capabilities::from_globals(backend, &advertised))
+}
+
+struct ProbeState;
+
+impl
+    wayland_client::Dispatch<
+        wayland_client::protocol::wl_registry::WlRegistry,
+        wayland_client::globals::GlobalListContents,
+    > for ProbeState
+{
+    fn event(
+        _: &mut Self,
+        _: &wayland_client::protocol::wl_registry::WlRegistry,
+        _: wayland_client::protocol::wl_registry::Event,
+        _: &wayland_client::globals::GlobalListContents,
+        _: &wayland_client::Connection,
+        _: &wayland_client::QueueHandle<Self>,
+    ) {
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn globals(list: &[(&str, u32)]) -> Vec<(String, u32)> {
+        list.iter().map(|(i, v)| ((*i).to_string(), *v)).collect()
+    }
+
+    #[test]
+    fn negotiates_highest_common_version() {
+        let caps = Capabilities::from_globals(
+            "wlr",
+            &globals(&[
+                ("wl_seat", 7),
+                ("zwlr_foreign_toplevel_manager_v1", 5),
+                ("zwlr_screencopy_manager_v1", 3),
+            ]),
+        );
+        assert!(caps.can_activate);
+        assert!(caps.can_close);
+        assert!(caps.can_maximize && !caps.can_tile);
+        assert!(!caps.can_stick && !caps.can_keep_above);
+        assert!(caps.has_screencopy);
+        assert!(!caps.has_workspace_info && !caps.can_switch_workspace);
+        assert!(!caps.can_move_to_output);
+        assert_eq!(
+            caps.protocols,
+            globals(&[("zwlr_foreign_toplevel_manager_v1", 3), ("wl_seat", 7)])
+        );
+    }
+
+    #[test]
+    fn cosmic_without_manager_cannot_activate() {
+        let caps = Capabilities::from_globals(
+            "cosmic",
+            &globals(&[
+                ("wl_seat", 9),
+                ("ext_foreign_toplevel_list_v1", 1),
+                ("zcosmic_toplevel_info_v1", 3),
+                ("zwlr_foreign_toplevel_manager_v1", 3),
+                ("ext_workspace_manager_v1", 1),
+            ]),
+        );
+        assert!(!caps.can_activate);
+        assert!(!caps.can_close);
+        assert!(!caps.can_maximize);
+        assert!(caps.has_workspace_info && caps.can_switch_workspace);
+    }
+
+    #[test]
+    fn cosmic_sticky_needs_manager_v4() {
+        let caps = |manager: u32| {
+            Capabilities::from_globals(
+                "cosmic",
+                &globals(&[("wl_seat", 9), ("zcosmic_toplevel_manager_v1", manager)]),
+            )
+        };
+        assert!(!caps(3).can_stick);
+        assert!(caps(4).can_stick);
+        assert!(caps(5).can_stick && !caps(5).can_keep_above);
+    }
+    #[test]
+    fn moving_to_outputs_needs_cosmic_manager_v4_and_workspaces() {
+        let caps = |backend, manager: u32, workspaces: bool| {
+            let mut list = vec![
+                ("wl_seat", 9),
+                ("zcosmic_toplevel_manager_v1", manager),
+                ("zwlr_foreign_toplevel_manager_v1", 3),
+            ];
+            if workspaces {
+                list.push(("ext_workspace_manager_v1", 1));
+            }
+            Capabilities::from_globals(backend, &globals(&list)).can_move_to_output
+        };
+        assert!(caps("cosmic", 4, true));
+        assert!(!caps("cosmic", 3, true));
+        assert!(!caps("cosmic", 4, false));
+        assert!(!caps("wlr", 4, true));
+    }
+}
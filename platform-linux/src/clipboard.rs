@@ -6,7 +6,9 @@
 //!
 //! COSMIC note: requires `COSMIC_DATA_CONTROL_ENABLED=1` environment variable.
 //!
-//! Phase 1: trait definition only. Implementation in Phase 5 (Clipboard).
+//! With the `desktop` feature, [`connect_data_control`] returns a writer
+//! (`write_selection`); reading and change subscription are not implemented
+//! yet. Without it, no backend is available.
 
 use std::future::Future;
 use std::pin::Pin;
@@ -18,7 +20,7 @@ pub struct ClipboardContent {
     pub data: Vec<u8>,
 }
 
-type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+pub(crate) type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
 
 /// Abstraction over Wayland data-control clipboard protocols.
 pub trait DataControl: Send + Sync {
@@ -40,9 +42,28 @@ pub trait DataControl: Send + Sync {
 
 /// Detect and instantiate the appropriate data-control backend.
 ///
-/// Phase 1: returns an error (no implementations yet).
+/// Prefers `ext-data-control-v1`, falling back to `wlr-data-control-v1`.
+/// Errors if neither is advertised or the `desktop` feature is disabled.
 pub fn connect_data_control() -> core_types::Result<Box<dyn DataControl>> {
-    Err(core_types::Error::Platform(
-        "data-control connection not yet implemented (Phase 5)".into(),
-    ))
+    #[cfg(feature = "desktop")]
+    {
+        Ok(Box::new(crate::data_control::WaylandDataControl::connect()?))
+    }
+    #[cfg(not(feature = "desktop"))]
+    {
+        Err(core_types::Error::Platform(
+            "data-control requires the desktop feature".into(),
+        ))
+    }
+}
+
+/// Copy UTF-8 text to the clipboard selection.
+pub async fn copy_text(text: &str) -> core_types::Result<()> {
+    let control = connect_data_control()?;
+    control
+        .write_selection(&ClipboardContent {
+            mime_type: "text/plain;charset=utf-8".into(),
+            data: text.as_bytes().to_vec(),
+        })
+        .await
 }
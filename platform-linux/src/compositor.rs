@@ -7,17 +7,34 @@
 //! - `wlr-foreign-toplevel-management-v1` (Hyprland, sway, niri, Wayfire)
 //!
 //! Backend implementations live in dedicated modules:
-//! - `backend_wlr` — wlr-foreign-toplevel-management-v1
-//! - `backend_cosmic` — COSMIC toplevel-info/manager protocols
+//! - `backend_wlr` — wlr-foreign-toplevel-management-v1 (`wlroots` feature)
+//! - `backend_cosmic` — COSMIC toplevel-info/manager protocols (`cosmic` feature)
+//! - `backend_mock` — fixture-driven, no display (CI)
 //!
-//! Focus tracking lives in `focus_monitor`.
+//! Workspaces come from `ext_workspace_v1`, which COSMIC and other
+//! compositors advertise alike; both Wayland backends use `workspaces`.
+//! Outputs, and the output each window is on, are tracked by `outputs`.
+//!
+//! `SESAME_BACKEND` forces a backend (`cosmic`, `wlr` or `mock`) instead of
+//! detecting one.
+//!
+//! Focus tracking lives in `focus_monitor`; protocol version negotiation and
+//! the resulting `Capabilities` live in `capabilities`. Title and app ID
+//! changes of listed windows are pushed to `subscribe_changes` receivers as
+//! `WindowChange`s.
 //!
 //! To add a new compositor backend:
 //! 1. Create `backend_<name>.rs` implementing `CompositorBackend`
 //! 2. Add `pub(crate) mod backend_<name>;` to `lib.rs`
 //! 3. Add a match arm to `detect_compositor()` below
 
-use core_types::{CompositorWorkspaceId, Geometry, Window, WindowId};
+use core_types::{
+    AppId, CompositorWorkspaceId, Geometry, MonitorId, Output, Window, WindowId, WindowPlacement,
+    WindowToggle,
+};
+// Workspaces moved to core-types so the overlay can list them as rows;
+// re-exported at the old path for downstream crates.
+pub use core_types::Workspace;
 use std::future::Future;
 use std::pin::Pin;
 
@@ -25,17 +42,62 @@ use std::pin::Pin;
 // Downstream crates use `platform_linux::compositor::{FocusEvent, focus_monitor}`.
 pub use crate::focus_monitor::{FocusEvent, focus_monitor};
 
-/// A Wayland workspace.
-#[derive(Debug, Clone)]
-pub struct Workspace {
-    pub id: CompositorWorkspaceId,
-    pub name: String,
-    pub is_active: bool,
-}
+pub use crate::capabilities::{Capabilities, probe_capabilities};
+
+pub use crate::backend_mock::{MOCK_FIXTURE_ENV, MockBackend};
+
+/// Forces a backend instead of detecting one: `cosmic`, `wlr` or `mock`.
+pub const BACKEND_ENV: &str = "SESAME_BACKEND";
 
 // Type alias for boxed async results used by CompositorBackend methods.
 pub(crate) type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
 
+/// Changes buffered per subscriber before the oldest are dropped.
+const WINDOW_CHANGES_CAPACITY: usize = 64;
+
+/// A listed window's title or app ID changed, as the compositor committed
+/// it (a terminal's directory, a browser tab's page).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WindowChange {
+    pub id: WindowId,
+    pub app_id: AppId,
+    pub title: String,
+}
+
+impl WindowChange {
+    /// The change from `before` (app ID and title) to `app_id` and `title`,
+    /// if they differ. Without `before` the window is new, not changed.
+    pub(crate) fn between(
+        id: WindowId,
+        before: Option<(&str, &str)>,
+        app_id: &str,
+        title: &str,
+    ) -> Option<Self> {
+        let (before_app_id, before_title) = before?;
+        (before_app_id != app_id || before_title != title).then(|| Self {
+            id,
+            app_id: AppId::new(app_id),
+            title: title.to_string(),
+        })
+    }
+}
+
+/// UUID v5 namespace for `MonitorId`s derived from output names.
+const OUTPUT_NAMESPACE: uuid::Uuid = uuid::Uuid::from_bytes([
+    0x6f, 0x70, 0x65, 0x6e, 0x2d, 0x73, 0x65, 0x73, 0x61, 0x6d, 0x65, 0x2d, 0x6f, 0x75, 0x74, 0x70,
+]); // "open-sesame-outp" as bytes
+
+/// Stable id for the output named `name` (the connector, e.g. `DP-1`),
+/// shared by the Wayland backends and the mock.
+pub(crate) fn output_id(name: &str) -> MonitorId {
+    MonitorId::from_uuid(uuid::Uuid::new_v5(&OUTPUT_NAMESPACE, name.as_bytes()))
+}
+
+/// The sender a backend pushes `WindowChange`s to.
+pub(crate) fn window_changes() -> tokio::sync::broadcast::Sender<WindowChange> {
+    tokio::sync::broadcast::channel(WINDOW_CHANGES_CAPACITY).0
+}
+
 /// Abstraction over Wayland compositor protocols for window management.
 ///
 /// Implementations:
@@ -59,8 +121,46 @@ pub trait CompositorBackend: Send + Sync {
         id: &WindowId,
         ws: &CompositorWorkspaceId,
     ) -> BoxFuture<'_, core_types::Result<()>>;
+    /// Switch to a workspace from `list_workspaces`. Backends without
+    /// `Capabilities::can_switch_workspace` return an error.
+    fn activate_workspace(
+        &self,
+        id: &CompositorWorkspaceId,
+    ) -> BoxFuture<'_, core_types::Result<()>>;
+    /// Outputs by connector name, for `Window::monitor_id`.
+    fn list_outputs(&self) -> BoxFuture<'_, core_types::Result<Vec<Output>>>;
+    /// Move a window to an output from `list_outputs`. Backends without
+    /// `Capabilities::can_move_to_output` return an error.
+    fn move_to_output(
+        &self,
+        id: &WindowId,
+        output: &MonitorId,
+    ) -> BoxFuture<'_, core_types::Result<()>>;
     fn focus_window(&self, id: &WindowId) -> BoxFuture<'_, core_types::Result<()>>;
     fn close_window(&self, id: &WindowId) -> BoxFuture<'_, core_types::Result<()>>;
+    /// Tile or maximize a window. Placements the backend cannot perform
+    /// (see `Capabilities::can_maximize` and `can_tile`) return an error.
+    fn place_window(
+        &self,
+        id: &WindowId,
+        placement: WindowPlacement,
+    ) -> BoxFuture<'_, core_types::Result<()>>;
+    /// Switch a window state on or off. States the backend cannot change
+    /// (see `Capabilities::can_stick` and `can_keep_above`) return an error.
+    fn set_window_toggle(
+        &self,
+        id: &WindowId,
+        toggle: WindowToggle,
+        on: bool,
+    ) -> BoxFuture<'_, core_types::Result<()>>;
+
+    /// Title and app ID changes of listed windows from now on. Each call
+    /// is a new subscription; a subscriber that falls behind loses the
+    /// oldest changes.
+    fn subscribe_changes(&self) -> tokio::sync::broadcast::Receiver<WindowChange>;
+
+    /// What the compositor supports, as negotiated when the backend connected.
+    fn capabilities(&self) -> Capabilities;
 
     /// Human-readable backend name for diagnostics (e.g. "cosmic", "wlr", "sway-ipc").
     fn name(&self) -> &str;
@@ -68,25 +168,65 @@ pub trait CompositorBackend: Send + Sync {
 
 /// Detect and instantiate the appropriate compositor backend.
 ///
-/// Detection order:
+/// Uses the backend named by `SESAME_BACKEND` if set. Otherwise the
+/// detection order is:
 /// 1. COSMIC-specific protocols (if `cosmic` feature enabled)
-/// 2. wlr-foreign-toplevel-management-v1 (Hyprland, sway, niri)
+/// 2. wlr-foreign-toplevel-management-v1 (if `wlroots` feature enabled)
 pub fn detect_compositor() -> core_types::Result<Box<dyn CompositorBackend>> {
-    #[cfg(feature = "cosmic")]
-    {
-        match crate::backend_cosmic::CosmicBackend::connect() {
-            Ok(backend) => {
-                tracing::info!(
-                    "compositor backend: cosmic (ext_foreign_toplevel + zcosmic_toplevel)"
-                );
-                return Ok(Box::new(backend));
-            }
-            Err(e) => {
-                tracing::info!("cosmic backend unavailable, trying wlr: {e}");
-            }
+    match requested_backend()?.as_deref() {
+        Some("mock") => {
+            let backend = MockBackend::from_env()?;
+            tracing::info!("compositor backend: mock (fixture)");
+            return Ok(Box::new(backend));
         }
+        #[cfg(feature = "wlroots")]
+        Some("wlr") => return connect_wlr(),
+        #[cfg(feature = "cosmic")]
+        Some("cosmic") => return connect_cosmic(),
+        _ => {}
     }
 
+    #[cfg(feature = "cosmic")]
+    match connect_cosmic() {
+        Ok(backend) => return Ok(backend),
+        Err(e) => tracing::info!("cosmic backend unavailable, trying wlr: {e}"),
+    }
+
+    connect_wlr()
+}
+
+/// The backend forced by `SESAME_BACKEND`, or `None` to detect one.
+pub(crate) fn requested_backend() -> core_types::Result<Option<String>> {
+    let name = match std::env::var(BACKEND_ENV) {
+        Ok(name) if !name.is_empty() => name,
+        _ => return Ok(None),
+    };
+    let known = [
+        "mock",
+        #[cfg(feature = "wlroots")]
+        "wlr",
+        #[cfg(feature = "cosmic")]
+        "cosmic",
+    ];
+    if known.contains(&name.as_str()) {
+        Ok(Some(name))
+    } else {
+        Err(core_types::Error::Platform(format!(
+            "unknown {BACKEND_ENV} '{name}' (expected one of: {})",
+            known.join(", ")
+        )))
+    }
+}
+
+#[cfg(feature = "cosmic")]
+fn connect_cosmic() -> core_types::Result<Box<dyn CompositorBackend>> {
+    let backend = crate::backend_cosmic::CosmicBackend::connect()?;
+    tracing::info!("compositor backend: cosmic (ext_foreign_toplevel + zcosmic_toplevel)");
+    Ok(Box::new(backend))
+}
+
+#[cfg(feature = "wlroots")]
+fn connect_wlr() -> core_types::Result<Box<dyn CompositorBackend>> {
     match crate::backend_wlr::WlrBackend::connect() {
         Ok(backend) => {
             tracing::info!("compositor backend: wlr-foreign-toplevel-management-v1");
@@ -97,3 +237,10 @@ pub fn detect_compositor() -> core_types::Result<Box<dyn CompositorBackend>> {
         ))),
     }
 }
+
+#[cfg(not(feature = "wlroots"))]
+fn connect_wlr() -> core_types::Result<Box<dyn CompositorBackend>> {
+    Err(core_types::Error::Platform(
+        "no supported compositor backend: built without the wlroots backend".into(),
+    ))
+}
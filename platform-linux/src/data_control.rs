@@ -0,0 +1,373 @@
+//! Clipboard writer over `ext-data-control-v1` / `wlr-data-control-unstable-v1`.
+//!
+//! Both protocols have the same shape (manager → device per seat → source
+//! with offered MIME types), so the Dispatch impls are generated once per
+//! protocol by `data_control_dispatch!`.
+//!
+//! Architecture: each `write_selection()` opens its own Wayland connection,
+//! sets the selection, and hands the connection to a short-lived serving
+//! thread. The thread answers `send` requests from pasting clients until the
+//! compositor reports `cancelled` (another client took the selection), then
+//! exits and drops the connection. Writes are rare user actions, so a
+//! connection per write keeps the serving state trivially isolated.
+
+use crate::clipboard::{BoxFuture, ClipboardContent, DataControl};
+
+/// MIME types offered in addition to `text/plain;charset=utf-8` for text
+/// content. X11 atom names keep XWayland clients working.
+const TEXT_MIME_ALIASES: &[&str] = &["text/plain", "UTF8_STRING", "TEXT"];
+
+/// Which data-control protocol the compositor advertises.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Protocol {
+    Ext,
+    Wlr,
+}
+
+impl Protocol {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Ext => "ext-data-control-v1",
+            Self::Wlr => "wlr-data-control-v1",
+        }
+    }
+}
+
+pub(crate) struct WaylandDataControl {
+    protocol: Protocol,
+}
+
+/// Dispatch state for one selection: the bytes being served.
+struct SourceState {
+    content: ClipboardContent,
+    cancelled: bool,
+}
+
+// -- Dispatch impls shared by both protocols --
+
+impl
+    wayland_client::Dispatch<
+        wayland_client::protocol::wl_registry::WlRegistry,
+        wayland_client::globals::GlobalListContents,
+    > for SourceState
+{
+    fn event(
+        _: &mut Self,
+        _: &wayland_client::protocol::wl_registry::WlRegistry,
+        _: wayland_client::protocol::wl_registry::Event,
+        _: &wayland_client::globals::GlobalListContents,
+        _: &wayland_client::Connection,
+        _: &wayland_client::QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl wayland_client::Dispatch<wayland_client::protocol::wl_seat::WlSeat, ()> for SourceState {
+    fn event(
+        _: &mut Self,
+        _: &wayland_client::protocol::wl_seat::WlSeat,
+        _: wayland_client::protocol::wl_seat::Event,
+        _: &(),
+        _: &wayland_client::Connection,
+        _: &wayland_client::QueueHandle<Self>,
+    ) {
+    }
+}
+
+/// Generate Dispatch impls for one data-control protocol module.
+///
+/// Offers announced on the device (the current selection owned by other
+/// clients) are destroyed immediately — this writer never reads them.
+macro_rules! data_control_dispatch {
+    ($manager:ident, $device:ident, $source:ident, $offer:ident) => {
+        impl wayland_client::Dispatch<$manager::Manager, ()> for SourceState {
+            fn event(
+                _: &mut Self,
+                _: &$manager::Manager,
+                _: $manager::Event,
+                _: &(),
+                _: &wayland_client::Connection,
+                _: &wayland_client::QueueHandle<Self>,
+            ) {
+            }
+        }
+
+        impl wayland_client::Dispatch<$device::Device, ()> for SourceState {
+            fn event(
+                state: &mut Self,
+                _: &$device::Device,
+                event: $device::Event,
+                _: &(),
+                _: &wayland_client::Connection,
+                _: &wayland_client::QueueHandle<Self>,
+            ) {
+                match event {
+                    $device::Event::Selection { id: Some(offer) }
+                    | $device::Event::PrimarySelection { id: Some(offer) } => offer.destroy(),
+                    $device::Event::Finished => state.cancelled = true,
+                    _ => {}
+                }
+            }
+
+            wayland_client::event_created_child!(SourceState, $device::Device, [
+                $device::EVT_DATA_OFFER_OPCODE => ($offer::Offer, ())
+            ]);
+        }
+
+        impl wayland_client::Dispatch<$source::Source, ()> for SourceState {
+            fn event(
+                state: &mut Self,
+                source: &$source::Source,
+                event: $source::Event,
+                _: &(),
+                _: &wayland_client::Connection,
+                _: &wayland_client::QueueHandle<Self>,
+            ) {
+                match event {
+                    $source::Event::Send { mime_type, fd } => {
+                        use std::io::Write;
+                        let mut pipe = std::fs::File::from(fd);
+                        if let Err(e) = pipe.write_all(&state.content.data) {
+                            tracing::debug!(error = %e, %mime_type, "clipboard send failed");
+                        }
+                    }
+                    $source::Event::Cancelled => {
+                        source.destroy();
+                        state.cancelled = true;
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        impl wayland_client::Dispatch<$offer::Offer, ()> for SourceState {
+            fn event(
+                _: &mut Self,
+                _: &$offer::Offer,
+                _: $offer::Event,
+                _: &(),
+                _: &wayland_client::Connection,
+                _: &wayland_client::QueueHandle<Self>,
+            ) {
+            }
+        }
+    };
+}
+
+mod ext_manager {
+    pub(super) use wayland_protocols::ext::data_control::v1::client::ext_data_control_manager_v1::{
+        Event, ExtDataControlManagerV1 as Manager,
+    };
+}
+mod ext_device {
+    pub(super) use wayland_protocols::ext::data_control::v1::client::ext_data_control_device_v1::{
+        EVT_DATA_OFFER_OPCODE, Event, ExtDataControlDeviceV1 as Device,
+    };
+}
+mod ext_source {
+    pub(super) use wayland_protocols::ext::data_control::v1::client::ext_data_control_source_v1::{
+        Event, ExtDataControlSourceV1 as Source,
+    };
+}
+mod ext_offer {
+    pub(super) use wayland_protocols::ext::data_control::v1::client::ext_data_control_offer_v1::{
+        Event, ExtDataControlOfferV1 as Offer,
+    };
+}
+mod wlr_manager {
+    pub(super) use wayland_protocols_wlr::data_control::v1::client::zwlr_data_control_manager_v1::{
+        Event, ZwlrDataControlManagerV1 as Manager,
+    };
+}
+mod wlr_device {
+    pub(super) use wayland_protocols_wlr::data_control::v1::client::zwlr_data_control_device_v1::{
+        EVT_DATA_OFFER_OPCODE, Event, ZwlrDataControlDeviceV1 as Device,
+    };
+}
+mod wlr_source {
+    pub(super) use wayland_protocols_wlr::data_control::v1::client::zwlr_data_control_source_v1::{
+        Event, ZwlrDataControlSourceV1 as Source,
+    };
+}
+mod wlr_offer {
+    pub(super) use wayland_protocols_wlr::data_control::v1::client::zwlr_data_control_offer_v1::{
+        Event, ZwlrDataControlOfferV1 as Offer,
+    };
+}
+
+data_control_dispatch!(ext_manager, ext_device, ext_source, ext_offer);
+data_control_dispatch!(wlr_manager, wlr_device, wlr_source, wlr_offer);
+
+/// MIME types to offer for `content`.
+fn offered_mime_types(content: &ClipboardContent) -> Vec<&str> {
+    let mut types = vec![content.mime_type.as_str()];
+    if content.mime_type.starts_with("text/plain") {
+        types.extend(
+            TEXT_MIME_ALIASES
+                .iter()
+                .copied()
+                .filter(|m| *m != content.mime_type),
+        );
+    }
+    types
+}
+
+impl WaylandDataControl {
+    /// Probe the compositor for a data-control global (ext preferred).
+    pub(crate) fn connect() -> core_types::Result<Self> {
+        let (_conn, globals, _queue) = connect_registry()?;
+        let contents = globals.contents();
+        let advertised = |interface: &str| {
+            contents.with_list(|list| list.iter().any(|g| g.interface == interface))
+        };
+        let protocol = if advertised("ext_data_control_manager_v1") {
+            Protocol::Ext
+        } else if advertised("zwlr_data_control_manager_v1") {
+            Protocol::Wlr
+        } else {
+            return Err(core_types::Error::Platform(
+                "no data-control protocol advertised (on COSMIC set COSMIC_DATA_CONTROL_ENABLED=1)"
+                    .into(),
+            ));
+        };
+        Ok(Self { protocol })
+    }
+
+    fn write_blocking(&self, content: ClipboardContent) -> core_types::Result<()> {
+        use wayland_client::protocol::wl_seat;
+
+        let (conn, globals, mut queue) = connect_registry()?;
+        let qh = queue.handle();
+        let seat: wl_seat::WlSeat = globals
+            .bind(&qh, 1..=9, ())
+            .map_err(|e| core_types::Error::Platform(format!("wl_seat not available: {e}")))?;
+        let mime_types = offered_mime_types(&content);
+        let bind_err =
+            |e| core_types::Error::Platform(format!("{} not available: {e}", self.protocol.name()));
+
+        match self.protocol {
+            Protocol::Ext => {
+                let manager: ext_manager::Manager =
+                    globals.bind(&qh, 1..=1, ()).map_err(bind_err)?;
+                let source = manager.create_data_source(&qh, ());
+                for mime in &mime_types {
+                    source.offer((*mime).to_string());
+                }
+                manager
+                    .get_data_device(&seat, &qh, ())
+                    .set_selection(Some(&source));
+            }
+            Protocol::Wlr => {
+                let manager: wlr_manager::Manager =
+                    globals.bind(&qh, 1..=2, ()).map_err(bind_err)?;
+                let source = manager.create_data_source(&qh, ());
+                for mime in &mime_types {
+                    source.offer((*mime).to_string());
+                }
+                manager
+                    .get_data_device(&seat, &qh, ())
+                    .set_selection(Some(&source));
+            }
+        }
+
+        let mut state = SourceState {
+            content,
+            cancelled: false,
+        };
+        // Round-trip so protocol errors surface here rather than on the thread.
+        queue
+            .roundtrip(&mut state)
+            .map_err(|e| core_types::Error::Platform(format!("Wayland roundtrip failed: {e}")))?;
+
+        std::thread::Builder::new()
+            .name("clipboard-source".into())
+            .spawn(move || {
+                while !state.cancelled {
+                    if let Err(e) = queue.blocking_dispatch(&mut state) {
+                        let proto_err = conn.protocol_error();
+                        tracing::warn!(error = %e, ?proto_err, "clipboard source dispatch failed");
+                        break;
+                    }
+                }
+            })
+            .map_err(|e| {
+                core_types::Error::Platform(format!("failed to spawn clipboard thread: {e}"))
+            })?;
+        Ok(())
+    }
+}
+
+fn connect_registry() -> core_types::Result<(
+    wayland_client::Connection,
+    wayland_client::globals::GlobalList,
+    wayland_client::EventQueue<SourceState>,
+)> {
+    let conn = wayland_client::Connection::connect_to_env()
+        .map_err(|e| core_types::Error::Platform(format!("Wayland connection failed: {e}")))?;
+    let (globals, queue) = wayland_client::globals::registry_queue_init::<SourceState>(&conn)
+        .map_err(|e| core_types::Error::Platform(format!("Wayland registry init failed: {e}")))?;
+    Ok((conn, globals, queue))
+}
+
+impl DataControl for WaylandDataControl {
+    fn read_selection(&self) -> BoxFuture<'_, core_types::Result<Option<ClipboardContent>>> {
+        Box::pin(async {
+            Err(core_types::Error::Platform(
+                "reading the clipboard is not implemented".into(),
+            ))
+        })
+    }
+
+    fn write_selection(&self, content: &ClipboardContent) -> BoxFuture<'_, core_types::Result<()>> {
+        let content = content.clone();
+        Box::pin(async move { self.write_blocking(content) })
+    }
+
+    fn subscribe(
+        &self,
+    ) -> BoxFuture<'_, core_types::Result<tokio::sync::mpsc::Receiver<ClipboardContent>>> {
+        Box::pin(async {
+            Err(core_types::Error::Platform(
+                "clipboard subscription is not implemented".into(),
+            ))
+        })
+    }
+
+    fn protocol_name(&self) -> &str {
+        self.protocol.name()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn content(mime_type: &str) -> ClipboardContent {
+        ClipboardContent {
+            mime_type: mime_type.into(),
+            data: b"firefox".to_vec(),
+        }
+    }
+
+    #[test]
+    fn text_offers_x11_aliases() {
+        assert_eq!(
+            offered_mime_types(&content("text/plain;charset=utf-8")),
+            vec![
+                "text/plain;charset=utf-8",
+                "text/plain",
+                "UTF8_STRING",
+                "TEXT"
+            ]
+        );
+        assert_eq!(
+            offered_mime_types(&content("text/plain")),
+            vec!["text/plain", "UTF8_STRING", "TEXT"]
+        );
+    }
+
+    #[test]
+    fn binary_offers_only_its_type() {
+        assert_eq!(offered_mime_types(&content("image/png")), vec!["image/png"]);
+    }
+}
@@ -3,6 +3,9 @@
 //! Provides typed proxies for:
 //! - `org.freedesktop.secrets` (Secret Service API) — KEK-only storage
 //! - `org.freedesktop.portal.GlobalShortcuts` — compositor-agnostic hotkeys
+//! - `org.freedesktop.Notifications` — desktop notifications
+//! - `org.freedesktop.UPower.PowerProfiles` — active power profile
+//! - `org.freedesktop.login1.Session` — session lock state
 //! - Custom `org.pds.*` interfaces for daemon-to-daemon RPC over D-Bus
 //!   (secondary to the postcard IPC bus; used for portal integration)
 //!
@@ -365,6 +368,101 @@ impl GlobalShortcutsProxy {
     }
 }
 
+// ============================================================================
+// Desktop Notifications
+// ============================================================================
+
+/// Urgency hint for [`NotificationsProxy::notify`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Urgency {
+    Low = 0,
+    Normal = 1,
+    Critical = 2,
+}
+
+/// Desktop notifications via `org.freedesktop.Notifications`.
+///
+/// Implemented by COSMIC, GNOME Shell, KDE Plasma and standalone daemons
+/// such as mako and dunst.
+pub struct NotificationsProxy {
+    conn: zbus::Connection,
+}
+
+impl NotificationsProxy {
+    pub fn new(bus: &SessionBus) -> Self {
+        Self {
+            conn: bus.conn.clone(),
+        }
+    }
+
+    /// Show a notification and return its server-assigned ID.
+    ///
+    /// `body` is sent as plain text; characters the server could read as
+    /// markup are escaped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no notification server is running or the call
+    /// fails.
+    pub async fn notify(
+        &self,
+        summary: &str,
+        body: &str,
+        icon: &str,
+        urgency: Urgency,
+    ) -> core_types::Result<u32> {
+        let proxy = zbus::Proxy::new(
+            &self.conn,
+            "org.freedesktop.Notifications",
+            "/org/freedesktop/Notifications",
+            "org.freedesktop.Notifications",
+        )
+        .await
+        .map_err(|e| core_types::Error::Platform(format!("Notifications proxy failed: {e}")))?;
+
+        let mut hints = std::collections::HashMap::new();
+        hints.insert("urgency", Value::U8(urgency as u8));
+        let actions: Vec<&str> = Vec::new();
+        // Arguments: app_name, replaces_id, app_icon, summary, body,
+        // actions, hints, expire_timeout (-1 = server default).
+        let id: u32 = proxy
+            .call(
+                "Notify",
+                &(
+                    "Open Sesame",
+                    0u32,
+                    icon,
+                    summary,
+                    escape_markup(body),
+                    actions,
+                    hints,
+                    -1i32,
+                ),
+            )
+            .await
+            .map_err(|e| {
+                core_types::Error::Platform(format!("Notifications Notify failed: {e}"))
+            })?;
+
+        tracing::debug!(id, summary, "notification sent");
+        Ok(id)
+    }
+}
+
+/// Escape the characters the notification body markup subset interprets.
+fn escape_markup(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
 // ============================================================================
 // NetworkManager SSID Monitor
 // ============================================================================
@@ -563,3 +661,165 @@ async fn read_current_ssid(conn: &zbus::Connection) -> Option<String> {
 
     String::from_utf8(ssid_bytes).ok()
 }
+
+// ============================================================================
+// Power Profiles Monitor
+// ============================================================================
+
+/// Monitors the active power profile via power-profiles-daemon.
+///
+/// Reads the `ActiveProfile` property of `org.freedesktop.UPower.PowerProfiles`
+/// on the system bus (`"power-saver"`, `"balanced"` or `"performance"`) and
+/// sends it through the channel at startup and on every change. Exits when
+/// the daemon is not running.
+///
+/// Runs as a long-lived task — spawn with `tokio::spawn`.
+pub async fn power_profile_monitor(tx: tokio::sync::mpsc::Sender<String>) {
+    if let Err(e) = power_profile_monitor_inner(&tx).await {
+        tracing::info!(error = %e, "power profile monitor exiting");
+    }
+}
+
+async fn power_profile_monitor_inner(
+    tx: &tokio::sync::mpsc::Sender<String>,
+) -> core_types::Result<()> {
+    let conn = zbus::Connection::system()
+        .await
+        .map_err(|e| core_types::Error::Platform(format!("system bus connection failed: {e}")))?;
+
+    let proxy = zbus::Proxy::new(
+        &conn,
+        "org.freedesktop.UPower.PowerProfiles",
+        "/org/freedesktop/UPower/PowerProfiles",
+        "org.freedesktop.UPower.PowerProfiles",
+    )
+    .await
+    .map_err(|e| core_types::Error::Platform(format!("PowerProfiles proxy failed: {e}")))?;
+
+    // Subscribe before the first read so a change in between is not missed.
+    let mut changes = proxy
+        .receive_property_changed::<String>("ActiveProfile")
+        .await;
+    let profile: String = proxy.get_property("ActiveProfile").await.map_err(|e| {
+        core_types::Error::Platform(format!("PowerProfiles ActiveProfile read failed: {e}"))
+    })?;
+    tracing::info!(profile = %profile, "power profile");
+    if tx.send(profile).await.is_err() {
+        return Ok(());
+    }
+
+    use futures_util::StreamExt;
+    while let Some(change) = changes.next().await {
+        let Ok(profile) = change.get().await else {
+            continue;
+        };
+        tracing::info!(profile = %profile, "power profile changed");
+        if tx.send(profile).await.is_err() {
+            break; // receiver dropped
+        }
+    }
+
+    Ok(())
+}
+
+// ============================================================================
+// logind Session Lock Monitor
+// ============================================================================
+
+/// A change reported by [`session_lock_monitor`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionLockChange {
+    /// logind's `Lock` signal: a one-time request to lock. Lockers such as
+    /// swaylock never send `Unlock` or set `LockedHint`, so this says
+    /// nothing about how long the session stays locked.
+    LockRequested,
+    /// The `LockedHint` property, which lock screens that set it keep
+    /// until the session unlocks.
+    Locked(bool),
+}
+
+/// Monitors whether the user's graphical session is locked via logind.
+///
+/// Wayland gives ordinary clients no lock state: `ext_session_lock_v1` is
+/// only for the lock screen itself. Instead this watches the session
+/// object `org.freedesktop.login1.Session` on the system bus. The locked
+/// state comes only from the `LockedHint` property, sent as
+/// [`SessionLockChange::Locked`] on every change starting with the current
+/// value; each `Lock` signal is sent as
+/// [`SessionLockChange::LockRequested`].
+///
+/// Runs as a long-lived task — spawn with `tokio::spawn`.
+pub async fn session_lock_monitor(tx: tokio::sync::mpsc::Sender<SessionLockChange>) {
+    if let Err(e) = session_lock_monitor_inner(&tx).await {
+        tracing::info!(error = %e, "session lock monitor exiting");
+    }
+}
+
+async fn session_lock_monitor_inner(
+    tx: &tokio::sync::mpsc::Sender<SessionLockChange>,
+) -> core_types::Result<()> {
+    use futures_util::StreamExt;
+
+    let conn = zbus::Connection::system()
+        .await
+        .map_err(|e| core_types::Error::Platform(format!("system bus connection failed: {e}")))?;
+
+    // `auto` resolves to the caller's session, or the user's graphical
+    // session for processes outside one (systemd user services).
+    let proxy = zbus::Proxy::new(
+        &conn,
+        "org.freedesktop.login1",
+        "/org/freedesktop/login1/session/auto",
+        "org.freedesktop.login1.Session",
+    )
+    .await
+    .map_err(|e| core_types::Error::Platform(format!("logind session proxy failed: {e}")))?;
+
+    let subscribe = |e: zbus::Error| {
+        core_types::Error::Platform(format!("failed to subscribe to logind session: {e}"))
+    };
+    let locks = proxy
+        .receive_signal("Lock")
+        .await
+        .map_err(subscribe)?
+        .map(|_| Some(SessionLockChange::LockRequested));
+    let hints = proxy
+        .receive_property_changed::<bool>("LockedHint")
+        .await
+        .then(|change| async move { change.get().await.ok().map(SessionLockChange::Locked) });
+    let mut changes = std::pin::pin!(futures_util::stream::select(locks, hints));
+
+    let mut locked: bool = proxy.get_property("LockedHint").await.unwrap_or(false);
+    if tx.send(SessionLockChange::Locked(locked)).await.is_err() {
+        return Ok(());
+    }
+    while let Some(change) = changes.next().await {
+        let Some(change) = change else {
+            continue;
+        };
+        match change {
+            SessionLockChange::Locked(now_locked) if now_locked == locked => continue,
+            SessionLockChange::Locked(now_locked) => {
+                locked = now_locked;
+                tracing::info!(locked, "session lock changed");
+            }
+            SessionLockChange::LockRequested => tracing::info!("session lock requested"),
+        }
+        if tx.send(change).await.is_err() {
+            break; // receiver dropped
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn notification_body_is_escaped() {
+        assert_eq!(escape_markup("a <b> & c"), "a &lt;b&gt; &amp; c");
+        assert_eq!(escape_markup("plain"), "plain");
+    }
+}
@@ -12,20 +12,33 @@
 //!   and clipboard modules. Requires wayland-client, smithay-client-toolkit, evdev.
 //! - `cosmic`: enables COSMIC-specific Wayland protocol support. Implies `desktop`.
 //!   Pulls in GPL-3.0 dependencies (cosmic-client-toolkit, cosmic-protocols).
+//! - `wlroots`: enables the wlr-foreign-toplevel-management backend (Hyprland,
+//!   sway, niri). Implies `desktop`.
+//! - `dbus`: enables the `dbus` and `applet` modules. Requires zbus.
+//!
+//! With `desktop` but neither `cosmic` nor `wlroots`, only the mock
+//! compositor backend is available.
 //!
 //! Without any features, only headless-safe modules are available:
-//! sandbox, security, systemd, dbus, cosmic_keys, clipboard (trait only).
+//! process, sandbox, security, systemd, cosmic_keys, autostart,
+//! clipboard (trait only).
 
 // -- Always available (headless-safe) --
+#[cfg(all(target_os = "linux", feature = "dbus"))]
+pub mod applet;
+#[cfg(target_os = "linux")]
+pub mod autostart;
 #[cfg(target_os = "linux")]
 pub mod clipboard;
 #[cfg(target_os = "linux")]
 pub mod cosmic_keys;
 #[cfg(all(target_os = "linux", feature = "cosmic"))]
 pub mod cosmic_theme;
-#[cfg(target_os = "linux")]
+#[cfg(all(target_os = "linux", feature = "dbus"))]
 pub mod dbus;
 #[cfg(target_os = "linux")]
+pub mod process;
+#[cfg(target_os = "linux")]
 pub mod sandbox;
 #[cfg(target_os = "linux")]
 pub mod security;
@@ -36,10 +49,22 @@ pub mod systemd;
 #[cfg(all(target_os = "linux", feature = "cosmic"))]
 pub(crate) mod backend_cosmic;
 #[cfg(all(target_os = "linux", feature = "desktop"))]
+pub(crate) mod backend_mock;
+#[cfg(all(target_os = "linux", feature = "wlroots"))]
 pub(crate) mod backend_wlr;
 #[cfg(all(target_os = "linux", feature = "desktop"))]
+pub(crate) mod capabilities;
+#[cfg(all(target_os = "linux", feature = "desktop"))]
 pub mod compositor;
 #[cfg(all(target_os = "linux", feature = "desktop"))]
+pub(crate) mod data_control;
+#[cfg(all(target_os = "linux", feature = "desktop"))]
 pub mod focus_monitor;
 #[cfg(all(target_os = "linux", feature = "desktop"))]
 pub mod input;
+#[cfg(all(target_os = "linux", any(feature = "cosmic", feature = "wlroots")))]
+pub(crate) mod outputs;
+#[cfg(all(target_os = "linux", any(feature = "cosmic", feature = "wlroots")))]
+pub(crate) mod toplevel_state;
+#[cfg(all(target_os = "linux", any(feature = "cosmic", feature = "wlroots")))]
+pub(crate) mod workspaces;
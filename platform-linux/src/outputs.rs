@@ -0,0 +1,178 @@
+//! Output (monitor) tracking over `wl_output`.
+//!
+//! Toplevel protocols report the outputs a window is on as `wl_output`
+//! proxies, which only mean something to the connection that bound them.
+//! [`OutputState`] binds every output on a backend's connection and keeps
+//! its name, so a window's output can be reported as a stable
+//! [`MonitorId`]. It also publishes the named outputs on every change
+//! ([`OutputState::published`]), so a backend lists them without a
+//! connection or roundtrip of its own.
+//!
+//! Dispatch states embed an `OutputState`, implement `AsMut<OutputState>`
+//! and delegate `wl_output` events to it with
+//! `wayland_client::delegate_dispatch!`.
+
+use crate::capabilities::WL_OUTPUT_MAX;
+use crate::compositor::output_id;
+use core_types::{MonitorId, Output};
+use std::sync::{Arc, Mutex};
+use wayland_client::globals::GlobalList;
+use wayland_client::protocol::{wl_output, wl_registry};
+use wayland_client::{Connection, Dispatch, QueueHandle};
+
+/// The outputs bound on one connection.
+#[derive(Default)]
+pub(crate) struct OutputState {
+    outputs: Vec<BoundOutput>,
+    /// [`Self::list`] as of the last change, for callers on other threads.
+    published: Arc<Mutex<Vec<Output>>>,
+}
+
+struct BoundOutput {
+    /// Registry name of the global, for removal.
+    global: u32,
+    proxy: wl_output::WlOutput,
+    /// Connector name (`wl_output` v4); empty until sent.
+    name: String,
+    description: String,
+}
+
+impl OutputState {
+    /// Bind every output advertised by `globals`.
+    pub(crate) fn bind_all<D>(&mut self, globals: &GlobalList, qh: &QueueHandle<D>)
+    where
+        D: Dispatch<wl_output::WlOutput, ()> + 'static,
+    {
+        let advertised: Vec<(u32, u32)> = globals.contents().with_list(|list| {
+            list.iter()
+                .filter(|g| g.interface == "wl_output")
+                .map(|g| (g.name, g.version))
+                .collect()
+        });
+        for (global, version) in advertised {
+            self.bind(globals.registry(), global, version, qh);
+        }
+    }
+
+    /// Track an output global announced or removed after startup. Call from
+    /// the registry dispatch of a long-lived connection.
+    pub(crate) fn registry_event<D>(
+        &mut self,
+        registry: &wl_registry::WlRegistry,
+        event: wl_registry::Event,
+        qh: &QueueHandle<D>,
+    ) where
+        D: Dispatch<wl_output::WlOutput, ()> + 'static,
+    {
+        match event {
+            wl_registry::Event::Global {
+                name,
+                interface,
+                version,
+            } if interface == "wl_output" => self.bind(registry, name, version, qh),
+            wl_registry::Event::GlobalRemove { name } => {
+                self.outputs.retain(|o| o.global != name);
+                self.publish();
+            }
+            _ => {}
+        }
+    }
+
+    fn bind<D>(
+        &mut self,
+        registry: &wl_registry::WlRegistry,
+        global: u32,
+        version: u32,
+        qh: &QueueHandle<D>,
+    ) where
+        D: Dispatch<wl_output::WlOutput, ()> + 'static,
+    {
+        let proxy =
+            registry.bind::<wl_output::WlOutput, _, D>(global, version.min(WL_OUTPUT_MAX), qh, ());
+        self.outputs.push(BoundOutput {
+            global,
+            proxy,
+            name: String::new(),
+            description: String::new(),
+        });
+    }
+
+    /// The id of a bound output, once its name is known.
+    pub(crate) fn id_of(&self, output: &wl_output::WlOutput) -> Option<MonitorId> {
+        self.outputs
+            .iter()
+            .find(|o| o.proxy == *output && !o.name.is_empty())
+            .map(|o| output_id(&o.name))
+    }
+
+    /// The bound output with id `id`.
+    // Used by the COSMIC backend only.
+    #[cfg_attr(not(feature = "cosmic"), allow(dead_code))]
+    pub(crate) fn proxy(&self, id: &MonitorId) -> Option<&wl_output::WlOutput> {
+        self.outputs
+            .iter()
+            .find(|o| !o.name.is_empty() && output_id(&o.name) == *id)
+            .map(|o| &o.proxy)
+    }
+
+    /// The named outputs, kept up to date by the connection's dispatch.
+    pub(crate) fn published(&self) -> Arc<Mutex<Vec<Output>>> {
+        Arc::clone(&self.published)
+    }
+
+    fn publish(&self) {
+        match self.published.lock() {
+            Ok(mut published) => *published = self.list(),
+            Err(e) => tracing::error!("output list mutex poisoned: {e}"),
+        }
+    }
+
+    /// The named outputs.
+    pub(crate) fn list(&self) -> Vec<Output> {
+        self.outputs
+            .iter()
+            .filter(|o| !o.name.is_empty())
+            .map(|o| Output {
+                id: output_id(&o.name),
+                name: o.name.clone(),
+                description: o.description.clone(),
+            })
+            .collect()
+    }
+}
+
+impl<D> Dispatch<wl_output::WlOutput, (), D> for OutputState
+where
+    D: Dispatch<wl_output::WlOutput, ()> + AsMut<OutputState>,
+{
+    fn event(
+        state: &mut D,
+        proxy: &wl_output::WlOutput,
+        event: wl_output::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<D>,
+    ) {
+        let outputs = state.as_mut();
+        let Some(output) = outputs.outputs.iter_mut().find(|o| o.proxy == *proxy) else {
+            return;
+        };
+        match event {
+            wl_output::Event::Name { name } => output.name = name,
+            wl_output::Event::Description { description } => output.description = description,
+            _ => return,
+        }
+        outputs.publish();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ids_follow_the_connector_name() {
+        assert_eq!(output_id("DP-1"), output_id("DP-1"));
+        assert_ne!(output_id("DP-1"), output_id("DP-2"));
+    }
+}
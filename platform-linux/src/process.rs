@@ -0,0 +1,60 @@
+//! Spawning processes that outlive the one that starts them.
+//!
+//! Apps started by daemon-launcher and the overlay resident started by a
+//! short-lived `sesame wm overlay` must not share their parent's session:
+//! closing the terminal it ran in, or a signal to its process group, would
+//! take them down too.
+
+use std::os::unix::process::CommandExt;
+use std::process::{Command, Stdio};
+
+/// Make `cmd` start detached: in a new session with no controlling
+/// terminal, and with stdin, stdout and stderr on `/dev/null`.
+///
+/// Callers that want the child's stderr (daemon-launcher sends it to the
+/// journal) set it again afterwards. The parent still has to reap the
+/// child.
+pub fn detach(cmd: &mut Command) -> &mut Command {
+    cmd.stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    // SAFETY: the closure runs in the forked child before exec and only
+    // calls setsid(2), which is async-signal-safe and touches no memory.
+    // It fails only when the child already leads a process group, which a
+    // freshly forked child never does.
+    #[allow(unsafe_code)]
+    unsafe {
+        cmd.pre_exec(|| {
+            if libc::setsid() == -1 {
+                return Err(std::io::Error::last_os_error());
+            }
+            Ok(())
+        });
+    }
+    cmd
+}
+
+#[cfg(test)]
+#[allow(unsafe_code)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detached_child_has_own_session_and_no_stdio() {
+        let mut child = detach(Command::new("sleep").arg("30")).spawn().unwrap();
+        let pid = child.id();
+
+        // SAFETY: getsid only reads the session ids of live processes.
+        let (ours, theirs) = unsafe { (libc::getsid(0), libc::getsid(pid as libc::pid_t)) };
+        assert_eq!(theirs, pid as libc::pid_t, "child leads its own session");
+        assert_ne!(theirs, ours);
+        for fd in 0..3 {
+            let target = std::fs::read_link(format!("/proc/{pid}/fd/{fd}")).unwrap();
+            assert_eq!(target, std::path::Path::new("/dev/null"), "fd {fd}");
+        }
+
+        child.kill().unwrap();
+        child.wait().unwrap();
+        assert!(!std::path::Path::new(&format!("/proc/{pid}")).exists());
+    }
+}
@@ -28,3 +28,46 @@ pub fn notify_watchdog() {
 pub fn notify_status(status: &str) {
     sd_notify::notify(false, &[sd_notify::NotifyState::Status(status)]).ok();
 }
+
+/// Take the listening socket passed by systemd socket activation, if any.
+///
+/// Reads `LISTEN_PID`/`LISTEN_FDS` (unsetting them so children do not
+/// inherit them) and returns the first passed descriptor. Returns `None`
+/// when the process was not socket-activated or the descriptor is not a
+/// listening stream socket.
+#[must_use]
+pub fn listen_socket() -> Option<std::os::unix::net::UnixListener> {
+    use std::os::fd::FromRawFd;
+
+    let fd = match sd_notify::listen_fds() {
+        Ok(mut fds) => fds.next()?,
+        Err(e) => {
+            tracing::warn!(error = %e, "socket activation: invalid LISTEN_FDS");
+            return None;
+        }
+    };
+
+    let mut listening: libc::c_int = 0;
+    let mut len = std::mem::size_of::<libc::c_int>() as libc::socklen_t;
+    // SAFETY: `getsockopt(SO_ACCEPTCONN)` writes one c_int into `listening`,
+    // a stack variable valid for the call; `len` holds its size. An invalid
+    // fd returns -1 without touching memory.
+    let rc = unsafe {
+        libc::getsockopt(
+            fd,
+            libc::SOL_SOCKET,
+            libc::SO_ACCEPTCONN,
+            std::ptr::addr_of_mut!(listening).cast(),
+            &mut len,
+        )
+    };
+    if rc != 0 || listening == 0 {
+        tracing::warn!(fd, "socket activation: passed fd is not a listening socket");
+        return None;
+    }
+
+    tracing::info!(fd, "socket activation: using inherited listener");
+    // SAFETY: systemd passed `fd` to this process (LISTEN_PID matched) and
+    // nothing else in the process owns it; the listener takes ownership.
+    Some(unsafe { std::os::unix::net::UnixListener::from_raw_fd(fd) })
+}
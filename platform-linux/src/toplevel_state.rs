@@ -0,0 +1,87 @@
+//! Decoding of the `state` event's array argument.
+//!
+//! `zwlr_foreign_toplevel_handle_v1` and `zcosmic_toplevel_handle_v1` both
+//! send a toplevel's states as a `wl_array` of native-endian `u32` enum
+//! values, with the same numbering for the states they share (COSMIC adds
+//! `sticky` in v2). [`decode`] turns that array into a [`ToplevelState`].
+
+use core_types::ToplevelState;
+
+const MAXIMIZED: u32 = 0;
+const MINIMIZED: u32 = 1;
+const ACTIVATED: u32 = 2;
+const FULLSCREEN: u32 = 3;
+const STICKY: u32 = 4;
+
+/// States in a `state` event's array. Values this crate doesn't know are
+/// ignored; `None` if the array isn't a whole number of `u32`s.
+pub(crate) fn decode(bytes: &[u8]) -> Option<ToplevelState> {
+    if !bytes.len().is_multiple_of(4) {
+        return None;
+    }
+    let mut state = ToplevelState::empty();
+    for chunk in bytes.chunks_exact(4) {
+        let value = u32::from_ne_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        state |= match value {
+            MAXIMIZED => ToplevelState::MAXIMIZED,
+            MINIMIZED => ToplevelState::MINIMIZED,
+            ACTIVATED => ToplevelState::ACTIVATED,
+            FULLSCREEN => ToplevelState::FULLSCREEN,
+            STICKY => ToplevelState::STICKY,
+            _ => ToplevelState::empty(),
+        };
+    }
+    Some(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn array(values: &[u32]) -> Vec<u8> {
+        values.iter().flat_map(|v| v.to_ne_bytes()).collect()
+    }
+
+    #[test]
+    fn empty_array_is_no_state() {
+        assert_eq!(decode(&[]), Some(ToplevelState::empty()));
+    }
+
+    #[test]
+    fn each_value_maps_to_its_state() {
+        for (value, expected) in [
+            (MAXIMIZED, ToplevelState::MAXIMIZED),
+            (MINIMIZED, ToplevelState::MINIMIZED),
+            (ACTIVATED, ToplevelState::ACTIVATED),
+            (FULLSCREEN, ToplevelState::FULLSCREEN),
+            (STICKY, ToplevelState::STICKY),
+        ] {
+            assert_eq!(decode(&array(&[value])), Some(expected), "value {value}");
+        }
+    }
+
+    #[test]
+    fn several_values_combine() {
+        let state = decode(&array(&[ACTIVATED, MAXIMIZED, STICKY])).unwrap();
+        assert!(state.contains(ToplevelState::ACTIVATED | ToplevelState::MAXIMIZED));
+        assert!(state.contains(ToplevelState::STICKY));
+        assert!(!state.contains(ToplevelState::MINIMIZED));
+        assert!(!state.contains(ToplevelState::FULLSCREEN));
+    }
+
+    #[test]
+    fn unknown_values_are_ignored() {
+        assert_eq!(
+            decode(&array(&[99, MINIMIZED, u32::MAX])),
+            Some(ToplevelState::MINIMIZED)
+        );
+    }
+
+    #[test]
+    fn truncated_array_is_rejected() {
+        let mut bytes = array(&[ACTIVATED]);
+        bytes.push(0);
+        assert_eq!(decode(&bytes), None);
+        assert_eq!(decode(&[2, 0]), None);
+    }
+}
@@ -0,0 +1,350 @@
+//! Workspace listing and switching over `ext_workspace_v1`.
+//!
+//! COSMIC names its workspaces through `ext_workspace_manager_v1` (its
+//! `zcosmic_workspace` protocol only extends it), and other compositors
+//! served by the wlr backend advertise the same global, so both backends
+//! share this module.
+//!
+//! Each call reads the workspaces from a disposable connection, like COSMIC
+//! window activation: the overlay lists them once per activation, and a
+//! long-lived binding would wake the daemon on every workspace change.
+//! Workspaces the compositor marks hidden are left out.
+//!
+//! [`WorkspaceState`] can also be embedded in another dispatch state (one
+//! implementing `AsMut<WorkspaceState>`, delegating the workspace interfaces
+//! to it), for requests that name a workspace alongside other objects.
+
+use crate::capabilities::{EXT_WORKSPACE_MANAGER, EXT_WORKSPACE_MANAGER_MAX};
+use core_types::{CompositorWorkspaceId, Workspace};
+use wayland_client::protocol::wl_output::WlOutput;
+use wayland_client::{Connection, Dispatch, EventQueue, Proxy, QueueHandle, WEnum};
+use wayland_protocols::ext::workspace::v1::client::{
+    ext_workspace_group_handle_v1::{self, ExtWorkspaceGroupHandleV1},
+    ext_workspace_handle_v1::{self, ExtWorkspaceHandleV1},
+    ext_workspace_manager_v1::{self, ExtWorkspaceManagerV1},
+};
+
+/// UUID v5 namespace for `CompositorWorkspaceId`s derived from protocol ids.
+const WORKSPACE_NAMESPACE: uuid::Uuid = uuid::Uuid::from_bytes([
+    0x6f, 0x70, 0x65, 0x6e, 0x2d, 0x73, 0x65, 0x73, 0x61, 0x6d, 0x65, 0x2d, 0x77, 0x6f, 0x72, 0x6b,
+]); // "open-sesame-work" as bytes
+
+/// All visible workspaces, grouped by workspace group (output) in the
+/// order the compositor lists them.
+pub(crate) fn list() -> core_types::Result<Vec<Workspace>> {
+    let (_conn, _manager, _queue, state) = connect()?;
+    Ok(state.visible().map(|(_, ws)| ws).collect())
+}
+
+/// Switch to the workspace `id` from [`list`].
+pub(crate) fn activate(id: &CompositorWorkspaceId) -> core_types::Result<()> {
+    let (_conn, manager, mut queue, mut state) = connect()?;
+    let (handle, can_activate) = state
+        .visible()
+        .find(|(_, ws)| ws.id == *id)
+        .map(|(listed, _)| (listed.handle.clone(), listed.can_activate))
+        .ok_or_else(|| core_types::Error::Platform("workspace not found".into()))?;
+    if !can_activate {
+        return Err(core_types::Error::Platform(
+            "compositor does not allow switching to this workspace".into(),
+        ));
+    }
+    handle.activate();
+    manager.commit();
+    // The roundtrip returns once the compositor has applied the commit, so
+    // the connection is not dropped with the request in flight.
+    queue
+        .roundtrip(&mut state)
+        .map_err(|e| core_types::Error::Platform(format!("Wayland roundtrip failed: {e}")))?;
+    Ok(())
+}
+
+/// Stable id for a workspace: from the compositor's id when it sends one,
+/// else from its name within its group.
+fn workspace_id(protocol_id: Option<&str>, group: usize, name: &str) -> CompositorWorkspaceId {
+    let key = protocol_id.map_or_else(|| format!("{group}/{name}"), |id| format!("id/{id}"));
+    CompositorWorkspaceId::from_uuid(uuid::Uuid::new_v5(&WORKSPACE_NAMESPACE, key.as_bytes()))
+}
+
+/// Bind the workspace manager on a fresh connection and read the current
+/// workspaces.
+#[allow(clippy::type_complexity)]
+fn connect() -> core_types::Result<(
+    Connection,
+    ExtWorkspaceManagerV1,
+    EventQueue<Listing>,
+    Listing,
+)> {
+    use wayland_client::globals::registry_queue_init;
+
+    let conn = Connection::connect_to_env()
+        .map_err(|e| core_types::Error::Platform(format!("Wayland connection failed: {e}")))?;
+    let (globals, mut queue) = registry_queue_init::<Listing>(&conn)
+        .map_err(|e| core_types::Error::Platform(format!("Wayland registry init failed: {e}")))?;
+    let manager = bind(&globals, &queue.handle())?;
+    let mut state = Listing::default();
+    queue
+        .roundtrip(&mut state)
+        .map_err(|e| core_types::Error::Platform(format!("Wayland roundtrip failed: {e}")))?;
+    Ok((conn, manager, queue, state))
+}
+
+/// Bind the workspace manager for a state embedding a [`WorkspaceState`].
+pub(crate) fn bind<D>(
+    globals: &wayland_client::globals::GlobalList,
+    qh: &QueueHandle<D>,
+) -> core_types::Result<ExtWorkspaceManagerV1>
+where
+    D: Dispatch<ExtWorkspaceManagerV1, ()> + 'static,
+{
+    globals
+        .bind(qh, 1..=EXT_WORKSPACE_MANAGER_MAX, ())
+        .map_err(|e| core_types::Error::Platform(format!("{EXT_WORKSPACE_MANAGER} bind: {e}")))
+}
+
+/// Workspaces and workspace groups announced on one connection.
+#[derive(Default)]
+pub(crate) struct WorkspaceState {
+    /// Workspace groups in the order they were announced, with the outputs
+    /// each is shown on.
+    groups: Vec<(ExtWorkspaceGroupHandleV1, Vec<WlOutput>)>,
+    workspaces: Vec<ListedWorkspace>,
+}
+
+struct ListedWorkspace {
+    handle: ExtWorkspaceHandleV1,
+    protocol_id: Option<String>,
+    name: String,
+    /// Index into `groups`, once a group announces the workspace.
+    group: Option<usize>,
+    active: bool,
+    hidden: bool,
+    can_activate: bool,
+}
+
+impl WorkspaceState {
+    /// Visible workspaces, ordered by group, with their listed state.
+    fn visible(&self) -> impl Iterator<Item = (&ListedWorkspace, Workspace)> {
+        let mut listed: Vec<&ListedWorkspace> =
+            self.workspaces.iter().filter(|w| !w.hidden).collect();
+        listed.sort_by_key(|w| w.group.unwrap_or(usize::MAX));
+        listed.into_iter().map(|w| {
+            let group = w.group.unwrap_or(usize::MAX);
+            let workspace = Workspace {
+                id: workspace_id(w.protocol_id.as_deref(), group, &w.name),
+                name: w.name.clone(),
+                is_active: w.active,
+            };
+            (w, workspace)
+        })
+    }
+
+    fn listed(&mut self, handle: &ExtWorkspaceHandleV1) -> Option<&mut ListedWorkspace> {
+        self.workspaces.iter_mut().find(|w| w.handle == *handle)
+    }
+
+    /// The id [`list`] gives the workspace `handle`.
+    // Used by the COSMIC backend only.
+    #[cfg_attr(not(feature = "cosmic"), allow(dead_code))]
+    pub(crate) fn id_of(&self, handle: &ExtWorkspaceHandleV1) -> Option<CompositorWorkspaceId> {
+        let w = self.workspaces.iter().find(|w| w.handle == *handle)?;
+        let group = w.group.unwrap_or(usize::MAX);
+        Some(workspace_id(w.protocol_id.as_deref(), group, &w.name))
+    }
+
+    /// The active workspace of the group shown on `output`.
+    // Used by the COSMIC backend only.
+    #[cfg_attr(not(feature = "cosmic"), allow(dead_code))]
+    pub(crate) fn active_on(&self, output: &WlOutput) -> Option<&ExtWorkspaceHandleV1> {
+        let group = self
+            .groups
+            .iter()
+            .position(|(_, outputs)| outputs.contains(output))?;
+        self.workspaces
+            .iter()
+            .find(|w| w.group == Some(group) && w.active)
+            .map(|w| &w.handle)
+    }
+}
+
+/// State for the disposable connections of [`list`] and [`activate`].
+#[derive(Default)]
+struct Listing {
+    workspaces: WorkspaceState,
+}
+
+impl Listing {
+    fn visible(&self) -> impl Iterator<Item = (&ListedWorkspace, Workspace)> {
+        self.workspaces.visible()
+    }
+}
+
+impl AsMut<WorkspaceState> for Listing {
+    fn as_mut(&mut self) -> &mut WorkspaceState {
+        &mut self.workspaces
+    }
+}
+
+wayland_client::delegate_dispatch!(Listing: [ExtWorkspaceManagerV1: ()] => WorkspaceState);
+wayland_client::delegate_dispatch!(Listing: [ExtWorkspaceGroupHandleV1: ()] => WorkspaceState);
+wayland_client::delegate_dispatch!(Listing: [ExtWorkspaceHandleV1: ()] => WorkspaceState);
+
+impl
+    Dispatch<
+        wayland_client::protocol::wl_registry::WlRegistry,
+        wayland_client::globals::GlobalListContents,
+    > for Listing
+{
+    fn event(
+        _: &mut Self,
+        _: &wayland_client::protocol::wl_registry::WlRegistry,
+        _: wayland_client::protocol::wl_registry::Event,
+        _: &wayland_client::globals::GlobalListContents,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl<D> Dispatch<ExtWorkspaceManagerV1, (), D> for WorkspaceState
+where
+    D: Dispatch<ExtWorkspaceManagerV1, ()>
+        + Dispatch<ExtWorkspaceGroupHandleV1, ()>
+        + Dispatch<ExtWorkspaceHandleV1, ()>
+        + AsMut<WorkspaceState>
+        + 'static,
+{
+    fn event(
+        state: &mut D,
+        _: &ExtWorkspaceManagerV1,
+        event: ext_workspace_manager_v1::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<D>,
+    ) {
+        let state = state.as_mut();
+        match event {
+            ext_workspace_manager_v1::Event::WorkspaceGroup { workspace_group } => {
+                state.groups.push((workspace_group, Vec::new()));
+            }
+            ext_workspace_manager_v1::Event::Workspace { workspace } => {
+                state.workspaces.push(ListedWorkspace {
+                    handle: workspace,
+                    protocol_id: None,
+                    name: String::new(),
+                    group: None,
+                    active: false,
+                    hidden: false,
+                    can_activate: false,
+                });
+            }
+            ext_workspace_manager_v1::Event::Finished => {
+                tracing::debug!("workspace manager finished");
+            }
+            _ => {}
+        }
+    }
+
+    wayland_client::event_created_child!(D, ExtWorkspaceManagerV1, [
+        ext_workspace_manager_v1::EVT_WORKSPACE_GROUP_OPCODE => (ExtWorkspaceGroupHandleV1, ()),
+        ext_workspace_manager_v1::EVT_WORKSPACE_OPCODE => (ExtWorkspaceHandleV1, ()),
+    ]);
+}
+
+impl<D> Dispatch<ExtWorkspaceGroupHandleV1, (), D> for WorkspaceState
+where
+    D: Dispatch<ExtWorkspaceGroupHandleV1, ()> + AsMut<WorkspaceState>,
+{
+    fn event(
+        state: &mut D,
+        group: &ExtWorkspaceGroupHandleV1,
+        event: ext_workspace_group_handle_v1::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<D>,
+    ) {
+        let state = state.as_mut();
+        let index = state.groups.iter().position(|(g, _)| g == group);
+        match event {
+            ext_workspace_group_handle_v1::Event::OutputEnter { output } => {
+                if let Some(i) = index {
+                    state.groups[i].1.push(output);
+                }
+            }
+            ext_workspace_group_handle_v1::Event::OutputLeave { output } => {
+                if let Some(i) = index {
+                    state.groups[i].1.retain(|o| *o != output);
+                }
+            }
+            ext_workspace_group_handle_v1::Event::WorkspaceEnter { workspace } => {
+                if let Some(listed) = state.listed(&workspace) {
+                    listed.group = index;
+                }
+            }
+            ext_workspace_group_handle_v1::Event::WorkspaceLeave { workspace } => {
+                if let Some(listed) = state.listed(&workspace) {
+                    listed.group = None;
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl<D> Dispatch<ExtWorkspaceHandleV1, (), D> for WorkspaceState
+where
+    D: Dispatch<ExtWorkspaceHandleV1, ()> + AsMut<WorkspaceState>,
+{
+    fn event(
+        state: &mut D,
+        handle: &ExtWorkspaceHandleV1,
+        event: ext_workspace_handle_v1::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<D>,
+    ) {
+        let state = state.as_mut();
+        if let ext_workspace_handle_v1::Event::Removed = event {
+            state.workspaces.retain(|w| w.handle != *handle);
+            return;
+        }
+        let Some(listed) = state.listed(handle) else {
+            tracing::debug!(id = %handle.id(), "event for unknown workspace");
+            return;
+        };
+        match event {
+            ext_workspace_handle_v1::Event::Id { id } => listed.protocol_id = Some(id),
+            ext_workspace_handle_v1::Event::Name { name } => listed.name = name,
+            ext_workspace_handle_v1::Event::State {
+                state: WEnum::Value(flags),
+            } => {
+                listed.active = flags.contains(ext_workspace_handle_v1::State::Active);
+                listed.hidden = flags.contains(ext_workspace_handle_v1::State::Hidden);
+            }
+            ext_workspace_handle_v1::Event::Capabilities {
+                capabilities: WEnum::Value(caps),
+            } => {
+                listed.can_activate =
+                    caps.contains(ext_workspace_handle_v1::WorkspaceCapabilities::Activate);
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ids_are_stable_and_distinct_across_groups() {
+        assert_eq!(workspace_id(None, 0, "1"), workspace_id(None, 0, "1"));
+        assert_ne!(workspace_id(None, 0, "1"), workspace_id(None, 1, "1"));
+        // A compositor id wins over the name, wherever the workspace is.
+        assert_eq!(
+            workspace_id(Some("abc"), 0, "1"),
+            workspace_id(Some("abc"), 1, "renamed")
+        );
+        assert_ne!(workspace_id(Some("1"), 0, "1"), workspace_id(None, 0, "1"));
+    }
+}